@@ -0,0 +1,116 @@
+//! 大きな入力での処理時間の計測
+//!
+//! 数MBの VHDL を生成し、字句解析・意味解析・テストベンチ生成の時間を測ります。
+//!
+//! ```sh
+//! cargo bench --bench perf          # 約4MB
+//! cargo bench --bench perf -- 16    # 約16MB
+//! ```
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use vig::analyzer::analyze_vhdl;
+use vig::generator::{TbConfig, generate_testbench};
+use vig::lexer::Lexer;
+
+/// 1エンティティ分の VHDL（ポート・信号・プロセスを含む）
+fn unit(i: usize) -> String {
+    let mut s = format!(
+        "-- ブロック {i}\n\
+         entity block_{i} is\n\
+         \x20   port (\n\
+         \x20       clk : in std_logic;\n\
+         \x20       rst : in std_logic;\n\
+         \x20       Data_In : in std_logic_vector(31 downto 0);\n\
+         \x20       valid : in std_logic;\n\
+         \x20       count : out integer;\n\
+         \x20       Data_Out : out std_logic_vector(31 downto 0)\n\
+         \x20   );\n\
+         end entity block_{i};\n\
+         \n\
+         architecture rtl of block_{i} is\n"
+    );
+    for j in 0..8 {
+        s.push_str(&format!(
+            "    signal stage_{j} : std_logic_vector(31 downto 0) := x\"0000_00{j:02}\";\n"
+        ));
+    }
+    s.push_str(
+        "begin\n\
+         \x20   process(clk)\n\
+         \x20   begin\n\
+         \x20       if rising_edge(clk) then\n\
+         \x20           if rst = '1' then\n\
+         \x20               stage_0 <= (others => '0');\n\
+         \x20           elsif valid = '1' and not (stage_7 /= Data_In) then\n\
+         \x20               stage_0 <= Data_In;\n\
+         \x20           end if;\n\
+         \x20       end if;\n\
+         \x20   end process;\n\
+         \x20   Data_Out <= stage_7;\n\
+         end architecture rtl;\n\n",
+    );
+    s
+}
+
+fn source(megabytes: usize) -> String {
+    let target = megabytes * 1024 * 1024;
+    let mut out = String::with_capacity(target + 4096);
+    let mut i = 0;
+    while out.len() < target {
+        out.push_str(&unit(i));
+        i += 1;
+    }
+    out
+}
+
+/// 最速の時間を返す
+fn measure<T>(runs: usize, mut f: impl FnMut() -> T) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn report(name: &str, bytes: usize, time: Duration) {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<12} {:>9.1} ms {:>8.1} MB/s",
+        name,
+        time.as_secs_f64() * 1000.0,
+        mb / time.as_secs_f64()
+    );
+}
+
+fn main() {
+    // `cargo bench` が渡す `--bench` などは読み飛ばす
+    let megabytes = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(4);
+    let source = source(megabytes);
+    println!("input: {} bytes", source.len());
+
+    let lex = measure(5, || Lexer::new(&source).filter(Result::is_ok).count());
+    report("lex", source.len(), lex);
+
+    let analyze = measure(5, || analyze_vhdl(&source).unwrap());
+    report("analyze", source.len(), analyze);
+
+    let result = analyze_vhdl(&source).unwrap();
+    let config = TbConfig::default();
+    let mut generated = 0;
+    let generate = measure(5, || {
+        generated = result
+            .entities
+            .iter()
+            .map(|entity| generate_testbench(entity, &config).len())
+            .sum::<usize>();
+    });
+    report("generate", generated, generate);
+}
@@ -0,0 +1,46 @@
+/*
+ * Vig C API
+ *
+ * Generated with cbindgen from src/capi.rs (see cbindgen.toml).
+ * Regenerate with:
+ *   cbindgen --config cbindgen.toml --output include/vig.h
+ *
+ * Build the library with `cargo build --release --features capi`.
+ * Every string returned by Vig must be released with vig_string_free().
+ */
+
+#ifndef VIG_H
+#define VIG_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Analyze VHDL source and return the result as a JSON string.
+ *
+ * Returns NULL on failure; if `error` is not NULL, an error message is
+ * stored there (release it with vig_string_free()).
+ */
+char *vig_analyze_to_json(const char *source, char **error);
+
+/*
+ * Generate testbenches for every entity in the VHDL source.
+ *
+ * Returns NULL on failure; if `error` is not NULL, an error message is
+ * stored there (release it with vig_string_free()).
+ */
+char *vig_generate_testbench(const char *source, uint64_t clock_period_ns, char **error);
+
+/*
+ * Release a string returned by Vig. NULL is ignored.
+ */
+void vig_string_free(char *s);
+
+#ifdef __cplusplus
+}  /* extern "C" */
+#endif
+
+#endif  /* VIG_H */
@@ -1,6 +1,14 @@
-use crate::lexer::{Lexer, Span, Token, TokenKind};
+use crate::diagnostic::Diagnostic;
+use crate::expr::{BinaryOp, Direction, Element, Expr, LOGICAL, MULTIPLYING, PRIMARY, UnaryOp};
+use crate::ident;
+use crate::interface::{InterfaceDef, type_to_value};
+use crate::json::Value;
+use crate::lexer::{Lexer, LitKind, Span, Token, TokenKind};
+use crate::source_map::{Location, SourceMap};
+use crate::trace::timed;
 
 /// ポートの方向
+#[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
 pub enum PortDirection {
     In,
@@ -10,15 +18,444 @@ pub enum PortDirection {
 }
 
 /// VHDLの型参照（簡易）
+///
+/// 標準の型は専用の値で、それ以外の型は名前（制約を含む表記）のまま [`VhdlType::Other`] になります。
+#[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
 pub enum VhdlType {
     StdLogic,
-    StdLogicVector { high: i64, low: i64 },
-    Integer,
+    StdLogicVector {
+        high: BoundExpr,
+        low: BoundExpr,
+        /// `to` の範囲か（`high`・`low` は向きによらず上・下の境界）
+        ascending: bool,
+    },
+    /// `integer [range 左 to 右]`
+    Integer(Option<RangeConstraint>),
     Boolean,
+    /// `numeric_std` の `signed`
+    Signed {
+        high: BoundExpr,
+        low: BoundExpr,
+        /// `to` の範囲か（`high`・`low` は向きによらず上・下の境界）
+        ascending: bool,
+    },
+    /// `numeric_std` の `unsigned`
+    Unsigned {
+        high: BoundExpr,
+        low: BoundExpr,
+        /// `to` の範囲か（`high`・`low` は向きによらず上・下の境界）
+        ascending: bool,
+    },
+    Natural(Option<RangeConstraint>),
+    Positive(Option<RangeConstraint>),
+    Real,
+    Time,
+    Bit,
+    BitVector {
+        high: BoundExpr,
+        low: BoundExpr,
+        /// `to` の範囲か（`high`・`low` は向きによらず上・下の境界）
+        ascending: bool,
+    },
+    /// `string`（範囲は `(左, 右)` で、`to` の向き）
+    Str(Option<(BoundExpr, BoundExpr)>),
     Other(String),
 }
 
+/// 整数型の範囲の制約（`range 左 to 右` / `range 左 downto 右`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeConstraint {
+    pub left: BoundExpr,
+    pub right: BoundExpr,
+    /// `to` の範囲か
+    pub ascending: bool,
+}
+
+impl std::fmt::Display for RangeConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = if self.ascending { "to" } else { "downto" };
+        write!(f, "{} {} {}", self.left, direction, self.right)
+    }
+}
+
+impl VhdlType {
+    /// ビット幅（型から決まらない場合や u64 に収まらない場合はNone）
+    pub fn bit_width(&self) -> Option<u64> {
+        match self {
+            VhdlType::StdLogic | VhdlType::Boolean | VhdlType::Bit => Some(1),
+            _ => {
+                let (high, low) = self.vector_range()?;
+                high.value()?.abs_diff(low.value()?).checked_add(1)
+            }
+        }
+    }
+
+    /// ビットのベクタ型（`std_logic_vector`・`signed`・`unsigned`・`bit_vector`）の上・下の境界
+    pub fn vector_range(&self) -> Option<(&BoundExpr, &BoundExpr)> {
+        match self {
+            VhdlType::StdLogicVector { high, low, .. }
+            | VhdlType::Signed { high, low, .. }
+            | VhdlType::Unsigned { high, low, .. }
+            | VhdlType::BitVector { high, low, .. } => Some((high, low)),
+            _ => None,
+        }
+    }
+
+    /// `to` の範囲のベクタ型か
+    pub fn is_ascending(&self) -> bool {
+        match self {
+            VhdlType::StdLogicVector { ascending, .. }
+            | VhdlType::Signed { ascending, .. }
+            | VhdlType::Unsigned { ascending, .. }
+            | VhdlType::BitVector { ascending, .. } => *ascending,
+            _ => false,
+        }
+    }
+
+    /// ベクタ型の `(左, 右)` の境界（`downto` なら上・下、`to` なら下・上の順）
+    pub fn vector_bounds(&self) -> Option<(&BoundExpr, &BoundExpr)> {
+        let (high, low) = self.vector_range()?;
+        Some(if self.is_ascending() {
+            (low, high)
+        } else {
+            (high, low)
+        })
+    }
+
+    /// 制約を除いた型名（[`VhdlType::Other`] は書いたままの表記）
+    pub fn base_name(&self) -> &str {
+        match self {
+            VhdlType::StdLogic => "std_logic",
+            VhdlType::StdLogicVector { .. } => "std_logic_vector",
+            VhdlType::Integer(_) => "integer",
+            VhdlType::Boolean => "boolean",
+            VhdlType::Signed { .. } => "signed",
+            VhdlType::Unsigned { .. } => "unsigned",
+            VhdlType::Natural(_) => "natural",
+            VhdlType::Positive(_) => "positive",
+            VhdlType::Real => "real",
+            VhdlType::Time => "time",
+            VhdlType::Bit => "bit",
+            VhdlType::BitVector { .. } => "bit_vector",
+            VhdlType::Str(_) => "string",
+            VhdlType::Other(name) => name,
+        }
+    }
+
+    /// 整数型（`integer`・`natural`・`positive`）の範囲の制約
+    pub fn scalar_range(&self) -> Option<&RangeConstraint> {
+        match self {
+            VhdlType::Integer(range) | VhdlType::Natural(range) | VhdlType::Positive(range) => {
+                range.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    /// 範囲の境界（ベクタは上・下、`string` と整数型の制約は左・右の順）
+    fn bounds_mut(&mut self) -> Vec<&mut BoundExpr> {
+        match self {
+            VhdlType::Integer(Some(range))
+            | VhdlType::Natural(Some(range))
+            | VhdlType::Positive(Some(range)) => vec![&mut range.left, &mut range.right],
+            VhdlType::StdLogicVector { high, low, .. }
+            | VhdlType::Signed { high, low, .. }
+            | VhdlType::Unsigned { high, low, .. }
+            | VhdlType::BitVector { high, low, .. }
+            | VhdlType::Str(Some((high, low))) => vec![high, low],
+            _ => Vec::new(),
+        }
+    }
+
+    /// 範囲の境界に名前（ジェネリックなど）を含むか
+    pub fn is_symbolic(&self) -> bool {
+        match self {
+            VhdlType::Str(Some((left, right))) => left.is_symbolic() || right.is_symbolic(),
+            VhdlType::Integer(Some(range))
+            | VhdlType::Natural(Some(range))
+            | VhdlType::Positive(Some(range)) => {
+                range.left.is_symbolic() || range.right.is_symbolic()
+            }
+            _ => self
+                .vector_range()
+                .is_some_and(|(high, low)| high.is_symbolic() || low.is_symbolic()),
+        }
+    }
+
+    /// 範囲の境界を `value_of` で名前を引いて評価した型
+    ///
+    /// 評価できない境界（値の分からない名前を含むもの）は式のまま残します。
+    pub fn resolve_bounds(&self, value_of: &dyn Fn(&str) -> Option<i64>) -> VhdlType {
+        let mut resolved = self.clone();
+        for bound in resolved.bounds_mut() {
+            if let Some(value) = bound.evaluate(value_of) {
+                *bound = BoundExpr::Literal(value);
+            }
+        }
+        resolved
+    }
+}
+
+/// ベクタの範囲の境界の式（`7`、`WIDTH - 1` など）
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoundExpr {
+    /// 整数リテラル
+    Literal(i64),
+    /// ジェネリックや定数の名前（書いたままの表記）
+    Name(String),
+    /// 二項演算
+    Binary(Box<BoundExpr>, BoundOp, Box<BoundExpr>),
+}
+
+/// 境界の式の演算子
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl BoundOp {
+    /// VHDLの演算子
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BoundOp::Add => "+",
+            BoundOp::Sub => "-",
+            BoundOp::Mul => "*",
+            BoundOp::Div => "/",
+            BoundOp::Pow => "**",
+        }
+    }
+
+    /// 結合の強さ（大きいほど強い）
+    fn precedence(self) -> u8 {
+        match self {
+            BoundOp::Add | BoundOp::Sub => 1,
+            BoundOp::Mul | BoundOp::Div => 2,
+            BoundOp::Pow => 3,
+        }
+    }
+
+    /// 整数の演算（あふれ・0除算・負の指数はNone）
+    fn apply(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            BoundOp::Add => a.checked_add(b),
+            BoundOp::Sub => a.checked_sub(b),
+            BoundOp::Mul => a.checked_mul(b),
+            BoundOp::Div => a.checked_div(b),
+            BoundOp::Pow => a.checked_pow(u32::try_from(b).ok()?),
+        }
+    }
+}
+
+impl BoundExpr {
+    /// 名前を含まない式の値
+    pub fn value(&self) -> Option<i64> {
+        self.evaluate(&|_| None)
+    }
+
+    /// 名前の値を `value_of` で引いて評価する
+    ///
+    /// ```
+    /// use vig::analyzer::BoundExpr;
+    ///
+    /// let high: BoundExpr = "WIDTH - 1".parse().unwrap();
+    /// assert_eq!(high.value(), None);
+    /// assert_eq!(high.evaluate(&|name| (name == "WIDTH").then_some(8)), Some(7));
+    /// assert_eq!(high.to_string(), "WIDTH - 1");
+    /// ```
+    pub fn evaluate(&self, value_of: &dyn Fn(&str) -> Option<i64>) -> Option<i64> {
+        match self {
+            BoundExpr::Literal(n) => Some(*n),
+            BoundExpr::Name(name) => value_of(name),
+            BoundExpr::Binary(lhs, op, rhs) => {
+                op.apply(lhs.evaluate(value_of)?, rhs.evaluate(value_of)?)
+            }
+        }
+    }
+
+    /// 名前を含むか
+    pub fn is_symbolic(&self) -> bool {
+        match self {
+            BoundExpr::Literal(_) => false,
+            BoundExpr::Name(_) => true,
+            BoundExpr::Binary(lhs, _, rhs) => lhs.is_symbolic() || rhs.is_symbolic(),
+        }
+    }
+
+    /// 演算の子として書くときに括弧が要るか
+    fn needs_parens(&self, parent: BoundOp, right: bool) -> bool {
+        match self {
+            // 符号は項全体に掛かるので、負の数を括弧なしで書けるのは加減算の左端だけ
+            BoundExpr::Literal(n) => *n < 0 && (right || parent.precedence() > 1),
+            BoundExpr::Name(_) => false,
+            BoundExpr::Binary(_, op, _) => {
+                // `**` は結合しないので、左でも同じ強さなら括弧を付ける
+                let strict = right || parent == BoundOp::Pow;
+                op.precedence() < parent.precedence()
+                    || (strict && op.precedence() == parent.precedence())
+            }
+        }
+    }
+}
+
+impl From<i64> for BoundExpr {
+    fn from(value: i64) -> Self {
+        BoundExpr::Literal(value)
+    }
+}
+
+impl std::fmt::Display for BoundExpr {
+    /// VHDLの式（`WIDTH - 1` など。必要な箇所だけ括弧を付ける）
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundExpr::Literal(n) => write!(f, "{}", n),
+            BoundExpr::Name(name) => f.write_str(name),
+            BoundExpr::Binary(lhs, op, rhs) => {
+                for (i, operand) in [lhs, rhs].into_iter().enumerate() {
+                    if i == 1 {
+                        write!(f, " {} ", op.as_str())?;
+                    }
+                    if operand.needs_parens(*op, i == 1) {
+                        write!(f, "({})", operand)?;
+                    } else {
+                        write!(f, "{}", operand)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for BoundExpr {
+    type Err = AnalyzeError;
+
+    /// ポートの範囲と同じ規則で境界の式を解析する
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokens_of(s)?;
+        let mut analyzer = Analyzer::new(tokens);
+        let bound = analyzer.parse_bound()?;
+        let rest = analyzer.current().clone();
+        if rest.kind != TokenKind::Eof {
+            return Err(AnalyzeError::new(
+                format!("unexpected '{}' after expression", rest.text),
+                rest.span,
+            ));
+        }
+        Ok(bound)
+    }
+}
+
+/// 式を範囲の境界に変換する（変換できない式はエラーメッセージを返す）
+fn bound_of(expr: &Expr) -> Result<BoundExpr, String> {
+    match expr {
+        // 実数リテラルも読んでから整数でないことを報告する
+        Expr::Literal(kind @ (LitKind::Integer | LitKind::Real), text) => {
+            let value = match kind {
+                LitKind::Integer => crate::literal::parse_integer(text),
+                _ => None,
+            };
+            value
+                .and_then(|n| i64::try_from(n).ok())
+                .map(BoundExpr::Literal)
+                .ok_or_else(|| format!("invalid integer literal '{}'", text))
+        }
+        Expr::Name(name) => Ok(BoundExpr::Name(name.clone())),
+        Expr::Unary(UnaryOp::Plus, operand) => bound_of(operand),
+        Expr::Unary(UnaryOp::Minus, operand) => Ok(match bound_of(operand)? {
+            BoundExpr::Literal(n) => BoundExpr::Literal(-n),
+            term => BoundExpr::Binary(Box::new(0.into()), BoundOp::Sub, Box::new(term)),
+        }),
+        Expr::Binary(lhs, op, rhs) => {
+            let op = match op {
+                BinaryOp::Add => BoundOp::Add,
+                BinaryOp::Sub => BoundOp::Sub,
+                BinaryOp::Mul => BoundOp::Mul,
+                BinaryOp::Div => BoundOp::Div,
+                BinaryOp::Pow => BoundOp::Pow,
+                _ => return Err(format!("expected range bound, found '{}'", expr)),
+            };
+            Ok(BoundExpr::Binary(
+                Box::new(bound_of(lhs)?),
+                op,
+                Box::new(bound_of(rhs)?),
+            ))
+        }
+        _ => Err(format!("expected range bound, found '{}'", expr)),
+    }
+}
+
+impl PortDirection {
+    /// VHDLのキーワード（小文字）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PortDirection::In => "in",
+            PortDirection::Out => "out",
+            PortDirection::Inout => "inout",
+            PortDirection::Buffer => "buffer",
+        }
+    }
+}
+
+impl std::fmt::Display for PortDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PortDirection {
+    type Err = AnalyzeError;
+
+    /// `in` `out` `inout` `buffer`（大文字・小文字を区別しない）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "in" => Ok(PortDirection::In),
+            "out" => Ok(PortDirection::Out),
+            "inout" => Ok(PortDirection::Inout),
+            "buffer" => Ok(PortDirection::Buffer),
+            _ => Err(AnalyzeError::new(
+                format!("invalid port direction '{}'", s),
+                Span::new(0, s.len()),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for VhdlType {
+    /// VHDLの型表記（`std_logic_vector(7 downto 0)` など）
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VhdlType::Str(Some((left, right))) => write!(f, "string({} to {})", left, right),
+            VhdlType::Integer(Some(range))
+            | VhdlType::Natural(Some(range))
+            | VhdlType::Positive(Some(range)) => {
+                write!(f, "{} range {}", self.base_name(), range)
+            }
+            _ => match self.vector_bounds() {
+                Some((left, right)) => {
+                    let direction = if self.is_ascending() { "to" } else { "downto" };
+                    write!(f, "{}({} {} {})", self.base_name(), left, direction, right)
+                }
+                None => f.write_str(self.base_name()),
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for VhdlType {
+    type Err = AnalyzeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_type_str(s)
+    }
+}
+
 /// ポート定義
 #[derive(Debug, Clone, PartialEq)]
 pub struct PortDef {
@@ -33,379 +470,2958 @@ pub struct PortDef {
 pub struct SignalDef {
     pub name: String,
     pub vhdl_type: VhdlType,
-    pub default_value: Option<String>,
+    /// 初期値の式
+    pub default_value: Option<Expr>,
     pub span: Span,
 }
 
-/// エンティティ定義
+/// 定数定義
 #[derive(Debug, Clone, PartialEq)]
-pub struct EntityDef {
+pub struct ConstantDef {
     pub name: String,
-    pub ports: Vec<PortDef>,
+    pub vhdl_type: VhdlType,
+    /// 値の式。値を後で与える定数は None
+    pub value: Option<Expr>,
     pub span: Span,
 }
 
-/// アーキテクチャ定義
+/// インスタンス化する設計単位
+#[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
-pub struct ArchitectureDef {
-    pub name: String,
-    pub entity_name: String,
-    pub signals: Vec<SignalDef>,
-    pub span: Span,
+pub enum InstantiatedUnit {
+    /// コンポーネント（`u0 : [component] adder`）
+    Component(String),
+    /// エンティティの直接実体化（`u0 : entity work.adder(rtl)`）
+    Entity {
+        /// ライブラリ名（`entity adder` のように省略した場合は None）
+        library: Option<String>,
+        name: String,
+        /// 選択したアーキテクチャ
+        architecture: Option<String>,
+    },
 }
 
-/// 意味解析の結果
-#[derive(Debug, Clone, PartialEq)]
-pub struct AnalyzeResult {
-    pub entities: Vec<EntityDef>,
-    pub architectures: Vec<ArchitectureDef>,
+impl InstantiatedUnit {
+    /// 設計単位の名前（書いたままの表記）
+    pub fn name(&self) -> &str {
+        match self {
+            InstantiatedUnit::Component(name) | InstantiatedUnit::Entity { name, .. } => name,
+        }
+    }
 }
 
-impl std::fmt::Display for AnalyzeResult {
+impl std::fmt::Display for InstantiatedUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for entity in &self.entities {
-            writeln!(f, "Entity: {}", entity.name)?;
-            for port in &entity.ports {
-                writeln!(
-                    f,
-                    "  Port: {} : {:?} {:?}",
-                    port.name, port.direction, port.vhdl_type
-                )?;
-            }
-        }
-        for arch in &self.architectures {
-            writeln!(f, "Architecture: {} of {}", arch.name, arch.entity_name)?;
-            for sig in &arch.signals {
-                write!(f, "  Signal: {} : {:?}", sig.name, sig.vhdl_type)?;
-                if let Some(v) = &sig.default_value {
-                    write!(f, " := {}", v)?;
+        match self {
+            InstantiatedUnit::Component(name) => write!(f, "component {}", name),
+            InstantiatedUnit::Entity {
+                library,
+                name,
+                architecture,
+            } => {
+                f.write_str("entity ")?;
+                if let Some(library) = library {
+                    write!(f, "{}.", library)?;
                 }
-                writeln!(f)?;
+                f.write_str(name)?;
+                if let Some(architecture) = architecture {
+                    write!(f, "({})", architecture)?;
+                }
+                Ok(())
             }
         }
-        Ok(())
     }
 }
 
-/// 解析エラー
+/// generic map・port map の関連付け（`formal => actual`）
 #[derive(Debug, Clone, PartialEq)]
-pub struct AnalyzeError {
-    pub message: String,
+pub struct AssociationDef {
+    /// 仮引数（トークンを空白で区切った表記）。位置による関連付けは None
+    pub formal: Option<String>,
+    /// 実引数（トークンを空白で区切った表記。未接続は `open`）
+    pub actual: String,
     pub span: Span,
 }
 
-impl AnalyzeError {
-    pub fn new(message: impl Into<String>, span: Span) -> Self {
+/// インスタンス化文
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceDef {
+    pub label: String,
+    pub unit: InstantiatedUnit,
+    pub generic_map: Vec<AssociationDef>,
+    pub port_map: Vec<AssociationDef>,
+    pub span: Span,
+}
+
+/// 変数定義（プロセスの宣言部）
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableDef {
+    pub name: String,
+    pub vhdl_type: VhdlType,
+    /// 初期値の式
+    pub default_value: Option<Expr>,
+    pub span: Span,
+}
+
+/// プロセス文
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessDef {
+    pub label: Option<String>,
+    /// 感度リストの名前（VHDL-2008 の `process (all)` は `all`）
+    pub sensitivity: Vec<String>,
+    /// 宣言部の定数（宣言の順）
+    pub constants: Vec<ConstantDef>,
+    pub variables: Vec<VariableDef>,
+    /// 宣言部の型とサブタイプ（宣言の順）
+    pub types: Vec<TypeDef>,
+    /// 宣言部の関数・手続き（宣言の順）
+    pub subprograms: Vec<SubprogramDecl>,
+    pub body: Vec<Statement>,
+    pub span: Span,
+}
+
+/// ループの繰り返し方
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoopScheme {
+    /// 条件のない `loop`
+    Forever,
+    /// `while 条件 loop`
+    While(Expr),
+    /// `for パラメータ in 範囲 loop`（範囲は [`Expr::Range`] か `a'range` などの名前）
+    For { parameter: String, range: Expr },
+}
+
+/// 順次文
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// `対象 <= 値 [after 遅延];`（波形の要素がひとつの代入。複数の要素や条件付きの代入は
+    /// [`Statement::Other`]）
+    SignalAssign {
+        target: Expr,
+        value: Expr,
+        after: Option<Expr>,
+        span: Span,
+    },
+    /// `対象 := 式;`
+    VariableAssign {
+        target: Expr,
+        value: Expr,
+        span: Span,
+    },
+    /// `if 条件 then ... [elsif 条件 then ...] [else ...] end if;`（分岐は条件と文の組）
+    If {
+        branches: Vec<(Expr, Vec<Statement>)>,
+        else_body: Vec<Statement>,
+        span: Span,
+    },
+    /// `case 選択式 is when 選択肢 | ... => ... end case;`
+    Case {
+        selector: Expr,
+        /// 選択肢（`others` は [`Expr::Others`]、範囲は [`Expr::Range`]）と文の組
+        arms: Vec<(Vec<Expr>, Vec<Statement>)>,
+        span: Span,
+    },
+    Loop {
+        label: Option<String>,
+        scheme: LoopScheme,
+        body: Vec<Statement>,
+        span: Span,
+    },
+    /// `wait [on 名前, ...] [until 条件] [for 時間];`
+    Wait {
+        on: Vec<Expr>,
+        until: Option<Expr>,
+        timeout: Option<Expr>,
+        span: Span,
+    },
+    Null {
+        span: Span,
+    },
+    /// そのほかの文（手続き呼び出し・`exit`・`next`・`return`・`assert`・`report` など）の表記
+    Other {
+        text: String,
+        span: Span,
+    },
+}
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::SignalAssign { span, .. }
+            | Statement::VariableAssign { span, .. }
+            | Statement::If { span, .. }
+            | Statement::Case { span, .. }
+            | Statement::Loop { span, .. }
+            | Statement::Wait { span, .. }
+            | Statement::Null { span }
+            | Statement::Other { span, .. } => *span,
+        }
+    }
+}
+
+/// ジェネリック定義
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenericDef {
+    pub name: String,
+    pub vhdl_type: VhdlType,
+    /// 既定値の式
+    pub default_value: Option<Expr>,
+    pub span: Span,
+}
+
+/// エンティティ定義
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityDef {
+    pub name: String,
+    /// ジェネリック（宣言の順）
+    pub generics: Vec<GenericDef>,
+    pub ports: Vec<PortDef>,
+    pub span: Span,
+}
+
+impl PortDef {
+    /// ソース上の位置を持たないポートを作成
+    pub fn new(name: impl Into<String>, direction: PortDirection, vhdl_type: VhdlType) -> Self {
         Self {
-            message: message.into(),
-            span,
+            name: name.into(),
+            direction,
+            vhdl_type,
+            span: Span::new(0, 0),
         }
     }
+
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
+    }
 }
 
-impl std::fmt::Display for AnalyzeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} at position {}-{}",
-            self.message, self.span.start, self.span.end
-        )
+impl GenericDef {
+    /// ソース上の位置を持たないジェネリックを作成
+    pub fn new(name: impl Into<String>, vhdl_type: VhdlType, default_value: Option<Expr>) -> Self {
+        Self {
+            name: name.into(),
+            vhdl_type,
+            default_value,
+            span: Span::new(0, 0),
+        }
+    }
+
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
     }
 }
 
-impl std::error::Error for AnalyzeError {}
+impl SignalDef {
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
+    }
+}
 
-/// Eof用のセンチネルトークン
-fn eof_token() -> Token {
-    Token::new(TokenKind::Eof, Span::new(0, 0), String::new())
+impl ConstantDef {
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
+    }
 }
 
-/// 意味解析器
-pub struct Analyzer {
-    tokens: Vec<Token>,
-    pos: usize,
+impl AssociationDef {
+    /// 仮引数の名前（`data(0) => ...` や `rec.f => ...` の部分的な関連付けでは先頭の名前）
+    pub fn formal_name(&self) -> Option<&str> {
+        let formal = self.formal.as_deref()?;
+        let end = [" (", " ."]
+            .iter()
+            .filter_map(|sep| formal.find(sep))
+            .min()
+            .unwrap_or(formal.len());
+        Some(&formal[..end])
+    }
 }
 
-impl Analyzer {
-    /// トークン列からAnalyzerを作成（Commentは除外）
-    pub fn new(tokens: Vec<Token>) -> Self {
-        let tokens: Vec<Token> = tokens
-            .into_iter()
-            .filter(|t| t.kind != TokenKind::Comment && t.kind != TokenKind::Eof)
-            .collect();
-        Self { tokens, pos: 0 }
+impl InstanceDef {
+    /// 比較用のラベル（[`ident::normalize`]。`label` は書いたままの表記）
+    pub fn label_normalized(&self) -> String {
+        ident::normalize(&self.label)
     }
 
-    /// 解析を実行
-    pub fn analyze(&mut self) -> Result<AnalyzeResult, AnalyzeError> {
-        let mut entities = Vec::new();
-        let mut architectures = Vec::new();
+    /// ポートに名前で関連付けた実引数（名前の正規形で比べる）
+    pub fn port_actual(&self, port: &str) -> Option<&str> {
+        let port = ident::normalize(port);
+        self.port_map
+            .iter()
+            .find(|a| {
+                a.formal
+                    .as_deref()
+                    .is_some_and(|f| ident::normalize(f) == port)
+            })
+            .map(|a| a.actual.as_str())
+    }
+}
 
-        while self.current().kind != TokenKind::Eof {
-            match self.current().kind {
-                TokenKind::Entity => {
-                    entities.push(self.parse_entity()?);
-                }
-                TokenKind::Architecture => {
-                    architectures.push(self.parse_architecture()?);
-                }
-                _ => {
-                    self.advance();
-                }
-            }
-        }
+impl ArchitectureDef {
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
+    }
 
-        Ok(AnalyzeResult {
-            entities,
-            architectures,
-        })
+    /// このアーキテクチャのエンティティか（名前の正規形で比べる）
+    pub fn is_of(&self, entity: &str) -> bool {
+        ident::normalize(&self.entity_name) == ident::normalize(entity)
     }
+}
 
-    // --- トークン操作 ---
+impl EntityDef {
+    /// コード上でエンティティを組み立てるビルダーを作成
+    ///
+    /// VHDLを書かずに独自の設定ファイルなどからテストベンチを生成するためのものです。
+    ///
+    /// ```
+    /// use vig::analyzer::{EntityDef, PortDirection::*, VhdlType::*};
+    /// use vig::generator::TestbenchBuilder;
+    ///
+    /// let fifo = EntityDef::builder("fifo")
+    ///     .port("clk", In, StdLogic)
+    ///     .port("din", In, StdLogicVector { high: 7.into(), low: 0.into(), ascending: false })
+    ///     .port("full", Out, StdLogic)
+    ///     .build();
+    /// let tb = TestbenchBuilder::new(&fifo).build();
+    /// assert!(tb.contains("uut: fifo"));
+    /// ```
+    pub fn builder(name: impl Into<String>) -> EntityBuilder {
+        EntityBuilder {
+            entity: EntityDef {
+                name: name.into(),
+                generics: Vec::new(),
+                ports: Vec::new(),
+                span: Span::new(0, 0),
+            },
+        }
+    }
+}
 
-    fn current(&self) -> &Token {
-        self.tokens.get(self.pos).unwrap_or(&EOF_SENTINEL)
+impl EntityDef {
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記で、生成にはこちらを使う）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
     }
 
-    fn peek(&self, offset: usize) -> &Token {
+    /// 整数のジェネリックの既定値（前に宣言したジェネリックを使う式も評価する）
+    pub fn generic_value(&self, name: &str) -> Option<i64> {
+        generic_value(&self.generics, name)
+    }
+
+    /// ポートの範囲をジェネリックの既定値で評価したエンティティ
+    ///
+    /// 既定値のないジェネリックを使う範囲は式のまま残します。
+    pub fn with_generic_bounds(&self) -> EntityDef {
+        let mut entity = self.clone();
+        for port in &mut entity.ports {
+            port.vhdl_type = port
+                .vhdl_type
+                .resolve_bounds(&|name| self.generic_value(name));
+        }
+        entity
+    }
+
+    /// 名前・ポート・型の範囲を検査する
+    ///
+    /// 解析したVHDLは文法上これらを満たしますが、ビルダーやJSON・IP-XACTから
+    /// 作成したエンティティはそのまま生成すると不正なVHDLになることがあります。
+    /// 問題がなければ空の配列を返します。
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if let Some(problem) = identifier_problem(&self.name) {
+            diagnostics.push(Diagnostic::error(
+                format!("entity name '{}' {}", self.name, problem),
+                self.span,
+            ));
+        }
+        if self.ports.is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                format!("entity '{}' has no ports", self.name),
+                self.span,
+            ));
+        }
+        for (i, port) in self.ports.iter().enumerate() {
+            if let Some(problem) = identifier_problem(&port.name) {
+                diagnostics.push(Diagnostic::error(
+                    format!("port name '{}' {}", port.name, problem),
+                    port.span,
+                ));
+            }
+            if self.ports[..i]
+                .iter()
+                .any(|p| same_identifier(&p.name, &port.name))
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!("duplicate port '{}'", port.name),
+                    port.span,
+                ));
+            }
+            if let Some((high, low)) = port.vhdl_type.vector_range()
+                && (high.value().is_some_and(|n| n < 0) || low.value().is_some_and(|n| n < 0))
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "range ({}, {}) of port '{}' has a negative index",
+                        high, low, port.name
+                    ),
+                    port.span,
+                ));
+            }
+            if let VhdlType::Other(name) = &port.vhdl_type
+                && name.trim().is_empty()
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!("port '{}' has an empty type name", port.name),
+                    port.span,
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// 識別子として使えない理由（使えれば None）
+///
+/// 拡張識別子（`\bus-signal\`）は中の `\` が `\\` に重なっていれば使えます。
+pub(crate) fn identifier_problem(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        return Some("is empty");
+    }
+    if ident::is_extended(name) {
+        let inner = ident::bare(name);
+        if inner.replace("\\\\", "").contains('\\') {
+            return Some("has an unescaped backslash");
+        }
+        return None;
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Some("must start with a letter");
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Some("contains invalid characters");
+    }
+    if name.contains("__") || name.ends_with('_') {
+        return Some("has a misplaced underscore");
+    }
+    if ident::is_reserved(name) {
+        return Some("is a reserved word");
+    }
+    None
+}
+
+/// `generics` の中で `name` の既定値を評価する（既定値は前のジェネリックだけを参照できる）
+fn generic_value(generics: &[GenericDef], name: &str) -> Option<i64> {
+    let i = generics
+        .iter()
+        .position(|g| same_identifier(&g.name, name))?;
+    let default = generics[i].default_value.as_ref()?;
+    default.evaluate(&|other| generic_value(&generics[..i], other))
+}
+
+/// 同じ識別子か（基本識別子は大文字・小文字を区別せず、拡張識別子は区別する）
+pub(crate) fn same_identifier(a: &str, b: &str) -> bool {
+    if ident::is_extended(a) || ident::is_extended(b) {
+        a == b
+    } else {
+        a.eq_ignore_ascii_case(b)
+    }
+}
+
+/// [`EntityDef`] のビルダー
+#[derive(Debug, Clone)]
+pub struct EntityBuilder {
+    entity: EntityDef,
+}
+
+impl EntityBuilder {
+    /// ポートを追加
+    pub fn port(
+        mut self,
+        name: impl Into<String>,
+        direction: PortDirection,
+        vhdl_type: VhdlType,
+    ) -> Self {
+        self.entity
+            .ports
+            .push(PortDef::new(name, direction, vhdl_type));
+        self
+    }
+
+    /// 入力ポートを追加
+    pub fn input(self, name: impl Into<String>, vhdl_type: VhdlType) -> Self {
+        self.port(name, PortDirection::In, vhdl_type)
+    }
+
+    /// 出力ポートを追加
+    pub fn output(self, name: impl Into<String>, vhdl_type: VhdlType) -> Self {
+        self.port(name, PortDirection::Out, vhdl_type)
+    }
+
+    /// 作成済みのポート定義を追加
+    pub fn port_def(mut self, port: PortDef) -> Self {
+        self.entity.ports.push(port);
+        self
+    }
+
+    /// ジェネリックを追加
+    pub fn generic(mut self, generic: GenericDef) -> Self {
+        self.entity.generics.push(generic);
+        self
+    }
+
+    pub fn build(self) -> EntityDef {
+        self.entity
+    }
+}
+
+/// アーキテクチャ定義
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchitectureDef {
+    pub name: String,
+    pub entity_name: String,
+    pub signals: Vec<SignalDef>,
+    /// 宣言部の定数（宣言の順）
+    pub constants: Vec<ConstantDef>,
+    /// 本体のインスタンス化文（記述の順）
+    pub instances: Vec<InstanceDef>,
+    /// 本体のプロセス文（記述の順）
+    pub processes: Vec<ProcessDef>,
+    /// 宣言部の型とサブタイプ（宣言の順）
+    pub types: Vec<TypeDef>,
+    /// 宣言部の関数・手続き（宣言の順）
+    pub subprograms: Vec<SubprogramDecl>,
+    pub span: Span,
+}
+
+/// 型宣言（`type` / `subtype`）
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeDef {
+    /// `subtype 名前 is 型;`（`integer range 0 to 15` の制約は型に含む。独自の型の制約は [`TypeDef::Other`]）
+    Subtype {
+        name: String,
+        vhdl_type: VhdlType,
+        span: Span,
+    },
+    /// 列挙型 `type 名前 is (IDLE, RUN, ...);`（リテラルは書いたままの表記で、文字リテラルは `'0'`）
+    Enum {
+        name: String,
+        literals: Vec<String>,
+        span: Span,
+    },
+    /// 一次元の配列型 `type 名前 is array (添字の範囲) of 要素の型;`
+    Array {
+        name: String,
+        /// 添字の範囲（`0 to 255` のような範囲か型名の式）。`natural range <>` のような制約のない配列は None
+        index_range: Option<Expr>,
+        /// 制約のない配列の添字の型（`natural range <>` の `natural`。制約のある配列は None）
+        index_type: Option<String>,
+        element: VhdlType,
+        span: Span,
+    },
+    /// そのほかの型（多次元の配列も含む）（定義はトークンを空白で区切った表記。不完全型の宣言は空）
+    Other {
+        name: String,
+        definition: String,
+        span: Span,
+    },
+}
+
+impl TypeDef {
+    pub fn name(&self) -> &str {
+        match self {
+            TypeDef::Subtype { name, .. }
+            | TypeDef::Enum { name, .. }
+            | TypeDef::Array { name, .. }
+            | TypeDef::Other { name, .. } => name,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            TypeDef::Subtype { span, .. }
+            | TypeDef::Enum { span, .. }
+            | TypeDef::Array { span, .. }
+            | TypeDef::Other { span, .. } => *span,
+        }
+    }
+}
+
+/// サブプログラムの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubprogramKind {
+    Function,
+    Procedure,
+}
+
+/// サブプログラムの宣言（パッケージ本体などでは実装も）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubprogramDecl {
+    pub kind: SubprogramKind,
+    /// `impure function` か
+    pub impure: bool,
+    /// 名前（演算子の多重定義は `"+"` のような文字列リテラルの表記）
+    pub name: String,
+    /// 仮引数並びと戻り値の型（トークンを空白で区切った表記。`( a : bit ) return bit` など）
+    pub signature: String,
+    /// 仮引数（宣言の順）
+    pub params: Vec<ParameterDef>,
+    /// 戻り値の型（手続きは None）
+    pub return_type: Option<VhdlType>,
+    /// 本体の順次文（宣言だけなら None）
+    pub body: Option<Vec<Statement>>,
+    /// 本体の宣言部の定数（宣言の順）
+    pub constants: Vec<ConstantDef>,
+    /// 本体の宣言部の変数（宣言の順）
+    pub variables: Vec<VariableDef>,
+    /// 本体の宣言部の型とサブタイプ（宣言の順）
+    pub types: Vec<TypeDef>,
+    /// 本体の宣言部の入れ子の関数・手続き（宣言の順）
+    pub subprograms: Vec<SubprogramDecl>,
+    /// 宣言全体の範囲（本体があれば `end ...;` まで）
+    pub span: Span,
+}
+
+impl SubprogramKind {
+    /// VHDLの予約語
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SubprogramKind::Function => "function",
+            SubprogramKind::Procedure => "procedure",
+        }
+    }
+}
+
+/// サブプログラムの仮引数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterDef {
+    pub name: String,
+    /// モード（省略すると `in`）
+    pub direction: PortDirection,
+    pub vhdl_type: VhdlType,
+    /// 既定値の式
+    pub default_value: Option<Expr>,
+    pub span: Span,
+}
+
+impl SubprogramDecl {
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
+    }
+
+    /// 仮引数の型名を並べた `(型, ...) return 型` の表記（制約のない配列型があるので型名だけ。
+    /// 手続きは `(型, ...)`）
+    pub fn type_signature(&self) -> String {
+        let params: Vec<&str> = self
+            .params
+            .iter()
+            .map(|p| p.vhdl_type.base_name())
+            .collect();
+        match &self.return_type {
+            Some(return_type) => {
+                format!("({}) return {}", params.join(", "), return_type.base_name())
+            }
+            None => format!("({})", params.join(", ")),
+        }
+    }
+}
+
+/// パッケージ宣言・パッケージ本体
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageDef {
+    pub name: String,
+    /// 定数（宣言の順。値を本体で与える定数は値が None）
+    pub constants: Vec<ConstantDef>,
+    /// 型とサブタイプ（宣言の順）
+    pub types: Vec<TypeDef>,
+    /// 関数・手続き（宣言の順。パッケージ本体では実装）
+    pub subprograms: Vec<SubprogramDecl>,
+    pub span: Span,
+}
+
+impl PackageDef {
+    /// 比較用の名前（[`ident::normalize`]。`name` は書いたままの表記）
+    pub fn name_normalized(&self) -> String {
+        ident::normalize(&self.name)
+    }
+
+    /// 型を名前で探す（名前の正規形で比べる）
+    pub fn find_type(&self, name: &str) -> Option<&TypeDef> {
+        self.types.iter().find(|t| same_identifier(t.name(), name))
+    }
+
+    /// 関数を名前で探す（多重定義があれば最初のもの）
+    pub fn find_function(&self, name: &str) -> Option<&SubprogramDecl> {
+        self.subprograms
+            .iter()
+            .find(|d| d.kind == SubprogramKind::Function && same_identifier(&d.name, name))
+    }
+}
+
+/// 意味解析の結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalyzeResult {
+    pub entities: Vec<EntityDef>,
+    pub architectures: Vec<ArchitectureDef>,
+    /// パッケージ宣言
+    pub packages: Vec<PackageDef>,
+    /// パッケージ本体（宣言と同じ形で、サブプログラムは本体を持つ）
+    pub package_bodies: Vec<PackageDef>,
+}
+
+impl AnalyzeResult {
+    /// 解析結果をJSON文字列に変換（エンティティはインターフェース記述形式）
+    pub fn to_json(&self) -> String {
+        let entities = self
+            .entities
+            .iter()
+            .map(|e| InterfaceDef::from_entity(e).to_value())
+            .collect();
+        let architectures = self
+            .architectures
+            .iter()
+            .map(|a| {
+                let signals = a
+                    .signals
+                    .iter()
+                    .map(|s| {
+                        Value::Object(vec![
+                            ("name".into(), s.name.as_str().into()),
+                            ("type".into(), type_to_value(&s.vhdl_type)),
+                            (
+                                "default".into(),
+                                s.default_value
+                                    .as_ref()
+                                    .map_or(Value::Null, |v| v.to_string().into()),
+                            ),
+                        ])
+                    })
+                    .collect();
+                let instances = a
+                    .instances
+                    .iter()
+                    .map(|i| {
+                        let (kind, library, architecture) = match &i.unit {
+                            InstantiatedUnit::Component(_) => ("component", None, None),
+                            InstantiatedUnit::Entity {
+                                library,
+                                architecture,
+                                ..
+                            } => ("entity", library.as_deref(), architecture.as_deref()),
+                        };
+                        Value::Object(vec![
+                            ("label".into(), i.label.as_str().into()),
+                            ("kind".into(), kind.into()),
+                            ("library".into(), library.map_or(Value::Null, Value::from)),
+                            ("unit".into(), i.unit.name().into()),
+                            (
+                                "architecture".into(),
+                                architecture.map_or(Value::Null, Value::from),
+                            ),
+                            ("generic_map".into(), associations_to_value(&i.generic_map)),
+                            ("port_map".into(), associations_to_value(&i.port_map)),
+                        ])
+                    })
+                    .collect();
+                Value::Object(vec![
+                    ("name".into(), a.name.as_str().into()),
+                    ("entity".into(), a.entity_name.as_str().into()),
+                    ("signals".into(), Value::Array(signals)),
+                    ("constants".into(), constants_to_value(&a.constants)),
+                    ("instances".into(), Value::Array(instances)),
+                    (
+                        "processes".into(),
+                        Value::Array(a.processes.iter().map(process_to_value).collect()),
+                    ),
+                    ("types".into(), types_to_value(&a.types)),
+                    ("subprograms".into(), subprograms_to_value(&a.subprograms)),
+                ])
+            })
+            .collect();
+        let packages = self.packages.iter().map(package_to_value).collect();
+        let package_bodies = self.package_bodies.iter().map(package_to_value).collect();
+        let mut s = Value::Object(vec![
+            ("entities".into(), Value::Array(entities)),
+            ("architectures".into(), Value::Array(architectures)),
+            ("packages".into(), Value::Array(packages)),
+            ("package_bodies".into(), Value::Array(package_bodies)),
+        ])
+        .to_pretty_string();
+        s.push('\n');
+        s
+    }
+}
+
+fn associations_to_value(associations: &[AssociationDef]) -> Value {
+    Value::Array(
+        associations
+            .iter()
+            .map(|a| {
+                Value::Object(vec![
+                    (
+                        "formal".into(),
+                        a.formal.as_deref().map_or(Value::Null, Value::from),
+                    ),
+                    ("actual".into(), a.actual.as_str().into()),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn constants_to_value(constants: &[ConstantDef]) -> Value {
+    Value::Array(
+        constants
+            .iter()
+            .map(|c| {
+                Value::Object(vec![
+                    ("name".into(), c.name.as_str().into()),
+                    ("type".into(), type_to_value(&c.vhdl_type)),
+                    (
+                        "value".into(),
+                        c.value
+                            .as_ref()
+                            .map_or(Value::Null, |v| v.to_string().into()),
+                    ),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn types_to_value(types: &[TypeDef]) -> Value {
+    Value::Array(
+        types
+            .iter()
+            .map(|t| match t {
+                TypeDef::Subtype {
+                    name, vhdl_type, ..
+                } => Value::Object(vec![
+                    ("name".into(), name.as_str().into()),
+                    ("kind".into(), "subtype".into()),
+                    ("type".into(), type_to_value(vhdl_type)),
+                ]),
+                TypeDef::Enum { name, literals, .. } => Value::Object(vec![
+                    ("name".into(), name.as_str().into()),
+                    ("kind".into(), "enum".into()),
+                    (
+                        "literals".into(),
+                        Value::Array(literals.iter().map(|l| l.as_str().into()).collect()),
+                    ),
+                ]),
+                TypeDef::Array {
+                    name,
+                    index_range,
+                    index_type,
+                    element,
+                    ..
+                } => Value::Object(vec![
+                    ("name".into(), name.as_str().into()),
+                    ("kind".into(), "array".into()),
+                    (
+                        "index_range".into(),
+                        index_range
+                            .as_ref()
+                            .map_or(Value::Null, |r| r.to_string().into()),
+                    ),
+                    (
+                        "index_type".into(),
+                        index_type.as_deref().map_or(Value::Null, Value::from),
+                    ),
+                    ("element".into(), type_to_value(element)),
+                ]),
+                TypeDef::Other {
+                    name, definition, ..
+                } => Value::Object(vec![
+                    ("name".into(), name.as_str().into()),
+                    ("kind".into(), "other".into()),
+                    ("definition".into(), definition.as_str().into()),
+                ]),
+            })
+            .collect(),
+    )
+}
+
+fn package_to_value(package: &PackageDef) -> Value {
+    Value::Object(vec![
+        ("name".into(), package.name.as_str().into()),
+        ("constants".into(), constants_to_value(&package.constants)),
+        ("types".into(), types_to_value(&package.types)),
+        (
+            "subprograms".into(),
+            subprograms_to_value(&package.subprograms),
+        ),
+    ])
+}
+
+fn subprograms_to_value(subprograms: &[SubprogramDecl]) -> Value {
+    Value::Array(
+        subprograms
+            .iter()
+            .map(|d| {
+                let params = d
+                    .params
+                    .iter()
+                    .map(|p| {
+                        Value::Object(vec![
+                            ("name".into(), p.name.as_str().into()),
+                            ("direction".into(), p.direction.as_str().into()),
+                            ("type".into(), type_to_value(&p.vhdl_type)),
+                            (
+                                "default".into(),
+                                p.default_value
+                                    .as_ref()
+                                    .map_or(Value::Null, |v| v.to_string().into()),
+                            ),
+                        ])
+                    })
+                    .collect();
+                Value::Object(vec![
+                    ("kind".into(), d.kind.as_str().into()),
+                    ("impure".into(), d.impure.into()),
+                    ("name".into(), d.name.as_str().into()),
+                    ("signature".into(), d.signature.as_str().into()),
+                    ("params".into(), Value::Array(params)),
+                    (
+                        "return_type".into(),
+                        d.return_type.as_ref().map_or(Value::Null, type_to_value),
+                    ),
+                    ("constants".into(), constants_to_value(&d.constants)),
+                    ("variables".into(), variables_to_value(&d.variables)),
+                    ("types".into(), types_to_value(&d.types)),
+                    ("subprograms".into(), subprograms_to_value(&d.subprograms)),
+                    (
+                        "body".into(),
+                        d.body.as_deref().map_or(Value::Null, statements_to_value),
+                    ),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn variables_to_value(variables: &[VariableDef]) -> Value {
+    Value::Array(
+        variables
+            .iter()
+            .map(|v| {
+                Value::Object(vec![
+                    ("name".into(), v.name.as_str().into()),
+                    ("type".into(), type_to_value(&v.vhdl_type)),
+                    (
+                        "default".into(),
+                        v.default_value
+                            .as_ref()
+                            .map_or(Value::Null, |v| v.to_string().into()),
+                    ),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn process_to_value(process: &ProcessDef) -> Value {
+    Value::Object(vec![
+        (
+            "label".into(),
+            process.label.as_deref().map_or(Value::Null, Value::from),
+        ),
+        (
+            "sensitivity".into(),
+            Value::Array(
+                process
+                    .sensitivity
+                    .iter()
+                    .map(|s| s.as_str().into())
+                    .collect(),
+            ),
+        ),
+        ("constants".into(), constants_to_value(&process.constants)),
+        ("variables".into(), variables_to_value(&process.variables)),
+        ("types".into(), types_to_value(&process.types)),
+        (
+            "subprograms".into(),
+            subprograms_to_value(&process.subprograms),
+        ),
+        ("body".into(), statements_to_value(&process.body)),
+    ])
+}
+
+fn statements_to_value(statements: &[Statement]) -> Value {
+    Value::Array(statements.iter().map(statement_to_value).collect())
+}
+
+fn statement_to_value(statement: &Statement) -> Value {
+    let expr = |expr: &Expr| Value::from(expr.to_string());
+    let optional = |e: &Option<Expr>| e.as_ref().map_or(Value::Null, expr);
+    let fields: Vec<(String, Value)> = match statement {
+        Statement::SignalAssign {
+            target,
+            value,
+            after,
+            ..
+        } => vec![
+            ("kind".into(), "signal_assign".into()),
+            ("target".into(), expr(target)),
+            ("value".into(), expr(value)),
+            ("after".into(), optional(after)),
+        ],
+        Statement::VariableAssign { target, value, .. } => vec![
+            ("kind".into(), "variable_assign".into()),
+            ("target".into(), expr(target)),
+            ("value".into(), expr(value)),
+        ],
+        Statement::If {
+            branches,
+            else_body,
+            ..
+        } => {
+            let branches = branches
+                .iter()
+                .map(|(condition, body)| {
+                    Value::Object(vec![
+                        ("condition".into(), expr(condition)),
+                        ("body".into(), statements_to_value(body)),
+                    ])
+                })
+                .collect();
+            vec![
+                ("kind".into(), "if".into()),
+                ("branches".into(), Value::Array(branches)),
+                ("else".into(), statements_to_value(else_body)),
+            ]
+        }
+        Statement::Case { selector, arms, .. } => {
+            let arms = arms
+                .iter()
+                .map(|(choices, body)| {
+                    Value::Object(vec![
+                        (
+                            "choices".into(),
+                            Value::Array(choices.iter().map(expr).collect()),
+                        ),
+                        ("body".into(), statements_to_value(body)),
+                    ])
+                })
+                .collect();
+            vec![
+                ("kind".into(), "case".into()),
+                ("selector".into(), expr(selector)),
+                ("arms".into(), Value::Array(arms)),
+            ]
+        }
+        Statement::Loop {
+            label,
+            scheme,
+            body,
+            ..
+        } => {
+            let mut fields = vec![
+                ("kind".into(), "loop".into()),
+                (
+                    "label".into(),
+                    label.as_deref().map_or(Value::Null, Value::from),
+                ),
+            ];
+            match scheme {
+                LoopScheme::Forever => {}
+                LoopScheme::While(condition) => {
+                    fields.push(("while".into(), expr(condition)));
+                }
+                LoopScheme::For { parameter, range } => {
+                    fields.push(("parameter".into(), parameter.as_str().into()));
+                    fields.push(("range".into(), expr(range)));
+                }
+            }
+            fields.push(("body".into(), statements_to_value(body)));
+            fields
+        }
+        Statement::Wait {
+            on, until, timeout, ..
+        } => vec![
+            ("kind".into(), "wait".into()),
+            ("on".into(), Value::Array(on.iter().map(expr).collect())),
+            ("until".into(), optional(until)),
+            ("for".into(), optional(timeout)),
+        ],
+        Statement::Null { .. } => vec![("kind".into(), "null".into())],
+        Statement::Other { text, .. } => vec![
+            ("kind".into(), "other".into()),
+            ("text".into(), text.as_str().into()),
+        ],
+    };
+    Value::Object(fields)
+}
+
+impl std::fmt::Display for AnalyzeResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entity in &self.entities {
+            writeln!(f, "Entity: {}", entity.name)?;
+            for generic in &entity.generics {
+                write!(f, "  Generic: {} : {:?}", generic.name, generic.vhdl_type)?;
+                if let Some(v) = &generic.default_value {
+                    write!(f, " := {}", v)?;
+                }
+                writeln!(f)?;
+            }
+            for port in &entity.ports {
+                writeln!(
+                    f,
+                    "  Port: {} : {:?} {:?}",
+                    port.name, port.direction, port.vhdl_type
+                )?;
+            }
+        }
+        for arch in &self.architectures {
+            writeln!(f, "Architecture: {} of {}", arch.name, arch.entity_name)?;
+            for sig in &arch.signals {
+                write!(f, "  Signal: {} : {:?}", sig.name, sig.vhdl_type)?;
+                if let Some(v) = &sig.default_value {
+                    write!(f, " := {}", v)?;
+                }
+                writeln!(f)?;
+            }
+            for constant in &arch.constants {
+                write!(
+                    f,
+                    "  Constant: {} : {:?}",
+                    constant.name, constant.vhdl_type
+                )?;
+                if let Some(v) = &constant.value {
+                    write!(f, " := {}", v)?;
+                }
+                writeln!(f)?;
+            }
+            for instance in &arch.instances {
+                writeln!(f, "  Instance: {} : {}", instance.label, instance.unit)?;
+                for (map, associations) in [
+                    ("Generic map", &instance.generic_map),
+                    ("Port map", &instance.port_map),
+                ] {
+                    for a in associations {
+                        match &a.formal {
+                            Some(formal) => writeln!(f, "    {}: {} => {}", map, formal, a.actual)?,
+                            None => writeln!(f, "    {}: {}", map, a.actual)?,
+                        }
+                    }
+                }
+            }
+            for process in &arch.processes {
+                write!(f, "  Process: {}", process.label.as_deref().unwrap_or("-"))?;
+                if !process.sensitivity.is_empty() {
+                    write!(f, " ({})", process.sensitivity.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+            write_types(f, &arch.types)?;
+            write_subprograms(f, &arch.subprograms)?;
+        }
+        let packages = self.packages.iter().map(|p| ("Package", p));
+        let bodies = self.package_bodies.iter().map(|p| ("Package body", p));
+        for (unit, package) in packages.chain(bodies) {
+            writeln!(f, "{}: {}", unit, package.name)?;
+            for constant in &package.constants {
+                write!(
+                    f,
+                    "  Constant: {} : {:?}",
+                    constant.name, constant.vhdl_type
+                )?;
+                if let Some(v) = &constant.value {
+                    write!(f, " := {}", v)?;
+                }
+                writeln!(f)?;
+            }
+            write_types(f, &package.types)?;
+            write_subprograms(f, &package.subprograms)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_subprograms(
+    f: &mut std::fmt::Formatter<'_>,
+    subprograms: &[SubprogramDecl],
+) -> std::fmt::Result {
+    for decl in subprograms {
+        writeln!(
+            f,
+            "  Subprogram: {} {} {}",
+            decl.kind.as_str(),
+            decl.name,
+            decl.signature
+        )?;
+    }
+    Ok(())
+}
+
+fn write_types(f: &mut std::fmt::Formatter<'_>, types: &[TypeDef]) -> std::fmt::Result {
+    for ty in types {
+        match ty {
+            TypeDef::Subtype {
+                name, vhdl_type, ..
+            } => writeln!(f, "  Subtype: {} : {:?}", name, vhdl_type)?,
+            TypeDef::Enum { name, literals, .. } => {
+                writeln!(f, "  Type: {} is ({})", name, literals.join(", "))?
+            }
+            TypeDef::Array {
+                name,
+                index_range,
+                index_type,
+                element,
+                ..
+            } => match index_range {
+                Some(range) => writeln!(f, "  Type: {} is array ({}) of {}", name, range, element)?,
+                None => writeln!(
+                    f,
+                    "  Type: {} is array ({} range <>) of {}",
+                    name,
+                    index_type.as_deref().unwrap_or(""),
+                    element
+                )?,
+            },
+            TypeDef::Other {
+                name, definition, ..
+            } => writeln!(f, "  Type: {} is {}", name, definition)?,
+        }
+    }
+    Ok(())
+}
+
+/// 解析エラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeError {
+    pub message: String,
+    pub span: Span,
+    /// 開始位置の行・列（[`analyze_vhdl`] が返すエラーには必ず付く）
+    pub location: Option<Location>,
+}
+
+impl AnalyzeError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+            location: None,
+        }
+    }
+
+    /// ソースから開始位置の行・列を求めて付ける
+    pub fn locate(mut self, source: &str) -> Self {
+        self.location = Some(SourceMap::new("", source).location(self.span.start));
+        self
+    }
+
+    /// 位置にファイル名を付ける
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        if let Some(location) = &mut self.location {
+            location.file = Some(file.into());
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(
+                f,
+                "{} at position {}-{}",
+                self.message, self.span.start, self.span.end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AnalyzeError {}
+
+/// 意味解析器
+pub struct Analyzer<'source> {
+    tokens: Vec<Token<'source>>,
+    pos: usize,
+    /// 順次文の入れ子の深さ
+    depth: usize,
+    /// 式の入れ子（括弧・単項演算子）の深さ
+    expr_depth: usize,
+    /// 読んでいる式の二項演算子の数
+    expr_operators: usize,
+}
+
+/// 順次文の入れ子の上限（これより深い入力はエラーにする）
+const MAX_NESTING: usize = 256;
+
+/// 式の入れ子の上限（式の1段は使うスタックが大きいので順次文より小さくする）
+const MAX_EXPR_NESTING: usize = 64;
+
+/// ひとつの式の二項演算子の上限（演算の連鎖も木を深くし、表示や破棄でスタックを使う）
+const MAX_EXPR_OPERATORS: usize = 1024;
+
+impl<'source> Analyzer<'source> {
+    /// トークン列からAnalyzerを作成（Commentは除外）
+    pub fn new(tokens: Vec<Token<'source>>) -> Self {
+        let tokens: Vec<Token> = tokens
+            .into_iter()
+            .filter(|t| t.kind != TokenKind::Comment && t.kind != TokenKind::Eof)
+            .collect();
+        Self {
+            tokens,
+            pos: 0,
+            depth: 0,
+            expr_depth: 0,
+            expr_operators: 0,
+        }
+    }
+
+    /// 解析を実行
+    pub fn analyze(&mut self) -> Result<AnalyzeResult, AnalyzeError> {
+        let _timed = timed!("parse", tokens = self.tokens.len());
+        let mut entities = Vec::new();
+        let mut architectures = Vec::new();
+        let mut packages = Vec::new();
+        let mut package_bodies = Vec::new();
+
+        while self.current().kind != TokenKind::Eof {
+            match self.current().kind {
+                TokenKind::Entity => {
+                    entities.push(self.parse_entity()?);
+                }
+                TokenKind::Architecture => {
+                    architectures.push(self.parse_architecture()?);
+                }
+                TokenKind::Package if self.peek(1).kind == TokenKind::Body => {
+                    package_bodies.push(self.parse_package_body()?);
+                }
+                TokenKind::Package => {
+                    packages.extend(self.parse_package()?);
+                }
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+
+        Ok(AnalyzeResult {
+            entities,
+            architectures,
+            packages,
+            package_bodies,
+        })
+    }
+
+    // --- トークン操作 ---
+
+    fn current(&self) -> &Token<'source> {
+        self.tokens.get(self.pos).unwrap_or(&EOF_SENTINEL)
+    }
+
+    fn peek(&self, offset: usize) -> &Token<'source> {
         self.tokens.get(self.pos + offset).unwrap_or(&EOF_SENTINEL)
     }
 
-    fn advance(&mut self) {
-        if self.pos < self.tokens.len() {
-            self.pos += 1;
-        }
+    fn advance(&mut self) {
+        if self.pos < self.tokens.len() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token<'source>, AnalyzeError> {
+        let token = self.current().clone();
+        if token.kind == kind {
+            self.advance();
+            Ok(token)
+        } else {
+            Err(AnalyzeError::new(
+                format!(
+                    "expected {:?}, found {:?} '{}'",
+                    kind, token.kind, token.text
+                ),
+                token.span,
+            ))
+        }
+    }
+
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        if self.current().kind == kind {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_until(&mut self, kinds: &[TokenKind]) {
+        while self.current().kind != TokenKind::Eof {
+            if kinds.contains(&self.current().kind) {
+                return;
+            }
+            self.advance();
+        }
+    }
+
+    // --- Entity 解析 ---
+
+    fn parse_entity(&mut self) -> Result<EntityDef, AnalyzeError> {
+        let start = self.current().span;
+        self.expect(TokenKind::Entity)?;
+        let name = self.expect(TokenKind::Identifier)?.text.to_string();
+        self.expect(TokenKind::Is)?;
+
+        let mut generics = Vec::new();
+        if self.current().kind == TokenKind::Generic {
+            self.advance(); // generic
+            self.expect(TokenKind::LeftParen)?;
+            generics = self.parse_generic_list()?;
+            self.expect(TokenKind::RightParen)?;
+            self.expect(TokenKind::Semicolon)?;
+        }
+
+        let mut ports = Vec::new();
+        if self.current().kind == TokenKind::Port {
+            self.advance(); // port
+            self.expect(TokenKind::LeftParen)?;
+            ports = self.parse_port_list()?;
+            self.expect(TokenKind::RightParen)?;
+            self.expect(TokenKind::Semicolon)?;
+        }
+
+        // end [entity] [name] ;
+        self.skip_until(&[TokenKind::Semicolon]);
+        let end = self.current().span;
+        self.advance(); // ;
+
+        Ok(EntityDef {
+            name,
+            generics,
+            ports,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    fn parse_generic_list(&mut self) -> Result<Vec<GenericDef>, AnalyzeError> {
+        let mut generics = Vec::new();
+
+        while self.current().kind != TokenKind::RightParen && self.current().kind != TokenKind::Eof
+        {
+            match self.current().kind {
+                // VHDL-2008 の型・サブプログラム・パッケージのジェネリックは読み飛ばす
+                TokenKind::Type
+                | TokenKind::Function
+                | TokenKind::Procedure
+                | TokenKind::Pure
+                | TokenKind::Impure
+                | TokenKind::Package => {
+                    self.interface_tail(&[TokenKind::Semicolon]);
+                }
+                _ => generics.append(&mut self.parse_generic_group()?),
+            }
+            self.eat(TokenKind::Semicolon);
+        }
+
+        Ok(generics)
+    }
+
+    /// `[constant] 名前, ... : [in] 型 [制約] [:= 既定値]`
+    fn parse_generic_group(&mut self) -> Result<Vec<GenericDef>, AnalyzeError> {
+        self.eat(TokenKind::Constant);
+        let span = self.current().span;
+        let mut names = Vec::new();
+
+        names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        while self.eat(TokenKind::Comma) {
+            names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        }
+
+        self.expect(TokenKind::Colon)?;
+        self.eat(TokenKind::In);
+        let vhdl_type = self.parse_type()?;
+        // `integer range 0 to 15` などの制約は読み飛ばす
+        self.interface_tail(&[TokenKind::Assignment, TokenKind::Semicolon]);
+        let default_value = if self.eat(TokenKind::Assignment) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(names
+            .into_iter()
+            .map(|n| GenericDef {
+                name: n,
+                vhdl_type: vhdl_type.clone(),
+                default_value: default_value.clone(),
+                span,
+            })
+            .collect())
+    }
+
+    /// インターフェースの要素の残りを、括弧の外の `stop` か閉じ括弧の手前まで読む（読んだ表記を返す）
+    fn interface_tail(&mut self, stop: &[TokenKind]) -> Vec<&'source str> {
+        let mut parts = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            let token = self.current();
+            match token.kind {
+                TokenKind::Eof => break,
+                TokenKind::RightParen if depth == 0 => break,
+                ref kind if depth == 0 && stop.contains(kind) => break,
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => depth -= 1,
+                _ => {}
+            }
+            parts.push(token.text);
+            self.advance();
+        }
+        parts
+    }
+
+    fn parse_port_list(&mut self) -> Result<Vec<PortDef>, AnalyzeError> {
+        let mut ports = Vec::new();
+
+        while self.current().kind != TokenKind::RightParen && self.current().kind != TokenKind::Eof
+        {
+            let mut group = self.parse_port_group()?;
+            ports.append(&mut group);
+            self.eat(TokenKind::Semicolon);
+        }
+
+        Ok(ports)
+    }
+
+    fn parse_port_group(&mut self) -> Result<Vec<PortDef>, AnalyzeError> {
+        let span = self.current().span;
+        let mut names = Vec::new();
+
+        names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        while self.eat(TokenKind::Comma) {
+            names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        }
+
+        self.expect(TokenKind::Colon)?;
+        let direction = self.parse_direction()?;
+        let vhdl_type = self.parse_type()?;
+
+        Ok(names
+            .into_iter()
+            .map(|n| PortDef {
+                name: n,
+                direction: direction.clone(),
+                vhdl_type: vhdl_type.clone(),
+                span,
+            })
+            .collect())
+    }
+
+    fn parse_direction(&mut self) -> Result<PortDirection, AnalyzeError> {
+        let token = self.current().clone();
+        match token.kind {
+            TokenKind::In => {
+                self.advance();
+                Ok(PortDirection::In)
+            }
+            TokenKind::Out => {
+                self.advance();
+                Ok(PortDirection::Out)
+            }
+            TokenKind::Inout => {
+                self.advance();
+                Ok(PortDirection::Inout)
+            }
+            TokenKind::Buffer => {
+                self.advance();
+                Ok(PortDirection::Buffer)
+            }
+            _ => Err(AnalyzeError::new(
+                format!("expected port direction, found '{}'", token.text),
+                token.span,
+            )),
+        }
+    }
+
+    // --- 型の解析 ---
+
+    fn parse_type(&mut self) -> Result<VhdlType, AnalyzeError> {
+        let token = self.current().clone();
+        match token.kind {
+            TokenKind::StdLogic => {
+                self.advance();
+                Ok(VhdlType::StdLogic)
+            }
+            TokenKind::StdLogicVector => {
+                self.advance();
+                let (high, low, ascending) = self.parse_vector_range()?;
+                Ok(VhdlType::StdLogicVector {
+                    high,
+                    low,
+                    ascending,
+                })
+            }
+            TokenKind::Integer => {
+                self.advance();
+                Ok(VhdlType::Integer(self.parse_scalar_range()?))
+            }
+            TokenKind::Boolean => {
+                self.advance();
+                Ok(VhdlType::Boolean)
+            }
+            TokenKind::Identifier => {
+                self.advance();
+                // 標準パッケージの型名は予約語ではないので綴りで見分ける
+                Ok(match token.text.to_ascii_lowercase().as_str() {
+                    "signed" => {
+                        let (high, low, ascending) = self.parse_vector_range()?;
+                        VhdlType::Signed {
+                            high,
+                            low,
+                            ascending,
+                        }
+                    }
+                    "unsigned" => {
+                        let (high, low, ascending) = self.parse_vector_range()?;
+                        VhdlType::Unsigned {
+                            high,
+                            low,
+                            ascending,
+                        }
+                    }
+                    "bit_vector" => {
+                        let (high, low, ascending) = self.parse_vector_range()?;
+                        VhdlType::BitVector {
+                            high,
+                            low,
+                            ascending,
+                        }
+                    }
+                    "string" => VhdlType::Str(
+                        self.parse_range_constraint()?
+                            .map(|(left, _, right)| (left, right)),
+                    ),
+                    "natural" => VhdlType::Natural(self.parse_scalar_range()?),
+                    "positive" => VhdlType::Positive(self.parse_scalar_range()?),
+                    "real" => VhdlType::Real,
+                    "time" => VhdlType::Time,
+                    "bit" => VhdlType::Bit,
+                    _ => VhdlType::Other(token.text.to_string()),
+                })
+            }
+            _ => Err(AnalyzeError::new(
+                format!("expected type, found '{}'", token.text),
+                token.span,
+            )),
+        }
+    }
+
+    /// ベクタの上・下の境界と `to` の向きか（制約がなければ `(0, 0)` の `downto`）
+    fn parse_vector_range(&mut self) -> Result<(BoundExpr, BoundExpr, bool), AnalyzeError> {
+        Ok(match self.parse_range_constraint()? {
+            Some((left, Direction::To, right)) => (right, left, true),
+            Some((left, Direction::Downto, right)) => (left, right, false),
+            None => (0.into(), 0.into(), false),
+        })
+    }
+
+    /// 整数型に続く `range 左 to|downto 右` の制約
+    fn parse_scalar_range(&mut self) -> Result<Option<RangeConstraint>, AnalyzeError> {
+        if !self.eat(TokenKind::Range) {
+            return Ok(None);
+        }
+        let left = self.parse_bound()?;
+        let ascending = self.parse_range_direction()? == Direction::To;
+        let right = self.parse_bound()?;
+        Ok(Some(RangeConstraint {
+            left,
+            right,
+            ascending,
+        }))
+    }
+
+    /// `to` か `downto`
+    fn parse_range_direction(&mut self) -> Result<Direction, AnalyzeError> {
+        let direction = match self.current().kind {
+            TokenKind::To => Direction::To,
+            TokenKind::Downto => Direction::Downto,
+            _ => {
+                let token = self.current();
+                return Err(AnalyzeError::new(
+                    format!("expected 'to' or 'downto', found '{}'", token.text),
+                    token.span,
+                ));
+            }
+        };
+        self.advance();
+        Ok(direction)
+    }
+
+    /// `(左 downto 右)` / `(左 to 右)` の制約
+    fn parse_range_constraint(
+        &mut self,
+    ) -> Result<Option<(BoundExpr, Direction, BoundExpr)>, AnalyzeError> {
+        if !self.eat(TokenKind::LeftParen) {
+            return Ok(None);
+        }
+        let left = self.parse_bound()?;
+        let direction = self.parse_range_direction()?;
+        let right = self.parse_bound()?;
+        self.expect(TokenKind::RightParen)?;
+        Ok(Some((left, direction, right)))
+    }
+
+    /// ベクタの範囲の境界（整数リテラル・名前と `+ - * / **` だけの式）
+    fn parse_bound(&mut self) -> Result<BoundExpr, AnalyzeError> {
+        let start = self.current().span;
+        let expr = self.parse_expr()?;
+        bound_of(&expr).map_err(|message| AnalyzeError::new(message, start))
+    }
+
+    // --- 式 ---
+
+    /// 式（範囲・`others` は読まない）
+    fn parse_expr(&mut self) -> Result<Expr, AnalyzeError> {
+        self.parse_expr_above(LOGICAL)
+    }
+
+    /// 強さ `min` 以上の二項演算子だけを結合した式（演算子順位法）
+    fn parse_expr_above(&mut self, min: u8) -> Result<Expr, AnalyzeError> {
+        // 深すぎる入力はスタックを使い切る前にエラーにする
+        if self.expr_depth >= MAX_EXPR_NESTING {
+            return Err(AnalyzeError::new("nesting too deep", self.current().span));
+        }
+        if self.expr_depth == 0 {
+            self.expr_operators = 0;
+        }
+        self.expr_depth += 1;
+        let result = self.parse_binary(min);
+        self.expr_depth -= 1;
+        result
+    }
+
+    fn parse_binary(&mut self, min: u8) -> Result<Expr, AnalyzeError> {
+        let sign = match self.current().kind {
+            TokenKind::Plus => Some(UnaryOp::Plus),
+            TokenKind::Minus => Some(UnaryOp::Minus),
+            _ => None,
+        };
+        let mut lhs = match sign {
+            // 符号は最初の項（乗除算まで）に掛かる
+            Some(op) => {
+                self.advance();
+                let operand = self.parse_expr_above(min.max(MULTIPLYING))?;
+                Expr::Unary(op, Box::new(operand))
+            }
+            None => self.parse_factor()?,
+        };
+        let mut previous: Option<BinaryOp> = None;
+        while let Some(op) = BinaryOp::from_token(&self.current().kind) {
+            let precedence = op.precedence();
+            if precedence < min {
+                break;
+            }
+            // `a and b or c` のような論理演算の混在と、関係・シフト・`**` の連続は括弧が要る
+            if let Some(previous) = previous
+                && previous.precedence() == precedence
+                && !op.may_follow(previous)
+            {
+                return Err(AnalyzeError::new(
+                    format!(
+                        "'{}' after '{}' needs parentheses",
+                        op.as_str(),
+                        previous.as_str()
+                    ),
+                    self.current().span,
+                ));
+            }
+            previous = Some(op);
+            self.expr_operators += 1;
+            if self.expr_operators > MAX_EXPR_OPERATORS {
+                return Err(AnalyzeError::new(
+                    "expression too long",
+                    self.current().span,
+                ));
+            }
+            self.advance();
+            let rhs = self.parse_expr_above(precedence + 1)?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    /// 因子（`not`・`abs`・`??` の演算か一次子）
+    fn parse_factor(&mut self) -> Result<Expr, AnalyzeError> {
+        let op = match self.current().kind {
+            TokenKind::Not => UnaryOp::Not,
+            TokenKind::Abs => UnaryOp::Abs,
+            TokenKind::Condition => UnaryOp::Condition,
+            _ => return self.parse_primary(),
+        };
+        self.advance();
+        let operand = self.parse_expr_above(PRIMARY)?;
+        Ok(Expr::Unary(op, Box::new(operand)))
+    }
+
+    /// 一次子（リテラル・名前・括弧で囲んだ式・集合体）
+    fn parse_primary(&mut self) -> Result<Expr, AnalyzeError> {
+        let token = self.current().clone();
+        match token.kind {
+            TokenKind::IntegerLiteral | TokenKind::RealLiteral => {
+                self.advance();
+                let TokenKind::Literal(kind) = token.kind else {
+                    unreachable!()
+                };
+                // 単位が続けば物理型のリテラル（`10 ns`）
+                if self.current().kind == TokenKind::Identifier {
+                    let unit = self.current().text.to_string();
+                    self.advance();
+                    return Ok(Expr::Physical(token.text.to_string(), unit));
+                }
+                Ok(Expr::Literal(kind, token.text.to_string()))
+            }
+            TokenKind::Literal(kind) => {
+                self.advance();
+                Ok(Expr::Literal(kind, token.text.to_string()))
+            }
+            TokenKind::Identifier | TokenKind::TypeName(_) => {
+                self.advance();
+                self.parse_name_suffix(Expr::Name(token.text.to_string()))
+            }
+            TokenKind::LeftParen => self.parse_parenthesized(),
+            _ => Err(AnalyzeError::new(
+                format!("expected expression, found '{}'", token.text),
+                token.span,
+            )),
+        }
+    }
+
+    /// 名前に続く選択（`.`）・属性と限定式（`'`）・添字と呼び出し（`(...)`）
+    fn parse_name_suffix(&mut self, mut name: Expr) -> Result<Expr, AnalyzeError> {
+        loop {
+            name = match self.current().kind {
+                TokenKind::Dot => {
+                    self.advance();
+                    let suffix = self.name_word("selected name")?;
+                    Expr::Selected(Box::new(name), suffix)
+                }
+                TokenKind::Apostrophe if self.peek(1).kind == TokenKind::LeftParen => {
+                    self.advance();
+                    let operand = self.parse_parenthesized()?;
+                    Expr::Qualified(Box::new(name), Box::new(operand))
+                }
+                TokenKind::Apostrophe => {
+                    self.advance();
+                    let attribute = self.name_word("attribute name")?;
+                    Expr::Attribute(Box::new(name), attribute)
+                }
+                TokenKind::LeftParen => {
+                    let elements = self.parse_elements()?;
+                    Expr::Apply(Box::new(name), elements)
+                }
+                _ => return Ok(name),
+            };
+        }
+    }
+
+    /// `.` や `'` の後の語（`all`・`range` などの予約語も名前として読む）
+    fn name_word(&mut self, what: &str) -> Result<String, AnalyzeError> {
+        let token = self.current().clone();
+        if !(token.kind == TokenKind::Identifier || token.kind.is_keyword() || token.kind.is_type())
+        {
+            return Err(AnalyzeError::new(
+                format!("expected {}, found '{}'", what, token.text),
+                token.span,
+            ));
+        }
+        self.advance();
+        Ok(token.text.to_string())
+    }
+
+    /// `(...)`（位置による要素ひとつだけなら括弧で囲んだ式、それ以外は集合体）
+    fn parse_parenthesized(&mut self) -> Result<Expr, AnalyzeError> {
+        let mut elements = self.parse_elements()?;
+        if elements.len() == 1
+            && elements[0].choices.is_empty()
+            && !matches!(elements[0].value, Expr::Range(..))
+        {
+            return Ok(elements.remove(0).value);
+        }
+        Ok(Expr::Aggregate(elements))
+    }
+
+    /// `(要素, ...)`（各要素は `選択肢 | ... => 値` か位置による値）
+    fn parse_elements(&mut self) -> Result<Vec<Element>, AnalyzeError> {
+        self.expect(TokenKind::LeftParen)?;
+        let mut elements = Vec::new();
+        loop {
+            let mut choices = vec![self.parse_choice()?];
+            while self.eat(TokenKind::Bar) {
+                choices.push(self.parse_choice()?);
+            }
+            let element = if self.current().kind == TokenKind::Association
+                || choices.len() > 1
+                || choices[0] == Expr::Others
+            {
+                self.expect(TokenKind::Association)?;
+                Element {
+                    choices,
+                    value: self.parse_expr()?,
+                }
+            } else {
+                Element {
+                    choices: Vec::new(),
+                    value: choices.remove(0),
+                }
+            };
+            elements.push(element);
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        self.expect(TokenKind::RightParen)?;
+        Ok(elements)
+    }
+
+    /// 選択肢（`others`・範囲・式）
+    fn parse_choice(&mut self) -> Result<Expr, AnalyzeError> {
+        if self.eat(TokenKind::Others) {
+            return Ok(Expr::Others);
+        }
+        self.parse_range_or_expr()
+    }
+
+    /// 範囲（`左 to 右` / `左 downto 右`）か式
+    fn parse_range_or_expr(&mut self) -> Result<Expr, AnalyzeError> {
+        let left = self.parse_expr()?;
+        let direction = match self.current().kind {
+            TokenKind::To => Direction::To,
+            TokenKind::Downto => Direction::Downto,
+            _ => return Ok(left),
+        };
+        self.advance();
+        let right = self.parse_expr()?;
+        Ok(Expr::Range(Box::new(left), direction, Box::new(right)))
+    }
+
+    // --- Package 解析 ---
+
+    /// `package 名前 is 宣言... end [package] [名前];`（インスタンス化の宣言は None）
+    fn parse_package(&mut self) -> Result<Option<PackageDef>, AnalyzeError> {
+        let start = self.current().span;
+        self.expect(TokenKind::Package)?;
+        let name = self.expect(TokenKind::Identifier)?.text.to_string();
+        self.expect(TokenKind::Is)?;
+        // `package 名前 is new ...;` は宣言部を持たない
+        if self.current().kind == TokenKind::New {
+            self.skip_declaration();
+            return Ok(None);
+        }
+        self.parse_package_items(name, start).map(Some)
+    }
+
+    /// `package body 名前 is 宣言... end [package body] [名前];`
+    fn parse_package_body(&mut self) -> Result<PackageDef, AnalyzeError> {
+        let start = self.current().span;
+        self.expect(TokenKind::Package)?;
+        self.expect(TokenKind::Body)?;
+        let name = self.expect(TokenKind::Identifier)?.text.to_string();
+        self.expect(TokenKind::Is)?;
+        self.parse_package_items(name, start)
+    }
+
+    /// パッケージ（本体）の宣言部と `end [package [body]] [名前];`
+    fn parse_package_items(
+        &mut self,
+        name: String,
+        start: Span,
+    ) -> Result<PackageDef, AnalyzeError> {
+        let mut constants = Vec::new();
+        let mut types = Vec::new();
+        let mut subprograms = Vec::new();
+        while !matches!(self.current().kind, TokenKind::End | TokenKind::Eof) {
+            match self.current().kind {
+                TokenKind::Constant => constants.append(&mut self.parse_constant_decl()?),
+                TokenKind::Type | TokenKind::Subtype => types.push(self.parse_type_decl()?),
+                TokenKind::Function
+                | TokenKind::Procedure
+                | TokenKind::Pure
+                | TokenKind::Impure => subprograms.push(self.parse_subprogram()?),
+                TokenKind::Component => self.skip_block(TokenKind::Component),
+                // ジェネリック（VHDL-2008）・信号・属性・別名などは読み飛ばす
+                TokenKind::Generic => {
+                    self.advance();
+                    self.skip_declaration();
+                }
+                _ => self.skip_declaration(),
+            }
+        }
+        self.expect(TokenKind::End)?;
+        if self.eat(TokenKind::Package) {
+            self.eat(TokenKind::Body);
+        }
+        self.eat(TokenKind::Identifier);
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+
+        Ok(PackageDef {
+            name,
+            constants,
+            types,
+            subprograms,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    /// `type 名前 is 定義;` / `type 名前 is (リテラル, ...);` / `type 名前 is array (範囲) of 型;` /
+    /// `subtype 名前 is 型;`
+    fn parse_type_decl(&mut self) -> Result<TypeDef, AnalyzeError> {
+        let start = self.current().span;
+        let subtype = self.current().kind == TokenKind::Subtype;
+        self.advance();
+        let name = self.expect(TokenKind::Identifier)?.text.to_string();
+        // 不完全型の宣言（`type cell;`）
+        if !self.eat(TokenKind::Is) {
+            let end = self.current().span;
+            self.expect(TokenKind::Semicolon)?;
+            return Ok(TypeDef::Other {
+                name,
+                definition: String::new(),
+                span: Span::new(start.start, end.end),
+            });
+        }
+        if subtype {
+            // 解決関数や独自の型の制約が続くサブタイプは表記のまま残す
+            let first = self.pos;
+            if let Ok(vhdl_type) = self.parse_type()
+                && self.current().kind == TokenKind::Semicolon
+            {
+                let end = self.current().span;
+                self.advance();
+                return Ok(TypeDef::Subtype {
+                    name,
+                    vhdl_type,
+                    span: Span::new(start.start, end.end),
+                });
+            }
+            self.pos = first;
+        } else if self.eat(TokenKind::LeftParen) {
+            let mut literals = Vec::new();
+            loop {
+                let token = self.current().clone();
+                if !matches!(
+                    token.kind,
+                    TokenKind::Identifier | TokenKind::CharacterLiteral
+                ) {
+                    return Err(AnalyzeError::new(
+                        format!("expected enumeration literal, found '{}'", token.text),
+                        token.span,
+                    ));
+                }
+                self.advance();
+                literals.push(token.text.to_string());
+                if !self.eat(TokenKind::Comma) {
+                    break;
+                }
+            }
+            self.expect(TokenKind::RightParen)?;
+            let end = self.current().span;
+            self.expect(TokenKind::Semicolon)?;
+            return Ok(TypeDef::Enum {
+                name,
+                literals,
+                span: Span::new(start.start, end.end),
+            });
+        } else if self.current().kind == TokenKind::Array {
+            // 多次元の配列や `natural range 0 to 3` のような添字は表記のまま残す
+            let first = self.pos;
+            if let Ok((index_range, index_type, element)) = self.parse_array_definition()
+                && self.current().kind == TokenKind::Semicolon
+            {
+                let end = self.current().span;
+                self.advance();
+                return Ok(TypeDef::Array {
+                    name,
+                    index_range,
+                    index_type,
+                    element,
+                    span: Span::new(start.start, end.end),
+                });
+            }
+            self.pos = first;
+        }
+        let definition = self.definition_text();
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+        Ok(TypeDef::Other {
+            name,
+            definition,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    /// `array (添字の範囲) of 要素の型` の添字の範囲（制約のない配列は添字の型）と要素の型
+    fn parse_array_definition(
+        &mut self,
+    ) -> Result<(Option<Expr>, Option<String>, VhdlType), AnalyzeError> {
+        self.expect(TokenKind::Array)?;
+        self.expect(TokenKind::LeftParen)?;
+        let (index_range, index_type) =
+            if self.peek(1).kind == TokenKind::Range && self.peek(2).kind == TokenKind::Box {
+                let index_type = self.current().text.to_string();
+                self.advance();
+                self.advance();
+                self.advance();
+                (None, Some(index_type))
+            } else {
+                (Some(self.parse_range_or_expr()?), None)
+            };
+        self.expect(TokenKind::RightParen)?;
+        self.expect(TokenKind::Of)?;
+        let element = self.parse_type()?;
+        Ok((index_range, index_type, element))
+    }
+
+    /// 型の定義を `;` まで読む（`record`・`units`・`protected` は対応する `end` まで）
+    fn definition_text(&mut self) -> String {
+        let mut parts = Vec::new();
+        let mut block: Option<TokenKind> = None;
+        loop {
+            let kind = self.current().kind.clone();
+            match kind {
+                TokenKind::Eof => break,
+                TokenKind::Semicolon if block.is_none() => break,
+                TokenKind::Record | TokenKind::Units | TokenKind::Protected if block.is_none() => {
+                    block = Some(kind);
+                }
+                TokenKind::End if block.as_ref() == Some(&self.peek(1).kind) => {
+                    parts.push(self.current().text);
+                    self.advance();
+                    block = None;
+                }
+                _ => {}
+            }
+            parts.push(self.current().text);
+            self.advance();
+        }
+        parts.join(" ")
+    }
+
+    /// `[pure|impure] function 名前 [(仮引数...)] return 型` / `procedure 名前 [(仮引数...)]` に続く
+    /// `;` か `is 宣言... begin 文... end [function|procedure] [名前];`
+    fn parse_subprogram(&mut self) -> Result<SubprogramDecl, AnalyzeError> {
+        let start = self.current().span;
+        let impure = self.eat(TokenKind::Impure);
+        if !impure {
+            self.eat(TokenKind::Pure);
+        }
+        let kind = if self.eat(TokenKind::Procedure) {
+            SubprogramKind::Procedure
+        } else {
+            self.expect(TokenKind::Function)?;
+            SubprogramKind::Function
+        };
+        let token = self.current().clone();
+        if !matches!(token.kind, TokenKind::Identifier | TokenKind::StringLiteral) {
+            return Err(AnalyzeError::new(
+                format!("expected subprogram name, found '{}'", token.text),
+                token.span,
+            ));
+        }
+        self.advance();
+        let (params, return_type) = self.parse_subprogram_header(kind)?;
+        let signature = self
+            .interface_tail(&[TokenKind::Semicolon, TokenKind::Is])
+            .join(" ");
+        let mut decl = SubprogramDecl {
+            kind,
+            impure,
+            name: token.text.to_string(),
+            signature,
+            params,
+            return_type,
+            body: None,
+            constants: Vec::new(),
+            variables: Vec::new(),
+            types: Vec::new(),
+            subprograms: Vec::new(),
+            span: start,
+        };
+        if self.current().kind == TokenKind::Is && self.peek(1).kind != TokenKind::New {
+            self.advance();
+            self.parse_subprogram_body(&mut decl)?;
+        } else {
+            // 汎用サブプログラムのインスタンス化（`is new ...`）は宣言として扱う
+            self.interface_tail(&[TokenKind::Semicolon]);
+        }
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+        decl.span = Span::new(start.start, end.end);
+        Ok(decl)
+    }
+
+    /// `[(仮引数; ...)]` と関数の `return 型` を読み、読み始めの位置に戻る
+    ///
+    /// 表記は [`SubprogramDecl::signature`] のために続けて読み直す。
+    fn parse_subprogram_header(
+        &mut self,
+        kind: SubprogramKind,
+    ) -> Result<(Vec<ParameterDef>, Option<VhdlType>), AnalyzeError> {
+        let first = self.pos;
+        let mut params = Vec::new();
+        if self.eat(TokenKind::LeftParen) {
+            while !matches!(self.current().kind, TokenKind::RightParen | TokenKind::Eof) {
+                params.append(&mut self.parse_parameter_group()?);
+                self.eat(TokenKind::Semicolon);
+            }
+            self.expect(TokenKind::RightParen)?;
+        }
+        let return_type = match kind {
+            SubprogramKind::Function => {
+                self.expect(TokenKind::Return)?;
+                Some(self.parse_type()?)
+            }
+            SubprogramKind::Procedure => None,
+        };
+        self.pos = first;
+        Ok((params, return_type))
+    }
+
+    /// `[constant|signal|variable|file] 名前, ... : [モード] 型 [制約] [:= 既定値]`
+    fn parse_parameter_group(&mut self) -> Result<Vec<ParameterDef>, AnalyzeError> {
+        if matches!(
+            self.current().kind,
+            TokenKind::Constant | TokenKind::Signal | TokenKind::Variable | TokenKind::File
+        ) {
+            self.advance();
+        }
+        let span = self.current().span;
+        let mut names = Vec::new();
+
+        names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        while self.eat(TokenKind::Comma) {
+            names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        }
+
+        self.expect(TokenKind::Colon)?;
+        let direction = match self.current().kind {
+            TokenKind::In | TokenKind::Out | TokenKind::Inout | TokenKind::Buffer => {
+                self.parse_direction()?
+            }
+            _ => PortDirection::In,
+        };
+        let vhdl_type = self.parse_type()?;
+        // `integer range 0 to 15` などの制約は読み飛ばす
+        self.interface_tail(&[TokenKind::Assignment, TokenKind::Semicolon]);
+        let default_value = if self.eat(TokenKind::Assignment) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        Ok(names
+            .into_iter()
+            .map(|n| ParameterDef {
+                name: n,
+                direction: direction.clone(),
+                vhdl_type: vhdl_type.clone(),
+                default_value: default_value.clone(),
+                span,
+            })
+            .collect())
+    }
+
+    /// サブプログラムの宣言部と本体（`end [function|procedure] [名前]` まで。`;` は読まない）
+    fn parse_subprogram_body(&mut self, decl: &mut SubprogramDecl) -> Result<(), AnalyzeError> {
+        if self.depth >= MAX_NESTING {
+            return Err(AnalyzeError::new("nesting too deep", self.current().span));
+        }
+        self.depth += 1;
+        let result = self.parse_subprogram_body_inner(decl);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_subprogram_body_inner(
+        &mut self,
+        decl: &mut SubprogramDecl,
+    ) -> Result<(), AnalyzeError> {
+        // 宣言部は定数・変数・型・入れ子のサブプログラムを読み、別名などは読み飛ばす
+        loop {
+            match self.current().kind {
+                TokenKind::Function
+                | TokenKind::Procedure
+                | TokenKind::Pure
+                | TokenKind::Impure => decl.subprograms.push(self.parse_subprogram()?),
+                TokenKind::Type | TokenKind::Subtype => decl.types.push(self.parse_type_decl()?),
+                TokenKind::Constant => decl.constants.append(&mut self.parse_constant_decl()?),
+                TokenKind::Variable => decl.variables.append(&mut self.parse_variable_decl()?),
+                TokenKind::Alias
+                | TokenKind::Attribute
+                | TokenKind::Use
+                | TokenKind::File
+                | TokenKind::Group => self.skip_declaration(),
+                _ => break,
+            }
+        }
+        self.expect(TokenKind::Begin)?;
+        decl.body = Some(self.parse_statements()?);
+        self.expect(TokenKind::End)?;
+        if !self.eat(TokenKind::Function) {
+            self.eat(TokenKind::Procedure);
+        }
+        if matches!(
+            self.current().kind,
+            TokenKind::Identifier | TokenKind::StringLiteral
+        ) {
+            self.advance();
+        }
+        Ok(())
+    }
+
+    /// 読まない宣言を `;` まで読み飛ばす
+    fn skip_declaration(&mut self) {
+        if self.interface_tail(&[TokenKind::Semicolon]).is_empty() {
+            self.advance();
+        }
+        self.eat(TokenKind::Semicolon);
+    }
+
+    /// `end 予約語 [名前];` まで読み飛ばす（コンポーネント宣言など）
+    fn skip_block(&mut self, kind: TokenKind) {
+        while self.current().kind != TokenKind::Eof
+            && !(self.current().kind == TokenKind::End && self.peek(1).kind == kind)
+        {
+            self.advance();
+        }
+        self.skip_declaration();
+    }
+
+    // --- Architecture 解析 ---
+
+    fn parse_architecture(&mut self) -> Result<ArchitectureDef, AnalyzeError> {
+        let start = self.current().span;
+        self.expect(TokenKind::Architecture)?;
+        let arch_name = self.expect(TokenKind::Identifier)?.text.to_string();
+        self.expect(TokenKind::Of)?;
+        let entity_name = self.expect(TokenKind::Identifier)?.text.to_string();
+        self.expect(TokenKind::Is)?;
+
+        let mut signals = Vec::new();
+        let mut constants = Vec::new();
+        let mut types = Vec::new();
+        let mut subprograms = Vec::new();
+
+        // 宣言部: begin が来るまで signal・constant・型・サブプログラムを抽出
+        while self.current().kind != TokenKind::Begin && self.current().kind != TokenKind::Eof {
+            match self.current().kind {
+                TokenKind::Signal => signals.append(&mut self.parse_signal_decl()?),
+                TokenKind::Constant => constants.append(&mut self.parse_constant_decl()?),
+                TokenKind::Type | TokenKind::Subtype => types.push(self.parse_type_decl()?),
+                TokenKind::Function
+                | TokenKind::Procedure
+                | TokenKind::Pure
+                | TokenKind::Impure => subprograms.push(self.parse_subprogram()?),
+                _ => self.advance(),
+            }
+        }
+
+        // begin 以降の本体からインスタンス化文とプロセス文を抽出（end architecture まで）
+        let (instances, processes) = self.parse_architecture_body()?;
+
+        let end_pos = self.pos.saturating_sub(1);
+        let end = self.tokens.get(end_pos).map(|t| t.span).unwrap_or(start);
+
+        Ok(ArchitectureDef {
+            name: arch_name,
+            entity_name,
+            signals,
+            constants,
+            instances,
+            processes,
+            types,
+            subprograms,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    /// 本体を end architecture まで読み、インスタンス化文とプロセス文以外の文は読み飛ばす
+    fn parse_architecture_body(
+        &mut self,
+    ) -> Result<(Vec<InstanceDef>, Vec<ProcessDef>), AnalyzeError> {
+        let mut instances = Vec::new();
+        let mut processes = Vec::new();
+        // 文の先頭か（直前が `begin`・`;`・`generate`）
+        let mut at_statement_start = true;
+        while self.current().kind != TokenKind::Eof {
+            if self.current().kind == TokenKind::End && self.peek(1).kind == TokenKind::Architecture
+            {
+                self.skip_until(&[TokenKind::Semicolon]);
+                self.advance(); // ;
+                break;
+            }
+            if at_statement_start && self.is_instance_start() {
+                instances.push(self.parse_instance()?);
+                continue;
+            }
+            if at_statement_start && self.is_process_start() {
+                processes.push(self.parse_process()?);
+                continue;
+            }
+            at_statement_start = matches!(
+                self.current().kind,
+                TokenKind::Begin | TokenKind::Semicolon | TokenKind::Generate
+            );
+            self.advance();
+        }
+        Ok((instances, processes))
+    }
+
+    /// `ラベル : [component] 名前 generic map | port map` か `ラベル : entity` で始まるか
+    ///
+    /// map のない `ラベル : 名前;` は手続き呼び出しと区別できないため対象外です。
+    fn is_instance_start(&self) -> bool {
+        if self.current().kind != TokenKind::Identifier || self.peek(1).kind != TokenKind::Colon {
+            return false;
+        }
+        match self.peek(2).kind {
+            TokenKind::Component | TokenKind::Entity => true,
+            TokenKind::Identifier => matches!(
+                (&self.peek(3).kind, &self.peek(4).kind),
+                (&TokenKind::Generic | &TokenKind::Port, &TokenKind::Map)
+            ),
+            _ => false,
+        }
+    }
+
+    /// `ラベル : 設計単位 [generic map (...)] [port map (...)];`
+    ///
+    /// 設計単位は `[component] 名前` か `entity [ライブラリ.]名前[(アーキテクチャ)]`。
+    fn parse_instance(&mut self) -> Result<InstanceDef, AnalyzeError> {
+        let start = self.current().span;
+        let label = self.expect(TokenKind::Identifier)?.text.to_string();
+        self.expect(TokenKind::Colon)?;
+        let unit = if self.eat(TokenKind::Entity) {
+            let first = self.expect(TokenKind::Identifier)?.text.to_string();
+            let (library, name) = if self.eat(TokenKind::Dot) {
+                let name = self.expect(TokenKind::Identifier)?.text.to_string();
+                (Some(first), name)
+            } else {
+                (None, first)
+            };
+            let architecture = if self.eat(TokenKind::LeftParen) {
+                let architecture = self.expect(TokenKind::Identifier)?.text.to_string();
+                self.expect(TokenKind::RightParen)?;
+                Some(architecture)
+            } else {
+                None
+            };
+            InstantiatedUnit::Entity {
+                library,
+                name,
+                architecture,
+            }
+        } else {
+            self.eat(TokenKind::Component);
+            InstantiatedUnit::Component(self.expect(TokenKind::Identifier)?.text.to_string())
+        };
+
+        let generic_map = if self.eat(TokenKind::Generic) {
+            self.expect(TokenKind::Map)?;
+            self.parse_association_list()?
+        } else {
+            Vec::new()
+        };
+        let port_map = if self.eat(TokenKind::Port) {
+            self.expect(TokenKind::Map)?;
+            self.parse_association_list()?
+        } else {
+            Vec::new()
+        };
+
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+
+        Ok(InstanceDef {
+            label,
+            unit,
+            generic_map,
+            port_map,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    /// `[ラベル :] [postponed] process` で始まるか
+    fn is_process_start(&self) -> bool {
+        let offset = if self.current().kind == TokenKind::Identifier
+            && self.peek(1).kind == TokenKind::Colon
+        {
+            2
+        } else {
+            0
+        };
+        matches!(
+            self.peek(offset).kind,
+            TokenKind::Process | TokenKind::Postponed
+        )
+    }
+
+    /// `[ラベル :] process [(感度リスト)] [is] 宣言 begin 文 end process [ラベル];`
+    ///
+    /// 宣言部は変数だけを読み、そのほかの宣言は読み飛ばします。
+    fn parse_process(&mut self) -> Result<ProcessDef, AnalyzeError> {
+        let start = self.current().span;
+        let label = if self.current().kind == TokenKind::Identifier {
+            let label = self.expect(TokenKind::Identifier)?.text.to_string();
+            self.expect(TokenKind::Colon)?;
+            Some(label)
+        } else {
+            None
+        };
+        self.eat(TokenKind::Postponed);
+        self.expect(TokenKind::Process)?;
+
+        let mut sensitivity = Vec::new();
+        if self.eat(TokenKind::LeftParen) {
+            loop {
+                sensitivity.push(self.expression_text(&[TokenKind::Comma])?);
+                if !self.eat(TokenKind::Comma) {
+                    break;
+                }
+            }
+            self.expect(TokenKind::RightParen)?;
+        }
+        self.eat(TokenKind::Is);
+
+        let mut constants = Vec::new();
+        let mut variables = Vec::new();
+        let mut types = Vec::new();
+        let mut subprograms = Vec::new();
+        while self.current().kind != TokenKind::Begin && self.current().kind != TokenKind::Eof {
+            match self.current().kind {
+                TokenKind::Constant => constants.append(&mut self.parse_constant_decl()?),
+                TokenKind::Variable => variables.append(&mut self.parse_variable_decl()?),
+                TokenKind::Type | TokenKind::Subtype => types.push(self.parse_type_decl()?),
+                TokenKind::Function
+                | TokenKind::Procedure
+                | TokenKind::Pure
+                | TokenKind::Impure => subprograms.push(self.parse_subprogram()?),
+                _ => {
+                    self.skip_until(&[TokenKind::Semicolon]);
+                    self.advance(); // ;
+                }
+            }
+        }
+        self.expect(TokenKind::Begin)?;
+        let body = self.parse_statements()?;
+
+        self.expect(TokenKind::End)?;
+        self.eat(TokenKind::Postponed);
+        self.expect(TokenKind::Process)?;
+        self.eat(TokenKind::Identifier);
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+
+        Ok(ProcessDef {
+            label,
+            sensitivity,
+            constants,
+            variables,
+            types,
+            subprograms,
+            body,
+            span: Span::new(start.start, end.end),
+        })
+    }
+
+    /// `variable 名前, ... : 型 [制約] [:= 初期値];`
+    fn parse_variable_decl(&mut self) -> Result<Vec<VariableDef>, AnalyzeError> {
+        let (names, vhdl_type, default_value, span) =
+            self.parse_object_decl(TokenKind::Variable)?;
+        Ok(names
+            .into_iter()
+            .map(|name| VariableDef {
+                name,
+                vhdl_type: vhdl_type.clone(),
+                default_value: default_value.clone(),
+                span,
+            })
+            .collect())
     }
 
-    fn expect(&mut self, kind: TokenKind) -> Result<Token, AnalyzeError> {
-        let token = self.current().clone();
-        if token.kind == kind {
-            self.advance();
-            Ok(token)
-        } else {
-            Err(AnalyzeError::new(
-                format!(
-                    "expected {:?}, found {:?} '{}'",
-                    kind, token.kind, token.text
-                ),
-                token.span,
-            ))
+    // --- 順次文 ---
+
+    /// `end`・`elsif`・`else`・`when`（case の次の選択肢）までの順次文
+    fn parse_statements(&mut self) -> Result<Vec<Statement>, AnalyzeError> {
+        if self.depth >= MAX_NESTING {
+            return Err(AnalyzeError::new("nesting too deep", self.current().span));
         }
+        self.depth += 1;
+        let mut statements = Vec::new();
+        let result = loop {
+            match self.current().kind {
+                TokenKind::End
+                | TokenKind::Elsif
+                | TokenKind::Else
+                | TokenKind::When
+                | TokenKind::Eof => break Ok(statements),
+                _ => match self.parse_statement() {
+                    Ok(statement) => statements.push(statement),
+                    Err(err) => break Err(err),
+                },
+            }
+        };
+        self.depth -= 1;
+        result
     }
 
-    fn eat(&mut self, kind: TokenKind) -> bool {
-        if self.current().kind == kind {
+    fn parse_statement(&mut self) -> Result<Statement, AnalyzeError> {
+        let start = self.current().span;
+        let label = if self.current().kind == TokenKind::Identifier
+            && self.peek(1).kind == TokenKind::Colon
+        {
+            let label = self.current().text.to_string();
             self.advance();
-            true
+            self.advance(); // :
+            Some(label)
         } else {
-            false
+            None
+        };
+        match self.current().kind {
+            TokenKind::If => self.parse_if(start),
+            TokenKind::Case => self.parse_case(start),
+            TokenKind::For | TokenKind::While | TokenKind::Loop => self.parse_loop(start, label),
+            TokenKind::Wait => self.parse_wait(start),
+            TokenKind::Null => {
+                self.advance();
+                let end = self.current().span;
+                self.expect(TokenKind::Semicolon)?;
+                Ok(Statement::Null {
+                    span: Span::new(start.start, end.end),
+                })
+            }
+            _ => self.parse_simple_statement(start),
         }
     }
 
-    fn skip_until(&mut self, kinds: &[TokenKind]) {
-        while self.current().kind != TokenKind::Eof {
-            if kinds.contains(&self.current().kind) {
-                return;
+    fn parse_if(&mut self, start: Span) -> Result<Statement, AnalyzeError> {
+        self.expect(TokenKind::If)?;
+        let mut branches = Vec::new();
+        let condition = self.parse_expr()?;
+        self.expect(TokenKind::Then)?;
+        branches.push((condition, self.parse_statements()?));
+        let mut else_body = Vec::new();
+        loop {
+            if self.eat(TokenKind::Elsif) {
+                let condition = self.parse_expr()?;
+                self.expect(TokenKind::Then)?;
+                branches.push((condition, self.parse_statements()?));
+            } else {
+                if self.eat(TokenKind::Else) {
+                    else_body = self.parse_statements()?;
+                }
+                break;
             }
-            self.advance();
         }
+        let end = self.finish_end(TokenKind::If)?;
+        Ok(Statement::If {
+            branches,
+            else_body,
+            span: Span::new(start.start, end.end),
+        })
     }
 
-    // --- Entity 解析 ---
-
-    fn parse_entity(&mut self) -> Result<EntityDef, AnalyzeError> {
-        let start = self.current().span;
-        self.expect(TokenKind::Entity)?;
-        let name = self.expect(TokenKind::Identifier)?.text;
+    fn parse_case(&mut self, start: Span) -> Result<Statement, AnalyzeError> {
+        self.expect(TokenKind::Case)?;
+        let selector = self.parse_expr()?;
         self.expect(TokenKind::Is)?;
-
-        let mut ports = Vec::new();
-        if self.current().kind == TokenKind::Port {
-            self.advance(); // port
-            self.expect(TokenKind::LeftParen)?;
-            ports = self.parse_port_list()?;
-            self.expect(TokenKind::RightParen)?;
-            self.expect(TokenKind::Semicolon)?;
+        let mut arms = Vec::new();
+        while self.eat(TokenKind::When) {
+            let mut choices = Vec::new();
+            loop {
+                choices.push(self.parse_choice()?);
+                if !self.eat(TokenKind::Bar) {
+                    break;
+                }
+            }
+            self.expect(TokenKind::Association)?;
+            arms.push((choices, self.parse_statements()?));
         }
-
-        // end [entity] [name] ;
-        self.skip_until(&[TokenKind::Semicolon]);
-        let end = self.current().span;
-        self.advance(); // ;
-
-        Ok(EntityDef {
-            name,
-            ports,
+        let end = self.finish_end(TokenKind::Case)?;
+        Ok(Statement::Case {
+            selector,
+            arms,
             span: Span::new(start.start, end.end),
         })
     }
 
-    fn parse_port_list(&mut self) -> Result<Vec<PortDef>, AnalyzeError> {
-        let mut ports = Vec::new();
+    fn parse_loop(
+        &mut self,
+        start: Span,
+        label: Option<String>,
+    ) -> Result<Statement, AnalyzeError> {
+        let scheme = if self.eat(TokenKind::While) {
+            LoopScheme::While(self.parse_expr()?)
+        } else if self.eat(TokenKind::For) {
+            let parameter = self.expect(TokenKind::Identifier)?.text.to_string();
+            self.expect(TokenKind::In)?;
+            let range = self.parse_range_or_expr()?;
+            LoopScheme::For { parameter, range }
+        } else {
+            LoopScheme::Forever
+        };
+        self.expect(TokenKind::Loop)?;
+        let body = self.parse_statements()?;
+        let end = self.finish_end(TokenKind::Loop)?;
+        Ok(Statement::Loop {
+            label,
+            scheme,
+            body,
+            span: Span::new(start.start, end.end),
+        })
+    }
 
-        while self.current().kind != TokenKind::RightParen && self.current().kind != TokenKind::Eof
-        {
-            let mut group = self.parse_port_group()?;
-            ports.append(&mut group);
-            self.eat(TokenKind::Semicolon);
+    fn parse_wait(&mut self, start: Span) -> Result<Statement, AnalyzeError> {
+        self.expect(TokenKind::Wait)?;
+        let mut on = Vec::new();
+        if self.eat(TokenKind::On) {
+            loop {
+                on.push(self.parse_expr()?);
+                if !self.eat(TokenKind::Comma) {
+                    break;
+                }
+            }
         }
-
-        Ok(ports)
+        let until = if self.eat(TokenKind::Until) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        let timeout = if self.eat(TokenKind::For) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+        Ok(Statement::Wait {
+            on,
+            until,
+            timeout,
+            span: Span::new(start.start, end.end),
+        })
     }
 
-    fn parse_port_group(&mut self) -> Result<Vec<PortDef>, AnalyzeError> {
-        let span = self.current().span;
-        let mut names = Vec::new();
-
-        names.push(self.expect(TokenKind::Identifier)?.text);
-        while self.eat(TokenKind::Comma) {
-            names.push(self.expect(TokenKind::Identifier)?.text);
+    /// 代入文、またはそのほかの `;` で終わる文
+    fn parse_simple_statement(&mut self, start: Span) -> Result<Statement, AnalyzeError> {
+        let first = self.pos;
+        if let Some(statement) = self.parse_assignment(start)? {
+            return Ok(statement);
         }
-
-        self.expect(TokenKind::Colon)?;
-        let direction = self.parse_direction()?;
-        let vhdl_type = self.parse_type()?;
-
-        Ok(names
-            .into_iter()
-            .map(|n| PortDef {
-                name: n,
-                direction: direction.clone(),
-                vhdl_type: vhdl_type.clone(),
-                span,
-            })
-            .collect())
+        // 代入として読めない文は `;` までの表記で残す
+        self.pos = first;
+        let text = self.interface_tail(&[TokenKind::Semicolon]).join(" ");
+        if text.is_empty() {
+            let token = self.current();
+            return Err(AnalyzeError::new(
+                format!("expected statement, found '{}'", token.text),
+                token.span,
+            ));
+        }
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+        Ok(Statement::Other {
+            text,
+            span: Span::new(start.start, end.end),
+        })
     }
 
-    fn parse_direction(&mut self) -> Result<PortDirection, AnalyzeError> {
-        let token = self.current().clone();
-        match token.kind {
-            TokenKind::In => {
-                self.advance();
-                Ok(PortDirection::In)
-            }
-            TokenKind::Out => {
-                self.advance();
-                Ok(PortDirection::Out)
+    /// `対象 <= 値 [after 遅延];` / `対象 := 式;`（代入として読めなければ None）
+    fn parse_assignment(&mut self, start: Span) -> Result<Option<Statement>, AnalyzeError> {
+        // 対象は名前か集合体（return・assert などの予約語で始まる文は代入ではない）
+        if !matches!(
+            self.current().kind,
+            TokenKind::Identifier | TokenKind::LeftParen
+        ) {
+            return Ok(None);
+        }
+        let Ok(target) = self.parse_primary() else {
+            return Ok(None);
+        };
+        let variable = match self.current().kind {
+            TokenKind::Lte => false,
+            TokenKind::Assignment => true,
+            _ => return Ok(None),
+        };
+        self.advance(); // <= / :=
+        // 遅延の種類・force などの指定は表記のまま残す
+        if matches!(
+            self.current().kind,
+            TokenKind::Transport
+                | TokenKind::Inertial
+                | TokenKind::Reject
+                | TokenKind::Force
+                | TokenKind::Release
+                | TokenKind::Unaffected
+        ) {
+            return Ok(None);
+        }
+        let Ok(value) = self.parse_expr() else {
+            return Ok(None);
+        };
+        let after = if !variable && self.eat(TokenKind::After) {
+            match self.parse_expr() {
+                Ok(delay) => Some(delay),
+                Err(_) => return Ok(None),
             }
-            TokenKind::Inout => {
-                self.advance();
-                Ok(PortDirection::Inout)
+        } else {
+            None
+        };
+        // 波形の続き（`,`）や条件付きの代入（`when`）は代入として読まない
+        if self.current().kind != TokenKind::Semicolon {
+            return Ok(None);
+        }
+        let end = self.current().span;
+        self.advance();
+        let span = Span::new(start.start, end.end);
+        Ok(Some(if variable {
+            Statement::VariableAssign {
+                target,
+                value,
+                span,
             }
-            TokenKind::Buffer => {
-                self.advance();
-                Ok(PortDirection::Buffer)
+        } else {
+            Statement::SignalAssign {
+                target,
+                value,
+                after,
+                span,
             }
-            _ => Err(AnalyzeError::new(
-                format!("expected port direction, found '{}'", token.text),
+        }))
+    }
+
+    /// `end 予約語 [ラベル];` を読み、`;` の位置を返す
+    fn finish_end(&mut self, kind: TokenKind) -> Result<Span, AnalyzeError> {
+        self.expect(TokenKind::End)?;
+        self.expect(kind)?;
+        self.eat(TokenKind::Identifier);
+        let end = self.current().span;
+        self.expect(TokenKind::Semicolon)?;
+        Ok(end)
+    }
+
+    /// `stop` か `;` までの式の表記（括弧の外でだけ止まる）
+    fn expression_text(&mut self, stop: &[TokenKind]) -> Result<String, AnalyzeError> {
+        let mut stop = stop.to_vec();
+        stop.push(TokenKind::Semicolon);
+        let parts = self.interface_tail(&stop);
+        if parts.is_empty() {
+            let token = self.current();
+            return Err(AnalyzeError::new(
+                format!("expected expression, found '{}'", token.text),
                 token.span,
-            )),
+            ));
         }
+        Ok(parts.join(" "))
     }
 
-    // --- 型の解析 ---
-
-    fn parse_type(&mut self) -> Result<VhdlType, AnalyzeError> {
-        let token = self.current().clone();
-        match token.kind {
-            TokenKind::StdLogic => {
-                self.advance();
-                Ok(VhdlType::StdLogic)
-            }
-            TokenKind::StdLogicVector => {
-                self.advance();
-                if self.current().kind == TokenKind::LeftParen {
-                    self.advance(); // (
-                    let high: i64 = self.expect(TokenKind::Number)?.text.parse().unwrap_or(0);
-                    // downto or to
-                    if self.current().kind == TokenKind::Downto
-                        || self.current().kind == TokenKind::To
-                    {
+    /// `(関連付け, ...)`（各関連付けは `formal => actual` か位置による `actual`）
+    fn parse_association_list(&mut self) -> Result<Vec<AssociationDef>, AnalyzeError> {
+        self.expect(TokenKind::LeftParen)?;
+        let mut associations = Vec::new();
+        loop {
+            let start = self.current().span;
+            let mut end = start;
+            let mut formal = None;
+            let mut parts = Vec::new();
+            let mut depth = 0usize;
+            loop {
+                let (kind, text, span) = {
+                    let token = self.current();
+                    (token.kind.clone(), token.text, token.span)
+                };
+                match kind {
+                    TokenKind::Eof => break,
+                    TokenKind::Comma | TokenKind::RightParen if depth == 0 => break,
+                    TokenKind::Association if depth == 0 && formal.is_none() => {
+                        formal = Some(parts.join(" "));
+                        parts.clear();
                         self.advance();
+                        continue;
                     }
-                    let low: i64 = self.expect(TokenKind::Number)?.text.parse().unwrap_or(0);
-                    self.expect(TokenKind::RightParen)?;
-                    Ok(VhdlType::StdLogicVector { high, low })
-                } else {
-                    Ok(VhdlType::StdLogicVector { high: 0, low: 0 })
+                    TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightParen => depth -= 1,
+                    _ => {}
                 }
-            }
-            TokenKind::Integer => {
+                parts.push(text);
+                end = span;
                 self.advance();
-                Ok(VhdlType::Integer)
             }
-            TokenKind::Boolean => {
-                self.advance();
-                Ok(VhdlType::Boolean)
+            if parts.is_empty() {
+                let token = self.current();
+                return Err(AnalyzeError::new(
+                    format!("expected association, found '{}'", token.text),
+                    token.span,
+                ));
             }
-            TokenKind::Identifier => {
-                self.advance();
-                Ok(VhdlType::Other(token.text))
+            associations.push(AssociationDef {
+                formal,
+                actual: parts.join(" "),
+                span: Span::new(start.start, end.end),
+            });
+            if !self.eat(TokenKind::Comma) {
+                break;
             }
-            _ => Err(AnalyzeError::new(
-                format!("expected type, found '{}'", token.text),
-                token.span,
-            )),
         }
+        self.expect(TokenKind::RightParen)?;
+        Ok(associations)
     }
 
-    // --- Architecture 解析 ---
-
-    fn parse_architecture(&mut self) -> Result<ArchitectureDef, AnalyzeError> {
-        let start = self.current().span;
-        self.expect(TokenKind::Architecture)?;
-        let arch_name = self.expect(TokenKind::Identifier)?.text;
-        self.expect(TokenKind::Of)?;
-        let entity_name = self.expect(TokenKind::Identifier)?.text;
-        self.expect(TokenKind::Is)?;
-
-        let mut signals = Vec::new();
-
-        // 宣言部: begin が来るまで signal を抽出
-        while self.current().kind != TokenKind::Begin && self.current().kind != TokenKind::Eof {
-            if self.current().kind == TokenKind::Signal {
-                signals.push(self.parse_signal_decl()?);
-            } else {
-                self.advance();
-            }
-        }
-
-        // begin 以降の本体をスキップ（end architecture を探す）
-        self.skip_until_end_architecture();
-
-        let end_pos = self.pos.saturating_sub(1);
-        let end = self.tokens.get(end_pos).map(|t| t.span).unwrap_or(start);
-
-        Ok(ArchitectureDef {
-            name: arch_name,
-            entity_name,
-            signals,
-            span: Span::new(start.start, end.end),
-        })
+    /// `signal 名前, ... : 型 [制約] [:= 初期値];`
+    fn parse_signal_decl(&mut self) -> Result<Vec<SignalDef>, AnalyzeError> {
+        let (names, vhdl_type, default_value, span) = self.parse_object_decl(TokenKind::Signal)?;
+        Ok(names
+            .into_iter()
+            .map(|name| SignalDef {
+                name,
+                vhdl_type: vhdl_type.clone(),
+                default_value: default_value.clone(),
+                span,
+            })
+            .collect())
     }
 
-    fn skip_until_end_architecture(&mut self) {
-        while self.current().kind != TokenKind::Eof {
-            if self.current().kind == TokenKind::End && self.peek(1).kind == TokenKind::Architecture
-            {
-                self.skip_until(&[TokenKind::Semicolon]);
-                self.advance(); // ;
-                return;
-            }
-            self.advance();
-        }
+    /// `constant 名前, ... : 型 [制約] [:= 値];`
+    fn parse_constant_decl(&mut self) -> Result<Vec<ConstantDef>, AnalyzeError> {
+        let (names, vhdl_type, value, span) = self.parse_object_decl(TokenKind::Constant)?;
+        Ok(names
+            .into_iter()
+            .map(|name| ConstantDef {
+                name,
+                vhdl_type: vhdl_type.clone(),
+                value: value.clone(),
+                span,
+            })
+            .collect())
     }
 
-    fn parse_signal_decl(&mut self) -> Result<SignalDef, AnalyzeError> {
+    /// `予約語 名前, ... : 型 [制約] [:= 値];` を名前・型・値・宣言全体の範囲に分ける
+    fn parse_object_decl(
+        &mut self,
+        keyword: TokenKind,
+    ) -> Result<(Vec<String>, VhdlType, Option<Expr>, Span), AnalyzeError> {
         let start = self.current().span;
-        self.expect(TokenKind::Signal)?;
-        let name = self.expect(TokenKind::Identifier)?.text;
+        self.expect(keyword)?;
+        let mut names = vec![self.expect(TokenKind::Identifier)?.text.to_string()];
+        while self.eat(TokenKind::Comma) {
+            names.push(self.expect(TokenKind::Identifier)?.text.to_string());
+        }
         self.expect(TokenKind::Colon)?;
         let vhdl_type = self.parse_type()?;
-
-        let default_value = if self.current().kind == TokenKind::Assignment {
-            self.advance(); // :=
-            Some(self.parse_default_value())
+        // `integer range 0 to 15` や独自の配列型の制約は読み飛ばす
+        self.interface_tail(&[TokenKind::Assignment, TokenKind::Semicolon]);
+        let value = if self.eat(TokenKind::Assignment) {
+            Some(self.parse_expr()?)
         } else {
             None
         };
@@ -413,31 +3429,118 @@ impl Analyzer {
         let end = self.current().span;
         self.expect(TokenKind::Semicolon)?;
 
-        Ok(SignalDef {
-            name,
-            vhdl_type,
-            default_value,
-            span: Span::new(start.start, end.end),
-        })
+        Ok((names, vhdl_type, value, Span::new(start.start, end.end)))
     }
+}
 
-    fn parse_default_value(&mut self) -> String {
-        let mut parts = Vec::new();
-        while self.current().kind != TokenKind::Semicolon && self.current().kind != TokenKind::Eof {
-            parts.push(self.current().text.clone());
-            self.advance();
+/// Eofセンチネル（borrowの都合でstaticに保持）
+static EOF_SENTINEL: Token<'static> = Token {
+    kind: TokenKind::Eof,
+    span: Span { start: 0, end: 0 },
+    text: "",
+    leading: "",
+    trailing: "",
+};
+
+/// ファイルを読み込んで解析する便利関数（文字コードは推定する、[`crate::encoding`]）
+pub fn analyze_file(path: impl AsRef<std::path::Path>) -> crate::Result<AnalyzeResult> {
+    let path = path.as_ref();
+    let source = crate::encoding::read_source(path, None)?;
+    analyze_vhdl(&source).map_err(|err| err.with_file(path.display().to_string()).into())
+}
+
+/// コメントを除いたトークン列（字句エラーは解析エラーにする）
+fn tokens_of(text: &str) -> Result<Vec<Token<'_>>, AnalyzeError> {
+    let mut tokens = Vec::new();
+    for token in Lexer::new(text) {
+        let token = token.map_err(|err| AnalyzeError::new(err.message, err.span))?;
+        if token.kind != TokenKind::Comment {
+            tokens.push(token);
         }
-        parts.join(" ")
     }
+    Ok(tokens)
 }
 
-/// Eofセンチネル（borrowの都合でstaticに保持）
-static EOF_SENTINEL: std::sync::LazyLock<Token> = std::sync::LazyLock::new(eof_token);
+/// 型の表記を解析する（設定ファイルやコマンドライン引数で型を文字列で指定するためのもの）
+///
+/// ポート宣言と同じ規則で解析します。`unsigned(7 downto 0)` のような未対応の型は
+/// 制約ごと [`VhdlType::Other`] になります。
+///
+/// ```
+/// use vig::analyzer::{VhdlType, parse_type_str};
+///
+/// let ty = parse_type_str("std_logic_vector(7 downto 0)").unwrap();
+/// assert_eq!(ty, VhdlType::StdLogicVector { high: 7.into(), low: 0.into(), ascending: false });
+/// assert_eq!(ty.to_string(), "std_logic_vector(7 downto 0)");
+/// ```
+pub fn parse_type_str(text: &str) -> Result<VhdlType, AnalyzeError> {
+    let tokens = tokens_of(text)?;
+    let Some(first) = tokens.first().cloned() else {
+        return Err(AnalyzeError::new("empty type", Span::new(0, text.len())));
+    };
+    let mut analyzer = Analyzer::new(tokens);
+    let vhdl_type = analyzer.parse_type()?;
+    let rest = analyzer.current().clone();
+    if rest.kind == TokenKind::Eof {
+        return Ok(vhdl_type);
+    }
+    // 型名に続く制約は型名と合わせて Other として扱う
+    if matches!(vhdl_type, VhdlType::Other(_)) && rest.kind == TokenKind::LeftParen {
+        let mut depth = 0usize;
+        while analyzer.current().kind != TokenKind::Eof {
+            match analyzer.current().kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            let closed = depth == 0;
+            analyzer.advance();
+            if closed {
+                break;
+            }
+        }
+        if depth == 0 && analyzer.current().kind == TokenKind::Eof {
+            return Ok(VhdlType::Other(
+                text[first.span.start..].trim_end().to_string(),
+            ));
+        }
+    }
+    let rest = analyzer.current().clone();
+    Err(AnalyzeError::new(
+        format!("unexpected '{}' after type", rest.text),
+        rest.span,
+    ))
+}
+
+/// 式の表記を解析する（範囲 `7 downto 0` と `others` も読む）
+///
+/// ```
+/// use vig::analyzer::parse_expr_str;
+///
+/// let expr = parse_expr_str("(others => '0')").unwrap();
+/// assert_eq!(expr.to_string(), "(others => '0')");
+/// assert!(parse_expr_str("a +").is_err());
+/// ```
+pub fn parse_expr_str(text: &str) -> Result<Expr, AnalyzeError> {
+    let tokens = tokens_of(text)?;
+    let mut analyzer = Analyzer::new(tokens);
+    let expr = analyzer.parse_choice()?;
+    let rest = analyzer.current().clone();
+    if rest.kind != TokenKind::Eof {
+        return Err(AnalyzeError::new(
+            format!("unexpected '{}' after expression", rest.text),
+            rest.span,
+        ));
+    }
+    Ok(expr)
+}
 
 /// ソースコードから直接解析する便利関数
 pub fn analyze_vhdl(source: &str) -> Result<AnalyzeResult, AnalyzeError> {
-    let lexer = Lexer::new(source);
-    let tokens: Vec<Token> = lexer.filter_map(|r| r.ok()).collect();
+    let tokens: Vec<Token> = {
+        let _timed = timed!("lex", bytes = source.len());
+        Lexer::new(source).filter_map(|r| r.ok()).collect()
+    };
     let mut analyzer = Analyzer::new(tokens);
-    analyzer.analyze()
+    analyzer.analyze().map_err(|err| err.locate(source))
 }
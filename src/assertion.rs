@@ -0,0 +1,747 @@
+//! アサーション記述からの検査プロセス生成
+//!
+//! `after reset, busy=0` や `done within 100 cycles of start` のような短い性質記述を解析し、
+//! 生成するテストベンチに検査プロセスとして組み込みます。記述は `vig.toml` の
+//! `[assertions]` テーブルか、設計ファイルの `-- vig: assert ...` コメントに書きます。
+//! エンティティごとの性質ファイル `<entity>.props.toml` からも作れます（[`from_props`]）。
+//!
+//! | 記述 | 意味 |
+//! |------|------|
+//! | `after reset, <条件>` | リセット解除の時点で条件が成り立つ |
+//! | `always <条件>` | リセット解除後の各クロックで条件が成り立つ |
+//! | `never <条件>` | リセット解除後の各クロックで条件が成り立たない |
+//! | `<条件> within N cycles of <条件>` | 後者が成り立ってから N クロック以内に前者が成り立つ |
+//!
+//! 条件はポート名と比較（`=` `/=` `<` `<=` `>` `>=`）を `and` `or` `not` と括弧で組み合わせます。
+//! 比較を省いた `std_logic` のポート名は `'1'` との比較になります。
+
+use crate::analyzer::{EntityDef, VhdlType};
+use crate::generator::{
+    AssertSeverity, ResetPolarity, TbProcess, TestbenchModel, find_clock_port, find_reset_port,
+};
+use crate::json::Value;
+use crate::lexer::{Lexer, Span, Token, TokenKind};
+use crate::literal;
+use crate::toml::{self, TomlError};
+
+/// アサーション記述のエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertionError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl AssertionError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for AssertionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for AssertionError {}
+
+impl From<TomlError> for AssertionError {
+    fn from(e: TomlError) -> Self {
+        Self::new(e.message, e.span)
+    }
+}
+
+/// 条件式
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// ポートと値の比較（`value` がなければポート単独）
+    Compare {
+        signal: String,
+        span: Span,
+        op: Option<CompareOp>,
+        /// 右辺の字句（`0` `'1'` `x"FF"` `true` や別のポート名）
+        value: Option<String>,
+    },
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    /// 含まれるすべての比較の位置を置き換える
+    fn set_span(&mut self, new: Span) {
+        match self {
+            Condition::Compare { span, .. } => *span = new,
+            Condition::Not(c) => c.set_span(new),
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                a.set_span(new);
+                b.set_span(new);
+            }
+        }
+    }
+}
+
+/// 比較演算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+impl CompareOp {
+    /// VHDLの演算子
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "=",
+            CompareOp::Neq => "/=",
+            CompareOp::Lt => "<",
+            CompareOp::Lte => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Gte => ">=",
+        }
+    }
+}
+
+/// 性質の種類
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyKind {
+    /// `after reset, <条件>`
+    AfterReset(Condition),
+    /// `always <条件>`
+    Always(Condition),
+    /// `never <条件>`
+    Never(Condition),
+    /// `<effect> within <cycles> cycles of <cause>`
+    Within {
+        effect: Condition,
+        cycles: u64,
+        cause: Condition,
+    },
+}
+
+/// 1つのアサーション
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    /// 元の記述（失敗時のメッセージに使う）
+    pub text: String,
+    pub kind: PropertyKind,
+    /// 記述の位置（[`Property::parse`] では記述の先頭が0）
+    pub span: Span,
+}
+
+impl Property {
+    /// 記述を解析する
+    pub fn parse(text: &str) -> Result<Self, AssertionError> {
+        Self::parse_at(text, 0)
+    }
+
+    /// ソース中の `offset` にある記述を解析する（エラー位置をソース基準にする）
+    fn parse_at(text: &str, offset: usize) -> Result<Self, AssertionError> {
+        let offset = offset + text.len() - text.trim_start().len();
+        let text = text.trim();
+        let mut lexer = Lexer::new(text);
+        let mut tokens = Vec::new();
+        loop {
+            let mut token = lexer
+                .next_token()
+                .map_err(|e| AssertionError::new(e.message, shift(e.span, offset)))?;
+            token.span = shift(token.span, offset);
+            if token.kind == TokenKind::Eof {
+                tokens.push(token);
+                break;
+            }
+            tokens.push(token);
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let kind = parser.property()?;
+        parser.expect_end()?;
+        Ok(Self {
+            text: text.to_string(),
+            kind,
+            span: Span::new(offset, offset + text.len()),
+        })
+    }
+
+    /// エンティティに対して検査プロセスを作れるか確認する
+    pub fn check(&self, entity: &EntityDef) -> Result<(), AssertionError> {
+        self.checker(entity, 0, ResetPolarity::default(), AssertSeverity::Error)
+            .map(|_| ())
+    }
+
+    /// 検査プロセスを作る（`index` はラベルの番号）
+    fn checker(
+        &self,
+        entity: &EntityDef,
+        index: usize,
+        polarity: ResetPolarity,
+        severity: AssertSeverity,
+    ) -> Result<TbProcess, AssertionError> {
+        let clk = find_clock_port(&entity.ports);
+        let rst = find_reset_port(&entity.ports);
+        let need_clock = || {
+            clk.clone()
+                .ok_or_else(|| AssertionError::new("assertion needs a clock port", self.span))
+        };
+        let message = self.text.replace('"', "\"\"");
+        let assert = |cond: &str| {
+            format!(
+                "assert {} report \"assertion failed: {}\" severity {};",
+                cond,
+                message,
+                severity.as_str()
+            )
+        };
+
+        let mut p = TbProcess::new(format!("assert_{}", index));
+        p.comment = Some(format!("アサーション: {}", self.text));
+        match &self.kind {
+            PropertyKind::AfterReset(cond) => {
+                let cond = compile(cond, entity)?;
+                let rst = rst.ok_or_else(|| {
+                    AssertionError::new("'after reset' needs a reset port", self.span)
+                })?;
+                p.body = vec![
+                    format!("wait until {} = {};", rst, polarity.asserted()),
+                    format!("wait until {} = {};", rst, polarity.released()),
+                    assert(&cond),
+                    "wait;".to_string(),
+                ];
+            }
+            PropertyKind::Always(cond) | PropertyKind::Never(cond) => {
+                let mut cond = compile(cond, entity)?;
+                if matches!(self.kind, PropertyKind::Never(_)) {
+                    cond = format!("not ({})", cond);
+                }
+                let clk = need_clock()?;
+                p.sensitivity = vec![clk.clone()];
+                let edge = match &rst {
+                    Some(rst) => format!(
+                        "if rising_edge({}) and {} = {} then",
+                        clk,
+                        rst,
+                        polarity.released()
+                    ),
+                    None => format!("if rising_edge({}) then", clk),
+                };
+                p.body = vec![
+                    edge,
+                    format!("    {}", assert(&cond)),
+                    "end if;".to_string(),
+                ];
+            }
+            PropertyKind::Within {
+                effect,
+                cycles,
+                cause,
+            } => {
+                let effect = compile(effect, entity)?;
+                let cause = compile(cause, entity)?;
+                let clk = need_clock()?;
+                let trigger = match &rst {
+                    Some(rst) => format!(
+                        "wait until rising_edge({}) and {} = {} and ({});",
+                        clk,
+                        rst,
+                        polarity.released(),
+                        cause
+                    ),
+                    None => format!("wait until rising_edge({}) and ({});", clk, cause),
+                };
+                p.body = vec![
+                    trigger,
+                    format!("for i in 1 to {} loop", cycles),
+                    format!("    wait until rising_edge({});", clk),
+                    format!("    exit when {};", effect),
+                    format!("    {}", assert(&format!("i < {}", cycles))),
+                    "end loop;".to_string(),
+                ];
+            }
+        }
+        Ok(p)
+    }
+}
+
+fn shift(span: Span, offset: usize) -> Span {
+    Span::new(span.start + offset, span.end + offset)
+}
+
+/// 条件をVHDLの式にする
+fn compile(cond: &Condition, entity: &EntityDef) -> Result<String, AssertionError> {
+    // and/or の入れ子は括弧で囲む
+    let operand = |c: &Condition| -> Result<String, AssertionError> {
+        let s = compile(c, entity)?;
+        Ok(match c {
+            Condition::And(..) | Condition::Or(..) => format!("({})", s),
+            _ => s,
+        })
+    };
+    match cond {
+        Condition::Not(c) => Ok(format!("not ({})", compile(c, entity)?)),
+        Condition::And(a, b) => Ok(format!("{} and {}", operand(a)?, operand(b)?)),
+        Condition::Or(a, b) => Ok(format!("{} or {}", operand(a)?, operand(b)?)),
+        Condition::Compare {
+            signal,
+            span,
+            op,
+            value,
+        } => {
+            let port = entity
+                .ports
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(signal))
+                .ok_or_else(|| {
+                    AssertionError::new(format!("unknown signal '{}'", signal), *span)
+                })?;
+            let name = &port.name;
+            let (Some(op), Some(value)) = (op, value) else {
+                return match port.vhdl_type {
+                    VhdlType::StdLogic => Ok(format!("{} = '1'", name)),
+                    VhdlType::Boolean => Ok(name.clone()),
+                    _ => Err(AssertionError::new(
+                        format!("'{}' needs a comparison", name),
+                        *span,
+                    )),
+                };
+            };
+            let op = op.as_str();
+            // 右辺が別のポートならそのまま比較する
+            if let Some(other) = entity
+                .ports
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(value))
+            {
+                return Ok(format!("{} {} {}", name, op, other.name));
+            }
+            let number = literal::parse_integer(value);
+            match (&port.vhdl_type, number) {
+                (VhdlType::StdLogic, Some(n @ 0..=1)) => Ok(format!("{} {} '{}'", name, op, n)),
+                (VhdlType::StdLogicVector { .. }, Some(n)) => {
+                    Ok(format!("unsigned({}) {} {}", name, op, n))
+                }
+                (
+                    VhdlType::Integer(_)
+                    | VhdlType::Natural(_)
+                    | VhdlType::Positive(_)
+                    | VhdlType::Signed { .. }
+                    | VhdlType::Unsigned { .. },
+                    Some(n),
+                ) => Ok(format!("{} {} {}", name, op, n)),
+                (_, None) if value.starts_with(['\'', '"']) || value.ends_with('"') => {
+                    Ok(format!("{} {} {}", name, op, value))
+                }
+                (VhdlType::Boolean, None)
+                    if value.eq_ignore_ascii_case("true")
+                        || value.eq_ignore_ascii_case("false") =>
+                {
+                    Ok(format!("{} {} {}", name, op, value.to_lowercase()))
+                }
+                _ => Err(AssertionError::new(
+                    format!("cannot compare '{}' with '{}'", name, value),
+                    *span,
+                )),
+            }
+        }
+    }
+}
+
+/// 記述のトークン列を読む
+struct Parser<'source> {
+    tokens: Vec<Token<'source>>,
+    pos: usize,
+}
+
+impl<'source> Parser<'source> {
+    fn peek(&self) -> &Token<'source> {
+        &self.tokens[self.pos.min(self.tokens.len() - 1)]
+    }
+
+    fn next(&mut self) -> &Token<'source> {
+        let i = self.pos.min(self.tokens.len() - 1);
+        self.pos += 1;
+        &self.tokens[i]
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        let t = self.peek();
+        t.text.eq_ignore_ascii_case(word)
+    }
+
+    fn error(&self, expected: &str) -> AssertionError {
+        let t = self.peek();
+        let found = if t.kind == TokenKind::Eof {
+            "end of assertion".to_string()
+        } else {
+            format!("'{}'", t.text)
+        };
+        AssertionError::new(format!("expected {}, found {}", expected, found), t.span)
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), AssertionError> {
+        if !self.is_word(word) {
+            return Err(self.error(&format!("'{}'", word)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn expect_end(&self) -> Result<(), AssertionError> {
+        if self.peek().kind != TokenKind::Eof {
+            return Err(self.error("end of assertion"));
+        }
+        Ok(())
+    }
+
+    fn property(&mut self) -> Result<PropertyKind, AssertionError> {
+        if self.is_word("after") {
+            self.pos += 1;
+            self.expect_word("reset")?;
+            if self.peek().kind == TokenKind::Comma {
+                self.pos += 1;
+            }
+            return Ok(PropertyKind::AfterReset(self.condition()?));
+        }
+        if self.is_word("always") {
+            self.pos += 1;
+            return Ok(PropertyKind::Always(self.condition()?));
+        }
+        if self.is_word("never") {
+            self.pos += 1;
+            return Ok(PropertyKind::Never(self.condition()?));
+        }
+        let effect = self.condition()?;
+        self.expect_word("within")?;
+        let t = self.next().clone();
+        let cycles = match t.kind {
+            TokenKind::IntegerLiteral => literal::parse_integer(t.text)
+                .and_then(|n| u64::try_from(n).ok())
+                .filter(|&n| n > 0),
+            _ => None,
+        }
+        .ok_or_else(|| AssertionError::new("expected a positive number of cycles", t.span))?;
+        if self.is_word("cycle") || self.is_word("cycles") {
+            self.pos += 1;
+        } else {
+            return Err(self.error("'cycles'"));
+        }
+        if self.peek().kind != TokenKind::Of {
+            return Err(self.error("'of'"));
+        }
+        self.pos += 1;
+        let cause = self.condition()?;
+        Ok(PropertyKind::Within {
+            effect,
+            cycles,
+            cause,
+        })
+    }
+
+    fn condition(&mut self) -> Result<Condition, AssertionError> {
+        let mut left = self.and_condition()?;
+        while self.peek().kind == TokenKind::Or {
+            self.pos += 1;
+            let right = self.and_condition()?;
+            left = Condition::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and_condition(&mut self) -> Result<Condition, AssertionError> {
+        let mut left = self.unary()?;
+        while self.peek().kind == TokenKind::And {
+            self.pos += 1;
+            let right = self.unary()?;
+            left = Condition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Condition, AssertionError> {
+        match self.peek().kind {
+            TokenKind::Not => {
+                self.pos += 1;
+                Ok(Condition::Not(Box::new(self.unary()?)))
+            }
+            TokenKind::LeftParen => {
+                self.pos += 1;
+                let cond = self.condition()?;
+                if self.peek().kind != TokenKind::RightParen {
+                    return Err(self.error("')'"));
+                }
+                self.pos += 1;
+                Ok(cond)
+            }
+            TokenKind::Identifier => self.compare(),
+            _ => Err(self.error("a signal name")),
+        }
+    }
+
+    fn compare(&mut self) -> Result<Condition, AssertionError> {
+        let t = self.next().clone();
+        let op = match self.peek().kind {
+            TokenKind::Eq => Some(CompareOp::Eq),
+            TokenKind::Neq => Some(CompareOp::Neq),
+            TokenKind::Lt => Some(CompareOp::Lt),
+            TokenKind::Lte => Some(CompareOp::Lte),
+            TokenKind::Gt => Some(CompareOp::Gt),
+            TokenKind::Gte => Some(CompareOp::Gte),
+            _ => None,
+        };
+        let mut value = None;
+        if op.is_some() {
+            self.pos += 1;
+            let v = self.next().clone();
+            match v.kind {
+                TokenKind::IntegerLiteral
+                | TokenKind::RealLiteral
+                | TokenKind::CharacterLiteral
+                | TokenKind::BitStringLiteral
+                | TokenKind::StringLiteral
+                | TokenKind::Identifier => value = Some(v.text.to_string()),
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("a value"));
+                }
+            }
+        }
+        Ok(Condition::Compare {
+            signal: t.text.to_string(),
+            span: t.span,
+            op,
+            value,
+        })
+    }
+}
+
+/// 設計ファイルの `-- vig: assert ...` コメントからエンティティのアサーションを集める
+///
+/// エンティティ宣言とそのアーキテクチャの中（次の設計単位まで）のコメントが対象です。
+pub fn from_comments(source: &str, entity: &str) -> Result<Vec<Property>, AssertionError> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    while let Ok(token) = lexer.next_token() {
+        if token.kind == TokenKind::Eof {
+            break;
+        }
+        tokens.push(token);
+    }
+
+    let mut properties = Vec::new();
+    let mut current: Option<&str> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let name = |k: usize| {
+            tokens
+                .get(k)
+                .filter(|t| t.kind == TokenKind::Identifier)
+                .map(|t| t.text)
+        };
+        match token.kind {
+            TokenKind::Entity if i == 0 || tokens[i - 1].kind != TokenKind::End => {
+                current = name(i + 1);
+            }
+            TokenKind::Architecture
+                if tokens.get(i + 2).is_some_and(|t| t.kind == TokenKind::Of) =>
+            {
+                current = name(i + 3);
+            }
+            TokenKind::Comment if current.is_some_and(|c| c.eq_ignore_ascii_case(entity)) => {
+                let body = token.text.trim_start_matches('-').trim_start();
+                let Some(rest) = body.strip_prefix("vig:") else {
+                    continue;
+                };
+                let rest = rest.trim_start();
+                let Some(text) = rest
+                    .strip_prefix("assert")
+                    .filter(|t| t.starts_with(char::is_whitespace))
+                else {
+                    continue;
+                };
+                let text = text.trim_start();
+                let offset = token.span.end - text.len();
+                properties.push(Property::parse_at(text, offset)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(properties)
+}
+
+/// `vig.toml` の `[assertions]` テーブルからエンティティのアサーションを読み込む
+///
+/// ```toml
+/// [assertions]
+/// uart_tx = ["after reset, busy=0", "done within 100 cycles of start"]
+/// ```
+pub fn from_config(source: &str, entity: &str) -> Result<Vec<Property>, AssertionError> {
+    let root = toml::parse(source)?;
+    let Some(Value::Object(table)) = root.get("assertions") else {
+        return Ok(Vec::new());
+    };
+    let Some((key, value)) = table.iter().find(|(k, _)| k.eq_ignore_ascii_case(entity)) else {
+        return Ok(Vec::new());
+    };
+    let err = || {
+        AssertionError::new(
+            format!("assertions of '{}' must be an array of strings", key),
+            Span::new(0, 0),
+        )
+    };
+    let items = value.as_array().ok_or_else(err)?;
+    items
+        .iter()
+        .map(|item| {
+            let text = item.as_str().ok_or_else(err)?;
+            // 記述の位置はファイル中の文字列から探す
+            let offset = source
+                .find(&format!("\"{}\"", text))
+                .map_or(0, |pos| pos + 1);
+            Property::parse_at(text, offset)
+        })
+        .collect()
+}
+
+/// エンティティの性質ファイル（`<entity>.props.toml`）を読み込む
+///
+/// リセット値・不変条件・レイテンシの上限をアサーションに変換します。
+///
+/// ```toml
+/// invariants = ["not (tx_busy and tx_done)"]
+///
+/// [reset]
+/// tx_busy = 0
+/// tx_out = 1
+///
+/// [[latency]]
+/// from = "tx_start"
+/// to = "tx_done"
+/// max_cycles = 5000
+/// ```
+pub fn from_props(source: &str) -> Result<Vec<Property>, AssertionError> {
+    let root = toml::parse(source)?;
+    // 値の位置はファイル中の文字列から探す（見つからなければ先頭）
+    let locate_from = |from: usize, needle: &str| {
+        source[from..].find(needle).map_or(Span::new(0, 0), |pos| {
+            Span::new(from + pos, from + pos + needle.len())
+        })
+    };
+    let locate = |needle: &str| locate_from(0, needle);
+    // 組み立てた記述を解析し、位置はすべてファイル中の値に置き換える
+    let parse = |text: String, span: Span| -> Result<Property, AssertionError> {
+        let mut property =
+            Property::parse(&text).map_err(|e| AssertionError::new(e.message, span))?;
+        property.span = span;
+        match &mut property.kind {
+            PropertyKind::AfterReset(c) | PropertyKind::Always(c) | PropertyKind::Never(c) => {
+                c.set_span(span)
+            }
+            PropertyKind::Within { effect, cause, .. } => {
+                effect.set_span(span);
+                cause.set_span(span);
+            }
+        }
+        Ok(property)
+    };
+    let mut properties = Vec::new();
+
+    if let Some(reset) = root.get("reset") {
+        let Value::Object(values) = reset else {
+            return Err(AssertionError::new(
+                "'reset' must be a table",
+                locate("reset"),
+            ));
+        };
+        let table = locate("[reset]").end;
+        for (name, value) in values {
+            let mut span = locate_from(table, &format!("\n{}", name));
+            if span.end > 0 {
+                span.start += 1;
+            }
+            let value = match value {
+                Value::Integer(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                // 'x' や x"FF" はそのまま、それ以外は文字・ビット列の表記にする
+                Value::String(s) if s.starts_with('\'') || s.contains('"') => s.clone(),
+                Value::String(s) if s.chars().count() == 1 => format!("'{}'", s),
+                Value::String(s) => format!("\"{}\"", s),
+                _ => {
+                    return Err(AssertionError::new(
+                        format!(
+                            "reset value of '{}' must be an integer, string or boolean",
+                            name
+                        ),
+                        span,
+                    ));
+                }
+            };
+            properties.push(parse(format!("after reset, {} = {}", name, value), span)?);
+        }
+    }
+
+    if let Some(invariants) = root.get("invariants") {
+        let err = || {
+            AssertionError::new(
+                "'invariants' must be an array of strings",
+                locate("invariants"),
+            )
+        };
+        for item in invariants.as_array().ok_or_else(err)? {
+            let text = item.as_str().ok_or_else(err)?;
+            properties.push(parse(format!("always {}", text), locate(text))?);
+        }
+    }
+
+    if let Some(latency) = root.get("latency") {
+        let err = || {
+            AssertionError::new(
+                "'latency' must be an array of tables with 'from', 'to' and 'max_cycles'",
+                locate("latency"),
+            )
+        };
+        for item in latency.as_array().ok_or_else(err)? {
+            let from = item.get("from").and_then(Value::as_str).ok_or_else(err)?;
+            let to = item.get("to").and_then(Value::as_str).ok_or_else(err)?;
+            let cycles = item
+                .get("max_cycles")
+                .and_then(Value::as_i64)
+                .ok_or_else(err)?;
+            let text = format!("{} within {} cycles of {}", to, cycles, from);
+            properties.push(parse(text, locate(from))?);
+        }
+    }
+    Ok(properties)
+}
+
+/// 検査プロセスをテストベンチに追加する
+///
+/// エンティティに合わない記述（存在しないポート、クロックがない等）は
+/// 理由をコメントに残して飛ばします。事前に [`Property::check`] で確認できます。
+pub(crate) fn add_checkers(
+    model: &mut TestbenchModel,
+    entity: &EntityDef,
+    properties: &[Property],
+    polarity: ResetPolarity,
+    severity: AssertSeverity,
+) {
+    for (i, property) in properties.iter().enumerate() {
+        match property.checker(entity, i + 1, polarity, severity) {
+            Ok(process) => model.processes.push(process),
+            Err(e) => model.declarations.push(format!(
+                "-- アサーション '{}' は生成できません: {}",
+                property.text, e.message
+            )),
+        }
+    }
+}
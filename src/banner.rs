@@ -0,0 +1,255 @@
+//! 生成ファイルの先頭に付ける著作権・ライセンス表示
+//!
+//! vig.toml の `[banner]` に書いたテンプレートを、生成する VHDL やスクリプトの先頭に
+//! コメントとして付けます。テンプレートは `text`（文字列か行の配列）か `file`
+//! （vig.toml からの相対パス）で指定し、`{year}` `{entity}` `{author}` を置き換えます。
+//!
+//! ```toml
+//! [banner]
+//! text = ["Copyright (c) {year} {author}", "SPDX-License-Identifier: Apache-2.0", "", "{entity}"]
+//! author = "Example Corp."
+//! ```
+//!
+//! ```
+//! use std::path::Path;
+//! use vig::banner::from_config;
+//!
+//! let config = "[banner]\ntext = [\"(c) {year} {author}\", \"{entity}\"]\nauthor = \"ACME\"\n";
+//! let banner = from_config(config, Path::new(".")).unwrap().unwrap();
+//! assert_eq!(banner.render("counter", 2026, "--"), "-- (c) 2026 ACME\n-- counter\n\n");
+//! ```
+
+use std::path::Path;
+
+use crate::json::Value;
+use crate::lexer::Span;
+use crate::toml::{self, TomlError};
+
+/// 表示の読み込みエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct BannerError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl BannerError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for BannerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for BannerError {}
+
+impl From<TomlError> for BannerError {
+    fn from(err: TomlError) -> Self {
+        BannerError::new(err.message, err.span)
+    }
+}
+
+/// 置き換えられる項目
+const PLACEHOLDERS: [&str; 3] = ["year", "entity", "author"];
+
+/// 著作権・ライセンス表示のテンプレート
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Banner {
+    /// テンプレート（コメント記号なし、行区切りは `\n`）
+    pub template: String,
+    /// `{author}` に入れる名前
+    pub author: String,
+}
+
+impl Banner {
+    pub fn new(template: impl Into<String>, author: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            author: author.into(),
+        }
+    }
+
+    /// コメントにした表示（`comment` は `--` や `#`、最後に空行を付ける）
+    ///
+    /// すでにコメント記号で始まる行はそのままにします。
+    pub fn render(&self, entity: &str, year: i32, comment: &str) -> String {
+        let text = self
+            .template
+            .replace("{year}", &year.to_string())
+            .replace("{entity}", entity)
+            .replace("{author}", &self.author);
+        let mut out = String::new();
+        for line in text.trim_end().lines() {
+            let line = line.trim_end();
+            if line.starts_with(comment) {
+                out.push_str(line);
+            } else if line.is_empty() {
+                out.push_str(comment);
+            } else {
+                out.push_str(comment);
+                out.push(' ');
+                out.push_str(line);
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+        out
+    }
+
+    /// 生成したテキストの先頭に表示を付ける（今年の年で置き換える）
+    pub fn prepend(&self, text: &str, entity: &str, comment: &str) -> String {
+        let mut out = self.render(entity, current_year(), comment);
+        out.push_str(text);
+        out
+    }
+}
+
+/// 現在の西暦年（UTC）
+pub fn current_year() -> i32 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    year_of_day((secs / 86_400) as i64)
+}
+
+/// 1970-01-01 からの日数の西暦年
+fn year_of_day(days: i64) -> i32 {
+    // 400年（146097日）周期のグレゴリオ暦で、3月始まりの年として数える
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let month_index = (5 * doy + 2) / 153;
+    let year = yoe + era * 400 + i64::from(month_index >= 10);
+    year as i32
+}
+
+/// vig.toml の `[banner]` を読み込む（なければ None）
+///
+/// `file` は `base`（vig.toml のあるディレクトリ）からの相対パスです。
+pub fn from_config(source: &str, base: &Path) -> Result<Option<Banner>, BannerError> {
+    let root = toml::parse(source)?;
+    let Some(value) = root.get("banner") else {
+        return Ok(None);
+    };
+    let locate = |text: &str| {
+        source
+            .find(text)
+            .map_or(Span::new(0, 0), |pos| Span::new(pos, pos + text.len()))
+    };
+    let Value::Object(fields) = value else {
+        return Err(BannerError::new(
+            "'banner' must be a table",
+            locate("banner"),
+        ));
+    };
+
+    let mut template = None;
+    let mut author = String::new();
+    for (field, value) in fields {
+        match field.as_str() {
+            "text" => {
+                let err = || {
+                    BannerError::new(
+                        "'text' must be a string or an array of strings",
+                        locate(field),
+                    )
+                };
+                template = Some(match value {
+                    Value::String(s) => s.clone(),
+                    Value::Array(lines) => lines
+                        .iter()
+                        .map(|l| l.as_str().ok_or_else(err))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join("\n"),
+                    _ => return Err(err()),
+                });
+            }
+            "file" => {
+                let path = value
+                    .as_str()
+                    .ok_or_else(|| BannerError::new("'file' must be a string", locate(field)))?;
+                let text = std::fs::read_to_string(base.join(path)).map_err(|e| {
+                    BannerError::new(
+                        format!("cannot read banner file '{}': {}", path, e),
+                        locate(path),
+                    )
+                })?;
+                template = Some(text);
+            }
+            "author" => {
+                author = value
+                    .as_str()
+                    .ok_or_else(|| BannerError::new("'author' must be a string", locate(field)))?
+                    .to_string();
+            }
+            _ => {
+                return Err(BannerError::new(
+                    format!("unknown field '{}' in banner", field),
+                    locate(field),
+                ));
+            }
+        }
+    }
+    let Some(template) = template else {
+        return Err(BannerError::new(
+            "banner needs 'text' or 'file'",
+            locate("banner"),
+        ));
+    };
+    if let Some(unknown) = unknown_placeholder(&template) {
+        return Err(BannerError::new(
+            format!(
+                "unknown placeholder '{{{}}}' in banner (expected {{year}}, {{entity}} or {{author}})",
+                unknown
+            ),
+            locate(&format!("{{{}}}", unknown)),
+        ));
+    }
+    Ok(Some(Banner::new(template, author)))
+}
+
+/// テンプレート中の未知の `{名前}`
+fn unknown_placeholder(template: &str) -> Option<&str> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let end = after.find('}')?;
+        let name = &after[..end];
+        let is_name =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if is_name && !PLACEHOLDERS.contains(&name) {
+            return Some(name);
+        }
+        rest = &after[end + 1..];
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::year_of_day;
+
+    #[test]
+    fn test_year_of_day() {
+        assert_eq!(year_of_day(0), 1970);
+        assert_eq!(year_of_day(364), 1970);
+        assert_eq!(year_of_day(365), 1971);
+        // 2000-02-29 と 2024-12-31
+        assert_eq!(year_of_day(11_016), 2000);
+        assert_eq!(year_of_day(20_088), 2024);
+        assert_eq!(year_of_day(20_089), 2025);
+    }
+}
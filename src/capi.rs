@@ -0,0 +1,115 @@
+//! C FFIレイヤー（`capi` feature）
+//!
+//! C/C++で書かれたEDAツールからVigを組み込むための関数群です。
+//! 返される文字列はすべてVig側で確保されるため、使用後は必ず
+//! `vig_string_free` で解放してください。対応するヘッダは `include/vig.h` です。
+
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+use crate::analyzer;
+use crate::generator::{self, TbConfig};
+
+/// RustのStringをCの文字列として呼び出し側に渡す
+fn into_c_string(s: String) -> *mut c_char {
+    // VHDL・JSONとも内部にNULは含まれないが、念のため除去する
+    let s = s.replace('\0', "");
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// エラーメッセージを出力引数に書き込む
+///
+/// # Safety
+/// `error` はNULLか、書き込み可能な `char*` へのポインタであること
+unsafe fn set_error(error: *mut *mut c_char, message: String) {
+    if !error.is_null() {
+        // SAFETY: 呼び出し側が有効なポインタを渡すことを保証する
+        unsafe { *error = into_c_string(message) };
+    }
+}
+
+/// Cの文字列をRustの&strとして借用する
+///
+/// # Safety
+/// `s` はNULLか、NUL終端された有効な文字列であること
+unsafe fn borrow_str<'a>(s: *const c_char) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err("source is NULL".to_string());
+    }
+    // SAFETY: 呼び出し側がNUL終端文字列であることを保証する
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| "source is not valid UTF-8".to_string())
+}
+
+/// VHDLソースを解析し、結果をJSON文字列で返す
+///
+/// 失敗時はNULLを返し、`error` がNULLでなければエラーメッセージを書き込みます。
+///
+/// # Safety
+/// `source` はNUL終端されたUTF-8文字列、`error` はNULLか書き込み可能なポインタであること
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vig_analyze_to_json(
+    source: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: 関数の前提条件をそのまま引き継ぐ
+    let result = unsafe { borrow_str(source) }
+        .and_then(|src| analyzer::analyze_vhdl(src).map_err(|e| e.to_string()));
+    match result {
+        Ok(result) => into_c_string(result.to_json()),
+        Err(message) => {
+            // SAFETY: 関数の前提条件をそのまま引き継ぐ
+            unsafe { set_error(error, message) };
+            ptr::null_mut()
+        }
+    }
+}
+
+/// VHDLソース中の全エンティティのテストベンチを生成する
+///
+/// 失敗時はNULLを返し、`error` がNULLでなければエラーメッセージを書き込みます。
+///
+/// # Safety
+/// `source` はNUL終端されたUTF-8文字列、`error` はNULLか書き込み可能なポインタであること
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vig_generate_testbench(
+    source: *const c_char,
+    clock_period_ns: u64,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: 関数の前提条件をそのまま引き継ぐ
+    let result = unsafe { borrow_str(source) }
+        .and_then(|src| analyzer::analyze_vhdl(src).map_err(|e| e.to_string()));
+    match result {
+        Ok(result) => {
+            let config = TbConfig {
+                clock_period_ns,
+                ..TbConfig::default()
+            };
+            let tb: String = result
+                .entities
+                .iter()
+                .map(|e| generator::generate_testbench(e, &config))
+                .collect();
+            into_c_string(tb)
+        }
+        Err(message) => {
+            // SAFETY: 関数の前提条件をそのまま引き継ぐ
+            unsafe { set_error(error, message) };
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Vigが返した文字列を解放する（NULLは無視）
+///
+/// # Safety
+/// `s` はNULLか、Vigの関数が返した未解放の文字列であること
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vig_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: into_c_string で確保した文字列であることを呼び出し側が保証する
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
@@ -0,0 +1,394 @@
+//! エディタ向けの補完候補
+//!
+//! カーソル位置の文脈（ユニットの外・ポート宣言・アーキテクチャの宣言部/本体など）
+//! に応じて、その場で書けるキーワードと参照できる名前を返します。
+//! 編集途中で全体の解析に失敗しても、カーソルより前のユニットとトークン列から候補を集めます。
+
+use crate::analyzer::{AnalyzeResult, PortDirection, analyze_vhdl};
+use crate::generator::{direction_to_vhdl, type_to_vhdl};
+use crate::ident;
+use crate::lexer::{Span, Token, TokenKind};
+use crate::semantic::{STANDARD_TYPES, SemanticTokenKind, Symbols, collect_symbols, lex_tokens};
+
+/// 補完候補の種類
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Keyword,
+    Type,
+    Port,
+    Signal,
+    Constant,
+    Entity,
+}
+
+impl CompletionKind {
+    /// LSPの CompletionItemKind の番号
+    pub fn lsp_kind(&self) -> u32 {
+        match self {
+            CompletionKind::Keyword => 14,
+            CompletionKind::Type => 7,
+            CompletionKind::Port => 5,
+            CompletionKind::Signal => 6,
+            CompletionKind::Constant => 21,
+            CompletionKind::Entity => 9,
+        }
+    }
+}
+
+/// 補完候補
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// 型や向きなどの補足（`in std_logic` など）
+    pub detail: Option<String>,
+}
+
+/// カーソル位置の文脈
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Region {
+    /// ユニットの外
+    TopLevel,
+    /// エンティティ宣言の generic/port 以外の部分
+    EntityHeader,
+    /// generic/port の括弧内
+    Clause { is_port: bool },
+    /// アーキテクチャの begin より前
+    ArchDecl,
+    /// アーキテクチャの begin 以降
+    ArchBody,
+}
+
+/// カーソル位置を含むユニットの状態
+struct Cursor<'a> {
+    region: Region,
+    /// アーキテクチャ内なら対象エンティティ名
+    entity: Option<&'a str>,
+    /// 現在のユニットの先頭トークン位置
+    unit_start: Option<usize>,
+}
+
+const TOP_LEVEL_KEYWORDS: &[&str] = &["library", "use", "entity", "architecture", "package"];
+const ENTITY_KEYWORDS: &[&str] = &["generic", "port", "end"];
+const DECL_KEYWORDS: &[&str] = &[
+    "signal",
+    "constant",
+    "type",
+    "subtype",
+    "component",
+    "begin",
+];
+const BODY_KEYWORDS: &[&str] = &[
+    "process", "begin", "end", "if", "then", "elsif", "else", "case", "when", "others", "and",
+    "or", "not", "xor", "wait", "for", "loop", "assert", "report", "severity",
+];
+const DIRECTIONS: &[&str] = &["in", "out", "inout", "buffer"];
+const BUILTIN_TYPES: &[&str] = &["std_logic", "std_logic_vector", "integer", "boolean"];
+
+/// カーソル位置（バイトオフセット）の補完候補を返す
+///
+/// カーソル直前の入力途中の単語を接頭辞として、大文字小文字を区別せずに絞り込みます。
+pub fn completions(source: &str, offset: usize) -> Vec<CompletionItem> {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let mut tokens = lex_tokens(source);
+
+    // 入力途中の単語（宣言として拾わないようにトークン列から外す）
+    let word = tokens.iter().position(|t| {
+        t.span.start < offset
+            && offset <= t.span.end
+            && t.text.starts_with(|c: char| c.is_ascii_alphabetic())
+    });
+    let word_start = word.map_or(offset, |i| tokens.remove(i).span.start);
+    let prefix = source[word_start..offset].to_lowercase();
+    let before: Vec<&Token> = tokens.iter().filter(|t| t.span.end <= word_start).collect();
+
+    let cursor = locate(&before);
+    // 全体を解析できなければ現在のユニットより前だけを解析する
+    let result = analyze_vhdl(source)
+        .or_else(|_| analyze_vhdl(&source[..cursor.unit_start.unwrap_or(word_start)]))
+        .unwrap_or_default();
+
+    let symbols = collect_symbols(&tokens, &result, Span::new(0, source.len()));
+    let names = |items: &mut Vec<CompletionItem>| {
+        push_names(items, &tokens, &before, &result, &symbols, &cursor, offset)
+    };
+
+    let last = before.last().map(|t| &t.kind);
+    let is_work_dot = last == Some(&TokenKind::Dot)
+        && before
+            .len()
+            .checked_sub(2)
+            .is_some_and(|i| before[i].text.eq_ignore_ascii_case("work"));
+
+    let mut items = Vec::new();
+    if last == Some(&TokenKind::Of) || is_work_dot {
+        push_entities(&mut items, &tokens, &result);
+    } else {
+        match cursor.region {
+            Region::TopLevel => push_keywords(&mut items, TOP_LEVEL_KEYWORDS),
+            Region::EntityHeader => push_keywords(&mut items, ENTITY_KEYWORDS),
+            Region::Clause { is_port } => match last.cloned() {
+                Some(TokenKind::Colon) => {
+                    if is_port {
+                        push_keywords(&mut items, DIRECTIONS);
+                    }
+                    push_types(&mut items, &symbols);
+                }
+                Some(TokenKind::In | TokenKind::Out | TokenKind::Inout | TokenKind::Buffer) => {
+                    push_types(&mut items, &symbols)
+                }
+                Some(TokenKind::Assignment) => names(&mut items),
+                _ => {}
+            },
+            Region::ArchDecl => match last.cloned() {
+                Some(TokenKind::Colon) => push_types(&mut items, &symbols),
+                Some(TokenKind::Assignment) => names(&mut items),
+                _ => push_keywords(&mut items, DECL_KEYWORDS),
+            },
+            Region::ArchBody => {
+                if last == Some(&TokenKind::Colon) {
+                    // ラベルの後はインスタンス化かプロセス
+                    push_keywords(&mut items, &["entity", "process"]);
+                    push_entities(&mut items, &tokens, &result);
+                } else {
+                    names(&mut items);
+                    push_keywords(&mut items, BODY_KEYWORDS);
+                }
+            }
+        }
+    }
+
+    // 接頭辞で絞り込み、同名は先に追加したものを残す
+    let mut seen: Vec<String> = Vec::new();
+    items.retain(|item| {
+        let lower = item.label.to_lowercase();
+        if !lower.starts_with(&prefix) || seen.contains(&lower) {
+            return false;
+        }
+        seen.push(lower);
+        true
+    });
+    items
+}
+
+/// カーソル直前までのトークンからユニットと文脈を求める
+fn locate<'a>(tokens: &[&'a Token]) -> Cursor<'a> {
+    let mut cursor = Cursor {
+        region: Region::TopLevel,
+        entity: None,
+        unit_start: None,
+    };
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let next = tokens.get(i + 1).map(|t| &t.kind);
+        match cursor.region {
+            Region::TopLevel => {
+                if token.kind == TokenKind::Entity {
+                    cursor.region = Region::EntityHeader;
+                    cursor.unit_start = Some(token.span.start);
+                } else if token.kind == TokenKind::Architecture {
+                    cursor.region = Region::ArchDecl;
+                    cursor.unit_start = Some(token.span.start);
+                    cursor.entity = tokens
+                        .get(i + 3)
+                        .filter(|t| t.kind == TokenKind::Identifier)
+                        .map(|t| t.text);
+                }
+            }
+            Region::EntityHeader => match token.kind {
+                TokenKind::Port | TokenKind::Generic if next == Some(&TokenKind::LeftParen) => {
+                    cursor.region = Region::Clause {
+                        is_port: token.kind == TokenKind::Port,
+                    };
+                    depth = 0;
+                }
+                TokenKind::End => i = close_unit(tokens, i, &mut cursor),
+                _ => {}
+            },
+            Region::Clause { .. } => match token.kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        cursor.region = Region::EntityHeader;
+                    }
+                }
+                _ => {}
+            },
+            Region::ArchDecl => {
+                if token.kind == TokenKind::Begin {
+                    cursor.region = Region::ArchBody;
+                }
+            }
+            Region::ArchBody => {
+                // end; / end architecture / end <名前> でアーキテクチャを閉じる
+                let closes = token.kind == TokenKind::End
+                    && matches!(
+                        next.cloned(),
+                        Some(
+                            TokenKind::Semicolon | TokenKind::Architecture | TokenKind::Identifier
+                        )
+                    );
+                if closes {
+                    i = close_unit(tokens, i, &mut cursor);
+                }
+            }
+        }
+        i += 1;
+    }
+    cursor
+}
+
+/// `end ... ;` を読み飛ばしてユニットを閉じる（`;` がまだ無ければ閉じない）
+fn close_unit(tokens: &[&Token], end: usize, cursor: &mut Cursor) -> usize {
+    match tokens[end..]
+        .iter()
+        .position(|t| t.kind == TokenKind::Semicolon)
+    {
+        Some(n) => {
+            cursor.region = Region::TopLevel;
+            cursor.entity = None;
+            cursor.unit_start = None;
+            end + n
+        }
+        None => tokens.len(),
+    }
+}
+
+fn push_keywords(items: &mut Vec<CompletionItem>, keywords: &[&str]) {
+    items.extend(keywords.iter().map(|k| CompletionItem {
+        label: k.to_string(),
+        kind: CompletionKind::Keyword,
+        detail: None,
+    }));
+}
+
+fn push_types(items: &mut Vec<CompletionItem>, symbols: &Symbols) {
+    let builtin = BUILTIN_TYPES
+        .iter()
+        .chain(STANDARD_TYPES)
+        .map(|s| s.to_string());
+    // 標準の型の後にソース中の型名を並べる
+    let user = symbols
+        .type_names
+        .iter()
+        .filter(|t| !STANDARD_TYPES.contains(&t.as_str()))
+        .cloned();
+    items.extend(builtin.chain(user).map(|label| CompletionItem {
+        label,
+        kind: CompletionKind::Type,
+        detail: None,
+    }));
+}
+
+fn push_entities(items: &mut Vec<CompletionItem>, tokens: &[Token], result: &AnalyzeResult) {
+    items.extend(result.entities.iter().map(|e| CompletionItem {
+        label: e.name.clone(),
+        kind: CompletionKind::Entity,
+        detail: Some(format!("{} ports", e.ports.len())),
+    }));
+    // 解析できなかった部分の entity <名前> is も拾う
+    for w in tokens.windows(3) {
+        if w[0].kind == TokenKind::Entity
+            && w[1].kind == TokenKind::Identifier
+            && w[2].kind == TokenKind::Is
+        {
+            items.push(CompletionItem {
+                label: w[1].text.to_string(),
+                kind: CompletionKind::Entity,
+                detail: None,
+            });
+        }
+    }
+}
+
+/// カーソル位置から参照できるポート・信号・定数
+fn push_names(
+    items: &mut Vec<CompletionItem>,
+    tokens: &[Token],
+    before: &[&Token],
+    result: &AnalyzeResult,
+    symbols: &Symbols,
+    cursor: &Cursor,
+    offset: usize,
+) {
+    // アーキテクチャが解析できていなくても対象エンティティのポートは見える
+    if let Some(entity) = cursor.entity.and_then(|name| {
+        result
+            .entities
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+    }) {
+        items.extend(entity.ports.iter().map(|p| CompletionItem {
+            label: p.name.clone(),
+            kind: CompletionKind::Port,
+            detail: Some(port_detail(&p.direction, &type_to_vhdl(&p.vhdl_type))),
+        }));
+    }
+    for arch in &result.architectures {
+        if arch.span.start <= offset && offset <= arch.span.end {
+            items.extend(arch.signals.iter().map(|s| CompletionItem {
+                label: s.name.clone(),
+                kind: CompletionKind::Signal,
+                detail: Some(type_to_vhdl(&s.vhdl_type)),
+            }));
+        }
+    }
+    // 現在のユニットでカーソルより前に宣言された信号
+    if let Some(start) = cursor.unit_start {
+        let unit: Vec<&Token> = before
+            .iter()
+            .copied()
+            .filter(|t| t.span.start >= start)
+            .collect();
+        for (i, token) in unit.iter().enumerate() {
+            if token.kind != TokenKind::Signal {
+                continue;
+            }
+            for t in unit[i + 1..].iter().step_by(2) {
+                if t.kind != TokenKind::Identifier {
+                    break;
+                }
+                items.push(CompletionItem {
+                    label: t.text.to_string(),
+                    kind: CompletionKind::Signal,
+                    detail: None,
+                });
+            }
+        }
+    }
+    for scoped in &symbols.names {
+        let kind = match scoped.kind {
+            SemanticTokenKind::Port => CompletionKind::Port,
+            SemanticTokenKind::Signal => CompletionKind::Signal,
+            SemanticTokenKind::Constant => CompletionKind::Constant,
+            _ => continue,
+        };
+        if scoped.scope.start <= offset && offset <= scoped.scope.end {
+            // 元の綴りをソースから取り出す
+            let label = tokens
+                .iter()
+                .find(|t| {
+                    t.kind == TokenKind::Identifier && t.text.eq_ignore_ascii_case(&scoped.name)
+                })
+                .map_or_else(|| scoped.name.clone(), |t| t.text.to_string());
+            if !ident::is_reserved(&scoped.name) {
+                items.push(CompletionItem {
+                    label,
+                    kind,
+                    detail: None,
+                });
+            }
+        }
+    }
+}
+
+fn port_detail(direction: &PortDirection, vhdl_type: &str) -> String {
+    format!("{} {}", direction_to_vhdl(direction), vhdl_type)
+}
@@ -0,0 +1,164 @@
+//! エンティティのポートから制約ファイル（XDC/SDC）の雛形を生成
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection};
+use crate::generator::find_clock_ports;
+
+/// 制約ファイルの形式
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintFormat {
+    /// Xilinx Vivado
+    Xdc,
+    /// Synopsys Design Constraints（Intel Quartus など）
+    Sdc,
+}
+
+/// 制約ファイル生成の設定
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct ConstraintConfig {
+    pub format: ConstraintFormat,
+    /// クロック周期（ns）
+    pub clock_period_ns: u64,
+    /// IO規格（XDCの IOSTANDARD、Quartus の IO_STANDARD に入れる値）
+    pub io_standard: String,
+}
+
+impl Default for ConstraintConfig {
+    fn default() -> Self {
+        Self {
+            format: ConstraintFormat::Xdc,
+            clock_period_ns: 10,
+            io_standard: "LVCMOS33".to_string(),
+        }
+    }
+}
+
+/// エンティティのポートから制約ファイルの雛形を生成する
+///
+/// 検出したクロックには `create_clock` を、ピン配置・IO規格（とSDCでは入出力遅延）は
+/// ポートごとにコメントアウトした雛形行を出力します。
+pub fn generate_constraints(entity: &EntityDef, config: &ConstraintConfig) -> String {
+    let clocks = find_clock_ports(&entity.ports);
+    let format_name = match config.format {
+        ConstraintFormat::Xdc => "XDC",
+        ConstraintFormat::Sdc => "SDC",
+    };
+    let mut out = String::new();
+    out.push_str(&format!(
+        "# {} の制約ファイル雛形 ({})\n",
+        entity.name, format_name
+    ));
+    out.push_str("# vig constraints により生成\n");
+
+    if !clocks.is_empty() {
+        out.push_str("\n# クロック\n");
+        for clk in &clocks {
+            out.push_str(&match config.format {
+                ConstraintFormat::Xdc => format!(
+                    "create_clock -period {}.000 -name {} [get_ports {}]\n",
+                    config.clock_period_ns, clk, clk
+                ),
+                ConstraintFormat::Sdc => format!(
+                    "create_clock -name {} -period {}.000 [get_ports {{{}}}]\n",
+                    clk, config.clock_period_ns, clk
+                ),
+            });
+        }
+    }
+
+    push_pins(&mut out, &entity.ports, config);
+    if config.format == ConstraintFormat::Sdc {
+        push_sdc_delays(&mut out, &entity.ports, &clocks);
+    }
+    out
+}
+
+/// ピン配置・IO規格の雛形
+///
+/// ベクタは `{d[*]}` でまとめてIO規格を指定し、ピンは先頭ビットの例を1行だけ出力します。
+/// SDCにはピン配置の構文がないため、Quartus の `.qsf` に書く割り当てを出力します。
+fn push_pins(out: &mut String, ports: &[PortDef], config: &ConstraintConfig) {
+    if ports.is_empty() {
+        return;
+    }
+    let xdc = config.format == ConstraintFormat::Xdc;
+    out.push_str(if xdc {
+        "\n# ピン配置（<pin> を実際のピン番号に置き換えてください）\n"
+    } else {
+        "\n# ピン配置・IO規格（.qsf に移して <pin> を実際のピン番号に置き換えてください）\n"
+    });
+    for port in ports {
+        let (comment, pin_target, target) = match port.vhdl_type.bit_width() {
+            Some(1) => (port.name.clone(), port.name.clone(), port.name.clone()),
+            width => {
+                let range = match width {
+                    Some(width) => format!("[{}:0]", width - 1),
+                    None => "（ビット幅不明）".to_string(),
+                };
+                (
+                    format!("{}{}（ピンはビットごとに割り当てる）", port.name, range),
+                    format!("{}[0]", port.name),
+                    format!("{}[*]", port.name),
+                )
+            }
+        };
+        out.push_str(&format!("\n# {}\n", comment));
+        if xdc {
+            out.push_str(&format!(
+                "# set_property PACKAGE_PIN <pin> [get_ports {}]\n",
+                xdc_target(&pin_target)
+            ));
+            out.push_str(&format!(
+                "# set_property IOSTANDARD {} [get_ports {}]\n",
+                config.io_standard,
+                xdc_target(&target)
+            ));
+        } else {
+            out.push_str(&format!(
+                "# set_location_assignment <pin> -to {}\n",
+                pin_target
+            ));
+            out.push_str(&format!(
+                "# set_instance_assignment -name IO_STANDARD \"{}\" -to {}\n",
+                config.io_standard, target
+            ));
+        }
+    }
+}
+
+/// XDCの get_ports の引数（添字付きは波括弧で囲む）
+fn xdc_target(target: &str) -> String {
+    if target.contains('[') {
+        format!("{{{}}}", target)
+    } else {
+        target.to_string()
+    }
+}
+
+/// SDCの入出力遅延の雛形
+fn push_sdc_delays(out: &mut String, ports: &[PortDef], clocks: &[String]) {
+    let io_ports: Vec<&PortDef> = ports.iter().filter(|p| !clocks.contains(&p.name)).collect();
+    if io_ports.is_empty() {
+        return;
+    }
+    let clock = clocks.first().map(String::as_str).unwrap_or("<clock>");
+    out.push_str("\n# 入出力遅延（<delay> を実際の値に置き換えてください）\n");
+    for port in io_ports {
+        let target = match port.vhdl_type.bit_width() {
+            Some(1) => port.name.clone(),
+            _ => format!("{}[*]", port.name),
+        };
+        let commands: &[&str] = match port.direction {
+            PortDirection::In => &["set_input_delay"],
+            PortDirection::Out | PortDirection::Buffer => &["set_output_delay"],
+            PortDirection::Inout => &["set_input_delay", "set_output_delay"],
+        };
+        for command in commands {
+            out.push_str(&format!(
+                "# {} -clock {} <delay> [get_ports {{{}}}]\n",
+                command, clock, target
+            ));
+        }
+    }
+}
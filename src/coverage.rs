@@ -0,0 +1,188 @@
+//! トグルカバレッジの計測
+//!
+//! 出力ポートの各ビットが 0→1 と 1→0 の両方に変化したかを数え、
+//! シミュレーションの最後に変化しなかったビットを報告するプロセスを生成します。
+//! シミュレータのカバレッジ機能を使わずに、テストの網羅性の目安を得るためのものです。
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use crate::generator::{TbProcess, TestbenchModel};
+use crate::ident::suffixed;
+
+/// 計測する出力があるか
+pub(crate) fn has_outputs(ports: &[PortDef]) -> bool {
+    !monitored(ports).is_empty()
+}
+
+/// 計測する出力（std_logic と std_logic_vector のみ）
+fn monitored(ports: &[PortDef]) -> Vec<(&str, Option<(i64, i64)>)> {
+    ports
+        .iter()
+        .filter(|p| p.direction != PortDirection::In)
+        .filter_map(|p| match &p.vhdl_type {
+            VhdlType::StdLogic => Some((p.name.as_str(), None)),
+            VhdlType::StdLogicVector { .. } => {
+                let (left, right) = p.vhdl_type.vector_bounds()?;
+                Some((p.name.as_str(), Some((left.value()?, right.value()?))))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 範囲の VHDL 表記（左・右の境界から）
+fn range(left: i64, right: i64) -> String {
+    let dir = if left >= right { "downto" } else { "to" };
+    format!("({} {} {})", left, dir, right)
+}
+
+/// 1ビットの判定と報告（`rose` `fell` は std_logic の式、`label` は文字列の式）
+fn report_bit(body: &mut Vec<String>, rose: &str, fell: &str, label: &str, indent: &str) {
+    body.extend([
+        format!("{}if {} = '0' and {} = '0' then", indent, rose, fell),
+        format!(
+            "{}    report \"toggle coverage: \" & {} & \" never toggled\" severity warning;",
+            indent, label
+        ),
+        format!("{}elsif {} = '0' then", indent, rose),
+        format!(
+            "{}    report \"toggle coverage: \" & {} & \" never rose (0->1)\" severity warning;",
+            indent, label
+        ),
+        format!("{}elsif {} = '0' then", indent, fell),
+        format!(
+            "{}    report \"toggle coverage: \" & {} & \" never fell (1->0)\" severity warning;",
+            indent, label
+        ),
+        format!("{}else", indent),
+        format!("{}    covered := covered + 1;", indent),
+        format!("{}end if;", indent),
+    ]);
+}
+
+/// 1ビットの変化を記録
+fn record_bit(
+    body: &mut Vec<String>,
+    name: &str,
+    last: &str,
+    flags: (&str, &str),
+    toggles: &str,
+    indent: &str,
+) {
+    body.extend([
+        format!("{}if {} = '0' and {} = '1' then", indent, last, name),
+        format!("{}    {} := '1';", indent, flags.0),
+        format!("{}    {} := {} + 1;", indent, toggles, toggles),
+        format!("{}end if;", indent),
+        format!("{}if {} = '1' and {} = '0' then", indent, last, name),
+        format!("{}    {} := '1';", indent, flags.1),
+        format!("{}    {} := {} + 1;", indent, toggles, toggles),
+        format!("{}end if;", indent),
+    ]);
+}
+
+/// トグルカバレッジの信号とプロセスを追加する
+///
+/// 報告はスティミュラスが `sim_done` を true にしたときに行います。
+/// 計測できる出力がなければ何もしません。
+pub(crate) fn add_toggle_coverage(model: &mut TestbenchModel, entity: &EntityDef) {
+    let outputs = monitored(&entity.ports);
+    if outputs.is_empty() {
+        return;
+    }
+    let mut process = TbProcess::new("toggle_coverage");
+    process.comment =
+        Some("トグルカバレッジ（出力の各ビットが 0→1 と 1→0 の両方に変化したか）".to_string());
+    let decls = &mut process.declarations;
+    for (name, bounds) in &outputs {
+        let (last, rose, fell) = (
+            suffixed(name, "_last"),
+            suffixed(name, "_rose"),
+            suffixed(name, "_fell"),
+        );
+        match bounds {
+            None => decls.extend([
+                format!("variable {} : std_logic := 'U';", last),
+                format!("variable {}, {} : std_logic := '0';", rose, fell),
+            ]),
+            Some((left, right)) => {
+                let range = range(*left, *right);
+                decls.extend([
+                    format!(
+                        "variable {} : std_logic_vector{} := (others => 'U');",
+                        last, range
+                    ),
+                    format!(
+                        "variable {}, {} : std_logic_vector{} := (others => '0');",
+                        rose, fell, range
+                    ),
+                ]);
+            }
+        }
+        decls.push(format!(
+            "variable {} : natural := 0;",
+            suffixed(name, "_toggles")
+        ));
+    }
+    decls.push("variable covered : natural;".to_string());
+
+    let names: Vec<&str> = outputs.iter().map(|(name, _)| *name).collect();
+    let body = &mut process.body;
+    body.push(format!(
+        "wait on {}, {};",
+        names.join(", "),
+        model.done_signal
+    ));
+    body.push(format!("if {} then", model.done_signal));
+    for (name, bounds) in &outputs {
+        body.push("    covered := 0;".to_string());
+        let (rose, fell) = (suffixed(name, "_rose"), suffixed(name, "_fell"));
+        let width = match bounds {
+            None => {
+                report_bit(body, &rose, &fell, &format!("\"{}\"", name), "    ");
+                1
+            }
+            Some((left, right)) => {
+                body.push(format!("    for i in {}'range loop", name));
+                let (rose, fell) = (format!("{}(i)", rose), format!("{}(i)", fell));
+                let label = format!("\"{}(\" & integer'image(i) & \")\"", name);
+                report_bit(body, &rose, &fell, &label, "        ");
+                body.push("    end loop;".to_string());
+                left.abs_diff(*right) + 1
+            }
+        };
+        body.push(format!(
+            "    report \"toggle coverage: {}: \" & integer'image({}) & \" toggles, \" & integer'image(covered) & \"/{} bits\" severity note;",
+            name,
+            suffixed(name, "_toggles"),
+            width
+        ));
+    }
+    body.push("    wait;".to_string());
+    body.push("end if;".to_string());
+
+    for (name, bounds) in &outputs {
+        let last = suffixed(name, "_last");
+        let toggles = suffixed(name, "_toggles");
+        let (rose, fell) = (suffixed(name, "_rose"), suffixed(name, "_fell"));
+        match bounds {
+            None => {
+                record_bit(body, name, &last, (&rose, &fell), &toggles, "");
+            }
+            Some(_) => {
+                body.push(format!("for i in {}'range loop", name));
+                let (rose, fell) = (format!("{}(i)", rose), format!("{}(i)", fell));
+                record_bit(
+                    body,
+                    &format!("{}(i)", name),
+                    &format!("{}(i)", last),
+                    (&rose, &fell),
+                    &toggles,
+                    "    ",
+                );
+                body.push("end loop;".to_string());
+            }
+        }
+        body.push(format!("{} := {};", last, name));
+    }
+    model.processes.push(process);
+}
@@ -0,0 +1,104 @@
+use crate::analyzer::{AnalyzeError, Analyzer};
+use crate::lexer::{LexError, Lexer, Span, Token, TokenKind};
+use crate::trace::timed;
+
+/// 診断の重大度
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(s)
+    }
+}
+
+/// ソース上の位置に紐づいた診断メッセージ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// 関連する位置と説明（信号の駆動元など）
+    pub related: Vec<(Span, String)>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            span,
+            related: Vec::new(),
+        }
+    }
+
+    /// 関連する位置を追加
+    pub fn with_related(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.related.push((span, message.into()));
+        self
+    }
+
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, message, span)
+    }
+
+    pub fn info(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Info, message, span)
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} at position {}-{}",
+            self.severity, self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl From<LexError> for Diagnostic {
+    fn from(err: LexError) -> Self {
+        Diagnostic::error(err.message, err.span)
+    }
+}
+
+impl From<AnalyzeError> for Diagnostic {
+    fn from(err: AnalyzeError) -> Self {
+        Diagnostic::error(err.message, err.span)
+    }
+}
+
+/// ソースコードを字句解析・意味解析し、見つかった問題をすべて診断として返す
+///
+/// `analyze_vhdl` と異なり、字句解析エラーを読み捨てずに報告します。
+pub fn diagnose(source: &str) -> Vec<Diagnostic> {
+    let (tokens, errors) = {
+        let _timed = timed!("lex", bytes = source.len());
+        Lexer::tokenize_with_errors(source)
+    };
+    let mut diagnostics: Vec<Diagnostic> = errors.into_iter().map(Diagnostic::from).collect();
+    // 読めなかった部分は意味解析では読み飛ばす
+    let tokens: Vec<Token> = tokens
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Unknown)
+        .collect();
+    if let Err(err) = Analyzer::new(tokens).analyze() {
+        diagnostics.push(err.into());
+    }
+    diagnostics
+}
@@ -0,0 +1,248 @@
+//! エンティティのブロック図（Graphviz DOT / SVG）
+//!
+//! 入力ポートを左、出力・双方向ポートを右に並べた箱を描きます。
+//! 共通プレフィックスのポート群（[`InterfaceDef`] のバス）はまとめて見出しを付けます。
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection};
+use crate::interface::InterfaceDef;
+use crate::xml::escape;
+
+/// 箱の片側に並べる行
+enum Row<'a> {
+    /// バスの見出し
+    Bus(String),
+    Port(&'a PortDef),
+}
+
+/// 入力側・出力側の行をバスごとにまとめて作る
+fn layout(entity: &EntityDef) -> (Vec<Row<'_>>, Vec<Row<'_>>) {
+    let interface = InterfaceDef::from_entity(entity);
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    let mut placed: Vec<&str> = Vec::new();
+
+    for port in &entity.ports {
+        if placed.contains(&port.name.as_str()) {
+            continue;
+        }
+        let side = |p: &PortDef| p.direction == PortDirection::In;
+        let bus = interface.buses.iter().find(|b| b.contains(&port.name));
+        match bus {
+            Some(bus) => {
+                // 同じバスの同じ向きのポートを続けて並べる
+                let members: Vec<&PortDef> = entity
+                    .ports
+                    .iter()
+                    .filter(|p| bus.contains(&p.name) && side(p) == side(port))
+                    .collect();
+                let rows = if side(port) { &mut left } else { &mut right };
+                rows.push(Row::Bus(bus.name.clone()));
+                for member in members {
+                    placed.push(&member.name);
+                    rows.push(Row::Port(member));
+                }
+            }
+            None => {
+                placed.push(&port.name);
+                if side(port) {
+                    left.push(Row::Port(port));
+                } else {
+                    right.push(Row::Port(port));
+                }
+            }
+        }
+    }
+    (left, right)
+}
+
+/// ポート名にビット範囲を付けたラベル
+fn port_label(port: &PortDef) -> String {
+    match port.vhdl_type.vector_bounds() {
+        Some((left, right)) => format!("{}[{}:{}]", port.name, left, right),
+        None => port.name.clone(),
+    }
+}
+
+/// Graphviz DOT 形式のブロック図を生成する
+pub fn generate_dot(entity: &EntityDef) -> String {
+    let (left, right) = layout(entity);
+    let cell = |row: Option<&Row>, align: &str| match row {
+        Some(Row::Bus(name)) => format!(
+            "<TD ALIGN=\"{}\"><FONT POINT-SIZE=\"10\"><I>{}</I></FONT></TD>",
+            align,
+            escape(name)
+        ),
+        Some(Row::Port(port)) => format!(
+            "<TD ALIGN=\"{}\" PORT=\"{}\">{}</TD>",
+            align,
+            escape(&port.name),
+            escape(&port_label(port))
+        ),
+        None => "<TD></TD>".to_string(),
+    };
+
+    let mut out = String::new();
+    out.push_str(&format!("digraph \"{}\" {{\n", escape(&entity.name)));
+    out.push_str("    rankdir=LR;\n");
+    out.push_str("    node [fontname=\"Helvetica\"];\n");
+    out.push_str("    edge [arrowsize=0.7];\n");
+    out.push('\n');
+    out.push_str("    entity [shape=plaintext, label=<\n");
+    out.push_str(
+        "        <TABLE BORDER=\"1\" CELLBORDER=\"0\" CELLSPACING=\"0\" CELLPADDING=\"4\">\n",
+    );
+    out.push_str(&format!(
+        "            <TR><TD COLSPAN=\"2\"><B>{}</B></TD></TR>\n",
+        escape(&entity.name)
+    ));
+    for i in 0..left.len().max(right.len()) {
+        out.push_str(&format!(
+            "            <TR>{}{}</TR>\n",
+            cell(left.get(i), "LEFT"),
+            cell(right.get(i), "RIGHT")
+        ));
+    }
+    out.push_str("        </TABLE>\n");
+    out.push_str("    >];\n");
+    out.push('\n');
+
+    // 外部との接続線
+    let ports = left.iter().chain(&right).filter_map(|row| match row {
+        Row::Port(port) => Some(*port),
+        Row::Bus(_) => None,
+    });
+    let mut edges = Vec::new();
+    for port in ports {
+        let name = escape(&port.name);
+        let mut attrs = Vec::new();
+        if port.vhdl_type.bit_width().is_some_and(|w| w > 1) {
+            attrs.push("penwidth=2.5");
+        }
+        out.push_str(&format!("    \"p_{}\" [shape=point, width=0.05];\n", name));
+        let (from, to) = match port.direction {
+            PortDirection::In => (format!("\"p_{}\"", name), format!("entity:\"{}\":w", name)),
+            PortDirection::Inout => {
+                attrs.push("dir=both");
+                (format!("entity:\"{}\":e", name), format!("\"p_{}\"", name))
+            }
+            PortDirection::Out | PortDirection::Buffer => {
+                (format!("entity:\"{}\":e", name), format!("\"p_{}\"", name))
+            }
+        };
+        if attrs.is_empty() {
+            edges.push(format!("    {} -> {};\n", from, to));
+        } else {
+            edges.push(format!("    {} -> {} [{}];\n", from, to, attrs.join(", ")));
+        }
+    }
+    if !edges.is_empty() {
+        out.push('\n');
+        for edge in edges {
+            out.push_str(&edge);
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// SVG形式のブロック図を生成する（Graphvizなしで描画）
+pub fn generate_svg(entity: &EntityDef) -> String {
+    const CHAR_W: usize = 8;
+    const ROW_H: usize = 20;
+    const TITLE_H: usize = 30;
+    const STUB: usize = 40;
+    const MARGIN: usize = 10;
+
+    let (left, right) = layout(entity);
+    let label_width = |rows: &[Row]| {
+        rows.iter()
+            .map(|row| match row {
+                Row::Bus(name) => name.chars().count(),
+                Row::Port(port) => port_label(port).chars().count(),
+            })
+            .max()
+            .unwrap_or(0)
+            * CHAR_W
+    };
+    let box_w = (label_width(&left) + label_width(&right) + 4 * CHAR_W)
+        .max((entity.name.chars().count() + 4) * CHAR_W)
+        .max(120);
+    let rows = left.len().max(right.len());
+    let box_h = TITLE_H + rows * ROW_H + MARGIN;
+    let x0 = MARGIN + STUB;
+    let x1 = x0 + box_w;
+    let width = x1 + STUB + MARGIN;
+    let height = box_h + 2 * MARGIN;
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"monospace\" font-size=\"13\">\n",
+        width, height, width, height
+    ));
+    out.push_str("  <defs>\n");
+    out.push_str("    <marker id=\"arrow\" markerWidth=\"8\" markerHeight=\"8\" refX=\"7\" refY=\"4\" orient=\"auto\">\n");
+    out.push_str("      <path d=\"M0,0 L8,4 L0,8 z\"/>\n");
+    out.push_str("    </marker>\n");
+    out.push_str("  </defs>\n");
+    out.push_str(&format!(
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"black\" stroke-width=\"2\"/>\n",
+        x0, MARGIN, box_w, box_h
+    ));
+    out.push_str(&format!(
+        "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-weight=\"bold\">{}</text>\n",
+        x0 + box_w / 2,
+        MARGIN + 20,
+        escape(&entity.name)
+    ));
+
+    for (rows, is_left) in [(&left, true), (&right, false)] {
+        for (i, row) in rows.iter().enumerate() {
+            let y = MARGIN + TITLE_H + i * ROW_H + ROW_H / 2;
+            let (text_x, anchor) = if is_left {
+                (x0 + CHAR_W, "start")
+            } else {
+                (x1 - CHAR_W, "end")
+            };
+            match row {
+                Row::Bus(name) => out.push_str(&format!(
+                    "  <text x=\"{}\" y=\"{}\" text-anchor=\"{}\" font-size=\"11\" font-style=\"italic\" fill=\"gray\">{}</text>\n",
+                    text_x,
+                    y + 4,
+                    anchor,
+                    escape(name)
+                )),
+                Row::Port(port) => {
+                    let bus_width = if port.vhdl_type.bit_width().is_some_and(|w| w > 1) {
+                        3
+                    } else {
+                        1
+                    };
+                    let (from, to, markers) = match port.direction {
+                        PortDirection::In => (x0 - STUB, x0, " marker-end=\"url(#arrow)\""),
+                        PortDirection::Inout => (
+                            x1 + STUB,
+                            x1,
+                            " marker-start=\"url(#arrow)\" marker-end=\"url(#arrow)\"",
+                        ),
+                        PortDirection::Out | PortDirection::Buffer => {
+                            (x1, x1 + STUB, " marker-end=\"url(#arrow)\"")
+                        }
+                    };
+                    out.push_str(&format!(
+                        "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" stroke-width=\"{}\"{}/>\n",
+                        from, y, to, y, bus_width, markers
+                    ));
+                    out.push_str(&format!(
+                        "  <text x=\"{}\" y=\"{}\" text-anchor=\"{}\">{}</text>\n",
+                        text_x,
+                        y + 4,
+                        anchor,
+                        escape(&port_label(port))
+                    ));
+                }
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
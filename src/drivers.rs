@@ -0,0 +1,326 @@
+//! アーキテクチャ本体の駆動と参照の抽出
+//!
+//! プロセスと並行代入をトークン列から読み取り、どの信号に代入し、どの名前を参照するかを
+//! 集めます。リントやメトリクスが共通に使う下位の解析です。
+
+use crate::lexer::{Lexer, LexerOptions, Span, Token, TokenKind};
+
+/// 名前の参照
+pub(crate) struct Read {
+    /// 小文字の名前
+    pub name: String,
+    pub span: Span,
+    /// 代入の右辺で参照していれば代入先
+    pub target: Option<String>,
+    /// `target <= name;` のように右辺がこの名前だけか
+    pub sole: bool,
+}
+
+/// 宣言部の信号
+pub(crate) struct SignalDecl {
+    /// 小文字の名前
+    pub name: String,
+    pub span: Span,
+    /// 小文字の型名（制約を除く）
+    pub type_name: String,
+}
+
+/// 駆動の単位（プロセス1つ、または並行代入1つ）
+pub(crate) struct Driver {
+    /// プロセスのラベル
+    pub label: Option<String>,
+    /// プロセスなら `process` キーワード、並行代入なら代入先の位置
+    pub span: Span,
+    pub is_process: bool,
+    /// クロック付きプロセスならクロック名（小文字）
+    pub clock: Option<String>,
+    /// 代入先（小文字の名前, 位置）
+    pub writes: Vec<(String, Span)>,
+    pub reads: Vec<Read>,
+    /// 文の数（複合文も1つと数え、中の文も数える）
+    pub statements: usize,
+    /// if / case / loop の入れ子の深さの最大
+    pub nesting_depth: usize,
+}
+
+/// アーキテクチャ1つ
+pub(crate) struct Architecture {
+    pub name: String,
+    /// 対象エンティティの名前
+    pub entity: String,
+    /// 宣言部の信号
+    pub signals: Vec<SignalDecl>,
+    pub drivers: Vec<Driver>,
+}
+
+/// ソース中のアーキテクチャをすべて読む
+pub(crate) fn architectures(source: &str) -> Vec<Architecture> {
+    let tokens: Vec<Token> = Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .filter_map(|r| r.ok())
+        .collect();
+    let mut scanner = Scanner {
+        tokens: &tokens,
+        pos: 0,
+    };
+    let mut archs = Vec::new();
+    while scanner.pos < tokens.len() {
+        if scanner.kind(0) == Some(&TokenKind::Architecture)
+            && scanner.kind(1) == Some(&TokenKind::Identifier)
+            && scanner.kind(2) == Some(&TokenKind::Of)
+        {
+            archs.push(scanner.architecture());
+        } else {
+            scanner.pos += 1;
+        }
+    }
+    archs
+}
+
+struct Scanner<'a> {
+    tokens: &'a [Token<'a>],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn kind(&self, offset: usize) -> Option<&'a TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| &t.kind)
+    }
+
+    fn is_word(&self, offset: usize, word: &str) -> bool {
+        self.tokens
+            .get(self.pos + offset)
+            .is_some_and(|t| t.text.eq_ignore_ascii_case(word))
+    }
+
+    /// `architecture a of e is` から `end architecture` まで
+    fn architecture(&mut self) -> Architecture {
+        let mut arch = Architecture {
+            name: self.tokens[self.pos + 1].text.to_string(),
+            entity: self
+                .tokens
+                .get(self.pos + 3)
+                .map_or(String::new(), |t| t.text.to_string()),
+            signals: Vec::new(),
+            drivers: Vec::new(),
+        };
+        // 宣言部
+        while let Some(kind) = self.kind(0) {
+            match *kind {
+                TokenKind::Begin => break,
+                TokenKind::Signal => {
+                    self.pos += 1;
+                    let first = arch.signals.len();
+                    while self.kind(0) == Some(&TokenKind::Identifier) {
+                        let t = &self.tokens[self.pos];
+                        arch.signals.push(SignalDecl {
+                            name: t.text.to_lowercase(),
+                            span: t.span,
+                            type_name: String::new(),
+                        });
+                        self.pos += 1;
+                        if self.kind(0) == Some(&TokenKind::Comma) {
+                            self.pos += 1;
+                        }
+                    }
+                    if self.kind(0) == Some(&TokenKind::Colon)
+                        && let Some(t) = self.tokens.get(self.pos + 1)
+                    {
+                        let type_name = t.text.to_lowercase();
+                        for decl in &mut arch.signals[first..] {
+                            decl.type_name = type_name.clone();
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        let mut start = true;
+        while let Some(kind) = self.kind(0) {
+            match *kind {
+                TokenKind::End
+                    if self.kind(1) == Some(&TokenKind::Architecture)
+                        || (self.kind(1) == Some(&TokenKind::Identifier)
+                            && self.kind(2) == Some(&TokenKind::Semicolon))
+                        || self.kind(1) == Some(&TokenKind::Semicolon) =>
+                {
+                    return arch;
+                }
+                TokenKind::Process => {
+                    let label = (self.pos >= 2
+                        && self.tokens[self.pos - 1].kind == TokenKind::Colon
+                        && self.tokens[self.pos - 2].kind == TokenKind::Identifier)
+                        .then(|| self.tokens[self.pos - 2].text.to_string());
+                    let mut driver = self.process();
+                    driver.label = label;
+                    arch.drivers.push(driver);
+                    start = true;
+                    continue;
+                }
+                TokenKind::Identifier if start => {
+                    if let Some((write, reads)) = self.assignment() {
+                        arch.drivers.push(Driver {
+                            label: None,
+                            span: write.1,
+                            is_process: false,
+                            clock: None,
+                            writes: vec![write],
+                            reads,
+                            statements: 1,
+                            nesting_depth: 0,
+                        });
+                        start = true;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            // ラベル（`name :`）の後も文の先頭とみなす
+            start = matches!(
+                *kind,
+                TokenKind::Semicolon | TokenKind::Begin | TokenKind::Colon
+            );
+            self.pos += 1;
+        }
+        arch
+    }
+
+    /// 文の先頭が `target [(...)] <=` なら代入を読む
+    ///
+    /// 右辺（`;` まで）と添字の識別子を参照とします。
+    fn assignment(&mut self) -> Option<((String, Span), Vec<Read>)> {
+        let target = self.tokens.get(self.pos)?;
+        let mut at = 1;
+        let mut reads = Vec::new();
+        if self.kind(1) == Some(&TokenKind::LeftParen) {
+            // 添字の中は参照
+            let mut depth = 0;
+            loop {
+                match *self.kind(at)? {
+                    TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightParen => depth -= 1,
+                    TokenKind::Identifier => reads.push(self.read(at, None, false)),
+                    _ => {}
+                }
+                at += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+        if self.kind(at) != Some(&TokenKind::Lte) {
+            return None;
+        }
+        let name = target.text.to_lowercase();
+        let rhs = self.pos + at + 1;
+        let end = (rhs..self.tokens.len())
+            .find(|&i| self.tokens[i].kind == TokenKind::Semicolon)
+            .unwrap_or(self.tokens.len());
+        let sole = end == rhs + 1;
+        for i in rhs..end {
+            if self.tokens[i].kind == TokenKind::Identifier {
+                reads.push(self.read(i - self.pos, Some(name.clone()), sole));
+            }
+        }
+        self.pos = end + 1;
+        Some(((name, target.span), reads))
+    }
+
+    fn read(&self, offset: usize, target: Option<String>, sole: bool) -> Read {
+        let token = &self.tokens[self.pos + offset];
+        Read {
+            name: token.text.to_lowercase(),
+            span: token.span,
+            target,
+            sole,
+        }
+    }
+
+    /// `process` から `end process;` まで
+    fn process(&mut self) -> Driver {
+        let mut driver = Driver {
+            label: None,
+            span: self.tokens[self.pos].span,
+            is_process: true,
+            clock: None,
+            writes: Vec::new(),
+            reads: Vec::new(),
+            statements: 0,
+            nesting_depth: 0,
+        };
+        self.pos += 1;
+        // 感度リストと宣言部
+        while self.kind(0).is_some_and(|k| *k != TokenKind::Begin) {
+            self.pos += 1;
+        }
+        self.pos += 1;
+        let mut start = true;
+        let mut depth = 0usize;
+        while let Some(kind) = self.kind(0) {
+            let after_end = self.pos > 0 && self.tokens[self.pos - 1].kind == TokenKind::End;
+            if *kind == TokenKind::End && self.kind(1) == Some(&TokenKind::Process) {
+                while self.kind(0).is_some_and(|k| *k != TokenKind::Semicolon) {
+                    self.pos += 1;
+                }
+                self.pos += 1;
+                break;
+            }
+            // rising_edge(clk) / falling_edge(clk)
+            if (self.is_word(0, "rising_edge") || self.is_word(0, "falling_edge"))
+                && self.kind(1) == Some(&TokenKind::LeftParen)
+                && self.kind(2) == Some(&TokenKind::Identifier)
+            {
+                let read = self.read(2, None, false);
+                if driver.clock.is_none() {
+                    driver.clock = Some(read.name.clone());
+                }
+                driver.reads.push(read);
+                self.pos += 4;
+                start = false;
+                continue;
+            }
+            if *kind == TokenKind::Identifier
+                && start
+                && let Some((write, reads)) = self.assignment()
+            {
+                driver.writes.push(write);
+                driver.reads.extend(reads);
+                driver.statements += 1;
+                continue;
+            }
+            // 複合文の入れ子（`end if` などで戻る）
+            if matches!(*kind, TokenKind::If | TokenKind::Case) || self.is_word(0, "loop") {
+                if after_end {
+                    // 対応する開始のない `end if` は数えない
+                    depth = depth.saturating_sub(1);
+                } else {
+                    depth += 1;
+                    driver.nesting_depth = driver.nesting_depth.max(depth);
+                }
+            }
+            if *kind == TokenKind::Semicolon {
+                driver.statements += 1;
+            }
+            if *kind == TokenKind::Identifier {
+                driver.reads.push(self.read(0, None, false));
+            }
+            start = matches!(
+                *kind,
+                TokenKind::Semicolon
+                    | TokenKind::Begin
+                    | TokenKind::Then
+                    | TokenKind::Else
+                    | TokenKind::Association
+                    | TokenKind::Colon
+            ) || self.is_word(0, "loop");
+            self.pos += 1;
+        }
+        driver
+    }
+}
@@ -0,0 +1,732 @@
+use crate::analyzer::{
+    AnalyzeResult, ArchitectureDef, AssociationDef, ConstantDef, EntityDef, GenericDef,
+    InstanceDef, InstantiatedUnit, LoopScheme, PackageDef, PortDef, PortDirection, ProcessDef,
+    SignalDef, Statement, SubprogramDecl, TypeDef, VariableDef, VhdlType, same_identifier,
+};
+use crate::generator::direction_to_vhdl;
+
+/// キーワードの大文字・小文字
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCase {
+    Lower,
+    Upper,
+}
+
+/// 出力スタイルの設定
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmitStyle {
+    /// 1段あたりのインデント幅（スペース数）
+    pub indent: usize,
+    pub keyword_case: KeywordCase,
+    /// ポート・信号宣言の `:` を縦に揃える
+    pub align_colons: bool,
+}
+
+impl Default for EmitStyle {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            keyword_case: KeywordCase::Lower,
+            align_colons: false,
+        }
+    }
+}
+
+/// 解析結果（AST）をVHDLテキストに戻すプリティプリンタ
+#[derive(Debug, Clone, Default)]
+pub struct Emitter {
+    pub style: EmitStyle,
+}
+
+impl Emitter {
+    pub fn new(style: EmitStyle) -> Self {
+        Self { style }
+    }
+
+    /// スタイルに従ってキーワードを整形
+    fn kw(&self, keyword: &str) -> String {
+        match self.style.keyword_case {
+            KeywordCase::Lower => keyword.to_lowercase(),
+            KeywordCase::Upper => keyword.to_uppercase(),
+        }
+    }
+
+    fn indent(&self, depth: usize) -> String {
+        " ".repeat(self.style.indent * depth)
+    }
+
+    /// 型のVHDL表現
+    pub fn vhdl_type(&self, vhdl_type: &VhdlType) -> String {
+        match vhdl_type {
+            VhdlType::Str(Some((left, right))) => {
+                format!("string({} {} {})", left, self.kw("to"), right)
+            }
+            VhdlType::Integer(Some(range))
+            | VhdlType::Natural(Some(range))
+            | VhdlType::Positive(Some(range)) => format!(
+                "{} {} {} {} {}",
+                vhdl_type.base_name(),
+                self.kw("range"),
+                range.left,
+                self.kw(if range.ascending { "to" } else { "downto" }),
+                range.right
+            ),
+            _ => match vhdl_type.vector_bounds() {
+                Some((left, right)) => format!(
+                    "{}({} {} {})",
+                    vhdl_type.base_name(),
+                    left,
+                    self.kw(if vhdl_type.is_ascending() {
+                        "to"
+                    } else {
+                        "downto"
+                    }),
+                    right
+                ),
+                None => vhdl_type.base_name().to_string(),
+            },
+        }
+    }
+
+    /// ポート方向のVHDL表現
+    pub fn direction(&self, direction: &PortDirection) -> String {
+        self.kw(direction_to_vhdl(direction))
+    }
+
+    /// library/use 節
+    pub fn context(&self, library: &str, uses: &[&str]) -> String {
+        let mut s = format!("{} {};\n", self.kw("library"), library);
+        for u in uses {
+            s.push_str(&format!("{} {};\n", self.kw("use"), u));
+        }
+        s
+    }
+
+    /// generic 節（`depth` は `generic` キーワードの段）
+    fn generic_clause(&self, generics: &[GenericDef], depth: usize) -> String {
+        let mut s = String::new();
+        if generics.is_empty() {
+            return s;
+        }
+        let width = if self.style.align_colons {
+            generics.iter().map(|g| g.name.len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+        s.push_str(&format!("{}{} (\n", self.indent(depth), self.kw("generic")));
+        for (i, generic) in generics.iter().enumerate() {
+            s.push_str(&format!(
+                "{}{:width$} : {}",
+                self.indent(depth + 1),
+                generic.name,
+                self.vhdl_type(&generic.vhdl_type),
+                width = width
+            ));
+            if let Some(v) = &generic.default_value {
+                s.push_str(&format!(" := {}", v));
+            }
+            s.push_str(if i + 1 < generics.len() { ";\n" } else { "\n" });
+        }
+        s.push_str(&format!("{});\n", self.indent(depth)));
+        s
+    }
+
+    /// port 節（`depth` は `port` キーワードの段）
+    fn port_clause(&self, ports: &[PortDef], depth: usize) -> String {
+        let mut s = String::new();
+        if ports.is_empty() {
+            return s;
+        }
+        let width = if self.style.align_colons {
+            ports.iter().map(|p| p.name.len()).max().unwrap_or(0)
+        } else {
+            0
+        };
+        s.push_str(&format!("{}{} (\n", self.indent(depth), self.kw("port")));
+        for (i, port) in ports.iter().enumerate() {
+            let sep = if i + 1 < ports.len() { ";" } else { "" };
+            s.push_str(&format!(
+                "{}{:width$} : {} {}{}\n",
+                self.indent(depth + 1),
+                port.name,
+                self.direction(&port.direction),
+                self.vhdl_type(&port.vhdl_type),
+                sep,
+                width = width
+            ));
+        }
+        s.push_str(&format!("{});\n", self.indent(depth)));
+        s
+    }
+
+    /// エンティティ宣言
+    pub fn entity(&self, entity: &EntityDef) -> String {
+        let mut s = format!("{} {} {}\n", self.kw("entity"), entity.name, self.kw("is"));
+        s.push_str(&self.generic_clause(&entity.generics, 1));
+        s.push_str(&self.port_clause(&entity.ports, 1));
+        s.push_str(&format!(
+            "{} {} {};\n",
+            self.kw("end"),
+            self.kw("entity"),
+            entity.name
+        ));
+        s
+    }
+
+    /// コンポーネント宣言（`depth` は `component` キーワードの段）
+    pub fn component(&self, entity: &EntityDef, depth: usize) -> String {
+        let mut s = format!(
+            "{}{} {} {}\n",
+            self.indent(depth),
+            self.kw("component"),
+            entity.name,
+            self.kw("is")
+        );
+        s.push_str(&self.generic_clause(&entity.generics, depth + 1));
+        s.push_str(&self.port_clause(&entity.ports, depth + 1));
+        s.push_str(&format!(
+            "{}{} {} {};\n",
+            self.indent(depth),
+            self.kw("end"),
+            self.kw("component"),
+            entity.name
+        ));
+        s
+    }
+
+    /// generic map・port map の関連付けの並び（`depth` は `map` のキーワードの段）
+    fn association_list(
+        &self,
+        keyword: &str,
+        associations: &[AssociationDef],
+        depth: usize,
+    ) -> String {
+        let mut s = format!("{}{} (\n", self.indent(depth), self.kw(keyword));
+        for (i, association) in associations.iter().enumerate() {
+            s.push_str(&self.indent(depth + 1));
+            if let Some(formal) = &association.formal {
+                s.push_str(&format!("{} => ", formal));
+            }
+            s.push_str(&association.actual);
+            s.push_str(if i + 1 < associations.len() {
+                ",\n"
+            } else {
+                "\n"
+            });
+        }
+        s.push_str(&format!("{})", self.indent(depth)));
+        s
+    }
+
+    /// インスタンス化文（`depth` はラベルの段）
+    pub fn instance(&self, instance: &InstanceDef, depth: usize) -> String {
+        let unit = match &instance.unit {
+            InstantiatedUnit::Component(name) => name.clone(),
+            InstantiatedUnit::Entity {
+                library,
+                name,
+                architecture,
+            } => {
+                let mut unit = format!("{} ", self.kw("entity"));
+                if let Some(library) = library {
+                    unit.push_str(&format!("{}.", library));
+                }
+                unit.push_str(name);
+                if let Some(architecture) = architecture {
+                    unit.push_str(&format!("({})", architecture));
+                }
+                unit
+            }
+        };
+        let mut s = format!("{}{} : {}", self.indent(depth), instance.label, unit);
+        for (keyword, associations) in [
+            ("generic map", &instance.generic_map),
+            ("port map", &instance.port_map),
+        ] {
+            if !associations.is_empty() {
+                s.push('\n');
+                s.push_str(&self.association_list(keyword, associations, depth + 1));
+            }
+        }
+        s.push_str(";\n");
+        s
+    }
+
+    /// アーキテクチャ（本体は解析結果の文だけ）
+    pub fn architecture(&self, arch: &ArchitectureDef) -> String {
+        self.architecture_with_body(arch, &[])
+    }
+
+    /// アーキテクチャ（解析結果の文の後ろに本体の行を追加、空文字列は空行）
+    pub fn architecture_with_body(&self, arch: &ArchitectureDef, body: &[String]) -> String {
+        self.architecture_unit(arch, &[], body)
+    }
+
+    /// アーキテクチャ（`components` は宣言部に書くコンポーネント宣言）
+    fn architecture_unit(
+        &self,
+        arch: &ArchitectureDef,
+        components: &[&EntityDef],
+        body: &[String],
+    ) -> String {
+        let mut s = format!(
+            "{} {} {} {} {}\n",
+            self.kw("architecture"),
+            arch.name,
+            self.kw("of"),
+            arch.entity_name,
+            self.kw("is")
+        );
+        s.push_str(&self.declarative_part(
+            Declarations {
+                types: &arch.types,
+                constants: &arch.constants,
+                signals: &arch.signals,
+                subprograms: &arch.subprograms,
+                ..Default::default()
+            },
+            1,
+        ));
+        for entity in components {
+            s.push_str(&self.component(entity, 1));
+        }
+        s.push_str(&format!("{}\n", self.kw("begin")));
+        // インスタンス化文とプロセス文はソース上の位置の順
+        let mut statements: Vec<(usize, String)> = arch
+            .instances
+            .iter()
+            .map(|i| (i.span.start, self.instance(i, 1)))
+            .chain(
+                arch.processes
+                    .iter()
+                    .map(|p| (p.span.start, self.process(p, 1))),
+            )
+            .collect();
+        statements.sort_by_key(|(start, _)| *start);
+        for (_, statement) in statements {
+            s.push_str(&statement);
+        }
+        for line in body {
+            if !line.is_empty() {
+                s.push_str(&self.indent(1));
+                s.push_str(line);
+            }
+            s.push('\n');
+        }
+        s.push_str(&format!(
+            "{} {} {};\n",
+            self.kw("end"),
+            self.kw("architecture"),
+            arch.name
+        ));
+        s
+    }
+
+    /// 宣言部（`depth` は宣言の段）
+    ///
+    /// 解析結果は宣言を種類ごとに持つので、ソース上の位置の順に並べ直します。位置を持たない
+    /// 宣言は型・定数・信号・変数・サブプログラムの順で、信号の範囲や初期値が定数を参照できます。
+    fn declarative_part(&self, declarations: Declarations<'_>, depth: usize) -> String {
+        let objects: Vec<_> = declarations
+            .constants
+            .iter()
+            .map(|c| (c.span, "constant", &c.name, &c.vhdl_type, c.value.as_ref()))
+            .chain(declarations.signals.iter().map(|sig| {
+                (
+                    sig.span,
+                    "signal",
+                    &sig.name,
+                    &sig.vhdl_type,
+                    sig.default_value.as_ref(),
+                )
+            }))
+            .chain(declarations.variables.iter().map(|v| {
+                (
+                    v.span,
+                    "variable",
+                    &v.name,
+                    &v.vhdl_type,
+                    v.default_value.as_ref(),
+                )
+            }))
+            .collect();
+        let width = if self.style.align_colons {
+            objects
+                .iter()
+                .map(|(_, keyword, name, ..)| keyword.len() + 1 + name.len())
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let mut items: Vec<(usize, String)> = declarations
+            .types
+            .iter()
+            .map(|t| (t.span().start, self.type_decl(t, depth)))
+            .collect();
+        for (span, keyword, name, vhdl_type, value) in objects {
+            let mut s = format!(
+                "{}{:width$} : {}",
+                self.indent(depth),
+                format!("{} {}", self.kw(keyword), name),
+                self.vhdl_type(vhdl_type),
+                width = width
+            );
+            if let Some(v) = value {
+                s.push_str(&format!(" := {}", v));
+            }
+            s.push_str(";\n");
+            items.push((span.start, s));
+        }
+        for decl in declarations.subprograms {
+            items.push((decl.span.start, self.subprogram(decl, depth)));
+        }
+        items.sort_by_key(|(start, _)| *start);
+        items.into_iter().map(|(_, s)| s).collect()
+    }
+
+    /// 型・サブタイプの宣言（`depth` は `type` キーワードの段）
+    pub fn type_decl(&self, type_def: &TypeDef, depth: usize) -> String {
+        let definition = match type_def {
+            TypeDef::Subtype { vhdl_type, .. } => self.vhdl_type(vhdl_type),
+            TypeDef::Enum { literals, .. } => format!("({})", literals.join(", ")),
+            TypeDef::Array {
+                index_range,
+                index_type,
+                element,
+                ..
+            } => {
+                let index = match (index_range, index_type) {
+                    (Some(range), _) => range.to_string(),
+                    (None, index_type) => format!(
+                        "{} {} <>",
+                        index_type.as_deref().unwrap_or("natural"),
+                        self.kw("range")
+                    ),
+                };
+                format!(
+                    "{} ({}) {} {}",
+                    self.kw("array"),
+                    index,
+                    self.kw("of"),
+                    self.vhdl_type(element)
+                )
+            }
+            TypeDef::Other { definition, .. } => definition.clone(),
+        };
+        let keyword = match type_def {
+            TypeDef::Subtype { .. } => "subtype",
+            _ => "type",
+        };
+        let mut s = format!(
+            "{}{} {}",
+            self.indent(depth),
+            self.kw(keyword),
+            type_def.name()
+        );
+        // 不完全型の宣言（`type cell;`）は定義が空
+        if !definition.is_empty() {
+            s.push_str(&format!(" {} {}", self.kw("is"), definition));
+        }
+        s.push_str(";\n");
+        s
+    }
+
+    /// 関数・手続きの宣言と本体（`depth` は `function` / `procedure` キーワードの段）
+    pub fn subprogram(&self, decl: &SubprogramDecl, depth: usize) -> String {
+        let mut s = self.indent(depth);
+        if decl.impure {
+            s.push_str(&self.kw("impure"));
+            s.push(' ');
+        }
+        s.push_str(&format!("{} {}", self.kw(decl.kind.as_str()), decl.name));
+        if !decl.signature.is_empty() {
+            s.push(' ');
+            s.push_str(&decl.signature);
+        }
+        let Some(body) = &decl.body else {
+            s.push_str(";\n");
+            return s;
+        };
+        s.push_str(&format!(" {}\n", self.kw("is")));
+        s.push_str(&self.declarative_part(
+            Declarations {
+                types: &decl.types,
+                constants: &decl.constants,
+                variables: &decl.variables,
+                subprograms: &decl.subprograms,
+                ..Default::default()
+            },
+            depth + 1,
+        ));
+        s.push_str(&format!("{}{}\n", self.indent(depth), self.kw("begin")));
+        s.push_str(&self.statements(body, depth + 1));
+        s.push_str(&format!(
+            "{}{} {} {};\n",
+            self.indent(depth),
+            self.kw("end"),
+            self.kw(decl.kind.as_str()),
+            decl.name
+        ));
+        s
+    }
+
+    /// プロセス文（`depth` はラベルの段）
+    pub fn process(&self, process: &ProcessDef, depth: usize) -> String {
+        let mut s = self.indent(depth);
+        if let Some(label) = &process.label {
+            s.push_str(&format!("{} : ", label));
+        }
+        s.push_str(&self.kw("process"));
+        if !process.sensitivity.is_empty() {
+            s.push_str(&format!(" ({})", process.sensitivity.join(", ")));
+        }
+        s.push('\n');
+        s.push_str(&self.declarative_part(
+            Declarations {
+                types: &process.types,
+                constants: &process.constants,
+                variables: &process.variables,
+                subprograms: &process.subprograms,
+                ..Default::default()
+            },
+            depth + 1,
+        ));
+        s.push_str(&format!("{}{}\n", self.indent(depth), self.kw("begin")));
+        s.push_str(&self.statements(&process.body, depth + 1));
+        s.push_str(&format!(
+            "{}{} {}",
+            self.indent(depth),
+            self.kw("end"),
+            self.kw("process")
+        ));
+        if let Some(label) = &process.label {
+            s.push_str(&format!(" {}", label));
+        }
+        s.push_str(";\n");
+        s
+    }
+
+    /// 順次文の並び（`depth` は文の段）
+    pub fn statements(&self, statements: &[Statement], depth: usize) -> String {
+        statements
+            .iter()
+            .map(|statement| self.statement(statement, depth))
+            .collect()
+    }
+
+    fn statement(&self, statement: &Statement, depth: usize) -> String {
+        let indent = self.indent(depth);
+        match statement {
+            Statement::SignalAssign {
+                target,
+                value,
+                after,
+                ..
+            } => match after {
+                Some(delay) => format!(
+                    "{}{} <= {} {} {};\n",
+                    indent,
+                    target,
+                    value,
+                    self.kw("after"),
+                    delay
+                ),
+                None => format!("{}{} <= {};\n", indent, target, value),
+            },
+            Statement::VariableAssign { target, value, .. } => {
+                format!("{}{} := {};\n", indent, target, value)
+            }
+            Statement::If {
+                branches,
+                else_body,
+                ..
+            } => {
+                let mut s = String::new();
+                for (i, (condition, body)) in branches.iter().enumerate() {
+                    let keyword = if i == 0 { "if" } else { "elsif" };
+                    s.push_str(&format!(
+                        "{}{} {} {}\n",
+                        indent,
+                        self.kw(keyword),
+                        condition,
+                        self.kw("then")
+                    ));
+                    s.push_str(&self.statements(body, depth + 1));
+                }
+                if !else_body.is_empty() {
+                    s.push_str(&format!("{}{}\n", indent, self.kw("else")));
+                    s.push_str(&self.statements(else_body, depth + 1));
+                }
+                s.push_str(&format!(
+                    "{}{} {};\n",
+                    indent,
+                    self.kw("end"),
+                    self.kw("if")
+                ));
+                s
+            }
+            Statement::Case { selector, arms, .. } => {
+                let mut s = format!(
+                    "{}{} {} {}\n",
+                    indent,
+                    self.kw("case"),
+                    selector,
+                    self.kw("is")
+                );
+                for (choices, body) in arms {
+                    let choices: Vec<String> = choices.iter().map(|c| c.to_string()).collect();
+                    s.push_str(&format!(
+                        "{}{} {} =>\n",
+                        self.indent(depth + 1),
+                        self.kw("when"),
+                        choices.join(" | ")
+                    ));
+                    s.push_str(&self.statements(body, depth + 2));
+                }
+                s.push_str(&format!(
+                    "{}{} {};\n",
+                    indent,
+                    self.kw("end"),
+                    self.kw("case")
+                ));
+                s
+            }
+            Statement::Loop {
+                label,
+                scheme,
+                body,
+                ..
+            } => {
+                let mut s = indent.clone();
+                if let Some(label) = label {
+                    s.push_str(&format!("{} : ", label));
+                }
+                match scheme {
+                    LoopScheme::Forever => {}
+                    LoopScheme::While(condition) => {
+                        s.push_str(&format!("{} {} ", self.kw("while"), condition));
+                    }
+                    LoopScheme::For { parameter, range } => {
+                        s.push_str(&format!(
+                            "{} {} {} {} ",
+                            self.kw("for"),
+                            parameter,
+                            self.kw("in"),
+                            range
+                        ));
+                    }
+                }
+                s.push_str(&format!("{}\n", self.kw("loop")));
+                s.push_str(&self.statements(body, depth + 1));
+                s.push_str(&format!("{}{} {}", indent, self.kw("end"), self.kw("loop")));
+                if let Some(label) = label {
+                    s.push_str(&format!(" {}", label));
+                }
+                s.push_str(";\n");
+                s
+            }
+            Statement::Wait {
+                on, until, timeout, ..
+            } => {
+                let mut s = format!("{}{}", indent, self.kw("wait"));
+                if !on.is_empty() {
+                    let names: Vec<String> = on.iter().map(|n| n.to_string()).collect();
+                    s.push_str(&format!(" {} {}", self.kw("on"), names.join(", ")));
+                }
+                if let Some(condition) = until {
+                    s.push_str(&format!(" {} {}", self.kw("until"), condition));
+                }
+                if let Some(time) = timeout {
+                    s.push_str(&format!(" {} {}", self.kw("for"), time));
+                }
+                s.push_str(";\n");
+                s
+            }
+            Statement::Null { .. } => format!("{}{};\n", indent, self.kw("null")),
+            Statement::Other { text, .. } => format!("{}{};\n", indent, text),
+        }
+    }
+
+    /// パッケージ宣言
+    pub fn package(&self, package: &PackageDef) -> String {
+        self.package_unit(package, false)
+    }
+
+    /// パッケージ本体
+    pub fn package_body(&self, package: &PackageDef) -> String {
+        self.package_unit(package, true)
+    }
+
+    fn package_unit(&self, package: &PackageDef, body: bool) -> String {
+        let keyword = if body {
+            format!("{} {}", self.kw("package"), self.kw("body"))
+        } else {
+            self.kw("package")
+        };
+        let mut s = format!("{} {} {}\n", keyword, package.name, self.kw("is"));
+        s.push_str(&self.declarative_part(
+            Declarations {
+                types: &package.types,
+                constants: &package.constants,
+                subprograms: &package.subprograms,
+                ..Default::default()
+            },
+            1,
+        ));
+        s.push_str(&format!(
+            "{} {} {};\n",
+            self.kw("end"),
+            keyword,
+            package.name
+        ));
+        s
+    }
+
+    /// 解析結果全体（パッケージ、パッケージ本体、エンティティ、アーキテクチャの順）
+    ///
+    /// 解析はアーキテクチャのコンポーネント宣言を読み飛ばすので、同じ解析結果にある
+    /// エンティティをコンポーネントとしてインスタンス化していれば宣言を書き直します。
+    pub fn design_file(&self, result: &AnalyzeResult) -> String {
+        let mut units = Vec::new();
+        for package in &result.packages {
+            units.push(self.package(package));
+        }
+        for package in &result.package_bodies {
+            units.push(self.package_body(package));
+        }
+        for entity in &result.entities {
+            units.push(self.entity(entity));
+        }
+        for arch in &result.architectures {
+            let mut components: Vec<&EntityDef> = Vec::new();
+            for instance in &arch.instances {
+                let InstantiatedUnit::Component(name) = &instance.unit else {
+                    continue;
+                };
+                if let Some(entity) = result
+                    .entities
+                    .iter()
+                    .find(|e| same_identifier(&e.name, name))
+                    && !components.iter().any(|c| std::ptr::eq(*c, entity))
+                {
+                    components.push(entity);
+                }
+            }
+            units.push(self.architecture_unit(arch, &components, &[]));
+        }
+        units.join("\n")
+    }
+}
+
+/// 宣言部に書く宣言（種類ごと）
+#[derive(Default)]
+struct Declarations<'a> {
+    types: &'a [TypeDef],
+    constants: &'a [ConstantDef],
+    signals: &'a [SignalDef],
+    variables: &'a [VariableDef],
+    subprograms: &'a [SubprogramDecl],
+}
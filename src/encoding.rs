@@ -0,0 +1,224 @@
+//! ソースの文字コード
+//!
+//! 古い VHDL は Shift_JIS や Latin-1 で書かれていることがあるため、ファイルを読み込む際に
+//! UTF-8 に変換します。解析はすべて変換後のテキストに対して行うので、位置（[`Span`]）も
+//! 変換後のテキストのバイト位置です。
+//!
+//! ```
+//! use vig::encoding::{Encoding, decode};
+//!
+//! // Shift_JIS の「ｶｳﾝﾀ」と「日本」
+//! let bytes = b"-- \xb6\xb3\xdd\xc0 \x93\xfa\x96\x7b\n";
+//! assert_eq!(Encoding::detect(bytes), Encoding::ShiftJis);
+//! assert_eq!(decode(bytes, Encoding::ShiftJis).unwrap(), "-- ｶｳﾝﾀ 日本\n");
+//! ```
+
+use crate::lexer::Span;
+
+/// 文字コードのエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodingError {
+    pub message: String,
+    /// 位置（[`decode`] は変換前のバイト列、[`encode`] は文字列でのもの）
+    pub span: Span,
+}
+
+impl EncodingError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// 対応する文字コード
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    /// Shift_JIS（Windows の拡張を含む CP932）
+    ShiftJis,
+    /// ISO-8859-1
+    Latin1,
+}
+
+impl Encoding {
+    /// バイト列から推定する
+    ///
+    /// UTF-8 として正しければ UTF-8、Shift_JIS として正しく2バイト文字を含めば
+    /// Shift_JIS、どちらでもなければ Latin-1（どのバイト列も変換できる）です。
+    pub fn detect(bytes: &[u8]) -> Self {
+        if std::str::from_utf8(bytes).is_ok() {
+            return Encoding::Utf8;
+        }
+        let has_double_byte = bytes
+            .iter()
+            .any(|&b| matches!(b, 0x81..=0x9f | 0xe0..=0xfc));
+        if has_double_byte
+            && encoding_rs::SHIFT_JIS
+                .decode_without_bom_handling_and_without_replacement(bytes)
+                .is_some()
+        {
+            Encoding::ShiftJis
+        } else {
+            Encoding::Latin1
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::ShiftJis => "Shift_JIS",
+            Encoding::Latin1 => "ISO-8859-1",
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = String;
+
+    /// `utf-8` `shift_jis` `latin1` とその別名（大文字・小文字、`-` と `_` を区別しない）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "shift-jis" | "sjis" | "cp932" | "windows-31j" => Ok(Encoding::ShiftJis),
+            "latin1" | "latin-1" | "iso-8859-1" | "iso8859-1" => Ok(Encoding::Latin1),
+            _ => Err(format!("unknown encoding '{}'", s)),
+        }
+    }
+}
+
+/// バイト列を文字コードに従って UTF-8 の文字列にする
+///
+/// 変換できないバイト列はエラーです（位置は変換前のバイト列でのもの）。
+pub fn decode(bytes: &[u8], encoding: Encoding) -> Result<String, EncodingError> {
+    match encoding {
+        Encoding::Utf8 => String::from_utf8(bytes.to_vec()).map_err(|e| {
+            let start = e.utf8_error().valid_up_to();
+            let len = e.utf8_error().error_len().unwrap_or(bytes.len() - start);
+            EncodingError::new("invalid UTF-8 sequence", Span::new(start, start + len))
+        }),
+        Encoding::Latin1 => Ok(bytes.iter().map(|&b| char::from(b)).collect()),
+        Encoding::ShiftJis => {
+            let mut decoder = encoding_rs::SHIFT_JIS.new_decoder_without_bom_handling();
+            let mut text = String::with_capacity(bytes.len() * 3 / 2);
+            let mut read = 0;
+            loop {
+                let (result, consumed) =
+                    decoder.decode_to_string_without_replacement(&bytes[read..], &mut text, true);
+                read += consumed;
+                match result {
+                    encoding_rs::DecoderResult::InputEmpty => return Ok(text),
+                    encoding_rs::DecoderResult::OutputFull => {
+                        text.reserve(bytes.len() - read + 16);
+                    }
+                    encoding_rs::DecoderResult::Malformed(bad, after) => {
+                        let end = read - usize::from(after);
+                        let start = end - usize::from(bad);
+                        return Err(EncodingError::new(
+                            "invalid Shift_JIS sequence",
+                            Span::new(start, end),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// バイト列を文字コードに従って UTF-8 の文字列にする（変換できないバイト列は U+FFFD に置き換える）
+///
+/// 文字コードの分からないベンダー提供のファイルやバイナリが混じった入力でも、読める部分だけを
+/// 解析するためのものです。置き換えた分だけ位置がずれるため、位置は変換後の文字列でのものです。
+///
+/// ```
+/// use vig::encoding::{Encoding, decode_lossy};
+///
+/// assert_eq!(decode_lossy(b"-- a\xff\n", Encoding::Utf8), "-- a\u{fffd}\n");
+/// assert_eq!(decode_lossy(b"-- \x83\n", Encoding::ShiftJis), "-- \u{fffd}\n");
+/// ```
+pub fn decode_lossy(bytes: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        Encoding::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+        Encoding::ShiftJis => encoding_rs::SHIFT_JIS
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned(),
+    }
+}
+
+/// 文字列を文字コードに従ってバイト列にする（読み込んだファイルを同じ文字コードで書き戻すため）
+///
+/// その文字コードで表せない文字はエラーです（位置は文字列でのもの）。
+pub fn encode(text: &str, encoding: Encoding) -> Result<Vec<u8>, EncodingError> {
+    let unmappable = |offset: usize, c: char| {
+        EncodingError::new(
+            format!("character '{}' cannot be encoded in {}", c, encoding),
+            Span::new(offset, offset + c.len_utf8()),
+        )
+    };
+    match encoding {
+        Encoding::Utf8 => Ok(text.as_bytes().to_vec()),
+        Encoding::Latin1 => text
+            .char_indices()
+            .map(|(i, c)| u8::try_from(c).map_err(|_| unmappable(i, c)))
+            .collect(),
+        Encoding::ShiftJis => {
+            let (bytes, _, had_errors) = encoding_rs::SHIFT_JIS.encode(text);
+            if !had_errors {
+                return Ok(bytes.into_owned());
+            }
+            // 表せない最初の文字を探す
+            let mut buf = [0u8; 4];
+            let (i, c) = text
+                .char_indices()
+                .find(|(_, c)| encoding_rs::SHIFT_JIS.encode(c.encode_utf8(&mut buf)).2)
+                .unwrap_or((0, '\u{fffd}'));
+            Err(unmappable(i, c))
+        }
+    }
+}
+
+/// ファイルを読み込んで UTF-8 の文字列にする（`encoding` が None なら推定する）
+pub fn read_source(
+    path: impl AsRef<std::path::Path>,
+    encoding: Option<Encoding>,
+) -> crate::Result<String> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| crate::Error::io(path, e))?;
+    let encoding = encoding.unwrap_or_else(|| Encoding::detect(&bytes));
+    Ok(decode(&bytes, encoding)?)
+}
+
+/// ファイルを読み込んで UTF-8 の文字列にする（変換できないバイト列は置き換える、[`decode_lossy`]）
+///
+/// 失敗するのはファイルを読めない場合だけです。
+pub fn read_source_lossy(
+    path: impl AsRef<std::path::Path>,
+    encoding: Option<Encoding>,
+) -> crate::Result<String> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| crate::Error::io(path, e))?;
+    let encoding = encoding.unwrap_or_else(|| Encoding::detect(&bytes));
+    Ok(decode_lossy(&bytes, encoding))
+}
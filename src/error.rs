@@ -0,0 +1,265 @@
+use std::path::PathBuf;
+
+use crate::analyzer::AnalyzeError;
+use crate::assertion::AssertionError;
+use crate::encoding::EncodingError;
+use crate::interface::InterfaceError;
+use crate::interp::InterpError;
+use crate::ipxact::ImportError;
+use crate::lexer::{LexError, Span};
+use crate::library::LibraryError;
+use crate::lint::LintError;
+use crate::profile::ProfileError;
+use crate::project::ProjectError;
+use crate::regmap::RegmapError;
+use crate::rewrite::RewriteError;
+use crate::sweep::SweepError;
+use crate::testbench::TestbenchError;
+use crate::vectors::VectorError;
+use crate::verilog::VerilogError;
+
+/// ライブラリ公開APIの統一エラー型
+///
+/// モジュールごとのエラー型をまとめ、`source()` で元のエラーを辿れるようにしたものです。
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// 字句解析エラー
+    Lex(LexError),
+    /// 意味解析エラー
+    Analyze(AnalyzeError),
+    /// IP-XACTの取り込みエラー
+    Import(ImportError),
+    /// インターフェース記述の読み込みエラー
+    Interface(InterfaceError),
+    /// レジスタマップ記述の読み込みエラー
+    Regmap(RegmapError),
+    /// 真理値表の読み込みエラー
+    Vectors(VectorError),
+    /// インタプリタの解釈・評価エラー
+    Interp(InterpError),
+    /// 既存テストベンチの読み取りエラー
+    Testbench(TestbenchError),
+    /// アサーション記述のエラー
+    Assertion(AssertionError),
+    /// リント設定のエラー
+    Lint(LintError),
+    /// ソースの書き換えのエラー
+    Rewrite(RewriteError),
+    /// ジェネリックの組の設定エラー
+    Sweep(SweepError),
+    /// エンティティごとの設定のエラー
+    Profile(ProfileError),
+    /// Verilog のモジュールヘッダの読み込みエラー
+    Verilog(VerilogError),
+    /// 複数ファイルの設計の解析エラー
+    Project(ProjectError),
+    /// ソースの文字コードの変換エラー
+    Encoding(EncodingError),
+    /// use 節のライブラリの解決エラー
+    Library(LibraryError),
+    /// ファイル入出力エラー
+    Io {
+        path: Option<PathBuf>,
+        source: std::io::Error,
+    },
+}
+
+/// `vig::Error` を用いたResult型
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// ファイルパス付きのIOエラーを作成
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Error::Io {
+            path: Some(path.into()),
+            source,
+        }
+    }
+
+    /// ソース上の位置（IOエラーの場合はNone）
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::Lex(e) => Some(e.span),
+            Error::Analyze(e) => Some(e.span),
+            Error::Import(e) => Some(e.span),
+            Error::Interface(e) => Some(e.span),
+            Error::Regmap(e) => Some(e.span),
+            Error::Vectors(e) => Some(e.span),
+            Error::Interp(e) => Some(e.span),
+            Error::Testbench(e) => Some(e.span),
+            Error::Assertion(e) => Some(e.span),
+            Error::Lint(e) => Some(e.span),
+            Error::Rewrite(e) => Some(e.span),
+            Error::Sweep(e) => Some(e.span),
+            Error::Profile(e) => Some(e.span),
+            Error::Verilog(e) => Some(e.span),
+            Error::Project(e) => Some(e.span),
+            Error::Encoding(e) => Some(e.span),
+            Error::Library(e) => Some(e.span),
+            Error::Io { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Lex(e) => write!(f, "lex error: {}", e),
+            Error::Analyze(e) => write!(f, "analyze error: {}", e),
+            Error::Import(e) => write!(f, "IP-XACT import error: {}", e),
+            Error::Interface(e) => write!(f, "interface error: {}", e),
+            Error::Regmap(e) => write!(f, "register map error: {}", e),
+            Error::Vectors(e) => write!(f, "truth table error: {}", e),
+            Error::Interp(e) => write!(f, "interpreter error: {}", e),
+            Error::Testbench(e) => write!(f, "testbench error: {}", e),
+            Error::Assertion(e) => write!(f, "assertion error: {}", e),
+            Error::Lint(e) => write!(f, "lint config error: {}", e),
+            Error::Rewrite(e) => write!(f, "rewrite error: {}", e),
+            Error::Sweep(e) => write!(f, "sweep config error: {}", e),
+            Error::Profile(e) => write!(f, "entity profile error: {}", e),
+            Error::Verilog(e) => write!(f, "Verilog error: {}", e),
+            Error::Project(e) => write!(f, "project error: {}", e),
+            Error::Encoding(e) => write!(f, "encoding error: {}", e),
+            Error::Library(e) => write!(f, "library error: {}", e),
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "failed to access '{}': {}", path.display(), source),
+            Error::Io { path: None, source } => write!(f, "I/O error: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Lex(e) => Some(e),
+            Error::Analyze(e) => Some(e),
+            Error::Import(e) => Some(e),
+            Error::Interface(e) => Some(e),
+            Error::Regmap(e) => Some(e),
+            Error::Vectors(e) => Some(e),
+            Error::Interp(e) => Some(e),
+            Error::Testbench(e) => Some(e),
+            Error::Assertion(e) => Some(e),
+            Error::Lint(e) => Some(e),
+            Error::Rewrite(e) => Some(e),
+            Error::Sweep(e) => Some(e),
+            Error::Profile(e) => Some(e),
+            Error::Verilog(e) => Some(e),
+            Error::Project(e) => Some(e),
+            Error::Encoding(e) => Some(e),
+            Error::Library(e) => Some(e),
+            Error::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<LexError> for Error {
+    fn from(err: LexError) -> Self {
+        Error::Lex(err)
+    }
+}
+
+impl From<AnalyzeError> for Error {
+    fn from(err: AnalyzeError) -> Self {
+        Error::Analyze(err)
+    }
+}
+
+impl From<ImportError> for Error {
+    fn from(err: ImportError) -> Self {
+        Error::Import(err)
+    }
+}
+
+impl From<InterfaceError> for Error {
+    fn from(err: InterfaceError) -> Self {
+        Error::Interface(err)
+    }
+}
+
+impl From<RegmapError> for Error {
+    fn from(err: RegmapError) -> Self {
+        Error::Regmap(err)
+    }
+}
+
+impl From<VectorError> for Error {
+    fn from(err: VectorError) -> Self {
+        Error::Vectors(err)
+    }
+}
+
+impl From<InterpError> for Error {
+    fn from(err: InterpError) -> Self {
+        Error::Interp(err)
+    }
+}
+
+impl From<TestbenchError> for Error {
+    fn from(err: TestbenchError) -> Self {
+        Error::Testbench(err)
+    }
+}
+
+impl From<AssertionError> for Error {
+    fn from(err: AssertionError) -> Self {
+        Error::Assertion(err)
+    }
+}
+
+impl From<LintError> for Error {
+    fn from(err: LintError) -> Self {
+        Error::Lint(err)
+    }
+}
+
+impl From<RewriteError> for Error {
+    fn from(err: RewriteError) -> Self {
+        Error::Rewrite(err)
+    }
+}
+
+impl From<SweepError> for Error {
+    fn from(err: SweepError) -> Self {
+        Error::Sweep(err)
+    }
+}
+
+impl From<ProfileError> for Error {
+    fn from(err: ProfileError) -> Self {
+        Error::Profile(err)
+    }
+}
+
+impl From<VerilogError> for Error {
+    fn from(err: VerilogError) -> Self {
+        Error::Verilog(err)
+    }
+}
+
+impl From<ProjectError> for Error {
+    fn from(err: ProjectError) -> Self {
+        Error::Project(err)
+    }
+}
+
+impl From<EncodingError> for Error {
+    fn from(err: EncodingError) -> Self {
+        Error::Encoding(err)
+    }
+}
+
+impl From<LibraryError> for Error {
+    fn from(err: LibraryError) -> Self {
+        Error::Library(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(source: std::io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
@@ -0,0 +1,435 @@
+//! VHDLの式の構文木
+//!
+//! 既定値・代入・条件・範囲の境界などの式を [`Expr`] の木で表します。解析は
+//! [`crate::analyzer`] の演算子順位法の式パーサが行い、ベクタの範囲の境界もこのパーサで
+//! 読んでから [`crate::analyzer::BoundExpr`] に変換します。
+//!
+//! [`Expr`] の表示（`Display`）は必要な箇所だけ括弧を付けた VHDL の表記で、読み直すと同じ木に
+//! なります。
+//!
+//! ```
+//! use vig::expr::{BinaryOp, Expr};
+//!
+//! let expr: Expr = "a + b * 2".parse().unwrap();
+//! let Expr::Binary(_, op, rhs) = &expr else {
+//!     panic!("expected a binary operation");
+//! };
+//! assert_eq!(*op, BinaryOp::Add);
+//! assert_eq!(rhs.to_string(), "b * 2");
+//! assert_eq!(expr.evaluate(&|name| (name == "a").then_some(1).or(Some(3))), Some(7));
+//! assert_eq!("(a + b) * 2".parse::<Expr>().unwrap().to_string(), "(a + b) * 2");
+//! ```
+
+use crate::analyzer::{AnalyzeError, parse_expr_str};
+use crate::lexer::{LitKind, TokenKind};
+use crate::literal;
+
+/// 式
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// リテラル（書いたままの表記。数値リテラルは符号を含まない）
+    Literal(LitKind, String),
+    /// 物理型のリテラル（`10 ns` の値と単位の表記）
+    Physical(String, String),
+    Name(String),
+    /// 選択名（`rec.field`、`work.pkg.all`）
+    Selected(Box<Expr>, String),
+    /// 属性名（`clk'event`。`t'image(x)` の引数は [`Expr::Apply`] で表す）
+    Attribute(Box<Expr>, String),
+    /// 添字・スライス・関数呼び出し（`a(i)`、`a(7 downto 0)`、`f(x => 1)`。構文では区別しない）
+    Apply(Box<Expr>, Vec<Element>),
+    /// 限定式（`unsigned'(x"0F")`）
+    Qualified(Box<Expr>, Box<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    /// 範囲（`7 downto 0`。スライス・選択肢・for ループに現れる）
+    Range(Box<Expr>, Direction, Box<Expr>),
+    /// 集合体（`(others => '0')`、`(a, b)`）
+    Aggregate(Vec<Element>),
+    /// 選択肢の `others`
+    Others,
+}
+
+/// 集合体・呼び出しの要素（`選択肢 | ... => 値`。位置による要素は選択肢が空）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub choices: Vec<Expr>,
+    pub value: Expr,
+}
+
+/// 範囲の向き
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    To,
+    Downto,
+}
+
+/// 単項演算子
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+    Abs,
+    /// VHDL-2008 の条件演算子 `??`
+    Condition,
+}
+
+/// 二項演算子
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    And,
+    Or,
+    Nand,
+    Nor,
+    Xor,
+    Xnor,
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    MatchEq,
+    MatchNeq,
+    MatchLt,
+    MatchLte,
+    MatchGt,
+    MatchGte,
+    Sll,
+    Srl,
+    Sla,
+    Sra,
+    Rol,
+    Ror,
+    Add,
+    Sub,
+    /// `&`
+    Concat,
+    Mul,
+    Div,
+    Mod,
+    Rem,
+    Pow,
+}
+
+// 結合の強さ（大きいほど強い）
+pub(crate) const LOGICAL: u8 = 1;
+pub(crate) const RELATIONAL: u8 = 2;
+pub(crate) const SHIFT: u8 = 3;
+pub(crate) const ADDING: u8 = 4;
+pub(crate) const MULTIPLYING: u8 = 5;
+pub(crate) const POWER: u8 = 6;
+pub(crate) const PRIMARY: u8 = 7;
+
+impl UnaryOp {
+    /// VHDLの演算子
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnaryOp::Plus => "+",
+            UnaryOp::Minus => "-",
+            UnaryOp::Not => "not",
+            UnaryOp::Abs => "abs",
+            UnaryOp::Condition => "??",
+        }
+    }
+
+    /// 結合の強さ（符号は加減算、`not`・`abs`・`??` は `**` と同じ）
+    pub fn precedence(self) -> u8 {
+        match self {
+            UnaryOp::Plus | UnaryOp::Minus => ADDING,
+            UnaryOp::Not | UnaryOp::Abs | UnaryOp::Condition => POWER,
+        }
+    }
+}
+
+impl BinaryOp {
+    /// VHDLの演算子
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BinaryOp::And => "and",
+            BinaryOp::Or => "or",
+            BinaryOp::Nand => "nand",
+            BinaryOp::Nor => "nor",
+            BinaryOp::Xor => "xor",
+            BinaryOp::Xnor => "xnor",
+            BinaryOp::Eq => "=",
+            BinaryOp::Neq => "/=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Lte => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Gte => ">=",
+            BinaryOp::MatchEq => "?=",
+            BinaryOp::MatchNeq => "?/=",
+            BinaryOp::MatchLt => "?<",
+            BinaryOp::MatchLte => "?<=",
+            BinaryOp::MatchGt => "?>",
+            BinaryOp::MatchGte => "?>=",
+            BinaryOp::Sll => "sll",
+            BinaryOp::Srl => "srl",
+            BinaryOp::Sla => "sla",
+            BinaryOp::Sra => "sra",
+            BinaryOp::Rol => "rol",
+            BinaryOp::Ror => "ror",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Concat => "&",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "mod",
+            BinaryOp::Rem => "rem",
+            BinaryOp::Pow => "**",
+        }
+    }
+
+    /// 結合の強さ（論理 < 関係 < シフト < 加減・連結 < 乗除 < `**`）
+    pub fn precedence(self) -> u8 {
+        use BinaryOp::*;
+        match self {
+            And | Or | Nand | Nor | Xor | Xnor => LOGICAL,
+            Eq | Neq | Lt | Lte | Gt | Gte | MatchEq | MatchNeq | MatchLt | MatchLte | MatchGt
+            | MatchGte => RELATIONAL,
+            Sll | Srl | Sla | Sra | Rol | Ror => SHIFT,
+            Add | Sub | Concat => ADDING,
+            Mul | Div | Mod | Rem => MULTIPLYING,
+            Pow => POWER,
+        }
+    }
+
+    /// 二項演算子のトークンなら演算子を返す
+    pub(crate) fn from_token(kind: &TokenKind) -> Option<BinaryOp> {
+        let op = match *kind {
+            TokenKind::And => BinaryOp::And,
+            TokenKind::Or => BinaryOp::Or,
+            TokenKind::Nand => BinaryOp::Nand,
+            TokenKind::Nor => BinaryOp::Nor,
+            TokenKind::Xor => BinaryOp::Xor,
+            TokenKind::Xnor => BinaryOp::Xnor,
+            TokenKind::Eq => BinaryOp::Eq,
+            TokenKind::Neq => BinaryOp::Neq,
+            TokenKind::Lt => BinaryOp::Lt,
+            TokenKind::Lte => BinaryOp::Lte,
+            TokenKind::Gt => BinaryOp::Gt,
+            TokenKind::Gte => BinaryOp::Gte,
+            TokenKind::MatchEq => BinaryOp::MatchEq,
+            TokenKind::MatchNeq => BinaryOp::MatchNeq,
+            TokenKind::MatchLt => BinaryOp::MatchLt,
+            TokenKind::MatchLte => BinaryOp::MatchLte,
+            TokenKind::MatchGt => BinaryOp::MatchGt,
+            TokenKind::MatchGte => BinaryOp::MatchGte,
+            TokenKind::Sll => BinaryOp::Sll,
+            TokenKind::Srl => BinaryOp::Srl,
+            TokenKind::Sla => BinaryOp::Sla,
+            TokenKind::Sra => BinaryOp::Sra,
+            TokenKind::Rol => BinaryOp::Rol,
+            TokenKind::Ror => BinaryOp::Ror,
+            TokenKind::Plus => BinaryOp::Add,
+            TokenKind::Minus => BinaryOp::Sub,
+            TokenKind::Ampersand => BinaryOp::Concat,
+            TokenKind::Star => BinaryOp::Mul,
+            TokenKind::Slash => BinaryOp::Div,
+            TokenKind::Mod => BinaryOp::Mod,
+            TokenKind::Rem => BinaryOp::Rem,
+            TokenKind::Power => BinaryOp::Pow,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// 同じ演算子を括弧なしで左に連ねられるか
+    ///
+    /// 加減算・乗除算は同じ強さの演算子どうしで連ねられ、論理演算は `nand`・`nor` 以外の
+    /// 同じ演算子だけ連ねられます。関係・シフト・`**` は連ねられません。
+    fn chains_with(self, left: &Expr) -> bool {
+        match left {
+            Expr::Binary(_, op, _) => self.may_follow(*op),
+            _ => matches!(self.precedence(), ADDING | MULTIPLYING),
+        }
+    }
+
+    /// 同じ強さの演算子 `previous` の後に括弧なしで続けられるか
+    pub(crate) fn may_follow(self, previous: BinaryOp) -> bool {
+        match self.precedence() {
+            ADDING | MULTIPLYING => true,
+            LOGICAL => previous == self && !matches!(self, BinaryOp::Nand | BinaryOp::Nor),
+            _ => false,
+        }
+    }
+
+    /// 整数の演算（あふれ・0除算・負の指数・整数以外の演算子はNone）
+    fn apply(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            BinaryOp::Add => a.checked_add(b),
+            BinaryOp::Sub => a.checked_sub(b),
+            BinaryOp::Mul => a.checked_mul(b),
+            BinaryOp::Div => a.checked_div(b),
+            // mod は右辺、rem は左辺の符号になる
+            BinaryOp::Mod => {
+                let r = a.checked_rem(b)?;
+                Some(if r != 0 && (r < 0) != (b < 0) {
+                    r + b
+                } else {
+                    r
+                })
+            }
+            BinaryOp::Rem => a.checked_rem(b),
+            BinaryOp::Pow => a.checked_pow(u32::try_from(b).ok()?),
+            _ => None,
+        }
+    }
+}
+
+impl Expr {
+    /// 名前の式を作る
+    pub fn name(name: impl Into<String>) -> Self {
+        Expr::Name(name.into())
+    }
+
+    /// 整数の式として評価する（名前の値は `value_of` で引く）
+    ///
+    /// 整数リテラル・名前・符号・`abs` と加減乗除・`mod`・`rem`・`**` だけを評価し、
+    /// それ以外を含む式や、あふれ・0除算になる式は None です。
+    pub fn evaluate(&self, value_of: &dyn Fn(&str) -> Option<i64>) -> Option<i64> {
+        match self {
+            Expr::Literal(LitKind::Integer, text) => {
+                i64::try_from(literal::parse_integer(text)?).ok()
+            }
+            Expr::Name(name) => value_of(name),
+            Expr::Unary(op, operand) => {
+                let value = operand.evaluate(value_of)?;
+                match op {
+                    UnaryOp::Plus => Some(value),
+                    UnaryOp::Minus => value.checked_neg(),
+                    UnaryOp::Abs => value.checked_abs(),
+                    _ => None,
+                }
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                op.apply(lhs.evaluate(value_of)?, rhs.evaluate(value_of)?)
+            }
+            _ => None,
+        }
+    }
+
+    /// 名前の根（`a(3).b` なら `a`。名前でない式は None）
+    pub fn base_name(&self) -> Option<&str> {
+        match self {
+            Expr::Name(name) => Some(name),
+            Expr::Selected(prefix, _) | Expr::Attribute(prefix, _) | Expr::Apply(prefix, _) => {
+                prefix.base_name()
+            }
+            _ => None,
+        }
+    }
+
+    /// 結合の強さ（演算でない式は最も強い）
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Unary(op, _) => op.precedence(),
+            Expr::Binary(_, op, _) => op.precedence(),
+            Expr::Range(..) => 0,
+            _ => PRIMARY,
+        }
+    }
+}
+
+impl From<i64> for Expr {
+    /// 整数リテラル（負の数は符号の演算）
+    fn from(value: i64) -> Self {
+        let literal = Expr::Literal(LitKind::Integer, value.unsigned_abs().to_string());
+        if value < 0 {
+            Expr::Unary(UnaryOp::Minus, Box::new(literal))
+        } else {
+            literal
+        }
+    }
+}
+
+impl std::str::FromStr for Expr {
+    type Err = AnalyzeError;
+
+    /// 式を解析する（範囲 `0 to 7` と `others` も読む）
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_expr_str(s)
+    }
+}
+
+/// `parens` なら括弧で囲んで書く
+fn write_operand(f: &mut std::fmt::Formatter<'_>, expr: &Expr, parens: bool) -> std::fmt::Result {
+    if parens {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
+fn write_elements(f: &mut std::fmt::Formatter<'_>, elements: &[Element]) -> std::fmt::Result {
+    f.write_str("(")?;
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        for (j, choice) in element.choices.iter().enumerate() {
+            f.write_str(if j > 0 { " | " } else { "" })?;
+            write!(f, "{}", choice)?;
+        }
+        if !element.choices.is_empty() {
+            f.write_str(" => ")?;
+        }
+        write!(f, "{}", element.value)?;
+    }
+    f.write_str(")")
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Direction::To => "to",
+            Direction::Downto => "downto",
+        })
+    }
+}
+
+impl std::fmt::Display for Expr {
+    /// VHDLの式（必要な箇所だけ括弧を付ける）
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Literal(_, text) | Expr::Name(text) => f.write_str(text),
+            Expr::Physical(value, unit) => write!(f, "{} {}", value, unit),
+            Expr::Selected(prefix, suffix) => write!(f, "{}.{}", prefix, suffix),
+            Expr::Attribute(prefix, attribute) => write!(f, "{}'{}", prefix, attribute),
+            Expr::Apply(prefix, elements) => {
+                write!(f, "{}", prefix)?;
+                write_elements(f, elements)
+            }
+            Expr::Qualified(mark, operand) => match operand.as_ref() {
+                Expr::Aggregate(_) => write!(f, "{}'{}", mark, operand),
+                _ => write!(f, "{}'({})", mark, operand),
+            },
+            Expr::Unary(op, operand) => {
+                let (separator, min) = match op {
+                    // 符号は項（乗除算）に掛かる
+                    UnaryOp::Plus | UnaryOp::Minus => ("", MULTIPLYING),
+                    _ => (" ", PRIMARY),
+                };
+                write!(f, "{}{}", op.as_str(), separator)?;
+                write_operand(f, operand, operand.precedence() < min)
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                let p = op.precedence();
+                let lp = lhs.precedence();
+                write_operand(f, lhs, lp < p || (lp == p && !op.chains_with(lhs)))?;
+                write!(f, " {} ", op.as_str())?;
+                write_operand(f, rhs, rhs.precedence() <= p)
+            }
+            Expr::Range(left, direction, right) => {
+                write!(f, "{} {} {}", left, direction, right)
+            }
+            Expr::Aggregate(elements) => write_elements(f, elements),
+            Expr::Others => f.write_str("others"),
+        }
+    }
+}
@@ -0,0 +1,730 @@
+//! ステートマシンの抽出とテストシナリオ
+//!
+//! 列挙型の信号を選択式にした `case` 文をステートマシンとみなし、状態と遷移（遷移先の代入と
+//! それを囲む `if` の条件）を取り出します。テストベンチ生成では、リセット状態からすべての遷移を
+//! たどる入力系列と、観測した状態・遷移を報告するホワイトボックスのモニタを作ります。
+//!
+//! モニタは VHDL-2008 の外部名で DUT の状態信号を参照するため、状態型がパッケージで
+//! 宣言されている（テストベンチから見える）場合だけ生成します。
+
+use std::collections::VecDeque;
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use crate::generator::{ResetPolarity, type_default_value};
+use crate::lexer::{Lexer, LexerOptions, Span, Token, TokenKind};
+use crate::trace::timed;
+
+/// ステートマシン
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fsm {
+    /// 状態を保持する信号（`case` の選択式）
+    pub state_signal: String,
+    /// 状態の列挙型
+    pub state_type: String,
+    /// 状態型を宣言しているパッケージ（アーキテクチャ内の宣言なら None）
+    pub type_package: Option<String>,
+    pub states: Vec<String>,
+    /// リセット時の状態
+    pub reset_state: String,
+    pub transitions: Vec<Transition>,
+    /// `case` 文の位置
+    pub span: Span,
+}
+
+/// 状態遷移
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub from: String,
+    pub to: String,
+    /// 遷移を囲む条件（すべて成り立つと遷移する。空なら無条件）
+    pub conditions: Vec<String>,
+    /// 遷移先の代入の位置
+    pub span: Span,
+}
+
+/// 列挙型の宣言
+struct EnumType {
+    name: String,
+    literals: Vec<String>,
+    package: Option<String>,
+    /// アーキテクチャ内の宣言ならそのエンティティ名
+    owner: Option<String>,
+}
+
+/// 状態型の信号の宣言
+struct StateSignal {
+    name: String,
+    type_index: usize,
+    init: Option<String>,
+}
+
+/// 宣言を読み取っている設計単位
+#[derive(Clone, PartialEq)]
+enum Scope {
+    None,
+    Package(String),
+    Architecture(String),
+}
+
+/// エンティティのアーキテクチャからステートマシンを取り出す
+pub fn extract_fsms(source: &str, entity: &str) -> Vec<Fsm> {
+    let _timed = timed!("extract_fsms", entity);
+    let tokens: Vec<Token> = Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .filter_map(|r| r.ok())
+        .collect();
+    let is_word = |t: &Token, word: &str| t.text.eq_ignore_ascii_case(word);
+
+    // 設計単位ごとの範囲と、列挙型・状態信号の宣言を集める
+    let mut scopes = Vec::with_capacity(tokens.len());
+    let mut scope = Scope::None;
+    let mut types: Vec<EnumType> = Vec::new();
+    let mut signals: Vec<StateSignal> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let next = |n: usize| tokens.get(i + n);
+        let prev_is_end = i > 0 && tokens[i - 1].kind == TokenKind::End;
+        scopes.push(scope.clone());
+        match token.kind {
+            TokenKind::Entity if !prev_is_end => scope = Scope::None,
+            TokenKind::Architecture if next(2).is_some_and(|t| t.kind == TokenKind::Of) => {
+                scope =
+                    Scope::Architecture(next(3).map(|t| t.text.to_lowercase()).unwrap_or_default());
+            }
+            TokenKind::Package if !prev_is_end => {
+                scope = match next(1) {
+                    Some(t)
+                        if next(2).is_some_and(|t| t.kind == TokenKind::Is)
+                            && !is_word(t, "body") =>
+                    {
+                        Scope::Package(t.text.to_string())
+                    }
+                    _ => Scope::None,
+                };
+            }
+            // type T is (A, B, C);
+            TokenKind::Type
+                if next(2).is_some_and(|t| t.kind == TokenKind::Is)
+                    && next(3).is_some_and(|t| t.kind == TokenKind::LeftParen) =>
+            {
+                let literals: Vec<String> = tokens[i + 4..]
+                    .iter()
+                    .take_while(|t| t.kind != TokenKind::RightParen)
+                    .filter(|t| t.kind == TokenKind::Identifier)
+                    .map(|t| t.text.to_string())
+                    .collect();
+                if let Some(name) = next(1).filter(|_| !literals.is_empty()) {
+                    types.push(EnumType {
+                        name: name.text.to_string(),
+                        literals,
+                        package: match &scope {
+                            Scope::Package(p) => Some(p.clone()),
+                            _ => None,
+                        },
+                        owner: match &scope {
+                            Scope::Architecture(e) => Some(e.clone()),
+                            _ => None,
+                        },
+                    });
+                }
+            }
+            // signal a, b : T [:= X];
+            TokenKind::Signal if scope == Scope::Architecture(entity.to_lowercase()) => {
+                let names: Vec<&Token> = tokens[i + 1..]
+                    .iter()
+                    .take_while(|t| t.kind != TokenKind::Colon)
+                    .filter(|t| t.kind == TokenKind::Identifier)
+                    .collect();
+                let at = i
+                    + 1
+                    + tokens[i + 1..]
+                        .iter()
+                        .position(|t| t.kind == TokenKind::Colon)
+                        .unwrap_or(0);
+                let Some(ty) = tokens.get(at + 1) else {
+                    continue;
+                };
+                let visible = |t: &EnumType| {
+                    t.name.eq_ignore_ascii_case(ty.text)
+                        && (t.package.is_some()
+                            || t.owner.as_deref() == Some(&entity.to_lowercase()))
+                };
+                let Some(type_index) = types.iter().rposition(visible) else {
+                    continue;
+                };
+                let init = (tokens.get(at + 2).map(|t| &t.kind) == Some(&TokenKind::Assignment))
+                    .then(|| tokens.get(at + 3).map(|t| t.text.to_string()))
+                    .flatten();
+                for name in names {
+                    signals.push(StateSignal {
+                        name: name.text.to_string(),
+                        type_index,
+                        init: init.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let arch = Scope::Architecture(entity.to_lowercase());
+    let state_signal = |name: &str| signals.iter().find(|s| s.name.eq_ignore_ascii_case(name));
+    let mut fsms: Vec<Fsm> = Vec::new();
+    for i in 0..tokens.len() {
+        if scopes[i] != arch || tokens[i].kind != TokenKind::Case {
+            continue;
+        }
+        // case <状態信号> is
+        let Some(selector) = tokens.get(i + 1).and_then(|t| state_signal(t.text)) else {
+            continue;
+        };
+        if tokens.get(i + 2).map(|t| &t.kind) != Some(&TokenKind::Is)
+            || fsms
+                .iter()
+                .any(|f| f.state_signal.eq_ignore_ascii_case(&selector.name))
+        {
+            continue;
+        }
+        let ty = &types[selector.type_index];
+        let targets: Vec<&str> = signals
+            .iter()
+            .filter(|s| s.type_index == selector.type_index)
+            .map(|s| s.name.as_str())
+            .collect();
+        let mut scanner = Scanner {
+            tokens: &tokens,
+            pos: i,
+            targets: &targets,
+            states: &ty.literals,
+        };
+        let (transitions, end) = scanner.fsm_case();
+        let span = Span::new(
+            tokens[i].span.start,
+            tokens[end.min(tokens.len() - 1)].span.end,
+        );
+
+        // リセット状態: case の外で状態信号に代入している最初の状態、なければ初期値
+        let reset_state = (0..tokens.len())
+            .filter(|&k| scopes[k] == arch && (k < i || k > end))
+            .find_map(|k| {
+                let is_target = tokens[k].text.eq_ignore_ascii_case(&selector.name)
+                    && tokens.get(k + 1).map(|t| &t.kind) == Some(&TokenKind::Lte)
+                    && tokens.get(k + 3).map(|t| &t.kind) == Some(&TokenKind::Semicolon);
+                is_target
+                    .then(|| state_literal(&ty.literals, tokens[k + 2].text))
+                    .flatten()
+            })
+            .or_else(|| {
+                selector
+                    .init
+                    .as_deref()
+                    .and_then(|s| state_literal(&ty.literals, s))
+            })
+            .unwrap_or_else(|| ty.literals[0].clone());
+
+        fsms.push(Fsm {
+            state_signal: selector.name.clone(),
+            state_type: ty.name.clone(),
+            type_package: ty.package.clone(),
+            states: ty.literals.clone(),
+            reset_state,
+            transitions,
+            span,
+        });
+    }
+    fsms
+}
+
+/// 状態名（宣言どおりの表記）
+fn state_literal(states: &[String], text: &str) -> Option<String> {
+    states
+        .iter()
+        .find(|s| s.eq_ignore_ascii_case(text))
+        .cloned()
+}
+
+/// トークン列を空白でつなぐ（括弧の内側とカンマの前には空白を入れない）
+fn join_tokens(tokens: &[Token]) -> String {
+    let mut s = String::new();
+    for (i, t) in tokens.iter().enumerate() {
+        let glue = i == 0
+            || tokens[i - 1].kind == TokenKind::LeftParen
+            || matches!(t.kind, TokenKind::RightParen | TokenKind::Comma);
+        if !glue {
+            s.push(' ');
+        }
+        s.push_str(t.text);
+    }
+    s
+}
+
+/// 否定した条件
+fn negate(cond: &str) -> String {
+    format!("not ({})", cond)
+}
+
+/// ステートマシンの `case` 文を読む
+struct Scanner<'a> {
+    tokens: &'a [Token<'a>],
+    pos: usize,
+    /// 状態型の信号（遷移先の代入先）
+    targets: &'a [&'a str],
+    states: &'a [String],
+}
+
+/// 遷移先の代入（条件, 遷移先, 位置）
+type Assign = (Vec<String>, String, Span);
+
+impl Scanner<'_> {
+    fn kind(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|t| t.text.eq_ignore_ascii_case(word))
+    }
+
+    /// 指定の種類のトークンまでを読み、条件の文字列にする（終端は読み飛ばす）
+    fn until(&mut self, end: TokenKind) -> String {
+        let start = self.pos;
+        while self.kind().is_some_and(|k| *k != end) {
+            self.pos += 1;
+        }
+        // 入力が途中で終わっていれば空
+        let text = self
+            .tokens
+            .get(start..self.pos)
+            .map_or_else(String::new, join_tokens);
+        self.pos += 1;
+        text
+    }
+
+    /// `end xxx [label];` を読み飛ばす
+    fn finish_end(&mut self) {
+        self.until(TokenKind::Semicolon);
+    }
+
+    /// `case <状態信号> is` から `end case;` まで。遷移と `end` の位置を返す
+    fn fsm_case(&mut self) -> (Vec<Transition>, usize) {
+        self.pos += 3;
+        let mut transitions: Vec<Transition> = Vec::new();
+        let mut listed: Vec<String> = Vec::new();
+        while self.kind() == Some(&TokenKind::When) {
+            self.pos += 1;
+            let start = self.pos;
+            while self.kind().is_some_and(|k| *k != TokenKind::Association) {
+                self.pos += 1;
+            }
+            let choices: Vec<&Token> = self.tokens[start..self.pos.min(self.tokens.len())]
+                .iter()
+                .filter(|t| matches!(t.kind, TokenKind::Identifier | TokenKind::Others))
+                .collect();
+            self.pos += 1;
+            let mut assigns = Vec::new();
+            self.statements(&mut Vec::new(), &mut assigns);
+
+            let from: Vec<String> = if choices.iter().any(|t| t.kind == TokenKind::Others) {
+                self.states
+                    .iter()
+                    .filter(|s| !listed.iter().any(|l| l.eq_ignore_ascii_case(s)))
+                    .cloned()
+                    .collect()
+            } else {
+                choices
+                    .iter()
+                    .filter_map(|t| state_literal(self.states, t.text))
+                    .collect()
+            };
+            for state in &from {
+                for (conditions, to, span) in &assigns {
+                    transitions.push(Transition {
+                        from: state.clone(),
+                        to: to.clone(),
+                        conditions: conditions.clone(),
+                        span: *span,
+                    });
+                }
+            }
+            listed.extend(from);
+        }
+        let end = self.pos;
+        self.finish_end();
+        (transitions, end)
+    }
+
+    /// 文の並び（`end` `when` `elsif` `else` の手前まで）
+    fn statements(&mut self, conds: &mut Vec<String>, out: &mut Vec<Assign>) {
+        loop {
+            match self.kind().cloned() {
+                None
+                | Some(TokenKind::End)
+                | Some(TokenKind::When)
+                | Some(TokenKind::Elsif)
+                | Some(TokenKind::Else) => return,
+                Some(TokenKind::If) => self.if_statement(conds, out),
+                Some(TokenKind::Case) => self.case_statement(conds, out),
+                _ if self.is_word("for") || self.is_word("while") || self.is_word("loop") => {
+                    while !self.is_word("loop") && self.kind().is_some() {
+                        self.pos += 1;
+                    }
+                    self.pos += 1;
+                    self.statements(conds, out);
+                    self.finish_end();
+                }
+                Some(TokenKind::Identifier)
+                    if self.tokens.get(self.pos + 1).map(|t| &t.kind) == Some(&TokenKind::Lte) =>
+                {
+                    let target = &self.tokens[self.pos];
+                    let value = self.tokens.get(self.pos + 2);
+                    let single = self.tokens.get(self.pos + 3).map(|t| &t.kind)
+                        == Some(&TokenKind::Semicolon);
+                    if self
+                        .targets
+                        .iter()
+                        .any(|t| t.eq_ignore_ascii_case(target.text))
+                        && single
+                        && let Some(to) = value.and_then(|v| state_literal(self.states, v.text))
+                    {
+                        let span = Span::new(target.span.start, self.tokens[self.pos + 3].span.end);
+                        out.push((conds.clone(), to, span));
+                    }
+                    self.until(TokenKind::Semicolon);
+                }
+                Some(_) => {
+                    self.until(TokenKind::Semicolon);
+                }
+            }
+        }
+    }
+
+    fn if_statement(&mut self, conds: &mut Vec<String>, out: &mut Vec<Assign>) {
+        self.pos += 1;
+        let mut previous: Vec<String> = Vec::new();
+        let mut cond = Some(self.until(TokenKind::Then));
+        loop {
+            let depth = conds.len();
+            conds.extend(previous.iter().map(|c| negate(c)));
+            conds.extend(cond.clone());
+            self.statements(conds, out);
+            conds.truncate(depth);
+            previous.extend(cond);
+            match self.kind().cloned() {
+                Some(TokenKind::Elsif) => {
+                    self.pos += 1;
+                    cond = Some(self.until(TokenKind::Then));
+                }
+                Some(TokenKind::Else) => {
+                    self.pos += 1;
+                    cond = None;
+                }
+                _ => break,
+            }
+        }
+        self.finish_end();
+    }
+
+    /// 状態信号以外の `case` は `式 = 選択肢` を条件にする
+    fn case_statement(&mut self, conds: &mut Vec<String>, out: &mut Vec<Assign>) {
+        self.pos += 1;
+        let selector = self.until(TokenKind::Is);
+        let mut previous: Vec<String> = Vec::new();
+        while self.kind() == Some(&TokenKind::When) {
+            self.pos += 1;
+            let choice = self.until(TokenKind::Association);
+            let depth = conds.len();
+            let this = if choice.eq_ignore_ascii_case("others") {
+                conds.extend(previous.iter().map(|c| negate(c)));
+                None
+            } else {
+                let c = format!("{} = {}", selector, choice);
+                conds.push(c.clone());
+                Some(c)
+            };
+            self.statements(conds, out);
+            conds.truncate(depth);
+            previous.extend(this);
+        }
+        self.finish_end();
+    }
+}
+
+// --- テストシナリオ ---
+
+/// 条件から入力ポートへの代入を作る（`and` で区切った項のうち入力で満たせないものは別に返す）
+fn drive(cond: &str, ports: &[PortDef]) -> (Vec<(String, String)>, Vec<String>) {
+    let tokens: Vec<Token> = Lexer::new(cond)
+        .filter_map(|r| r.ok())
+        .filter(|t| t.kind != TokenKind::Eof)
+        .collect();
+    let input = |name: &str| {
+        ports
+            .iter()
+            .find(|p| p.direction == PortDirection::In && p.name.eq_ignore_ascii_case(name))
+    };
+    let opposite = |value: &str| match value {
+        "'0'" => Some("'1'".to_string()),
+        "'1'" => Some("'0'".to_string()),
+        _ if value.eq_ignore_ascii_case("true") => Some("false".to_string()),
+        _ if value.eq_ignore_ascii_case("false") => Some("true".to_string()),
+        _ => None,
+    };
+    // a = v / a /= v / a
+    let simple = |t: &[Token]| -> Option<(String, String)> {
+        let port = input(t.first()?.text)?;
+        match t {
+            [_] => match port.vhdl_type {
+                VhdlType::StdLogic | VhdlType::Bit => Some((port.name.clone(), "'1'".to_string())),
+                VhdlType::Boolean => Some((port.name.clone(), "true".to_string())),
+                _ => None,
+            },
+            [_, op, value] if op.kind == TokenKind::Eq => {
+                Some((port.name.clone(), value.text.to_string()))
+            }
+            [_, op, value] if op.kind == TokenKind::Neq => {
+                Some((port.name.clone(), opposite(value.text)?))
+            }
+            _ => None,
+        }
+    };
+
+    let mut drives = Vec::new();
+    let mut internal = Vec::new();
+    for part in tokens.split(|t| t.kind == TokenKind::And) {
+        let found = match part {
+            [not, open, inner @ .., close]
+                if not.kind == TokenKind::Not
+                    && open.kind == TokenKind::LeftParen
+                    && close.kind == TokenKind::RightParen =>
+            {
+                simple(inner).and_then(|(name, value)| Some((name, opposite(&value)?)))
+            }
+            _ => simple(part),
+        };
+        match found {
+            Some(d) => drives.push(d),
+            None => internal.push(join_tokens(part)),
+        }
+    }
+    (drives, internal)
+}
+
+/// 状態 `from` から未通過の遷移を通るまでの最短経路（遷移の番号列）
+fn shortest_path(fsm: &Fsm, from: &str, done: &[bool]) -> Option<Vec<usize>> {
+    let mut queue = VecDeque::from([(from.to_string(), Vec::new())]);
+    let mut visited = vec![from.to_string()];
+    while let Some((state, path)) = queue.pop_front() {
+        for (i, t) in fsm.transitions.iter().enumerate() {
+            if t.from != state {
+                continue;
+            }
+            let mut next = path.clone();
+            next.push(i);
+            if !done[i] {
+                return Some(next);
+            }
+            if !visited.contains(&t.to) {
+                visited.push(t.to.clone());
+                queue.push_back((t.to.clone(), next));
+            }
+        }
+    }
+    None
+}
+
+/// リセット状態からすべての遷移をたどるスティミュラスの行
+///
+/// 入力ポートで満たせる条件は代入し、内部信号による条件は TODO コメントを残して
+/// 1クロック進めます。たどれなくなったらリセットからやり直します。
+pub(crate) fn scenario(
+    fsm: &Fsm,
+    ports: &[PortDef],
+    clk: Option<&str>,
+    rst: Option<&str>,
+    period_ns: u64,
+    reset_cycles: u64,
+    polarity: ResetPolarity,
+) -> Vec<String> {
+    let mut lines = vec![format!(
+        "-- ステートマシン {} の遷移をたどる",
+        fsm.state_signal
+    )];
+    let Some(clk) = clk else {
+        lines.push("-- クロックがないため遷移をたどれません".to_string());
+        return lines;
+    };
+    let mut done = vec![false; fsm.transitions.len()];
+    let mut state = fsm.reset_state.clone();
+    while done.iter().any(|d| !d) {
+        let Some(path) = shortest_path(fsm, &state, &done) else {
+            // リセット状態からもたどれなければ終わり
+            let Some(rst) = rst.filter(|_| state != fsm.reset_state) else {
+                break;
+            };
+            lines.extend([
+                format!("-- {} に戻す", fsm.reset_state),
+                format!("{} <= {};", rst, polarity.asserted()),
+                format!("wait for {} ns;", period_ns * reset_cycles),
+                format!("{} <= {};", rst, polarity.released()),
+                format!("wait for {} ns;", period_ns * reset_cycles),
+            ]);
+            state = fsm.reset_state.clone();
+            continue;
+        };
+        for i in path {
+            let t = &fsm.transitions[i];
+            done[i] = true;
+            let label = if t.conditions.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", t.conditions.join(" and "))
+            };
+            lines.push(format!("-- {} -> {}{}", t.from, t.to, label));
+            let mut driven: Vec<(String, String)> = Vec::new();
+            for cond in &t.conditions {
+                let (drives, internal) = drive(cond, ports);
+                driven.extend(drives);
+                for part in internal {
+                    lines.push(format!("-- TODO: {} が成り立つまで待つ", part));
+                }
+            }
+            for (name, value) in &driven {
+                lines.push(format!("{} <= {};", name, value));
+            }
+            lines.push(format!("wait until rising_edge({});", clk));
+            // 入力は1クロックだけ与えて既定値に戻す
+            for (name, value) in &driven {
+                let default = ports
+                    .iter()
+                    .find(|p| &p.name == name)
+                    .map(|p| type_default_value(&p.vhdl_type));
+                if let Some(default) = default.filter(|d| d != value) {
+                    lines.push(format!("{} <= {};", name, default));
+                }
+            }
+            state = t.to.clone();
+        }
+    }
+    let missed: Vec<String> = fsm
+        .transitions
+        .iter()
+        .zip(&done)
+        .filter(|(_, d)| !**d)
+        .map(|(t, _)| format!("{} -> {}", t.from, t.to))
+        .collect();
+    if !missed.is_empty() {
+        lines.push(format!(
+            "-- リセット状態から到達できない遷移: {}",
+            missed.join(", ")
+        ));
+    }
+    lines.push(String::new());
+    lines
+}
+
+/// 状態と遷移の観測プロセスの宣言部と本体（状態型がパッケージにある場合だけ）
+///
+/// `path` は状態信号の外部名のパス（`.counter_tb.uut.state`）です。
+pub(crate) fn monitor(
+    fsm: &Fsm,
+    path: &str,
+    clk: &str,
+    done: &str,
+) -> Option<(Vec<String>, Vec<String>)> {
+    fsm.type_package.as_ref()?;
+    let ty = &fsm.state_type;
+    let alias = format!("dut_{}", fsm.state_signal);
+    let n = fsm.transitions.len().max(1);
+    let declarations = vec![
+        format!("alias {} is << signal {} : {} >>;", alias, path, ty),
+        format!("type seen_t is array ({}) of boolean;", ty),
+        "variable seen : seen_t := (others => false);".to_string(),
+        format!(
+            "variable taken : boolean_vector(1 to {}) := (others => false);",
+            n
+        ),
+        format!("variable prev : {};", ty),
+        "variable started : boolean := false;".to_string(),
+        "variable states, transitions : natural := 0;".to_string(),
+    ];
+    let mut body = vec![
+        format!("wait until rising_edge({}) or {};", clk, done),
+        format!("if {} then", done),
+        format!("    for s in {} loop", ty),
+        "        if seen(s) then".to_string(),
+        "            states := states + 1;".to_string(),
+        "        else".to_string(),
+        format!(
+            "            report \"fsm coverage: state \" & {}'image(s) & \" never reached\" severity warning;",
+            ty
+        ),
+        "        end if;".to_string(),
+        "    end loop;".to_string(),
+    ];
+    for (i, t) in fsm.transitions.iter().enumerate() {
+        body.extend([
+            format!("    if taken({}) then", i + 1),
+            "        transitions := transitions + 1;".to_string(),
+            "    else".to_string(),
+            format!(
+                "        report \"fsm coverage: transition {} -> {} never taken\" severity warning;",
+                t.from, t.to
+            ),
+            "    end if;".to_string(),
+        ]);
+    }
+    body.extend([
+        format!(
+            "    report \"fsm coverage: {}: \" & integer'image(states) & \"/{} states, \" & integer'image(transitions) & \"/{} transitions\" severity note;",
+            fsm.state_signal,
+            fsm.states.len(),
+            fsm.transitions.len()
+        ),
+        "    wait;".to_string(),
+        "end if;".to_string(),
+        "if started then".to_string(),
+    ]);
+    for (i, t) in fsm.transitions.iter().enumerate() {
+        body.push(format!(
+            "    if prev = {} and {} = {} then taken({}) := true; end if;",
+            t.from,
+            alias,
+            t.to,
+            i + 1
+        ));
+    }
+    body.extend([
+        "end if;".to_string(),
+        format!("seen({}) := true;", alias),
+        format!("prev := {};", alias),
+        "started := true;".to_string(),
+    ]);
+    Some((declarations, body))
+}
+
+impl Fsm {
+    /// 遷移条件に現れる入力ポート
+    pub fn trigger_inputs<'a>(&self, entity: &'a EntityDef) -> Vec<&'a str> {
+        entity
+            .ports
+            .iter()
+            .filter(|p| p.direction == PortDirection::In)
+            .filter(|p| {
+                self.transitions
+                    .iter()
+                    .flat_map(|t| &t.conditions)
+                    .any(|c| {
+                        Lexer::new(c)
+                            .filter_map(|r| r.ok())
+                            .any(|t| t.text.eq_ignore_ascii_case(&p.name))
+                    })
+            })
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+}
@@ -0,0 +1,66 @@
+//! ファジングの入口
+//!
+//! 字句解析・意味解析と、それを使う解析（診断・セマンティックトークン・補完・リント・
+//! 指標・ステートマシンの抽出・インタプリタ・テストベンチ生成）と Verilog ヘッダの読み取りに
+//! 任意のバイト列を通し、パニックせずに診断やエラーとして返すかを確かめるためのものです。
+//! テストではテストデータを変異させた入力（ビット列リテラルの幅指定やベクタの範囲を
+//! 極端に大きな数にしたものを含む）を通しています。
+//!
+//! cargo-fuzz のターゲットからはそのまま呼び出せます。
+//!
+//! ```ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| vig::fuzz::run(data));
+//! ```
+
+use crate::analyzer::analyze_vhdl;
+use crate::encoding::{Encoding, decode_lossy};
+use crate::generator::{TbConfig, TestbenchBuilder, generate_testbench};
+use crate::lint::{LintConfig, lint};
+use crate::{completion, diagnostic, fsm, interp, metrics, semantic, signals, todo, verilog};
+
+/// 補完を試す位置の数（入力の長さに比例させない）
+const COMPLETION_POINTS: usize = 8;
+
+/// バイト列を VHDL ソースとしてすべての解析に通す（UTF-8 でない部分は置き換える）
+pub fn run(data: &[u8]) {
+    run_source(&decode_lossy(data, Encoding::Utf8));
+}
+
+/// ソースをすべての解析に通す
+pub fn run_source(source: &str) {
+    let _ = diagnostic::diagnose(source);
+    let _ = semantic::semantic_tokens(source);
+    let _ = lint(source, &LintConfig::default());
+    let _ = metrics::line_counts(source);
+    let _ = todo::find_todos(source);
+    if let Ok(modules) = verilog::parse_modules(source) {
+        for module in &modules {
+            let _ = generate_testbench(&module.to_entity(), &TbConfig::default());
+        }
+    }
+    for i in 0..=COMPLETION_POINTS {
+        let mut offset = source.len() * i / COMPLETION_POINTS;
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let _ = completion::completions(source, offset);
+    }
+
+    let Ok(result) = analyze_vhdl(source) else {
+        return;
+    };
+    let config = TbConfig::default();
+    for entity in &result.entities {
+        let _ = metrics::metrics(source, entity);
+        let _ = signals::usages(source, entity);
+        let _ = interp::Interpreter::new(source, &entity.name);
+        let _ = generate_testbench(entity, &config);
+        for machine in fsm::extract_fsms(source, &entity.name) {
+            let _ = TestbenchBuilder::new(entity).fsm(machine).build();
+        }
+        for port in &entity.ports {
+            let _ = port.vhdl_type.bit_width();
+        }
+    }
+}
@@ -1,111 +1,835 @@
-use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use std::fmt::Write;
+
+use crate::analyzer::{ArchitectureDef, EntityDef, PortDef, PortDirection, VhdlType};
+use crate::assertion::{self, Property};
+use crate::coverage;
+use crate::emit::Emitter;
+use crate::fsm::{self, Fsm};
+use crate::ident::{self, Namer};
+use crate::regress::matches_filter;
+use crate::style::{self, TbStyle};
+use crate::trace::timed;
+
+/// テストベンチモデルを加工する生成パス
+///
+/// レンダリング直前に登録順で呼び出されます。社内ヘッダの挿入や
+/// プロセスの追加、命名の調整などをジェネレータを改造せずに行えます。
+pub type GenerationPass = fn(&mut TestbenchModel, &EntityDef);
+
+/// スティミュラスでリセットをアサート・解除した後に待つクロック数
+pub(crate) const RESET_CYCLES: u64 = 2;
+/// スティミュラスのテストパターン部分のクロック数
+pub(crate) const TEST_CYCLES: u64 = 10;
+/// スティミュラスの終了を知らせてカバレッジを報告させる信号
+pub(crate) const DONE_SIGNAL: &str = "sim_done";
+/// シミュレーション終了の報告
+pub(crate) const FINISH_MESSAGE: &str = "Simulation finished";
+/// 監視プロセスによる時間切れの報告の先頭
+pub(crate) const TIMEOUT_MESSAGE: &str = "Simulation timed out";
+
+/// リセットの極性
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResetPolarity {
+    #[default]
+    ActiveHigh,
+    ActiveLow,
+}
+
+impl ResetPolarity {
+    /// リセット中の値
+    pub fn asserted(self) -> &'static str {
+        match self {
+            ResetPolarity::ActiveHigh => "'1'",
+            ResetPolarity::ActiveLow => "'0'",
+        }
+    }
+
+    /// リセット解除後の値
+    pub fn released(self) -> &'static str {
+        match self {
+            ResetPolarity::ActiveHigh => "'0'",
+            ResetPolarity::ActiveLow => "'1'",
+        }
+    }
+}
+
+/// 生成するアサーションの重大度（VHDL の `severity_level`）
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AssertSeverity {
+    Note,
+    Warning,
+    Error,
+    Failure,
+}
+
+impl AssertSeverity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AssertSeverity::Note => "note",
+            AssertSeverity::Warning => "warning",
+            AssertSeverity::Error => "error",
+            AssertSeverity::Failure => "failure",
+        }
+    }
+}
+
+impl std::str::FromStr for AssertSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "note" => Ok(AssertSeverity::Note),
+            "warning" => Ok(AssertSeverity::Warning),
+            "error" => Ok(AssertSeverity::Error),
+            "failure" => Ok(AssertSeverity::Failure),
+            _ => Err(format!("unknown severity '{}'", s)),
+        }
+    }
+}
 
 /// テストベンチ生成の設定
+#[non_exhaustive]
+#[derive(Debug, Clone)]
 pub struct TbConfig {
     /// クロック周期（ns）
     pub clock_period_ns: u64,
+    /// レンダリング前に適用する生成パス
+    pub passes: Vec<GenerationPass>,
+    /// 出力のトグルカバレッジを計測し、シミュレーションの最後に報告する
+    pub toggle_coverage: bool,
+    /// 検査プロセスとして組み込むアサーション
+    pub assertions: Vec<Property>,
+    /// 遷移をたどるシナリオと状態の観測を生成するステートマシン
+    pub fsms: Vec<Fsm>,
+    /// リセットポートの極性
+    pub reset_polarity: ResetPolarity,
+    /// クロック・リセットの推定、スティミュラスの例、検査・カバレッジから外すポート
+    /// （大文字小文字を区別しない、DUT には接続したまま）
+    pub exclude_ports: Vec<String>,
+    /// 検査（アサーション・期待値の比較）が失敗したときの重大度
+    pub check_severity: AssertSeverity,
+    /// シミュレーションの制限時間（ns）。超えたら監視プロセスが報告する
+    pub timeout_ns: Option<u64>,
+    /// 制限時間を超えたときの重大度
+    pub timeout_severity: AssertSeverity,
+    /// シミュレーション終了の報告の重大度（`failure` で止めるシミュレータ向け）
+    pub finish_severity: AssertSeverity,
+    /// テストベンチのスタイル
+    pub style: TbStyle,
+    /// 実体化するアーキテクチャ（None ならアーキテクチャを指定しない）
+    ///
+    /// 指定するとテストベンチ名は `<エンティティ>_<アーキテクチャ>_tb` になります。
+    pub architecture: Option<ArchitectureDef>,
+    /// 値の変化を報告するアーキテクチャの内部信号（`*` `?` のワイルドカード、空なら報告しない）
+    pub whitebox: Vec<String>,
+}
+
+impl TbConfig {
+    /// クロック・リセットの推定やスティミュラス・検査の対象にするポートだけのエンティティ
+    pub(crate) fn target_ports(&self, entity: &EntityDef) -> EntityDef {
+        let mut target = entity.clone();
+        target.ports.retain(|p| {
+            !self
+                .exclude_ports
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(&p.name))
+        });
+        target
+    }
 }
 
 impl Default for TbConfig {
     fn default() -> Self {
         Self {
             clock_period_ns: 10,
+            passes: Vec::new(),
+            toggle_coverage: false,
+            assertions: Vec::new(),
+            fsms: Vec::new(),
+            reset_polarity: ResetPolarity::default(),
+            exclude_ports: Vec::new(),
+            check_severity: AssertSeverity::Error,
+            timeout_ns: None,
+            timeout_severity: AssertSeverity::Failure,
+            finish_severity: AssertSeverity::Note,
+            style: TbStyle::default(),
+            architecture: None,
+            whitebox: Vec::new(),
         }
     }
 }
 
-/// EntityDefからテストベンチのVHDLコードを生成する
-pub fn generate_testbench(entity: &EntityDef, config: &TbConfig) -> String {
-    let tb_name = format!("{}_tb", entity.name);
-    let clk_port = find_clock_port(&entity.ports);
-    let rst_port = find_reset_port(&entity.ports);
+/// テストベンチ生成のビルダー
+///
+/// `TbConfig` を直接組み立てずに設定を変えながら生成するためのものです。
+pub struct TestbenchBuilder<'a> {
+    entity: &'a EntityDef,
+    config: TbConfig,
+}
 
-    let mut out = String::new();
+impl<'a> TestbenchBuilder<'a> {
+    /// デフォルト設定でビルダーを作成
+    pub fn new(entity: &'a EntityDef) -> Self {
+        Self {
+            entity,
+            config: TbConfig::default(),
+        }
+    }
 
-    // ライブラリ宣言
-    out.push_str("library ieee;\n");
-    out.push_str("use ieee.std_logic_1164.all;\n");
-    out.push_str("use ieee.numeric_std.all;\n");
-    out.push('\n');
+    /// 設定をまとめて差し替える
+    pub fn config(mut self, config: TbConfig) -> Self {
+        self.config = config;
+        self
+    }
 
-    // テストベンチentity（ポートなし）
-    out.push_str(&format!("entity {} is\n", tb_name));
-    out.push_str(&format!("end entity {};\n", tb_name));
-    out.push('\n');
+    /// クロック周期（ns）を設定
+    pub fn clock_period_ns(mut self, period: u64) -> Self {
+        self.config.clock_period_ns = period;
+        self
+    }
 
-    // architecture
-    out.push_str(&format!("architecture testbench of {} is\n", tb_name));
-    out.push('\n');
+    /// トグルカバレッジの計測を有効にする
+    pub fn toggle_coverage(mut self, enabled: bool) -> Self {
+        self.config.toggle_coverage = enabled;
+        self
+    }
 
-    // コンポーネント宣言
-    out.push_str(&gen_component(entity));
-    out.push('\n');
+    /// アサーションを追加
+    pub fn assertion(mut self, property: Property) -> Self {
+        self.config.assertions.push(property);
+        self
+    }
 
-    // 信号宣言
-    out.push_str(&gen_signals(&entity.ports));
-    out.push('\n');
+    /// ステートマシンのシナリオと観測を追加
+    pub fn fsm(mut self, fsm: Fsm) -> Self {
+        self.config.fsms.push(fsm);
+        self
+    }
 
-    out.push_str("begin\n");
-    out.push('\n');
+    /// リセットポートの極性を設定
+    pub fn reset_polarity(mut self, polarity: ResetPolarity) -> Self {
+        self.config.reset_polarity = polarity;
+        self
+    }
 
-    // DUTインスタンス
-    out.push_str(&gen_dut_instance(entity));
-    out.push('\n');
+    /// クロック・リセットの推定やスティミュラス・検査から外すポートを追加
+    pub fn exclude_port(mut self, name: impl Into<String>) -> Self {
+        self.config.exclude_ports.push(name.into());
+        self
+    }
+
+    /// 検査が失敗したときの重大度を設定
+    pub fn check_severity(mut self, severity: AssertSeverity) -> Self {
+        self.config.check_severity = severity;
+        self
+    }
+
+    /// シミュレーションの制限時間（ns）と、超えたときの重大度を設定
+    pub fn timeout(mut self, timeout_ns: u64, severity: AssertSeverity) -> Self {
+        self.config.timeout_ns = Some(timeout_ns);
+        self.config.timeout_severity = severity;
+        self
+    }
+
+    /// シミュレーション終了の報告の重大度を設定
+    pub fn finish_severity(mut self, severity: AssertSeverity) -> Self {
+        self.config.finish_severity = severity;
+        self
+    }
+
+    /// テストベンチのスタイルを設定
+    pub fn style(mut self, style: TbStyle) -> Self {
+        self.config.style = style;
+        self
+    }
+
+    /// 実体化するアーキテクチャを設定
+    pub fn architecture(mut self, architecture: ArchitectureDef) -> Self {
+        self.config.architecture = Some(architecture);
+        self
+    }
+
+    /// 値の変化を報告する内部信号の名前のパターンを追加
+    pub fn whitebox(mut self, pattern: impl Into<String>) -> Self {
+        self.config.whitebox.push(pattern.into());
+        self
+    }
+
+    /// 生成パスを追加
+    pub fn pass(mut self, pass: GenerationPass) -> Self {
+        self.config.passes.push(pass);
+        self
+    }
+
+    /// 生成パス適用済みのモデルを作成
+    pub fn build_model(&self) -> TestbenchModel {
+        build_model(self.entity, &self.config)
+    }
+
+    /// テストベンチのVHDLコードを生成
+    pub fn build(&self) -> String {
+        generate_testbench(self.entity, &self.config)
+    }
+}
+
+/// テストベンチ内の信号
+#[derive(Debug, Clone, PartialEq)]
+pub struct TbSignal {
+    pub name: String,
+    pub vhdl_type: VhdlType,
+    pub init: String,
+}
+
+/// DUTのインスタンス
+#[derive(Debug, Clone, PartialEq)]
+pub struct TbInstance {
+    pub label: String,
+    /// コンポーネント名、または直接実体化なら `entity work.名前`
+    pub unit: String,
+    /// (ジェネリック名, 値) の組
+    pub generic_map: Vec<(String, String)>,
+    /// (仮引数, 実引数) の組
+    pub port_map: Vec<(String, String)>,
+}
+
+impl TbInstance {
+    /// 直接実体化（`entity work.名前`）か
+    pub fn is_direct(&self) -> bool {
+        self.unit
+            .get(..7)
+            .is_some_and(|s| s.eq_ignore_ascii_case("entity "))
+    }
+}
+
+/// テストベンチ内のプロセス
+#[derive(Debug, Clone, PartialEq)]
+pub struct TbProcess {
+    /// プロセス直前のコメント（"-- " は不要）
+    pub comment: Option<String>,
+    pub label: String,
+    pub sensitivity: Vec<String>,
+    /// 宣言部の行（インデントなし）
+    pub declarations: Vec<String>,
+    /// 本体の行（インデントなし、空文字列は空行）
+    pub body: Vec<String>,
+}
+
+impl TbProcess {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            comment: None,
+            label: label.into(),
+            sensitivity: Vec::new(),
+            declarations: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+}
+
+/// レンダリング前のテストベンチの中間表現
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestbenchModel {
+    /// ファイル先頭のコメント行（"-- " は不要）
+    pub header: Vec<String>,
+    /// library/use 節の行
+    pub context: Vec<String>,
+    /// テストベンチのエンティティ名
+    pub name: String,
+    /// テストベンチのアーキテクチャ名
+    pub architecture: String,
+    /// コンポーネント宣言するDUT
+    pub component: EntityDef,
+    pub signals: Vec<TbSignal>,
+    /// 信号宣言に続く追加の宣言行（インデントなし）
+    pub declarations: Vec<String>,
+    pub instance: TbInstance,
+    /// スティミュラスの終了を知らせる信号の名前（ポートと重なれば `sim_done_1` など）
+    pub done_signal: String,
+    /// DUTインスタンスに続く追加の並行文の行（インデントなし）
+    pub statements: Vec<String>,
+    pub processes: Vec<TbProcess>,
+}
+
+impl TestbenchModel {
+    /// 名前で信号を探す
+    pub fn signal_mut(&mut self, name: &str) -> Option<&mut TbSignal> {
+        self.signals.iter_mut().find(|s| s.name == name)
+    }
+
+    /// ラベルでプロセスを探す
+    pub fn process_mut(&mut self, label: &str) -> Option<&mut TbProcess> {
+        self.processes.iter_mut().find(|p| p.label == label)
+    }
+
+    /// VHDLコードとして出力
+    pub fn render(&self) -> String {
+        let _timed = timed!("render", testbench = %self.name);
+        let mut out = String::with_capacity(self.estimated_len());
+
+        for line in &self.header {
+            if line.is_empty() {
+                out.push_str("--\n");
+            } else {
+                let _ = writeln!(out, "-- {}", line);
+            }
+        }
+        if !self.header.is_empty() {
+            out.push('\n');
+        }
+
+        // ライブラリ宣言
+        for line in &self.context {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push('\n');
+
+        // テストベンチentity（ポートなし）
+        let _ = writeln!(out, "entity {} is", self.name);
+        let _ = writeln!(out, "end entity {};", self.name);
+        out.push('\n');
+
+        // architecture
+        let _ = writeln!(
+            out,
+            "architecture {} of {} is",
+            self.architecture, self.name
+        );
+        out.push('\n');
+
+        // コンポーネント宣言（直接実体化では不要）
+        if !self.instance.is_direct() {
+            out.push_str(&gen_component(&self.component));
+            out.push('\n');
+        }
+
+        // 信号宣言
+        for sig in &self.signals {
+            let _ = writeln!(
+                out,
+                "    signal {} : {} := {};",
+                sig.name,
+                type_to_vhdl(&sig.vhdl_type),
+                sig.init
+            );
+        }
+        push_lines(&mut out, &self.declarations, 1);
+        out.push('\n');
+
+        out.push_str("begin\n");
+        out.push('\n');
+
+        // DUTインスタンス
+        write_dut_instance(&mut out, &self.instance);
+        out.push('\n');
+
+        if !self.statements.is_empty() {
+            push_lines(&mut out, &self.statements, 1);
+            out.push('\n');
+        }
+
+        // プロセス（ラベルが信号や他のラベルと重なれば番号を付ける）
+        let mut labels = Namer::new(
+            self.signals
+                .iter()
+                .map(|s| s.name.as_str())
+                .chain([self.instance.label.as_str()]),
+        );
+        for process in &self.processes {
+            write_process(&mut out, process, &labels.fresh(&process.label));
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "end architecture {};", self.architecture);
+        out
+    }
+
+    /// 出力のおおよその長さ（バッファの確保用）
+    fn estimated_len(&self) -> usize {
+        let lines = |lines: &[String]| lines.iter().map(|l| l.len() + 9).sum::<usize>();
+        let processes: usize = self
+            .processes
+            .iter()
+            .map(|p| 64 + lines(&p.declarations) + lines(&p.body))
+            .sum();
+        let ports = self.component.ports.len() + self.instance.port_map.len();
+        512 + lines(&self.header)
+            + lines(&self.context)
+            + lines(&self.declarations)
+            + lines(&self.statements)
+            + self.signals.len() * 64
+            + ports * 48
+            + processes
+    }
+}
+
+/// 行をインデント付きで追加（空文字列は空行）
+fn push_lines(out: &mut String, lines: &[String], depth: usize) {
+    for line in lines {
+        if !line.is_empty() {
+            for _ in 0..depth {
+                out.push_str("    ");
+            }
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+/// EntityDefからテストベンチモデルを作成し、生成パスを適用する
+pub fn build_model(entity: &EntityDef, config: &TbConfig) -> TestbenchModel {
+    let _timed = timed!("build_model", entity = %entity.name);
+    // 生成する名前は DUT の名前・ポートと予約語を避けて決める
+    let mut names = Namer::new(
+        entity
+            .ports
+            .iter()
+            .map(|p| p.name.as_str())
+            .chain([entity.name.as_str()]),
+    );
+    // 拡張識別子の DUT では `\Net-List_tb\` のように拡張識別子のままにする
+    let tb_name = ident::escape(&match &config.architecture {
+        Some(arch) => names.fresh(&format!(
+            "{}_{}_tb",
+            ident::bare(&entity.name),
+            ident::bare(&arch.name)
+        )),
+        None => names.fresh(&format!("{}_tb", ident::bare(&entity.name))),
+    });
+    let label = names.fresh("uut");
+    let done_signal = names.fresh(DONE_SIGNAL);
+    // 範囲の式はジェネリックの既定値が分かれば評価する（テストベンチにジェネリックはないため）
+    let entity = &escape_names(&entity.with_generic_bounds());
+    let target = config.target_ports(entity);
+    let clk_port = find_clock_port(&target.ports);
+    let rst_port = find_reset_port(&target.ports);
+
+    let signals = entity
+        .ports
+        .iter()
+        .map(|p| TbSignal {
+            name: p.name.clone(),
+            vhdl_type: p.vhdl_type.clone(),
+            init: type_default_value(&p.vhdl_type),
+        })
+        .collect();
+
+    let instance = TbInstance {
+        label,
+        unit: match &config.architecture {
+            Some(arch) => format!("entity work.{}({})", entity.name, arch.name),
+            None => entity.name.clone(),
+        },
+        generic_map: Vec::new(),
+        port_map: entity
+            .ports
+            .iter()
+            .map(|p| (p.name.clone(), p.name.clone()))
+            .collect(),
+    };
+
+    let mut processes = Vec::new();
 
     // クロック生成プロセス
     if let Some(clk) = &clk_port {
-        out.push_str(&gen_clock_process(clk, config.clock_period_ns));
-        out.push('\n');
+        processes.push(gen_clock_process(clk, config.clock_period_ns));
     }
 
     // スティミュラスプロセス
-    out.push_str(&gen_stimulus_process(
-        &entity.ports,
+    processes.push(gen_stimulus_process(
+        &target.ports,
         clk_port.as_deref(),
         rst_port.as_deref(),
-        config.clock_period_ns,
+        config,
+        &done_signal,
     ));
-    out.push('\n');
 
-    out.push_str("end architecture testbench;\n");
-    out
+    let mut model = TestbenchModel {
+        header: Vec::new(),
+        context: vec![
+            "library ieee;".to_string(),
+            "use ieee.std_logic_1164.all;".to_string(),
+            "use ieee.numeric_std.all;".to_string(),
+        ],
+        name: tb_name,
+        architecture: "testbench".to_string(),
+        component: entity.clone(),
+        signals,
+        declarations: Vec::new(),
+        instance,
+        done_signal,
+        statements: Vec::new(),
+        processes,
+    };
+
+    assertion::add_checkers(
+        &mut model,
+        &target,
+        &config.assertions,
+        config.reset_polarity,
+        config.check_severity,
+    );
+    if let Some(timeout) = config.timeout_ns {
+        let watchdog = gen_watchdog_process(timeout, config.timeout_severity, &model.done_signal);
+        model.processes.push(watchdog);
+    }
+    if signals_done(&target.ports, config) {
+        model.signals.push(TbSignal {
+            name: model.done_signal.clone(),
+            vhdl_type: VhdlType::Boolean,
+            init: "false".to_string(),
+        });
+    }
+    if config.toggle_coverage {
+        coverage::add_toggle_coverage(&mut model, &target);
+    }
+    add_fsm_monitors(&mut model, clk_port.as_deref(), config);
+    add_whitebox_monitor(&mut model, config);
+    style::apply(&mut model, &target, config);
+    for pass in &config.passes {
+        pass(&mut model, entity);
+    }
+    model
 }
 
-/// 型のVHDL文字列表現
-fn type_to_vhdl(vhdl_type: &VhdlType) -> String {
-    match vhdl_type {
-        VhdlType::StdLogic => "std_logic".to_string(),
-        VhdlType::StdLogicVector { high, low } => {
-            format!("std_logic_vector({} downto {})", high, low)
+/// 予約語や基本識別子として書けないエンティティ名・ポート名を拡張識別子にしたもの
+fn escape_names(entity: &EntityDef) -> EntityDef {
+    let mut escaped = entity.clone();
+    escaped.name = ident::escape(&entity.name);
+    for port in &mut escaped.ports {
+        port.name = ident::escape(&port.name);
+    }
+    escaped
+}
+
+/// ステートマシンの状態を観測するプロセスを追加する
+fn add_fsm_monitors(model: &mut TestbenchModel, clk: Option<&str>, config: &TbConfig) {
+    for fsm in &config.fsms {
+        let Some(package) = &fsm.type_package else {
+            model.declarations.push(format!(
+                "-- {} の状態を観測するには状態型 {} をパッケージで宣言してください",
+                fsm.state_signal, fsm.state_type
+            ));
+            continue;
+        };
+        let Some(clk) = clk else {
+            continue;
+        };
+        let path = format!(
+            ".{}.{}.{}",
+            model.name, model.instance.label, fsm.state_signal
+        );
+        let Some((declarations, body)) = fsm::monitor(fsm, &path, clk, &model.done_signal) else {
+            continue;
+        };
+        let using = format!("use work.{}.all;", package);
+        if !model.context.contains(&using) {
+            model.context.push(using);
         }
-        VhdlType::Integer => "integer".to_string(),
-        VhdlType::Boolean => "boolean".to_string(),
-        VhdlType::Other(name) => name.clone(),
+        let mut process = TbProcess::new(ident::suffixed(&fsm.state_signal, "_monitor"));
+        process.comment = Some(format!(
+            "状態カバレッジ（{} をホワイトボックスで観測）",
+            fsm.state_signal
+        ));
+        process.declarations = declarations;
+        process.body = body;
+        model.processes.push(process);
+    }
+}
+
+/// 選んだ内部信号の値の変化を報告するプロセスを追加する
+fn add_whitebox_monitor(model: &mut TestbenchModel, config: &TbConfig) {
+    let Some(arch) = &config.architecture else {
+        return;
+    };
+    if config.whitebox.is_empty() {
+        return;
+    }
+    let patterns = config.whitebox.join(",");
+    let mut declarations = Vec::new();
+    let mut aliases = Vec::new();
+    for signal in &arch.signals {
+        if !matches_filter(&signal.name, &patterns) {
+            continue;
+        }
+        if let VhdlType::Other(ty) = &signal.vhdl_type {
+            declarations.push(format!(
+                "-- {} の型 {} は表示できないため観測しません",
+                signal.name, ty
+            ));
+            continue;
+        }
+        let alias = ident::escape(&format!("wb_{}", ident::bare(&signal.name)));
+        declarations.push(format!(
+            "alias {} is << signal .{}.{}.{} : {} >>;",
+            alias,
+            model.name,
+            model.instance.label,
+            signal.name,
+            type_to_vhdl(&signal.vhdl_type)
+        ));
+        aliases.push((signal.name.as_str(), alias));
+    }
+    if aliases.is_empty() {
+        model.declarations.extend(declarations);
+        return;
+    }
+    let mut process = TbProcess::new("whitebox_monitor");
+    process.comment = Some(format!(
+        "{} の内部信号の変化（ホワイトボックスで観測）",
+        arch.name
+    ));
+    process.declarations = declarations;
+    let names: Vec<&str> = aliases.iter().map(|(_, alias)| alias.as_str()).collect();
+    process.body.push(format!("wait on {};", names.join(", ")));
+    for (name, alias) in &aliases {
+        process.body.extend([
+            format!("if {}'event then", alias),
+            format!(
+                "    report \"{}: {} = \" & to_string({}) severity note;",
+                arch.name, name, alias
+            ),
+            "end if;".to_string(),
+        ]);
+    }
+    model.processes.push(process);
+}
+
+/// シミュレーションの最後に報告するプロセスがあるか
+fn reports_at_end(ports: &[PortDef], config: &TbConfig) -> bool {
+    let fsm_monitor =
+        find_clock_port(ports).is_some() && config.fsms.iter().any(|f| f.type_package.is_some());
+    (config.toggle_coverage && coverage::has_outputs(ports)) || fsm_monitor
+}
+
+/// スティミュラスの終了を知らせる信号が要るか
+fn signals_done(ports: &[PortDef], config: &TbConfig) -> bool {
+    reports_at_end(ports, config) || config.timeout_ns.is_some()
+}
+
+/// スティミュラスの最後の行（カバレッジの報告を含む）
+pub(crate) fn simulation_end(ports: &[PortDef], config: &TbConfig, done: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    if signals_done(ports, config) {
+        let comment = if reports_at_end(ports, config) {
+            "-- カバレッジの報告"
+        } else {
+            "-- 監視プロセスへの終了の通知"
+        };
+        lines.extend([
+            comment.to_string(),
+            format!("{} <= true;", done),
+            "wait for 0 ns;".to_string(),
+            String::new(),
+        ]);
     }
+    lines.extend([
+        "-- シミュレーション終了".to_string(),
+        format!(
+            "assert false report \"{}\" severity {};",
+            FINISH_MESSAGE,
+            config.finish_severity.as_str()
+        ),
+        "wait;".to_string(),
+    ]);
+    lines
+}
+
+/// 制限時間までにスティミュラスが終わらなければ報告する監視プロセス
+fn gen_watchdog_process(timeout_ns: u64, severity: AssertSeverity, done: &str) -> TbProcess {
+    let mut process = TbProcess::new("watchdog");
+    process.comment = Some(format!("タイムアウトの監視（{} ns）", timeout_ns));
+    process.body = vec![
+        format!("wait until {} for {} ns;", done, timeout_ns),
+        format!(
+            "assert {} report \"{} after {} ns\" severity {};",
+            done,
+            TIMEOUT_MESSAGE,
+            timeout_ns,
+            severity.as_str()
+        ),
+        "wait;".to_string(),
+    ];
+    process
+}
+
+/// EntityDefからテストベンチのVHDLコードを生成する
+pub fn generate_testbench(entity: &EntityDef, config: &TbConfig) -> String {
+    build_model(entity, config).render()
+}
+
+/// EntityDefからエンティティ宣言と空のアーキテクチャの雛形を生成する
+pub fn generate_entity_skeleton(entity: &EntityDef) -> String {
+    let emitter = Emitter::default();
+    let arch = ArchitectureDef {
+        name: "rtl".to_string(),
+        entity_name: entity.name.clone(),
+        signals: Vec::new(),
+        constants: Vec::new(),
+        instances: Vec::new(),
+        processes: Vec::new(),
+        types: Vec::new(),
+        subprograms: Vec::new(),
+        span: entity.span,
+    };
+
+    let mut out = emitter.context("ieee", &["ieee.std_logic_1164.all", "ieee.numeric_std.all"]);
+    out.push('\n');
+    out.push_str(&emitter.entity(entity));
+    out.push('\n');
+    out.push_str(&emitter.architecture_with_body(&arch, &["-- TODO: 実装を記述".to_string()]));
+    out
+}
+
+/// ポート方向のVHDL文字列表現
+pub(crate) fn direction_to_vhdl(direction: &PortDirection) -> &'static str {
+    direction.as_str()
+}
+
+/// 型のVHDL文字列表現
+pub(crate) fn type_to_vhdl(vhdl_type: &VhdlType) -> String {
+    vhdl_type.to_string()
 }
 
 /// 型のデフォルト初期値
-fn type_default_value(vhdl_type: &VhdlType) -> String {
+pub(crate) fn type_default_value(vhdl_type: &VhdlType) -> String {
     match vhdl_type {
-        VhdlType::StdLogic => "'0'".to_string(),
-        VhdlType::StdLogicVector { .. } => "(others => '0')".to_string(),
-        VhdlType::Integer => "0".to_string(),
+        VhdlType::StdLogic | VhdlType::Bit => "'0'".to_string(),
+        VhdlType::StdLogicVector { .. }
+        | VhdlType::Signed { .. }
+        | VhdlType::Unsigned { .. }
+        | VhdlType::BitVector { .. } => "(others => '0')".to_string(),
+        VhdlType::Integer(Some(range))
+        | VhdlType::Natural(Some(range))
+        | VhdlType::Positive(Some(range)) => range.left.to_string(),
+        VhdlType::Integer(None) | VhdlType::Natural(None) => "0".to_string(),
+        VhdlType::Positive(None) => "1".to_string(),
+        VhdlType::Real => "0.0".to_string(),
+        VhdlType::Time => "0 ns".to_string(),
         VhdlType::Boolean => "false".to_string(),
+        VhdlType::Str(_) => "(others => ' ')".to_string(),
         VhdlType::Other(_) => "'0'".to_string(),
     }
 }
 
 /// clk を含むポートを探す（大文字小文字を区別しない）
-fn find_clock_port(ports: &[PortDef]) -> Option<String> {
-    let lower_contains = |name: &str, pat: &str| name.to_lowercase().contains(pat);
+pub(crate) fn find_clock_port(ports: &[PortDef]) -> Option<String> {
+    find_clock_ports(ports).into_iter().next()
+}
+
+/// clk を含む入力ポートをすべて探す（大文字小文字を区別しない）
+pub(crate) fn find_clock_ports(ports: &[PortDef]) -> Vec<String> {
     ports
         .iter()
-        .find(|p| p.direction == PortDirection::In && lower_contains(&p.name, "clk"))
+        .filter(|p| p.direction == PortDirection::In && p.name.to_lowercase().contains("clk"))
         .map(|p| p.name.clone())
+        .collect()
 }
 
 /// reset を含むポートを探す（大文字小文字を区別しない）
-fn find_reset_port(ports: &[PortDef]) -> Option<String> {
+pub(crate) fn find_reset_port(ports: &[PortDef]) -> Option<String> {
     let lower_contains = |name: &str, pat: &str| name.to_lowercase().contains(pat);
     ports
         .iter()
@@ -118,81 +842,85 @@ fn find_reset_port(ports: &[PortDef]) -> Option<String> {
 
 /// コンポーネント宣言を生成
 fn gen_component(entity: &EntityDef) -> String {
-    let mut s = String::new();
-    s.push_str(&format!("    component {} is\n", entity.name));
-    if !entity.ports.is_empty() {
-        s.push_str("        port (\n");
-        for (i, port) in entity.ports.iter().enumerate() {
-            let dir = match port.direction {
-                PortDirection::In => "in",
-                PortDirection::Out => "out",
-                PortDirection::Inout => "inout",
-                PortDirection::Buffer => "buffer",
-            };
-            let sep = if i + 1 < entity.ports.len() { ";" } else { "" };
-            s.push_str(&format!(
-                "            {} : {} {}{}",
-                port.name,
-                dir,
-                type_to_vhdl(&port.vhdl_type),
-                sep
-            ));
-            s.push('\n');
-        }
-        s.push_str("        );\n");
-    }
-    s.push_str(&format!("    end component {};\n", entity.name));
-    s
+    Emitter::default().component(entity, 1)
 }
 
-/// ポートに対応する信号宣言を生成
-fn gen_signals(ports: &[PortDef]) -> String {
-    let mut s = String::new();
-    for port in ports {
-        s.push_str(&format!(
-            "    signal {} : {} := {};\n",
-            port.name,
-            type_to_vhdl(&port.vhdl_type),
-            type_default_value(&port.vhdl_type)
-        ));
+/// DUTインスタンスを書き出す
+fn write_dut_instance(s: &mut String, instance: &TbInstance) {
+    let _ = writeln!(s, "    {}: {}", instance.label, instance.unit);
+    if !instance.generic_map.is_empty() {
+        s.push_str("        generic map (\n");
+        for (i, (name, value)) in instance.generic_map.iter().enumerate() {
+            let sep = if i + 1 < instance.generic_map.len() {
+                ","
+            } else {
+                ""
+            };
+            let _ = writeln!(s, "            {} => {}{}", name, value, sep);
+        }
+        s.push_str("        )\n");
     }
-    s
-}
-
-/// DUTインスタンスを生成
-fn gen_dut_instance(entity: &EntityDef) -> String {
-    let mut s = String::new();
-    s.push_str(&format!("    uut: {}\n", entity.name));
-    if !entity.ports.is_empty() {
+    if !instance.port_map.is_empty() {
         s.push_str("        port map (\n");
-        for (i, port) in entity.ports.iter().enumerate() {
-            let sep = if i + 1 < entity.ports.len() { "," } else { "" };
-            s.push_str(&format!(
-                "            {} => {}{}",
-                port.name, port.name, sep
-            ));
-            s.push('\n');
+        for (i, (formal, actual)) in instance.port_map.iter().enumerate() {
+            let sep = if i + 1 < instance.port_map.len() {
+                ","
+            } else {
+                ""
+            };
+            let _ = writeln!(s, "            {} => {}{}", formal, actual, sep);
         }
         s.push_str("        );\n");
     } else {
         s.push_str("    ;\n");
     }
-    s
+}
+
+/// プロセスを書き出す
+fn write_process(s: &mut String, process: &TbProcess, label: &str) {
+    if let Some(comment) = &process.comment {
+        let _ = writeln!(s, "    -- {}", comment);
+    }
+    if process.sensitivity.is_empty() {
+        let _ = writeln!(s, "    {}: process", label);
+    } else {
+        let _ = writeln!(
+            s,
+            "    {}: process({})",
+            label,
+            process.sensitivity.join(", ")
+        );
+    }
+    push_lines(s, &process.declarations, 2);
+    s.push_str("    begin\n");
+    push_lines(s, &process.body, 2);
+    let _ = writeln!(s, "    end process {};", label);
 }
 
 /// クロック生成プロセスを生成
-fn gen_clock_process(clk_name: &str, period_ns: u64) -> String {
+fn gen_clock_process(clk_name: &str, period_ns: u64) -> TbProcess {
     let half = period_ns / 2;
-    let mut s = String::new();
-    s.push_str(&format!("    -- クロック生成 (周期 {} ns)\n", period_ns));
-    s.push_str("    clk_process: process\n");
-    s.push_str("    begin\n");
-    s.push_str(&format!("        {} <= '0';\n", clk_name));
-    s.push_str(&format!("        wait for {} ns;\n", half));
-    s.push_str(&format!("        {} <= '1';\n", clk_name));
-    s.push_str(&format!("        wait for {} ns;\n", half));
-    s.push_str("    end process clk_process;\n");
-    s
+    let mut p = TbProcess::new("clk_process");
+    p.comment = Some(format!("クロック生成 (周期 {} ns)", period_ns));
+    p.body = vec![
+        format!("{} <= '0';", clk_name),
+        format!("wait for {} ns;", half),
+        format!("{} <= '1';", clk_name),
+        format!("wait for {} ns;", half),
+    ];
+    p
+}
+
+/// リセットをかけて解除する行
+pub(crate) fn reset_sequence(rst: &str, config: &TbConfig) -> Vec<String> {
+    let polarity = config.reset_polarity;
+    let wait = format!("wait for {} ns;", config.clock_period_ns * RESET_CYCLES);
+    vec![
+        format!("{} <= {};", rst, polarity.asserted()),
+        wait.clone(),
+        format!("{} <= {};", rst, polarity.released()),
+        wait,
+    ]
 }
 
 /// スティミュラスプロセスを生成
@@ -200,26 +928,37 @@ fn gen_stimulus_process(
     ports: &[PortDef],
     clk_name: Option<&str>,
     rst_name: Option<&str>,
-    period_ns: u64,
-) -> String {
-    let mut s = String::new();
-    s.push_str("    -- テストシナリオ\n");
-    s.push_str("    stim_process: process\n");
-    s.push_str("    begin\n");
+    config: &TbConfig,
+    done: &str,
+) -> TbProcess {
+    let period_ns = config.clock_period_ns;
+    let mut p = TbProcess::new("stim_process");
+    p.comment = Some("テストシナリオ".to_string());
+    let body = &mut p.body;
 
     // リセットシーケンス
     if let Some(rst) = rst_name {
-        s.push_str("        -- リセット\n");
-        s.push_str(&format!("        {} <= '1';\n", rst));
-        s.push_str(&format!("        wait for {} ns;\n", period_ns * 2));
-        s.push_str(&format!("        {} <= '0';\n", rst));
-        s.push_str(&format!("        wait for {} ns;\n", period_ns * 2));
-        s.push('\n');
+        body.push("-- リセット".to_string());
+        body.extend(reset_sequence(rst, config));
+        body.push(String::new());
     }
 
-    s.push_str("        -- TODO: テストパターンを記述\n");
-    s.push_str(&format!("        wait for {} ns;\n", period_ns * 10));
-    s.push('\n');
+    if config.fsms.is_empty() {
+        body.push("-- TODO: テストパターンを記述".to_string());
+        body.push(format!("wait for {} ns;", period_ns * TEST_CYCLES));
+        body.push(String::new());
+    }
+    for fsm in &config.fsms {
+        body.extend(fsm::scenario(
+            fsm,
+            ports,
+            clk_name,
+            rst_name,
+            period_ns,
+            RESET_CYCLES,
+            config.reset_polarity,
+        ));
+    }
 
     // 入力ポートの初期化例をコメントで示す
     let input_ports: Vec<&PortDef> = ports
@@ -232,21 +971,18 @@ fn gen_stimulus_process(
         .collect();
 
     if !input_ports.is_empty() {
-        s.push_str("        -- 入力信号の例:\n");
+        body.push("-- 入力信号の例:".to_string());
         for port in &input_ports {
-            s.push_str(&format!(
-                "        -- {} <= {};\n",
+            body.push(format!(
+                "-- {} <= {};",
                 port.name,
                 type_default_value(&port.vhdl_type)
             ));
         }
-        s.push_str(&format!("        -- wait for {} ns;\n", period_ns));
-        s.push('\n');
+        body.push(format!("-- wait for {} ns;", period_ns));
+        body.push(String::new());
     }
 
-    s.push_str("        -- シミュレーション終了\n");
-    s.push_str("        assert false report \"Simulation finished\" severity note;\n");
-    s.push_str("        wait;\n");
-    s.push_str("    end process stim_process;\n");
-    s
+    body.extend(simulation_end(ports, config, done));
+    p
 }
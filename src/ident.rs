@@ -0,0 +1,288 @@
+//! 生成する識別子の衝突回避
+//!
+//! テストベンチが作る名前（`uut` `stim_process` `sim_done` `<エンティティ>_tb` など）が
+//! DUT のポートや予約語と重ならないよう、決まった順に `_1` `_2` … を付けて名前を変えます。
+//! IP-XACT や JSON から読んだ予約語の名前（`end` など）は拡張識別子 `\end\` にします。
+//!
+//! ```
+//! use vig::ident::{Namer, escape};
+//!
+//! let mut names = Namer::new(["uut", "clk"]);
+//! assert_eq!(names.fresh("uut"), "uut_1");
+//! assert_eq!(names.fresh("stim_process"), "stim_process");
+//! assert_eq!(names.fresh("signal"), "signal_1");
+//! assert_eq!(escape("end"), "\\end\\");
+//! ```
+
+/// VHDL-2008 の予約語
+pub const RESERVED: [&str; 115] = [
+    "abs",
+    "access",
+    "after",
+    "alias",
+    "all",
+    "and",
+    "architecture",
+    "array",
+    "assert",
+    "assume",
+    "assume_guarantee",
+    "attribute",
+    "begin",
+    "block",
+    "body",
+    "buffer",
+    "bus",
+    "case",
+    "component",
+    "configuration",
+    "constant",
+    "context",
+    "cover",
+    "default",
+    "disconnect",
+    "downto",
+    "else",
+    "elsif",
+    "end",
+    "entity",
+    "exit",
+    "fairness",
+    "file",
+    "for",
+    "force",
+    "function",
+    "generate",
+    "generic",
+    "group",
+    "guarded",
+    "if",
+    "impure",
+    "in",
+    "inertial",
+    "inout",
+    "is",
+    "label",
+    "library",
+    "linkage",
+    "literal",
+    "loop",
+    "map",
+    "mod",
+    "nand",
+    "new",
+    "next",
+    "nor",
+    "not",
+    "null",
+    "of",
+    "on",
+    "open",
+    "or",
+    "others",
+    "out",
+    "package",
+    "parameter",
+    "port",
+    "postponed",
+    "procedure",
+    "process",
+    "property",
+    "protected",
+    "pure",
+    "range",
+    "record",
+    "register",
+    "reject",
+    "release",
+    "rem",
+    "report",
+    "restrict",
+    "restrict_guarantee",
+    "return",
+    "rol",
+    "ror",
+    "select",
+    "sequence",
+    "severity",
+    "shared",
+    "signal",
+    "sla",
+    "sll",
+    "sra",
+    "srl",
+    "strong",
+    "subtype",
+    "then",
+    "to",
+    "transport",
+    "type",
+    "unaffected",
+    "units",
+    "until",
+    "use",
+    "variable",
+    "vmode",
+    "vprop",
+    "vunit",
+    "wait",
+    "when",
+    "while",
+    "with",
+    "xnor",
+    "xor",
+];
+
+/// 予約語か（大文字小文字を区別しない）
+pub fn is_reserved(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    RESERVED.binary_search(&lower.as_str()).is_ok()
+}
+
+/// 基本識別子として書けるか（英字で始まり、英数字と単独の `_` だけで、`_` で終わらない）
+pub fn is_basic(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.contains("__")
+        && !name.ends_with('_')
+}
+
+/// 拡張識別子（`\bus-signal\`）か
+pub fn is_extended(name: &str) -> bool {
+    name.len() > 2 && name.starts_with('\\') && name.ends_with('\\')
+}
+
+/// 名前を比べるための正規形（基本識別子は小文字、拡張識別子は書いたまま）
+///
+/// ```
+/// use vig::ident::normalize;
+///
+/// assert_eq!(normalize("MyEnt"), "myent");
+/// assert_eq!(normalize("\\MyEnt\\"), "\\MyEnt\\");
+/// ```
+pub fn normalize(name: &str) -> String {
+    if is_extended(name) {
+        name.to_string()
+    } else {
+        name.to_ascii_lowercase()
+    }
+}
+
+/// 予約語や基本識別子として書けない名前なら拡張識別子にする（それ以外はそのまま）
+///
+/// 中の `\` は `\\` に重ねます。拡張識別子と空の名前はそのままです。
+///
+/// ```
+/// use vig::ident::escape;
+///
+/// assert_eq!(escape("Bus-Signal_tb"), "\\Bus-Signal_tb\\");
+/// assert_eq!(escape("\\Bus-Signal\\"), "\\Bus-Signal\\");
+/// ```
+pub fn escape(name: &str) -> String {
+    if name.is_empty() || is_extended(name) || (is_basic(name) && !is_reserved(name)) {
+        name.to_string()
+    } else {
+        format!("\\{}\\", name.replace('\\', "\\\\"))
+    }
+}
+
+/// 名前に接尾辞を付けた識別子（拡張識別子は中身に付けて、必要なら改めてエスケープする）
+///
+/// ```
+/// use vig::ident::suffixed;
+///
+/// assert_eq!(suffixed("count", "_value"), "count_value");
+/// assert_eq!(suffixed("\\in\\", "_value"), "in_value");
+/// assert_eq!(suffixed("\\Bus-Signal\\", "_value"), "\\Bus-Signal_value\\");
+/// ```
+pub fn suffixed(name: &str, suffix: &str) -> String {
+    let joined = format!("{}{}", bare(name), suffix);
+    if is_extended(name) && (!is_basic(&joined) || is_reserved(&joined)) {
+        format!("\\{}\\", joined)
+    } else {
+        escape(&joined)
+    }
+}
+
+/// 拡張識別子の `\` を外した名前
+pub fn bare(name: &str) -> &str {
+    if is_extended(name) {
+        &name[1..name.len() - 1]
+    } else {
+        name
+    }
+}
+
+/// ファイル名・ディレクトリ名に使える形（`\` を外し、英数字・`_`・`-` 以外を `_` にする）
+///
+/// ```
+/// use vig::ident::file_stem;
+///
+/// assert_eq!(file_stem("counter_tb"), "counter_tb");
+/// assert_eq!(file_stem("\\my top/tb\\"), "my_top_tb");
+/// ```
+pub fn file_stem(name: &str) -> String {
+    bare(name)
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 使用済みの名前と重ならない識別子を決める
+#[derive(Debug, Clone, Default)]
+pub struct Namer {
+    taken: Vec<String>,
+}
+
+impl Namer {
+    /// 使用済みの名前（DUT のポートなど）から作る
+    pub fn new<'a>(taken: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut namer = Self::default();
+        for name in taken {
+            namer.reserve(name);
+        }
+        namer
+    }
+
+    /// 名前を使用済みにする
+    pub fn reserve(&mut self, name: &str) {
+        self.taken.push(name.to_ascii_lowercase());
+    }
+
+    /// 使用済みか（大文字小文字を区別しない）
+    pub fn is_taken(&self, name: &str) -> bool {
+        let lower = name.to_ascii_lowercase();
+        self.taken.contains(&lower)
+    }
+
+    /// `base` が使えればそのまま、使えなければ `base_1` `base_2` … の最初の空きを返し、使用済みにする
+    pub fn fresh(&mut self, base: &str) -> String {
+        let usable = |name: &str| !self.is_taken(name) && !is_reserved(name);
+        let name = if usable(base) {
+            base.to_string()
+        } else {
+            (1..)
+                .map(|i| format!("{}_{}", base, i))
+                .find(|n| usable(n))
+                .unwrap_or_default()
+        };
+        self.reserve(&name);
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RESERVED;
+
+    #[test]
+    fn test_reserved_is_sorted() {
+        assert!(RESERVED.windows(2).all(|w| w[0] < w[1]));
+    }
+}
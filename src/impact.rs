@@ -0,0 +1,458 @@
+//! インターフェース変更の影響の解析
+//!
+//! 変更したエンティティを参照しているアーキテクチャ・コンポーネント宣言・インスタンスを
+//! 設計全体から探し、それぞれが新しいポートと合っているか（つないでいない入力・存在しない
+//! ポート・型や方向の違い）を調べます。テストベンチの中の参照はそれと分かるように印を付けます。
+//!
+//! ```
+//! use vig::analyzer::{EntityDef, VhdlType::*};
+//! use vig::impact::analyze_impact;
+//!
+//! let fifo = EntityDef::builder("fifo")
+//!     .input("clk", StdLogic)
+//!     .input("en", StdLogic)
+//!     .output("q", StdLogic)
+//!     .build();
+//! let top = "architecture rtl of top is
+//!     signal clk, q : std_logic;
+//! begin
+//!     u_fifo: entity work.fifo port map (clk => clk, q => q);
+//! end architecture;";
+//! let impact = analyze_impact(&fifo, &[("top.vhd", top)]);
+//! assert_eq!(impact.references.len(), 1);
+//! assert_eq!(impact.references[0].issues[0].port, "en");
+//! ```
+
+use crate::analyzer::{
+    AssociationDef, EntityDef, PortDef, PortDirection, VhdlType, analyze_vhdl, parse_type_str,
+};
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{Span, TokenKind};
+use crate::project::Language;
+use crate::testbench::{Component, Instance, Testbench, same_type};
+use crate::verilog;
+
+/// 参照の種類
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// エンティティのアーキテクチャ
+    Architecture,
+    /// 同じ名前のコンポーネント宣言
+    Component,
+    /// インスタンス（コンポーネント・エンティティの直接インスタンス・Verilog のモジュール）
+    Instance,
+}
+
+impl ReferenceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReferenceKind::Architecture => "architecture",
+            ReferenceKind::Component => "component",
+            ReferenceKind::Instance => "instance",
+        }
+    }
+}
+
+/// ポートの食い違いの種類
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortIssueKind {
+    /// エンティティにあるが、つないでいない入力（宣言ではエンティティにあるが宣言していないポート）
+    Missing,
+    /// エンティティにないポート
+    Extra,
+    /// 型が違う（`found` は宣言またはつないだ信号の型の表記）
+    Retyped { found: String },
+    /// 方向が違う（コンポーネント宣言）
+    Redirected { found: PortDirection },
+}
+
+/// ポートの食い違い
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortIssue {
+    pub port: String,
+    pub kind: PortIssueKind,
+    pub span: Span,
+}
+
+/// 参照1つ
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub kind: ReferenceKind,
+    /// 参照しているファイル（[`Impact::files`] の添字）
+    pub file: usize,
+    /// アーキテクチャ名・コンポーネント名・インスタンスのラベル
+    pub name: String,
+    /// 参照を含むアーキテクチャのエンティティ（パッケージの中のコンポーネント宣言は None）
+    pub owner: Option<String>,
+    /// テストベンチの中の参照か（ポートのないエンティティ、または名前が `_tb` で終わるか
+    /// `tb_` で始まるエンティティのアーキテクチャ）
+    pub in_testbench: bool,
+    pub span: Span,
+    /// 新しいポートとの食い違い（空なら互換）
+    pub issues: Vec<PortIssue>,
+}
+
+impl Reference {
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 食い違いを診断にする（位置はこの参照のファイル上）
+    pub fn diagnostics(&self, entity: &EntityDef) -> Vec<Diagnostic> {
+        self.issues
+            .iter()
+            .map(|issue| {
+                let port = entity
+                    .ports
+                    .iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(&issue.port));
+                let message = match (&issue.kind, port) {
+                    (PortIssueKind::Missing, _) if self.kind == ReferenceKind::Component => {
+                        format!(
+                            "port '{}' of '{}' is not declared in the component",
+                            issue.port, entity.name
+                        )
+                    }
+                    (PortIssueKind::Missing, _) => format!(
+                        "input port '{}' of '{}' is not connected",
+                        issue.port, entity.name
+                    ),
+                    (PortIssueKind::Extra, _) => {
+                        format!("'{}' has no port '{}'", entity.name, issue.port)
+                    }
+                    (PortIssueKind::Retyped { found }, Some(port)) => format!(
+                        "port '{}' of '{}' is {} but {} is {}",
+                        issue.port,
+                        entity.name,
+                        port.vhdl_type,
+                        if self.kind == ReferenceKind::Component {
+                            "the declaration"
+                        } else {
+                            "the connected signal"
+                        },
+                        found
+                    ),
+                    (PortIssueKind::Redirected { found }, Some(port)) => format!(
+                        "port '{}' of '{}' is {} but declared as {}",
+                        issue.port, entity.name, port.direction, found
+                    ),
+                    _ => format!("port '{}' does not match '{}'", issue.port, entity.name),
+                };
+                Diagnostic::error(message, issue.span)
+            })
+            .collect()
+    }
+}
+
+/// 影響の解析の結果
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Impact {
+    /// ファイルのパス（与えた順）
+    pub files: Vec<String>,
+    /// 参照（ファイルの順、ファイルの中では出てきた順）
+    pub references: Vec<Reference>,
+}
+
+impl Impact {
+    /// 新しいポートと合わない参照
+    pub fn incompatible(&self) -> Vec<&Reference> {
+        self.references
+            .iter()
+            .filter(|r| !r.is_compatible())
+            .collect()
+    }
+}
+
+/// 変更後のエンティティを参照している箇所をファイル（パス, ソース）の組から探す
+///
+/// Verilog のファイルはモジュール本体のインスタンスを挙げるだけで、接続は調べません。
+pub fn analyze_impact(entity: &EntityDef, files: &[(&str, &str)]) -> Impact {
+    // テストベンチの判定に使う、ポートのないエンティティ
+    let portless: Vec<String> = files
+        .iter()
+        .filter(|(path, _)| Language::from_path(path) == Language::Vhdl)
+        .filter_map(|(_, source)| analyze_vhdl(source).ok())
+        .flat_map(|result| result.entities)
+        .filter(|e| e.ports.is_empty())
+        .map(|e| e.name)
+        .collect();
+    let is_testbench = |owner: &str| {
+        let lower = owner.to_lowercase();
+        lower.ends_with("_tb")
+            || lower.starts_with("tb_")
+            || portless.iter().any(|p| p.eq_ignore_ascii_case(owner))
+    };
+
+    let mut impact = Impact::default();
+    for (file, (path, source)) in files.iter().enumerate() {
+        impact.files.push(path.to_string());
+        if Language::from_path(path) == Language::Verilog {
+            for module in verilog::parse_modules(source).unwrap_or_default() {
+                for instance in module
+                    .instances
+                    .iter()
+                    .filter(|i| i.unit.eq_ignore_ascii_case(&entity.name))
+                {
+                    impact.references.push(Reference {
+                        kind: ReferenceKind::Instance,
+                        file,
+                        name: instance.label.clone(),
+                        in_testbench: is_testbench(&module.name),
+                        owner: Some(module.name.clone()),
+                        span: instance.span,
+                        issues: Vec::new(),
+                    });
+                }
+            }
+            continue;
+        }
+
+        let tb = Testbench::parse(source);
+        let architectures = architectures(&tb);
+        let packages = packages(&tb);
+        // 直前のアーキテクチャ（その後にパッケージが始まっていれば None）
+        let owner_at = |offset: usize| {
+            let (start, _, owner, _) = architectures
+                .iter()
+                .rev()
+                .find(|(start, ..)| *start <= offset)?;
+            (!packages.iter().any(|p| (*start..=offset).contains(p))).then(|| owner.clone())
+        };
+        let mut found = Vec::new();
+        for (start, name, owner, span) in &architectures {
+            if owner.eq_ignore_ascii_case(&entity.name) {
+                found.push((
+                    *start,
+                    Reference {
+                        kind: ReferenceKind::Architecture,
+                        file,
+                        name: name.clone(),
+                        owner: Some(owner.clone()),
+                        in_testbench: false,
+                        span: *span,
+                        issues: Vec::new(),
+                    },
+                ));
+            }
+        }
+        for component in tb
+            .components
+            .iter()
+            .filter(|c| c.name.eq_ignore_ascii_case(&entity.name))
+        {
+            let owner = owner_at(component.span.start);
+            found.push((
+                component.span.start,
+                Reference {
+                    kind: ReferenceKind::Component,
+                    file,
+                    name: component.name.clone(),
+                    in_testbench: owner.as_deref().is_some_and(is_testbench),
+                    owner,
+                    span: component.name_span,
+                    issues: component_issues(component, entity),
+                },
+            ));
+        }
+        for instance in tb
+            .instances
+            .iter()
+            .filter(|i| i.unit().eq_ignore_ascii_case(&entity.name))
+        {
+            let owner = owner_at(instance.label_span.start);
+            found.push((
+                instance.label_span.start,
+                Reference {
+                    kind: ReferenceKind::Instance,
+                    file,
+                    name: instance.def.label.clone(),
+                    in_testbench: owner.as_deref().is_some_and(is_testbench),
+                    owner,
+                    span: instance.label_span,
+                    issues: instance_issues(instance, entity, &tb, source),
+                },
+            ));
+        }
+        found.sort_by_key(|(start, _)| *start);
+        impact.references.extend(found.into_iter().map(|(_, r)| r));
+    }
+    impact
+}
+
+/// `architecture A of E` の（位置, アーキテクチャ名, エンティティ名, 名前の位置）
+fn architectures(tb: &Testbench) -> Vec<(usize, String, String, Span)> {
+    tb.tokens
+        .windows(4)
+        .filter(|w| {
+            w[0].kind == TokenKind::Architecture
+                && w[1].kind == TokenKind::Identifier
+                && w[2].kind == TokenKind::Of
+                && w[3].kind == TokenKind::Identifier
+        })
+        .map(|w| {
+            (
+                w[0].span.start,
+                w[1].text.to_string(),
+                w[3].text.to_string(),
+                w[1].span,
+            )
+        })
+        .collect()
+}
+
+/// `package` の位置（`end package` は除く）
+fn packages(tb: &Testbench) -> Vec<usize> {
+    tb.tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| {
+            t.kind == TokenKind::Package && (*i == 0 || tb.tokens[i - 1].kind != TokenKind::End)
+        })
+        .map(|(_, t)| t.span.start)
+        .collect()
+}
+
+/// コンポーネント宣言とエンティティのポートの食い違い
+fn component_issues(component: &Component, entity: &EntityDef) -> Vec<PortIssue> {
+    let mut issues = Vec::new();
+    for group in &component.groups {
+        for (name, span) in &group.names {
+            let Some(port) = find_port(entity, name) else {
+                issues.push(PortIssue {
+                    port: name.clone(),
+                    kind: PortIssueKind::Extra,
+                    span: *span,
+                });
+                continue;
+            };
+            if port.direction != group.direction {
+                issues.push(PortIssue {
+                    port: port.name.clone(),
+                    kind: PortIssueKind::Redirected {
+                        found: group.direction.clone(),
+                    },
+                    span: group.span,
+                });
+            } else if is_retyped(&group.type_text, &port.vhdl_type) {
+                issues.push(PortIssue {
+                    port: port.name.clone(),
+                    kind: PortIssueKind::Retyped {
+                        found: group.type_text.clone(),
+                    },
+                    span: group.span,
+                });
+            }
+        }
+    }
+    for port in &entity.ports {
+        let declared = component
+            .groups
+            .iter()
+            .flat_map(|g| &g.names)
+            .any(|(name, _)| name.eq_ignore_ascii_case(&port.name));
+        if !declared {
+            issues.push(PortIssue {
+                port: port.name.clone(),
+                kind: PortIssueKind::Missing,
+                span: component.name_span,
+            });
+        }
+    }
+    issues
+}
+
+/// インスタンスのポートマップとエンティティのポートの食い違い
+fn instance_issues(
+    instance: &Instance,
+    entity: &EntityDef,
+    tb: &Testbench,
+    source: &str,
+) -> Vec<PortIssue> {
+    let mut issues = Vec::new();
+    let mut connected: Vec<&PortDef> = Vec::new();
+    for (position, assoc) in instance.def.port_map.iter().enumerate() {
+        let port = match assoc.formal_name() {
+            Some(formal) => find_port(entity, formal),
+            None => entity.ports.get(position),
+        };
+        let Some(port) = port else {
+            issues.push(PortIssue {
+                port: assoc
+                    .formal_name()
+                    .map_or_else(|| format!("#{}", position + 1), str::to_string),
+                kind: PortIssueKind::Extra,
+                span: assoc.span,
+            });
+            continue;
+        };
+        connected.push(port);
+        if let Some(found) = connected_type(assoc, tb, source)
+            && is_retyped(&found, &port.vhdl_type)
+        {
+            issues.push(PortIssue {
+                port: port.name.clone(),
+                kind: PortIssueKind::Retyped { found },
+                span: assoc.span,
+            });
+        }
+    }
+    for port in &entity.ports {
+        let is_input = matches!(port.direction, PortDirection::In | PortDirection::Inout);
+        if is_input && !connected.iter().any(|p| p.name == port.name) {
+            issues.push(PortIssue {
+                port: port.name.clone(),
+                kind: PortIssueKind::Missing,
+                span: instance.label_span,
+            });
+        }
+    }
+    issues
+}
+
+/// 単純な名前でつないだ信号の宣言の型（部分的な関連付けや式、`open` は調べない）
+fn connected_type(assoc: &AssociationDef, tb: &Testbench, source: &str) -> Option<String> {
+    let text = &source[assoc.span.start..assoc.span.end];
+    // data(0) => ...
+    if text
+        .split("=>")
+        .next()
+        .is_some_and(|formal| formal.contains('('))
+        && assoc.formal.is_some()
+    {
+        return None;
+    }
+    let (decl, _) = tb.signal(assoc.actual.trim())?;
+    Some(
+        source[decl.type_span.start..decl.type_span.end]
+            .trim()
+            .to_string(),
+    )
+}
+
+/// 型が違うと言い切れるか（制約が式の型は、型の名前が違うときだけ）
+fn is_retyped(found: &str, vhdl_type: &VhdlType) -> bool {
+    if same_type(found, vhdl_type) {
+        return false;
+    }
+    let base = |text: &str| {
+        text.split('(')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+    };
+    parse_type_str(found).is_ok_and(|t| !t.is_symbolic())
+        || base(found) != base(&vhdl_type.to_string())
+}
+
+fn find_port<'a>(entity: &'a EntityDef, name: &str) -> Option<&'a PortDef> {
+    entity
+        .ports
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+}
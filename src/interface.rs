@@ -0,0 +1,546 @@
+//! エンティティのインターフェースを他ツールと交換するための中立フォーマット
+//!
+//! VHDLを解析せずにVigと連携できるよう、ジェネリック・ポート・クロック・リセット・バスを
+//! JSONで表現します（JSONはYAML 1.2のサブセットなので、そのままYAMLとしても読めます）。
+//!
+//! ```json
+//! {
+//!   "vig_interface": 1,
+//!   "name": "uart_tx",
+//!   "generics": [{ "name": "WIDTH", "type": { "name": "positive" }, "default": "8" }],
+//!   "ports": [
+//!     { "name": "clk", "direction": "in", "type": { "name": "std_logic" } },
+//!     { "name": "tx_data", "direction": "in",
+//!       "type": { "name": "std_logic_vector", "high": "WIDTH - 1", "low": 0 },
+//!       "bus": "tx", "role": "data" }
+//!   ],
+//!   "clocks": ["clk"],
+//!   "resets": [{ "name": "reset", "active": "high" }],
+//!   "buses": [{ "name": "tx", "ports": ["tx_start", "tx_data"] }]
+//! }
+//! ```
+//!
+//! - `direction`: `in` / `out` / `inout` / `buffer`
+//! - `type.name`: `std_logic` / `std_logic_vector`（`high`・`low` 必須。`to` の範囲は `"ascending": true`）/
+//!   `integer`（`range` の制約は `left`・`right`・`ascending`）/ `boolean` / その他の型名
+//! - `generics[].default`: 既定値のVHDLの式（省略可能）。範囲の式はこの既定値で評価できます
+//! - `bus`・`role`: ポートが属するバスとバスの中での役割（省略時の役割はバス名のプレフィックスを除いた名前）
+//! - `generics`・`clocks`・`resets`・`buses` は省略可能
+//!
+//! 完全なスキーマは `schema/vig-interface.schema.json` を参照してください。
+
+use crate::analyzer::{
+    BoundExpr, EntityDef, GenericDef, PortDef, PortDirection, RangeConstraint, VhdlType,
+};
+use crate::expr::Expr;
+use crate::generator::{direction_to_vhdl, find_clock_port, find_reset_port};
+use crate::json::{self, Value};
+use crate::lexer::Span;
+
+/// フォーマットのバージョン
+pub const SCHEMA_VERSION: i64 = 1;
+
+/// リセット信号の定義
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResetDef {
+    pub name: String,
+    pub active_high: bool,
+}
+
+/// 共通プレフィックスでまとめたポート群
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusDef {
+    pub name: String,
+    pub ports: Vec<BusPort>,
+}
+
+/// バスに属するポートとバスの中での役割（`tx_data` なら `data`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusPort {
+    pub name: String,
+    pub role: String,
+}
+
+impl BusPort {
+    /// ポート名からバス名のプレフィックスを除いたものを役割とする
+    pub fn new(bus: &str, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let role = name
+            .get(..bus.len() + 1)
+            .filter(|head| head.eq_ignore_ascii_case(&format!("{}_", bus)))
+            .map_or(name.as_str(), |head| &name[head.len()..])
+            .to_string();
+        Self { name, role }
+    }
+}
+
+impl BusDef {
+    /// `port` がこのバスに属するか
+    pub fn contains(&self, port: &str) -> bool {
+        self.ports.iter().any(|p| p.name == port)
+    }
+}
+
+/// 交換フォーマットで表現されるインターフェース
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceDef {
+    pub entity: EntityDef,
+    pub clocks: Vec<String>,
+    pub resets: Vec<ResetDef>,
+    pub buses: Vec<BusDef>,
+}
+
+/// 交換フォーマットの読み込みエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl InterfaceError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for InterfaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for InterfaceError {}
+
+impl From<json::JsonError> for InterfaceError {
+    fn from(err: json::JsonError) -> Self {
+        Self::new(err.message, err.span)
+    }
+}
+
+/// 負論理を表す接尾辞（`rst_n` `reset_b` など）
+const ACTIVE_LOW_SUFFIXES: &[&str] = &["_n", "_b", "_l", "_ni"];
+
+/// リセットの名前が負論理の命名規則に従っているか
+///
+/// `_n` `_b` `_l` `_ni` の接尾辞、`rstn` `aresetn` のように `rst`/`reset` の直後の `n`、
+/// `nrst` `n_reset` のように `rst`/`reset` の直前の `n` を負論理とみなします
+/// （`rst_in` `reset_main` `rst_gen` のように途中や別の語の末尾にある `n` は見ません）。
+fn is_active_low_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    let negated_prefix = name
+        .strip_prefix('n')
+        .map(|rest| rest.strip_prefix('_').unwrap_or(rest))
+        .is_some_and(|rest| rest.starts_with("rst") || rest.starts_with("reset"));
+    ACTIVE_LOW_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || name.ends_with("rstn")
+        || name.ends_with("resetn")
+        || negated_prefix
+}
+
+impl InterfaceDef {
+    /// EntityDefからクロック・リセット・バスを推定して作成
+    pub fn from_entity(entity: &EntityDef) -> Self {
+        let clocks: Vec<String> = find_clock_port(&entity.ports).into_iter().collect();
+        let resets: Vec<ResetDef> = find_reset_port(&entity.ports)
+            .into_iter()
+            .map(|name| ResetDef {
+                active_high: !is_active_low_name(&name),
+                name,
+            })
+            .collect();
+
+        // クロック・リセット以外を "<prefix>_" でグループ化（2ポート以上のもの）
+        let mut buses: Vec<BusDef> = Vec::new();
+        for port in &entity.ports {
+            if clocks.contains(&port.name) || resets.iter().any(|r| r.name == port.name) {
+                continue;
+            }
+            let Some((prefix, _)) = port.name.split_once('_') else {
+                continue;
+            };
+            match buses.iter_mut().find(|b| b.name == prefix) {
+                Some(bus) => bus.ports.push(BusPort::new(prefix, &port.name)),
+                None => buses.push(BusDef {
+                    name: prefix.to_string(),
+                    ports: vec![BusPort::new(prefix, &port.name)],
+                }),
+            }
+        }
+        buses.retain(|b| b.ports.len() >= 2);
+
+        Self {
+            entity: entity.clone(),
+            clocks,
+            resets,
+            buses,
+        }
+    }
+
+    /// JSON値に変換
+    pub(crate) fn to_value(&self) -> Value {
+        let generics = self
+            .entity
+            .generics
+            .iter()
+            .map(|g| {
+                let mut members = vec![
+                    ("name".into(), g.name.as_str().into()),
+                    ("type".into(), type_to_value(&g.vhdl_type)),
+                ];
+                if let Some(default) = &g.default_value {
+                    members.push(("default".into(), default.to_string().into()));
+                }
+                Value::Object(members)
+            })
+            .collect();
+        let ports = self
+            .entity
+            .ports
+            .iter()
+            .map(|p| {
+                let mut members = vec![
+                    ("name".into(), p.name.as_str().into()),
+                    ("direction".into(), direction_to_vhdl(&p.direction).into()),
+                    ("type".into(), type_to_value(&p.vhdl_type)),
+                ];
+                let member = self.buses.iter().find_map(|b| {
+                    let port = b.ports.iter().find(|bp| bp.name == p.name)?;
+                    Some((b, port))
+                });
+                if let Some((bus, port)) = member {
+                    members.push(("bus".into(), bus.name.as_str().into()));
+                    members.push(("role".into(), port.role.as_str().into()));
+                }
+                Value::Object(members)
+            })
+            .collect();
+        let clocks = self.clocks.iter().map(|c| c.as_str().into()).collect();
+        let resets = self
+            .resets
+            .iter()
+            .map(|r| {
+                Value::Object(vec![
+                    ("name".into(), r.name.as_str().into()),
+                    (
+                        "active".into(),
+                        if r.active_high { "high" } else { "low" }.into(),
+                    ),
+                ])
+            })
+            .collect();
+        let buses = self
+            .buses
+            .iter()
+            .map(|b| {
+                Value::Object(vec![
+                    ("name".into(), b.name.as_str().into()),
+                    (
+                        "ports".into(),
+                        Value::Array(b.ports.iter().map(|p| p.name.as_str().into()).collect()),
+                    ),
+                ])
+            })
+            .collect();
+
+        Value::Object(vec![
+            ("vig_interface".into(), SCHEMA_VERSION.into()),
+            ("name".into(), self.entity.name.as_str().into()),
+            ("generics".into(), Value::Array(generics)),
+            ("ports".into(), Value::Array(ports)),
+            ("clocks".into(), Value::Array(clocks)),
+            ("resets".into(), Value::Array(resets)),
+            ("buses".into(), Value::Array(buses)),
+        ])
+    }
+
+    /// JSON文字列に変換（末尾改行付き）
+    pub fn to_json(&self) -> String {
+        let mut s = self.to_value().to_pretty_string();
+        s.push('\n');
+        s
+    }
+
+    /// JSON文字列から読み込む
+    pub fn from_json(source: &str) -> Result<Self, InterfaceError> {
+        let root = json::parse(source)?;
+        Self::from_value(&root)
+    }
+
+    /// JSON値から読み込む
+    pub(crate) fn from_value(root: &Value) -> Result<Self, InterfaceError> {
+        let span = Span::new(0, 0);
+        let err = |msg: String| InterfaceError::new(msg, span);
+
+        match root.get("vig_interface").and_then(Value::as_i64) {
+            Some(SCHEMA_VERSION) => {}
+            Some(v) => return Err(err(format!("unsupported vig_interface version {}", v))),
+            None => return Err(err("missing 'vig_interface' version".to_string())),
+        }
+
+        let name = root
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| err("missing 'name'".to_string()))?
+            .to_string();
+
+        let mut generics = Vec::new();
+        for generic in root
+            .get("generics")
+            .and_then(Value::as_array)
+            .unwrap_or(&[])
+        {
+            let generic_name = generic
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| err("generic without 'name'".to_string()))?;
+            let vhdl_type = generic
+                .get("type")
+                .ok_or_else(|| err(format!("generic '{}' has no 'type'", generic_name)))
+                .and_then(|t| {
+                    value_to_type(t)
+                        .ok_or_else(|| err(format!("invalid type on generic '{}'", generic_name)))
+                })?;
+            // 既定値はVHDLの式の文字列（整数も受け付ける）
+            let default_value = match generic.get("default") {
+                None | Some(Value::Null) => None,
+                Some(value) => {
+                    let text = match value.as_i64() {
+                        Some(n) => n.to_string(),
+                        None => value.as_str().map(str::to_string).ok_or_else(|| {
+                            err(format!("invalid default on generic '{}'", generic_name))
+                        })?,
+                    };
+                    Some(text.parse::<Expr>().map_err(|e| {
+                        err(format!(
+                            "invalid default on generic '{}': {}",
+                            generic_name, e.message
+                        ))
+                    })?)
+                }
+            };
+            generics.push(GenericDef::new(generic_name, vhdl_type, default_value));
+        }
+
+        let mut ports = Vec::new();
+        // ポートに書かれたバスと役割（"buses" より優先する）
+        let mut port_buses: Vec<(String, BusPort)> = Vec::new();
+        for port in root.get("ports").and_then(Value::as_array).unwrap_or(&[]) {
+            let port_name = port
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| err("port without 'name'".to_string()))?;
+            let direction = match port.get("direction").and_then(Value::as_str) {
+                Some("in") => PortDirection::In,
+                Some("out") => PortDirection::Out,
+                Some("inout") => PortDirection::Inout,
+                Some("buffer") => PortDirection::Buffer,
+                other => {
+                    return Err(err(format!(
+                        "invalid direction {:?} on port '{}'",
+                        other, port_name
+                    )));
+                }
+            };
+            let vhdl_type = port
+                .get("type")
+                .ok_or_else(|| err(format!("port '{}' has no 'type'", port_name)))
+                .and_then(|t| {
+                    value_to_type(t)
+                        .ok_or_else(|| err(format!("invalid type on port '{}'", port_name)))
+                })?;
+            if let Some(bus) = port.get("bus") {
+                let bus_name = bus
+                    .as_str()
+                    .ok_or_else(|| err(format!("invalid bus on port '{}'", port_name)))?;
+                let mut member = BusPort::new(bus_name, port_name);
+                if let Some(role) = port.get("role") {
+                    member.role = role
+                        .as_str()
+                        .ok_or_else(|| err(format!("invalid role on port '{}'", port_name)))?
+                        .to_string();
+                }
+                port_buses.push((bus_name.to_string(), member));
+            }
+            ports.push(PortDef {
+                name: port_name.to_string(),
+                direction,
+                vhdl_type,
+                span,
+            });
+        }
+
+        let string_list = |v: &Value| -> Vec<String> {
+            v.as_array()
+                .unwrap_or(&[])
+                .iter()
+                .filter_map(|s| s.as_str().map(str::to_string))
+                .collect()
+        };
+
+        let clocks = root.get("clocks").map(string_list).unwrap_or_default();
+        let mut resets = Vec::new();
+        for reset in root.get("resets").and_then(Value::as_array).unwrap_or(&[]) {
+            let reset_name = reset
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| err("reset without 'name'".to_string()))?;
+            let active_high = match reset.get("active").and_then(Value::as_str) {
+                None | Some("high") => true,
+                Some("low") => false,
+                Some(other) => return Err(err(format!("invalid reset level '{}'", other))),
+            };
+            resets.push(ResetDef {
+                name: reset_name.to_string(),
+                active_high,
+            });
+        }
+        let mut buses = Vec::new();
+        for bus in root.get("buses").and_then(Value::as_array).unwrap_or(&[]) {
+            let bus_name = bus
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| err("bus without 'name'".to_string()))?;
+            let members = bus.get("ports").map(string_list).unwrap_or_default();
+            buses.push(BusDef {
+                name: bus_name.to_string(),
+                ports: members
+                    .into_iter()
+                    .map(|p| BusPort::new(bus_name, p))
+                    .collect(),
+            });
+        }
+        for (bus_name, member) in port_buses {
+            let index = match buses.iter().position(|b| b.name == bus_name) {
+                Some(i) => i,
+                None => {
+                    buses.push(BusDef {
+                        name: bus_name,
+                        ports: Vec::new(),
+                    });
+                    buses.len() - 1
+                }
+            };
+            let bus = &mut buses[index];
+            match bus.ports.iter_mut().find(|p| p.name == member.name) {
+                Some(existing) => *existing = member,
+                None => bus.ports.push(member),
+            }
+        }
+
+        // 参照先のポートが存在するか確認
+        let referenced = clocks
+            .iter()
+            .chain(resets.iter().map(|r| &r.name))
+            .chain(buses.iter().flat_map(|b| b.ports.iter().map(|p| &p.name)));
+        for name in referenced {
+            if !ports.iter().any(|p| &p.name == name) {
+                return Err(err(format!("reference to unknown port '{}'", name)));
+            }
+        }
+
+        Ok(Self {
+            entity: EntityDef {
+                name,
+                generics,
+                ports,
+                span,
+            },
+            clocks,
+            resets,
+            buses,
+        })
+    }
+}
+
+pub(crate) fn type_to_value(vhdl_type: &VhdlType) -> Value {
+    let mut members = vec![("name".into(), vhdl_type.base_name().into())];
+    if let Some((high, low)) = vhdl_type.vector_range() {
+        members.push(("high".into(), bound_to_value(high)));
+        members.push(("low".into(), bound_to_value(low)));
+        if vhdl_type.is_ascending() {
+            members.push(("ascending".into(), true.into()));
+        }
+    } else if let VhdlType::Str(Some((left, right))) = vhdl_type {
+        members.push(("left".into(), bound_to_value(left)));
+        members.push(("right".into(), bound_to_value(right)));
+    } else if let Some(range) = vhdl_type.scalar_range() {
+        members.push(("left".into(), bound_to_value(&range.left)));
+        members.push(("right".into(), bound_to_value(&range.right)));
+        if range.ascending {
+            members.push(("ascending".into(), true.into()));
+        }
+    }
+    Value::Object(members)
+}
+
+/// 境界は整数、名前を含む式は文字列（`"WIDTH - 1"`）
+fn bound_to_value(bound: &BoundExpr) -> Value {
+    match bound {
+        BoundExpr::Literal(n) => (*n).into(),
+        expr => expr.to_string().into(),
+    }
+}
+
+fn value_to_bound(value: &Value) -> Option<BoundExpr> {
+    match value.as_i64() {
+        Some(n) => Some(n.into()),
+        None => value.as_str()?.parse().ok(),
+    }
+}
+
+fn value_to_type(value: &Value) -> Option<VhdlType> {
+    let name = value.get("name")?.as_str()?;
+    let bound = |key: &str| value_to_bound(value.get(key)?);
+    let ascending = value
+        .get("ascending")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let range = bound("left")
+        .zip(bound("right"))
+        .map(|(left, right)| RangeConstraint {
+            left,
+            right,
+            ascending,
+        });
+    Some(match name.to_lowercase().as_str() {
+        "std_logic" => VhdlType::StdLogic,
+        "std_logic_vector" => VhdlType::StdLogicVector {
+            high: bound("high")?,
+            low: bound("low")?,
+            ascending,
+        },
+        "signed" => VhdlType::Signed {
+            high: bound("high")?,
+            low: bound("low")?,
+            ascending,
+        },
+        "unsigned" => VhdlType::Unsigned {
+            high: bound("high")?,
+            low: bound("low")?,
+            ascending,
+        },
+        "bit_vector" => VhdlType::BitVector {
+            high: bound("high")?,
+            low: bound("low")?,
+            ascending,
+        },
+        "string" => VhdlType::Str(bound("left").zip(bound("right"))),
+        "integer" => VhdlType::Integer(range),
+        "boolean" => VhdlType::Boolean,
+        "natural" => VhdlType::Natural(range),
+        "positive" => VhdlType::Positive(range),
+        "real" => VhdlType::Real,
+        "time" => VhdlType::Time,
+        "bit" => VhdlType::Bit,
+        _ => VhdlType::Other(name.to_string()),
+    })
+}
@@ -0,0 +1,760 @@
+//! 名前のインターンとアリーナ上の解析結果
+//!
+//! [`AnalyzeResult`] はポート名・信号名・型名をそれぞれ `String` で持つため、ネットリスト規模の
+//! 入力では同じ名前の複製でメモリを使い、名前の比較も文字列比較になります。
+//! [`InternedAst`] は名前を [`Interner`] で1つにまとめて [`Symbol`]（整数）で参照し、
+//! ポートと信号を連続した配列（アリーナ）に置きます。名前の比較は整数比較で済み、
+//! VHDL の識別子として大文字小文字を区別しない比較もキーの [`Symbol`] で行えます。
+//!
+//! 所有型の [`AnalyzeResult`] とは [`InternedAst::from_result`] と [`InternedAst::to_result`] で
+//! 相互に変換できます。
+//!
+//! ```
+//! use vig::intern::InternedAst;
+//!
+//! let ast = InternedAst::analyze(
+//!     "entity a is port (clk : in std_logic; q : out std_logic); end entity;\n\
+//!      entity b is port (CLK : in std_logic); end entity;",
+//! )
+//! .unwrap();
+//! let a = ast.entity("A").unwrap();
+//! let b = ast.entity("b").unwrap();
+//! // `clk` と `CLK` は綴りは別だがキーは同じ
+//! assert_ne!(ast.ports(a)[0].name.text, ast.ports(b)[0].name.text);
+//! assert_eq!(ast.ports(a)[0].name.key, ast.ports(b)[0].name.key);
+//! assert_eq!(ast.to_result().entities[0].ports[1].name, "q");
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::analyzer::{
+    AnalyzeError, AnalyzeResult, ArchitectureDef, AssociationDef, BoundExpr, ConstantDef,
+    EntityDef, GenericDef, InstanceDef, InstantiatedUnit, PackageDef, PortDef, PortDirection,
+    ProcessDef, RangeConstraint, SignalDef, SubprogramDecl, TypeDef, VhdlType, analyze_vhdl,
+};
+use crate::expr::Expr;
+use crate::lexer::Span;
+
+/// インターンした文字列の番号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// 番号（インターンした順に 0 から）
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 文字列を重複なく保持し、[`Symbol`] で引けるようにする
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    map: HashMap<Arc<str>, Symbol>,
+    strings: Vec<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 文字列をインターンする（同じ文字列には同じ番号を返す）
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.map.get(text) {
+            return symbol;
+        }
+        let symbol =
+            Symbol(u32::try_from(self.strings.len()).expect("more than u32::MAX interned strings"));
+        let text: Arc<str> = Arc::from(text);
+        self.strings.push(text.clone());
+        self.map.insert(text, symbol);
+        symbol
+    }
+
+    /// インターン済みなら番号を返す
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.map.get(text).copied()
+    }
+
+    /// 番号の文字列
+    ///
+    /// 別の `Interner` の番号を渡すとパニックするか、別の文字列を返します。
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.index()]
+    }
+
+    /// インターンした文字列の数
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// 識別子（綴りと、大文字小文字を区別しない比較用の小文字のキー）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Name {
+    pub text: Symbol,
+    pub key: Symbol,
+}
+
+/// 型参照（[`VhdlType`] の名前をインターンしたもの）
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeNode {
+    StdLogic,
+    StdLogicVector {
+        high: BoundNode,
+        low: BoundNode,
+        ascending: bool,
+    },
+    Integer(Option<RangeNode>),
+    Boolean,
+    Signed {
+        high: BoundNode,
+        low: BoundNode,
+        ascending: bool,
+    },
+    Unsigned {
+        high: BoundNode,
+        low: BoundNode,
+        ascending: bool,
+    },
+    Natural(Option<RangeNode>),
+    Positive(Option<RangeNode>),
+    Real,
+    Time,
+    Bit,
+    BitVector {
+        high: BoundNode,
+        low: BoundNode,
+        ascending: bool,
+    },
+    Str(Option<(BoundNode, BoundNode)>),
+    Other(Symbol),
+}
+
+/// ベクタの範囲の境界（[`BoundExpr`] の式をインターンしたもの）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundNode {
+    Literal(i64),
+    /// 名前を含む式の表記（`WIDTH - 1` など）
+    Expr(Symbol),
+}
+
+/// 整数型の範囲の制約（[`RangeConstraint`] の境界をインターンしたもの）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeNode {
+    pub left: BoundNode,
+    pub right: BoundNode,
+    pub ascending: bool,
+}
+
+/// ポート
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortNode {
+    pub name: Name,
+    pub direction: PortDirection,
+    pub vhdl_type: TypeNode,
+    pub span: Span,
+}
+
+/// ジェネリック
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenericNode {
+    pub name: Name,
+    pub vhdl_type: TypeNode,
+    /// 式の表記（[`Expr`] の表示）
+    pub default_value: Option<Symbol>,
+    pub span: Span,
+}
+
+/// 信号
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalNode {
+    pub name: Name,
+    pub vhdl_type: TypeNode,
+    /// 式の表記（[`Expr`] の表示）
+    pub default_value: Option<Symbol>,
+    pub span: Span,
+}
+
+/// 定数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstantNode {
+    pub name: Name,
+    pub vhdl_type: TypeNode,
+    /// 式の表記（[`Expr`] の表示）
+    pub value: Option<Symbol>,
+    pub span: Span,
+}
+
+/// インスタンス化する設計単位
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitNode {
+    Component(Name),
+    Entity {
+        library: Option<Name>,
+        name: Name,
+        architecture: Option<Name>,
+    },
+}
+
+/// generic map・port map の関連付け
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssociationNode {
+    pub formal: Option<Symbol>,
+    pub actual: Symbol,
+    pub span: Span,
+}
+
+/// インスタンス化文（関連付けはアリーナ中の範囲）
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceNode {
+    pub label: Name,
+    pub unit: UnitNode,
+    pub generic_map: Range<usize>,
+    pub port_map: Range<usize>,
+    pub span: Span,
+}
+
+/// エンティティ（ジェネリックとポートはアリーナ中の範囲）
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityNode {
+    pub name: Name,
+    pub generics: Range<usize>,
+    pub ports: Range<usize>,
+    pub span: Span,
+}
+
+/// アーキテクチャ（信号・定数・インスタンス・プロセス・型・関数はアリーナ中の範囲）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchitectureNode {
+    pub name: Name,
+    pub entity_name: Name,
+    pub signals: Range<usize>,
+    pub constants: Range<usize>,
+    pub instances: Range<usize>,
+    pub processes: Range<usize>,
+    pub types: Range<usize>,
+    pub subprograms: Range<usize>,
+    pub span: Span,
+}
+
+/// 名前をインターンし、ポートと信号をアリーナに置いた解析結果
+#[derive(Debug, Clone, Default)]
+pub struct InternedAst {
+    interner: Interner,
+    entities: Vec<EntityNode>,
+    architectures: Vec<ArchitectureNode>,
+    generics: Vec<GenericNode>,
+    ports: Vec<PortNode>,
+    signals: Vec<SignalNode>,
+    constants: Vec<ConstantNode>,
+    instances: Vec<InstanceNode>,
+    associations: Vec<AssociationNode>,
+    /// プロセスの文の木は名前をインターンせずにそのまま持つ
+    processes: Vec<ProcessDef>,
+    /// 型とサブプログラムもそのまま持つ
+    types: Vec<TypeDef>,
+    subprograms: Vec<SubprogramDecl>,
+    /// パッケージ（本体）も名前をインターンせずにそのまま持つ
+    packages: Vec<PackageDef>,
+    package_bodies: Vec<PackageDef>,
+}
+
+impl InternedAst {
+    /// ソースを解析してインターンした結果を返す
+    pub fn analyze(source: &str) -> Result<Self, AnalyzeError> {
+        analyze_vhdl(source).map(|result| Self::from_result(&result))
+    }
+
+    /// 所有型の解析結果から作る
+    pub fn from_result(result: &AnalyzeResult) -> Self {
+        let mut ast = Self::default();
+        for entity in &result.entities {
+            let name = ast.name_of(&entity.name);
+            let generics_start = ast.generics.len();
+            for generic in &entity.generics {
+                let node = GenericNode {
+                    name: ast.name_of(&generic.name),
+                    vhdl_type: ast.type_of(&generic.vhdl_type),
+                    default_value: generic.default_value.as_ref().map(|v| ast.expr_of(v)),
+                    span: generic.span,
+                };
+                ast.generics.push(node);
+            }
+            let start = ast.ports.len();
+            for port in &entity.ports {
+                let node = PortNode {
+                    name: ast.name_of(&port.name),
+                    direction: port.direction.clone(),
+                    vhdl_type: ast.type_of(&port.vhdl_type),
+                    span: port.span,
+                };
+                ast.ports.push(node);
+            }
+            ast.entities.push(EntityNode {
+                name,
+                generics: generics_start..ast.generics.len(),
+                ports: start..ast.ports.len(),
+                span: entity.span,
+            });
+        }
+        for arch in &result.architectures {
+            let name = ast.name_of(&arch.name);
+            let entity_name = ast.name_of(&arch.entity_name);
+            let start = ast.signals.len();
+            for signal in &arch.signals {
+                let node = SignalNode {
+                    name: ast.name_of(&signal.name),
+                    vhdl_type: ast.type_of(&signal.vhdl_type),
+                    default_value: signal.default_value.as_ref().map(|v| ast.expr_of(v)),
+                    span: signal.span,
+                };
+                ast.signals.push(node);
+            }
+            let constants_start = ast.constants.len();
+            for constant in &arch.constants {
+                let node = ConstantNode {
+                    name: ast.name_of(&constant.name),
+                    vhdl_type: ast.type_of(&constant.vhdl_type),
+                    value: constant.value.as_ref().map(|v| ast.expr_of(v)),
+                    span: constant.span,
+                };
+                ast.constants.push(node);
+            }
+            let instances_start = ast.instances.len();
+            for instance in &arch.instances {
+                let label = ast.name_of(&instance.label);
+                let unit = match &instance.unit {
+                    InstantiatedUnit::Component(name) => UnitNode::Component(ast.name_of(name)),
+                    InstantiatedUnit::Entity {
+                        library,
+                        name,
+                        architecture,
+                    } => UnitNode::Entity {
+                        library: library.as_deref().map(|l| ast.name_of(l)),
+                        name: ast.name_of(name),
+                        architecture: architecture.as_deref().map(|a| ast.name_of(a)),
+                    },
+                };
+                let generic_map = ast.associations_of(&instance.generic_map);
+                let port_map = ast.associations_of(&instance.port_map);
+                ast.instances.push(InstanceNode {
+                    label,
+                    unit,
+                    generic_map,
+                    port_map,
+                    span: instance.span,
+                });
+            }
+            ast.architectures.push(ArchitectureNode {
+                name,
+                entity_name,
+                signals: start..ast.signals.len(),
+                constants: constants_start..ast.constants.len(),
+                instances: instances_start..ast.instances.len(),
+                processes: {
+                    let start = ast.processes.len();
+                    ast.processes.extend(arch.processes.iter().cloned());
+                    start..ast.processes.len()
+                },
+                types: {
+                    let start = ast.types.len();
+                    ast.types.extend(arch.types.iter().cloned());
+                    start..ast.types.len()
+                },
+                subprograms: {
+                    let start = ast.subprograms.len();
+                    ast.subprograms.extend(arch.subprograms.iter().cloned());
+                    start..ast.subprograms.len()
+                },
+                span: arch.span,
+            });
+        }
+        ast.packages = result.packages.clone();
+        ast.package_bodies = result.package_bodies.clone();
+        ast
+    }
+
+    /// 所有型の解析結果に戻す
+    pub fn to_result(&self) -> AnalyzeResult {
+        let entities = self
+            .entities
+            .iter()
+            .map(|e| EntityDef {
+                name: self.resolve(e.name).to_string(),
+                generics: self
+                    .generics(e)
+                    .iter()
+                    .map(|g| GenericDef {
+                        name: self.resolve(g.name).to_string(),
+                        vhdl_type: self.vhdl_type(g.vhdl_type),
+                        default_value: g.default_value.map(|v| self.expr(v)),
+                        span: g.span,
+                    })
+                    .collect(),
+                ports: self
+                    .ports(e)
+                    .iter()
+                    .map(|p| PortDef {
+                        name: self.resolve(p.name).to_string(),
+                        direction: p.direction.clone(),
+                        vhdl_type: self.vhdl_type(p.vhdl_type),
+                        span: p.span,
+                    })
+                    .collect(),
+                span: e.span,
+            })
+            .collect();
+        let architectures = self
+            .architectures
+            .iter()
+            .map(|a| ArchitectureDef {
+                name: self.resolve(a.name).to_string(),
+                entity_name: self.resolve(a.entity_name).to_string(),
+                signals: self
+                    .signals(a)
+                    .iter()
+                    .map(|s| SignalDef {
+                        name: self.resolve(s.name).to_string(),
+                        vhdl_type: self.vhdl_type(s.vhdl_type),
+                        default_value: s.default_value.map(|v| self.expr(v)),
+                        span: s.span,
+                    })
+                    .collect(),
+                constants: self
+                    .constants(a)
+                    .iter()
+                    .map(|c| ConstantDef {
+                        name: self.resolve(c.name).to_string(),
+                        vhdl_type: self.vhdl_type(c.vhdl_type),
+                        value: c.value.map(|v| self.expr(v)),
+                        span: c.span,
+                    })
+                    .collect(),
+                instances: self
+                    .instances(a)
+                    .iter()
+                    .map(|i| InstanceDef {
+                        label: self.resolve(i.label).to_string(),
+                        unit: match i.unit {
+                            UnitNode::Component(name) => {
+                                InstantiatedUnit::Component(self.resolve(name).to_string())
+                            }
+                            UnitNode::Entity {
+                                library,
+                                name,
+                                architecture,
+                            } => InstantiatedUnit::Entity {
+                                library: library.map(|l| self.resolve(l).to_string()),
+                                name: self.resolve(name).to_string(),
+                                architecture: architecture.map(|a| self.resolve(a).to_string()),
+                            },
+                        },
+                        generic_map: self.association_defs(self.generic_map(i)),
+                        port_map: self.association_defs(self.port_map(i)),
+                        span: i.span,
+                    })
+                    .collect(),
+                processes: self.processes(a).to_vec(),
+                types: self.types(a).to_vec(),
+                subprograms: self.subprograms(a).to_vec(),
+                span: a.span,
+            })
+            .collect();
+        AnalyzeResult {
+            entities,
+            architectures,
+            packages: self.packages.clone(),
+            package_bodies: self.package_bodies.clone(),
+        }
+    }
+
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    pub fn entities(&self) -> &[EntityNode] {
+        &self.entities
+    }
+
+    pub fn architectures(&self) -> &[ArchitectureNode] {
+        &self.architectures
+    }
+
+    /// エンティティのジェネリック
+    pub fn generics(&self, entity: &EntityNode) -> &[GenericNode] {
+        &self.generics[entity.generics.clone()]
+    }
+
+    /// エンティティのポート
+    pub fn ports(&self, entity: &EntityNode) -> &[PortNode] {
+        &self.ports[entity.ports.clone()]
+    }
+
+    /// アーキテクチャの信号
+    pub fn signals(&self, arch: &ArchitectureNode) -> &[SignalNode] {
+        &self.signals[arch.signals.clone()]
+    }
+
+    /// アーキテクチャの定数
+    pub fn constants(&self, arch: &ArchitectureNode) -> &[ConstantNode] {
+        &self.constants[arch.constants.clone()]
+    }
+
+    /// アーキテクチャのインスタンス化文
+    pub fn instances(&self, arch: &ArchitectureNode) -> &[InstanceNode] {
+        &self.instances[arch.instances.clone()]
+    }
+
+    /// インスタンスの generic map
+    pub fn generic_map(&self, instance: &InstanceNode) -> &[AssociationNode] {
+        &self.associations[instance.generic_map.clone()]
+    }
+
+    /// インスタンスの port map
+    pub fn port_map(&self, instance: &InstanceNode) -> &[AssociationNode] {
+        &self.associations[instance.port_map.clone()]
+    }
+
+    /// アーキテクチャのプロセス文
+    pub fn processes(&self, arch: &ArchitectureNode) -> &[ProcessDef] {
+        &self.processes[arch.processes.clone()]
+    }
+
+    /// アーキテクチャの宣言部の型とサブタイプ
+    pub fn types(&self, arch: &ArchitectureNode) -> &[TypeDef] {
+        &self.types[arch.types.clone()]
+    }
+
+    /// アーキテクチャの宣言部の関数・手続き
+    pub fn subprograms(&self, arch: &ArchitectureNode) -> &[SubprogramDecl] {
+        &self.subprograms[arch.subprograms.clone()]
+    }
+
+    /// パッケージ宣言
+    pub fn packages(&self) -> &[PackageDef] {
+        &self.packages
+    }
+
+    /// パッケージ本体
+    pub fn package_bodies(&self) -> &[PackageDef] {
+        &self.package_bodies
+    }
+
+    /// 識別子の綴り
+    pub fn resolve(&self, name: Name) -> &str {
+        self.interner.resolve(name.text)
+    }
+
+    /// 名前でエンティティを探す（大文字小文字を区別しない）
+    pub fn entity(&self, name: &str) -> Option<&EntityNode> {
+        let key = self.interner.get(&name.to_lowercase())?;
+        self.entities.iter().find(|e| e.name.key == key)
+    }
+
+    /// エンティティのアーキテクチャ
+    pub fn architectures_of<'a>(
+        &'a self,
+        entity: &'a EntityNode,
+    ) -> impl Iterator<Item = &'a ArchitectureNode> + 'a {
+        self.architectures
+            .iter()
+            .filter(move |a| a.entity_name.key == entity.name.key)
+    }
+
+    fn name_of(&mut self, text: &str) -> Name {
+        let lower = text.to_lowercase();
+        Name {
+            text: self.interner.intern(text),
+            key: self.interner.intern(&lower),
+        }
+    }
+
+    fn associations_of(&mut self, associations: &[AssociationDef]) -> Range<usize> {
+        let start = self.associations.len();
+        for association in associations {
+            let node = AssociationNode {
+                formal: association
+                    .formal
+                    .as_deref()
+                    .map(|f| self.interner.intern(f)),
+                actual: self.interner.intern(&association.actual),
+                span: association.span,
+            };
+            self.associations.push(node);
+        }
+        start..self.associations.len()
+    }
+
+    fn association_defs(&self, nodes: &[AssociationNode]) -> Vec<AssociationDef> {
+        nodes
+            .iter()
+            .map(|a| AssociationDef {
+                formal: a.formal.map(|f| self.interner.resolve(f).to_string()),
+                actual: self.interner.resolve(a.actual).to_string(),
+                span: a.span,
+            })
+            .collect()
+    }
+
+    fn type_of(&mut self, vhdl_type: &VhdlType) -> TypeNode {
+        match vhdl_type {
+            VhdlType::StdLogic => TypeNode::StdLogic,
+            VhdlType::StdLogicVector {
+                high,
+                low,
+                ascending,
+            } => TypeNode::StdLogicVector {
+                high: self.bound_of(high),
+                low: self.bound_of(low),
+                ascending: *ascending,
+            },
+            VhdlType::Integer(range) => TypeNode::Integer(self.range_of(range)),
+            VhdlType::Boolean => TypeNode::Boolean,
+            VhdlType::Signed {
+                high,
+                low,
+                ascending,
+            } => TypeNode::Signed {
+                high: self.bound_of(high),
+                low: self.bound_of(low),
+                ascending: *ascending,
+            },
+            VhdlType::Unsigned {
+                high,
+                low,
+                ascending,
+            } => TypeNode::Unsigned {
+                high: self.bound_of(high),
+                low: self.bound_of(low),
+                ascending: *ascending,
+            },
+            VhdlType::Natural(range) => TypeNode::Natural(self.range_of(range)),
+            VhdlType::Positive(range) => TypeNode::Positive(self.range_of(range)),
+            VhdlType::Real => TypeNode::Real,
+            VhdlType::Time => TypeNode::Time,
+            VhdlType::Bit => TypeNode::Bit,
+            VhdlType::BitVector {
+                high,
+                low,
+                ascending,
+            } => TypeNode::BitVector {
+                high: self.bound_of(high),
+                low: self.bound_of(low),
+                ascending: *ascending,
+            },
+            VhdlType::Str(range) => TypeNode::Str(
+                range
+                    .as_ref()
+                    .map(|(left, right)| (self.bound_of(left), self.bound_of(right))),
+            ),
+            VhdlType::Other(name) => TypeNode::Other(self.interner.intern(name)),
+        }
+    }
+
+    fn range_of(&mut self, range: &Option<RangeConstraint>) -> Option<RangeNode> {
+        range.as_ref().map(|r| RangeNode {
+            left: self.bound_of(&r.left),
+            right: self.bound_of(&r.right),
+            ascending: r.ascending,
+        })
+    }
+
+    fn bound_of(&mut self, bound: &BoundExpr) -> BoundNode {
+        match bound {
+            BoundExpr::Literal(n) => BoundNode::Literal(*n),
+            expr => BoundNode::Expr(self.interner.intern(&expr.to_string())),
+        }
+    }
+
+    fn expr_of(&mut self, expr: &Expr) -> Symbol {
+        self.interner.intern(&expr.to_string())
+    }
+
+    fn expr(&self, symbol: Symbol) -> Expr {
+        // 表記は Expr の Display なので読み直せる
+        let text = self.interner.resolve(symbol);
+        text.parse().unwrap_or_else(|_| Expr::name(text))
+    }
+
+    fn range_constraint(&self, node: Option<RangeNode>) -> Option<RangeConstraint> {
+        node.map(|r| RangeConstraint {
+            left: self.bound_expr(r.left),
+            right: self.bound_expr(r.right),
+            ascending: r.ascending,
+        })
+    }
+
+    fn bound_expr(&self, node: BoundNode) -> BoundExpr {
+        match node {
+            BoundNode::Literal(n) => BoundExpr::Literal(n),
+            // 表記は BoundExpr の Display なので読み直せる
+            BoundNode::Expr(text) => {
+                let text = self.interner.resolve(text);
+                text.parse()
+                    .unwrap_or_else(|_| BoundExpr::Name(text.to_string()))
+            }
+        }
+    }
+
+    fn vhdl_type(&self, node: TypeNode) -> VhdlType {
+        match node {
+            TypeNode::StdLogic => VhdlType::StdLogic,
+            TypeNode::StdLogicVector {
+                high,
+                low,
+                ascending,
+            } => VhdlType::StdLogicVector {
+                high: self.bound_expr(high),
+                low: self.bound_expr(low),
+                ascending,
+            },
+            TypeNode::Integer(range) => VhdlType::Integer(self.range_constraint(range)),
+            TypeNode::Boolean => VhdlType::Boolean,
+            TypeNode::Signed {
+                high,
+                low,
+                ascending,
+            } => VhdlType::Signed {
+                high: self.bound_expr(high),
+                low: self.bound_expr(low),
+                ascending,
+            },
+            TypeNode::Unsigned {
+                high,
+                low,
+                ascending,
+            } => VhdlType::Unsigned {
+                high: self.bound_expr(high),
+                low: self.bound_expr(low),
+                ascending,
+            },
+            TypeNode::Natural(range) => VhdlType::Natural(self.range_constraint(range)),
+            TypeNode::Positive(range) => VhdlType::Positive(self.range_constraint(range)),
+            TypeNode::Real => VhdlType::Real,
+            TypeNode::Time => VhdlType::Time,
+            TypeNode::Bit => VhdlType::Bit,
+            TypeNode::BitVector {
+                high,
+                low,
+                ascending,
+            } => VhdlType::BitVector {
+                high: self.bound_expr(high),
+                low: self.bound_expr(low),
+                ascending,
+            },
+            TypeNode::Str(range) => VhdlType::Str(
+                range.map(|(left, right)| (self.bound_expr(left), self.bound_expr(right))),
+            ),
+            TypeNode::Other(name) => VhdlType::Other(self.interner.resolve(name).to_string()),
+        }
+    }
+}
@@ -0,0 +1,1830 @@
+//! 組み合わせ回路の簡易インタプリタ
+//!
+//! アーキテクチャ本体の並行信号代入（条件付き・選択付きを含む）とプロセスを解釈し、
+//! 入力ポートの値から出力ポートの値を求めます。外部シミュレータを使わずに
+//! テストベクタの期待値を予測するためのものです。
+//!
+//! クロック付きプロセスは `rising_edge`/`falling_edge` を常に真とみなして素通しにするため、
+//! 1クロックで出力が確定する回路では「入力を与えて1周期後の値」が得られます。
+//! レジスタを介した帰還（カウンタなど）は値が収束しないためエラーになります。
+
+use std::collections::HashMap;
+
+use crate::analyzer::{EntityDef, PortDirection, VhdlType, analyze_vhdl};
+use crate::ident;
+use crate::lexer::{Lexer, Span, Token, TokenKind};
+use crate::logic::{LogicVector, MAX_VECTOR_WIDTH, StdLogic};
+
+/// 値が変わらなくなるまで評価を繰り返す上限
+const MAX_ITERATIONS: usize = 64;
+
+/// 入出力の値
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// std_logic
+    Logic(StdLogic),
+    /// std_logic_vector・unsigned・signed
+    Vector(LogicVector),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl std::fmt::Display for Value {
+    /// VHDLのリテラル表記
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Logic(c) => write!(f, "'{}'", c),
+            Value::Vector(bits) => write!(f, "\"{}\"", bits),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// 解釈・評価のエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl InterpError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for InterpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for InterpError {}
+
+// --- 内部の値と型 ---
+
+/// 評価中の値（signed を区別する）
+#[derive(Debug, Clone, PartialEq)]
+enum V {
+    Logic(StdLogic),
+    Vector(LogicVector),
+    Signed(LogicVector),
+    Integer(i64),
+    Boolean(bool),
+}
+
+/// 宣言された型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ty {
+    Logic,
+    /// 左端・右端のインデックス
+    Vector {
+        left: i64,
+        right: i64,
+        signed: bool,
+    },
+    Integer,
+    Boolean,
+}
+
+impl Ty {
+    fn from_vhdl(vhdl_type: &VhdlType) -> Option<Ty> {
+        match vhdl_type {
+            VhdlType::StdLogic | VhdlType::Bit => Some(Ty::Logic),
+            VhdlType::Integer(_) | VhdlType::Natural(_) | VhdlType::Positive(_) => {
+                Some(Ty::Integer)
+            }
+            VhdlType::Boolean => Some(Ty::Boolean),
+            _ => {
+                let (left, right) = vhdl_type.vector_bounds()?;
+                let ty = Ty::Vector {
+                    left: left.value()?,
+                    right: right.value()?,
+                    signed: matches!(vhdl_type, VhdlType::Signed { .. }),
+                };
+                ty.width()
+                    .is_some_and(|w| w <= MAX_VECTOR_WIDTH)
+                    .then_some(ty)
+            }
+        }
+    }
+
+    fn width(&self) -> Option<usize> {
+        match self {
+            Ty::Vector { left, right, .. } => {
+                usize::try_from(left.abs_diff(*right)).ok()?.checked_add(1)
+            }
+            _ => None,
+        }
+    }
+
+    /// 型の初期値（std_logic は 'U'）
+    fn initial(&self) -> V {
+        match self {
+            Ty::Logic => V::Logic(StdLogic::U),
+            Ty::Vector { signed: true, .. } => {
+                V::Signed(LogicVector::filled(self.width().unwrap_or(0), StdLogic::U))
+            }
+            Ty::Vector { .. } => {
+                V::Vector(LogicVector::filled(self.width().unwrap_or(0), StdLogic::U))
+            }
+            Ty::Integer => V::Integer(0),
+            Ty::Boolean => V::Boolean(false),
+        }
+    }
+
+    /// インデックスから左端基準の位置
+    fn position(&self, index: i64) -> Option<usize> {
+        let Ty::Vector { left, right, .. } = *self else {
+            return None;
+        };
+        let in_range = if left >= right {
+            (right..=left).contains(&index)
+        } else {
+            (left..=right).contains(&index)
+        };
+        in_range.then(|| left.abs_diff(index) as usize)
+    }
+}
+
+// --- 構文木 ---
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Add,
+    Sub,
+    Concat,
+    Mul,
+    Div,
+    Mod,
+    Rem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(V),
+    Name(String),
+    /// 名前(引数) … 添字・範囲・関数呼び出しは評価時に区別する
+    Apply(String, Vec<Arg>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    /// (others => e)
+    Others(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Arg {
+    Expr(Expr),
+    /// 左端, 右端（`downto`/`to` は位置計算で吸収する）
+    Range(Expr, Expr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Target {
+    name: String,
+    select: Option<Arg>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Choice {
+    Expr(Expr),
+    Others,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stmt {
+    Signal(Target, Expr, Span),
+    Variable(Target, Expr, Span),
+    If(Vec<(Expr, Vec<Stmt>)>, Vec<Stmt>, Span),
+    Case(Expr, Vec<(Vec<Choice>, Vec<Stmt>)>, Span),
+    For(String, Expr, Expr, Vec<Stmt>, Span),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Concurrent {
+    /// target <= e1 when c1 else e2 ...;
+    Conditional(Target, Vec<(Expr, Option<Expr>)>, Span),
+    /// with s select target <= e1 when c1, ...;
+    Selected(Expr, Target, Vec<(Expr, Vec<Choice>)>, Span),
+    Process(Process),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Process {
+    variables: Vec<(String, Ty, Option<Expr>)>,
+    body: Vec<Stmt>,
+    span: Span,
+}
+
+/// 組み合わせ回路のインタプリタ
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    entity: EntityDef,
+    /// 信号・ポート・定数の型（小文字の名前）
+    types: HashMap<String, Ty>,
+    /// 信号の初期値と定数
+    initial: Vec<(String, Expr)>,
+    constants: Vec<String>,
+    statements: Vec<Concurrent>,
+}
+
+impl Interpreter {
+    /// ソース中のエンティティと、そのアーキテクチャ（複数あれば最後のもの）を読み込む
+    pub fn new(source: &str, entity_name: &str) -> Result<Self, InterpError> {
+        let result = analyze_vhdl(source).map_err(|err| InterpError::new(err.message, err.span))?;
+        let entity = result
+            .entities
+            .iter()
+            .find(|e| e.name_normalized() == ident::normalize(entity_name))
+            .ok_or_else(|| {
+                InterpError::new(
+                    format!("entity '{}' not found", entity_name),
+                    Span::new(0, 0),
+                )
+            })?
+            .clone();
+        let arch = result
+            .architectures
+            .iter()
+            .rev()
+            .find(|a| a.is_of(entity_name))
+            .ok_or_else(|| {
+                InterpError::new(
+                    format!("no architecture for '{}'", entity_name),
+                    entity.span,
+                )
+            })?;
+
+        let mut types = HashMap::new();
+        for port in &entity.ports {
+            if let Some(ty) = Ty::from_vhdl(&port.vhdl_type) {
+                types.insert(port.name.to_lowercase(), ty);
+            }
+        }
+
+        let tokens: Vec<Token> = Lexer::new(source)
+            .filter_map(|r| r.ok())
+            .filter(|t| {
+                t.kind != TokenKind::Comment
+                    && arch.span.start <= t.span.start
+                    && t.span.end <= arch.span.end
+            })
+            .collect();
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: arch.span,
+            depth: 0,
+        };
+        let mut interpreter = Interpreter {
+            entity,
+            types,
+            initial: Vec::new(),
+            constants: Vec::new(),
+            statements: Vec::new(),
+        };
+        parser.parse_architecture(&mut interpreter)?;
+        Ok(interpreter)
+    }
+
+    /// 対象のエンティティ
+    pub fn entity(&self) -> &EntityDef {
+        &self.entity
+    }
+
+    /// 入力ポートに値を与えて回路を評価し、出力ポートの値を返す
+    ///
+    /// 与えなかった入力ポートは未初期化（`'U'`）として扱います。
+    pub fn evaluate(&self, inputs: &[(&str, Value)]) -> Result<Vec<(String, Value)>, InterpError> {
+        let mut state = State {
+            values: HashMap::new(),
+            types: self.types.clone(),
+        };
+        for (name, ty) in &self.types {
+            state.values.insert(name.clone(), ty.initial());
+        }
+        for (name, init) in &self.initial {
+            let ty = self.types[name];
+            let value = state.eval(init, ty.width(), self.entity.span)?;
+            let value = coerce(value, ty, name, self.entity.span)?;
+            state.values.insert(name.clone(), value);
+        }
+        for (name, value) in inputs {
+            let port = self
+                .entity
+                .ports
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(name))
+                .filter(|p| matches!(p.direction, PortDirection::In | PortDirection::Inout))
+                .ok_or_else(|| {
+                    InterpError::new(
+                        format!("'{}' is not an input of '{}'", name, self.entity.name),
+                        Span::new(0, 0),
+                    )
+                })?;
+            let key = port.name.to_lowercase();
+            let ty = *self.types.get(&key).ok_or_else(|| {
+                InterpError::new(
+                    format!("unsupported type of port '{}'", port.name),
+                    port.span,
+                )
+            })?;
+            let value = coerce(V::from_value(value), ty, &port.name, port.span)?;
+            state.values.insert(key, value);
+        }
+
+        // 値が変わらなくなるまで全ての文を評価する
+        let mut settled = false;
+        for _ in 0..MAX_ITERATIONS {
+            let mut changed = false;
+            for statement in &self.statements {
+                for (target, value, span) in state.run(statement)? {
+                    if self.constants.contains(&target.name) {
+                        return Err(InterpError::new(
+                            format!("cannot assign to constant '{}'", target.name),
+                            span,
+                        ));
+                    }
+                    changed |= state.assign(&target, value, span)?;
+                }
+            }
+            if !changed {
+                settled = true;
+                break;
+            }
+        }
+        if !settled {
+            return Err(InterpError::new(
+                "values did not settle (feedback through a register or combinational loop)",
+                self.entity.span,
+            ));
+        }
+
+        Ok(self
+            .entity
+            .ports
+            .iter()
+            .filter(|p| p.direction != PortDirection::In)
+            .filter_map(|p| {
+                let value = state.values.get(&p.name.to_lowercase())?;
+                Some((p.name.clone(), value.to_value()))
+            })
+            .collect())
+    }
+}
+
+impl V {
+    fn from_value(value: &Value) -> V {
+        match value {
+            Value::Logic(b) => V::Logic(*b),
+            Value::Vector(bits) => V::Vector(bits.clone()),
+            Value::Integer(n) => V::Integer(*n),
+            Value::Boolean(b) => V::Boolean(*b),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            V::Logic(b) => Value::Logic(*b),
+            V::Vector(bits) | V::Signed(bits) => Value::Vector(bits.clone()),
+            V::Integer(n) => Value::Integer(*n),
+            V::Boolean(b) => Value::Boolean(*b),
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            V::Logic(_) => "std_logic",
+            V::Vector(_) => "vector",
+            V::Signed(_) => "signed",
+            V::Integer(_) => "integer",
+            V::Boolean(_) => "boolean",
+        }
+    }
+}
+
+/// 値を宣言された型に合わせる（幅が違えばエラー）
+fn coerce(value: V, ty: Ty, name: &str, span: Span) -> Result<V, InterpError> {
+    let mismatch = |value: &V| {
+        InterpError::new(
+            format!("cannot assign {} to '{}'", value.type_name(), name),
+            span,
+        )
+    };
+    match (ty, value) {
+        (Ty::Logic, V::Logic(c)) => Ok(V::Logic(c)),
+        (Ty::Vector { signed, .. }, V::Vector(bits) | V::Signed(bits)) => {
+            let width = ty.width().unwrap_or(0);
+            if bits.width() != width {
+                return Err(InterpError::new(
+                    format!(
+                        "width mismatch: '{}' is {} bits, value is {} bits",
+                        name,
+                        width,
+                        bits.width()
+                    ),
+                    span,
+                ));
+            }
+            Ok(if signed {
+                V::Signed(bits)
+            } else {
+                V::Vector(bits)
+            })
+        }
+        (Ty::Integer, V::Integer(n)) => Ok(V::Integer(n)),
+        (Ty::Boolean, V::Boolean(b)) => Ok(V::Boolean(b)),
+        (_, value) => Err(mismatch(&value)),
+    }
+}
+
+// --- 評価 ---
+
+/// 評価中の信号値
+struct State {
+    values: HashMap<String, V>,
+    types: HashMap<String, Ty>,
+}
+
+impl State {
+    /// 並行文を1回実行して代入の一覧を返す
+    fn run(&mut self, statement: &Concurrent) -> Result<Vec<(Target, V, Span)>, InterpError> {
+        match statement {
+            Concurrent::Conditional(target, waveforms, span) => {
+                let width = self.target_width(target);
+                for (value, condition) in waveforms {
+                    let taken = match condition {
+                        Some(condition) => self.condition(condition, *span)?,
+                        None => true,
+                    };
+                    if taken {
+                        let value = self.eval(value, width, *span)?;
+                        return Ok(vec![(target.clone(), value, *span)]);
+                    }
+                }
+                Ok(Vec::new())
+            }
+            Concurrent::Selected(selector, target, arms, span) => {
+                let width = self.target_width(target);
+                let selected = self.eval(selector, None, *span)?;
+                for (value, choices) in arms {
+                    if self.matches(&selected, choices, *span)? {
+                        let value = self.eval(value, width, *span)?;
+                        return Ok(vec![(target.clone(), value, *span)]);
+                    }
+                }
+                Ok(Vec::new())
+            }
+            Concurrent::Process(process) => {
+                // 変数は起動のたびに初期化する
+                let saved_types = self.types.clone();
+                let mut locals = Vec::new();
+                for (name, ty, init) in &process.variables {
+                    self.types.insert(name.clone(), *ty);
+                    let value = match init {
+                        Some(init) => coerce(
+                            self.eval(init, ty.width(), process.span)?,
+                            *ty,
+                            name,
+                            process.span,
+                        )?,
+                        None => ty.initial(),
+                    };
+                    let previous = self.values.insert(name.clone(), value);
+                    locals.push((name.clone(), previous));
+                }
+                let mut pending = Vec::new();
+                let result = self.exec(&process.body, &mut pending);
+                // 変数を片付けて信号代入だけを返す
+                for (name, previous) in locals {
+                    match previous {
+                        Some(value) => self.values.insert(name, value),
+                        None => self.values.remove(&name),
+                    };
+                }
+                self.types = saved_types;
+                result?;
+                Ok(pending)
+            }
+        }
+    }
+
+    fn exec(
+        &mut self,
+        statements: &[Stmt],
+        pending: &mut Vec<(Target, V, Span)>,
+    ) -> Result<(), InterpError> {
+        for statement in statements {
+            match statement {
+                Stmt::Signal(target, value, span) => {
+                    let value = self.eval(value, self.target_width(target), *span)?;
+                    // 添字はループ変数などが消える前に確定させておく
+                    let target = self.resolve_target(target, *span)?;
+                    pending.push((target, value, *span));
+                }
+                Stmt::Variable(target, value, span) => {
+                    let value = self.eval(value, self.target_width(target), *span)?;
+                    self.assign(target, value, *span)?;
+                }
+                Stmt::If(branches, otherwise, span) => {
+                    let mut taken = false;
+                    for (condition, body) in branches {
+                        if self.condition(condition, *span)? {
+                            self.exec(body, pending)?;
+                            taken = true;
+                            break;
+                        }
+                    }
+                    if !taken {
+                        self.exec(otherwise, pending)?;
+                    }
+                }
+                Stmt::Case(selector, arms, span) => {
+                    let selected = self.eval(selector, None, *span)?;
+                    for (choices, body) in arms {
+                        if self.matches(&selected, choices, *span)? {
+                            self.exec(body, pending)?;
+                            break;
+                        }
+                    }
+                }
+                Stmt::For(var, from, to, body, span) => {
+                    let (V::Integer(from), V::Integer(to)) =
+                        (self.eval(from, None, *span)?, self.eval(to, None, *span)?)
+                    else {
+                        return Err(InterpError::new("loop range must be integer", *span));
+                    };
+                    let range: Box<dyn Iterator<Item = i64>> = if from <= to {
+                        Box::new(from..=to)
+                    } else {
+                        Box::new((to..=from).rev())
+                    };
+                    let previous = self.values.remove(var);
+                    self.types.insert(var.clone(), Ty::Integer);
+                    for i in range {
+                        self.values.insert(var.clone(), V::Integer(i));
+                        self.exec(body, pending)?;
+                    }
+                    self.types.remove(var);
+                    match previous {
+                        Some(value) => self.values.insert(var.clone(), value),
+                        None => self.values.remove(var),
+                    };
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 代入先の添字を評価して定数にする
+    fn resolve_target(&mut self, target: &Target, span: Span) -> Result<Target, InterpError> {
+        let mut constant = |expr: &Expr| -> Result<Expr, InterpError> {
+            Ok(Expr::Literal(self.eval(expr, None, span)?))
+        };
+        let select = match &target.select {
+            None => None,
+            Some(Arg::Expr(index)) => Some(Arg::Expr(constant(index)?)),
+            Some(Arg::Range(left, right)) => Some(Arg::Range(constant(left)?, constant(right)?)),
+        };
+        Ok(Target {
+            name: target.name.clone(),
+            select,
+        })
+    }
+
+    fn target_width(&self, target: &Target) -> Option<usize> {
+        let ty = self.types.get(&target.name)?;
+        match &target.select {
+            None => ty.width(),
+            // 範囲指定の幅は評価時に決まる
+            Some(_) => None,
+        }
+    }
+
+    /// 代入する（値が変わったら true）
+    fn assign(&mut self, target: &Target, value: V, span: Span) -> Result<bool, InterpError> {
+        let ty = *self
+            .types
+            .get(&target.name)
+            .ok_or_else(|| InterpError::new(format!("unknown name '{}'", target.name), span))?;
+        let new = match &target.select {
+            None => coerce(value, ty, &target.name, span)?,
+            Some(select) => {
+                let mut bits = match self.values.get(&target.name) {
+                    Some(V::Vector(bits) | V::Signed(bits)) => bits.clone(),
+                    _ => {
+                        return Err(InterpError::new(
+                            format!("'{}' is not a vector", target.name),
+                            span,
+                        ));
+                    }
+                };
+                let (from, to) = self.select_positions(ty, select, &target.name, span)?;
+                let part = match value {
+                    V::Logic(b) if from == to => vec![b],
+                    V::Vector(part) | V::Signed(part) if part.width() == to - from + 1 => {
+                        part.into_bits()
+                    }
+                    _ => {
+                        return Err(InterpError::new(
+                            format!("width mismatch in assignment to '{}'", target.name),
+                            span,
+                        ));
+                    }
+                };
+                bits.bits_mut().splice(from..=to, part);
+                match ty {
+                    Ty::Vector { signed: true, .. } => V::Signed(bits),
+                    _ => V::Vector(bits),
+                }
+            }
+        };
+        let changed = self.values.get(&target.name) != Some(&new);
+        self.values.insert(target.name.clone(), new);
+        Ok(changed)
+    }
+
+    /// 添字・範囲を左端基準の位置 (from, to) に
+    fn select_positions(
+        &mut self,
+        ty: Ty,
+        select: &Arg,
+        name: &str,
+        span: Span,
+    ) -> Result<(usize, usize), InterpError> {
+        let mut index = |expr: &Expr| -> Result<usize, InterpError> {
+            match self.eval(expr, None, span)? {
+                V::Integer(i) => ty.position(i).ok_or_else(|| {
+                    InterpError::new(format!("index {} out of range of '{}'", i, name), span)
+                }),
+                _ => Err(InterpError::new("index must be integer", span)),
+            }
+        };
+        match select {
+            Arg::Expr(expr) => {
+                let i = index(expr)?;
+                Ok((i, i))
+            }
+            Arg::Range(left, right) => {
+                let (a, b) = (index(left)?, index(right)?);
+                if a > b {
+                    return Err(InterpError::new(
+                        format!("slice direction does not match '{}'", name),
+                        span,
+                    ));
+                }
+                Ok((a, b))
+            }
+        }
+    }
+
+    fn condition(&mut self, expr: &Expr, span: Span) -> Result<bool, InterpError> {
+        match self.eval(expr, None, span)? {
+            V::Boolean(b) => Ok(b),
+            // VHDL-2008 の条件演算子と同じく '1'/'H' を真とみなす
+            V::Logic(b) => Ok(b.to_x01() == StdLogic::One),
+            value => Err(InterpError::new(
+                format!("condition must be boolean, found {}", value.type_name()),
+                span,
+            )),
+        }
+    }
+
+    fn matches(
+        &mut self,
+        selected: &V,
+        choices: &[Choice],
+        span: Span,
+    ) -> Result<bool, InterpError> {
+        let width = match selected {
+            V::Vector(bits) | V::Signed(bits) => Some(bits.width()),
+            _ => None,
+        };
+        for choice in choices {
+            match choice {
+                Choice::Others => return Ok(true),
+                Choice::Expr(expr) => {
+                    let value = self.eval(expr, width, span)?;
+                    if compare(BinOp::Eq, selected, &value, span)? {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// 式を評価する（`width` は集合体の幅を決めるための代入先の幅）
+    fn eval(&mut self, expr: &Expr, width: Option<usize>, span: Span) -> Result<V, InterpError> {
+        match expr {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Name(name) => self
+                .values
+                .get(name)
+                .cloned()
+                .ok_or_else(|| InterpError::new(format!("unknown name '{}'", name), span)),
+            Expr::Others(value) => {
+                let width = width.ok_or_else(|| {
+                    InterpError::new("cannot determine the width of (others => ...)", span)
+                })?;
+                match self.eval(value, None, span)? {
+                    V::Logic(b) => Ok(V::Vector(LogicVector::filled(width, b))),
+                    _ => Err(InterpError::new("(others => ...) needs a std_logic", span)),
+                }
+            }
+            Expr::Not(inner) => match self.eval(inner, width, span)? {
+                V::Logic(b) => Ok(V::Logic(b.not())),
+                V::Vector(bits) => Ok(V::Vector(bits.not())),
+                V::Signed(bits) => Ok(V::Signed(bits.not())),
+                V::Boolean(b) => Ok(V::Boolean(!b)),
+                V::Integer(_) => Err(InterpError::new("'not' on integer", span)),
+            },
+            Expr::Neg(inner) => match self.eval(inner, width, span)? {
+                V::Integer(n) => Ok(V::Integer(-n)),
+                V::Signed(bits) => Ok(V::Signed(LogicVector::zeros(bits.width()).sub(&bits, true))),
+                value => Err(InterpError::new(
+                    format!("unary '-' on {}", value.type_name()),
+                    span,
+                )),
+            },
+            Expr::Binary(op, left, right) => {
+                // 片方が (others => ...) のときはもう片方の幅を使う
+                let l = self.eval(left, width, span)?;
+                let rw = match &l {
+                    V::Vector(bits) | V::Signed(bits) => Some(bits.width()),
+                    _ => width,
+                };
+                let r = self.eval(right, rw, span)?;
+                binary(*op, l, r, span)
+            }
+            Expr::Apply(name, args) => {
+                if let Some(ty) = self.types.get(name).copied() {
+                    let value = self.values.get(name).cloned().ok_or_else(|| {
+                        InterpError::new(format!("unknown name '{}'", name), span)
+                    })?;
+                    let [select] = args.as_slice() else {
+                        return Err(InterpError::new(
+                            format!("'{}' takes one index", name),
+                            span,
+                        ));
+                    };
+                    let (from, to) = self.select_positions(ty, select, name, span)?;
+                    return match (value, select) {
+                        (V::Vector(bits) | V::Signed(bits), Arg::Expr(_)) => {
+                            Ok(V::Logic(bits.bits()[from]))
+                        }
+                        (V::Vector(bits), Arg::Range(..)) => {
+                            Ok(V::Vector(bits.bits()[from..=to].to_vec().into()))
+                        }
+                        (V::Signed(bits), Arg::Range(..)) => {
+                            Ok(V::Signed(bits.bits()[from..=to].to_vec().into()))
+                        }
+                        _ => Err(InterpError::new(
+                            format!("'{}' is not a vector", name),
+                            span,
+                        )),
+                    };
+                }
+                let mut values = Vec::new();
+                for arg in args {
+                    match arg {
+                        Arg::Expr(expr) => values.push(self.eval(expr, None, span)?),
+                        Arg::Range(..) => {
+                            return Err(InterpError::new(
+                                format!("unexpected range in call to '{}'", name),
+                                span,
+                            ));
+                        }
+                    }
+                }
+                call(name, values, span)
+            }
+        }
+    }
+}
+
+/// 組み込み関数・型変換
+fn call(name: &str, args: Vec<V>, span: Span) -> Result<V, InterpError> {
+    let bad = || InterpError::new(format!("invalid arguments to '{}'", name), span);
+    let int = |v: &V| match v {
+        V::Integer(n) => Some(*n),
+        _ => None,
+    };
+    match (name, args.as_slice()) {
+        // クロック付きプロセスは素通しにする
+        ("rising_edge" | "falling_edge", [_]) => Ok(V::Boolean(true)),
+        ("unsigned" | "std_logic_vector", [V::Vector(bits) | V::Signed(bits)]) => {
+            Ok(V::Vector(bits.clone()))
+        }
+        ("signed", [V::Vector(bits) | V::Signed(bits)]) => Ok(V::Signed(bits.clone())),
+        ("to_unsigned" | "to_signed", [n, w]) => {
+            let (n, w) = (int(n).ok_or_else(bad)?, int(w).ok_or_else(bad)?);
+            let w = usize::try_from(w)
+                .ok()
+                .filter(|&w| w <= MAX_VECTOR_WIDTH)
+                .ok_or_else(bad)?;
+            let bits = LogicVector::from_signed(n as i128, w);
+            Ok(if name == "to_signed" {
+                V::Signed(bits)
+            } else {
+                V::Vector(bits)
+            })
+        }
+        ("to_integer", [V::Vector(bits)]) => Ok(V::Integer(bits.to_unsigned().unwrap_or(0) as i64)),
+        ("to_integer", [V::Signed(bits)]) => Ok(V::Integer(bits.to_signed().unwrap_or(0) as i64)),
+        ("resize", [value, w]) => {
+            let w = usize::try_from(int(w).ok_or_else(bad)?)
+                .ok()
+                .filter(|&w| w <= MAX_VECTOR_WIDTH)
+                .ok_or_else(bad)?;
+            match value {
+                V::Vector(bits) => Ok(V::Vector(bits.resize(w, false))),
+                V::Signed(bits) => Ok(V::Signed(bits.resize(w, true))),
+                _ => Err(bad()),
+            }
+        }
+        ("shift_left" | "shift_right", [value, n]) => {
+            let n = usize::try_from(int(n).ok_or_else(bad)?).map_err(|_| bad())?;
+            let (bits, signed) = match value {
+                V::Vector(bits) => (bits, false),
+                V::Signed(bits) => (bits, true),
+                _ => return Err(bad()),
+            };
+            let out = if name == "shift_left" {
+                bits.shift_left(n)
+            } else {
+                bits.shift_right(n, signed)
+            };
+            Ok(if signed {
+                V::Signed(out)
+            } else {
+                V::Vector(out)
+            })
+        }
+        _ => Err(InterpError::new(
+            format!("unsupported function or unknown name '{}'", name),
+            span,
+        )),
+    }
+}
+
+fn binary(op: BinOp, l: V, r: V, span: Span) -> Result<V, InterpError> {
+    use BinOp::*;
+    let unsupported = |l: &V, r: &V| {
+        InterpError::new(
+            format!(
+                "unsupported operands {} and {} for {:?}",
+                l.type_name(),
+                r.type_name(),
+                op
+            ),
+            span,
+        )
+    };
+    match op {
+        And | Or | Xor | Nand | Nor | Xnor => {
+            let f = match op {
+                And => StdLogic::and,
+                Or => StdLogic::or,
+                Xor => StdLogic::xor,
+                Nand => StdLogic::nand,
+                Nor => StdLogic::nor,
+                _ => StdLogic::xnor,
+            };
+            match (&l, &r) {
+                (V::Logic(a), V::Logic(b)) => Ok(V::Logic(f(*a, *b))),
+                (V::Boolean(a), V::Boolean(b)) => Ok(V::Boolean(match op {
+                    And => *a && *b,
+                    Or => *a || *b,
+                    Xor => a != b,
+                    Nand => !(*a && *b),
+                    Nor => !(*a || *b),
+                    _ => a == b,
+                })),
+                (V::Vector(a) | V::Signed(a), V::Vector(b) | V::Signed(b))
+                    if a.width() == b.width() =>
+                {
+                    let bits = a
+                        .bits()
+                        .iter()
+                        .zip(b.bits())
+                        .map(|(&x, &y)| f(x, y))
+                        .collect::<Vec<_>>()
+                        .into();
+                    Ok(if matches!(l, V::Signed(_)) {
+                        V::Signed(bits)
+                    } else {
+                        V::Vector(bits)
+                    })
+                }
+                _ => Err(unsupported(&l, &r)),
+            }
+        }
+        Eq | Neq | Lt | Lte | Gt | Gte => Ok(V::Boolean(compare(op, &l, &r, span)?)),
+        Concat => {
+            let bits = |v: &V| match v {
+                V::Logic(b) => Some(LogicVector::new(vec![*b])),
+                V::Vector(bits) | V::Signed(bits) => Some(bits.clone()),
+                _ => None,
+            };
+            match (bits(&l), bits(&r)) {
+                (Some(a), Some(b)) => Ok(V::Vector(a.concat(&b))),
+                _ => Err(unsupported(&l, &r)),
+            }
+        }
+        Add | Sub | Mul | Div | Mod | Rem => match (&l, &r) {
+            (V::Integer(a), V::Integer(b)) => {
+                if matches!(op, Div | Mod | Rem) && *b == 0 {
+                    return Err(InterpError::new("division by zero", span));
+                }
+                Ok(V::Integer(match op {
+                    Add => a.wrapping_add(*b),
+                    Sub => a.wrapping_sub(*b),
+                    Mul => a.wrapping_mul(*b),
+                    Div => a / b,
+                    Mod => {
+                        // mod の符号は右辺に合わせる
+                        let m = a % b;
+                        if m != 0 && (m < 0) != (*b < 0) {
+                            m + b
+                        } else {
+                            m
+                        }
+                    }
+                    _ => a % b,
+                }))
+            }
+            _ => {
+                // numeric_std の算術（整数はもう一方のベクタの幅に揃える）
+                let signed = matches!(l, V::Signed(_)) || matches!(r, V::Signed(_));
+                let (a, b) = match (&l, &r) {
+                    (V::Vector(a) | V::Signed(a), V::Vector(b) | V::Signed(b))
+                        if !signed || (matches!(l, V::Signed(_)) && matches!(r, V::Signed(_))) =>
+                    {
+                        (a.clone(), b.clone())
+                    }
+                    (V::Vector(a) | V::Signed(a), V::Integer(n)) => {
+                        (a.clone(), LogicVector::from_signed(*n as i128, a.width()))
+                    }
+                    (V::Integer(n), V::Vector(b) | V::Signed(b)) => {
+                        (LogicVector::from_signed(*n as i128, b.width()), b.clone())
+                    }
+                    _ => return Err(unsupported(&l, &r)),
+                };
+                let bits = match op {
+                    Add => a.add(&b, signed),
+                    Sub => a.sub(&b, signed),
+                    Mul => a.mul(&b, signed),
+                    Div => a
+                        .div(&b, signed)
+                        .ok_or_else(|| InterpError::new("division by zero", span))?,
+                    Mod => a
+                        .modulo(&b, signed)
+                        .ok_or_else(|| InterpError::new("division by zero", span))?,
+                    _ => a
+                        .rem(&b, signed)
+                        .ok_or_else(|| InterpError::new("division by zero", span))?,
+                };
+                Ok(if signed {
+                    V::Signed(bits)
+                } else {
+                    V::Vector(bits)
+                })
+            }
+        },
+    }
+}
+
+/// 比較演算
+fn compare(op: BinOp, l: &V, r: &V, span: Span) -> Result<bool, InterpError> {
+    use std::cmp::Ordering;
+    let ordering: Option<Ordering> = match (l, r) {
+        (V::Logic(a), V::Logic(b)) => {
+            return match op {
+                BinOp::Eq => Ok(a == b),
+                BinOp::Neq => Ok(a != b),
+                _ => Ok(compare_order(op, a.cmp(b))),
+            };
+        }
+        (V::Boolean(a), V::Boolean(b)) => Some(a.cmp(b)),
+        (V::Integer(a), V::Integer(b)) => Some(a.cmp(b)),
+        // 同じ長さのベクタの等価比較は文字ごと
+        (V::Vector(a), V::Vector(b))
+            if a.width() == b.width() && matches!(op, BinOp::Eq | BinOp::Neq) =>
+        {
+            return Ok((a == b) == (op == BinOp::Eq));
+        }
+        (V::Vector(a) | V::Signed(a), V::Vector(b) | V::Signed(b)) => {
+            let x = a.to_integer(matches!(l, V::Signed(_)));
+            let y = b.to_integer(matches!(r, V::Signed(_)));
+            x.zip(y).map(|(x, y)| x.cmp(&y))
+        }
+        (V::Vector(a) | V::Signed(a), V::Integer(n)) => a
+            .to_integer(matches!(l, V::Signed(_)))
+            .map(|x| x.cmp(&(*n as i128))),
+        (V::Integer(n), V::Vector(b) | V::Signed(b)) => b
+            .to_integer(matches!(r, V::Signed(_)))
+            .map(|y| (*n as i128).cmp(&y)),
+        _ => {
+            return Err(InterpError::new(
+                format!("cannot compare {} with {}", l.type_name(), r.type_name()),
+                span,
+            ));
+        }
+    };
+    // 不定値との比較は偽
+    Ok(ordering.is_some_and(|o| compare_order(op, o)))
+}
+
+fn compare_order(op: BinOp, ordering: std::cmp::Ordering) -> bool {
+    use std::cmp::Ordering::*;
+    match op {
+        BinOp::Eq => ordering == Equal,
+        BinOp::Neq => ordering != Equal,
+        BinOp::Lt => ordering == Less,
+        BinOp::Lte => ordering != Greater,
+        BinOp::Gt => ordering == Greater,
+        _ => ordering != Less,
+    }
+}
+
+// --- 定数式の評価 ---
+
+/// 定数式の値（リントで条件や選択肢を静的に判定するためのもの）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Constant(V);
+
+impl Constant {
+    /// 条件として評価した真偽（真偽値・std_logic 以外は None）
+    pub(crate) fn truth(&self) -> Option<bool> {
+        match &self.0 {
+            V::Boolean(b) => Some(*b),
+            V::Logic(b) => Some(b.to_x01() == StdLogic::One),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Constant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.to_value().fmt(f)
+    }
+}
+
+/// 式の並び（case の選択肢など）を定数として評価する
+///
+/// 定数以外の名前を参照する式や、解釈できない式を含めば None を返します。
+pub(crate) fn eval_constants(
+    tokens: &[Token],
+    constants: &HashMap<String, Constant>,
+) -> Option<Vec<Constant>> {
+    let end = tokens.last()?.span;
+    let mut parser = Parser {
+        tokens: tokens.to_vec(),
+        pos: 0,
+        end,
+        depth: 0,
+    };
+    let mut state = State {
+        values: constants
+            .iter()
+            .map(|(name, c)| (name.clone(), c.0.clone()))
+            .collect(),
+        types: HashMap::new(),
+    };
+    let mut values = Vec::new();
+    while parser.current().is_some() {
+        let expr = parser.parse_expr().ok()?;
+        values.push(Constant(state.eval(&expr, None, end).ok()?));
+        // case の選択肢は `|` で区切る
+        parser.eat(TokenKind::Bar);
+    }
+    Some(values)
+}
+
+// --- 構文解析 ---
+
+/// 式と文の入れ子の上限（再帰の深さを抑え、スタックを使い切らないようにする）
+const MAX_NESTING: usize = 64;
+
+struct Parser<'source> {
+    tokens: Vec<Token<'source>>,
+    pos: usize,
+    /// アーキテクチャ全体の範囲（末尾のエラー位置に使う）
+    end: Span,
+    /// 現在の入れ子の深さ
+    depth: usize,
+}
+
+impl<'source> Parser<'source> {
+    /// 入れ子を1段深くして読む
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, InterpError>,
+    ) -> Result<T, InterpError> {
+        if self.depth >= MAX_NESTING {
+            return Err(self.error("nesting too deep"));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// 二項演算の連なりも木の深さになるため、入れ子と合わせて上限を確かめる
+    fn check_chain(&self, length: usize) -> Result<(), InterpError> {
+        if self.depth + length > MAX_NESTING {
+            return Err(self.error("nesting too deep"));
+        }
+        Ok(())
+    }
+
+    fn current(&self) -> Option<&Token<'source>> {
+        self.tokens.get(self.pos)
+    }
+
+    fn kind(&self) -> Option<&TokenKind> {
+        self.current().map(|t| &t.kind)
+    }
+
+    fn kind_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| &t.kind)
+    }
+
+    fn span(&self) -> Span {
+        self.current()
+            .map_or(Span::new(self.end.end, self.end.end), |t| t.span)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.current()
+            .is_some_and(|t| t.text.eq_ignore_ascii_case(word))
+    }
+
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        if self.kind() == Some(&kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if self.is_word(word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> InterpError {
+        InterpError::new(message, self.span())
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<(), InterpError> {
+        if self.eat(kind.clone()) {
+            Ok(())
+        } else {
+            let found = self
+                .current()
+                .map_or("end of architecture".to_string(), |t| {
+                    format!("'{}'", t.text)
+                });
+            Err(self.error(format!("expected {:?}, found {}", kind, found)))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, InterpError> {
+        match self.current() {
+            Some(t) if t.kind == TokenKind::Identifier => {
+                let name = t.text.to_lowercase();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    /// `;` まで読み飛ばす（括弧内の `;` は無視）
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some(kind) = self.kind() {
+            match *kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => depth = depth.saturating_sub(1),
+                TokenKind::Semicolon if depth == 0 => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// `end` の後の `;` までを読む（`end if;` `end process label;` など）
+    fn finish_end(&mut self) -> Result<(), InterpError> {
+        while self.kind().is_some_and(|k| *k != TokenKind::Semicolon) {
+            self.pos += 1;
+        }
+        self.expect(TokenKind::Semicolon)
+    }
+
+    fn parse_architecture(&mut self, interp: &mut Interpreter) -> Result<(), InterpError> {
+        // architecture <名前> of <エンティティ> is
+        while self.kind().is_some_and(|k| *k != TokenKind::Is) {
+            self.pos += 1;
+        }
+        self.expect(TokenKind::Is)?;
+
+        // 宣言部
+        loop {
+            match self.kind().cloned() {
+                Some(TokenKind::Begin) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(TokenKind::Signal) => {
+                    self.pos += 1;
+                    self.parse_object_decl(interp, false)?;
+                }
+                _ if self.is_word("constant") => {
+                    self.pos += 1;
+                    self.parse_object_decl(interp, true)?;
+                }
+                Some(TokenKind::Component) => {
+                    // end component; まで
+                    while !(self.kind() == Some(&TokenKind::End)
+                        && self.kind_at(1) == Some(&TokenKind::Component))
+                    {
+                        if self.current().is_none() {
+                            return Err(self.error("unterminated component declaration"));
+                        }
+                        self.pos += 1;
+                    }
+                    self.finish_end()?;
+                }
+                _ if self.is_word("function") || self.is_word("procedure") => {
+                    return Err(self.error("subprograms are not supported"));
+                }
+                Some(_) => self.skip_statement(),
+                None => return Err(self.error("expected 'begin'")),
+            }
+        }
+
+        // 本体
+        loop {
+            match self.kind().cloned() {
+                Some(TokenKind::End) => break,
+                Some(_) => {
+                    if let Some(statement) = self.parse_concurrent()? {
+                        interp.statements.push(statement);
+                    }
+                }
+                None => return Err(self.error("expected 'end'")),
+            }
+        }
+        Ok(())
+    }
+
+    /// signal/constant 宣言（キーワードの後から）
+    fn parse_object_decl(
+        &mut self,
+        interp: &mut Interpreter,
+        constant: bool,
+    ) -> Result<(), InterpError> {
+        let mut names = vec![self.identifier()?];
+        while self.eat(TokenKind::Comma) {
+            names.push(self.identifier()?);
+        }
+        self.expect(TokenKind::Colon)?;
+        let span = self.span();
+        let ty = self.parse_type()?;
+        let init = if self.eat(TokenKind::Assignment) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        self.expect(TokenKind::Semicolon)?;
+        if constant && init.is_none() {
+            return Err(InterpError::new("constant needs a value", span));
+        }
+        for name in names {
+            interp.types.insert(name.clone(), ty);
+            if let Some(init) = &init {
+                interp.initial.push((name.clone(), init.clone()));
+            }
+            if constant {
+                interp.constants.push(name);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_type(&mut self) -> Result<Ty, InterpError> {
+        let span = self.span();
+        let token = self
+            .current()
+            .ok_or_else(|| self.error("expected type"))?
+            .clone();
+        self.pos += 1;
+        let name = token.text.to_lowercase();
+        match name.as_str() {
+            "std_logic" | "std_ulogic" | "bit" => Ok(Ty::Logic),
+            "integer" | "natural" | "positive" => {
+                // range 指定は読み飛ばす
+                if self.eat_word("range") {
+                    self.parse_expr()?;
+                    if !(self.eat(TokenKind::To) || self.eat(TokenKind::Downto)) {
+                        return Err(self.error("expected 'to' or 'downto'"));
+                    }
+                    self.parse_expr()?;
+                }
+                Ok(Ty::Integer)
+            }
+            "boolean" => Ok(Ty::Boolean),
+            "std_logic_vector" | "std_ulogic_vector" | "bit_vector" | "unsigned" | "signed" => {
+                self.expect(TokenKind::LeftParen)?;
+                let left = self.parse_static_int()?;
+                if !(self.eat(TokenKind::Downto) || self.eat(TokenKind::To)) {
+                    return Err(self.error("expected 'to' or 'downto'"));
+                }
+                let right = self.parse_static_int()?;
+                self.expect(TokenKind::RightParen)?;
+                if left.abs_diff(right) >= MAX_VECTOR_WIDTH as u64 {
+                    return Err(InterpError::new(
+                        format!("vector type '{}' is too wide", token.text),
+                        span,
+                    ));
+                }
+                Ok(Ty::Vector {
+                    left,
+                    right,
+                    signed: name == "signed",
+                })
+            }
+            _ => Err(InterpError::new(
+                format!("unsupported type '{}'", token.text),
+                span,
+            )),
+        }
+    }
+
+    /// 型の範囲に書ける定数式（整数リテラルの加減算のみ）
+    fn parse_static_int(&mut self) -> Result<i64, InterpError> {
+        let span = self.span();
+        let expr = self.parse_expr()?;
+        let mut state = State {
+            values: HashMap::new(),
+            types: HashMap::new(),
+        };
+        match state.eval(&expr, None, span) {
+            Ok(V::Integer(n)) => Ok(n),
+            _ => Err(InterpError::new(
+                "range bound must be an integer literal",
+                span,
+            )),
+        }
+    }
+
+    /// 並行文（未対応の文は None ではなくエラー、assert などは読み飛ばして None）
+    fn parse_concurrent(&mut self) -> Result<Option<Concurrent>, InterpError> {
+        let start = self.span();
+        // ラベル
+        if self.kind() == Some(&TokenKind::Identifier) && self.kind_at(1) == Some(&TokenKind::Colon)
+        {
+            self.pos += 2;
+            if self.kind() != Some(&TokenKind::Process) {
+                return Err(InterpError::new(
+                    "component instantiation and generate statements are not supported",
+                    start,
+                ));
+            }
+        }
+        if self.eat(TokenKind::Process) {
+            return self
+                .parse_process(start)
+                .map(|p| Some(Concurrent::Process(p)));
+        }
+        if self.is_word("assert") || self.is_word("report") {
+            self.skip_statement();
+            return Ok(None);
+        }
+        if self.eat_word("with") {
+            let selector = self.parse_expr()?;
+            if !self.eat_word("select") {
+                return Err(self.error("expected 'select'"));
+            }
+            let target = self.parse_target()?;
+            self.expect(TokenKind::Lte)?;
+            let mut arms = Vec::new();
+            loop {
+                let value = self.parse_expr()?;
+                self.expect(TokenKind::When)?;
+                let choices = self.parse_choices()?;
+                arms.push((value, choices));
+                if !self.eat(TokenKind::Comma) {
+                    break;
+                }
+            }
+            let span = Span::new(start.start, self.span().end);
+            self.expect(TokenKind::Semicolon)?;
+            return Ok(Some(Concurrent::Selected(selector, target, arms, span)));
+        }
+
+        let target = self.parse_target()?;
+        self.expect(TokenKind::Lte)?;
+        let mut waveforms = Vec::new();
+        loop {
+            let value = self.parse_waveform()?;
+            if self.eat(TokenKind::When) {
+                let condition = self.parse_expr()?;
+                waveforms.push((value, Some(condition)));
+                if self.eat(TokenKind::Else) {
+                    continue;
+                }
+            } else {
+                waveforms.push((value, None));
+            }
+            break;
+        }
+        let span = Span::new(start.start, self.span().end);
+        self.expect(TokenKind::Semicolon)?;
+        Ok(Some(Concurrent::Conditional(target, waveforms, span)))
+    }
+
+    /// 代入値（`after` 以降の遅延は無視する）
+    fn parse_waveform(&mut self) -> Result<Expr, InterpError> {
+        let value = self.parse_expr()?;
+        if self.eat_word("after") {
+            self.parse_expr()?;
+            // 時間の単位
+            self.eat(TokenKind::Identifier);
+        }
+        Ok(value)
+    }
+
+    fn parse_process(&mut self, start: Span) -> Result<Process, InterpError> {
+        // 感度リスト（値が収束するまで全プロセスを評価するので内容は使わない）
+        if self.eat(TokenKind::LeftParen) {
+            while !self.eat(TokenKind::RightParen) {
+                if self.current().is_none() {
+                    return Err(self.error("unterminated sensitivity list"));
+                }
+                self.pos += 1;
+            }
+        }
+        self.eat(TokenKind::Is);
+
+        let mut variables = Vec::new();
+        while !self.eat(TokenKind::Begin) {
+            if self.eat_word("variable") || self.eat_word("constant") {
+                let mut names = vec![self.identifier()?];
+                while self.eat(TokenKind::Comma) {
+                    names.push(self.identifier()?);
+                }
+                self.expect(TokenKind::Colon)?;
+                let ty = self.parse_type()?;
+                let init = if self.eat(TokenKind::Assignment) {
+                    Some(self.parse_expr()?)
+                } else {
+                    None
+                };
+                self.expect(TokenKind::Semicolon)?;
+                for name in names {
+                    variables.push((name, ty, init.clone()));
+                }
+            } else if self.current().is_none() {
+                return Err(self.error("expected 'begin'"));
+            } else {
+                return Err(self.error("unsupported declaration in process"));
+            }
+        }
+        let body = self.parse_statements()?;
+        self.expect(TokenKind::End)?;
+        let span = Span::new(start.start, self.span().end);
+        self.finish_end()?;
+        Ok(Process {
+            variables,
+            body,
+            span,
+        })
+    }
+
+    /// `end`・`elsif`・`else`・`when`（case の次の選択肢）までの順次文
+    fn parse_statements(&mut self) -> Result<Vec<Stmt>, InterpError> {
+        self.nested(Self::parse_statement_list)
+    }
+
+    fn parse_statement_list(&mut self) -> Result<Vec<Stmt>, InterpError> {
+        let mut statements = Vec::new();
+        loop {
+            let start = self.span();
+            match self.kind().cloned() {
+                None
+                | Some(TokenKind::End)
+                | Some(TokenKind::Elsif)
+                | Some(TokenKind::Else)
+                | Some(TokenKind::When) => return Ok(statements),
+                Some(TokenKind::If) => {
+                    self.pos += 1;
+                    let mut branches = Vec::new();
+                    let condition = self.parse_expr()?;
+                    self.expect(TokenKind::Then)?;
+                    branches.push((condition, self.parse_statements()?));
+                    let mut otherwise = Vec::new();
+                    loop {
+                        if self.eat(TokenKind::Elsif) {
+                            let condition = self.parse_expr()?;
+                            self.expect(TokenKind::Then)?;
+                            branches.push((condition, self.parse_statements()?));
+                        } else if self.eat(TokenKind::Else) {
+                            otherwise = self.parse_statements()?;
+                        } else {
+                            break;
+                        }
+                    }
+                    self.expect(TokenKind::End)?;
+                    self.finish_end()?;
+                    statements.push(Stmt::If(branches, otherwise, start));
+                }
+                Some(TokenKind::Case) => {
+                    self.pos += 1;
+                    let selector = self.parse_expr()?;
+                    self.expect(TokenKind::Is)?;
+                    let mut arms = Vec::new();
+                    while self.eat(TokenKind::When) {
+                        let choices = self.parse_choices()?;
+                        self.expect(TokenKind::Association)?;
+                        arms.push((choices, self.parse_statements()?));
+                    }
+                    self.expect(TokenKind::End)?;
+                    self.finish_end()?;
+                    statements.push(Stmt::Case(selector, arms, start));
+                }
+                _ if self.eat_word("for") => {
+                    let var = self.identifier()?;
+                    self.expect(TokenKind::In)?;
+                    let from = self.parse_expr()?;
+                    if !(self.eat(TokenKind::To) || self.eat(TokenKind::Downto)) {
+                        return Err(self.error("expected 'to' or 'downto'"));
+                    }
+                    let to = self.parse_expr()?;
+                    if !self.eat_word("loop") {
+                        return Err(self.error("expected 'loop'"));
+                    }
+                    let body = self.parse_statements()?;
+                    self.expect(TokenKind::End)?;
+                    self.finish_end()?;
+                    statements.push(Stmt::For(var, from, to, body, start));
+                }
+                _ if self.eat_word("null") => self.expect(TokenKind::Semicolon)?,
+                _ if self.is_word("assert") || self.is_word("report") => self.skip_statement(),
+                _ if self.is_word("wait") => {
+                    return Err(self.error("wait statements are not supported"));
+                }
+                Some(_) => {
+                    let target = self.parse_target()?;
+                    let variable = if self.eat(TokenKind::Assignment) {
+                        true
+                    } else {
+                        self.expect(TokenKind::Lte)?;
+                        false
+                    };
+                    let value = self.parse_waveform()?;
+                    let span = Span::new(start.start, self.span().end);
+                    self.expect(TokenKind::Semicolon)?;
+                    statements.push(if variable {
+                        Stmt::Variable(target, value, span)
+                    } else {
+                        Stmt::Signal(target, value, span)
+                    });
+                }
+            }
+        }
+    }
+
+    fn parse_target(&mut self) -> Result<Target, InterpError> {
+        let name = self.identifier()?;
+        let select = if self.eat(TokenKind::LeftParen) {
+            let arg = self.parse_arg()?;
+            self.expect(TokenKind::RightParen)?;
+            Some(arg)
+        } else {
+            None
+        };
+        Ok(Target { name, select })
+    }
+
+    /// case/選択代入の選択肢
+    ///
+    /// `|` はまだ字句解析できないため、連続した式をそれぞれ別の選択肢とみなす。
+    fn parse_choices(&mut self) -> Result<Vec<Choice>, InterpError> {
+        let mut choices = Vec::new();
+        loop {
+            if self.eat(TokenKind::Others) {
+                choices.push(Choice::Others);
+            } else {
+                choices.push(Choice::Expr(self.parse_expr()?));
+            }
+            if self.eat(TokenKind::Bar) {
+                continue;
+            }
+            match self.kind().cloned() {
+                Some(TokenKind::Association | TokenKind::Comma | TokenKind::Semicolon) | None => {
+                    return Ok(choices);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_arg(&mut self) -> Result<Arg, InterpError> {
+        let left = self.parse_expr()?;
+        if self.eat(TokenKind::Downto) || self.eat(TokenKind::To) {
+            let right = self.parse_expr()?;
+            Ok(Arg::Range(left, right))
+        } else {
+            Ok(Arg::Expr(left))
+        }
+    }
+
+    // 式: 論理 < 関係 < 加減・連結 < 符号 < 乗除 < not
+
+    fn parse_expr(&mut self) -> Result<Expr, InterpError> {
+        self.nested(Self::parse_logical)
+    }
+
+    fn parse_logical(&mut self) -> Result<Expr, InterpError> {
+        let mut left = self.parse_relation()?;
+        let mut length = 0;
+        loop {
+            let op = match self.kind().cloned() {
+                Some(TokenKind::And) => BinOp::And,
+                Some(TokenKind::Or) => BinOp::Or,
+                Some(TokenKind::Xor) => BinOp::Xor,
+                Some(TokenKind::Nand) => BinOp::Nand,
+                Some(TokenKind::Nor) => BinOp::Nor,
+                Some(TokenKind::Xnor) => BinOp::Xnor,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            length += 1;
+            self.check_chain(length)?;
+            let right = self.parse_relation()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_relation(&mut self) -> Result<Expr, InterpError> {
+        let left = self.parse_simple()?;
+        let op = match self.kind().cloned() {
+            Some(TokenKind::Eq) => BinOp::Eq,
+            Some(TokenKind::Neq) => BinOp::Neq,
+            Some(TokenKind::Lt) => BinOp::Lt,
+            Some(TokenKind::Lte) => BinOp::Lte,
+            Some(TokenKind::Gt) => BinOp::Gt,
+            Some(TokenKind::Gte) => BinOp::Gte,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.parse_simple()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_simple(&mut self) -> Result<Expr, InterpError> {
+        let negative = self.eat(TokenKind::Minus);
+        if !negative {
+            self.eat(TokenKind::Plus);
+        }
+        let mut left = self.parse_term()?;
+        if negative {
+            left = Expr::Neg(Box::new(left));
+        }
+        let mut length = 0;
+        loop {
+            let op = match self.kind().cloned() {
+                Some(TokenKind::Plus) => BinOp::Add,
+                Some(TokenKind::Minus) => BinOp::Sub,
+                Some(TokenKind::Ampersand) => BinOp::Concat,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            length += 1;
+            self.check_chain(length)?;
+            let right = self.parse_term()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, InterpError> {
+        let mut left = self.parse_factor()?;
+        let mut length = 0;
+        loop {
+            let op = match self.kind().cloned() {
+                Some(TokenKind::Star) => BinOp::Mul,
+                Some(TokenKind::Slash) => BinOp::Div,
+                Some(TokenKind::Mod) => BinOp::Mod,
+                Some(TokenKind::Rem) => BinOp::Rem,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            length += 1;
+            self.check_chain(length)?;
+            let right = self.parse_factor()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, InterpError> {
+        if self.eat(TokenKind::Not) {
+            return Ok(Expr::Not(Box::new(self.nested(Self::parse_factor)?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, InterpError> {
+        let token = self
+            .current()
+            .cloned()
+            .ok_or_else(|| self.error("expected expression"))?;
+        self.pos += 1;
+        match token.kind {
+            TokenKind::CharacterLiteral => token
+                .text
+                .chars()
+                .nth(1)
+                .and_then(StdLogic::from_char)
+                .map(|b| Expr::Literal(V::Logic(b)))
+                .ok_or_else(|| InterpError::new("unsupported character literal", token.span)),
+            TokenKind::StringLiteral => LogicVector::parse(token.text.trim_matches('"'))
+                .map(|bits| Expr::Literal(V::Vector(bits)))
+                .ok_or_else(|| InterpError::new("unsupported string literal", token.span)),
+            TokenKind::BitStringLiteral => crate::literal::bit_string_vector(token.text)
+                .map(|bits| Expr::Literal(V::Vector(bits)))
+                .ok_or_else(|| InterpError::new("invalid bit string literal", token.span)),
+            TokenKind::IntegerLiteral => crate::literal::parse_integer(token.text)
+                .and_then(|n| i64::try_from(n).ok())
+                .map(|n| Expr::Literal(V::Integer(n)))
+                .ok_or_else(|| InterpError::new("unsupported numeric literal", token.span)),
+            TokenKind::LeftParen => {
+                if self.eat(TokenKind::Others) {
+                    self.expect(TokenKind::Association)?;
+                    let value = self.parse_expr()?;
+                    self.expect(TokenKind::RightParen)?;
+                    return Ok(Expr::Others(Box::new(value)));
+                }
+                let inner = self.parse_expr()?;
+                self.expect(TokenKind::RightParen)?;
+                Ok(inner)
+            }
+            // 型名と同じ綴りのキーワードも変換関数として呼べる
+            TokenKind::Identifier | TokenKind::StdLogicVector | TokenKind::Integer => {
+                let name = token.text.to_lowercase();
+                match name.as_str() {
+                    "true" => return Ok(Expr::Literal(V::Boolean(true))),
+                    "false" => return Ok(Expr::Literal(V::Boolean(false))),
+                    _ => {}
+                }
+                if self.eat(TokenKind::LeftParen) {
+                    let mut args = vec![self.parse_arg()?];
+                    while self.eat(TokenKind::Comma) {
+                        args.push(self.parse_arg()?);
+                    }
+                    self.expect(TokenKind::RightParen)?;
+                    Ok(Expr::Apply(name, args))
+                } else {
+                    Ok(Expr::Name(name))
+                }
+            }
+            _ => Err(InterpError::new(
+                format!("unexpected '{}' in expression", token.text),
+                token.span,
+            )),
+        }
+    }
+}
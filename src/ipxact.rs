@@ -0,0 +1,185 @@
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType, parse_type_str};
+use crate::lexer::Span;
+use crate::xml::{self, Element};
+
+/// IP-XACT取り込み時のエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl ImportError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<xml::XmlError> for ImportError {
+    fn from(err: xml::XmlError) -> Self {
+        Self::new(err.message, err.span)
+    }
+}
+
+/// IP-XACTコンポーネントXML（IEEE 1685-2009/2014/2022）をEntityDefに変換する
+///
+/// `spirit:`/`ipxact:` いずれの名前空間プレフィックスも受け付けます。
+/// wireポートのみを対象とし、transactionalポートは無視します。
+pub fn import_component(source: &str) -> Result<EntityDef, ImportError> {
+    let root = xml::parse(source)?;
+    if root.name != "component" {
+        return Err(ImportError::new(
+            format!("expected <component> root element, found <{}>", root.name),
+            root.span,
+        ));
+    }
+
+    let name = root
+        .child_text("name")
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| ImportError::new("component has no <name>", root.span))?
+        .to_string();
+
+    let mut ports = Vec::new();
+    if let Some(port_list) = root.child("model").and_then(|m| m.child("ports")) {
+        for port in port_list.children_named("port") {
+            if let Some(def) = convert_port(port)? {
+                ports.push(def);
+            }
+        }
+    }
+
+    Ok(EntityDef {
+        name,
+        generics: Vec::new(),
+        ports,
+        span: root.span,
+    })
+}
+
+/// `<port>` 要素を変換（wireでなければNone）
+fn convert_port(port: &Element) -> Result<Option<PortDef>, ImportError> {
+    let name = port
+        .child_text("name")
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| ImportError::new("port has no <name>", port.span))?
+        .to_string();
+
+    let Some(wire) = port.child("wire") else {
+        return Ok(None);
+    };
+
+    let direction = match wire.child_text("direction") {
+        Some("in") => PortDirection::In,
+        Some("out") => PortDirection::Out,
+        Some("inout") => PortDirection::Inout,
+        // phantomポートは実体を持たない
+        Some("phantom") => return Ok(None),
+        Some(other) => {
+            return Err(ImportError::new(
+                format!("unknown port direction '{}' on port '{}'", other, name),
+                wire.span,
+            ));
+        }
+        None => {
+            return Err(ImportError::new(
+                format!("port '{}' has no <direction>", name),
+                wire.span,
+            ));
+        }
+    };
+
+    let vhdl_type = convert_type(wire)?;
+
+    Ok(Some(PortDef {
+        name,
+        direction,
+        vhdl_type,
+        span: port.span,
+    }))
+}
+
+/// `<wire>` の型情報をVhdlTypeに変換
+fn convert_type(wire: &Element) -> Result<VhdlType, ImportError> {
+    // 2009: <vector> 直下, 2014以降: <vectors><vector>
+    let vector = wire
+        .child("vector")
+        .or_else(|| wire.child("vectors").and_then(|v| v.child("vector")));
+    let bounds = match vector {
+        Some(v) => {
+            let (left, right) = (bound(v, "left")?, bound(v, "right")?);
+            // left < right は `to` の範囲
+            Some((left.max(right), left.min(right), left < right))
+        }
+        None => None,
+    };
+
+    let type_name = wire
+        .child("wireTypeDefs")
+        .and_then(|defs| defs.child("wireTypeDef"))
+        .and_then(|def| def.child_text("typeName"))
+        .map(|t| t.to_lowercase());
+
+    Ok(match (type_name.as_deref(), bounds) {
+        (Some("std_logic") | Some("std_ulogic"), _) => VhdlType::StdLogic,
+        (Some("integer"), _) => VhdlType::Integer(None),
+        (Some("boolean"), _) => VhdlType::Boolean,
+        (
+            Some("std_logic_vector") | Some("std_ulogic_vector") | None,
+            Some((high, low, ascending)),
+        ) => VhdlType::StdLogicVector {
+            high: high.into(),
+            low: low.into(),
+            ascending,
+        },
+        (Some("signed"), Some((high, low, ascending))) => VhdlType::Signed {
+            high: high.into(),
+            low: low.into(),
+            ascending,
+        },
+        (Some("unsigned"), Some((high, low, ascending))) => VhdlType::Unsigned {
+            high: high.into(),
+            low: low.into(),
+            ascending,
+        },
+        (Some("bit_vector"), Some((high, low, ascending))) => VhdlType::BitVector {
+            high: high.into(),
+            low: low.into(),
+            ascending,
+        },
+        (None, None) => VhdlType::StdLogic,
+        // natural・time などの標準の型名は専用の型にする
+        (Some(other), _) => match parse_type_str(other) {
+            Ok(t) if t.vector_range().is_none() => t,
+            _ => VhdlType::Other(other.to_string()),
+        },
+    })
+}
+
+/// `<left>`/`<right>` の値を整数として取得
+fn bound(vector: &Element, name: &str) -> Result<i64, ImportError> {
+    let text = vector
+        .child_text(name)
+        .ok_or_else(|| ImportError::new(format!("vector has no <{}>", name), vector.span))?;
+    text.parse().map_err(|_| {
+        ImportError::new(
+            format!("unsupported vector bound '{}' (integer expected)", text),
+            vector.span,
+        )
+    })
+}
@@ -1,14 +1,63 @@
+use std::cell::OnceCell;
+use std::collections::VecDeque;
+
+use crate::literal::{self, Numeric};
+use crate::source_map::{Location, SourceMap};
+
 /// VHDLのトークンの種類を表す列挙型
-#[derive(Debug, Clone, PartialEq)]
+///
+/// 予約語・演算子・リテラルなどは分類ごとの列挙型（[`Keyword`] [`Op`] [`LitKind`] [`TypeName`]
+/// [`Delim`]）にまとめてあり、分類単位で照合できます。新しい予約語を加えても
+/// `TokenKind` 自体の形は変わりません。
+///
+/// 分類前の平らな名前（`TokenKind::Entity` `TokenKind::Lte` `TokenKind::IntegerLiteral` など）は
+/// 関連定数として残してあり、式にもパターンにもそのまま使えます
+/// （予約語の `literal` は分類の `TokenKind::Literal` と重なるため `TokenKind::LiteralKeyword` です）。
+/// 関連定数のパターンは参照を通らないため、`&TokenKind` と照合するときは `*kind` や
+/// `.cloned()` で値にしてから照合します。
+///
+/// ```
+/// use vig::lexer::{Keyword, Lexer, Op, TokenKind};
+///
+/// let kinds: Vec<TokenKind> = Lexer::new("if a <= b").map(|t| t.unwrap().kind).collect();
+/// assert_eq!(kinds[0], TokenKind::Keyword(Keyword::If));
+/// assert!(matches!(kinds[2], TokenKind::Op(Op::Lte)));
+/// assert_eq!(kinds[2], TokenKind::Lte);
+/// ```
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum TokenKind {
-    // 識別子とリテラル
     Identifier,
-    Number,
-    BitStringLiteral, // B"1010", X"FF"など
-    CharacterLiteral, // '0', '1'など
-    StringLiteral,
+    Literal(LitKind),
+    /// 予約語（`and` `mod` などの演算子は [`TokenKind::Op`]）
+    Keyword(Keyword),
+    /// 組み込みの型名
+    TypeName(TypeName),
+    /// 記号と予約語の演算子（`:=` `=>` を含む）
+    Op(Op),
+    /// 括弧・区切り記号（`|` `<>` を含む）
+    Delim(Delim),
+    Comment,
+    Eof,
+    /// エラー（Lexer::tokenize_with_errors が読めなかった部分）
+    Unknown,
+}
 
-    // VHDLキーワード
+/// リテラルの種類
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LitKind {
+    Integer,   // 42, 1_000, 1e3, 16#FF#
+    Real,      // 1.5, 2.0e-3, 16#F.8#E1
+    BitString, // B"1010", X"FF"など
+    Character, // '0', '1'など
+    String,
+}
+
+/// 予約語（演算子の予約語を除く）
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Keyword {
     Entity,
     Architecture,
     Port,
@@ -36,14 +85,94 @@ pub enum TokenKind {
     Component,
     To,
     Downto,
+    Access,
+    After,
+    Alias,
+    All,
+    Array,
+    Assert,
+    Assume,
+    AssumeGuarantee,
+    Attribute,
+    Block,
+    Body,
+    Bus,
+    Configuration,
+    Constant,
+    Context,
+    Cover,
+    Default,
+    Disconnect,
+    Exit,
+    Fairness,
+    File,
+    For,
+    Force,
+    Function,
+    Generate,
+    Group,
+    Guarded,
+    Impure,
+    Inertial,
+    Label,
+    Linkage,
+    Literal,
+    Loop,
+    New,
+    Next,
+    Null,
+    On,
+    Open,
+    Package,
+    Parameter,
+    Postponed,
+    Procedure,
+    Property,
+    Protected,
+    Pure,
+    Range,
+    Record,
+    Register,
+    Reject,
+    Release,
+    Report,
+    Restrict,
+    RestrictGuarantee,
+    Return,
+    Select,
+    Sequence,
+    Severity,
+    Shared,
+    Strong,
+    Subtype,
+    Transport,
+    Type,
+    Unaffected,
+    Units,
+    Until,
+    Variable,
+    Vmode,
+    Vprop,
+    Vunit,
+    Wait,
+    While,
+    With,
+}
 
-    // 型
+/// 組み込みの型名
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeName {
     StdLogic,
     StdLogicVector,
     Integer,
     Boolean,
+}
 
-    // 演算子
+/// 演算子
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
     Assignment,  // :=
     Association, // =>
     Eq,          // =
@@ -57,6 +186,13 @@ pub enum TokenKind {
     Star,        // *
     Slash,       // /
     Power,       // **
+    Condition,   // ??
+    MatchEq,     // ?=
+    MatchNeq,    // ?/=
+    MatchLt,     // ?<
+    MatchLte,    // ?<=
+    MatchGt,     // ?>
+    MatchGte,    // ?>=
     Ampersand,   // &
     And,
     Or,
@@ -64,24 +200,329 @@ pub enum TokenKind {
     Xor,
     Nand,
     Nor,
+    Xnor,
+    Mod,
+    Rem,
+    Abs,
+    Sll,
+    Srl,
+    Sla,
+    Sra,
+    Rol,
+    Ror,
+}
 
-    // 区切り文字
-    LeftParen,  // (
-    RightParen, // )
-    Semicolon,  // ;
-    Colon,      // :
-    Comma,      // ,
-    Dot,        // .
-    Apostrophe, // '
+/// 括弧・区切り記号
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Delim {
+    LeftParen,    // (
+    RightParen,   // )
+    LeftBracket,  // [
+    RightBracket, // ]
+    Bar,          // |
+    Box,          // <>
+    Semicolon,    // ;
+    Colon,        // :
+    Comma,        // ,
+    Dot,          // .
+    Apostrophe,   // '
+}
 
-    // 特殊トークン
+impl From<LitKind> for TokenKind {
+    fn from(kind: LitKind) -> Self {
+        TokenKind::Literal(kind)
+    }
+}
+
+impl From<Keyword> for TokenKind {
+    fn from(kind: Keyword) -> Self {
+        TokenKind::Keyword(kind)
+    }
+}
+
+impl From<TypeName> for TokenKind {
+    fn from(kind: TypeName) -> Self {
+        TokenKind::TypeName(kind)
+    }
+}
+
+impl From<Op> for TokenKind {
+    fn from(kind: Op) -> Self {
+        TokenKind::Op(kind)
+    }
+}
+
+impl From<Delim> for TokenKind {
+    fn from(kind: Delim) -> Self {
+        TokenKind::Delim(kind)
+    }
+}
+
+/// 分類前の平らな名前で表示する（`Op(Lte)` ではなく `Lte`。エラーメッセージに使う）
+impl std::fmt::Debug for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Identifier => f.write_str("Identifier"),
+            TokenKind::Literal(kind) => write!(f, "{:?}Literal", kind),
+            TokenKind::Keyword(keyword) => keyword.fmt(f),
+            TokenKind::TypeName(name) => name.fmt(f),
+            TokenKind::Op(op) => op.fmt(f),
+            TokenKind::Delim(delim) => delim.fmt(f),
+            TokenKind::Comment => f.write_str("Comment"),
+            TokenKind::Eof => f.write_str("Eof"),
+            TokenKind::Unknown => f.write_str("Unknown"),
+        }
+    }
+}
+
+/// 分類前の平らな名前（互換のため）
+#[allow(non_upper_case_globals)]
+impl TokenKind {
+    pub const IntegerLiteral: TokenKind = TokenKind::Literal(LitKind::Integer);
+    pub const RealLiteral: TokenKind = TokenKind::Literal(LitKind::Real);
+    pub const BitStringLiteral: TokenKind = TokenKind::Literal(LitKind::BitString);
+    pub const CharacterLiteral: TokenKind = TokenKind::Literal(LitKind::Character);
+    pub const StringLiteral: TokenKind = TokenKind::Literal(LitKind::String);
+    pub const Entity: TokenKind = TokenKind::Keyword(Keyword::Entity);
+    pub const Architecture: TokenKind = TokenKind::Keyword(Keyword::Architecture);
+    pub const Port: TokenKind = TokenKind::Keyword(Keyword::Port);
+    pub const Signal: TokenKind = TokenKind::Keyword(Keyword::Signal);
+    pub const Process: TokenKind = TokenKind::Keyword(Keyword::Process);
+    pub const Begin: TokenKind = TokenKind::Keyword(Keyword::Begin);
+    pub const End: TokenKind = TokenKind::Keyword(Keyword::End);
+    pub const If: TokenKind = TokenKind::Keyword(Keyword::If);
+    pub const Then: TokenKind = TokenKind::Keyword(Keyword::Then);
+    pub const Else: TokenKind = TokenKind::Keyword(Keyword::Else);
+    pub const Elsif: TokenKind = TokenKind::Keyword(Keyword::Elsif);
+    pub const Case: TokenKind = TokenKind::Keyword(Keyword::Case);
+    pub const When: TokenKind = TokenKind::Keyword(Keyword::When);
+    pub const Is: TokenKind = TokenKind::Keyword(Keyword::Is);
+    pub const Of: TokenKind = TokenKind::Keyword(Keyword::Of);
+    pub const Others: TokenKind = TokenKind::Keyword(Keyword::Others);
+    pub const Library: TokenKind = TokenKind::Keyword(Keyword::Library);
+    pub const Use: TokenKind = TokenKind::Keyword(Keyword::Use);
+    pub const In: TokenKind = TokenKind::Keyword(Keyword::In);
+    pub const Out: TokenKind = TokenKind::Keyword(Keyword::Out);
+    pub const Inout: TokenKind = TokenKind::Keyword(Keyword::Inout);
+    pub const Buffer: TokenKind = TokenKind::Keyword(Keyword::Buffer);
+    pub const Generic: TokenKind = TokenKind::Keyword(Keyword::Generic);
+    pub const Map: TokenKind = TokenKind::Keyword(Keyword::Map);
+    pub const Component: TokenKind = TokenKind::Keyword(Keyword::Component);
+    pub const To: TokenKind = TokenKind::Keyword(Keyword::To);
+    pub const Downto: TokenKind = TokenKind::Keyword(Keyword::Downto);
+    pub const Access: TokenKind = TokenKind::Keyword(Keyword::Access);
+    pub const After: TokenKind = TokenKind::Keyword(Keyword::After);
+    pub const Alias: TokenKind = TokenKind::Keyword(Keyword::Alias);
+    pub const All: TokenKind = TokenKind::Keyword(Keyword::All);
+    pub const Array: TokenKind = TokenKind::Keyword(Keyword::Array);
+    pub const Assert: TokenKind = TokenKind::Keyword(Keyword::Assert);
+    pub const Assume: TokenKind = TokenKind::Keyword(Keyword::Assume);
+    pub const AssumeGuarantee: TokenKind = TokenKind::Keyword(Keyword::AssumeGuarantee);
+    pub const Attribute: TokenKind = TokenKind::Keyword(Keyword::Attribute);
+    pub const Block: TokenKind = TokenKind::Keyword(Keyword::Block);
+    pub const Body: TokenKind = TokenKind::Keyword(Keyword::Body);
+    pub const Bus: TokenKind = TokenKind::Keyword(Keyword::Bus);
+    pub const Configuration: TokenKind = TokenKind::Keyword(Keyword::Configuration);
+    pub const Constant: TokenKind = TokenKind::Keyword(Keyword::Constant);
+    pub const Context: TokenKind = TokenKind::Keyword(Keyword::Context);
+    pub const Cover: TokenKind = TokenKind::Keyword(Keyword::Cover);
+    pub const Default: TokenKind = TokenKind::Keyword(Keyword::Default);
+    pub const Disconnect: TokenKind = TokenKind::Keyword(Keyword::Disconnect);
+    pub const Exit: TokenKind = TokenKind::Keyword(Keyword::Exit);
+    pub const Fairness: TokenKind = TokenKind::Keyword(Keyword::Fairness);
+    pub const File: TokenKind = TokenKind::Keyword(Keyword::File);
+    pub const For: TokenKind = TokenKind::Keyword(Keyword::For);
+    pub const Force: TokenKind = TokenKind::Keyword(Keyword::Force);
+    pub const Function: TokenKind = TokenKind::Keyword(Keyword::Function);
+    pub const Generate: TokenKind = TokenKind::Keyword(Keyword::Generate);
+    pub const Group: TokenKind = TokenKind::Keyword(Keyword::Group);
+    pub const Guarded: TokenKind = TokenKind::Keyword(Keyword::Guarded);
+    pub const Impure: TokenKind = TokenKind::Keyword(Keyword::Impure);
+    pub const Inertial: TokenKind = TokenKind::Keyword(Keyword::Inertial);
+    pub const Label: TokenKind = TokenKind::Keyword(Keyword::Label);
+    pub const Linkage: TokenKind = TokenKind::Keyword(Keyword::Linkage);
+    /// 予約語の `literal`（`TokenKind::Literal` はリテラルの分類と重なるため別名）
+    pub const LiteralKeyword: TokenKind = TokenKind::Keyword(Keyword::Literal);
+    pub const Loop: TokenKind = TokenKind::Keyword(Keyword::Loop);
+    pub const New: TokenKind = TokenKind::Keyword(Keyword::New);
+    pub const Next: TokenKind = TokenKind::Keyword(Keyword::Next);
+    pub const Null: TokenKind = TokenKind::Keyword(Keyword::Null);
+    pub const On: TokenKind = TokenKind::Keyword(Keyword::On);
+    pub const Open: TokenKind = TokenKind::Keyword(Keyword::Open);
+    pub const Package: TokenKind = TokenKind::Keyword(Keyword::Package);
+    pub const Parameter: TokenKind = TokenKind::Keyword(Keyword::Parameter);
+    pub const Postponed: TokenKind = TokenKind::Keyword(Keyword::Postponed);
+    pub const Procedure: TokenKind = TokenKind::Keyword(Keyword::Procedure);
+    pub const Property: TokenKind = TokenKind::Keyword(Keyword::Property);
+    pub const Protected: TokenKind = TokenKind::Keyword(Keyword::Protected);
+    pub const Pure: TokenKind = TokenKind::Keyword(Keyword::Pure);
+    pub const Range: TokenKind = TokenKind::Keyword(Keyword::Range);
+    pub const Record: TokenKind = TokenKind::Keyword(Keyword::Record);
+    pub const Register: TokenKind = TokenKind::Keyword(Keyword::Register);
+    pub const Reject: TokenKind = TokenKind::Keyword(Keyword::Reject);
+    pub const Release: TokenKind = TokenKind::Keyword(Keyword::Release);
+    pub const Report: TokenKind = TokenKind::Keyword(Keyword::Report);
+    pub const Restrict: TokenKind = TokenKind::Keyword(Keyword::Restrict);
+    pub const RestrictGuarantee: TokenKind = TokenKind::Keyword(Keyword::RestrictGuarantee);
+    pub const Return: TokenKind = TokenKind::Keyword(Keyword::Return);
+    pub const Select: TokenKind = TokenKind::Keyword(Keyword::Select);
+    pub const Sequence: TokenKind = TokenKind::Keyword(Keyword::Sequence);
+    pub const Severity: TokenKind = TokenKind::Keyword(Keyword::Severity);
+    pub const Shared: TokenKind = TokenKind::Keyword(Keyword::Shared);
+    pub const Strong: TokenKind = TokenKind::Keyword(Keyword::Strong);
+    pub const Subtype: TokenKind = TokenKind::Keyword(Keyword::Subtype);
+    pub const Transport: TokenKind = TokenKind::Keyword(Keyword::Transport);
+    pub const Type: TokenKind = TokenKind::Keyword(Keyword::Type);
+    pub const Unaffected: TokenKind = TokenKind::Keyword(Keyword::Unaffected);
+    pub const Units: TokenKind = TokenKind::Keyword(Keyword::Units);
+    pub const Until: TokenKind = TokenKind::Keyword(Keyword::Until);
+    pub const Variable: TokenKind = TokenKind::Keyword(Keyword::Variable);
+    pub const Vmode: TokenKind = TokenKind::Keyword(Keyword::Vmode);
+    pub const Vprop: TokenKind = TokenKind::Keyword(Keyword::Vprop);
+    pub const Vunit: TokenKind = TokenKind::Keyword(Keyword::Vunit);
+    pub const Wait: TokenKind = TokenKind::Keyword(Keyword::Wait);
+    pub const While: TokenKind = TokenKind::Keyword(Keyword::While);
+    pub const With: TokenKind = TokenKind::Keyword(Keyword::With);
+    pub const StdLogic: TokenKind = TokenKind::TypeName(TypeName::StdLogic);
+    pub const StdLogicVector: TokenKind = TokenKind::TypeName(TypeName::StdLogicVector);
+    pub const Integer: TokenKind = TokenKind::TypeName(TypeName::Integer);
+    pub const Boolean: TokenKind = TokenKind::TypeName(TypeName::Boolean);
+    pub const Assignment: TokenKind = TokenKind::Op(Op::Assignment);
+    pub const Association: TokenKind = TokenKind::Op(Op::Association);
+    pub const Eq: TokenKind = TokenKind::Op(Op::Eq);
+    pub const Neq: TokenKind = TokenKind::Op(Op::Neq);
+    pub const Lt: TokenKind = TokenKind::Op(Op::Lt);
+    pub const Lte: TokenKind = TokenKind::Op(Op::Lte);
+    pub const Gt: TokenKind = TokenKind::Op(Op::Gt);
+    pub const Gte: TokenKind = TokenKind::Op(Op::Gte);
+    pub const Plus: TokenKind = TokenKind::Op(Op::Plus);
+    pub const Minus: TokenKind = TokenKind::Op(Op::Minus);
+    pub const Star: TokenKind = TokenKind::Op(Op::Star);
+    pub const Slash: TokenKind = TokenKind::Op(Op::Slash);
+    pub const Power: TokenKind = TokenKind::Op(Op::Power);
+    pub const Condition: TokenKind = TokenKind::Op(Op::Condition);
+    pub const MatchEq: TokenKind = TokenKind::Op(Op::MatchEq);
+    pub const MatchNeq: TokenKind = TokenKind::Op(Op::MatchNeq);
+    pub const MatchLt: TokenKind = TokenKind::Op(Op::MatchLt);
+    pub const MatchLte: TokenKind = TokenKind::Op(Op::MatchLte);
+    pub const MatchGt: TokenKind = TokenKind::Op(Op::MatchGt);
+    pub const MatchGte: TokenKind = TokenKind::Op(Op::MatchGte);
+    pub const Ampersand: TokenKind = TokenKind::Op(Op::Ampersand);
+    pub const And: TokenKind = TokenKind::Op(Op::And);
+    pub const Or: TokenKind = TokenKind::Op(Op::Or);
+    pub const Not: TokenKind = TokenKind::Op(Op::Not);
+    pub const Xor: TokenKind = TokenKind::Op(Op::Xor);
+    pub const Nand: TokenKind = TokenKind::Op(Op::Nand);
+    pub const Nor: TokenKind = TokenKind::Op(Op::Nor);
+    pub const Xnor: TokenKind = TokenKind::Op(Op::Xnor);
+    pub const Mod: TokenKind = TokenKind::Op(Op::Mod);
+    pub const Rem: TokenKind = TokenKind::Op(Op::Rem);
+    pub const Abs: TokenKind = TokenKind::Op(Op::Abs);
+    pub const Sll: TokenKind = TokenKind::Op(Op::Sll);
+    pub const Srl: TokenKind = TokenKind::Op(Op::Srl);
+    pub const Sla: TokenKind = TokenKind::Op(Op::Sla);
+    pub const Sra: TokenKind = TokenKind::Op(Op::Sra);
+    pub const Rol: TokenKind = TokenKind::Op(Op::Rol);
+    pub const Ror: TokenKind = TokenKind::Op(Op::Ror);
+    pub const LeftParen: TokenKind = TokenKind::Delim(Delim::LeftParen);
+    pub const RightParen: TokenKind = TokenKind::Delim(Delim::RightParen);
+    pub const LeftBracket: TokenKind = TokenKind::Delim(Delim::LeftBracket);
+    pub const RightBracket: TokenKind = TokenKind::Delim(Delim::RightBracket);
+    pub const Bar: TokenKind = TokenKind::Delim(Delim::Bar);
+    pub const Box: TokenKind = TokenKind::Delim(Delim::Box);
+    pub const Semicolon: TokenKind = TokenKind::Delim(Delim::Semicolon);
+    pub const Colon: TokenKind = TokenKind::Delim(Delim::Colon);
+    pub const Comma: TokenKind = TokenKind::Delim(Delim::Comma);
+    pub const Dot: TokenKind = TokenKind::Delim(Delim::Dot);
+    pub const Apostrophe: TokenKind = TokenKind::Delim(Delim::Apostrophe);
+}
+
+/// トークンの種類の大まかな分類（[`TokenKind::category`]）
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenCategory {
+    Identifier,
+    Literal,
+    /// 予約語（`and` `mod` などの演算子は `Operator`）
+    Keyword,
+    /// 組み込みの型名（`std_logic` `integer` など）
+    Type,
+    /// 記号と予約語の演算子（`:=` `=>` を含む）
+    Operator,
+    /// 括弧・区切り記号（`|` `<>` を含む）
+    Delimiter,
     Comment,
     Eof,
-
-    // エラー
     Unknown,
 }
 
+impl TokenKind {
+    /// 役割による分類
+    pub fn category(&self) -> TokenCategory {
+        match self {
+            TokenKind::Identifier => TokenCategory::Identifier,
+            TokenKind::Literal(_) => TokenCategory::Literal,
+            TokenKind::Keyword(_) => TokenCategory::Keyword,
+            TokenKind::TypeName(_) => TokenCategory::Type,
+            TokenKind::Op(_) => TokenCategory::Operator,
+            TokenKind::Delim(_) => TokenCategory::Delimiter,
+            TokenKind::Comment => TokenCategory::Comment,
+            TokenKind::Eof => TokenCategory::Eof,
+            TokenKind::Unknown => TokenCategory::Unknown,
+        }
+    }
+
+    /// 予約語か（`and` `mod` などの演算子も含む。[`crate::ident::is_reserved`] と同じ範囲）
+    pub fn is_keyword(&self) -> bool {
+        match self {
+            TokenKind::Keyword(_) => true,
+            TokenKind::Op(op) => op.is_word(),
+            _ => false,
+        }
+    }
+
+    /// 演算子か（記号と予約語の両方）
+    pub fn is_operator(&self) -> bool {
+        self.category() == TokenCategory::Operator
+    }
+
+    /// リテラルか
+    pub fn is_literal(&self) -> bool {
+        self.category() == TokenCategory::Literal
+    }
+
+    /// 組み込みの型名か
+    pub fn is_type(&self) -> bool {
+        self.category() == TokenCategory::Type
+    }
+}
+
+impl Op {
+    /// 予約語の演算子（`and` `mod` `sll` など）か
+    pub fn is_word(&self) -> bool {
+        matches!(
+            self,
+            Op::And
+                | Op::Or
+                | Op::Not
+                | Op::Xor
+                | Op::Nand
+                | Op::Nor
+                | Op::Xnor
+                | Op::Mod
+                | Op::Rem
+                | Op::Abs
+                | Op::Sll
+                | Op::Srl
+                | Op::Sla
+                | Op::Sra
+                | Op::Rol
+                | Op::Ror
+        )
+    }
+}
+
 /// トークンの位置情報
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Span {
@@ -104,16 +545,54 @@ impl Span {
 }
 
 /// トークン本体
+///
+/// 字句はソースから切り出したまま借用します（トークンごとに文字列を確保しない）。
+/// ソースより長く持つには `text.to_string()` で複製してください。
 #[derive(Debug, Clone, PartialEq)]
-pub struct Token {
+pub struct Token<'source> {
     pub kind: TokenKind,
     pub span: Span,
-    pub text: String,
+    pub text: &'source str,
+    /// 前に付く空白とコメント（[`Lexer::with_trivia`] のときだけ。それ以外は空）
+    pub leading: &'source str,
+    /// 後ろに付く、同じ行の空白とコメントと改行（[`Lexer::with_trivia`] のときだけ）
+    pub trailing: &'source str,
 }
 
-impl Token {
-    pub fn new(kind: TokenKind, span: Span, text: String) -> Self {
-        Self { kind, span, text }
+impl<'source> Token<'source> {
+    pub fn new(kind: TokenKind, span: Span, text: &'source str) -> Self {
+        Self {
+            kind,
+            span,
+            text,
+            leading: "",
+            trailing: "",
+        }
+    }
+
+    /// 前後のトリビアを含めた範囲
+    pub fn full_span(&self) -> Span {
+        Span::new(
+            self.span.start - self.leading.len(),
+            self.span.end + self.trailing.len(),
+        )
+    }
+
+    /// 数値リテラルの値（`IntegerLiteral`・`RealLiteral` 以外か、値が範囲外なら None）
+    pub fn numeric_value(&self) -> Option<Numeric> {
+        match self.kind {
+            TokenKind::IntegerLiteral => literal::parse_integer(self.text).map(Numeric::Integer),
+            TokenKind::RealLiteral => literal::parse_real(self.text).map(Numeric::Real),
+            _ => None,
+        }
+    }
+
+    /// 文字列リテラルの中身（`""` を `"` に戻したもの。`StringLiteral` 以外なら None）
+    pub fn string_value(&self) -> Option<String> {
+        match self.kind {
+            TokenKind::StringLiteral => literal::parse_string(self.text),
+            _ => None,
+        }
     }
 }
 
@@ -122,6 +601,8 @@ impl Token {
 pub struct LexError {
     pub message: String,
     pub span: Span,
+    /// 開始位置の行・列（Lexerが返すエラーには必ず付く）
+    pub location: Option<Location>,
 }
 
 impl LexError {
@@ -129,219 +610,792 @@ impl LexError {
         Self {
             message: message.into(),
             span,
+            location: None,
         }
     }
+
+    /// 位置にファイル名を付ける
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        if let Some(location) = &mut self.location {
+            location.file = Some(file.into());
+        }
+        self
+    }
 }
 
 impl std::fmt::Display for LexError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} at position {}-{}",
-            self.message, self.span.start, self.span.end
-        )
+        match &self.location {
+            Some(location) => write!(f, "{}: {}", location, self.message),
+            None => write!(
+                f,
+                "{} at position {}-{}",
+                self.message, self.span.start, self.span.end
+            ),
+        }
     }
 }
 
 impl std::error::Error for LexError {}
 
+/// バイトオーダーマーク
+pub(crate) const BOM: char = '\u{feff}';
+
+/// 先頭の BOM の長さ（なければ 0）
+pub(crate) fn skip_bom(source: &str) -> usize {
+    if source.starts_with(BOM) {
+        BOM.len_utf8()
+    } else {
+        0
+    }
+}
+
+/// ビット列リテラルの基数指定（`B` `O` `X` `D` と VHDL-2008 の `UB` `SX` など）と
+/// 続く `"` があれば、基数指定の長さ
+fn base_specifier_len(rest: &str) -> Option<usize> {
+    let bytes = rest.as_bytes();
+    let signed = matches!(bytes.first(), Some(b'u' | b'U' | b's' | b'S'));
+    let len = if signed && matches!(bytes.get(1), Some(b'b' | b'B' | b'o' | b'O' | b'x' | b'X')) {
+        2
+    } else if matches!(
+        bytes.first(),
+        Some(b'b' | b'B' | b'o' | b'O' | b'x' | b'X' | b'd' | b'D')
+    ) {
+        1
+    } else {
+        return None;
+    };
+    (bytes.get(len) == Some(&b'"')).then_some(len)
+}
+
+/// トークンの先頭バイトの分類（[`Lexer::scan_token`] はこれで読み方を振り分ける）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteClass {
+    /// ASCII以外か、トークンを始めない文字
+    Other,
+    /// ASCIIの空白（垂直タブを含む）
+    Space,
+    /// 英字と `_`（基数指定になり得る文字を除く）
+    Letter,
+    /// 基数指定（`b` `o` `x` `d` `u` `s`）になり得る英字
+    BaseLetter,
+    Digit,
+    /// 記号・演算子（`-` を除く）
+    Symbol,
+    Minus,
+    Quote,
+    Apostrophe,
+    Backslash,
+    Backtick,
+}
+
+/// バイトごとの [`ByteClass`]
+static BYTE_CLASS: [ByteClass; 256] = {
+    let mut table = [ByteClass::Other; 256];
+    let mut b = 0;
+    while b < 128 {
+        let byte = b as u8;
+        table[b] = match byte {
+            b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => ByteClass::Space,
+            b'b' | b'o' | b'x' | b'd' | b'u' | b's' | b'B' | b'O' | b'X' | b'D' | b'U' | b'S' => {
+                ByteClass::BaseLetter
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => ByteClass::Letter,
+            b'0'..=b'9' => ByteClass::Digit,
+            b':' | b'=' | b'<' | b'>' | b'+' | b'*' | b'/' | b'&' | b'|' | b'(' | b')' | b'['
+            | b']' | b';' | b',' | b'.' | b'?' => ByteClass::Symbol,
+            b'-' => ByteClass::Minus,
+            b'"' => ByteClass::Quote,
+            b'\'' => ByteClass::Apostrophe,
+            b'\\' => ByteClass::Backslash,
+            b'`' => ByteClass::Backtick,
+            _ => ByteClass::Other,
+        };
+        b += 1;
+    }
+    table
+};
+
+/// 識別子の2文字目以降になるASCII（英数字と `_`）か
+#[inline]
+fn is_word_byte(byte: u8) -> bool {
+    matches!(
+        BYTE_CLASS[usize::from(byte)],
+        ByteClass::Letter | ByteClass::BaseLetter | ByteClass::Digit
+    )
+}
+
+/// 保護（暗号化）されたソースの指示の始まり
+const PROTECT: &str = "`protect";
+
+/// 保護された領域の始まりと終わりの指示名
+const PROTECT_BEGIN: [&str; 2] = ["begin_protected", "begin"];
+const PROTECT_END: [&str; 2] = ["end_protected", "end"];
+
+/// 行頭（空白を除く）がツール指示 `` `protect <name>`` で、`<name>` が `names` のどれかか
+/// （大文字・小文字を区別しない）
+fn is_protect_directive(line: &str, names: [&str; 2]) -> bool {
+    let line = line.trim_start_matches([' ', '\t']);
+    let Some(rest) = line
+        .get(..PROTECT.len())
+        .filter(|head| head.eq_ignore_ascii_case(PROTECT))
+        .map(|_| &line[PROTECT.len()..])
+    else {
+        return false;
+    };
+    let name = rest.trim_start_matches([' ', '\t']);
+    if name.len() == rest.len() {
+        return false;
+    }
+    let len = name
+        .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+        .unwrap_or(name.len());
+    names.iter().any(|n| name[..len].eq_ignore_ascii_case(n))
+}
+
+/// `` `protect begin_protected`` で始まる保護された領域の、`` `protect end_protected`` の行末までの長さ
+///
+/// 始まりでないか、終わりの指示がなければ None です。
+fn protected_region_len(rest: &str) -> Option<usize> {
+    if !is_protect_directive(rest, PROTECT_BEGIN) {
+        return None;
+    }
+    let mut offset = rest.find('\n')? + 1;
+    loop {
+        let line = &rest[offset..];
+        let line_len = line.find('\n');
+        if is_protect_directive(line, PROTECT_END) {
+            let end = line_len.unwrap_or(line.len());
+            // CRLF の CR は領域に含めない
+            return Some(offset + line[..end].strip_suffix('\r').map_or(end, str::len));
+        }
+        offset += line_len? + 1;
+    }
+}
+
+/// 10進の数値リテラル（`1_000` `1.5` `2.0E-3`）の種類（書式が正しくなければ None）
+fn decimal_literal_kind(text: &str) -> Option<TokenKind> {
+    let integer = |s: &str| {
+        s.starts_with(|ch: char| ch.is_ascii_digit())
+            && s.ends_with(|ch: char| ch.is_ascii_digit())
+            && !s.contains("__")
+            && s.chars().all(|ch| ch.is_ascii_digit() || ch == '_')
+    };
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    let real = match mantissa.split_once('.') {
+        Some((whole, fraction)) if integer(whole) && integer(fraction) => true,
+        None if integer(mantissa) => false,
+        _ => return None,
+    };
+    if let Some(exponent) = exponent {
+        // 整数リテラルの指数は負にできない
+        let digits = match exponent.strip_prefix('-') {
+            Some(_) if !real => return None,
+            Some(digits) => digits,
+            None => exponent.strip_prefix('+').unwrap_or(exponent),
+        };
+        if !integer(digits) {
+            return None;
+        }
+    }
+    Some(if real {
+        TokenKind::RealLiteral
+    } else {
+        TokenKind::IntegerLiteral
+    })
+}
+
+/// トークン列の形（[`Lexer::with_options`]）
+///
+/// ```
+/// use vig::lexer::{Lexer, LexerOptions, TokenKind};
+///
+/// let mut options = LexerOptions::default();
+/// options.include_eof = true;
+/// options.skip_comments = true;
+/// let kinds: Vec<TokenKind> = Lexer::new("a; -- note")
+///     .with_options(options)
+///     .map(|t| t.unwrap().kind)
+///     .collect();
+/// assert_eq!(kinds, [TokenKind::Identifier, TokenKind::Semicolon, TokenKind::Eof]);
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// Iterator が最後に `Eof` を1つ返す
+    pub include_eof: bool,
+    /// コメントをトークンとして返さない
+    pub skip_comments: bool,
+    /// 空白とコメントをトークンの `leading` / `trailing` に付ける（[`Lexer::with_trivia`]）
+    pub trivia: bool,
+}
+
 /// Lexer本体
 ///
 /// VHDLソースコードをトークン列に分割します
 pub struct Lexer<'source> {
+    source: &'source str,
     position: usize,
-    chars: std::str::Chars<'source>,
-    current_char: Option<char>,
+    /// エラーの行・列を求める表（最初のエラーで作る）
+    lines: OnceCell<SourceMap>,
+    /// 直前のトークン（コメントを除く）の種類。`'` が属性の区切りかを決めるのに使う
+    previous: Option<TokenKind>,
+    options: LexerOptions,
+    /// Iterator が `Eof` まで読み終えたか
+    finished: bool,
+    /// まだどのトークンにも付けていないトリビアの先頭
+    trivia_start: usize,
+    /// 先読みして、まだ返していない結果
+    lookahead: VecDeque<Result<Token<'source>, LexError>>,
 }
 
+/// VHDLキーワード（小文字）と対応するTokenKind
+const KEYWORDS: &[(&str, TokenKind)] = &[
+    ("abs", TokenKind::Abs),
+    ("access", TokenKind::Access),
+    ("after", TokenKind::After),
+    ("alias", TokenKind::Alias),
+    ("all", TokenKind::All),
+    ("and", TokenKind::And),
+    ("architecture", TokenKind::Architecture),
+    ("array", TokenKind::Array),
+    ("assert", TokenKind::Assert),
+    ("assume", TokenKind::Assume),
+    ("assume_guarantee", TokenKind::AssumeGuarantee),
+    ("attribute", TokenKind::Attribute),
+    ("begin", TokenKind::Begin),
+    ("block", TokenKind::Block),
+    ("body", TokenKind::Body),
+    ("boolean", TokenKind::Boolean),
+    ("buffer", TokenKind::Buffer),
+    ("bus", TokenKind::Bus),
+    ("case", TokenKind::Case),
+    ("component", TokenKind::Component),
+    ("configuration", TokenKind::Configuration),
+    ("constant", TokenKind::Constant),
+    ("context", TokenKind::Context),
+    ("cover", TokenKind::Cover),
+    ("default", TokenKind::Default),
+    ("disconnect", TokenKind::Disconnect),
+    ("downto", TokenKind::Downto),
+    ("else", TokenKind::Else),
+    ("elsif", TokenKind::Elsif),
+    ("end", TokenKind::End),
+    ("entity", TokenKind::Entity),
+    ("exit", TokenKind::Exit),
+    ("fairness", TokenKind::Fairness),
+    ("file", TokenKind::File),
+    ("for", TokenKind::For),
+    ("force", TokenKind::Force),
+    ("function", TokenKind::Function),
+    ("generate", TokenKind::Generate),
+    ("generic", TokenKind::Generic),
+    ("group", TokenKind::Group),
+    ("guarded", TokenKind::Guarded),
+    ("if", TokenKind::If),
+    ("impure", TokenKind::Impure),
+    ("in", TokenKind::In),
+    ("inertial", TokenKind::Inertial),
+    ("inout", TokenKind::Inout),
+    ("integer", TokenKind::Integer),
+    ("is", TokenKind::Is),
+    ("label", TokenKind::Label),
+    ("library", TokenKind::Library),
+    ("linkage", TokenKind::Linkage),
+    ("literal", TokenKind::LiteralKeyword),
+    ("loop", TokenKind::Loop),
+    ("map", TokenKind::Map),
+    ("mod", TokenKind::Mod),
+    ("nand", TokenKind::Nand),
+    ("new", TokenKind::New),
+    ("next", TokenKind::Next),
+    ("nor", TokenKind::Nor),
+    ("not", TokenKind::Not),
+    ("null", TokenKind::Null),
+    ("of", TokenKind::Of),
+    ("on", TokenKind::On),
+    ("open", TokenKind::Open),
+    ("or", TokenKind::Or),
+    ("others", TokenKind::Others),
+    ("out", TokenKind::Out),
+    ("package", TokenKind::Package),
+    ("parameter", TokenKind::Parameter),
+    ("port", TokenKind::Port),
+    ("postponed", TokenKind::Postponed),
+    ("procedure", TokenKind::Procedure),
+    ("process", TokenKind::Process),
+    ("property", TokenKind::Property),
+    ("protected", TokenKind::Protected),
+    ("pure", TokenKind::Pure),
+    ("range", TokenKind::Range),
+    ("record", TokenKind::Record),
+    ("register", TokenKind::Register),
+    ("reject", TokenKind::Reject),
+    ("release", TokenKind::Release),
+    ("rem", TokenKind::Rem),
+    ("report", TokenKind::Report),
+    ("restrict", TokenKind::Restrict),
+    ("restrict_guarantee", TokenKind::RestrictGuarantee),
+    ("return", TokenKind::Return),
+    ("rol", TokenKind::Rol),
+    ("ror", TokenKind::Ror),
+    ("select", TokenKind::Select),
+    ("sequence", TokenKind::Sequence),
+    ("severity", TokenKind::Severity),
+    ("shared", TokenKind::Shared),
+    ("signal", TokenKind::Signal),
+    ("sla", TokenKind::Sla),
+    ("sll", TokenKind::Sll),
+    ("sra", TokenKind::Sra),
+    ("srl", TokenKind::Srl),
+    ("std_logic", TokenKind::StdLogic),
+    ("std_logic_vector", TokenKind::StdLogicVector),
+    ("strong", TokenKind::Strong),
+    ("subtype", TokenKind::Subtype),
+    ("then", TokenKind::Then),
+    ("to", TokenKind::To),
+    ("transport", TokenKind::Transport),
+    ("type", TokenKind::Type),
+    ("unaffected", TokenKind::Unaffected),
+    ("units", TokenKind::Units),
+    ("until", TokenKind::Until),
+    ("use", TokenKind::Use),
+    ("variable", TokenKind::Variable),
+    ("vmode", TokenKind::Vmode),
+    ("vprop", TokenKind::Vprop),
+    ("vunit", TokenKind::Vunit),
+    ("wait", TokenKind::Wait),
+    ("when", TokenKind::When),
+    ("while", TokenKind::While),
+    ("with", TokenKind::With),
+    ("xnor", TokenKind::Xnor),
+    ("xor", TokenKind::Xor),
+];
+
+/// [`keyword_hash`] の値のビット数
+const KEYWORD_HASH_BITS: u32 = 10;
+
+/// [`KEYWORD_INDEX`] の空き
+const EMPTY_SLOT: u8 = u8::MAX;
+
+/// 語（2文字以上）の大文字・小文字を区別しないハッシュ
+///
+/// 先頭2文字・末尾2文字・長さを掛け算で混ぜたもので、[`KEYWORDS`] の中では衝突しません
+/// （衝突すると [`KEYWORD_INDEX`] の構築がコンパイル時に失敗します）。
+#[inline]
+const fn keyword_hash(word: &[u8]) -> usize {
+    let n = word.len();
+    let key = word[0].to_ascii_lowercase() as u64
+        | (word[1].to_ascii_lowercase() as u64) << 8
+        | (word[n - 2].to_ascii_lowercase() as u64) << 16
+        | (word[n - 1].to_ascii_lowercase() as u64) << 24
+        | (n as u64) << 32;
+    (key.wrapping_mul(0x3955_7226_e216_6949) >> (64 - KEYWORD_HASH_BITS)) as usize
+}
+
+/// ハッシュ値から [`KEYWORDS`] の番号を引く表
+static KEYWORD_INDEX: [u8; 1 << KEYWORD_HASH_BITS] = {
+    let mut index = [EMPTY_SLOT; 1 << KEYWORD_HASH_BITS];
+    let mut i = 0;
+    while i < KEYWORDS.len() {
+        let slot = keyword_hash(KEYWORDS[i].0.as_bytes());
+        assert!(index[slot] == EMPTY_SLOT, "keyword hash collision");
+        index[slot] = i as u8;
+        i += 1;
+    }
+    index
+};
+
 impl<'source> Lexer<'source> {
     /// 新しいLexerを作成
+    ///
+    /// 先頭の BOM は読み飛ばします（位置は BOM を含む元のソースでのもの）。
     pub fn new(source: &'source str) -> Self {
-        let mut chars = source.chars();
-        let current_char = chars.next();
-
+        let position = skip_bom(source);
         Self {
-            position: 0,
-            chars,
-            current_char,
+            source,
+            position,
+            lines: OnceCell::new(),
+            previous: None,
+            options: LexerOptions::default(),
+            finished: false,
+            trivia_start: 0,
+            lookahead: VecDeque::new(),
         }
     }
 
+    /// トークン列の形を指定する
+    pub fn with_options(mut self, options: LexerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 空白とコメントを捨てず、トークンの `leading` / `trailing` に付ける
+    ///
+    /// コメントはトークンとしては返さなくなります。`Eof` まで `next_token` で読み、
+    /// 各トークンの `leading`・`text`・`trailing` をつなぐと元のソース（BOM を含む）に戻ります。
+    /// 行末までの空白とコメントと改行は直前のトークンの `trailing`、それ以外は次のトークンの
+    /// `leading` です。字句解析エラーになった部分は戻りません。
+    ///
+    /// ```
+    /// use vig::lexer::{Lexer, TokenKind};
+    ///
+    /// let source = "a <= b; -- copy\n  c <= d;\n";
+    /// let mut lexer = Lexer::new(source).with_trivia();
+    /// let mut rebuilt = String::new();
+    /// loop {
+    ///     let token = lexer.next_token().unwrap();
+    ///     rebuilt.push_str(token.leading);
+    ///     rebuilt.push_str(token.text);
+    ///     rebuilt.push_str(token.trailing);
+    ///     if token.kind == TokenKind::Eof {
+    ///         break;
+    ///     }
+    /// }
+    /// assert_eq!(rebuilt, source);
+    /// ```
+    pub fn with_trivia(mut self) -> Self {
+        self.options.trivia = true;
+        self
+    }
+
     /// 記号表による記号のトークン化
-    /// 戻り値: (TokenKind, 消費する文字数)
-    fn try_symbol(&self, ch: char) -> Option<(TokenKind, usize)> {
-        let next_ch = self.peek();
-
-        // 2文字記号を優先的にチェック
-        if let Some(next) = next_ch {
-            match (ch, next) {
-                (':', '=') => return Some((TokenKind::Assignment, 2)),
-                ('=', '>') => return Some((TokenKind::Association, 2)),
-                ('/', '=') => return Some((TokenKind::Neq, 2)),
-                ('<', '=') => return Some((TokenKind::Lte, 2)),
-                ('>', '=') => return Some((TokenKind::Gte, 2)),
-                ('*', '*') => return Some((TokenKind::Power, 2)),
-                _ => {}
-            }
+    /// 戻り値: (TokenKind, 消費するバイト数)
+    #[inline]
+    fn try_symbol(&self) -> Option<(TokenKind, usize)> {
+        // 記号はすべてASCIIなので、バイトで見れば足りる
+        let bytes = &self.source.as_bytes()[self.position..];
+        let first = bytes[0];
+        let next = bytes.get(1).copied().unwrap_or(0);
+
+        // 2文字・3文字の記号を優先的にチェック
+        let long = match (first, next) {
+            (b':', b'=') => Some(TokenKind::Assignment),
+            (b'=', b'>') => Some(TokenKind::Association),
+            (b'/', b'=') => Some(TokenKind::Neq),
+            (b'<', b'=') => Some(TokenKind::Lte),
+            (b'>', b'=') => Some(TokenKind::Gte),
+            (b'<', b'>') => Some(TokenKind::Box),
+            (b'*', b'*') => Some(TokenKind::Power),
+            (b'?', _) => return Self::match_symbol(bytes),
+            _ => None,
+        };
+        if let Some(kind) = long {
+            return Some((kind, 2));
         }
 
         // 1文字記号
-        match ch {
-            ':' => Some((TokenKind::Colon, 1)),
-            '=' => Some((TokenKind::Eq, 1)),
-            '<' => Some((TokenKind::Lt, 1)),
-            '>' => Some((TokenKind::Gt, 1)),
-            '+' => Some((TokenKind::Plus, 1)),
-            '-' => Some((TokenKind::Minus, 1)),
-            '*' => Some((TokenKind::Star, 1)),
-            '/' => Some((TokenKind::Slash, 1)),
-            '&' => Some((TokenKind::Ampersand, 1)),
-            '(' => Some((TokenKind::LeftParen, 1)),
-            ')' => Some((TokenKind::RightParen, 1)),
-            ';' => Some((TokenKind::Semicolon, 1)),
-            ',' => Some((TokenKind::Comma, 1)),
-            '.' => Some((TokenKind::Dot, 1)),
-            _ => None,
+        let kind = match first {
+            b':' => TokenKind::Colon,
+            b'=' => TokenKind::Eq,
+            b'<' => TokenKind::Lt,
+            b'>' => TokenKind::Gt,
+            b'+' => TokenKind::Plus,
+            b'-' => TokenKind::Minus,
+            b'*' => TokenKind::Star,
+            b'/' => TokenKind::Slash,
+            b'&' => TokenKind::Ampersand,
+            b'|' => TokenKind::Bar,
+            b'(' => TokenKind::LeftParen,
+            b')' => TokenKind::RightParen,
+            b'[' => TokenKind::LeftBracket,
+            b']' => TokenKind::RightBracket,
+            b';' => TokenKind::Semicolon,
+            b',' => TokenKind::Comma,
+            b'.' => TokenKind::Dot,
+            _ => return None,
+        };
+        Some((kind, 1))
+    }
+
+    /// `?` で始まる記号（VHDL-2008 の条件演算子と一致比較）
+    fn match_symbol(bytes: &[u8]) -> Option<(TokenKind, usize)> {
+        for (symbol, kind) in [
+            ("?/=", TokenKind::MatchNeq),
+            ("?<=", TokenKind::MatchLte),
+            ("?>=", TokenKind::MatchGte),
+            ("??", TokenKind::Condition),
+            ("?=", TokenKind::MatchEq),
+            ("?<", TokenKind::MatchLt),
+            ("?>", TokenKind::MatchGt),
+        ] {
+            if bytes.starts_with(symbol.as_bytes()) {
+                return Some((kind, symbol.len()));
+            }
         }
+        None
     }
 
     /// 文字列がVHDLキーワードかチェックして対応するTokenKindを返す
+    #[inline]
     fn keyword_or_identifier(text: &str) -> TokenKind {
-        // VHDLは大文字小文字を区別しないため、小文字に統一して比較
-        match text.to_lowercase().as_str() {
-            "entity" => TokenKind::Entity,
-            "architecture" => TokenKind::Architecture,
-            "port" => TokenKind::Port,
-            "signal" => TokenKind::Signal,
-            "process" => TokenKind::Process,
-            "begin" => TokenKind::Begin,
-            "end" => TokenKind::End,
-            "if" => TokenKind::If,
-            "then" => TokenKind::Then,
-            "else" => TokenKind::Else,
-            "elsif" => TokenKind::Elsif,
-            "case" => TokenKind::Case,
-            "when" => TokenKind::When,
-            "is" => TokenKind::Is,
-            "of" => TokenKind::Of,
-            "others" => TokenKind::Others,
-            "library" => TokenKind::Library,
-            "use" => TokenKind::Use,
-            "in" => TokenKind::In,
-            "out" => TokenKind::Out,
-            "inout" => TokenKind::Inout,
-            "buffer" => TokenKind::Buffer,
-            "generic" => TokenKind::Generic,
-            "map" => TokenKind::Map,
-            "component" => TokenKind::Component,
-            "to" => TokenKind::To,
-            "downto" => TokenKind::Downto,
-            "std_logic" => TokenKind::StdLogic,
-            "std_logic_vector" => TokenKind::StdLogicVector,
-            "integer" => TokenKind::Integer,
-            "boolean" => TokenKind::Boolean,
-            "and" => TokenKind::And,
-            "or" => TokenKind::Or,
-            "not" => TokenKind::Not,
-            "xor" => TokenKind::Xor,
-            "nand" => TokenKind::Nand,
-            "nor" => TokenKind::Nor,
-            _ => TokenKind::Identifier,
-        }
-    }
-
-    /// 現在の文字を取得
+        // VHDLは大文字小文字を区別しないため、コピーせずにASCIIの大文字・小文字を無視して比較
+        // （キーワードはASCIIのみなので、ASCII以外を含む語は一致しない）
+        let word = text.as_bytes();
+        if word.len() < 2 {
+            return TokenKind::Identifier;
+        }
+        match KEYWORD_INDEX[keyword_hash(word)] {
+            EMPTY_SLOT => TokenKind::Identifier,
+            i => match &KEYWORDS[usize::from(i)] {
+                (keyword, kind) if keyword.as_bytes().eq_ignore_ascii_case(word) => kind.clone(),
+                _ => TokenKind::Identifier,
+            },
+        }
+    }
+
+    /// 現在の文字を取得（ASCIIはバイトから直接作る）
+    #[inline]
     fn current(&self) -> Option<char> {
-        self.current_char
+        self.char_at(self.position)
+    }
+
+    #[inline]
+    fn char_at(&self, position: usize) -> Option<char> {
+        match self.source.as_bytes().get(position) {
+            Some(&b) if b.is_ascii() => Some(char::from(b)),
+            Some(_) => self.source[position..].chars().next(),
+            None => None,
+        }
     }
 
-    /// 次の文字を先読み
-    fn peek(&self) -> Option<char> {
-        self.chars.clone().next()
+    /// 指定した位置まで進む
+    #[inline]
+    fn seek(&mut self, position: usize) {
+        self.position = position;
     }
 
     /// 次の文字に進む
+    #[inline]
     fn advance(&mut self) {
-        if let Some(ch) = self.current_char {
+        if let Some(ch) = self.current() {
             self.position += ch.len_utf8();
-            self.current_char = self.chars.next();
         }
     }
 
-    /// 条件を満たす間、文字を消費し続ける
-    fn consume_while<F>(&mut self, start: usize, predicate: F) -> (String, Span)
+    /// 条件を満たす間、文字を消費し続ける（文字列はソースから1回で切り出す）
+    fn consume_while<F>(&mut self, start: usize, predicate: F) -> (&'source str, Span)
     where
         F: Fn(char) -> bool,
     {
-        let mut result = String::new();
+        let rest = &self.source[self.position..];
+        let len = rest.find(|ch| !predicate(ch)).unwrap_or(rest.len());
+        self.position += len;
 
-        while let Some(ch) = self.current() {
-            if predicate(ch) {
-                result.push(ch);
-                self.advance();
-            } else {
-                break;
+        let span = Span::new(start, self.position);
+        (&self.source[start..self.position], span)
+    }
+
+    /// 空白文字をスキップ
+    ///
+    /// 保護された領域（`` `protect begin_protected`` 〜 `` `protect end_protected``）も
+    /// 中身を読まずに空白と同じく読み飛ばします（トリビアに含まれます）。
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        // ASCIIの空白はバイト単位で読み、全角空白などが現れたら文字単位に切り替える
+        let bytes = self.source.as_bytes();
+        let mut position = self.position;
+        loop {
+            while bytes
+                .get(position)
+                .is_some_and(|&b| BYTE_CLASS[usize::from(b)] == ByteClass::Space)
+            {
+                position += 1;
+            }
+            if bytes.get(position).is_some_and(|&b| !b.is_ascii()) {
+                let rest = &self.source[position..];
+                position += rest.len() - rest.trim_start().len();
+            }
+            match bytes.get(position) {
+                Some(b'`') => match protected_region_len(&self.source[position..]) {
+                    Some(len) => position += len,
+                    None => break,
+                },
+                _ => break,
             }
         }
+        if position != self.position {
+            self.seek(position);
+        }
+    }
+
+    /// ASCIIの条件を満たす間、バイト単位で消費する
+    fn consume_ascii_while<F>(&mut self, start: usize, predicate: F) -> (&'source str, Span)
+    where
+        F: Fn(u8) -> bool,
+    {
+        let bytes = self.source.as_bytes();
+        let mut position = self.position;
+        while bytes
+            .get(position)
+            .is_some_and(|&b| b.is_ascii() && predicate(b))
+        {
+            position += 1;
+        }
+        self.seek(position);
 
         let span = Span::new(start, self.position);
-        (result, span)
+        (&self.source[start..self.position], span)
     }
 
-    /// 空白文字をスキップ
-    fn skip_whitespace(&mut self) {
-        while let Some(ch) = self.current() {
-            if ch.is_whitespace() {
+    /// 識別子またはキーワードをトークン化（`X"FF"` のように基数指定に続く文字列はビット列リテラル）
+    fn lex_identifier(&mut self, start: usize) -> Result<TokenKind, LexError> {
+        if let Some(len) = base_specifier_len(&self.source[start..]) {
+            for _ in 0..len {
                 self.advance();
-            } else {
-                break;
             }
+            return self.lex_bit_string(start);
         }
+        Ok(self.lex_word(start))
     }
 
-    /// 識別子またはキーワードをトークン化
-    fn lex_identifier(&mut self, start: usize) -> Token {
-        let (text, span) = self.consume_while(start, |ch| ch.is_alphanumeric() || ch == '_');
+    /// 識別子またはキーワードをトークン化（基数指定かどうかは見ない）
+    #[inline]
+    fn lex_word(&mut self, start: usize) -> TokenKind {
+        let (mut text, _) = self.consume_ascii_while(start, is_word_byte);
+        if self.current().is_some_and(|ch| ch.is_alphanumeric()) {
+            // ASCII以外の文字を含む識別子は文字単位で読む
+            (text, _) = self.consume_while(start, |ch| ch.is_alphanumeric() || ch == '_');
+        }
+        Self::keyword_or_identifier(text)
+    }
 
-        let kind = Self::keyword_or_identifier(&text);
-        Token::new(kind, span, text)
+    /// ビット列リテラルの `"` 以降をトークン化
+    fn lex_bit_string(&mut self, start: usize) -> Result<TokenKind, LexError> {
+        self.advance(); // 開始の '"' をスキップ
+        while let Some(ch) = self.current() {
+            if ch == '\n' {
+                break;
+            }
+            self.advance();
+            if ch == '"' {
+                return Ok(TokenKind::BitStringLiteral);
+            }
+        }
+
+        let span = Span::new(start, self.position);
+        Err(LexError::new("unclosed bit string literal", span))
     }
 
     /// 数値をトークン化
-    fn lex_number(&mut self, start: usize) -> Token {
-        let (text, span) = self.consume_while(start, |ch| {
-            ch.is_ascii_digit()
-                || ch == '.'
-                || ch == '_'
-                || ch.is_ascii_lowercase() && "eE".contains(ch)
+    ///
+    /// 小数点のないものは `IntegerLiteral`、あるものは `RealLiteral` として、
+    /// 基数付きリテラル（`16#FF#` `2#1010_1100#` `16#F.8#E1`）も含めてそのままの表記で返します
+    /// （値は [`Token::numeric_value`] で求められます）。
+    fn lex_number(&mut self, start: usize) -> Result<TokenKind, LexError> {
+        let (text, _) = self.consume_ascii_while(start, |b| b.is_ascii_digit() || b == b'_');
+        if self.current() == Some('#') {
+            return self.lex_based_literal(start, text);
+        }
+        // 幅指定付きのビット列リテラル（8X"F"）
+        if let Some(len) = base_specifier_len(&self.source[self.position..]) {
+            for _ in 0..len {
+                self.advance();
+            }
+            return self.lex_bit_string(start);
+        }
+        // 小数部（`1.2.3` のような誤りもまとめて読んでから検査する）
+        if self.current() == Some('.') {
+            self.consume_ascii_while(start, |b| b.is_ascii_digit() || b == b'.' || b == b'_');
+        }
+        // 指数（e3, E+3, e-2 など）
+        if self.current().is_some_and(|ch| ch == 'e' || ch == 'E') {
+            self.advance();
+            if self.current().is_some_and(|ch| ch == '+' || ch == '-') {
+                self.advance();
+            }
+            self.consume_ascii_while(start, |b| b.is_ascii_digit() || b == b'_');
+        }
+
+        let text = &self.source[start..self.position];
+        decimal_literal_kind(text).ok_or_else(|| {
+            let span = Span::new(start, self.position);
+            LexError::new(format!("invalid numeric literal: {}", text), span)
+        })
+    }
+
+    /// 基数付きリテラル（`基数#数字#指数`）の `#` 以降をトークン化
+    fn lex_based_literal(&mut self, start: usize, base: &str) -> Result<TokenKind, LexError> {
+        self.advance(); // 開始の '#' をスキップ
+        let digits_start = self.position;
+        self.consume_while(digits_start, |ch| {
+            ch.is_ascii_hexdigit() || ch == '_' || ch == '.'
         });
+        let digits = &self.source[digits_start..self.position];
+        if self.current() != Some('#') {
+            let span = Span::new(start, self.position);
+            return Err(LexError::new("unterminated based literal", span));
+        }
+        self.advance(); // 終わりの '#' をスキップ
+
+        // 指数（E+3, e-2 など）
+        let mut negative = false;
+        if self.current().is_some_and(|ch| ch == 'e' || ch == 'E') {
+            let rest = &self.source[self.position + 1..];
+            let sign = usize::from(rest.starts_with(['+', '-']));
+            if rest[sign..].starts_with(|ch: char| ch.is_ascii_digit()) {
+                negative = rest.starts_with('-');
+                for _ in 0..=sign {
+                    self.advance();
+                }
+                self.consume_ascii_while(start, |b| b.is_ascii_digit() || b == b'_');
+            }
+        }
 
-        Token::new(TokenKind::Number, span, text)
+        let span = Span::new(start, self.position);
+        let radix: u32 = base.replace('_', "").parse().unwrap_or(0);
+        let real = digits.contains('.');
+        let valid = (2..=16).contains(&radix)
+            && !digits.is_empty()
+            && digits.matches('.').count() <= 1
+            && digits
+                .chars()
+                .all(|ch| ch == '_' || ch == '.' || ch.is_digit(radix))
+            // 整数リテラルの指数は負にできない
+            && (real || !negative);
+        if !valid {
+            return Err(LexError::new(
+                format!(
+                    "invalid based literal: {}",
+                    &self.source[start..self.position]
+                ),
+                span,
+            ));
+        }
+        Ok(if real {
+            TokenKind::RealLiteral
+        } else {
+            TokenKind::IntegerLiteral
+        })
     }
 
     /// VHDLコメント（-- から行末まで）をトークン化
-    fn lex_comment(&mut self, start: usize) -> Token {
+    fn lex_comment(&mut self) -> TokenKind {
         self.advance(); // 2つ目の '-' をスキップ
 
-        let (text, span) = self.consume_while(start, |ch| ch != '\n');
-
-        Token::new(TokenKind::Comment, span, text)
+        let rest = &self.source[self.position..];
+        let mut len = rest.find('\n').unwrap_or(rest.len());
+        // CRLF の CR はコメントに含めない（続く空白として読み飛ばす）
+        if rest[..len].ends_with('\r') {
+            len -= 1;
+        }
+        self.seek(self.position + len);
+        TokenKind::Comment
     }
 
     /// 文字リテラルをトークン化 ('0', '1', 'X'など)
-    fn lex_character(&mut self, start: usize) -> Result<Token, LexError> {
+    fn lex_character(&mut self, start: usize) -> Result<TokenKind, LexError> {
         self.advance(); // 開始の '\'' をスキップ
-        let mut text = String::from("'");
 
-        if let Some(ch) = self.current() {
-            text.push(ch);
+        if self.current().is_some() {
             self.advance();
 
             if let Some('\'') = self.current() {
-                text.push('\'');
                 self.advance();
-                let span = Span::new(start, self.position);
-                return Ok(Token::new(TokenKind::CharacterLiteral, span, text));
+                return Ok(TokenKind::CharacterLiteral);
             }
         }
 
@@ -349,18 +1403,47 @@ impl<'source> Lexer<'source> {
         Err(LexError::new("invalid character literal", span))
     }
 
+    /// 拡張識別子（`\bus-signal\`）をトークン化
+    ///
+    /// 大文字・小文字を含めて書いたままの表記の `Identifier` にします。中の `\\` は `\` 1文字です。
+    fn lex_extended_identifier(&mut self, start: usize) -> Result<TokenKind, LexError> {
+        self.advance(); // 開始の '\' をスキップ
+        while let Some(ch) = self.current() {
+            if ch == '\n' {
+                break;
+            }
+            self.advance();
+            if ch == '\\' {
+                if self.current() == Some('\\') {
+                    self.advance();
+                    continue;
+                }
+                let span = Span::new(start, self.position);
+                if span.len() == 2 {
+                    return Err(LexError::new("empty extended identifier", span));
+                }
+                return Ok(TokenKind::Identifier);
+            }
+        }
+
+        let span = Span::new(start, self.position);
+        Err(LexError::new("unclosed extended identifier", span))
+    }
+
     /// 文字列リテラルをトークン化
-    fn lex_string_literal(&mut self, start: usize) -> Result<Token, LexError> {
+    fn lex_string_literal(&mut self, start: usize) -> Result<TokenKind, LexError> {
         self.advance(); // 開始の '"' をスキップ
-        let mut text = String::from("\"");
 
         while let Some(ch) = self.current() {
-            text.push(ch);
             self.advance();
 
             if ch == '"' {
-                let span = Span::new(start, self.position);
-                return Ok(Token::new(TokenKind::StringLiteral, span, text));
+                // `""` は文字列中の `"` 1文字
+                if self.current() == Some('"') {
+                    self.advance();
+                    continue;
+                }
+                return Ok(TokenKind::StringLiteral);
             }
         }
 
@@ -370,60 +1453,362 @@ impl<'source> Lexer<'source> {
     }
 
     /// 次のトークンを取得
-    pub fn next_token(&mut self) -> Result<Token, LexError> {
-        self.skip_whitespace();
+    #[inline]
+    pub fn next_token(&mut self) -> Result<Token<'source>, LexError> {
+        match self.lookahead.pop_front() {
+            Some(result) => result,
+            None => self.lex(),
+        }
+    }
+
+    /// 次のトークンを消費せずに見る（[`Lexer::peek_nth`] の 0 番目）
+    pub fn peek_token(&mut self) -> Result<&Token<'source>, &LexError> {
+        self.peek_nth(0)
+    }
 
+    /// `n` 個先（0 が次）のトークンを消費せずに見る
+    ///
+    /// 先読みした分は `next_token` がそのまま順に返します。`Eof` の先も `Eof` です。
+    ///
+    /// ```
+    /// use vig::lexer::{Lexer, TokenKind};
+    ///
+    /// let mut lexer = Lexer::new("clk'event");
+    /// assert_eq!(lexer.peek_nth(1).unwrap().kind, TokenKind::Apostrophe);
+    /// assert_eq!(lexer.peek_nth(3).unwrap().kind, TokenKind::Eof);
+    /// assert_eq!(lexer.next_token().unwrap().text, "clk");
+    /// assert_eq!(lexer.peek_token().unwrap().text, "'");
+    /// ```
+    pub fn peek_nth(&mut self, n: usize) -> Result<&Token<'source>, &LexError> {
+        while self.lookahead.len() <= n {
+            let result = self.lex();
+            self.lookahead.push_back(result);
+        }
+        self.lookahead[n].as_ref()
+    }
+
+    /// 先読みを通さずにソースから次のトークンを読む
+    #[inline]
+    fn lex(&mut self) -> Result<Token<'source>, LexError> {
+        // 既定の設定では1回読むだけ（ループを通さないほうが速い）
+        if !self.options.trivia && !self.options.skip_comments {
+            return match self.scan_token() {
+                Ok(token) => {
+                    if token.kind != TokenKind::Comment {
+                        self.previous = Some(token.kind.clone());
+                    }
+                    Ok(token)
+                }
+                Err(err) => Err(self.locate(err)),
+            };
+        }
+        loop {
+            let result = if self.options.trivia {
+                self.scan_with_trivia()
+            } else {
+                self.scan_token()
+            };
+            match result {
+                Ok(token) if token.kind == TokenKind::Comment => {
+                    if !self.options.skip_comments {
+                        return Ok(token);
+                    }
+                }
+                Ok(token) => {
+                    self.previous = Some(token.kind.clone());
+                    return Ok(token);
+                }
+                Err(err) => return Err(self.locate(err)),
+            }
+        }
+    }
+
+    /// エラーに行・列を付ける
+    #[cold]
+    fn locate(&mut self, mut err: LexError) -> LexError {
+        self.previous = None;
+        let lines = self.lines.get_or_init(|| SourceMap::new("", self.source));
+        err.location = Some(lines.location(err.span.start));
+        err
+    }
+
+    /// 前後のトリビアを付けて次のトークンを読む（コメントはトリビアに含める）
+    fn scan_with_trivia(&mut self) -> Result<Token<'source>, LexError> {
+        let mut token = loop {
+            match self.scan_token() {
+                Ok(token) if token.kind == TokenKind::Comment => continue,
+                Ok(token) => break token,
+                Err(err) => {
+                    self.trivia_start = self.position;
+                    return Err(err);
+                }
+            }
+        };
+        token.leading = &self.source[self.trivia_start..token.span.start];
+        if token.kind != TokenKind::Eof {
+            let end = self.trailing_trivia_end();
+            token.trailing = &self.source[self.position..end];
+            self.seek(end);
+        }
+        self.trivia_start = self.position;
+        Ok(token)
+    }
+
+    /// 現在位置から、同じ行の空白・コメントと改行1つまでの終わり
+    fn trailing_trivia_end(&self) -> usize {
+        let rest = &self.source[self.position..];
+        let mut len = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        if rest[len..].starts_with("--") {
+            len += rest[len..].find('\n').unwrap_or(rest.len() - len);
+        }
+        if rest[len..].starts_with("\r\n") {
+            len += 2;
+        } else if rest[len..].starts_with('\n') {
+            len += 1;
+        }
+        self.position + len
+    }
+
+    /// ソース全体をトークン化し、エラーで止まらずに最後まで読む
+    ///
+    /// 読めなかった部分は `Unknown` トークンとしてトークン列に残し、エラーは別に集めます。
+    /// エディタや診断で、すべての問題を一度に示すためのものです。`Eof` は含みません。
+    ///
+    /// ```
+    /// use vig::lexer::{Lexer, TokenKind};
+    ///
+    /// let (tokens, errors) = Lexer::tokenize_with_errors("a $ b ? c");
+    /// assert_eq!(tokens.len(), 5);
+    /// assert_eq!(tokens[1].kind, TokenKind::Unknown);
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn tokenize_with_errors(source: &'source str) -> (Vec<Token<'source>>, Vec<LexError>) {
+        let mut lexer = Lexer::new(source);
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        loop {
+            let (token, error) = lexer.next_recovering();
+            if token.kind == TokenKind::Eof {
+                break;
+            }
+            tokens.push(token);
+            errors.extend(error);
+        }
+        (tokens, errors)
+    }
+
+    /// 次のトークンを読む（読めなかった範囲は `Unknown` トークンにしてエラーと一緒に返す）
+    fn next_recovering(&mut self) -> (Token<'source>, Option<LexError>) {
         let start = self.position;
+        match self.lex() {
+            Ok(token) => (token, None),
+            Err(err) => {
+                // 必ず1文字は進めて、読めなかった範囲を Unknown にする
+                if self.position == start {
+                    self.advance();
+                }
+                let span = Span::new(err.span.start, self.position.max(err.span.end));
+                let text = &self.source[span.start..span.end];
+                (Token::new(TokenKind::Unknown, span, text), Some(err))
+            }
+        }
+    }
 
-        match self.current() {
-            None => {
-                let span = Span::new(start, start);
-                Ok(Token::new(TokenKind::Eof, span, String::new()))
+    /// 編集の前後で変わる部分だけを読み直し、トークン列を更新する
+    ///
+    /// `old_tokens` は編集前のソースを [`Lexer::tokenize_with_errors`] で読んだトークン列、
+    /// `edit` はその編集前のソースへの置き換え、`source` は編集後のソースです。
+    /// 編集位置の少し前から読み直し、編集より後ろで元のトークンと同じ位置・種類のトークンに
+    /// 戻ったところで残りを元の列から位置をずらして使います。結果は編集後のソースを
+    /// `tokenize_with_errors` で読んだものと同じです（トリビアは付けません）。
+    ///
+    /// ```
+    /// use vig::lexer::Lexer;
+    /// use vig::rewrite::Edit;
+    ///
+    /// let old = "a <= b;\nc <= d;";
+    /// let (tokens, _) = Lexer::tokenize_with_errors(old);
+    /// let edit = Edit::replace(vig::lexer::Span::new(5, 6), "b_next");
+    /// let new = "a <= b_next;\nc <= d;";
+    /// let tokens = Lexer::relex(new, &tokens, &edit);
+    /// assert_eq!(tokens, Lexer::tokenize_with_errors(new).0);
+    /// ```
+    pub fn relex(
+        source: &'source str,
+        old_tokens: &[Token<'_>],
+        edit: &crate::rewrite::Edit,
+    ) -> Vec<Token<'source>> {
+        let delta = edit.text.len() as isize - edit.span.len() as isize;
+        let shifted = |span: Span| {
+            Span::new(
+                (span.start as isize + delta) as usize,
+                (span.end as isize + delta) as usize,
+            )
+        };
+        let copy = |kind: &TokenKind, span: Span| {
+            Token::new(kind.clone(), span, &source[span.start..span.end])
+        };
+
+        // 編集位置に接するトークンの1つ前から読み直す（`--` や `\r\n` の続きも拾う）
+        let first = old_tokens
+            .iter()
+            .position(|t| t.span.end >= edit.span.start)
+            .unwrap_or(old_tokens.len())
+            .saturating_sub(1);
+        let mut tokens: Vec<Token<'source>> = old_tokens[..first]
+            .iter()
+            .map(|t| copy(&t.kind, t.span))
+            .collect();
+        let resume = old_tokens
+            .get(first)
+            .map_or(edit.span.start, |t| t.span.start.min(edit.span.start));
+        let mut lexer = Lexer::new(source);
+        if resume > lexer.position {
+            lexer.seek(resume);
+        }
+        lexer.previous = old_tokens[..first]
+            .iter()
+            .rev()
+            .find(|t| t.kind != TokenKind::Comment)
+            .filter(|t| t.kind != TokenKind::Unknown)
+            .map(|t| t.kind.clone());
+
+        let mut old = first;
+        loop {
+            let (token, _) = lexer.next_recovering();
+            if token.kind == TokenKind::Eof {
+                return tokens;
+            }
+            // 編集より後ろの元のトークンと同じ位置・種類になれば、以降は変わらない
+            while old < old_tokens.len()
+                && (old_tokens[old].span.start < edit.span.end
+                    || shifted(old_tokens[old].span).start < token.span.start)
+            {
+                old += 1;
+            }
+            let synced = old_tokens.get(old).is_some_and(|t| {
+                shifted(t.span).start == token.span.start
+                    && t.kind == token.kind
+                    && t.kind != TokenKind::Comment
+            });
+            tokens.push(token);
+            if synced {
+                tokens.extend(
+                    old_tokens[old + 1..]
+                        .iter()
+                        .map(|t| copy(&t.kind, shifted(t.span))),
+                );
+                return tokens;
             }
+        }
+    }
 
-            Some(ch) if ch.is_alphabetic() || ch == '_' => Ok(self.lex_identifier(start)),
+    /// `'` が属性名や修飾式の区切り（`clk'event` `vec'range` `t'(...)`）になる位置か
+    ///
+    /// 名前・`)`・`all` と型名の直後の `'` は文字リテラルの始まりではありません。
+    fn after_name(&self) -> bool {
+        matches!(
+            self.previous,
+            Some(
+                TokenKind::Identifier
+                    | TokenKind::RightParen
+                    | TokenKind::All
+                    | TokenKind::StdLogic
+                    | TokenKind::StdLogicVector
+                    | TokenKind::Integer
+                    | TokenKind::Boolean
+            )
+        )
+    }
 
-            Some(ch) if ch.is_ascii_digit() => Ok(self.lex_number(start)),
+    #[inline]
+    fn scan_token(&mut self) -> Result<Token<'source>, LexError> {
+        self.skip_whitespace();
 
-            Some('"') => self.lex_string_literal(start),
+        let start = self.position;
+        let kind = self.scan_kind(start)?;
+        // 字句は各読み取りが進めた位置までをソースから1回だけ切り出す
+        let span = Span::new(start, self.position);
+        Ok(Token::new(kind, span, &self.source[start..self.position]))
+    }
+
+    /// `start` から始まるトークンを読み、その種類を返す（位置はトークンの終わりまで進む）
+    #[inline]
+    fn scan_kind(&mut self, start: usize) -> Result<TokenKind, LexError> {
+        let Some(&byte) = self.source.as_bytes().get(start) else {
+            return Ok(TokenKind::Eof);
+        };
+
+        // 先頭バイトの分類で振り分ける（ASCII以外は文字単位で読む）
+        match BYTE_CLASS[usize::from(byte)] {
+            ByteClass::Letter => Ok(self.lex_word(start)),
 
-            Some('\'') => self.lex_character(start),
+            ByteClass::BaseLetter => self.lex_identifier(start),
+
+            ByteClass::Digit => self.lex_number(start),
 
             // コメント --
-            Some('-') if self.peek() == Some('-') => Ok(self.lex_comment(start)),
-
-            // 記号・演算子の処理（記号表を使用）
-            Some(ch) => {
-                if let Some((kind, len)) = self.try_symbol(ch) {
-                    let mut text = String::new();
-                    for _ in 0..len {
-                        if let Some(c) = self.current() {
-                            text.push(c);
-                            self.advance();
-                        }
-                    }
-                    let span = Span::new(start, self.position);
-                    Ok(Token::new(kind, span, text))
-                } else {
-                    self.advance();
-                    let span = Span::new(start, self.position);
-                    Err(LexError::new(
-                        format!("unexpected character: '{}'", ch),
-                        span,
-                    ))
+            ByteClass::Minus if self.source.as_bytes().get(start + 1) == Some(&b'-') => {
+                Ok(self.lex_comment())
+            }
+
+            ByteClass::Symbol | ByteClass::Minus => match self.try_symbol() {
+                Some((kind, len)) => {
+                    self.seek(start + len);
+                    Ok(kind)
                 }
+                None => Err(self.unexpected_character(start)),
+            },
+
+            ByteClass::Quote => self.lex_string_literal(start),
+
+            ByteClass::Backslash => self.lex_extended_identifier(start),
+
+            ByteClass::Apostrophe if self.after_name() => {
+                self.advance();
+                Ok(TokenKind::Apostrophe)
             }
+
+            ByteClass::Apostrophe => self.lex_character(start),
+
+            // 終わりの指示のない保護された領域（閉じていれば空白として読み飛ばしている）
+            ByteClass::Backtick if is_protect_directive(&self.source[start..], PROTECT_BEGIN) => {
+                self.seek(self.source.len());
+                let span = Span::new(start, self.position);
+                Err(LexError::new("unterminated `protect envelope", span))
+            }
+
+            ByteClass::Backtick | ByteClass::Space | ByteClass::Other => match self.current() {
+                Some(ch) if ch.is_alphabetic() => self.lex_identifier(start),
+                _ => Err(self.unexpected_character(start)),
+            },
         }
     }
+
+    /// 読めない文字を1文字進めてエラーにする
+    #[cold]
+    fn unexpected_character(&mut self, start: usize) -> LexError {
+        let ch = self.current().unwrap_or_default();
+        self.advance();
+        let span = Span::new(start, self.position);
+        LexError::new(format!("unexpected character: '{}'", ch), span)
+    }
 }
 
 /// LexerをIteratorとして扱えるようにする
 impl<'source> Iterator for Lexer<'source> {
-    type Item = Result<Token, LexError>;
+    type Item = Result<Token<'source>, LexError>;
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
         match self.next_token() {
-            Ok(token) if token.kind == TokenKind::Eof => None,
+            Ok(token) if token.kind == TokenKind::Eof => {
+                self.finished = true;
+                self.options.include_eof.then_some(Ok(token))
+            }
             result => Some(result),
         }
     }
@@ -445,6 +1830,220 @@ mod tests {
         assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Signal);
     }
 
+    #[test]
+    fn test_reserved_words_are_keywords() {
+        // 予約語はすべて識別子以外のトークンになる
+        for word in crate::ident::RESERVED {
+            let kind = Lexer::keyword_or_identifier(word);
+            assert_ne!(kind, TokenKind::Identifier, "{}", word);
+            assert_eq!(Lexer::keyword_or_identifier(&word.to_uppercase()), kind);
+        }
+        let source = "wait for 10 ns; Restrict_Guarantee xnor waiting";
+        let kinds: Vec<TokenKind> = Lexer::new(source).map(|t| t.unwrap().kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Wait,
+                TokenKind::For,
+                TokenKind::IntegerLiteral,
+                TokenKind::Identifier,
+                TokenKind::Semicolon,
+                TokenKind::RestrictGuarantee,
+                TokenKind::Xnor,
+                TokenKind::Identifier
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matching_operators() {
+        let source = "?? ?= ?/= ?< ?<= ?> ?>= if ?? enable then";
+        let kinds: Vec<TokenKind> = Lexer::new(source).map(|t| t.unwrap().kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Condition,
+                TokenKind::MatchEq,
+                TokenKind::MatchNeq,
+                TokenKind::MatchLt,
+                TokenKind::MatchLte,
+                TokenKind::MatchGt,
+                TokenKind::MatchGte,
+                TokenKind::If,
+                TokenKind::Condition,
+                TokenKind::Identifier,
+                TokenKind::Then,
+            ]
+        );
+        // `?` 単独は記号ではない
+        let err = Lexer::new("a ? b").nth(1).unwrap().unwrap_err();
+        assert_eq!(err.span, Span::new(2, 3));
+    }
+
+    #[test]
+    fn test_choice_bar_box_and_brackets() {
+        let kinds = |source: &str| -> Vec<TokenKind> {
+            Lexer::new(source).map(|t| t.unwrap().kind).collect()
+        };
+        assert_eq!(
+            kinds("when \"00\" | \"01\" =>"),
+            [
+                TokenKind::When,
+                TokenKind::StringLiteral,
+                TokenKind::Bar,
+                TokenKind::StringLiteral,
+                TokenKind::Association,
+            ]
+        );
+        assert_eq!(
+            kinds("array (natural range <>) of bit"),
+            [
+                TokenKind::Array,
+                TokenKind::LeftParen,
+                TokenKind::Identifier,
+                TokenKind::Range,
+                TokenKind::Box,
+                TokenKind::RightParen,
+                TokenKind::Of,
+                TokenKind::Identifier,
+            ]
+        );
+        assert_eq!(
+            kinds("f[natural return bit]"),
+            [
+                TokenKind::Identifier,
+                TokenKind::LeftBracket,
+                TokenKind::Identifier,
+                TokenKind::Return,
+                TokenKind::Identifier,
+                TokenKind::RightBracket,
+            ]
+        );
+        // `<=` と `< >` は箱ではない
+        assert_eq!(
+            kinds("a<=b"),
+            [TokenKind::Identifier, TokenKind::Lte, TokenKind::Identifier]
+        );
+        assert_eq!(kinds("< >"), [TokenKind::Lt, TokenKind::Gt]);
+    }
+
+    #[test]
+    fn test_operator_keywords() {
+        let source = "a mod 4 REM b xnor c sll 1 srl 2 sla 3 sra 4 rol 5 ror 6 abs d";
+        let operators: Vec<TokenKind> = Lexer::new(source)
+            .map(|t| t.unwrap().kind)
+            .filter(|kind| !matches!(*kind, TokenKind::Identifier | TokenKind::IntegerLiteral))
+            .collect();
+        assert_eq!(
+            operators,
+            [
+                TokenKind::Mod,
+                TokenKind::Rem,
+                TokenKind::Xnor,
+                TokenKind::Sll,
+                TokenKind::Srl,
+                TokenKind::Sla,
+                TokenKind::Sra,
+                TokenKind::Rol,
+                TokenKind::Ror,
+                TokenKind::Abs,
+            ]
+        );
+        // 演算子名を含む識別子はそのまま
+        let kinds: Vec<TokenKind> = Lexer::new("modulo remainder sll_count")
+            .map(|t| t.unwrap().kind)
+            .collect();
+        assert_eq!(kinds, vec![TokenKind::Identifier; 3]);
+    }
+
+    #[test]
+    fn test_token_categories() {
+        // 予約語は演算子を含めてすべて is_keyword
+        for word in crate::ident::RESERVED {
+            assert!(Lexer::keyword_or_identifier(word).is_keyword(), "{}", word);
+        }
+        let categories: Vec<TokenCategory> = Lexer::new("x <= a mod 16#F# ; -- c\nstd_logic")
+            .map(|t| t.unwrap().kind.category())
+            .collect();
+        assert_eq!(
+            categories,
+            [
+                TokenCategory::Identifier,
+                TokenCategory::Operator,
+                TokenCategory::Identifier,
+                TokenCategory::Operator,
+                TokenCategory::Literal,
+                TokenCategory::Delimiter,
+                TokenCategory::Comment,
+                TokenCategory::Type,
+            ]
+        );
+        assert!(TokenKind::Mod.is_operator() && TokenKind::Mod.is_keyword());
+        assert!(TokenKind::Signal.is_keyword() && !TokenKind::Signal.is_operator());
+        assert!(TokenKind::StdLogic.is_type() && !TokenKind::StdLogic.is_keyword());
+        assert!(TokenKind::CharacterLiteral.is_literal());
+        assert_eq!(TokenKind::Box.category(), TokenCategory::Delimiter);
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut lexer = Lexer::new("a <= $ b;");
+        assert_eq!(lexer.peek_token().unwrap().kind, TokenKind::Identifier);
+        // エラーも先読みでき、取り出すまで残る
+        assert_eq!(lexer.peek_nth(2).unwrap_err().span, Span::new(5, 6));
+        assert_eq!(lexer.peek_nth(3).unwrap().text, "b");
+        assert_eq!(lexer.next_token().unwrap().text, "a");
+        assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Lte);
+        assert!(lexer.next_token().is_err());
+        assert_eq!(lexer.peek_token().unwrap().text, "b");
+        let rest: Vec<TokenKind> = lexer.map(|t| t.unwrap().kind).collect();
+        assert_eq!(rest, [TokenKind::Identifier, TokenKind::Semicolon]);
+
+        let mut lexer = Lexer::new("x");
+        assert_eq!(lexer.peek_nth(5).unwrap().kind, TokenKind::Eof);
+        assert_eq!(lexer.next_token().unwrap().text, "x");
+        assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Eof);
+    }
+
+    #[test]
+    fn test_lexer_options() {
+        let source = "-- c\na; -- d\n'x'";
+        let kinds = |options: LexerOptions| -> Vec<TokenKind> {
+            Lexer::new(source)
+                .with_options(options)
+                .map(|t| t.unwrap().kind)
+                .collect()
+        };
+        assert_eq!(
+            kinds(LexerOptions::default()),
+            [
+                TokenKind::Comment,
+                TokenKind::Identifier,
+                TokenKind::Semicolon,
+                TokenKind::Comment,
+                TokenKind::CharacterLiteral,
+            ]
+        );
+        let options = LexerOptions {
+            include_eof: true,
+            skip_comments: true,
+            ..LexerOptions::default()
+        };
+        assert_eq!(
+            kinds(options),
+            [
+                TokenKind::Identifier,
+                TokenKind::Semicolon,
+                TokenKind::CharacterLiteral,
+                TokenKind::Eof,
+            ]
+        );
+        // Eof は1回だけ
+        let mut lexer = Lexer::new("").with_options(options);
+        assert_eq!(lexer.next().unwrap().unwrap().kind, TokenKind::Eof);
+        assert!(lexer.next().is_none());
+    }
+
     #[test]
     fn test_case_insensitive() {
         let source = "ENTITY Entity entity";
@@ -496,6 +2095,131 @@ mod tests {
         assert_eq!(token3.text, "'X'");
     }
 
+    #[test]
+    fn test_attribute_apostrophe() {
+        let kinds = |source: &str| -> Vec<(TokenKind, String)> {
+            Lexer::new(source)
+                .map(|t| t.unwrap())
+                .map(|t| (t.kind, t.text.to_string()))
+                .collect()
+        };
+        let tok = |kind: TokenKind, text: &str| (kind, text.to_string());
+        assert_eq!(
+            kinds("clk'event"),
+            [
+                tok(TokenKind::Identifier, "clk"),
+                tok(TokenKind::Apostrophe, "'"),
+                tok(TokenKind::Identifier, "event"),
+            ]
+        );
+        // 属性名が予約語でも属性
+        assert_eq!(
+            kinds("vec(3 downto 0)'range"),
+            [
+                tok(TokenKind::Identifier, "vec"),
+                tok(TokenKind::LeftParen, "("),
+                tok(TokenKind::IntegerLiteral, "3"),
+                tok(TokenKind::Downto, "downto"),
+                tok(TokenKind::IntegerLiteral, "0"),
+                tok(TokenKind::RightParen, ")"),
+                tok(TokenKind::Apostrophe, "'"),
+                tok(TokenKind::Range, "range"),
+            ]
+        );
+        // 修飾式の中の文字リテラル '('
+        assert_eq!(
+            kinds("character'('(')"),
+            [
+                tok(TokenKind::Identifier, "character"),
+                tok(TokenKind::Apostrophe, "'"),
+                tok(TokenKind::LeftParen, "("),
+                tok(TokenKind::CharacterLiteral, "'('"),
+                tok(TokenKind::RightParen, ")"),
+            ]
+        );
+        // 演算子やキーワードの後ろは文字リテラル
+        assert_eq!(
+            kinds("x <= '1' when en = '0' -- c'x\nelse a'length;")
+                .iter()
+                .filter(|(kind, _)| *kind == TokenKind::CharacterLiteral)
+                .count(),
+            2
+        );
+        assert_eq!(kinds("integer'image(n)")[1].0, TokenKind::Apostrophe);
+    }
+
+    #[test]
+    fn test_token_text_borrows_source() {
+        let source = "signal data : std_logic; -- note\r\n";
+        for token in Lexer::new(source).map(|t| t.unwrap()) {
+            // 字句は複製せず、ソースの同じ位置を指す
+            assert!(std::ptr::eq(
+                token.text,
+                &source[token.span.start..token.span.end]
+            ));
+        }
+    }
+
+    #[test]
+    fn test_trivia() {
+        let source = "\u{feff}-- header\r\nx <= a; -- note\r\n\n  y <= b;";
+        let mut lexer = Lexer::new(source).with_trivia();
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.next_token().unwrap();
+            let eof = token.kind == TokenKind::Eof;
+            tokens.push(token);
+            if eof {
+                break;
+            }
+        }
+        let x = &tokens[0];
+        assert_eq!(x.leading, "\u{feff}-- header\r\n");
+        assert_eq!(x.trailing, " ");
+        assert_eq!(x.full_span(), Span::new(0, 16));
+        // 行末のコメントと改行は `;` に付き、空行とインデントは次の行の先頭に付く
+        let semicolon = &tokens[3];
+        assert_eq!(semicolon.text, ";");
+        assert_eq!(semicolon.trailing, " -- note\r\n");
+        assert_eq!(tokens[4].leading, "\n  ");
+        assert_eq!(tokens.last().unwrap().kind, TokenKind::Eof);
+        // 既定ではトリビアを付けず、コメントはトークン
+        let first = Lexer::new(source).next_token().unwrap();
+        assert_eq!(first.kind, TokenKind::Comment);
+        assert_eq!(first.leading, "");
+    }
+
+    #[test]
+    fn test_tokenize_with_errors() {
+        let source = "signal a $ : bit;\nx <= \"abc";
+        let (tokens, errors) = Lexer::tokenize_with_errors(source);
+        let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Signal,
+                TokenKind::Identifier,
+                TokenKind::Unknown,
+                TokenKind::Colon,
+                TokenKind::Identifier,
+                TokenKind::Semicolon,
+                TokenKind::Identifier,
+                TokenKind::Lte,
+                TokenKind::Unknown,
+            ]
+        );
+        assert_eq!(tokens[2].text, "$");
+        assert_eq!(tokens[8].text, "\"abc");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].span, Span::new(9, 10));
+        assert_eq!(errors[1].location.as_ref().map(|l| l.line), Some(2));
+
+        // エラーがなければ通常の字句解析と同じ
+        let (tokens, errors) = Lexer::tokenize_with_errors("a <= b; -- c");
+        assert!(errors.is_empty());
+        assert_eq!(tokens.len(), 5);
+    }
+
     #[test]
     fn test_string_literal() {
         let source = r#""hello" "std_logic""#;
@@ -510,6 +2234,34 @@ mod tests {
         assert_eq!(token2.text, r#""std_logic""#);
     }
 
+    #[test]
+    fn test_string_literal_doubled_quotes() {
+        let source = r#"report "he said ""hi""" & """" & ""; x"#;
+        let tokens: Vec<_> = Lexer::new(source).map(|t| t.unwrap()).collect();
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Report,
+                TokenKind::StringLiteral,
+                TokenKind::Ampersand,
+                TokenKind::StringLiteral,
+                TokenKind::Ampersand,
+                TokenKind::StringLiteral,
+                TokenKind::Semicolon,
+                TokenKind::Identifier,
+            ]
+        );
+        assert_eq!(tokens[1].text, r#""he said ""hi""""#);
+        assert_eq!(tokens[1].string_value().as_deref(), Some(r#"he said "hi""#));
+        assert_eq!(tokens[3].string_value().as_deref(), Some(r#"""#));
+        assert_eq!(tokens[5].string_value().as_deref(), Some(""));
+        assert_eq!(tokens[7].string_value(), None);
+
+        let err = Lexer::new(r#""a"""#).next_token().unwrap_err();
+        assert_eq!(err.message, "unclosed string literal");
+    }
+
     #[test]
     fn test_simple_entity() {
         let source = r#"
@@ -545,6 +2297,164 @@ end entity;
         assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Semicolon);
     }
 
+    #[test]
+    fn test_based_literals() {
+        let source = "16#FF# 2#1010_1100# 2#1010#e4 16#F.8#E+1 8#777#;";
+        let tokens: Vec<Token> = Lexer::new(source).collect::<Result<_, _>>().unwrap();
+
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(
+            texts,
+            [
+                "16#FF#",
+                "2#1010_1100#",
+                "2#1010#e4",
+                "16#F.8#E+1",
+                "8#777#",
+                ";"
+            ]
+        );
+        let kinds: Vec<_> = tokens[..5].iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::IntegerLiteral,
+                TokenKind::IntegerLiteral,
+                TokenKind::IntegerLiteral,
+                TokenKind::RealLiteral,
+                TokenKind::IntegerLiteral
+            ]
+        );
+        assert_eq!(tokens[1].span, Span::new(7, 19));
+        // 指数は基数のべき（10 * 2^4）
+        assert_eq!(crate::literal::parse_integer(tokens[2].text), Some(160));
+    }
+
+    #[test]
+    fn test_invalid_based_literals() {
+        let err = Lexer::new("x := 16#FF").nth(2).unwrap().unwrap_err();
+        assert_eq!(err.message, "unterminated based literal");
+        assert_eq!(err.span, Span::new(5, 10));
+
+        let err = Lexer::new("2#102#").next_token().unwrap_err();
+        assert_eq!(err.message, "invalid based literal: 2#102#");
+        let err = Lexer::new("17#1#").next_token().unwrap_err();
+        assert_eq!(err.message, "invalid based literal: 17#1#");
+        let err = Lexer::new("16##").next_token().unwrap_err();
+        assert_eq!(err.message, "invalid based literal: 16##");
+    }
+
+    #[test]
+    fn test_integer_and_real_literals() {
+        let source = "42 1_000 1e3 1E+3 1.5 0.25e-2 3.0E2";
+        let tokens: Vec<Token> = Lexer::new(source).collect::<Result<_, _>>().unwrap();
+
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::IntegerLiteral,
+                TokenKind::IntegerLiteral,
+                TokenKind::IntegerLiteral,
+                TokenKind::IntegerLiteral,
+                TokenKind::RealLiteral,
+                TokenKind::RealLiteral,
+                TokenKind::RealLiteral
+            ]
+        );
+        assert_eq!(tokens[5].text, "0.25e-2");
+        assert_eq!(tokens[5].span, Span::new(22, 29));
+    }
+
+    #[test]
+    fn test_invalid_numeric_literals() {
+        for (source, text) in [
+            ("1.2.3e ", "1.2.3e"),
+            ("1e-3;", "1e-3"),
+            ("1.5e;", "1.5e"),
+            ("1._5", "1._5"),
+            ("1__0", "1__0"),
+            ("2#1#e-1", "2#1#e-1"),
+        ] {
+            let err = Lexer::new(source).next_token().unwrap_err();
+            assert!(err.message.ends_with(text), "{}", err.message);
+            assert_eq!(err.span, Span::new(0, text.len()));
+        }
+    }
+
+    #[test]
+    fn test_bit_string_literals() {
+        let source = r#"X"FF" b"1010_0001" O"777" 8X"F" 12UB"101" sx"F0" d"300" x"#;
+        let tokens: Vec<Token> = Lexer::new(source).collect::<Result<_, _>>().unwrap();
+
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(
+            texts,
+            [
+                r#"X"FF""#,
+                r#"b"1010_0001""#,
+                r#"O"777""#,
+                r#"8X"F""#,
+                r#"12UB"101""#,
+                r#"sx"F0""#,
+                r#"d"300""#,
+                "x"
+            ]
+        );
+        assert!(
+            tokens[..7]
+                .iter()
+                .all(|t| t.kind == TokenKind::BitStringLiteral)
+        );
+        // 基数指定でない識別子の後の文字列はそのまま
+        assert_eq!(tokens[7].kind, TokenKind::Identifier);
+        let kinds: Vec<TokenKind> = Lexer::new(r#"q"1" xb"1""#)
+            .map(|t| t.unwrap().kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Identifier,
+                TokenKind::StringLiteral,
+                TokenKind::Identifier,
+                TokenKind::StringLiteral
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_bit_string_literal() {
+        let err = Lexer::new("X\"FF;\nport").next_token().unwrap_err();
+        assert_eq!(err.message, "unclosed bit string literal");
+        assert_eq!(err.span, Span::new(0, 5));
+    }
+
+    #[test]
+    fn test_extended_identifiers() {
+        let source = r"signal \Bus-Signal\, \my\ : std_logic; \a\\b\ \end\";
+        let tokens: Vec<Token> = Lexer::new(source).collect::<Result<_, _>>().unwrap();
+
+        let idents: Vec<&str> = tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::Identifier)
+            .map(|t| t.text)
+            .collect();
+        // 書いたままの表記（予約語と同じ綴りでも識別子）
+        assert_eq!(idents, [r"\Bus-Signal\", r"\my\", r"\a\\b\", r"\end\"]);
+        assert_eq!(tokens[1].span, Span::new(7, 19));
+        assert_eq!(tokens[2].kind, TokenKind::Comma);
+    }
+
+    #[test]
+    fn test_invalid_extended_identifiers() {
+        let err = Lexer::new("\\bus\nport").next_token().unwrap_err();
+        assert_eq!(err.message, "unclosed extended identifier");
+        assert_eq!(err.span, Span::new(0, 4));
+        let err = Lexer::new(r"\\ x").next_token().unwrap_err();
+        assert_eq!(err.message, "empty extended identifier");
+        assert_eq!(err.span, Span::new(0, 2));
+    }
+
     #[test]
     fn test_logical_operators() {
         let source = "a and b or not c";
@@ -557,4 +2467,109 @@ end entity;
         assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Not);
         assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Identifier);
     }
+
+    #[test]
+    fn test_keyword_table() {
+        for (keyword, kind) in KEYWORDS {
+            let upper = keyword.to_ascii_uppercase();
+            for text in [keyword.to_string(), upper.clone()] {
+                let token = Lexer::new(&text).next_token().unwrap();
+                assert_eq!(token.kind, *kind, "{text}");
+            }
+            // 1文字足りない・多い綴りは識別子
+            for text in [&keyword[1..], &format!("{keyword}s")] {
+                let kinds: Vec<_> = Lexer::new(text).map(|t| t.unwrap().kind).collect();
+                if !KEYWORDS.iter().any(|(k, _)| *k == text) {
+                    assert_eq!(kinds, [TokenKind::Identifier], "{text}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_identifiers_and_spaces() {
+        let source = "a\u{3000}caf\u{e9}_1\t\u{3000} \u{5024}x <= b;";
+        let tokens: Vec<_> = Lexer::new(source).map(|t| t.unwrap()).collect();
+        let texts: Vec<_> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(texts, ["a", "caf\u{e9}_1", "\u{5024}x", "<=", "b", ";"]);
+        assert_eq!(tokens[1].kind, TokenKind::Identifier);
+        assert_eq!(tokens[1].span, Span::new(4, 11));
+    }
+
+    #[test]
+    fn test_protected_region() {
+        let source = "entity e is end;\n`protect begin_protected\n`protect data_block\n\
+                      a$?'\"\n`PROTECT END_PROTECTED\r\narchitecture";
+        let kinds: Vec<_> = Lexer::new(source).map(|t| t.unwrap().kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Entity,
+                TokenKind::Identifier,
+                TokenKind::Is,
+                TokenKind::End,
+                TokenKind::Semicolon,
+                TokenKind::Architecture,
+            ]
+        );
+
+        // 領域はトリビアとして残る
+        let mut lexer = Lexer::new(source).with_trivia();
+        let mut rebuilt = String::new();
+        loop {
+            let token = lexer.next_token().unwrap();
+            rebuilt.push_str(token.leading);
+            rebuilt.push_str(token.text);
+            rebuilt.push_str(token.trailing);
+            if token.kind == TokenKind::Architecture {
+                assert!(token.leading.starts_with("`protect begin_protected"));
+            }
+            if token.kind == TokenKind::Eof {
+                break;
+            }
+        }
+        assert_eq!(rebuilt, source);
+
+        // 終わりの指示がなければ末尾までが1つのエラー
+        let source = "a `protect begin\n`protect data_block\nxx";
+        let mut lexer = Lexer::new(source);
+        lexer.next_token().unwrap();
+        let err = lexer.next_token().unwrap_err();
+        assert_eq!(err.message, "unterminated `protect envelope");
+        assert_eq!(err.span, Span::new(2, source.len()));
+        assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Eof);
+
+        // その他のツール指示はこれまでどおりエラー
+        let err = Lexer::new("`protect key_keyowner = \"x\"").next_token();
+        assert_eq!(err.unwrap_err().message, "unexpected character: '`'");
+    }
+
+    #[test]
+    fn test_token_kind_groups() {
+        let kinds: Vec<TokenKind> = Lexer::new("if a ?<= 16#F# then x(1) <= literal;")
+            .map(|t| t.unwrap().kind)
+            .collect();
+        assert_eq!(kinds[0], TokenKind::Keyword(Keyword::If));
+        assert_eq!(kinds[2], TokenKind::Op(Op::MatchLte));
+        assert_eq!(kinds[3], TokenKind::Literal(LitKind::Integer));
+        assert_eq!(kinds[6], TokenKind::Delim(Delim::LeftParen));
+        assert_eq!(kinds[10], TokenKind::Keyword(Keyword::Literal));
+
+        // 分類前の名前は同じ値で、パターンにも使える
+        assert_eq!(TokenKind::Then, TokenKind::Keyword(Keyword::Then));
+        assert_eq!(TokenKind::from(Op::Lte), TokenKind::Lte);
+        assert!(matches!(kinds[9], TokenKind::Lte));
+        assert_eq!(kinds[10], TokenKind::LiteralKeyword);
+        let keywords = kinds
+            .iter()
+            .filter(|k| matches!(k, TokenKind::Keyword(_)))
+            .count();
+        assert_eq!(keywords, 3);
+
+        // 表示は分類前の名前
+        assert_eq!(format!("{:?}", TokenKind::Lte), "Lte");
+        assert_eq!(format!("{:?}", TokenKind::IntegerLiteral), "IntegerLiteral");
+        assert_eq!(format!("{:?}", TokenKind::StdLogic), "StdLogic");
+        assert!(Op::Xnor.is_word() && !Op::Lte.is_word());
+    }
 }
@@ -1,3 +1,112 @@
+//! Vig - VHDLテストベンチ生成ライブラリ
+//!
+//! VHDLソースを解析してエンティティのポート情報を取り出し、
+//! テストベンチを生成します。通常は [`prelude`] を取り込んで使います。
+//!
+//! ```
+//! use vig::prelude::*;
+//!
+//! let source = "entity counter is port (clk : in std_logic; count : out integer); end entity;";
+//! let result = vig::analyze(source)?;
+//! for entity in &result.entities {
+//!     let tb = TestbenchBuilder::new(entity).build();
+//!     assert!(tb.contains("uut: counter"));
+//! }
+//! # Ok::<(), vig::Error>(())
+//! ```
+//!
+//! 任意の入力を解析にまとめて通すファジングの入口は [`fuzz`] にあります。
+
 pub mod analyzer;
+pub mod assertion;
+pub mod banner;
+pub mod completion;
+pub mod constraints;
+mod coverage;
+pub mod diagnostic;
+pub mod diagram;
+mod drivers;
+pub mod emit;
+pub mod encoding;
+mod error;
+pub mod expr;
+pub mod fsm;
+pub mod fuzz;
 pub mod generator;
+pub mod ident;
+pub mod impact;
+pub mod interface;
+pub mod intern;
+pub mod interp;
+pub mod ipxact;
+mod json;
 pub mod lexer;
+pub mod library;
+pub mod lint;
+pub mod literal;
+pub mod logic;
+pub mod metrics;
+pub mod mock;
+pub mod pinlist;
+pub mod prelude;
+pub mod profile;
+pub mod project;
+pub mod regmap;
+pub mod regress;
+pub mod report;
+pub mod rewrite;
+pub mod semantic;
+pub mod signals;
+pub mod sim;
+pub mod simlog;
+pub mod source_map;
+pub mod style;
+pub mod sweep;
+pub mod testbench;
+pub mod testing;
+pub mod todo;
+mod toml;
+mod trace;
+pub mod vectors;
+pub mod vendor;
+pub mod verilog;
+pub mod wavedrom;
+mod xml;
+mod yaml;
+
+pub use analyzer::{AnalyzeResult, EntityDef as Entity, PortDef as Port, PortDirection, VhdlType};
+pub use diagnostic::{Diagnostic, Severity};
+pub use error::{Error, Result};
+pub use generator::{TbConfig, TestbenchBuilder};
+
+/// VHDLソースを解析する（[`analyzer::analyze_vhdl`] の統一エラー版）
+pub fn analyze(source: &str) -> Result<AnalyzeResult> {
+    Ok(analyzer::analyze_vhdl(source)?)
+}
+
+/// VHDLソースを解析し、スレッド間で共有できる形で返す
+///
+/// 解析結果は不変で `Send + Sync` なので、LSP のハンドラや並列に実行するリントの間で
+/// 1つの解析を使い回せます（[`lint::lint_analyzed`]）。
+pub fn analyze_shared(source: &str) -> Result<std::sync::Arc<AnalyzeResult>> {
+    Ok(std::sync::Arc::new(analyzer::analyze_vhdl(source)?))
+}
+
+// 解析結果・AST・シンボル表はスレッド間で共有できること
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<AnalyzeResult>();
+    assert_send_sync::<analyzer::AnalyzeError>();
+    assert_send_sync::<intern::InternedAst>();
+    assert_send_sync::<intern::Interner>();
+    assert_send_sync::<signals::ArchitectureUsage>();
+    assert_send_sync::<Diagnostic>();
+    assert_send_sync::<Error>();
+};
+
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,444 @@
+//! use 節のライブラリの解決
+//!
+//! vig.toml の `[libraries]` にライブラリごとの検索ディレクトリを書くと、`use work.my_pkg.all;`
+//! のように参照したパッケージ・エンティティを定義するファイルを探して解析します。
+//! 1ファイルだけを指定して実行しても、パッケージで宣言したサブタイプのポートを
+//! 具体的な型として扱えます。
+//!
+//! ```toml
+//! [libraries]
+//! work = ["rtl", "pkg"]
+//! common = ["../common/src"]
+//! ```
+//!
+//! ディレクトリは vig.toml からの相対パスで、サブディレクトリも含めて `.vhd` / `.vhdl` を
+//! 探します。ファイルは必要になったときに初めて読み込み（ユニット名と同じ名前のファイルを
+//! 先に調べる）、一度読んだ結果は [`Resolver`] が覚えておきます。パッケージは解析器の
+//! [`PackageDef`] をそのまま使います。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{
+    AnalyzeError, AnalyzeResult, EntityDef, PackageDef, TypeDef, VhdlType, analyze_vhdl,
+};
+use crate::diagnostic::Diagnostic;
+use crate::encoding::{self, Encoding};
+use crate::json::Value;
+use crate::lexer::{Lexer, LexerOptions, Span, Token, TokenKind};
+use crate::toml::{self, TomlError};
+use crate::vendor;
+
+/// ライブラリの解決のエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryError {
+    pub message: String,
+    /// エラーのあったファイル（vig.toml の設定のエラーは None）
+    pub path: Option<PathBuf>,
+    pub span: Span,
+}
+
+impl LibraryError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            path: None,
+            span,
+        }
+    }
+
+    fn in_file(message: impl Into<String>, path: &Path, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            path: Some(path.to_path_buf()),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for LibraryError {}
+
+impl From<TomlError> for LibraryError {
+    fn from(err: TomlError) -> Self {
+        LibraryError::new(err.message, err.span)
+    }
+}
+
+/// ライブラリごとの検索ディレクトリ
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LibraryPaths {
+    /// (ライブラリ名, ディレクトリ) の組（書いた順）
+    pub libraries: Vec<(String, Vec<PathBuf>)>,
+}
+
+impl LibraryPaths {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ライブラリに検索ディレクトリを加える
+    pub fn add(&mut self, library: &str, dir: impl Into<PathBuf>) -> &mut Self {
+        let dir = dir.into();
+        match self
+            .libraries
+            .iter_mut()
+            .find(|(name, _)| name.eq_ignore_ascii_case(library))
+        {
+            Some((_, dirs)) => dirs.push(dir),
+            None => self.libraries.push((library.to_string(), vec![dir])),
+        }
+        self
+    }
+
+    /// ライブラリの検索ディレクトリ（大文字小文字を区別しない）
+    pub fn dirs(&self, library: &str) -> &[PathBuf] {
+        self.libraries
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(library))
+            .map_or(&[], |(_, dirs)| dirs)
+    }
+}
+
+/// vig.toml の `[libraries]` を読み込む（なければ None）
+///
+/// 相対パスは `base_dir`（vig.toml のあるディレクトリ）からのものとして扱います。
+pub fn from_config(source: &str, base_dir: &Path) -> Result<Option<LibraryPaths>, LibraryError> {
+    let root = toml::parse(source)?;
+    let Some(section) = root.get("libraries") else {
+        return Ok(None);
+    };
+    let locate = |text: &str| {
+        source
+            .find(text)
+            .map_or(Span::new(0, 0), |pos| Span::new(pos, pos + text.len()))
+    };
+    let Value::Object(table) = section else {
+        return Err(LibraryError::new(
+            "'libraries' must be a table",
+            locate("libraries"),
+        ));
+    };
+    let mut paths = LibraryPaths::new();
+    for (library, value) in table {
+        let err = || {
+            LibraryError::new(
+                format!("library '{}' must be an array of directories", library),
+                locate(library),
+            )
+        };
+        for dir in value.as_array().ok_or_else(err)? {
+            paths.add(library, base_dir.join(dir.as_str().ok_or_else(err)?));
+        }
+    }
+    Ok(Some(paths))
+}
+
+/// use 節
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UseClause {
+    pub library: String,
+    pub package: String,
+    /// `all` または使う名前
+    pub item: String,
+    pub span: Span,
+}
+
+impl UseClause {
+    /// 名前がこの use 節で見えるか
+    pub fn makes_visible(&self, name: &str) -> bool {
+        self.item.eq_ignore_ascii_case("all") || self.item.eq_ignore_ascii_case(name)
+    }
+}
+
+/// ソース中の `use <ライブラリ>.<パッケージ>.<名前>;` をすべて集める
+pub fn use_clauses(source: &str) -> Vec<UseClause> {
+    let tokens = tokens(source);
+    let text = |i: usize, kind: TokenKind| {
+        tokens
+            .get(i)
+            .filter(|t| t.kind == kind)
+            .map(|t| t.text.to_string())
+    };
+    let mut clauses = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Use {
+            continue;
+        }
+        let clause = (|| {
+            let library = text(i + 1, TokenKind::Identifier)?;
+            text(i + 2, TokenKind::Dot)?;
+            let package = text(i + 3, TokenKind::Identifier)?;
+            text(i + 4, TokenKind::Dot)?;
+            let item =
+                text(i + 5, TokenKind::All).or_else(|| text(i + 5, TokenKind::Identifier))?;
+            let end = tokens
+                .get(i + 6)
+                .filter(|t| t.kind == TokenKind::Semicolon)?;
+            Some(UseClause {
+                library,
+                package,
+                item,
+                span: Span::new(token.span.start, end.span.end),
+            })
+        })();
+        clauses.extend(clause);
+    }
+    clauses
+}
+
+/// コメントを除くトークン（字句解析エラーのところで止める）
+fn tokens(source: &str) -> Vec<Token<'_>> {
+    Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .map_while(Result::ok)
+        .collect()
+}
+
+/// 読み込んだファイル1つ
+#[derive(Debug)]
+struct ScannedFile {
+    /// 解析の結果（エラーはそのファイルのユニットを探したときに返す）
+    analysis: Result<AnalyzeResult, AnalyzeError>,
+    /// エンティティ名（解析できなかったファイルでもエラーを返せるように字句だけで拾う）
+    entities: Vec<String>,
+}
+
+impl ScannedFile {
+    fn packages(&self) -> &[PackageDef] {
+        self.analysis.as_ref().map_or(&[], |r| &r.packages)
+    }
+}
+
+/// 検索ディレクトリからパッケージ・エンティティを探す
+///
+/// 読み込んだファイルは覚えておくので、同じ Resolver で何度探しても同じファイルを
+/// 読み直すことはありません。
+#[derive(Debug)]
+pub struct Resolver {
+    paths: LibraryPaths,
+    encoding: Option<Encoding>,
+    /// ライブラリごとの VHDL ファイルの一覧（小文字のライブラリ名）
+    listings: HashMap<String, Vec<PathBuf>>,
+    scanned: HashMap<PathBuf, ScannedFile>,
+}
+
+impl Resolver {
+    pub fn new(paths: LibraryPaths) -> Self {
+        Self {
+            paths,
+            encoding: None,
+            listings: HashMap::new(),
+            scanned: HashMap::new(),
+        }
+    }
+
+    /// ファイルの文字コード（指定しなければ推定する）
+    pub fn encoding(mut self, encoding: Option<Encoding>) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// パッケージを探す（検索パスになければ組み込みのスタブ、それもなければ None）
+    pub fn package(
+        &mut self,
+        library: &str,
+        name: &str,
+    ) -> Result<Option<&PackageDef>, LibraryError> {
+        let Some(path) = self.locate(library, name, |file| {
+            file.packages()
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(name))
+        })?
+        else {
+            // パッケージと同じ名前のファイルが解析できなければ、見つからないことにせずエラーにする
+            let broken = self.scanned.iter().find_map(|(path, file)| {
+                let err = file.analysis.as_ref().err()?;
+                let stem = path.file_stem().and_then(|s| s.to_str())?;
+                stem.eq_ignore_ascii_case(name).then_some((path, err))
+            });
+            if let Some((path, err)) = broken {
+                return Err(LibraryError::in_file(&err.message, path, err.span));
+            }
+            return Ok(vendor::package(library, name));
+        };
+        Ok(self.scanned[&path]
+            .packages()
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name)))
+    }
+
+    /// エンティティを探して解析する（検索パスになければ組み込みのスタブ、それもなければ None）
+    pub fn entity(&mut self, library: &str, name: &str) -> Result<Option<EntityDef>, LibraryError> {
+        let Some(path) = self.locate(library, name, |file| {
+            file.entities.iter().any(|e| e.eq_ignore_ascii_case(name))
+        })?
+        else {
+            return Ok(vendor::entity(library, name));
+        };
+        let result = self.scanned[&path]
+            .analysis
+            .as_ref()
+            .map_err(|e| LibraryError::in_file(&e.message, &path, e.span))?;
+        Ok(result
+            .entities
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+            .cloned())
+    }
+
+    /// ソースの use 節で見えるパッケージのサブタイプで、エンティティのポートの型を置き換える
+    ///
+    /// 見つからないパッケージは警告として返します（位置は `source` の use 節）。
+    /// vig.toml に書いていないライブラリは、組み込みのスタブ（[`crate::vendor`]）があるものだけを
+    /// 調べ、見つからなくても警告しません。
+    pub fn resolve_entity(
+        &mut self,
+        source: &str,
+        entity: &mut EntityDef,
+    ) -> Result<Vec<Diagnostic>, LibraryError> {
+        let mut diagnostics = Vec::new();
+        let mut visible = Vec::new();
+        for clause in use_clauses(source) {
+            let searched = !self.paths.dirs(&clause.library).is_empty();
+            if !searched && !vendor::is_builtin_library(&clause.library) {
+                continue;
+            }
+            match self.package(&clause.library, &clause.package)? {
+                Some(package) => visible.push((clause, package.clone())),
+                None if !searched => {}
+                None => diagnostics.push(Diagnostic::warning(
+                    format!(
+                        "package '{}.{}' not found in library search paths",
+                        clause.library, clause.package
+                    ),
+                    clause.span,
+                )),
+            }
+        }
+        for port in &mut entity.ports {
+            if let Some(resolved) = resolve_type(&port.vhdl_type, &visible) {
+                port.vhdl_type = resolved;
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// 条件に合うファイルを探す（ユニット名と同じ名前のファイルを先に調べる）
+    fn locate(
+        &mut self,
+        library: &str,
+        name: &str,
+        matches: impl Fn(&ScannedFile) -> bool,
+    ) -> Result<Option<PathBuf>, LibraryError> {
+        let mut files = self.listing(library).to_vec();
+        // 安定ソートなので、同じ名前のもの以外は元の順のまま
+        files.sort_by_key(|path| {
+            !path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .is_some_and(|s| s.eq_ignore_ascii_case(name))
+        });
+        for path in files {
+            if !self.scanned.contains_key(&path) {
+                let source = encoding::read_source(&path, self.encoding).map_err(|e| {
+                    LibraryError::in_file(e.to_string(), &path, e.span().unwrap_or(Span::new(0, 0)))
+                })?;
+                let file = ScannedFile {
+                    analysis: analyze_vhdl(&source),
+                    entities: entity_names(&source),
+                };
+                self.scanned.insert(path.clone(), file);
+            }
+            if matches(&self.scanned[&path]) {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// ライブラリの VHDL ファイルの一覧（初回だけディレクトリをたどる）
+    fn listing(&mut self, library: &str) -> &[PathBuf] {
+        let key = library.to_lowercase();
+        if !self.listings.contains_key(&key) {
+            let mut files = Vec::new();
+            for dir in self.paths.dirs(library) {
+                collect_vhdl_files(dir, &mut files);
+            }
+            self.listings.insert(key.clone(), files);
+        }
+        &self.listings[&key]
+    }
+}
+
+/// 見えるパッケージのサブタイプをたどって型を具体的にする（たどれなければ None）
+fn resolve_type(vhdl_type: &VhdlType, visible: &[(UseClause, PackageDef)]) -> Option<VhdlType> {
+    let mut current = vhdl_type.clone();
+    // サブタイプのサブタイプもたどる（循環していても止まるように回数を制限する）
+    for _ in 0..16 {
+        let VhdlType::Other(name) = &current else {
+            break;
+        };
+        let next = visible.iter().find_map(|(clause, package)| {
+            if !clause.makes_visible(name) {
+                return None;
+            }
+            match package.find_type(name)? {
+                TypeDef::Subtype { vhdl_type, .. } => Some(vhdl_type.clone()),
+                _ => None,
+            }
+        })?;
+        current = next;
+    }
+    (current != *vhdl_type).then_some(current)
+}
+
+/// エンティティ宣言の名前（`entity <名前> is`）
+fn entity_names(source: &str) -> Vec<String> {
+    tokens(source)
+        .windows(3)
+        .filter(|w| {
+            w[0].kind == TokenKind::Entity
+                && w[1].kind == TokenKind::Identifier
+                && w[2].kind == TokenKind::Is
+        })
+        .map(|w| w[1].text.to_string())
+        .collect()
+}
+
+/// ディレクトリ以下の `.vhd` / `.vhdl` ファイル（名前順、読めないディレクトリは飛ばす）
+fn collect_vhdl_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_vhdl_files(&path, files);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("vhd") || e.eq_ignore_ascii_case("vhdl"))
+        {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,1255 @@
+//! 設計のリント
+//!
+//! 解析とは別に、合成はできるが誤りやすい書き方を診断として報告します。
+//! 規則は [`lint`] でまとめて実行するか、規則ごとの関数で個別に実行します。
+//!
+//! | 規則 | 関数 |
+//! |------|------|
+//! | 同期化なしのクロックドメイン間の受け渡し | [`check_cdc`] |
+//! | 命名規則（大文字小文字・接頭辞・接尾辞） | [`check_naming`] |
+//! | 実行されない論理（定数条件・到達しない case 分岐・上書きされる代入） | [`check_dead_logic`] |
+//! | 解決関数のない型の信号の複数駆動 | [`signals::check_multiple_drivers`] |
+//! | ポートの並び順（クロック・リセット・入力・出力、接頭辞ごと） | [`check_port_order`] |
+//!
+//! 命名規則は vig.toml の `[lint.naming.<対象>]`、ポートの並び順は `[lint.port_order]` で
+//! 設定します（[`LintConfig::from_toml`]）。並び順の違反は [`fix_port_order`] で直せます。
+
+use std::collections::HashMap;
+
+use crate::analyzer::{AnalyzeResult, EntityDef, PortDef, PortDirection, analyze_vhdl};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::drivers::{self, Driver};
+use crate::interp::{Constant, eval_constants};
+use crate::json::Value;
+use crate::lexer::{Lexer, LexerOptions, Span, Token, TokenKind};
+use crate::rewrite::{RewriteError, Rewriter};
+use crate::signals;
+use crate::toml::{self, TomlError};
+use crate::trace::timed;
+
+/// リント設定のエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl LintError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for LintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for LintError {}
+
+impl From<TomlError> for LintError {
+    fn from(e: TomlError) -> Self {
+        Self::new(e.message, e.span)
+    }
+}
+
+/// リントの設定
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintConfig {
+    /// 命名規則（空なら検査しない）
+    pub naming: Vec<NamingRule>,
+    /// ポートの並び順の方針（None なら検査しない）
+    pub port_order: Option<PortOrderPolicy>,
+}
+
+/// 命名規則を適用する対象
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamingTarget {
+    /// 以下のすべて
+    Identifiers,
+    Entities,
+    Generics,
+    Inputs,
+    /// out と buffer のポート
+    Outputs,
+    Inouts,
+    Signals,
+    Constants,
+    /// `= '0'` で判定している負論理のポート・信号
+    ActiveLow,
+}
+
+impl NamingTarget {
+    const ALL: [(&'static str, NamingTarget); 9] = [
+        ("identifiers", NamingTarget::Identifiers),
+        ("entities", NamingTarget::Entities),
+        ("generics", NamingTarget::Generics),
+        ("inputs", NamingTarget::Inputs),
+        ("outputs", NamingTarget::Outputs),
+        ("inouts", NamingTarget::Inouts),
+        ("signals", NamingTarget::Signals),
+        ("constants", NamingTarget::Constants),
+        ("active_low", NamingTarget::ActiveLow),
+    ];
+}
+
+/// 識別子の大文字小文字の書き方
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `snake_case`
+    Snake,
+    /// `UPPER_SNAKE_CASE`
+    UpperSnake,
+    /// `PascalCase`
+    Pascal,
+    /// `camelCase`
+    Camel,
+}
+
+impl Case {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "snake" => Some(Case::Snake),
+            "upper_snake" => Some(Case::UpperSnake),
+            "pascal" => Some(Case::Pascal),
+            "camel" => Some(Case::Camel),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Case::Snake => "snake_case",
+            Case::UpperSnake => "UPPER_SNAKE_CASE",
+            Case::Pascal => "PascalCase",
+            Case::Camel => "camelCase",
+        }
+    }
+
+    /// 名前がこの書き方に従っているか
+    pub fn matches(self, name: &str) -> bool {
+        let mut chars = name.chars();
+        let Some(first) = chars.next() else {
+            return true;
+        };
+        match self {
+            Case::Snake => !name.contains("__") && name.chars().all(|c| !c.is_ascii_uppercase()),
+            Case::UpperSnake => {
+                !name.contains("__") && name.chars().all(|c| !c.is_ascii_lowercase())
+            }
+            Case::Pascal => first.is_ascii_uppercase() && !name.contains('_'),
+            Case::Camel => first.is_ascii_lowercase() && !name.contains('_'),
+        }
+    }
+}
+
+/// 命名規則1つ
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamingRule {
+    pub target: NamingTarget,
+    pub case: Option<Case>,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    /// 違反を報告する重大度（省略時は警告）
+    pub severity: Severity,
+    /// 規則を適用しない名前（大文字小文字は区別しない）
+    pub allow: Vec<String>,
+}
+
+impl NamingRule {
+    pub fn new(target: NamingTarget) -> Self {
+        Self {
+            target,
+            case: None,
+            prefix: None,
+            suffix: None,
+            severity: Severity::Warning,
+            allow: Vec::new(),
+        }
+    }
+}
+
+/// 並び順を決めるポートの分類
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortClass {
+    /// 名前に clk を含む入力
+    Clocks,
+    /// 名前に rst か reset を含む入力
+    Resets,
+    Inputs,
+    Inouts,
+    /// out と buffer のポート
+    Outputs,
+}
+
+impl PortClass {
+    const ALL: [(&'static str, PortClass); 5] = [
+        ("clocks", PortClass::Clocks),
+        ("resets", PortClass::Resets),
+        ("inputs", PortClass::Inputs),
+        ("inouts", PortClass::Inouts),
+        ("outputs", PortClass::Outputs),
+    ];
+
+    pub fn of(port: &PortDef) -> Self {
+        let name = port.name.to_lowercase();
+        match port.direction {
+            PortDirection::In if name.contains("clk") => PortClass::Clocks,
+            PortDirection::In if name.contains("rst") || name.contains("reset") => {
+                PortClass::Resets
+            }
+            PortDirection::In => PortClass::Inputs,
+            PortDirection::Inout => PortClass::Inouts,
+            _ => PortClass::Outputs,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            PortClass::Clocks => "clock",
+            PortClass::Resets => "reset",
+            PortClass::Inputs => "input",
+            PortClass::Inouts => "inout",
+            PortClass::Outputs => "output",
+        }
+    }
+}
+
+/// ポートの並び順の方針
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortOrderPolicy {
+    /// 分類の順（挙げなかった分類は既定の順で後ろに続く）
+    pub order: Vec<PortClass>,
+    /// 分類の中で、名前の接頭辞（最後の `_` より前）が同じポートをまとめる
+    pub group_by_prefix: bool,
+    /// 違反を報告する重大度（省略時は警告）
+    pub severity: Severity,
+}
+
+impl Default for PortOrderPolicy {
+    /// クロック・リセット・入力・双方向・出力の順で、接頭辞ごとにまとめる
+    fn default() -> Self {
+        Self {
+            order: PortClass::ALL.iter().map(|(_, c)| *c).collect(),
+            group_by_prefix: true,
+            severity: Severity::Warning,
+        }
+    }
+}
+
+impl PortOrderPolicy {
+    fn rank(&self, class: PortClass) -> usize {
+        self.order
+            .iter()
+            .position(|c| *c == class)
+            .unwrap_or_else(|| {
+                self.order.len()
+                    + PortClass::ALL
+                        .iter()
+                        .position(|(_, c)| *c == class)
+                        .unwrap_or(0)
+            })
+    }
+}
+
+/// 重大度の名前
+fn parse_severity(text: &str) -> Option<Severity> {
+    match text {
+        "error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        "info" => Some(Severity::Info),
+        _ => None,
+    }
+}
+
+impl LintConfig {
+    /// vig.toml の `[lint]` を読み込む
+    ///
+    /// ```toml
+    /// [lint.naming.identifiers]
+    /// case = "snake"
+    ///
+    /// [lint.naming.inputs]
+    /// prefix = "i_"
+    /// allow = ["clk", "rst"]
+    ///
+    /// [lint.naming.active_low]
+    /// suffix = "_n"
+    /// severity = "error"
+    ///
+    /// [lint.port_order]
+    /// order = ["clocks", "resets", "inputs", "outputs"]
+    /// group_by_prefix = true
+    /// ```
+    pub fn from_toml(source: &str) -> Result<Self, LintError> {
+        let root = toml::parse(source)?;
+        let mut config = LintConfig::default();
+        // 値の位置はファイル中の文字列から探す
+        let locate = |text: &str| {
+            source
+                .find(text)
+                .map_or(Span::new(0, 0), |pos| Span::new(pos, pos + text.len()))
+        };
+        if let Some(port_order) = root.get("lint").and_then(|l| l.get("port_order")) {
+            config.port_order = Some(port_order_policy(port_order, &locate)?);
+        }
+        let Some(Value::Object(naming)) = root.get("lint").and_then(|l| l.get("naming")) else {
+            return Ok(config);
+        };
+        for (key, value) in naming {
+            let Some(&(_, target)) = NamingTarget::ALL.iter().find(|(name, _)| name == key) else {
+                return Err(LintError::new(
+                    format!("unknown naming rule '{}'", key),
+                    locate(key),
+                ));
+            };
+            let Value::Object(fields) = value else {
+                return Err(LintError::new(
+                    format!("naming rule '{}' must be a table", key),
+                    locate(key),
+                ));
+            };
+            let mut rule = NamingRule::new(target);
+            for (field, value) in fields {
+                let text = || {
+                    value.as_str().ok_or_else(|| {
+                        LintError::new(format!("'{}' must be a string", field), locate(field))
+                    })
+                };
+                match field.as_str() {
+                    "case" => {
+                        let text = text()?;
+                        rule.case = Some(Case::parse(text).ok_or_else(|| {
+                            LintError::new(
+                                format!(
+                                    "unknown case '{}' (expected snake, upper_snake, pascal or camel)",
+                                    text
+                                ),
+                                locate(&format!("\"{}\"", text)),
+                            )
+                        })?);
+                    }
+                    "prefix" => rule.prefix = Some(text()?.to_string()),
+                    "suffix" => rule.suffix = Some(text()?.to_string()),
+                    "severity" => {
+                        let text = text()?;
+                        rule.severity =
+                            parse_severity(text).ok_or_else(|| unknown_severity(text, &locate))?;
+                    }
+                    "allow" => {
+                        let err =
+                            || LintError::new("'allow' must be an array of strings", locate(field));
+                        rule.allow = value
+                            .as_array()
+                            .ok_or_else(err)?
+                            .iter()
+                            .map(|v| v.as_str().map(str::to_string).ok_or_else(err))
+                            .collect::<Result<_, _>>()?;
+                    }
+                    _ => {
+                        return Err(LintError::new(
+                            format!("unknown field '{}' in naming rule '{}'", field, key),
+                            locate(field),
+                        ));
+                    }
+                }
+            }
+            config.naming.push(rule);
+        }
+        Ok(config)
+    }
+}
+
+fn unknown_severity(text: &str, locate: &dyn Fn(&str) -> Span) -> LintError {
+    LintError::new(
+        format!(
+            "unknown severity '{}' (expected error, warning or info)",
+            text
+        ),
+        locate(&format!("\"{}\"", text)),
+    )
+}
+
+/// `[lint.port_order]` を読む
+fn port_order_policy(
+    value: &Value,
+    locate: &dyn Fn(&str) -> Span,
+) -> Result<PortOrderPolicy, LintError> {
+    let Value::Object(fields) = value else {
+        return Err(LintError::new(
+            "'port_order' must be a table",
+            locate("port_order"),
+        ));
+    };
+    let mut policy = PortOrderPolicy::default();
+    for (field, value) in fields {
+        match field.as_str() {
+            "order" => {
+                let err = || LintError::new("'order' must be an array of strings", locate(field));
+                policy.order = Vec::new();
+                for item in value.as_array().ok_or_else(err)? {
+                    let name = item.as_str().ok_or_else(err)?;
+                    let Some(&(_, class)) = PortClass::ALL.iter().find(|(n, _)| *n == name) else {
+                        return Err(LintError::new(
+                            format!(
+                                "unknown port class '{}' (expected clocks, resets, inputs, inouts or outputs)",
+                                name
+                            ),
+                            locate(&format!("\"{}\"", name)),
+                        ));
+                    };
+                    policy.order.push(class);
+                }
+            }
+            "group_by_prefix" => {
+                policy.group_by_prefix = value.as_bool().ok_or_else(|| {
+                    LintError::new("'group_by_prefix' must be a boolean", locate(field))
+                })?;
+            }
+            "severity" => {
+                let text = value
+                    .as_str()
+                    .ok_or_else(|| LintError::new("'severity' must be a string", locate(field)))?;
+                policy.severity =
+                    parse_severity(text).ok_or_else(|| unknown_severity(text, locate))?;
+            }
+            _ => {
+                return Err(LintError::new(
+                    format!("unknown field '{}' in port_order", field),
+                    locate(field),
+                ));
+            }
+        }
+    }
+    Ok(policy)
+}
+
+/// すべての規則を実行する
+pub fn lint(source: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let result = analyze_vhdl(source).unwrap_or_default();
+    lint_analyzed(source, &result, config)
+}
+
+/// 解析済みの結果を使ってすべての規則を実行する
+///
+/// 複数のスレッドで [`crate::analyze_shared`] の結果を共有し、解析をやり直さずに
+/// リントできます。
+pub fn lint_analyzed(source: &str, result: &AnalyzeResult, config: &LintConfig) -> Vec<Diagnostic> {
+    let _timed = timed!("lint");
+    let mut diagnostics = check_cdc(source);
+    diagnostics.extend(check_naming(source, &config.naming));
+    diagnostics.extend(check_dead_logic(source));
+    for entity in &result.entities {
+        diagnostics.extend(signals::check_multiple_drivers(source, entity));
+    }
+    if let Some(policy) = &config.port_order {
+        diagnostics.extend(port_order_diagnostics(&result.entities, policy));
+    }
+    diagnostics.sort_by_key(|d| d.span.start);
+    diagnostics
+}
+
+// --- クロックドメイン ---
+
+/// 信号の属するクロックドメインと駆動元
+struct Domain {
+    clock: String,
+    span: Span,
+}
+
+/// 駆動元からクロックドメインを推定する
+///
+/// クロック付きプロセスで代入する信号はそのクロック、組み合わせ回路で代入する信号は
+/// 参照する信号のドメインが1つに決まる場合にそのドメインとします。
+fn infer_domains(drivers: &[Driver]) -> HashMap<String, Domain> {
+    let mut domains: HashMap<String, Domain> = HashMap::new();
+    for driver in drivers {
+        let Some(clock) = &driver.clock else {
+            continue;
+        };
+        for (name, span) in &driver.writes {
+            domains.entry(name.clone()).or_insert_with(|| Domain {
+                clock: clock.clone(),
+                span: *span,
+            });
+        }
+    }
+    // 組み合わせ回路を通した伝搬（変化がなくなるまで）
+    loop {
+        let mut changed = false;
+        for driver in drivers.iter().filter(|d| d.clock.is_none()) {
+            let mut clocks: Vec<&str> = driver
+                .reads
+                .iter()
+                .filter_map(|r| domains.get(&r.name).map(|d| d.clock.as_str()))
+                .collect();
+            clocks.sort_unstable();
+            clocks.dedup();
+            let [clock] = clocks[..] else {
+                continue;
+            };
+            let clock = clock.to_string();
+            for (name, span) in &driver.writes {
+                if !domains.contains_key(name) {
+                    domains.insert(
+                        name.clone(),
+                        Domain {
+                            clock: clock.clone(),
+                            span: *span,
+                        },
+                    );
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    domains
+}
+
+/// 同期化なしにクロックドメインをまたぐ信号を報告する
+///
+/// あるクロックのプロセスで代入した信号を別のクロックのプロセスで参照していて、
+/// 受け側が同期化の形になっていないものを警告します。同期化とみなすのは、
+/// `s1 <= x;` の代入先をさらに `s2 <= s1;` で受ける2段のフリップフロップか、
+/// 代入先の名前に `sync` または `meta` を含むもの（シフトレジスタ形式）です。
+/// 警告には参照した位置と、駆動元の位置を関連情報として含めます。
+pub fn check_cdc(source: &str) -> Vec<Diagnostic> {
+    let _timed = timed!("check_cdc");
+    drivers::architectures(source)
+        .iter()
+        .flat_map(|arch| cdc_crossings(source, &arch.drivers))
+        .collect()
+}
+
+/// アーキテクチャ1つの中のドメイン間の受け渡し
+fn cdc_crossings(source: &str, drivers: &[Driver]) -> Vec<Diagnostic> {
+    let domains = infer_domains(drivers);
+    // 代入先を右辺だけで受け直している信号（同期化の2段目）
+    let restaged = |name: &str, clock: &str| {
+        drivers
+            .iter()
+            .filter(|d| d.clock.as_deref() == Some(clock))
+            .flat_map(|d| &d.reads)
+            .any(|r| r.name == name && r.sole)
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut reported: Vec<(String, String)> = Vec::new();
+    for driver in drivers {
+        let Some(clock) = &driver.clock else {
+            continue;
+        };
+        for read in &driver.reads {
+            let Some(domain) = domains.get(&read.name) else {
+                continue;
+            };
+            if domain.clock == *clock {
+                continue;
+            }
+            let synchronized = read.target.as_ref().is_some_and(|stage| {
+                stage.contains("sync")
+                    || stage.contains("meta")
+                    || (read.sole && restaged(stage, clock))
+            });
+            let key = (read.name.clone(), clock.clone());
+            if synchronized || reported.contains(&key) {
+                continue;
+            }
+            reported.push(key);
+            let name = &source[read.span.start..read.span.end];
+            diagnostics.push(
+                Diagnostic::warning(
+                    format!(
+                        "signal '{}' crosses from clock domain '{}' to '{}' without a synchronizer",
+                        name, domain.clock, clock
+                    ),
+                    read.span,
+                )
+                .with_related(
+                    domain.span,
+                    format!(
+                        "'{}' is driven in clock domain '{}' here",
+                        name, domain.clock
+                    ),
+                ),
+            );
+        }
+    }
+    diagnostics
+}
+
+// --- 命名規則 ---
+
+/// 宣言された名前
+struct Declaration<'a> {
+    name: &'a str,
+    span: Span,
+    /// 当てはまる対象（Identifiers 以外）
+    targets: Vec<NamingTarget>,
+}
+
+/// 対象の表示名
+fn describe(target: NamingTarget) -> &'static str {
+    match target {
+        NamingTarget::Identifiers => "identifier",
+        NamingTarget::Entities => "entity",
+        NamingTarget::Generics => "generic",
+        NamingTarget::Inputs => "input port",
+        NamingTarget::Outputs => "output port",
+        NamingTarget::Inouts => "inout port",
+        NamingTarget::Signals => "signal",
+        NamingTarget::Constants => "constant",
+        NamingTarget::ActiveLow => "active-low signal",
+    }
+}
+
+/// エンティティ・ジェネリック・ポート・信号・定数の宣言を集める
+///
+/// コンポーネント宣言は別のエンティティの写しなので対象にしません。
+fn declarations<'a>(tokens: &'a [Token]) -> Vec<Declaration<'a>> {
+    let mut decls: Vec<Declaration> = Vec::new();
+    let mut in_component = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let kind = &tokens[i].kind;
+        let next = tokens.get(i + 1).map(|t| &t.kind);
+        match *kind {
+            TokenKind::Component => in_component = i == 0 || tokens[i - 1].kind != TokenKind::End,
+            TokenKind::Entity
+                if next == Some(&TokenKind::Identifier)
+                    && tokens.get(i + 2).is_some_and(|t| t.kind == TokenKind::Is) =>
+            {
+                let t = &tokens[i + 1];
+                decls.push(Declaration {
+                    name: t.text,
+                    span: t.span,
+                    targets: vec![NamingTarget::Entities],
+                });
+            }
+            TokenKind::Generic | TokenKind::Port
+                if next == Some(&TokenKind::LeftParen) && !in_component =>
+            {
+                i = interface_list(tokens, i + 1, *kind == TokenKind::Generic, &mut decls);
+                continue;
+            }
+            TokenKind::Signal => {
+                i = object_names(tokens, i + 1, NamingTarget::Signals, &mut decls);
+                continue;
+            }
+            TokenKind::Constant if next == Some(&TokenKind::Identifier) => {
+                i = object_names(tokens, i + 1, NamingTarget::Constants, &mut decls);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    decls
+}
+
+/// `a, b :` の名前を読む（`:` の次の位置を返す）
+fn object_names<'a>(
+    tokens: &'a [Token<'a>],
+    mut i: usize,
+    target: NamingTarget,
+    decls: &mut Vec<Declaration<'a>>,
+) -> usize {
+    while let Some(t) = tokens.get(i) {
+        match t.kind {
+            TokenKind::Identifier => decls.push(Declaration {
+                name: t.text,
+                span: t.span,
+                targets: vec![target],
+            }),
+            TokenKind::Comma => {}
+            TokenKind::Colon => return i + 1,
+            _ => return i,
+        }
+        i += 1;
+    }
+    i
+}
+
+/// `generic (...)` / `port (...)` の中の宣言を読む（閉じ括弧の次の位置を返す）
+fn interface_list<'a>(
+    tokens: &'a [Token<'a>],
+    mut i: usize,
+    generic: bool,
+    decls: &mut Vec<Declaration<'a>>,
+) -> usize {
+    let mut depth = 0;
+    let mut item_start = false;
+    while let Some(t) = tokens.get(i) {
+        match t.kind {
+            TokenKind::LeftParen => {
+                depth += 1;
+                item_start = depth == 1;
+            }
+            TokenKind::RightParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            TokenKind::Semicolon if depth == 1 => item_start = true,
+            TokenKind::Identifier if item_start && depth == 1 => {
+                let first = decls.len();
+                let after = object_names(tokens, i, NamingTarget::Generics, decls);
+                let target = if generic {
+                    NamingTarget::Generics
+                } else {
+                    match tokens.get(after).map(|t| t.kind.clone()) {
+                        Some(TokenKind::Out | TokenKind::Buffer) => NamingTarget::Outputs,
+                        Some(TokenKind::Inout) => NamingTarget::Inouts,
+                        _ => NamingTarget::Inputs,
+                    }
+                };
+                for decl in &mut decls[first..] {
+                    decl.targets = vec![target];
+                }
+                item_start = false;
+                i = after;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// `if x = '0'` のように '0' で判定している名前（小文字）
+fn active_low_names(tokens: &[Token]) -> Vec<String> {
+    tokens
+        .windows(4)
+        .filter(|w| {
+            matches!(
+                w[0].kind,
+                TokenKind::If
+                    | TokenKind::Elsif
+                    | TokenKind::And
+                    | TokenKind::Or
+                    | TokenKind::Until
+            ) && w[1].kind == TokenKind::Identifier
+                && w[2].kind == TokenKind::Eq
+                && w[3].kind == TokenKind::CharacterLiteral
+                && w[3].text == "'0'"
+        })
+        .map(|w| w[1].text.to_lowercase())
+        .collect()
+}
+
+/// 命名規則に従わない宣言を報告する
+///
+/// 大文字小文字は規則の接頭辞・接尾辞を除いた部分で判定します。対象ごとの規則で
+/// `case` を指定した宣言には、`identifiers` の `case` は適用しません。
+pub fn check_naming(source: &str, rules: &[NamingRule]) -> Vec<Diagnostic> {
+    let _timed = timed!("check_naming");
+    if rules.is_empty() {
+        return Vec::new();
+    }
+    let tokens: Vec<Token> = Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .filter_map(|r| r.ok())
+        .collect();
+    let mut decls = declarations(&tokens);
+    let active_low = active_low_names(&tokens);
+    for decl in &mut decls {
+        let is_object = decl.targets.iter().any(|t| {
+            matches!(
+                t,
+                NamingTarget::Inputs
+                    | NamingTarget::Outputs
+                    | NamingTarget::Inouts
+                    | NamingTarget::Signals
+            )
+        });
+        if is_object && active_low.contains(&decl.name.to_lowercase()) {
+            decl.targets.push(NamingTarget::ActiveLow);
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for decl in &decls {
+        let applies = |rule: &&NamingRule| {
+            (rule.target == NamingTarget::Identifiers || decl.targets.contains(&rule.target))
+                && !rule.allow.iter().any(|a| a.eq_ignore_ascii_case(decl.name))
+        };
+        let specific_case = rules
+            .iter()
+            .filter(applies)
+            .any(|r| r.target != NamingTarget::Identifiers && r.case.is_some());
+        for rule in rules.iter().filter(applies) {
+            let kind = describe(match rule.target {
+                NamingTarget::Identifiers => decl.targets[0],
+                target => target,
+            });
+            let mut core = decl.name;
+            let mut report = |message: String| {
+                diagnostics.push(Diagnostic::new(rule.severity, message, decl.span));
+            };
+            if let Some(prefix) = &rule.prefix {
+                match strip_prefix(core, prefix) {
+                    Some(rest) => core = rest,
+                    None => report(format!(
+                        "{} '{}' should start with '{}'",
+                        kind, decl.name, prefix
+                    )),
+                }
+            }
+            if let Some(suffix) = &rule.suffix {
+                match strip_suffix(core, suffix) {
+                    Some(rest) => core = rest,
+                    None => report(format!(
+                        "{} '{}' should end with '{}'",
+                        kind, decl.name, suffix
+                    )),
+                }
+            }
+            if let Some(case) = rule.case
+                && !(rule.target == NamingTarget::Identifiers && specific_case)
+                && !case.matches(core)
+            {
+                report(format!(
+                    "{} '{}' should be {}",
+                    kind,
+                    decl.name,
+                    case.as_str()
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// 大文字小文字を区別せずに接頭辞を除く
+fn strip_prefix<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = name.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &name[prefix.len()..])
+}
+
+/// 大文字小文字を区別せずに接尾辞を除く
+fn strip_suffix<'a>(name: &'a str, suffix: &str) -> Option<&'a str> {
+    let at = name.len().checked_sub(suffix.len())?;
+    let tail = name.get(at..)?;
+    tail.eq_ignore_ascii_case(suffix).then(|| &name[..at])
+}
+
+// --- ポートの並び順 ---
+
+/// 方針に従ったポートの並び（分類の順、分類の中では接頭辞の最初の出現順、それ以外は元の順）
+pub fn expected_port_order(entity: &EntityDef, policy: &PortOrderPolicy) -> Vec<String> {
+    let prefix = |port: &PortDef| {
+        let name = port.name.to_lowercase();
+        match name.rfind('_') {
+            Some(i) if i > 0 && policy.group_by_prefix => name[..i].to_string(),
+            _ => name,
+        }
+    };
+    let mut keyed: Vec<(usize, usize, usize, &PortDef)> = Vec::new();
+    for (index, port) in entity.ports.iter().enumerate() {
+        let class = PortClass::of(port);
+        // 同じ分類で同じ接頭辞のポートが最初に出てくる位置
+        let group = if policy.group_by_prefix {
+            entity
+                .ports
+                .iter()
+                .position(|p| PortClass::of(p) == class && prefix(p) == prefix(port))
+                .unwrap_or(index)
+        } else {
+            index
+        };
+        keyed.push((policy.rank(class), group, index, port));
+    }
+    keyed.sort_by_key(|(rank, group, index, _)| (*rank, *group, *index));
+    keyed.into_iter().map(|(.., p)| p.name.clone()).collect()
+}
+
+/// ポートの並び順が方針に従わないエンティティを報告する
+///
+/// エンティティごとに、最初に順番が違うところを1つ報告します。
+pub fn check_port_order(source: &str, policy: &PortOrderPolicy) -> Vec<Diagnostic> {
+    let result = analyze_vhdl(source).unwrap_or_default();
+    port_order_diagnostics(&result.entities, policy)
+}
+
+fn port_order_diagnostics(entities: &[EntityDef], policy: &PortOrderPolicy) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for entity in entities {
+        let expected = expected_port_order(entity, policy);
+        let Some(i) = (0..expected.len()).find(|&i| expected[i] != entity.ports[i].name) else {
+            continue;
+        };
+        let moved = entity
+            .ports
+            .iter()
+            .find(|p| p.name == expected[i])
+            .expect("並べ替えたポートはエンティティにある");
+        let displaced = &entity.ports[i];
+        diagnostics.push(
+            Diagnostic::new(
+                policy.severity,
+                format!(
+                    "port '{}' ({}) of entity '{}' should come before '{}' ({})",
+                    moved.name,
+                    PortClass::of(moved).describe(),
+                    entity.name,
+                    displaced.name,
+                    PortClass::of(displaced).describe()
+                ),
+                moved.span,
+            )
+            .with_related(
+                displaced.span,
+                format!("'{}' is declared here", displaced.name),
+            ),
+        );
+    }
+    diagnostics
+}
+
+/// ポートの並び順を方針に合わせて書き換えたソース
+///
+/// 並べ替えは [`Rewriter::reorder_ports`] で行うので、ほかの記述はそのまま残ります。
+pub fn fix_port_order(source: &str, policy: &PortOrderPolicy) -> Result<String, RewriteError> {
+    let result = analyze_vhdl(source).unwrap_or_default();
+    let mut rewriter = Rewriter::new(source);
+    for entity in &result.entities {
+        let expected = expected_port_order(entity, policy);
+        if expected
+            .iter()
+            .zip(&entity.ports)
+            .any(|(e, p)| *e != p.name)
+        {
+            let order: Vec<&str> = expected.iter().map(String::as_str).collect();
+            rewriter.reorder_ports(&entity.name, &order)?;
+        }
+    }
+    rewriter.finish()
+}
+
+// --- 実行されない論理 ---
+
+/// 実行されない論理を報告する（重大度は info）
+///
+/// 式の評価にはインタプリタを使い、リテラルと定数だけの式を静的に評価します。
+///
+/// - 常に真・常に偽の if / elsif の条件
+/// - 到達しない case 分岐（定数の選択式、前の分岐と同じ選択肢）
+/// - 同じ文の並びの中で、途中に wait を挟まずに無条件で上書きされる信号代入
+pub fn check_dead_logic(source: &str) -> Vec<Diagnostic> {
+    let _timed = timed!("check_dead_logic");
+    let tokens: Vec<Token> = Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .filter_map(|r| r.ok())
+        .collect();
+    let mut scanner = DeadLogic {
+        tokens: &tokens,
+        pos: 0,
+        constants: HashMap::new(),
+        diagnostics: Vec::new(),
+        depth: 0,
+    };
+    while scanner.pos < tokens.len() {
+        let after_end = scanner.pos > 0 && tokens[scanner.pos - 1].kind == TokenKind::End;
+        match tokens[scanner.pos].kind {
+            TokenKind::Process if !after_end => scanner.process(),
+            TokenKind::Constant => scanner.constant(),
+            _ => scanner.pos += 1,
+        }
+    }
+    scanner.diagnostics
+}
+
+/// 信号代入（小文字の名前, 位置）
+type Assignment = (String, Span);
+
+struct DeadLogic<'a> {
+    tokens: &'a [Token<'a>],
+    pos: usize,
+    /// これまでに宣言された定数（小文字の名前）
+    constants: HashMap<String, Constant>,
+    diagnostics: Vec<Diagnostic>,
+    /// 順次文の入れ子の深さ
+    depth: usize,
+}
+
+/// 順次文の入れ子の上限（これより深い入力は以降を調べない）
+const MAX_NESTING: usize = 256;
+
+impl<'a> DeadLogic<'a> {
+    fn kind(&self, offset: usize) -> Option<&'a TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| &t.kind)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|t| t.text.eq_ignore_ascii_case(word))
+    }
+
+    /// 現在位置から `stop` を満たすトークン（括弧の外）の直前までを返し、そのトークンへ進む
+    fn until(&mut self, stop: impl Fn(&Token) -> bool) -> &'a [Token<'a>] {
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(t) = self.tokens.get(self.pos) {
+            match t.kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => depth = depth.saturating_sub(1),
+                _ if depth == 0 && (stop(t) || t.kind == TokenKind::Semicolon) => break,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        &self.tokens[start..self.pos]
+    }
+
+    /// `end` の後の `;` まで
+    fn finish_end(&mut self) {
+        while self.kind(0).is_some_and(|k| *k != TokenKind::Semicolon) {
+            self.pos += 1;
+        }
+        self.pos += 1;
+    }
+
+    /// `constant name : type := expr;`
+    fn constant(&mut self) {
+        self.pos += 1;
+        let name = self.tokens.get(self.pos).map(|t| t.text.to_lowercase());
+        self.until(|t| t.kind == TokenKind::Assignment);
+        if self.kind(0) == Some(&TokenKind::Assignment) {
+            self.pos += 1;
+            let expr = self.until(|_| false);
+            if let (Some(name), Some([value])) =
+                (name, eval_constants(expr, &self.constants).as_deref())
+            {
+                self.constants.insert(name, value.clone());
+            }
+        }
+        self.pos += 1;
+    }
+
+    /// `process` から `end process;` まで
+    fn process(&mut self) {
+        while self.kind(0).is_some_and(|k| *k != TokenKind::Begin) {
+            if self.is_word("constant") {
+                self.constant();
+            } else {
+                self.pos += 1;
+            }
+        }
+        self.pos += 1;
+        self.statements();
+        self.finish_end();
+    }
+
+    fn span_of(tokens: &[Token]) -> Option<Span> {
+        Some(Span::new(
+            tokens.first()?.span.start,
+            tokens.last()?.span.end,
+        ))
+    }
+
+    /// 定数の条件なら真偽を返す
+    fn truth(&self, tokens: &[Token]) -> Option<bool> {
+        match eval_constants(tokens, &self.constants)?.as_slice() {
+            [value] => value.truth(),
+            _ => None,
+        }
+    }
+
+    /// 順次文の並び（`end`・`elsif`・`else`・`when` まで）
+    ///
+    /// 途中に wait があれば true を返します。
+    fn statements(&mut self) -> bool {
+        if self.depth >= MAX_NESTING {
+            self.pos = self.tokens.len();
+            return false;
+        }
+        self.depth += 1;
+        let waits = self.statement_list();
+        self.depth -= 1;
+        waits
+    }
+
+    fn statement_list(&mut self) -> bool {
+        let mut pending: Vec<Assignment> = Vec::new();
+        let mut waits = false;
+        while let Some(kind) = self.kind(0) {
+            let start = self.pos;
+            let nested_wait = match *kind {
+                TokenKind::End | TokenKind::Elsif | TokenKind::Else | TokenKind::When => break,
+                // ラベル
+                TokenKind::Identifier if self.kind(1) == Some(&TokenKind::Colon) => {
+                    self.pos += 2;
+                    continue;
+                }
+                TokenKind::If => self.if_statement(),
+                TokenKind::Case => self.case_statement(),
+                _ if self.is_word("wait") => {
+                    self.until(|_| false);
+                    self.pos += 1;
+                    true
+                }
+                _ if self.is_word("for") || self.is_word("while") || self.is_word("loop") => {
+                    self.until(|t| t.text.eq_ignore_ascii_case("loop"));
+                    self.pos += 1;
+                    let waits = self.statements();
+                    self.finish_end();
+                    waits
+                }
+                TokenKind::Identifier if self.kind(1) == Some(&TokenKind::Lte) => {
+                    let target = &self.tokens[start];
+                    let name = target.text.to_lowercase();
+                    self.until(|_| false);
+                    self.pos += 1;
+                    if let Some(index) = pending.iter().position(|(n, _)| *n == name) {
+                        let (_, first) = pending.remove(index);
+                        self.diagnostics.push(
+                            Diagnostic::info(
+                                format!(
+                                    "assignment to '{}' is always overwritten before it takes effect",
+                                    target.text
+                                ),
+                                first,
+                            )
+                            .with_related(target.span, "overwritten here"),
+                        );
+                    }
+                    pending.push((name, target.span));
+                    false
+                }
+                _ => {
+                    self.until(|_| false);
+                    self.pos += 1;
+                    false
+                }
+            };
+            if nested_wait {
+                pending.clear();
+                waits = true;
+            }
+        }
+        waits
+    }
+
+    /// `if` から `end if;` まで
+    fn if_statement(&mut self) -> bool {
+        let mut waits = false;
+        // 前の条件が常に真なら以降の分岐には到達しない
+        let mut decided = false;
+        loop {
+            // if / elsif
+            self.pos += 1;
+            let condition = self.until(|t| t.kind == TokenKind::Then);
+            self.pos += 1;
+            if !decided
+                && let Some(truth) = self.truth(condition)
+                && let Some(span) = Self::span_of(condition)
+            {
+                let message = if truth {
+                    "condition is always true"
+                } else {
+                    "condition is always false; the branch is never taken"
+                };
+                self.diagnostics.push(Diagnostic::info(message, span));
+                decided = truth;
+            }
+            waits |= self.statements();
+            match self.kind(0).cloned() {
+                Some(TokenKind::Elsif) => continue,
+                Some(TokenKind::Else) => {
+                    self.pos += 1;
+                    waits |= self.statements();
+                }
+                _ => {}
+            }
+            break;
+        }
+        self.finish_end();
+        waits
+    }
+
+    /// `case` から `end case;` まで
+    fn case_statement(&mut self) -> bool {
+        self.pos += 1;
+        let selector_tokens = self.until(|t| t.kind == TokenKind::Is);
+        let selector = match eval_constants(selector_tokens, &self.constants).as_deref() {
+            Some([value]) => Some(value.clone()),
+            _ => None,
+        };
+        self.pos += 1;
+        let mut waits = false;
+        let mut seen: Vec<(Constant, Span)> = Vec::new();
+        // 定数の選択式に一致する分岐が見つかったか
+        let mut matched = false;
+        while self.kind(0) == Some(&TokenKind::When) {
+            let when = self.tokens[self.pos].span;
+            self.pos += 1;
+            let choice_tokens = self.until(|t| t.kind == TokenKind::Association);
+            self.pos += 1;
+            let others = choice_tokens.iter().any(|t| t.kind == TokenKind::Others);
+            let choices = if others {
+                None
+            } else {
+                eval_constants(choice_tokens, &self.constants)
+            };
+            let span = Self::span_of(choice_tokens).unwrap_or(when);
+            if let Some(choices) = &choices {
+                let earlier: Vec<Span> = choices
+                    .iter()
+                    .filter_map(|c| seen.iter().find(|(s, _)| s == c).map(|(_, span)| *span))
+                    .collect();
+                if !choices.is_empty() && earlier.len() == choices.len() {
+                    self.diagnostics.push(
+                        Diagnostic::info(
+                            "case branch is unreachable: its choices are covered by earlier branches",
+                            span,
+                        )
+                        .with_related(earlier[0], "covered here"),
+                    );
+                }
+                seen.extend(choices.iter().map(|c| (c.clone(), span)));
+            }
+            if let Some(value) = &selector {
+                let hit = others || choices.as_ref().is_some_and(|c| c.contains(value));
+                if matched || (!hit && choices.is_some()) {
+                    self.diagnostics.push(
+                        Diagnostic::info("case branch is never taken", span).with_related(
+                            Self::span_of(selector_tokens).unwrap_or(when),
+                            format!("the selector is always {}", value),
+                        ),
+                    );
+                }
+                matched |= hit;
+            }
+            waits |= self.statements();
+        }
+        self.finish_end();
+        waits
+    }
+}
@@ -0,0 +1,372 @@
+//! VHDLリテラルの数値化
+//!
+//! 整数リテラル（`1_000` `1e3` `16#FF#` `2#1010#E2`）、実数リテラル（`1.5` `2.0e-3` `16#F.8#`）、
+//! ビット列リテラル（`X"FF"` `8B"101"`）、物理型の時間リテラル（`10 ns` `1.5 us`）を
+//! Rust の数値に変換します。
+//! 字句解析では数値と単位は別のトークンなので、[`physical_literals`] で組にします。
+//! いずれも書式が正しくないか値が範囲外なら `None` を返します。
+
+use std::time::Duration;
+
+use crate::ident;
+use crate::lexer::{Span, Token, TokenKind};
+use crate::logic::{LogicVector, MAX_VECTOR_WIDTH, StdLogic};
+
+/// 整数リテラルを数値にする
+///
+/// ```
+/// use vig::literal::parse_integer;
+///
+/// assert_eq!(parse_integer("1_000"), Some(1000));
+/// assert_eq!(parse_integer("16#FF#"), Some(255));
+/// assert_eq!(parse_integer("2#1#E3"), Some(8));
+/// ```
+pub fn parse_integer(text: &str) -> Option<i128> {
+    let text = text.trim();
+    let (mantissa, exponent) = split_exponent(text)?;
+    let value = match mantissa.split_once('#') {
+        // 基数付きリテラル: base#digits#
+        Some((base, rest)) => {
+            let base: u32 = digits(base)?.parse().ok()?;
+            let digits = digits(rest.strip_suffix('#')?)?;
+            if !(2..=16).contains(&base) {
+                return None;
+            }
+            let value = u128::from_str_radix(&digits, base).ok()?;
+            let scale = (base as i128).checked_pow(exponent)?;
+            i128::try_from(value).ok()?.checked_mul(scale)?
+        }
+        None => {
+            let value: i128 = digits(mantissa)?.parse().ok()?;
+            value.checked_mul(10i128.checked_pow(exponent)?)?
+        }
+    };
+    Some(value)
+}
+
+/// 実数リテラルを数値にする
+///
+/// ```
+/// use vig::literal::parse_real;
+///
+/// assert_eq!(parse_real("1.5"), Some(1.5));
+/// assert_eq!(parse_real("2.5e-3"), Some(0.0025));
+/// assert_eq!(parse_real("16#F.8#E1"), Some(248.0));
+/// assert_eq!(parse_real("1.2.3"), None);
+/// ```
+pub fn parse_real(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (mantissa, exponent) = split_signed_exponent(text)?;
+    match mantissa.split_once('#') {
+        // 基数付きリテラル: base#digits.digits#
+        Some((base, rest)) => {
+            let base: u32 = digits(base)?.parse().ok()?;
+            if !(2..=16).contains(&base) {
+                return None;
+            }
+            let (whole, fraction) = rest.strip_suffix('#')?.split_once('.')?;
+            let mut value = 0.0;
+            for c in digits(whole)?.chars() {
+                value = value * base as f64 + c.to_digit(base)? as f64;
+            }
+            let mut place = 1.0;
+            for c in digits(fraction)?.chars() {
+                place /= base as f64;
+                value += c.to_digit(base)? as f64 * place;
+            }
+            Some(value * (base as f64).powi(exponent))
+        }
+        None => {
+            let (whole, fraction) = mantissa.split_once('.')?;
+            let number = format!("{}.{}", digits(whole)?, digits(fraction)?);
+            if !number.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                return None;
+            }
+            format!("{}e{}", number, exponent).parse().ok()
+        }
+    }
+}
+
+/// 数値リテラル（整数・実数）の値
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Numeric {
+    Integer(i128),
+    Real(f64),
+}
+
+/// 数値リテラルを値にする（小数点があれば実数、なければ整数）
+///
+/// ```
+/// use vig::literal::{Numeric, parse_numeric};
+///
+/// assert_eq!(parse_numeric("1e3"), Some(Numeric::Integer(1000)));
+/// assert_eq!(parse_numeric("1.0e3"), Some(Numeric::Real(1000.0)));
+/// ```
+pub fn parse_numeric(text: &str) -> Option<Numeric> {
+    if text.contains('.') {
+        parse_real(text).map(Numeric::Real)
+    } else {
+        parse_integer(text).map(Numeric::Integer)
+    }
+}
+
+/// 指数部を分ける（実数リテラルの指数は負にできる）
+fn split_signed_exponent(text: &str) -> Option<(&str, i32)> {
+    let Some(i) = exponent_at(text)? else {
+        return Some((text, 0));
+    };
+    let exponent = &text[i + 1..];
+    let (negative, exponent) = match exponent.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, exponent.strip_prefix('+').unwrap_or(exponent)),
+    };
+    let exponent: i32 = digits(exponent)?.parse().ok()?;
+    Some((&text[..i], if negative { -exponent } else { exponent }))
+}
+
+/// 指数の `e` の位置（基数付きなら2つ目の `#` より後ろだけを探す）
+fn exponent_at(text: &str) -> Option<Option<usize>> {
+    let search_from = match text.find('#') {
+        Some(first) => first + 1 + text[first + 1..].find('#')? + 1,
+        None => 0,
+    };
+    Some(
+        text[search_from..]
+            .find(['e', 'E'])
+            .map(|i| search_from + i),
+    )
+}
+
+/// 指数部を分ける（整数リテラルの指数は負にできない）
+fn split_exponent(text: &str) -> Option<(&str, u32)> {
+    match exponent_at(text)? {
+        Some(i) => {
+            let exponent = text[i + 1..].trim_start_matches('+');
+            Some((&text[..i], digits(exponent)?.parse().ok()?))
+        }
+        None => Some((text, 0)),
+    }
+}
+
+/// `_` 区切りを取り除いた数字列（先頭・末尾・連続の `_` は不正）
+fn digits(text: &str) -> Option<String> {
+    if text.is_empty() || text.starts_with('_') || text.ends_with('_') || text.contains("__") {
+        return None;
+    }
+    let digits: String = text.chars().filter(|&c| c != '_').collect();
+    digits
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric())
+        .then_some(digits)
+}
+
+/// 文字列リテラルの中身（前後の `"` を外し、`""` を `"` に戻す）
+///
+/// ```
+/// use vig::literal::parse_string;
+///
+/// assert_eq!(parse_string(r#""he said ""hi""""#).as_deref(), Some(r#"he said "hi""#));
+/// assert_eq!(parse_string(r#""""#).as_deref(), Some(""));
+/// assert_eq!(parse_string("abc"), None);
+/// ```
+pub fn parse_string(text: &str) -> Option<String> {
+    let body = text.strip_prefix('"')?.strip_suffix('"')?;
+    Some(body.replace("\"\"", "\""))
+}
+
+/// ビット列リテラルを符号なしの数値にする
+///
+/// `B` `O` `X` に加えて VHDL-2008 の `D`・`U`/`S` 付きの基数指定と幅指定（`8X"F"`）に対応します。
+/// `X` `Z` などのメタ値を含む場合と、幅指定が [`MAX_VECTOR_WIDTH`] を超える場合は `None` です。
+///
+/// ```
+/// use vig::literal::parse_bit_string;
+///
+/// assert_eq!(parse_bit_string("X\"FF\""), Some(255));
+/// assert_eq!(parse_bit_string("b\"1010_0001\""), Some(0xA1));
+/// assert_eq!(parse_bit_string("12D\"100\""), Some(100));
+/// ```
+pub fn parse_bit_string(text: &str) -> Option<u128> {
+    let bits = bit_string_vector(text)?;
+    bits.to_unsigned()
+}
+
+/// ビット列リテラルをビット列に展開する（幅指定があればその幅にする）
+pub fn bit_string_vector(text: &str) -> Option<LogicVector> {
+    let text = text.trim();
+    let quote = text.find('"')?;
+    let body = text[quote + 1..].strip_suffix('"')?;
+    let prefix = &text[..quote];
+    let base_at = prefix.find(|c: char| !c.is_ascii_digit())?;
+    let (width, base) = prefix.split_at(base_at);
+    // S は幅を広げるとき符号拡張する（U は指定なしと同じ）
+    let signed = base.len() == 2 && base[..1].eq_ignore_ascii_case("s");
+    let base = match base.len() {
+        2 if base[..1].eq_ignore_ascii_case("u") || signed => &base[1..],
+        _ => base,
+    };
+    let width: Option<usize> = if width.is_empty() {
+        None
+    } else {
+        Some(width.parse().ok().filter(|&w| w <= MAX_VECTOR_WIDTH)?)
+    };
+
+    let bits = if base.eq_ignore_ascii_case("d") {
+        let value: u128 = digits(body)?.parse().ok()?;
+        let minimal = (128 - value.leading_zeros() as usize).max(1);
+        LogicVector::from_unsigned(value, width.unwrap_or(minimal))
+    } else {
+        LogicVector::from_bit_string(base, body)?
+    };
+    match width {
+        None => Some(bits),
+        Some(width) if width >= bits.width() => Some(bits.resize(width, signed)),
+        Some(width) => {
+            // 切り捨てる上位ビットは '0'（符号付きなら残す最上位ビットと同じ値）でなければならない
+            let (dropped, kept) = bits.bits().split_at(bits.width() - width);
+            let fill = match kept.first() {
+                Some(&sign) if signed => sign,
+                _ => StdLogic::Zero,
+            };
+            dropped
+                .iter()
+                .all(|&b| b == fill)
+                .then(|| LogicVector::new(kept.to_vec()))
+        }
+    }
+}
+
+/// 時間の単位（フェムト秒換算）
+const TIME_UNITS: [(&str, u128); 8] = [
+    ("fs", 1),
+    ("ps", 1_000),
+    ("ns", 1_000_000),
+    ("us", 1_000_000_000),
+    ("ms", 1_000_000_000_000),
+    ("sec", 1_000_000_000_000_000),
+    ("min", 60_000_000_000_000_000),
+    ("hr", 3_600_000_000_000_000_000),
+];
+
+/// 時間リテラルをフェムト秒にする（VHDLの time の最小単位）
+///
+/// 数値と単位の間の空白は省略できます。小数は 10 進で書いたものだけを受け付けます。
+pub fn parse_time_fs(text: &str) -> Option<u128> {
+    let text = text.trim();
+    let split = text.find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')?;
+    let (number, unit) = text.split_at(split);
+    let unit = unit.trim().to_ascii_lowercase();
+    let scale = TIME_UNITS.iter().find(|(name, _)| *name == unit)?.1;
+    let number = number.trim();
+    match number.split_once('.') {
+        None => u128::try_from(parse_integer(number)?)
+            .ok()?
+            .checked_mul(scale),
+        Some((whole, fraction)) => {
+            // 小数部は単位の分解能で割り切れる桁までを使う
+            let whole: u128 = digits(whole)?.parse().ok()?;
+            let fraction = digits(fraction)?;
+            let mut value = whole.checked_mul(scale)?;
+            let mut place = scale;
+            for c in fraction.chars() {
+                let digit = c.to_digit(10)? as u128;
+                if place % 10 != 0 {
+                    // フェムト秒未満の端数は表せない
+                    if digit != 0 {
+                        return None;
+                    }
+                    continue;
+                }
+                place /= 10;
+                value = value.checked_add(digit * place)?;
+            }
+            Some(value)
+        }
+    }
+}
+
+/// 時間リテラルを [`Duration`] にする
+///
+/// `Duration` の分解能はナノ秒のため、`ps`・`fs` で ns 未満の端数が出る値は `None` です。
+///
+/// ```
+/// use std::time::Duration;
+/// use vig::literal::parse_time;
+///
+/// assert_eq!(parse_time("10 ns"), Some(Duration::from_nanos(10)));
+/// assert_eq!(parse_time("1.5us"), Some(Duration::from_nanos(1500)));
+/// assert_eq!(parse_time("100 ps"), None);
+/// ```
+pub fn parse_time(text: &str) -> Option<Duration> {
+    let fs = parse_time_fs(text)?;
+    if fs % 1_000_000 != 0 {
+        return None;
+    }
+    let nanos = u64::try_from(fs / 1_000_000).ok()?;
+    Some(Duration::from_nanos(nanos))
+}
+
+/// 物理型リテラル（`10 ns` `100 MHz` のような数値リテラルと単位名の組）
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhysicalLiteral {
+    /// 数値の書いたままの表記
+    pub number: String,
+    pub value: Numeric,
+    /// 単位名の書いたままの表記
+    pub unit: String,
+    /// 数値の先頭から単位名の末尾まで
+    pub span: Span,
+}
+
+impl PhysicalLiteral {
+    /// `time` の単位（`fs` 〜 `hr`）ならフェムト秒
+    pub fn time_fs(&self) -> Option<u128> {
+        parse_time_fs(&self.to_string())
+    }
+}
+
+impl std::fmt::Display for PhysicalLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.number, self.unit)
+    }
+}
+
+/// トークン列の `index` 番目からの物理型リテラル
+///
+/// 数値リテラルの直後が予約語でない識別子なら単位名とみなします（コメントは読み飛ばします）。
+pub fn physical_literal(tokens: &[Token], index: usize) -> Option<PhysicalLiteral> {
+    let number = tokens.get(index)?;
+    let value = number.numeric_value()?;
+    let unit = tokens[index + 1..]
+        .iter()
+        .find(|t| t.kind != TokenKind::Comment)?;
+    if unit.kind != TokenKind::Identifier || ident::is_reserved(unit.text) {
+        return None;
+    }
+    Some(PhysicalLiteral {
+        number: number.text.to_string(),
+        value,
+        unit: unit.text.to_string(),
+        span: Span::new(number.span.start, unit.span.end),
+    })
+}
+
+/// トークン列の物理型リテラルをすべて探す
+///
+/// ```
+/// use vig::lexer::Lexer;
+/// use vig::literal::physical_literals;
+///
+/// let tokens: Vec<_> = Lexer::new("wait for 10 ns; -- 100 MHz")
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// let found = physical_literals(&tokens);
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].to_string(), "10 ns");
+/// assert_eq!(found[0].time_fs(), Some(10_000_000));
+/// ```
+pub fn physical_literals(tokens: &[Token]) -> Vec<PhysicalLiteral> {
+    (0..tokens.len())
+        .filter_map(|i| physical_literal(tokens, i))
+        .collect()
+}
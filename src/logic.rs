@@ -0,0 +1,543 @@
+//! std_logic の9値論理
+//!
+//! IEEE 1164 の `std_ulogic` 値と、その配列（`std_logic_vector`・`unsigned`・`signed`）の
+//! 論理演算・解決関数・numeric_std 相当の算術を提供します。
+//! インタプリタ・真理値表・ビット列リテラルの評価はすべてこのモジュールを使います。
+
+use std::fmt;
+
+/// std_ulogic の値（並びは VHDL の std_ulogic と同じ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StdLogic {
+    /// 'U' 未初期化
+    U,
+    /// 'X' 不定
+    X,
+    /// '0'
+    Zero,
+    /// '1'
+    One,
+    /// 'Z' ハイインピーダンス
+    Z,
+    /// 'W' 弱い不定
+    W,
+    /// 'L' 弱い0
+    L,
+    /// 'H' 弱い1
+    H,
+    /// '-' ドントケア
+    DontCare,
+}
+
+use StdLogic::{DontCare, H, L, One, U, W, X, Z, Zero};
+
+/// 解決関数の表（std_logic_1164 の resolution_table と同じ並び）
+const RESOLUTION: [[StdLogic; 9]; 9] = [
+    [U, U, U, U, U, U, U, U, U],
+    [U, X, X, X, X, X, X, X, X],
+    [U, X, Zero, X, Zero, Zero, Zero, Zero, X],
+    [U, X, X, One, One, One, One, One, X],
+    [U, X, Zero, One, Z, W, L, H, X],
+    [U, X, Zero, One, W, W, W, W, X],
+    [U, X, Zero, One, L, W, L, W, X],
+    [U, X, Zero, One, H, W, W, H, X],
+    [U, X, X, X, X, X, X, X, X],
+];
+
+impl StdLogic {
+    /// 9値すべて（'U' から '-' の順）
+    pub const ALL: [StdLogic; 9] = [U, X, Zero, One, Z, W, L, H, DontCare];
+
+    /// 1文字から（大文字・小文字を区別しない）
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'U' => Some(U),
+            'X' => Some(X),
+            '0' => Some(Zero),
+            '1' => Some(One),
+            'Z' => Some(Z),
+            'W' => Some(W),
+            'L' => Some(L),
+            'H' => Some(H),
+            '-' => Some(DontCare),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            U => 'U',
+            X => 'X',
+            Zero => '0',
+            One => '1',
+            Z => 'Z',
+            W => 'W',
+            L => 'L',
+            H => 'H',
+            DontCare => '-',
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&v| v == self).unwrap_or(0)
+    }
+
+    /// 'L'/'H' を '0'/'1' に、それ以外の非論理値を 'X' にする（to_X01）
+    pub fn to_x01(self) -> Self {
+        match self {
+            Zero | L => Zero,
+            One | H => One,
+            _ => X,
+        }
+    }
+
+    /// '0' か '1' か
+    pub fn is_01(self) -> bool {
+        matches!(self, Zero | One)
+    }
+
+    /// 2つのドライバの値を解決する
+    pub fn resolve(self, other: Self) -> Self {
+        RESOLUTION[self.index()][other.index()]
+    }
+
+    pub fn and(self, other: Self) -> Self {
+        if self.to_x01() == Zero || other.to_x01() == Zero {
+            Zero
+        } else if self == U || other == U {
+            U
+        } else if self.to_x01() == One && other.to_x01() == One {
+            One
+        } else {
+            X
+        }
+    }
+
+    pub fn or(self, other: Self) -> Self {
+        self.not().and(other.not()).not()
+    }
+
+    pub fn xor(self, other: Self) -> Self {
+        if self == U || other == U {
+            U
+        } else {
+            match (self.to_x01(), other.to_x01()) {
+                (X, _) | (_, X) => X,
+                (a, b) if a == b => Zero,
+                _ => One,
+            }
+        }
+    }
+
+    pub fn nand(self, other: Self) -> Self {
+        self.and(other).not()
+    }
+
+    pub fn nor(self, other: Self) -> Self {
+        self.or(other).not()
+    }
+
+    pub fn xnor(self, other: Self) -> Self {
+        self.xor(other).not()
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        match self {
+            U => U,
+            Zero | L => One,
+            One | H => Zero,
+            _ => X,
+        }
+    }
+
+    /// std_match の1ビット分（'-' はどの値とも一致、メタ値は不一致）
+    pub fn matches(self, other: Self) -> bool {
+        if self == DontCare || other == DontCare {
+            return true;
+        }
+        let (a, b) = (self.to_x01(), other.to_x01());
+        a != X && a == b
+    }
+}
+
+impl From<bool> for StdLogic {
+    fn from(value: bool) -> Self {
+        if value { One } else { Zero }
+    }
+}
+
+impl fmt::Display for StdLogic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl std::ops::Not for StdLogic {
+    type Output = StdLogic;
+
+    fn not(self) -> StdLogic {
+        StdLogic::not(self)
+    }
+}
+
+impl std::ops::BitAnd for StdLogic {
+    type Output = StdLogic;
+
+    fn bitand(self, rhs: StdLogic) -> StdLogic {
+        self.and(rhs)
+    }
+}
+
+impl std::ops::BitOr for StdLogic {
+    type Output = StdLogic;
+
+    fn bitor(self, rhs: StdLogic) -> StdLogic {
+        self.or(rhs)
+    }
+}
+
+impl std::ops::BitXor for StdLogic {
+    type Output = StdLogic;
+
+    fn bitxor(self, rhs: StdLogic) -> StdLogic {
+        self.xor(rhs)
+    }
+}
+
+/// 複数のドライバを解決する（ドライバがなければ 'Z'）
+pub fn resolve(drivers: &[StdLogic]) -> StdLogic {
+    drivers.iter().fold(Z, |acc, &d| acc.resolve(d))
+}
+
+/// リテラルやインタプリタで作るベクタの幅の上限
+///
+/// 幅はソースに書かれた数値から決まるため、上限を超えるものは確保せずに不正として扱います。
+pub const MAX_VECTOR_WIDTH: usize = 65_536;
+
+/// std_ulogic の配列（左端が先頭）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct LogicVector {
+    bits: Vec<StdLogic>,
+}
+
+impl LogicVector {
+    pub fn new(bits: Vec<StdLogic>) -> Self {
+        Self { bits }
+    }
+
+    /// 全ビットが同じ値のベクタ
+    pub fn filled(width: usize, value: StdLogic) -> Self {
+        Self::new(vec![value; width])
+    }
+
+    /// 全ビット '0' のベクタ
+    pub fn zeros(width: usize) -> Self {
+        Self::filled(width, Zero)
+    }
+
+    /// `"01XZ"` のような文字列から（`_` は無視）
+    pub fn parse(text: &str) -> Option<Self> {
+        text.chars()
+            .filter(|&c| c != '_')
+            .map(StdLogic::from_char)
+            .collect::<Option<Vec<_>>>()
+            .map(Self::new)
+    }
+
+    /// ビット列リテラル（`B"1010"` `O"17"` `X"FF"`）の中身を展開する
+    ///
+    /// `base` は `b`/`o`/`x`（大文字・小文字を区別しない）。
+    /// 桁が `X` `Z` などのメタ値ならその値で桁の全ビットを埋めます。
+    pub fn from_bit_string(base: &str, digits: &str) -> Option<Self> {
+        let bits_per_digit = match base.to_ascii_lowercase().as_str() {
+            "b" => 1,
+            "o" => 3,
+            "x" => 4,
+            _ => return None,
+        };
+        let mut bits = Vec::new();
+        for c in digits.chars().filter(|&c| c != '_') {
+            if let Some(value) = c.to_digit(1 << bits_per_digit) {
+                bits.extend(
+                    (0..bits_per_digit)
+                        .rev()
+                        .map(|i| StdLogic::from((value >> i) & 1 == 1)),
+                );
+            } else {
+                let value = StdLogic::from_char(c).filter(|v| !v.is_01())?;
+                bits.extend(std::iter::repeat_n(value, bits_per_digit as usize));
+            }
+        }
+        Some(Self::new(bits))
+    }
+
+    /// 符号なし整数から（幅に収まらない上位ビットは捨てる）
+    pub fn from_unsigned(value: u128, width: usize) -> Self {
+        Self::from_signed(value as i128, width)
+    }
+
+    /// 符号付き整数から2の補数で（幅に収まらない上位ビットは捨てる）
+    pub fn from_signed(value: i128, width: usize) -> Self {
+        Self::new(
+            (0..width)
+                .rev()
+                // 127 ビット目以降は符号ビットで埋める
+                .map(|i| {
+                    StdLogic::from(if i < 127 {
+                        (value >> i) & 1 == 1
+                    } else {
+                        value < 0
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn bits(&self) -> &[StdLogic] {
+        &self.bits
+    }
+
+    pub fn bits_mut(&mut self) -> &mut Vec<StdLogic> {
+        &mut self.bits
+    }
+
+    pub fn into_bits(self) -> Vec<StdLogic> {
+        self.bits
+    }
+
+    /// 全ビットが '0' か '1' か
+    pub fn is_01(&self) -> bool {
+        self.bits.iter().all(|b| b.is_01())
+    }
+
+    /// 符号なし整数として（メタ値を含むか127ビットを超えれば None）
+    pub fn to_unsigned(&self) -> Option<u128> {
+        if self.width() > 127 {
+            return None;
+        }
+        self.bits.iter().try_fold(0u128, |acc, b| match b.to_x01() {
+            Zero => Some(acc << 1),
+            One => Some((acc << 1) | 1),
+            _ => None,
+        })
+    }
+
+    /// 2の補数の符号付き整数として
+    pub fn to_signed(&self) -> Option<i128> {
+        let value = self.to_unsigned()? as i128;
+        match self.bits.first().map(|b| b.to_x01()) {
+            // 127 ビット幅では 1 << 127 が i128::MIN になるので wrapping で引く
+            Some(One) => Some(value.wrapping_sub(1i128 << self.width())),
+            _ => Some(value),
+        }
+    }
+
+    /// 数値として（`signed` なら2の補数）
+    pub fn to_integer(&self, signed: bool) -> Option<i128> {
+        if signed {
+            self.to_signed()
+        } else {
+            self.to_unsigned().map(|v| v as i128)
+        }
+    }
+
+    /// 幅を変える（numeric_std の resize と同じく、符号付きは符号ビットを残す）
+    pub fn resize(&self, width: usize, signed: bool) -> Self {
+        let len = self.width();
+        if !signed {
+            let mut bits = vec![Zero; width.saturating_sub(len)];
+            bits.extend(&self.bits[len.saturating_sub(width)..]);
+            return Self::new(bits);
+        }
+        let sign = self.bits.first().copied().unwrap_or(Zero);
+        if width >= len {
+            let mut bits = vec![sign; width - len];
+            bits.extend(&self.bits);
+            Self::new(bits)
+        } else if width == 0 {
+            Self::default()
+        } else {
+            let mut bits = vec![sign];
+            bits.extend(&self.bits[len - (width - 1)..]);
+            Self::new(bits)
+        }
+    }
+
+    /// 左シフト（空いたビットは '0'）
+    pub fn shift_left(&self, n: usize) -> Self {
+        let n = n.min(self.width());
+        let mut bits = self.bits[n..].to_vec();
+        bits.extend(std::iter::repeat_n(Zero, n));
+        Self::new(bits)
+    }
+
+    /// 右シフト（`signed` なら算術シフト）
+    pub fn shift_right(&self, n: usize, signed: bool) -> Self {
+        let n = n.min(self.width());
+        let fill = match self.bits.first() {
+            Some(&sign) if signed => sign,
+            _ => Zero,
+        };
+        let mut bits = vec![fill; n];
+        bits.extend(&self.bits[..self.width() - n]);
+        Self::new(bits)
+    }
+
+    /// 連結
+    pub fn concat(&self, other: &Self) -> Self {
+        let mut bits = self.bits.clone();
+        bits.extend(&other.bits);
+        Self::new(bits)
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(StdLogic, StdLogic) -> StdLogic) -> Option<Self> {
+        (self.width() == other.width()).then(|| {
+            Self::new(
+                self.bits
+                    .iter()
+                    .zip(&other.bits)
+                    .map(|(&a, &b)| f(a, b))
+                    .collect(),
+            )
+        })
+    }
+
+    /// ビットごとの and（幅が違えば None）
+    pub fn and(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::and)
+    }
+
+    pub fn or(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::or)
+    }
+
+    pub fn xor(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::xor)
+    }
+
+    pub fn nand(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::nand)
+    }
+
+    pub fn nor(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::nor)
+    }
+
+    pub fn xnor(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::xnor)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(&self) -> Self {
+        Self::new(self.bits.iter().map(|b| b.not()).collect())
+    }
+
+    /// ビットごとに2つのドライバを解決する（幅が違えば None）
+    pub fn resolve(&self, other: &Self) -> Option<Self> {
+        self.zip_with(other, StdLogic::resolve)
+    }
+
+    /// std_match（'-' はどの値とも一致）
+    pub fn std_match(&self, other: &Self) -> bool {
+        self.width() == other.width()
+            && self
+                .bits
+                .iter()
+                .zip(&other.bits)
+                .all(|(a, b)| a.matches(*b))
+    }
+
+    /// numeric_std の加算（結果は長い方の幅、メタ値を含めば全ビット 'X'）
+    pub fn add(&self, other: &Self, signed: bool) -> Self {
+        let width = self.width().max(other.width());
+        // 127 ビット幅では i128 を溢れるが、結果の幅のビットはラップしても正しい
+        self.arith(other, signed, width, |a, b| Some(a.wrapping_add(b)))
+    }
+
+    /// numeric_std の減算
+    pub fn sub(&self, other: &Self, signed: bool) -> Self {
+        let width = self.width().max(other.width());
+        self.arith(other, signed, width, |a, b| Some(a.wrapping_sub(b)))
+    }
+
+    /// numeric_std の乗算（結果は両方の幅の和）
+    pub fn mul(&self, other: &Self, signed: bool) -> Self {
+        let width = self.width() + other.width();
+        self.arith(other, signed, width, |a, b| Some(a.wrapping_mul(b)))
+    }
+
+    /// numeric_std の除算（0 除算なら None）
+    pub fn div(&self, other: &Self, signed: bool) -> Option<Self> {
+        self.checked_arith(other, signed, self.width(), i128::checked_div)
+    }
+
+    /// numeric_std の mod（0 除算なら None）
+    pub fn modulo(&self, other: &Self, signed: bool) -> Option<Self> {
+        self.checked_arith(other, signed, other.width(), |a, b| {
+            let m = a.checked_rem(b)?;
+            Some(if m != 0 && (m < 0) != (b < 0) {
+                m + b
+            } else {
+                m
+            })
+        })
+    }
+
+    /// numeric_std の rem（0 除算なら None）
+    pub fn rem(&self, other: &Self, signed: bool) -> Option<Self> {
+        self.checked_arith(other, signed, other.width(), i128::checked_rem)
+    }
+
+    fn arith(
+        &self,
+        other: &Self,
+        signed: bool,
+        width: usize,
+        f: impl Fn(i128, i128) -> Option<i128>,
+    ) -> Self {
+        match (self.to_integer(signed), other.to_integer(signed)) {
+            (Some(a), Some(b)) => f(a, b)
+                .map(|v| Self::from_signed(v, width))
+                .unwrap_or_else(|| Self::filled(width, X)),
+            _ => Self::filled(width, X),
+        }
+    }
+
+    fn checked_arith(
+        &self,
+        other: &Self,
+        signed: bool,
+        width: usize,
+        f: impl Fn(i128, i128) -> Option<i128>,
+    ) -> Option<Self> {
+        if other.to_integer(signed) == Some(0) {
+            return None;
+        }
+        Some(self.arith(other, signed, width, f))
+    }
+}
+
+impl From<Vec<StdLogic>> for LogicVector {
+    fn from(bits: Vec<StdLogic>) -> Self {
+        Self::new(bits)
+    }
+}
+
+impl fmt::Display for LogicVector {
+    /// `"01XZ"` の引用符なしの文字列
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.bits {
+            write!(f, "{}", b)?;
+        }
+        Ok(())
+    }
+}
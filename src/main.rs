@@ -1,29 +1,313 @@
 // VHDLのlexer・意味解析の使用例
 
-use vig::analyzer;
-use vig::generator;
+use vig::analyzer::{self, ArchitectureDef, EntityDef};
+use vig::assertion::{self, AssertionError, Property};
+use vig::banner::{self, Banner};
+use vig::constraints::{self, ConstraintConfig, ConstraintFormat};
+use vig::diagnostic::{self, Severity};
+use vig::diagram;
+use vig::encoding::{self, Encoding};
+use vig::fsm;
+use vig::generator::{self, AssertSeverity};
+use vig::impact;
+use vig::interface::InterfaceDef;
+use vig::interp::Interpreter;
+use vig::ipxact;
 use vig::lexer::{Lexer, TokenKind};
+use vig::library;
+use vig::lint::{self, LintConfig};
+use vig::metrics;
+use vig::mock::{self, MockStyle};
+use vig::pinlist::{self, PinListFormat};
+use vig::profile;
+use vig::project::{self, Language};
+use vig::regmap::RegisterMap;
+use vig::regress;
+use vig::report::{self, ReportFormat};
+use vig::signals;
+use vig::sim::{self, SimJob};
+use vig::simlog::{self, SimReport, SimStatus};
+use vig::source_map::SourceMap;
+use vig::style::TbStyle;
+use vig::sweep;
+use vig::testbench;
+use vig::todo;
+use vig::vectors::{self, TruthTable, VectorError};
+use vig::verilog;
+use vig::wavedrom;
 
 use std::env;
 use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::OnceLock;
+
+/// `--encoding` で指定したソースの文字コード（None なら推定）
+static ENCODING: OnceLock<Option<Encoding>> = OnceLock::new();
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let args = take_encoding(env::args().collect());
+
+    match args.get(1).map(String::as_str) {
+        Some("regmap") => {
+            run_regmap(&args[0], &args[2..]);
+            return;
+        }
+        Some("constraints") => {
+            run_constraints(&args[0], &args[2..]);
+            return;
+        }
+        Some("pins") => {
+            run_pins(&args[0], &args[2..]);
+            return;
+        }
+        Some("diagram") => {
+            run_diagram(&args[0], &args[2..]);
+            return;
+        }
+        Some("vectors") => {
+            run_vectors(&args[0], &args[2..]);
+            return;
+        }
+        Some("update-tb") => {
+            run_update_tb(&args[0], &args[2..]);
+            return;
+        }
+        Some("extract-dut") => {
+            run_extract_dut(&args[0], &args[2..]);
+            return;
+        }
+        Some("sim") => {
+            run_sim(&args[0], &args[2..]);
+            return;
+        }
+        Some("regress") => {
+            run_regress(&args[0], &args[2..]);
+            return;
+        }
+        Some("stats") => {
+            run_stats(&args[0], &args[2..]);
+            return;
+        }
+        Some("todo") => {
+            run_todo(&args[0], &args[2..]);
+            return;
+        }
+        Some("lint") => {
+            run_lint(&args[0], &args[2..]);
+            return;
+        }
+        Some("drivers") => {
+            run_drivers(&args[0], &args[2..]);
+            return;
+        }
+        Some("hierarchy") => {
+            run_hierarchy(&args[0], &args[2..]);
+            return;
+        }
+        Some("stub") => {
+            run_stub(&args[0], &args[2..]);
+            return;
+        }
+        Some("impact") => {
+            run_impact(&args[0], &args[2..]);
+            return;
+        }
+        _ => {}
+    }
 
     if args.len() < 2 {
-        eprintln!("使い方: {} [-d] <VHDLファイル>", args[0]);
+        eprintln!(
+            "使い方: {} [-d] [--coverage] [--fsm] [--sweep] [--style <スタイル>] [--arch <名前|all> [--whitebox <信号>[,<信号>...]]] [--mock <名前>[,<名前>...]] [--mock-style <tie|echo>] [--skeleton|--interface|--wavedrom] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            args[0]
+        );
         eprintln!("  -d: デバッグモード（構文解析と意味解析の結果を表示）");
+        eprintln!(
+            "  --encoding <utf-8|shift_jis|latin1>: 入力の文字コード（省略時は推定、各サブコマンドでも使用可）"
+        );
+        eprintln!("  --coverage: 出力のトグルカバレッジを計測してシミュレーション終了時に報告");
+        eprintln!("  --fsm: ステートマシンの全遷移をたどるシナリオと状態カバレッジを生成");
+        eprintln!(
+            "  --sweep: vig.toml の [[sweep.<エンティティ>]] のジェネリックの組ごとに DUT を実体化"
+        );
+        eprintln!(
+            "  --style: テストベンチのスタイル（standard, minimal, assert-heavy, file-driven, procedure-based）"
+        );
+        eprintln!(
+            "  --arch: 指定したアーキテクチャ（all なら全部）ごとに <エンティティ>_<アーキテクチャ>_tb を生成"
+        );
+        eprintln!(
+            "  --whitebox: --arch のアーキテクチャの内部信号のうち、値の変化を報告するもの（* ? を使用可）"
+        );
+        eprintln!(
+            "  --mock: テストベンチに続けて、指定した下位ブロックのモック（アーキテクチャ mock）を出力"
+        );
+        eprintln!(
+            "  --mock-style: モックの振る舞い（tie: 出力を既定値に固定、echo: 同じ型の入力を返す）"
+        );
+        eprintln!("  --skeleton: テストベンチの代わりにエンティティの雛形を出力");
+        eprintln!("  --interface: テストベンチの代わりにインターフェース記述(JSON)を出力");
+        eprintln!("  --wavedrom: テストシナリオのタイミング図(WaveDrom JSON)を出力");
+        eprintln!("  入力が .xml の場合は IP-XACT コンポーネント、.json の場合は");
+        eprintln!(
+            "  インターフェース記述、.v / .sv の場合は Verilog のモジュールとして読み込みます"
+        );
+        eprintln!();
+        eprintln!(
+            "       {} regmap [--tb|--markdown] <レジスタ記述(.toml|.yaml|.json)>",
+            args[0]
+        );
+        eprintln!("  レジスタ記述からレジスタファイル(VHDL)を生成します");
+        eprintln!(
+            "       {} constraints [--sdc] [--period <ns>] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            args[0]
+        );
+        eprintln!("  ポートから制約ファイル(XDC/SDC)の雛形を生成します");
+        eprintln!(
+            "       {} pins [--tsv] [--entity <名前>] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            args[0]
+        );
+        eprintln!("  トップエンティティのピンリスト(CSV/TSV)を出力します");
+        eprintln!(
+            "       {} diagram [--svg] [--entity <名前>] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            args[0]
+        );
+        eprintln!("  エンティティのブロック図(Graphviz DOT/SVG)を出力します");
+        eprintln!(
+            "       {} vectors [--entity <名前>] [--predict] [--coverage] <真理値表(.csv|.tsv)> <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            args[0]
+        );
+        eprintln!("  真理値表の各行を印加して出力を検査するテストベンチを生成します");
+        eprintln!(
+            "       {} update-tb [--entity <名前>] [--in-place] <テストベンチ> <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            args[0]
+        );
+        eprintln!("  既存のテストベンチをDUTのポート変更に合わせて更新します");
+        eprintln!(
+            "       {} extract-dut [--interface] <テストベンチ>",
+            args[0]
+        );
+        eprintln!("  テストベンチからDUTのエンティティ宣言を復元します");
+        eprintln!(
+            "       {} sim [--simulator <ghdl|nvc|questa>] [--top <名前>] [--std <93|02|08>] [--stop-time <時間>] [--work <ディレクトリ>] [--junit <ファイル>] [--tap <ファイル>] [--dry-run] <VHDLファイル>...",
+            args[0]
+        );
+        eprintln!("  ソースを依存順にコンパイルしてシミュレーションを実行します");
+        eprintln!(
+            "       {} regress [--simulator <ghdl|nvc|questa>] [--filter <パターン>] [--jobs <数>] [--tb-dir <ディレクトリ>] [--work <ディレクトリ>] [--std <93|02|08>] [--stop-time <時間>] [--junit <ファイル>] [--tap <ファイル>] <VHDLファイル>...",
+            args[0]
+        );
+        eprintln!("  エンティティごとのテストベンチを並列に実行し、結果を表にまとめます");
+        eprintln!(
+            "       {} stats [--metrics] [--json] <VHDLファイル>...",
+            args[0]
+        );
+        eprintln!("  行数とエンティティごとの規模（--metrics で複雑さの指標）を表示します");
+        eprintln!(
+            "       {} todo [--json] [--fail] <VHDLファイル>...",
+            args[0]
+        );
+        eprintln!(
+            "  TODO / FIXME / HACK コメントを一覧にします（--fail で見つかれば終了コード 1）"
+        );
+        eprintln!(
+            "       {} lint [--json] [--fix [--in-place]] <VHDLファイル>",
+            args[0]
+        );
+        eprintln!(
+            "  クロックドメインをまたぐ信号や命名規則・ポートの並び順（vig.toml の [lint]）の違反を報告します"
+        );
+        eprintln!("  --fix でポートを並び順の方針に合わせて並べ替えます");
+        eprintln!("       {} drivers [--json] <VHDLファイル>...", args[0]);
+        eprintln!(
+            "  ポートと信号ごとの駆動元と参照先を表示し、解決関数のない型の複数駆動を報告します"
+        );
+        eprintln!(
+            "       {} hierarchy <VHDLファイル|Verilogファイル>...",
+            args[0]
+        );
+        eprintln!("  VHDL と Verilog が混在する設計のインスタンス階層を表示します");
+        eprintln!("       {} stub <VHDLファイル|Verilogファイル>...", args[0]);
+        eprintln!(
+            "  エンティティのないコンポーネントのスタブ（出力を固定したエンティティ）を生成します"
+        );
+        eprintln!(
+            "       {} impact <エンティティ> <VHDLファイル|Verilogファイル>...",
+            args[0]
+        );
+        eprintln!(
+            "  エンティティを参照している箇所と、ポートの変更で合わなくなった接続を表示します"
+        );
         process::exit(1);
     }
 
     // フラグと引数を解析
     let mut debug_mode = false;
+    let mut output = Output::Testbench;
     let mut filename = None;
+    let mut config = generator::TbConfig::default();
+    let mut fsm_scenarios = false;
+    let mut sweep = false;
+    let mut mocks: Vec<String> = Vec::new();
+    let mut mock_style = MockStyle::default();
+    let mut arch = None;
 
-    for arg in &args[1..] {
-        if arg == "-d" {
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        if arg == "--mock" {
+            let Some(names) = rest.next() else {
+                eprintln!("エラー: --mock にブロック名が指定されていません");
+                process::exit(1);
+            };
+            mocks.extend(names.split(',').map(|n| n.trim().to_string()));
+        } else if arg == "--mock-style" {
+            let style = rest.next().map(|s| s.parse::<MockStyle>());
+            mock_style = match style {
+                Some(Ok(style)) => style,
+                _ => {
+                    eprintln!("エラー: --mock-style には tie か echo を指定してください");
+                    process::exit(1);
+                }
+            };
+        } else if arg == "--style" {
+            config.style = match rest.next().map(|s| s.parse::<TbStyle>()) {
+                Some(Ok(style)) => style,
+                _ => {
+                    let names: Vec<&str> = TbStyle::ALL.iter().map(|s| s.as_str()).collect();
+                    eprintln!(
+                        "エラー: --style には {} のいずれかを指定してください",
+                        names.join(", ")
+                    );
+                    process::exit(1);
+                }
+            };
+        } else if arg == "--arch" {
+            let Some(name) = rest.next() else {
+                eprintln!("エラー: --arch にアーキテクチャ名か all を指定してください");
+                process::exit(1);
+            };
+            arch = Some(name);
+        } else if arg == "--whitebox" {
+            let Some(patterns) = rest.next() else {
+                eprintln!("エラー: --whitebox に内部信号の名前を指定してください");
+                process::exit(1);
+            };
+            config.whitebox.push(patterns.clone());
+        } else if arg == "-d" {
             debug_mode = true;
+        } else if arg == "--coverage" {
+            config.toggle_coverage = true;
+        } else if arg == "--fsm" {
+            fsm_scenarios = true;
+        } else if arg == "--sweep" {
+            sweep = true;
+        } else if arg == "--skeleton" {
+            output = Output::Skeleton;
+        } else if arg == "--interface" {
+            output = Output::Interface;
+        } else if arg == "--wavedrom" {
+            output = Output::Wavedrom;
         } else {
             filename = Some(arg);
         }
@@ -33,18 +317,1387 @@ fn main() {
         Some(f) => f,
         None => {
             eprintln!("エラー: VHDLファイルが指定されていません");
-            eprintln!("使い方: {} [-d] <VHDLファイル>", args[0]);
+            eprintln!(
+                "使い方: {} [-d] [--coverage] [--fsm] [--sweep] [--style <スタイル>] [--arch <名前|all> [--whitebox <信号>[,<信号>...]]] [--mock <名前>[,<名前>...]] [--mock-style <tie|echo>] [--skeleton|--interface|--wavedrom] <VHDLファイル>",
+                args[0]
+            );
             process::exit(1);
         }
     };
 
-    let vhdl_code = match fs::read_to_string(filename) {
-        Ok(content) => content,
+    let entities = load_entities(filename, debug_mode);
+    let mocked = load_mocks(filename, &entities, &mocks);
+    let banner = load_banner(filename);
+
+    // テストベンチ（または雛形・インターフェース記述）生成（モックにするものは除く）
+    for entity in entities
+        .iter()
+        .filter(|e| !mocked.iter().any(|m| m.name.eq_ignore_ascii_case(&e.name)))
+    {
+        match output {
+            Output::Testbench => {
+                if debug_mode {
+                    eprintln!("\n=== {} のテストベンチ ===\n", entity.name);
+                }
+                let mut config = config.clone();
+                let profile = load_profile(filename, entity);
+                if let Some(profile) = &profile {
+                    profile.apply(&mut config);
+                }
+                config.assertions = load_assertions(filename, entity);
+                if profile.and_then(|p| p.fsm).unwrap_or(fsm_scenarios) {
+                    config.fsms = fsm::extract_fsms(&read_file(filename), &entity.name);
+                    for found in &config.fsms {
+                        eprintln!(
+                            "{}: ステートマシン {} ({} 状態, {} 遷移)",
+                            entity.name,
+                            found.state_signal,
+                            found.states.len(),
+                            found.transitions.len()
+                        );
+                    }
+                }
+                if let Some(arch) = arch {
+                    // アーキテクチャごとに1つずつ
+                    for (i, found) in load_architectures(filename, entity, arch)
+                        .into_iter()
+                        .enumerate()
+                    {
+                        config.architecture = Some(found);
+                        let tb = generator::generate_testbench(entity, &config);
+                        let tb = stamp(banner.as_ref(), tb, &entity.name, "--");
+                        print!("{}{}", if i > 0 { "\n" } else { "" }, tb);
+                    }
+                    continue;
+                }
+                let tb = if sweep {
+                    let sets = load_sweep(filename, entity);
+                    sweep::generate_sweep(entity, &sets, &config)
+                } else {
+                    generator::generate_testbench(entity, &config)
+                };
+                print!("{}", stamp(banner.as_ref(), tb, &entity.name, "--"));
+            }
+            Output::Skeleton => {
+                if debug_mode {
+                    eprintln!("\n=== {} の雛形 ===\n", entity.name);
+                }
+                let skeleton = generator::generate_entity_skeleton(entity);
+                print!("{}", stamp(banner.as_ref(), skeleton, &entity.name, "--"));
+            }
+            Output::Interface => {
+                print!("{}", InterfaceDef::from_entity(entity).to_json());
+            }
+            Output::Wavedrom => {
+                let mut config = config.clone();
+                if let Some(profile) = load_profile(filename, entity) {
+                    profile.apply(&mut config);
+                }
+                print!("{}", wavedrom::generate_wavedrom(entity, &config));
+            }
+        }
+    }
+    for entity in &mocked {
+        let code = mock::generate_mock(entity, mock_style);
+        print!("\n{}", stamp(banner.as_ref(), code, &entity.name, "--"));
+    }
+}
+
+/// モックにする下位ブロックのポート（入力のエンティティ、なければコンポーネント宣言から）
+fn load_mocks(filename: &str, entities: &[EntityDef], names: &[String]) -> Vec<EntityDef> {
+    if names.is_empty() {
+        return Vec::new();
+    }
+    let components = if Language::from_path(filename) == Language::Vhdl {
+        testbench::component_declarations(&read_file(filename))
+    } else {
+        Vec::new()
+    };
+    names
+        .iter()
+        .map(|name| {
+            entities
+                .iter()
+                .chain(&components)
+                .find(|e| e.name.eq_ignore_ascii_case(name))
+                .cloned()
+                .unwrap_or_else(|| {
+                    eprintln!(
+                        "エラー: '{}' のエンティティ宣言もコンポーネント宣言も見つかりません",
+                        name
+                    );
+                    process::exit(1);
+                })
+        })
+        .collect()
+}
+
+/// 出力する内容
+enum Output {
+    Testbench,
+    Skeleton,
+    Interface,
+    Wavedrom,
+}
+
+/// 拡張子に応じて入力ファイルを読み込む
+fn load_entities(filename: &str, debug_mode: bool) -> Vec<EntityDef> {
+    let lower = filename.to_lowercase();
+    let entities = if lower.ends_with(".xml") {
+        load_ipxact(filename, debug_mode)
+    } else if lower.ends_with(".json") {
+        load_interface(filename)
+    } else if Language::from_path(filename) == Language::Verilog {
+        load_verilog(filename)
+    } else {
+        return load_vhdl(filename, debug_mode);
+    };
+
+    // VHDL以外から作ったエンティティは不正なVHDLにならないか検査する
+    let diagnostics: Vec<_> = entities.iter().flat_map(|e| e.validate()).collect();
+    if !diagnostics.is_empty() {
+        let map = SourceMap::new(filename, read_file(filename));
+        eprint!("{}", report::render(&diagnostics, &map, stderr_format()));
+        if diagnostics
+            .iter()
+            .any(|d| d.severity == diagnostic::Severity::Error)
+        {
+            process::exit(1);
+        }
+    }
+    entities
+}
+
+/// 入力ファイルのディレクトリから親に向かって vig.toml を探す
+fn find_project_config(filename: &str) -> Option<PathBuf> {
+    let dir = Path::new(filename).parent()?;
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let dir = dir.canonicalize().ok()?;
+    dir.ancestors()
+        .map(|d| d.join("vig.toml"))
+        .find(|path| path.is_file())
+}
+
+/// vig.toml の `[libraries]` から use 節のパッケージを探し、ポートの型を解決する
+fn resolve_library_types(filename: &str, source: &str, entities: &mut [EntityDef]) {
+    let Some(config) = find_project_config(filename) else {
+        return;
+    };
+    let config_path = config.to_string_lossy().into_owned();
+    let config_map = SourceMap::new(&config_path, read_file(&config_path));
+    let base = config.parent().unwrap_or(Path::new("."));
+    let paths = match library::from_config(config_map.source(), base) {
+        Ok(Some(paths)) => paths,
+        Ok(None) => return,
+        Err(err) => {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!(
+                "{}",
+                report::render(&[diagnostic], &config_map, stderr_format())
+            );
+            process::exit(1);
+        }
+    };
+    let mut resolver = library::Resolver::new(paths).encoding(ENCODING.get().copied().flatten());
+    let mut diagnostics = Vec::new();
+    for entity in entities.iter_mut() {
+        match resolver.resolve_entity(source, entity) {
+            Ok(found) => {
+                // use 節はファイル全体で共通なので同じ警告は1度だけ
+                for d in found {
+                    if !diagnostics.contains(&d) {
+                        diagnostics.push(d);
+                    }
+                }
+            }
+            Err(err) => {
+                // 検索ディレクトリのファイルのエラーはそのファイルの該当行を表示
+                let path = err
+                    .path
+                    .as_ref()
+                    .map_or(filename.to_string(), |p| p.to_string_lossy().into_owned());
+                let map = SourceMap::new(&path, read_file(&path));
+                let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+                eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+                process::exit(1);
+            }
+        }
+    }
+    if !diagnostics.is_empty() {
+        let map = SourceMap::new(filename, source);
+        eprint!("{}", report::render(&diagnostics, &map, stderr_format()));
+    }
+}
+
+/// vig.toml からエンティティごとの設定（`[entity.<名前>]`）を読み込む
+fn load_profile(filename: &str, entity: &EntityDef) -> Option<profile::EntityProfile> {
+    let path = find_project_config(filename)?;
+    let path = path.to_string_lossy().into_owned();
+    let map = SourceMap::new(&path, read_file(&path));
+    match profile::from_config(map.source(), &entity.name) {
+        Ok(found) => found,
+        Err(err) => {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+            process::exit(1);
+        }
+    }
+}
+
+/// 入力ファイルからエンティティのアーキテクチャを探す（`name` が all なら全部、なければ終了）
+fn load_architectures(filename: &str, entity: &EntityDef, name: &str) -> Vec<ArchitectureDef> {
+    let source = read_file(filename);
+    let found: Vec<ArchitectureDef> = analyzer::analyze_vhdl(&source)
+        .map(|result| result.architectures)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|a| a.entity_name.eq_ignore_ascii_case(&entity.name))
+        .filter(|a| name.eq_ignore_ascii_case("all") || a.name.eq_ignore_ascii_case(name))
+        .collect();
+    if found.is_empty() {
+        if name.eq_ignore_ascii_case("all") {
+            eprintln!(
+                "エラー: {} のアーキテクチャが {} にありません",
+                entity.name, filename
+            );
+        } else {
+            eprintln!(
+                "エラー: {} のアーキテクチャ {} が {} にありません",
+                entity.name, name, filename
+            );
+        }
+        process::exit(1);
+    }
+    found
+}
+
+/// vig.toml から生成ファイルの先頭に付ける表示（`[banner]`）を読み込む
+fn load_banner(filename: &str) -> Option<Banner> {
+    let path = find_project_config(filename)?;
+    let base = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let path = path.to_string_lossy().into_owned();
+    let map = SourceMap::new(&path, read_file(&path));
+    match banner::from_config(map.source(), &base) {
+        Ok(found) => found,
+        Err(err) => {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+            process::exit(1);
+        }
+    }
+}
+
+/// 生成したテキストの先頭に表示を付ける（表示がなければそのまま）
+fn stamp(banner: Option<&Banner>, text: String, entity: &str, comment: &str) -> String {
+    match banner {
+        Some(banner) => banner.prepend(&text, entity, comment),
+        None => text,
+    }
+}
+
+/// vig.toml からエンティティのジェネリックの組を読み込む（なければ終了）
+fn load_sweep(filename: &str, entity: &EntityDef) -> Vec<sweep::GenericSet> {
+    let sets = match find_project_config(filename) {
+        Some(path) => {
+            let path = path.to_string_lossy().into_owned();
+            let map = SourceMap::new(&path, read_file(&path));
+            match sweep::from_config(map.source(), &entity.name) {
+                Ok(sets) => sets,
+                Err(err) => {
+                    let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+                    eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+                    process::exit(1);
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+    if sets.is_empty() {
+        eprintln!(
+            "エラー: vig.toml に {} のジェネリックの組（[[sweep.{}]]）がありません",
+            entity.name, entity.name
+        );
+        process::exit(1);
+    }
+    sets
+}
+
+/// vig.toml・設計ファイルのコメント・性質ファイルからエンティティのアサーションを集める
+fn load_assertions(filename: &str, entity: &EntityDef) -> Vec<Property> {
+    let fail = |err: AssertionError, map: &SourceMap| -> ! {
+        let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+        eprint!("{}", report::render(&[diagnostic], map, stderr_format()));
+        process::exit(1);
+    };
+    let mut sources = Vec::new();
+    if let Some(path) = find_project_config(filename) {
+        let path = path.to_string_lossy().into_owned();
+        let map = SourceMap::new(&path, read_file(&path));
+        let found = assertion::from_config(map.source(), &entity.name);
+        sources.push((map, found));
+    }
+    let lower = filename.to_lowercase();
+    if !lower.ends_with(".xml") && !lower.ends_with(".json") {
+        let map = SourceMap::new(filename, read_file(filename));
+        let found = assertion::from_comments(map.source(), &entity.name);
+        sources.push((map, found));
+    }
+    // 設計ファイルと同じディレクトリの <entity>.props.toml
+    let props = Path::new(filename).with_file_name(format!("{}.props.toml", entity.name));
+    if props.is_file() {
+        let path = props.to_string_lossy().into_owned();
+        let map = SourceMap::new(&path, read_file(&path));
+        let found = assertion::from_props(map.source());
+        sources.push((map, found));
+    }
+
+    let mut properties = Vec::new();
+    for (map, found) in sources {
+        let found = found.unwrap_or_else(|err| fail(err, &map));
+        for property in found {
+            if let Err(err) = property.check(entity) {
+                fail(err, &map);
+            }
+            properties.push(property);
+        }
+    }
+    properties
+}
+
+/// クロック周期（単位なしの整数は ns、`8ns` `1.25 us` のような時間も可）
+fn parse_period(text: &str) -> Option<u64> {
+    text.parse().ok().or_else(|| {
+        let period = vig::literal::parse_time(text)?;
+        u64::try_from(period.as_nanos()).ok()
+    })
+}
+
+/// `vig constraints` サブコマンド
+fn run_constraints(program: &str, args: &[String]) {
+    let usage = format!(
+        "使い方: {} constraints [--sdc] [--period <ns>] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+        program
+    );
+    let mut config = ConstraintConfig::default();
+    let mut filename = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--sdc" => config.format = ConstraintFormat::Sdc,
+            "--xdc" => config.format = ConstraintFormat::Xdc,
+            "--period" => match iter.next().and_then(|p| parse_period(p)) {
+                Some(period) => config.clock_period_ns = period,
+                None => {
+                    eprintln!(
+                        "エラー: --period にはクロック周期を ns の整数か時間（例: 8ns, 1.25us）で指定してください"
+                    );
+                    process::exit(1);
+                }
+            },
+            _ => filename = Some(arg),
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("エラー: 入力ファイルが指定されていません");
+        eprintln!("{}", usage);
+        process::exit(1);
+    };
+
+    let banner = load_banner(filename);
+    for entity in &load_entities(filename, false) {
+        let script = constraints::generate_constraints(entity, &config);
+        print!("{}", stamp(banner.as_ref(), script, &entity.name, "#"));
+    }
+}
+
+/// `vig pins` サブコマンド
+fn run_pins(program: &str, args: &[String]) {
+    let mut format = PinListFormat::Csv;
+    let mut entity_name = None;
+    let mut filename = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--tsv" => format = PinListFormat::Tsv,
+            "--csv" => format = PinListFormat::Csv,
+            "--entity" => entity_name = iter.next(),
+            _ => filename = Some(arg),
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("エラー: 入力ファイルが指定されていません");
+        eprintln!(
+            "使い方: {} pins [--tsv] [--entity <名前>] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let entities = load_entities(filename, false);
+    let entity = select_entity(&entities, entity_name);
+    print!("{}", pinlist::generate_pin_list(entity, format));
+}
+
+/// `vig diagram` サブコマンド
+fn run_diagram(program: &str, args: &[String]) {
+    let mut svg = false;
+    let mut entity_name = None;
+    let mut filename = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--svg" => svg = true,
+            "--dot" => svg = false,
+            "--entity" => entity_name = iter.next(),
+            _ => filename = Some(arg),
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("エラー: 入力ファイルが指定されていません");
+        eprintln!(
+            "使い方: {} diagram [--svg] [--entity <名前>] <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let entities = load_entities(filename, false);
+    let entity = select_entity(&entities, entity_name);
+    if svg {
+        print!("{}", diagram::generate_svg(entity));
+    } else {
+        print!("{}", diagram::generate_dot(entity));
+    }
+}
+
+/// 名前でエンティティを選ぶ（指定がなければファイル内の最後のエンティティをトップとみなす）
+fn select_entity<'a>(entities: &'a [EntityDef], name: Option<&String>) -> &'a EntityDef {
+    let found = match name {
+        Some(name) => entities.iter().find(|e| e.name.eq_ignore_ascii_case(name)),
+        None => entities.last(),
+    };
+    match found {
+        Some(entity) => entity,
+        None => {
+            eprintln!("エラー: エンティティが見つかりません");
+            process::exit(1);
+        }
+    }
+}
+
+/// `vig vectors` サブコマンド
+fn run_vectors(program: &str, args: &[String]) {
+    let mut entity_name = None;
+    let mut predict = false;
+    let mut config = generator::TbConfig::default();
+    let mut table_file = None;
+    let mut filename = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--entity" => entity_name = iter.next(),
+            "--predict" => predict = true,
+            "--coverage" => config.toggle_coverage = true,
+            _ => {
+                let lower = arg.to_lowercase();
+                if lower.ends_with(".csv") || lower.ends_with(".tsv") {
+                    table_file = Some(arg);
+                } else {
+                    filename = Some(arg);
+                }
+            }
+        }
+    }
+    let (Some(table_file), Some(filename)) = (table_file, filename) else {
+        eprintln!("エラー: 真理値表と入力ファイルを指定してください");
+        eprintln!(
+            "使い方: {} vectors [--entity <名前>] [--predict] [--coverage] <真理値表(.csv|.tsv)> <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let map = SourceMap::new(table_file, read_file(table_file));
+    // 真理値表のエラーは該当行を示して終了
+    let fail = |err: VectorError| -> ! {
+        let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+        eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+        process::exit(1);
+    };
+    let mut table = TruthTable::from_csv(map.source()).unwrap_or_else(|err| fail(err));
+
+    let entities = load_entities(filename, false);
+    let entity = select_entity(&entities, entity_name);
+    if predict {
+        // 期待値の空欄は設計を解釈して埋める
+        let design = SourceMap::new(filename, read_file(filename));
+        let interp = Interpreter::new(design.source(), &entity.name).unwrap_or_else(|err| {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!(
+                "{}",
+                report::render(&[diagnostic], &design, stderr_format())
+            );
+            process::exit(1);
+        });
+        table.fill_expected(&interp).unwrap_or_else(|err| fail(err));
+    }
+    match vectors::generate_testbench(entity, &table, &config) {
+        Ok(tb) => print!(
+            "{}",
+            stamp(load_banner(filename).as_ref(), tb, &entity.name, "--")
+        ),
+        Err(err) => fail(err),
+    }
+}
+
+/// `vig update-tb` サブコマンド
+fn run_update_tb(program: &str, args: &[String]) {
+    let mut entity_name = None;
+    let mut in_place = false;
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--entity" => entity_name = iter.next(),
+            "--in-place" | "-i" => in_place = true,
+            _ => files.push(arg),
+        }
+    }
+    let [tb_file, filename] = files[..] else {
+        eprintln!("エラー: テストベンチと入力ファイルを指定してください");
+        eprintln!(
+            "使い方: {} update-tb [--entity <名前>] [--in-place] <テストベンチ> <VHDLファイル|IP-XACTファイル|JSONファイル>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let entities = load_entities(filename, false);
+    let entity = select_entity(&entities, entity_name);
+    let map = SourceMap::new(tb_file, read_file(tb_file));
+    let update = match testbench::update_testbench(map.source(), entity) {
+        Ok(update) => update,
+        Err(err) => {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+            process::exit(1);
+        }
+    };
+    // 変更内容は標準エラーに表示
+    eprint!("{}", report::render(&update.changes, &map, stderr_format()));
+    if !in_place {
+        print!("{}", update.text);
+    } else if update.text != map.source() {
+        write_back(tb_file, &update.text);
+    }
+}
+
+/// 読み込んだときと同じ文字コードでファイルに書き戻す
+fn write_back(filename: &str, text: &str) {
+    let encoding = ENCODING
+        .get()
+        .copied()
+        .flatten()
+        .unwrap_or_else(|| Encoding::detect(&fs::read(filename).unwrap_or_default()));
+    let bytes = match encoding::encode(text, encoding) {
+        Ok(bytes) => bytes,
         Err(err) => {
-            eprintln!("ファイル '{}' の読み込みに失敗しました: {}", filename, err);
+            eprintln!("エラー: {} に書き戻せません: {}", encoding, err);
             process::exit(1);
         }
     };
+    if let Err(err) = fs::write(filename, bytes) {
+        eprintln!("ファイル '{}' の書き込みに失敗しました: {}", filename, err);
+        process::exit(1);
+    }
+}
+
+/// `vig extract-dut` サブコマンド
+fn run_extract_dut(program: &str, args: &[String]) {
+    let mut interface = false;
+    let mut filename = None;
+    for arg in args {
+        match arg.as_str() {
+            "--interface" => interface = true,
+            _ => filename = Some(arg),
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("エラー: テストベンチが指定されていません");
+        eprintln!(
+            "使い方: {} extract-dut [--interface] <テストベンチ>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let map = SourceMap::new(filename, read_file(filename));
+    let dut = match testbench::extract_dut(map.source()) {
+        Ok(dut) => dut,
+        Err(err) => {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+            process::exit(1);
+        }
+    };
+    // 推定で補った箇所は確認できるよう標準エラーに表示
+    eprint!("{}", report::render(&dut.warnings, &map, stderr_format()));
+    if interface {
+        print!("{}", InterfaceDef::from_entity(&dut.entity).to_json());
+    } else {
+        let skeleton = generator::generate_entity_skeleton(&dut.entity);
+        let banner = load_banner(filename);
+        print!(
+            "{}",
+            stamp(banner.as_ref(), skeleton, &dut.entity.name, "--")
+        );
+    }
+}
+
+/// `vig stats` サブコマンド
+fn run_stats(program: &str, args: &[String]) {
+    let mut detail = false;
+    let mut json = false;
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--metrics" => detail = true,
+            "--json" => json = true,
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("エラー: VHDLファイルが指定されていません");
+        eprintln!(
+            "使い方: {} stats [--metrics] [--json] <VHDLファイル>...",
+            program
+        );
+        process::exit(1);
+    }
+
+    let mut all = Vec::new();
+    for filename in files {
+        let map = SourceMap::new(filename, read_file(filename));
+        let entities = load_vhdl(filename, false);
+        let found: Vec<_> = entities
+            .iter()
+            .map(|e| metrics::metrics(map.source(), e))
+            .collect();
+        if !json {
+            let lines = metrics::line_counts(map.source());
+            println!(
+                "{}: {} 行（コード {}, コメント {}, 空行 {}）",
+                filename, lines.total, lines.code, lines.comment, lines.blank
+            );
+            for m in &found {
+                println!(
+                    "  {}: ポート {}, 信号 {}, プロセス {}, 並行代入 {}, 文 {}, 最大の入れ子 {}",
+                    m.name,
+                    m.ports,
+                    m.signals,
+                    m.process_count,
+                    m.concurrent_statements,
+                    m.statements,
+                    m.max_nesting_depth
+                );
+                if detail {
+                    print_metrics(m, &map);
+                }
+            }
+        }
+        all.extend(found);
+    }
+    if json {
+        print!("{}", metrics::to_json(&all));
+    }
+}
+
+/// プロセスと信号ごとの指標を表示
+fn print_metrics(m: &metrics::EntityMetrics, map: &SourceMap) {
+    for p in &m.processes {
+        let (line, _) = map.line_col(p.span.start);
+        println!(
+            "    プロセス {} ({}行目): 文 {}, 入れ子 {}, ファンイン {}, ファンアウト {}",
+            p.label.as_deref().unwrap_or("(ラベルなし)"),
+            line,
+            p.statements,
+            p.nesting_depth,
+            p.fan_in,
+            p.fan_out
+        );
+    }
+    for s in &m.signal_metrics {
+        println!(
+            "    信号 {}: ファンイン {}, ファンアウト {}",
+            s.name, s.fan_in, s.fan_out
+        );
+    }
+}
+
+/// `vig todo` サブコマンド
+fn run_todo(program: &str, args: &[String]) {
+    let mut format = ReportFormat::Plain;
+    let mut fail = false;
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => format = ReportFormat::Json,
+            "--fail" => fail = true,
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("エラー: VHDLファイルが指定されていません");
+        eprintln!(
+            "使い方: {} todo [--json] [--fail] <VHDLファイル>...",
+            program
+        );
+        process::exit(1);
+    }
+
+    let found: Vec<_> = files
+        .into_iter()
+        .map(|filename| {
+            let map = SourceMap::new(filename, read_file(filename));
+            let items = todo::find_todos(map.source());
+            (map, items)
+        })
+        .collect();
+    print!("{}", todo::render(&found, format));
+    if fail && found.iter().any(|(_, items)| !items.is_empty()) {
+        process::exit(1);
+    }
+}
+
+/// `vig lint` サブコマンド
+fn run_lint(program: &str, args: &[String]) {
+    let mut json = false;
+    let mut fix = false;
+    let mut in_place = false;
+    let mut filename = None;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--fix" => fix = true,
+            "--in-place" | "-i" => in_place = true,
+            _ => filename = Some(arg),
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("エラー: VHDLファイルが指定されていません");
+        eprintln!(
+            "使い方: {} lint [--json] [--fix [--in-place]] <VHDLファイル>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let config = match find_project_config(filename) {
+        Some(path) => {
+            let path = path.to_string_lossy().into_owned();
+            let map = SourceMap::new(&path, read_file(&path));
+            match LintConfig::from_toml(map.source()) {
+                Ok(config) => config,
+                Err(err) => {
+                    let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+                    eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+                    process::exit(1);
+                }
+            }
+        }
+        None => LintConfig::default(),
+    };
+    let map = SourceMap::new(filename, read_file(filename));
+    if fix {
+        // 並び順の方針を設定していなければ既定の方針で直す
+        let policy = config.port_order.clone().unwrap_or_default();
+        let fixed = match lint::fix_port_order(map.source(), &policy) {
+            Ok(fixed) => fixed,
+            Err(err) => {
+                let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+                eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+                process::exit(1);
+            }
+        };
+        if !in_place {
+            print!("{}", fixed);
+        } else if fixed != map.source() {
+            write_back(filename, &fixed);
+        }
+        return;
+    }
+    let diagnostics = lint::lint(map.source(), &config);
+    if json {
+        print!("{}", report::render(&diagnostics, &map, ReportFormat::Json));
+    } else {
+        eprint!("{}", report::render(&diagnostics, &map, stderr_format()));
+    }
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        process::exit(1);
+    }
+}
+
+/// `vig drivers` サブコマンド
+fn run_drivers(program: &str, args: &[String]) {
+    let mut format = ReportFormat::Plain;
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => format = ReportFormat::Json,
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("エラー: VHDLファイルが指定されていません");
+        eprintln!("使い方: {} drivers [--json] <VHDLファイル>...", program);
+        process::exit(1);
+    }
+
+    let mut found = Vec::new();
+    let mut failed = false;
+    for filename in files {
+        let map = SourceMap::new(filename, read_file(filename));
+        let entities = load_vhdl(filename, false);
+        let usages = entities
+            .iter()
+            .flat_map(|e| signals::usages(map.source(), e))
+            .collect();
+        let diagnostics: Vec<_> = entities
+            .iter()
+            .flat_map(|e| signals::check_multiple_drivers(map.source(), e))
+            .collect();
+        if !diagnostics.is_empty() {
+            eprint!("{}", report::render(&diagnostics, &map, stderr_format()));
+            failed = true;
+        }
+        found.push((map, usages));
+    }
+    print!("{}", signals::render(&found, format));
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// `vig hierarchy` サブコマンド
+fn run_hierarchy(program: &str, args: &[String]) {
+    if args.is_empty() {
+        eprintln!("エラー: ファイルが指定されていません");
+        eprintln!(
+            "使い方: {} hierarchy <VHDLファイル|Verilogファイル>...",
+            program
+        );
+        process::exit(1);
+    }
+    print!("{}", load_project(args).0.render_hierarchy());
+}
+
+/// `vig stub` サブコマンド
+fn run_stub(program: &str, args: &[String]) {
+    if args.is_empty() {
+        eprintln!("エラー: ファイルが指定されていません");
+        eprintln!("使い方: {} stub <VHDLファイル|Verilogファイル>...", program);
+        process::exit(1);
+    }
+    let (project, _) = load_project(args);
+    let unbound = project.unbound_components();
+    let banner = load_banner(&args[0]);
+    if unbound.is_empty() {
+        eprintln!("エンティティのないコンポーネントはありません");
+        return;
+    }
+    for (i, component) in unbound.iter().enumerate() {
+        eprintln!(
+            "スタブ: {}（{} で宣言）",
+            component.interface.name, project.files[component.file]
+        );
+        if i > 0 {
+            println!();
+        }
+        let stub = mock::generate_stub(&component.interface);
+        let name = &component.interface.name;
+        print!("{}", stamp(banner.as_ref(), stub, name, "--"));
+    }
+}
+
+/// `vig impact` サブコマンド
+fn run_impact(program: &str, args: &[String]) {
+    if args.len() < 2 {
+        eprintln!("エラー: エンティティとファイルを指定してください");
+        eprintln!(
+            "使い方: {} impact <エンティティ> <VHDLファイル|Verilogファイル>...",
+            program
+        );
+        process::exit(1);
+    }
+    let (project, maps) = load_project(&args[1..]);
+    let Some(unit) = project.unit(&args[0]) else {
+        eprintln!("エラー: エンティティ '{}' が見つかりません", args[0]);
+        process::exit(1);
+    };
+    let files: Vec<(&str, &str)> = args[1..]
+        .iter()
+        .zip(&maps)
+        .map(|(filename, map)| (filename.as_str(), map.source()))
+        .collect();
+    let impact = impact::analyze_impact(&unit.entity, &files);
+
+    println!(
+        "{} を参照している箇所: {}（合わないもの: {}）",
+        unit.entity.name,
+        impact.references.len(),
+        impact.incompatible().len()
+    );
+    for reference in &impact.references {
+        let mut notes = Vec::new();
+        if let Some(owner) = &reference.owner {
+            notes.push(owner.clone());
+        }
+        if reference.in_testbench {
+            notes.push("テストベンチ".to_string());
+        }
+        if !reference.is_compatible() {
+            notes.push("合わない".to_string());
+        }
+        println!(
+            "  {}: {} {}{}",
+            impact.files[reference.file],
+            reference.kind.as_str(),
+            reference.name,
+            if notes.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", notes.join(", "))
+            }
+        );
+    }
+    for reference in impact.incompatible() {
+        let diagnostics = reference.diagnostics(&unit.entity);
+        print!(
+            "\n{}",
+            report::render(&diagnostics, &maps[reference.file], ReportFormat::Plain)
+        );
+    }
+    if !impact.incompatible().is_empty() {
+        process::exit(1);
+    }
+}
+
+/// ファイルをまとめて設計として解析する（エラーは表示して終了）
+fn load_project(args: &[String]) -> (project::Project, Vec<SourceMap>) {
+    let maps: Vec<SourceMap> = args
+        .iter()
+        .map(|filename| SourceMap::new(filename, read_file(filename)))
+        .collect();
+    let files: Vec<(&str, &str)> = args
+        .iter()
+        .zip(&maps)
+        .map(|(filename, map)| (filename.as_str(), map.source()))
+        .collect();
+    match project::analyze_project(&files) {
+        Ok(project) => (project, maps),
+        Err(err) => {
+            let map = args
+                .iter()
+                .position(|f| *f == err.path)
+                .map(|i| &maps[i])
+                .unwrap_or(&maps[0]);
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!("{}", report::render(&[diagnostic], map, stderr_format()));
+            process::exit(1);
+        }
+    }
+}
+
+/// `vig sim` サブコマンド
+fn run_sim(program: &str, args: &[String]) {
+    let usage = format!(
+        "使い方: {} sim [--simulator <ghdl|nvc|questa>] [--top <名前>] [--std <93|02|08>] [--stop-time <時間>] [--work <ディレクトリ>] [--junit <ファイル>] [--tap <ファイル>] [--dry-run] <VHDLファイル>...",
+        program
+    );
+    let mut backend = "ghdl".to_string();
+    let mut job = SimJob::default();
+    let mut dry_run = false;
+    let mut junit = None;
+    let mut tap = None;
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--simulator" => backend = iter.next().cloned().unwrap_or_default(),
+            "--junit" => junit = iter.next().cloned(),
+            "--tap" => tap = iter.next().cloned(),
+            "--top" => job.top = iter.next().cloned().unwrap_or_default(),
+            "--work" => job.work_dir = iter.next().cloned().unwrap_or_default().into(),
+            "--std" => match iter.next().map(|s| s.parse()) {
+                Some(Ok(standard)) => job.standard = standard,
+                _ => {
+                    eprintln!("エラー: --std には 93, 02, 08 のいずれかを指定してください");
+                    process::exit(1);
+                }
+            },
+            "--stop-time" => match iter.next().and_then(|t| parse_period(t)) {
+                Some(ns) => job.stop_time_ns = Some(ns),
+                None => {
+                    eprintln!(
+                        "エラー: --stop-time には時間を ns の整数か時間（例: 500ns, 1ms）で指定してください"
+                    );
+                    process::exit(1);
+                }
+            },
+            "--dry-run" => dry_run = true,
+            _ => files.push(arg.clone()),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("エラー: VHDLファイルが指定されていません");
+        eprintln!("{}", usage);
+        process::exit(1);
+    }
+    let Some(simulator) = sim::simulator(&backend) else {
+        eprintln!("エラー: 未対応のシミュレータです: '{}'", backend);
+        eprintln!("{}", usage);
+        process::exit(1);
+    };
+
+    let sources: Vec<String> = files.iter().map(|f| read_file(f)).collect();
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    job.sources = sim::compile_order(&sources)
+        .into_iter()
+        .map(|i| files[i].clone().into())
+        .collect();
+    if job.top.is_empty() {
+        // ほかから参照されない最後のエンティティをトップとみなす
+        match sim::top_candidates(&sources).pop() {
+            Some(top) => job.top = top,
+            None => {
+                eprintln!(
+                    "エラー: トップのエンティティが見つかりません（--top で指定してください）"
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    if dry_run {
+        for command in simulator.commands(&job) {
+            println!("{}", command);
+        }
+        return;
+    }
+    let output = match simulator.simulate(&job) {
+        Ok(output) => output,
+        Err(err) => {
+            eprintln!(
+                "シミュレータ '{}' を実行できません: {}",
+                simulator.name(),
+                err
+            );
+            process::exit(1);
+        }
+    };
+    for step in &output.steps {
+        print!("{}", step.stdout);
+        eprint!("{}", step.stderr);
+    }
+    // 報告をまとめた結果で終了コードを決める
+    let report = SimReport::from_output(&output);
+    eprintln!("{}", report.summary());
+    let passed = report.status == SimStatus::Passed;
+    let cases = [(job.top.clone(), report)];
+    if let Some(path) = &junit {
+        write_report(path, &simlog::to_junit("vig", &cases));
+    }
+    if let Some(path) = &tap {
+        write_report(path, &simlog::to_tap(&cases));
+    }
+    if !passed {
+        process::exit(1);
+    }
+}
+
+/// `vig regress` サブコマンド
+fn run_regress(program: &str, args: &[String]) {
+    let usage = format!(
+        "使い方: {} regress [--simulator <ghdl|nvc|questa>] [--filter <パターン>] [--jobs <数>] [--tb-dir <ディレクトリ>] [--work <ディレクトリ>] [--std <93|02|08>] [--stop-time <時間>] [--junit <ファイル>] [--tap <ファイル>] <VHDLファイル>...",
+        program
+    );
+    let mut backend = "ghdl".to_string();
+    let mut filter = "*".to_string();
+    let mut jobs = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut tb_dir = PathBuf::from("vig_work/tb");
+    let mut template = SimJob::default();
+    let mut junit = None;
+    let mut tap = None;
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--simulator" => backend = iter.next().cloned().unwrap_or_default(),
+            "--filter" => filter = iter.next().cloned().unwrap_or_default(),
+            "--jobs" | "-j" => match iter.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => jobs = n,
+                _ => {
+                    eprintln!("エラー: --jobs には1以上の整数を指定してください");
+                    process::exit(1);
+                }
+            },
+            "--tb-dir" => tb_dir = iter.next().cloned().unwrap_or_default().into(),
+            "--work" => template.work_dir = iter.next().cloned().unwrap_or_default().into(),
+            "--std" => match iter.next().map(|s| s.parse()) {
+                Some(Ok(standard)) => template.standard = standard,
+                _ => {
+                    eprintln!("エラー: --std には 93, 02, 08 のいずれかを指定してください");
+                    process::exit(1);
+                }
+            },
+            "--stop-time" => match iter.next().and_then(|t| parse_period(t)) {
+                Some(ns) => template.stop_time_ns = Some(ns),
+                None => {
+                    eprintln!(
+                        "エラー: --stop-time には時間を ns の整数か時間（例: 500ns, 1ms）で指定してください"
+                    );
+                    process::exit(1);
+                }
+            },
+            "--junit" => junit = iter.next().cloned(),
+            "--tap" => tap = iter.next().cloned(),
+            _ => files.push(arg.clone()),
+        }
+    }
+    if files.is_empty() {
+        eprintln!("エラー: VHDLファイルが指定されていません");
+        eprintln!("{}", usage);
+        process::exit(1);
+    }
+    let Some(simulator) = sim::simulator(&backend) else {
+        eprintln!("エラー: 未対応のシミュレータです: '{}'", backend);
+        eprintln!("{}", usage);
+        process::exit(1);
+    };
+
+    let designs: Vec<(PathBuf, String)> = files
+        .iter()
+        .map(|f| (PathBuf::from(f), read_file(f)))
+        .collect();
+    // 終了の報告で止めないとクロックが回り続けるため failure にする（vig.toml で変えられる）
+    let mut base = generator::TbConfig::default();
+    base.finish_severity = AssertSeverity::Failure;
+    let mut benches = regress::plan(&designs, &filter, |path, entity| {
+        let filename = path.to_string_lossy();
+        let mut config = base.clone();
+        if let Some(profile) = load_profile(&filename, entity) {
+            profile.apply(&mut config);
+        }
+        config.assertions = load_assertions(&filename, entity);
+        config
+    });
+    if benches.is_empty() {
+        eprintln!("エラー: '{}' に合うエンティティがありません", filter);
+        process::exit(1);
+    }
+    if let Some(banner) = load_banner(&files[0]) {
+        for bench in &mut benches {
+            if let Some(code) = &bench.generated {
+                bench.generated = Some(banner.prepend(code, &bench.entity, "--"));
+            }
+        }
+    }
+    if let Err(err) = regress::write_testbenches(&mut benches, &tb_dir) {
+        eprintln!("エラー: テストベンチを書き出せません: {}", err);
+        process::exit(1);
+    }
+
+    let results = regress::run(&benches, simulator.as_ref(), &template, jobs);
+    print!("{}", regress::summary_table(&results));
+    let cases: Vec<(String, SimReport)> = results
+        .iter()
+        .map(|r| (r.top.clone(), r.report.clone()))
+        .collect();
+    if let Some(path) = &junit {
+        write_report(path, &simlog::to_junit("vig", &cases));
+    }
+    if let Some(path) = &tap {
+        write_report(path, &simlog::to_tap(&cases));
+    }
+    if results.iter().any(|r| r.report.status != SimStatus::Passed) {
+        process::exit(1);
+    }
+}
+
+/// テスト結果のファイルを書き出す
+fn write_report(path: &str, text: &str) {
+    if let Err(err) = fs::write(path, text) {
+        eprintln!("ファイル '{}' の書き込みに失敗しました: {}", path, err);
+        process::exit(1);
+    }
+}
+
+/// `vig regmap` サブコマンド
+fn run_regmap(program: &str, args: &[String]) {
+    let mut output = RegmapOutput::Vhdl;
+    let mut filename = None;
+    for arg in args {
+        match arg.as_str() {
+            "--tb" => output = RegmapOutput::Testbench,
+            "--markdown" => output = RegmapOutput::Markdown,
+            _ => filename = Some(arg),
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("エラー: レジスタ記述ファイルが指定されていません");
+        eprintln!(
+            "使い方: {} regmap [--tb|--markdown] <レジスタ記述(.toml|.yaml|.json)>",
+            program
+        );
+        process::exit(1);
+    };
+
+    let source = read_file(filename);
+    let lower = filename.to_lowercase();
+    let map = if lower.ends_with(".json") {
+        RegisterMap::from_json(&source)
+    } else if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+        RegisterMap::from_yaml(&source)
+    } else {
+        RegisterMap::from_toml(&source)
+    };
+    let map = match map {
+        Ok(map) => map,
+        Err(err) => {
+            eprintln!("レジスタ記述の読み込みエラー: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let banner = load_banner(filename);
+    match output {
+        RegmapOutput::Vhdl => {
+            let code = map.generate_vhdl();
+            print!("{}", stamp(banner.as_ref(), code, &map.name, "--"))
+        }
+        RegmapOutput::Testbench => {
+            let tb = map.generate_testbench(&generator::TbConfig::default());
+            print!("{}", stamp(banner.as_ref(), tb, &map.name, "--"))
+        }
+        RegmapOutput::Markdown => print!("{}", map.generate_markdown()),
+    }
+}
+
+/// `vig regmap` で出力する内容
+enum RegmapOutput {
+    Vhdl,
+    Testbench,
+    Markdown,
+}
+
+/// ファイルを文字列として読み込む（失敗時は終了）
+fn read_file(filename: &str) -> String {
+    let encoding = ENCODING.get().copied().flatten();
+    match encoding::read_source(filename, encoding) {
+        Ok(content) => content,
+        Err(vig::Error::Io { source, .. }) => {
+            eprintln!(
+                "ファイル '{}' の読み込みに失敗しました: {}",
+                filename, source
+            );
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!(
+                "ファイル '{}' を {} として読み込めません: {}",
+                filename,
+                encoding.unwrap_or(Encoding::Utf8),
+                err
+            );
+            process::exit(1);
+        }
+    }
+}
+
+/// 引数から `--encoding <名前>`（`--encoding=<名前>`）を取り除いて記録する
+fn take_encoding(args: Vec<String>) -> Vec<String> {
+    let mut rest = Vec::with_capacity(args.len());
+    let mut encoding = None;
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        let name = if arg == "--encoding" {
+            iter.next().unwrap_or_default()
+        } else if let Some(name) = arg.strip_prefix("--encoding=") {
+            name.to_string()
+        } else {
+            rest.push(arg);
+            continue;
+        };
+        match name.parse::<Encoding>() {
+            Ok(parsed) => encoding = Some(parsed),
+            Err(_) => {
+                eprintln!(
+                    "エラー: --encoding には utf-8, shift_jis, latin1 のいずれかを指定してください"
+                );
+                process::exit(1);
+            }
+        }
+    }
+    let _ = ENCODING.set(encoding);
+    rest
+}
+
+/// インターフェース記述(JSON)を読み込んでEntityDefに変換
+fn load_interface(filename: &str) -> Vec<EntityDef> {
+    match InterfaceDef::from_json(&read_file(filename)) {
+        Ok(interface) => vec![interface.entity],
+        Err(err) => {
+            eprintln!("インターフェース記述の読み込みエラー: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// Verilog のモジュールヘッダを読み込んでEntityDefに変換
+fn load_verilog(filename: &str) -> Vec<EntityDef> {
+    let map = SourceMap::new(filename, read_file(filename));
+    match verilog::parse_modules(map.source()) {
+        Ok(modules) => modules.iter().map(verilog::Module::to_entity).collect(),
+        Err(err) => {
+            let diagnostic = diagnostic::Diagnostic::error(err.message, err.span);
+            eprint!("{}", report::render(&[diagnostic], &map, stderr_format()));
+            process::exit(1);
+        }
+    }
+}
+
+/// IP-XACTコンポーネントXMLを読み込んでEntityDefに変換
+fn load_ipxact(filename: &str, debug_mode: bool) -> Vec<EntityDef> {
+    let xml = read_file(filename);
+
+    match ipxact::import_component(&xml) {
+        Ok(entity) => {
+            if debug_mode {
+                eprintln!("=== {} の IP-XACT 取り込み結果 ===\n", filename);
+                eprintln!("Entity: {}", entity.name);
+                for port in &entity.ports {
+                    eprintln!(
+                        "  Port: {} : {:?} {:?}",
+                        port.name, port.direction, port.vhdl_type
+                    );
+                }
+            }
+            vec![entity]
+        }
+        Err(err) => {
+            eprintln!("IP-XACT 取り込みエラー: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// VHDLファイルを読み込んで解析
+fn load_vhdl(filename: &str, debug_mode: bool) -> Vec<EntityDef> {
+    let vhdl_code = read_file(filename);
 
     // デバッグモード: トークン解析結果を表示
     if debug_mode {
@@ -75,34 +1728,38 @@ fn main() {
                     }
                 }
                 Err(err) => {
-                    eprintln!("エラー: {}", err);
+                    eprintln!("エラー: {}", err.with_file(filename));
                 }
             }
         }
     }
 
     // 意味解析
-    let result = match analyzer::analyze_vhdl(&vhdl_code) {
+    match analyzer::analyze_vhdl(&vhdl_code) {
         Ok(result) => {
             if debug_mode {
                 eprintln!("\n=== {} の意味解析 ===\n", filename);
                 eprint!("{}", result);
             }
-            result
+            let mut entities = result.entities;
+            resolve_library_types(filename, &vhdl_code, &mut entities);
+            entities
         }
-        Err(err) => {
-            eprintln!("解析エラー: {}", err);
+        Err(_) => {
+            // 字句解析エラーも含めて該当行付きで表示
+            let map = SourceMap::new(filename, vhdl_code);
+            let diagnostics = diagnostic::diagnose(map.source());
+            eprint!("{}", report::render(&diagnostics, &map, stderr_format()));
             process::exit(1);
         }
-    };
+    }
+}
 
-    // テストベンチ生成
-    let config = generator::TbConfig::default();
-    for entity in &result.entities {
-        if debug_mode {
-            eprintln!("\n=== {} のテストベンチ ===\n", entity.name);
-        }
-        let tb = generator::generate_testbench(entity, &config);
-        print!("{}", tb);
+/// 標準エラーが端末ならカラー表示（NO_COLOR 指定時を除く）
+fn stderr_format() -> ReportFormat {
+    if std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none() {
+        ReportFormat::Ansi
+    } else {
+        ReportFormat::Plain
     }
 }
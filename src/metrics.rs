@@ -0,0 +1,214 @@
+//! 設計の複雑さの指標
+//!
+//! エンティティごとに、プロセスの数・文の数・入れ子の深さ・信号のファンイン/ファンアウトを
+//! 数えます。値を記録し続けて、HDL の複雑さの推移を追うためのものです。
+
+use crate::analyzer::EntityDef;
+use crate::drivers::{self, Driver};
+use crate::json::Value;
+use crate::lexer::Span;
+use crate::trace::timed;
+
+/// プロセス1つの指標
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessMetrics {
+    /// ラベル（なければ None）
+    pub label: Option<String>,
+    pub span: Span,
+    /// 文の数（複合文も1つと数え、中の文も数える）
+    pub statements: usize,
+    /// if / case / loop の入れ子の深さの最大
+    pub nesting_depth: usize,
+    /// 参照する信号・ポートの数
+    pub fan_in: usize,
+    /// 代入する信号・ポートの数
+    pub fan_out: usize,
+}
+
+/// 信号（またはポート）1つの指標
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalMetrics {
+    pub name: String,
+    /// 代入しているプロセス・並行代入の数
+    pub fan_in: usize,
+    /// 参照しているプロセス・並行代入の数
+    pub fan_out: usize,
+}
+
+/// エンティティ1つの指標
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityMetrics {
+    pub name: String,
+    pub ports: usize,
+    /// アーキテクチャで宣言した信号の数
+    pub signals: usize,
+    pub process_count: usize,
+    pub concurrent_statements: usize,
+    /// プロセス内の文と並行代入の合計
+    pub statements: usize,
+    pub max_nesting_depth: usize,
+    pub processes: Vec<ProcessMetrics>,
+    /// ポートと信号（宣言順）
+    pub signal_metrics: Vec<SignalMetrics>,
+}
+
+/// ファイルの行数
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    pub total: usize,
+    pub code: usize,
+    /// コメントだけの行
+    pub comment: usize,
+    pub blank: usize,
+}
+
+/// 行数を数える
+pub fn line_counts(source: &str) -> LineCounts {
+    let mut counts = LineCounts::default();
+    for line in source.lines() {
+        let line = line.trim();
+        counts.total += 1;
+        if line.is_empty() {
+            counts.blank += 1;
+        } else if line.starts_with("--") {
+            counts.comment += 1;
+        } else {
+            counts.code += 1;
+        }
+    }
+    counts
+}
+
+/// エンティティの指標を数える
+///
+/// `source` 中のこのエンティティのアーキテクチャをすべて対象にします。
+/// アーキテクチャがなければポート数以外は 0 です。
+pub fn metrics(source: &str, entity: &EntityDef) -> EntityMetrics {
+    let _timed = timed!("metrics", entity = %entity.name);
+    let archs: Vec<_> = drivers::architectures(source)
+        .into_iter()
+        .filter(|a| a.entity.eq_ignore_ascii_case(&entity.name))
+        .collect();
+    let mut names: Vec<String> = entity.ports.iter().map(|p| p.name.clone()).collect();
+    let mut signals = 0;
+    for arch in &archs {
+        for decl in &arch.signals {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(&decl.name)) {
+                names.push(decl.name.clone());
+                signals += 1;
+            }
+        }
+    }
+    let lower: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+    let drivers: Vec<&Driver> = archs.iter().flat_map(|a| &a.drivers).collect();
+
+    let processes: Vec<ProcessMetrics> = drivers
+        .iter()
+        .filter(|d| d.is_process)
+        .map(|d| ProcessMetrics {
+            label: d.label.clone(),
+            span: d.span,
+            statements: d.statements,
+            nesting_depth: d.nesting_depth,
+            fan_in: count_known(d.reads.iter().map(|r| &r.name), &lower),
+            fan_out: count_known(d.writes.iter().map(|(name, _)| name), &lower),
+        })
+        .collect();
+    let signal_metrics = names
+        .iter()
+        .zip(&lower)
+        .map(|(name, key)| SignalMetrics {
+            name: name.clone(),
+            fan_in: drivers
+                .iter()
+                .filter(|d| d.writes.iter().any(|(w, _)| w == key))
+                .count(),
+            fan_out: drivers
+                .iter()
+                .filter(|d| d.reads.iter().any(|r| r.name == *key))
+                .count(),
+        })
+        .collect();
+
+    EntityMetrics {
+        name: entity.name.clone(),
+        ports: entity.ports.len(),
+        signals,
+        process_count: processes.len(),
+        concurrent_statements: drivers.iter().filter(|d| !d.is_process).count(),
+        statements: drivers.iter().map(|d| d.statements).sum(),
+        max_nesting_depth: processes.iter().map(|p| p.nesting_depth).max().unwrap_or(0),
+        processes,
+        signal_metrics,
+    }
+}
+
+/// 既知の名前（ポート・信号）のうち現れたものの数
+fn count_known<'a>(names: impl Iterator<Item = &'a String>, known: &[String]) -> usize {
+    let mut seen: Vec<&String> = names.filter(|n| known.contains(n)).collect();
+    seen.sort();
+    seen.dedup();
+    seen.len()
+}
+
+/// 指標の一覧を JSON 配列の文字列に変換する
+pub fn to_json(metrics: &[EntityMetrics]) -> String {
+    let mut s =
+        Value::Array(metrics.iter().map(EntityMetrics::to_value).collect()).to_pretty_string();
+    s.push('\n');
+    s
+}
+
+impl EntityMetrics {
+    fn to_value(&self) -> Value {
+        let count = |n: usize| Value::Integer(n as i64);
+        let processes = self
+            .processes
+            .iter()
+            .map(|p| {
+                Value::Object(vec![
+                    (
+                        "label".to_string(),
+                        p.label.clone().map_or(Value::Null, Value::String),
+                    ),
+                    ("statements".to_string(), count(p.statements)),
+                    ("nesting_depth".to_string(), count(p.nesting_depth)),
+                    ("fan_in".to_string(), count(p.fan_in)),
+                    ("fan_out".to_string(), count(p.fan_out)),
+                ])
+            })
+            .collect();
+        let signals = self
+            .signal_metrics
+            .iter()
+            .map(|s| {
+                Value::Object(vec![
+                    ("name".to_string(), Value::String(s.name.clone())),
+                    ("fan_in".to_string(), count(s.fan_in)),
+                    ("fan_out".to_string(), count(s.fan_out)),
+                ])
+            })
+            .collect();
+        Value::Object(vec![
+            ("entity".to_string(), Value::String(self.name.clone())),
+            ("ports".to_string(), count(self.ports)),
+            ("signals".to_string(), count(self.signals)),
+            ("processes".to_string(), count(self.process_count)),
+            (
+                "concurrent_statements".to_string(),
+                count(self.concurrent_statements),
+            ),
+            ("statements".to_string(), count(self.statements)),
+            (
+                "max_nesting_depth".to_string(),
+                count(self.max_nesting_depth),
+            ),
+            ("process_metrics".to_string(), Value::Array(processes)),
+            ("signal_metrics".to_string(), Value::Array(signals)),
+        ])
+    }
+}
@@ -0,0 +1,164 @@
+//! 下位ブロックのモック
+//!
+//! 上位のテストベンチを、すべての下位ブロックが揃う前から動かせるように、選んだ
+//! サブモジュールの代わりになるエンティティとアーキテクチャ `mock` を生成します。
+//! ポートは実装済みならそのエンティティから、未実装なら上位のコンポーネント宣言
+//! （[`crate::testbench::component_declarations`]）から得ます。
+//!
+//! ```
+//! use vig::analyzer::{EntityDef, VhdlType::*};
+//! use vig::mock::{MockStyle, generate_mock};
+//!
+//! let fifo = EntityDef::builder("fifo")
+//!     .input("clk", StdLogic)
+//!     .input("din", StdLogicVector { high: 7.into(), low: 0.into(), ascending: false })
+//!     .output("dout", StdLogicVector { high: 7.into(), low: 0.into(), ascending: false })
+//!     .output("empty", StdLogic)
+//!     .build();
+//! let mock = generate_mock(&fifo, MockStyle::Echo);
+//! assert!(mock.contains("architecture mock of fifo is"));
+//! assert!(mock.contains("dout <= din;"));
+//! assert!(mock.contains("empty <= '0';"));
+//! ```
+
+use crate::analyzer::{ArchitectureDef, EntityDef, PortDef, PortDirection, VhdlType};
+use crate::emit::Emitter;
+use crate::generator::{find_clock_port, find_reset_port, type_default_value};
+
+/// モックの振る舞い
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MockStyle {
+    /// 出力を既定値（`'0'`・`(others => '0')` など）に固定する
+    #[default]
+    Tie,
+    /// 出力に同じ型の入力を順に返す（クロックがあれば1クロック遅らせる）。
+    /// 対応する入力がない出力は既定値に固定する
+    Echo,
+}
+
+impl MockStyle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MockStyle::Tie => "tie",
+            MockStyle::Echo => "echo",
+        }
+    }
+}
+
+impl std::str::FromStr for MockStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "tie" => Ok(MockStyle::Tie),
+            "echo" => Ok(MockStyle::Echo),
+            _ => Err(format!("unknown mock style '{}'", s)),
+        }
+    }
+}
+
+/// モックのエンティティとアーキテクチャ `mock` を生成する
+///
+/// 双方向のポートは `std_logic` 系なら `'Z'` で開放し、それ以外は駆動しません。
+pub fn generate_mock(entity: &EntityDef, style: MockStyle) -> String {
+    generate(
+        entity,
+        style,
+        "mock",
+        &format!("{} のモック（{}）", entity.name, style.as_str()),
+    )
+}
+
+/// 対応するエンティティのないコンポーネントのためのスタブ（エンティティと、出力を
+/// 既定値に固定したアーキテクチャ `stub`）を生成する
+///
+/// 設計を早い段階からエラボレートできるようにするためのものです
+/// （[`crate::project::Project::unbound_components`]）。
+pub fn generate_stub(entity: &EntityDef) -> String {
+    generate(
+        entity,
+        MockStyle::Tie,
+        "stub",
+        &format!("{} のスタブ（実装されるまでの仮のもの）", entity.name),
+    )
+}
+
+fn generate(entity: &EntityDef, style: MockStyle, arch_name: &str, header: &str) -> String {
+    let emitter = Emitter::default();
+    let arch = ArchitectureDef {
+        name: arch_name.to_string(),
+        entity_name: entity.name.clone(),
+        signals: Vec::new(),
+        constants: Vec::new(),
+        instances: Vec::new(),
+        processes: Vec::new(),
+        types: Vec::new(),
+        subprograms: Vec::new(),
+        span: entity.span,
+    };
+
+    let clock = find_clock_port(&entity.ports);
+    let reset = find_reset_port(&entity.ports);
+    // 返す元にする入力（クロック・リセットは除く）
+    let mut sources: Vec<&PortDef> = entity
+        .ports
+        .iter()
+        .filter(|p| p.direction == PortDirection::In)
+        .filter(|p| Some(&p.name) != clock.as_ref() && Some(&p.name) != reset.as_ref())
+        .collect();
+
+    let mut tied = Vec::new();
+    let mut echoed = Vec::new();
+    for port in &entity.ports {
+        match port.direction {
+            PortDirection::Out | PortDirection::Buffer => {
+                let source = (style == MockStyle::Echo)
+                    .then(|| sources.iter().position(|s| s.vhdl_type == port.vhdl_type))
+                    .flatten()
+                    .map(|i| sources.remove(i));
+                match source {
+                    Some(source) => echoed.push(format!("{} <= {};", port.name, source.name)),
+                    None => tied.push(format!(
+                        "{} <= {};",
+                        port.name,
+                        type_default_value(&port.vhdl_type)
+                    )),
+                }
+            }
+            PortDirection::Inout => match &port.vhdl_type {
+                VhdlType::StdLogic => tied.push(format!("{} <= 'Z';", port.name)),
+                VhdlType::StdLogicVector { .. }
+                | VhdlType::Signed { .. }
+                | VhdlType::Unsigned { .. } => {
+                    tied.push(format!("{} <= (others => 'Z');", port.name))
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut body = tied;
+    match (&clock, echoed.is_empty()) {
+        (_, true) => {}
+        (Some(clock), false) => {
+            body.push(String::new());
+            body.push(format!("process ({})", clock));
+            body.push("begin".to_string());
+            body.push(format!("    if rising_edge({}) then", clock));
+            body.extend(echoed.iter().map(|line| format!("        {}", line)));
+            body.push("    end if;".to_string());
+            body.push("end process;".to_string());
+        }
+        (None, false) => body.extend(echoed),
+    }
+
+    let mut out = format!("-- {}\n", header);
+    out.push_str(&emitter.context("ieee", &["ieee.std_logic_1164.all", "ieee.numeric_std.all"]));
+    out.push('\n');
+    out.push_str(&emitter.entity(entity));
+    out.push('\n');
+    out.push_str(&emitter.architecture_with_body(&arch, &body));
+    out
+}
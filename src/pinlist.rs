@@ -0,0 +1,56 @@
+//! 基板設計者への受け渡し用のピンリスト（CSV/TSV）
+
+use crate::analyzer::EntityDef;
+use crate::generator::direction_to_vhdl;
+
+/// ピンリストの形式
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinListFormat {
+    Csv,
+    Tsv,
+}
+
+impl PinListFormat {
+    fn separator(&self) -> char {
+        match self {
+            PinListFormat::Csv => ',',
+            PinListFormat::Tsv => '\t',
+        }
+    }
+}
+
+/// トップエンティティのポートをピンリストとして出力する
+///
+/// 列は `name`・`direction`・`width`・`bank` です。`bank` は記入用の空欄で、
+/// 型からビット幅が決まらないポートの `width` も空欄になります。
+pub fn generate_pin_list(entity: &EntityDef, format: PinListFormat) -> String {
+    let sep = format.separator().to_string();
+    let mut out = ["name", "direction", "width", "bank"].join(&sep);
+    out.push('\n');
+    for port in &entity.ports {
+        let width = port
+            .vhdl_type
+            .bit_width()
+            .map(|w| w.to_string())
+            .unwrap_or_default();
+        let row = [
+            field(&port.name, format),
+            direction_to_vhdl(&port.direction).to_string(),
+            width,
+            String::new(),
+        ];
+        out.push_str(&row.join(&sep));
+        out.push('\n');
+    }
+    out
+}
+
+/// 区切り文字・引用符を含む場合は引用符で囲む
+fn field(value: &str, format: PinListFormat) -> String {
+    if value.contains([format.separator(), '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
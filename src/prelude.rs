@@ -0,0 +1,19 @@
+//! よく使う型と関数をまとめて取り込むためのモジュール
+//!
+//! ```
+//! use vig::prelude::*;
+//!
+//! let result = analyze("entity a is port (clk : in std_logic); end entity;").unwrap();
+//! let tb = TestbenchBuilder::new(&result.entities[0])
+//!     .clock_period_ns(20)
+//!     .build();
+//! assert!(tb.contains("entity a_tb is"));
+//! ```
+
+pub use crate::analyzer::{AnalyzeResult, EntityBuilder, PortDirection, VhdlType};
+pub use crate::diagnostic::{Diagnostic, Severity};
+pub use crate::generator::{
+    AssertSeverity, GenerationPass, ResetPolarity, TbConfig, TestbenchBuilder, TestbenchModel,
+};
+pub use crate::style::TbStyle;
+pub use crate::{Entity, Error, Port, Result, analyze};
@@ -0,0 +1,206 @@
+//! エンティティごとの生成設定（プロファイル）
+//!
+//! vig.toml の `[entity.<エンティティ>]` に書いた設定で、そのエンティティのテストベンチを
+//! 生成するときだけ [`TbConfig`] を上書きします。ファイル中の複数のエンティティを
+//! まとめて生成するとき、クロック周期やリセットの極性をエンティティごとに変えられます。
+//!
+//! ```toml
+//! [entity.uart_tx]
+//! clock_period_ns = 20
+//! reset = "active_low"
+//! coverage = true
+//! fsm = true
+//! exclude_ports = ["scan_en", "test_mode"]
+//! check_severity = "failure"
+//! timeout_ns = 100000
+//! timeout_severity = "failure"
+//! finish_severity = "note"
+//! ```
+
+use crate::generator::{AssertSeverity, ResetPolarity, TbConfig};
+use crate::json::Value;
+use crate::lexer::Span;
+use crate::toml::{self, TomlError};
+
+/// プロファイルの読み込みエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl ProfileError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<TomlError> for ProfileError {
+    fn from(err: TomlError) -> Self {
+        ProfileError::new(err.message, err.span)
+    }
+}
+
+/// エンティティ1つの設定（None の項目は上書きしない）
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityProfile {
+    pub clock_period_ns: Option<u64>,
+    pub reset_polarity: Option<ResetPolarity>,
+    /// トグルカバレッジの計測
+    pub toggle_coverage: Option<bool>,
+    /// ステートマシンのシナリオの生成（抽出は呼び出し側で行う）
+    pub fsm: Option<bool>,
+    /// クロック・リセットの推定やスティミュラス・検査から外すポート
+    pub exclude_ports: Vec<String>,
+    /// 検査が失敗したときの重大度
+    pub check_severity: Option<AssertSeverity>,
+    /// シミュレーションの制限時間（ns）
+    pub timeout_ns: Option<u64>,
+    /// 制限時間を超えたときの重大度
+    pub timeout_severity: Option<AssertSeverity>,
+    /// シミュレーション終了の報告の重大度
+    pub finish_severity: Option<AssertSeverity>,
+}
+
+impl EntityProfile {
+    /// 設定した項目で生成設定を上書きする
+    pub fn apply(&self, config: &mut TbConfig) {
+        if let Some(period) = self.clock_period_ns {
+            config.clock_period_ns = period;
+        }
+        if let Some(polarity) = self.reset_polarity {
+            config.reset_polarity = polarity;
+        }
+        if let Some(enabled) = self.toggle_coverage {
+            config.toggle_coverage = enabled;
+        }
+        config
+            .exclude_ports
+            .extend(self.exclude_ports.iter().cloned());
+        if let Some(severity) = self.check_severity {
+            config.check_severity = severity;
+        }
+        if let Some(timeout) = self.timeout_ns {
+            config.timeout_ns = Some(timeout);
+        }
+        if let Some(severity) = self.timeout_severity {
+            config.timeout_severity = severity;
+        }
+        if let Some(severity) = self.finish_severity {
+            config.finish_severity = severity;
+        }
+    }
+}
+
+/// vig.toml の `[entity.<エンティティ>]` を読み込む（なければ None）
+pub fn from_config(source: &str, entity: &str) -> Result<Option<EntityProfile>, ProfileError> {
+    let root = toml::parse(source)?;
+    let Some(Value::Object(table)) = root.get("entity") else {
+        return Ok(None);
+    };
+    let Some((key, value)) = table.iter().find(|(k, _)| k.eq_ignore_ascii_case(entity)) else {
+        return Ok(None);
+    };
+    // 値の位置はファイル中の文字列から探す
+    let locate = |text: &str| {
+        source
+            .find(text)
+            .map_or(Span::new(0, 0), |pos| Span::new(pos, pos + text.len()))
+    };
+    let Value::Object(fields) = value else {
+        return Err(ProfileError::new(
+            format!("profile of '{}' must be a table", key),
+            locate(&format!("entity.{}", key)),
+        ));
+    };
+    let mut profile = EntityProfile::default();
+    for (field, value) in fields {
+        let bool_value = || {
+            value.as_bool().ok_or_else(|| {
+                ProfileError::new(format!("'{}' must be a boolean", field), locate(field))
+            })
+        };
+        let severity_value = || {
+            value
+                .as_str()
+                .and_then(|s| s.parse::<AssertSeverity>().ok())
+                .ok_or_else(|| {
+                    ProfileError::new(
+                        format!(
+                            "'{}' must be \"note\", \"warning\", \"error\" or \"failure\"",
+                            field
+                        ),
+                        locate(field),
+                    )
+                })
+        };
+        match field.as_str() {
+            "clock_period_ns" => {
+                let period = value.as_i64().filter(|&n| n > 0).ok_or_else(|| {
+                    ProfileError::new(
+                        "'clock_period_ns' must be a positive integer",
+                        locate(field),
+                    )
+                })?;
+                profile.clock_period_ns = Some(period as u64);
+            }
+            "reset" => {
+                profile.reset_polarity = Some(match value.as_str() {
+                    Some("active_high") => ResetPolarity::ActiveHigh,
+                    Some("active_low") => ResetPolarity::ActiveLow,
+                    _ => {
+                        return Err(ProfileError::new(
+                            "'reset' must be \"active_high\" or \"active_low\"",
+                            locate(field),
+                        ));
+                    }
+                });
+            }
+            "timeout_ns" => {
+                let timeout = value.as_i64().filter(|&n| n > 0).ok_or_else(|| {
+                    ProfileError::new("'timeout_ns' must be a positive integer", locate(field))
+                })?;
+                profile.timeout_ns = Some(timeout as u64);
+            }
+            "check_severity" => profile.check_severity = Some(severity_value()?),
+            "timeout_severity" => profile.timeout_severity = Some(severity_value()?),
+            "finish_severity" => profile.finish_severity = Some(severity_value()?),
+            "coverage" => profile.toggle_coverage = Some(bool_value()?),
+            "fsm" => profile.fsm = Some(bool_value()?),
+            "exclude_ports" => {
+                let err = || {
+                    ProfileError::new("'exclude_ports' must be an array of strings", locate(field))
+                };
+                profile.exclude_ports = value
+                    .as_array()
+                    .ok_or_else(err)?
+                    .iter()
+                    .map(|v| v.as_str().map(str::to_string).ok_or_else(err))
+                    .collect::<Result<_, _>>()?;
+            }
+            _ => {
+                return Err(ProfileError::new(
+                    format!("unknown field '{}' in profile of '{}'", field, key),
+                    locate(field),
+                ));
+            }
+        }
+    }
+    Ok(Some(profile))
+}
@@ -0,0 +1,364 @@
+//! 複数ファイル・混在言語の設計の解析
+//!
+//! VHDL のエンティティと Verilog のモジュール（ヘッダのみ、[`crate::verilog`]）を1つの
+//! 設計単位の名前空間にまとめ、アーキテクチャ・モジュール本体のインスタンスを言語をまたいで
+//! 解決します。Verilog のモジュールもポートを VHDL の型にした [`EntityDef`] を持つので、
+//! そのままテストベンチを生成できます。
+//!
+//! 名前の解決は綴りが一致するものを優先し、なければ大文字小文字を区別せずに探します
+//! （VHDL の識別子は大文字小文字を区別しないため）。設計に見つからない単位は、ベンダーの
+//! プリミティブの組み込みスタブ（[`crate::vendor`]）から探します。
+//!
+//! ```
+//! use vig::project::analyze_project;
+//!
+//! let project = analyze_project(&[
+//!     (
+//!         "top.vhd",
+//!         "entity top is port (clk : in std_logic); end entity;\n\
+//!          architecture rtl of top is begin\n\
+//!              u_core: entity work.core port map (clk => clk);\n\
+//!          end architecture;",
+//!     ),
+//!     ("core.v", "module core (input clk); endmodule"),
+//! ])
+//! .unwrap();
+//! let top = project.unit("top").unwrap();
+//! let core = top.instances[0].resolved.unwrap();
+//! assert_eq!(project.units[core].entity.name, "core");
+//! ```
+
+use crate::analyzer::{EntityDef, analyze_vhdl};
+use crate::lexer::Span;
+use crate::testbench::component_declarations;
+use crate::vendor;
+use crate::verilog;
+
+/// 設計の読み込みエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectError {
+    pub message: String,
+    /// エラーのあったファイル
+    pub path: String,
+    pub span: Span,
+}
+
+impl ProjectError {
+    pub fn new(message: impl Into<String>, path: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            path: path.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} at position {}-{}",
+            self.path, self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+/// ソースの言語
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Vhdl,
+    Verilog,
+}
+
+impl Language {
+    /// 拡張子から判定する（`.v` `.sv` `.vh` `.svh` は Verilog、それ以外は VHDL）
+    pub fn from_path(path: &str) -> Self {
+        let lower = path.to_lowercase();
+        if [".v", ".sv", ".vh", ".svh"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+        {
+            Language::Verilog
+        } else {
+            Language::Vhdl
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Language::Vhdl => "VHDL",
+            Language::Verilog => "Verilog",
+        }
+    }
+}
+
+/// インスタンス
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    /// インスタンスのラベル
+    pub label: String,
+    /// 参照している設計単位の名前（書かれたとおり）
+    pub unit: String,
+    /// 解決した設計単位（[`Project::units`] の添字）。設計にも組み込みのスタブにも
+    /// ないものは None
+    pub resolved: Option<usize>,
+    pub span: Span,
+}
+
+/// 設計単位の出どころ
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// 定義しているファイル（[`Project::files`] の添字）
+    File(usize),
+    /// 組み込みのスタブ（ライブラリ名）
+    Builtin(&'static str),
+}
+
+/// 設計単位（VHDL のエンティティまたは Verilog のモジュール）
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignUnit {
+    pub entity: EntityDef,
+    pub language: Language,
+    pub origin: Origin,
+    /// 本体のインスタンス（VHDL はすべてのアーキテクチャの分）
+    pub instances: Vec<Instance>,
+}
+
+/// VHDL のコンポーネント宣言
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDecl {
+    /// 宣言どおりのポートを持つエンティティ
+    pub interface: EntityDef,
+    /// 宣言しているファイル（[`Project::files`] の添字）
+    pub file: usize,
+}
+
+/// 設計全体
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Project {
+    /// ファイルのパス（与えた順）
+    pub files: Vec<String>,
+    pub units: Vec<DesignUnit>,
+    /// すべてのコンポーネント宣言（ファイルの順）
+    pub components: Vec<ComponentDecl>,
+}
+
+impl Project {
+    /// 名前で設計単位を探す（綴りの一致を優先し、なければ大文字小文字を区別しない）
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.units
+            .iter()
+            .position(|u| u.entity.name == name)
+            .or_else(|| {
+                self.units
+                    .iter()
+                    .position(|u| u.entity.name.eq_ignore_ascii_case(name))
+            })
+    }
+
+    pub fn unit(&self, name: &str) -> Option<&DesignUnit> {
+        self.find(name).map(|i| &self.units[i])
+    }
+
+    /// どこからもインスタンス化されていない設計単位（階層の最上位）
+    pub fn tops(&self) -> Vec<usize> {
+        (0..self.units.len())
+            .filter(|&i| {
+                !self
+                    .units
+                    .iter()
+                    .enumerate()
+                    .any(|(j, u)| j != i && u.instances.iter().any(|inst| inst.resolved == Some(i)))
+            })
+            .collect()
+    }
+
+    /// 設計にも組み込みのスタブにもない設計単位を参照するインスタンス（(設計単位, インスタンス) の組）
+    pub fn unresolved(&self) -> Vec<(&DesignUnit, &Instance)> {
+        self.units
+            .iter()
+            .flat_map(|u| {
+                u.instances
+                    .iter()
+                    .filter(|i| i.resolved.is_none())
+                    .map(move |i| (u, i))
+            })
+            .collect()
+    }
+
+    /// 対応する設計単位も組み込みのスタブもないコンポーネント宣言（同じ名前は最初の1つ）
+    pub fn unbound_components(&self) -> Vec<&ComponentDecl> {
+        let mut found: Vec<&ComponentDecl> = Vec::new();
+        for component in &self.components {
+            let name = &component.interface.name;
+            if self.find(name).is_none()
+                && vendor::find_entity(name).is_none()
+                && !found
+                    .iter()
+                    .any(|c| c.interface.name.eq_ignore_ascii_case(name))
+            {
+                found.push(component);
+            }
+        }
+        found
+    }
+
+    /// 最上位からの階層をテキストの木にする
+    pub fn render_hierarchy(&self) -> String {
+        let mut out = String::new();
+        for top in self.tops() {
+            out.push_str(&format!("{}\n", self.describe(top)));
+            self.render_children(top, 1, &mut vec![top], &mut out);
+        }
+        out
+    }
+
+    fn describe(&self, index: usize) -> String {
+        let unit = &self.units[index];
+        match unit.origin {
+            Origin::File(file) => format!(
+                "{} ({}, {})",
+                unit.entity.name,
+                unit.language.as_str(),
+                self.files[file]
+            ),
+            Origin::Builtin(library) => format!("{} ({}, 組み込み)", unit.entity.name, library),
+        }
+    }
+
+    fn render_children(&self, index: usize, depth: usize, path: &mut Vec<usize>, out: &mut String) {
+        for instance in &self.units[index].instances {
+            let indent = "  ".repeat(depth);
+            let Some(child) = instance.resolved else {
+                out.push_str(&format!(
+                    "{}{}: {} (未解決)\n",
+                    indent, instance.label, instance.unit
+                ));
+                continue;
+            };
+            if path.contains(&child) {
+                out.push_str(&format!(
+                    "{}{}: {} (循環)\n",
+                    indent, instance.label, self.units[child].entity.name
+                ));
+                continue;
+            }
+            out.push_str(&format!(
+                "{}{}: {}\n",
+                indent,
+                instance.label,
+                self.describe(child)
+            ));
+            path.push(child);
+            self.render_children(child, depth + 1, path, out);
+            path.pop();
+        }
+    }
+}
+
+/// ファイル（パス, ソース）の組をまとめて解析する
+///
+/// 言語は拡張子で判定します（[`Language::from_path`]）。同じ名前の設計単位が
+/// 2つ以上あるとエラーです。
+pub fn analyze_project(files: &[(&str, &str)]) -> Result<Project, ProjectError> {
+    let mut project = Project::default();
+    for (file, (path, source)) in files.iter().enumerate() {
+        project.files.push(path.to_string());
+        let language = Language::from_path(path);
+        let units: Vec<(EntityDef, Vec<Instance>)> = match language {
+            Language::Vhdl => {
+                let result = analyze_vhdl(source)
+                    .map_err(|e| ProjectError::new(e.message, *path, e.span))?;
+                project.components.extend(
+                    component_declarations(source)
+                        .into_iter()
+                        .map(|interface| ComponentDecl { interface, file }),
+                );
+                let architectures = result.architectures;
+                result
+                    .entities
+                    .into_iter()
+                    .map(|entity| {
+                        let own = architectures
+                            .iter()
+                            .filter(|arch| arch.is_of(&entity.name))
+                            .flat_map(|arch| &arch.instances)
+                            .map(|i| Instance {
+                                label: i.label.clone(),
+                                unit: i.unit.name().to_string(),
+                                resolved: None,
+                                span: i.span,
+                            })
+                            .collect();
+                        (entity, own)
+                    })
+                    .collect()
+            }
+            Language::Verilog => verilog::parse_modules(source)
+                .map_err(|e| ProjectError::new(e.message, *path, e.span))?
+                .into_iter()
+                .map(|module| {
+                    let instances = module
+                        .instances
+                        .iter()
+                        .map(|i| Instance {
+                            label: i.label.clone(),
+                            unit: i.unit.clone(),
+                            resolved: None,
+                            span: i.span,
+                        })
+                        .collect();
+                    (module.to_entity(), instances)
+                })
+                .collect(),
+        };
+        for (entity, instances) in units {
+            if let Some(other) = project.find(&entity.name) {
+                let Origin::File(other_file) = project.units[other].origin else {
+                    unreachable!("組み込みのスタブはファイルを読み終えてから加える");
+                };
+                return Err(ProjectError::new(
+                    format!(
+                        "duplicate design unit '{}' (also defined in {})",
+                        entity.name, project.files[other_file]
+                    ),
+                    *path,
+                    entity.span,
+                ));
+            }
+            project.units.push(DesignUnit {
+                entity,
+                language,
+                origin: Origin::File(file),
+                instances,
+            });
+        }
+    }
+
+    // すべて読んでから言語をまたいで解決する（設計になければ組み込みのスタブを加える）
+    for i in 0..project.units.len() {
+        for j in 0..project.units[i].instances.len() {
+            let name = project.units[i].instances[j].unit.clone();
+            let resolved = project.find(&name).or_else(|| {
+                let (library, entity) = vendor::find_entity(&name)?;
+                project.units.push(DesignUnit {
+                    entity,
+                    language: Language::Vhdl,
+                    origin: Origin::Builtin(library),
+                    instances: Vec::new(),
+                });
+                Some(project.units.len() - 1)
+            });
+            project.units[i].instances[j].resolved = resolved;
+        }
+    }
+    Ok(project)
+}
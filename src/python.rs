@@ -0,0 +1,120 @@
+//! Pythonバインディング（`python` feature, PyO3）
+//!
+//! ```python
+//! import vig
+//! for entity in vig.analyze(open("counter.vhd").read()):
+//!     print(entity.name, [(p.name, p.direction, p.type) for p in entity.ports])
+//!     print(vig.generate_testbench(entity, clock_period_ns=20))
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::analyzer::{self, EntityDef};
+use crate::generator::{self, TbConfig, direction_to_vhdl, type_to_vhdl};
+use crate::interface::InterfaceDef;
+
+/// ポート（読み取り専用）
+#[pyclass(name = "Port", module = "vig", frozen)]
+#[derive(Clone)]
+pub struct PyPort {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    direction: String,
+    #[pyo3(get, name = "type")]
+    vhdl_type: String,
+}
+
+#[pymethods]
+impl PyPort {
+    fn __repr__(&self) -> String {
+        format!(
+            "Port(name={:?}, direction={:?}, type={:?})",
+            self.name, self.direction, self.vhdl_type
+        )
+    }
+}
+
+/// エンティティ（読み取り専用）
+#[pyclass(name = "Entity", module = "vig", frozen)]
+#[derive(Clone)]
+pub struct PyEntity {
+    inner: EntityDef,
+}
+
+#[pymethods]
+impl PyEntity {
+    #[getter]
+    fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    #[getter]
+    fn ports(&self) -> Vec<PyPort> {
+        self.inner
+            .ports
+            .iter()
+            .map(|p| PyPort {
+                name: p.name.clone(),
+                direction: direction_to_vhdl(&p.direction).to_string(),
+                vhdl_type: type_to_vhdl(&p.vhdl_type),
+            })
+            .collect()
+    }
+
+    /// インターフェース記述(JSON)に変換
+    fn to_json(&self) -> String {
+        InterfaceDef::from_entity(&self.inner).to_json()
+    }
+
+    /// インターフェース記述(JSON)から作成
+    #[staticmethod]
+    fn from_json(source: &str) -> PyResult<Self> {
+        InterfaceDef::from_json(source)
+            .map(|i| Self { inner: i.entity })
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Entity(name={:?}, ports={})",
+            self.inner.name,
+            self.inner.ports.len()
+        )
+    }
+}
+
+/// VHDLソースを解析してエンティティのリストを返す
+#[pyfunction]
+fn analyze(source: &str) -> PyResult<Vec<PyEntity>> {
+    analyzer::analyze_vhdl(source)
+        .map(|r| {
+            r.entities
+                .into_iter()
+                .map(|inner| PyEntity { inner })
+                .collect()
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// エンティティのテストベンチを生成する
+#[pyfunction]
+#[pyo3(signature = (entity, clock_period_ns = 10))]
+fn generate_testbench(entity: &PyEntity, clock_period_ns: u64) -> String {
+    let config = TbConfig {
+        clock_period_ns,
+        ..TbConfig::default()
+    };
+    generator::generate_testbench(&entity.inner, &config)
+}
+
+/// Pythonモジュール `vig`
+#[pymodule]
+fn vig(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyEntity>()?;
+    m.add_class::<PyPort>()?;
+    m.add_function(wrap_pyfunction!(analyze, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_testbench, m)?)?;
+    Ok(())
+}
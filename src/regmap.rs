@@ -0,0 +1,1177 @@
+//! レジスタマップ記述からのレジスタファイル生成
+//!
+//! レジスタ名・オフセット・フィールド・アクセス種別を記述したTOML（またはYAML・JSON）から、
+//! AXI4-Lite / Avalon-MM スレーブのレジスタファイル、そのテストベンチ、
+//! Markdownのレジスタマップ表を生成します。
+//!
+//! ```toml
+//! name = "uart_regs"
+//! bus = "axi-lite"        # "axi-lite" / "avalon"（省略時 axi-lite）
+//! data_width = 32         # 32 / 64（省略時 32）
+//!
+//! [[registers]]
+//! name = "ctrl"
+//! offset = 0x00
+//! description = "制御レジスタ"
+//!
+//! [[registers.fields]]
+//! name = "enable"
+//! bits = 0                # ビット番号、または "7:4" の形式
+//! access = "rw"           # "rw" / "ro" / "wo"（省略時はレジスタのアクセス種別）
+//! reset = 0
+//!
+//! [[registers]]
+//! name = "status"
+//! offset = 0x04
+//! access = "ro"           # フィールド省略時はデータ幅全体の1フィールド
+//! ```
+//!
+//! `rw`・`wo` フィールドは出力ポート、`ro` フィールドは入力ポートになります。
+//! ポート名は `<レジスタ名>_<フィールド名>`（フィールド省略時はレジスタ名）です。
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use crate::emit::Emitter;
+use crate::generator::{TbConfig, build_model, simulation_end, type_to_vhdl};
+use crate::json::{self, Value};
+use crate::lexer::Span;
+use crate::toml::{self, TomlError};
+use crate::yaml::{self, YamlError};
+
+/// レジスタ・フィールドのアクセス種別
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    ReadWrite,
+    ReadOnly,
+    WriteOnly,
+}
+
+impl Access {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "rw" => Some(Access::ReadWrite),
+            "ro" => Some(Access::ReadOnly),
+            "wo" => Some(Access::WriteOnly),
+            _ => None,
+        }
+    }
+
+    /// 記述ファイル上の表記（"rw" / "ro" / "wo"）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Access::ReadWrite => "rw",
+            Access::ReadOnly => "ro",
+            Access::WriteOnly => "wo",
+        }
+    }
+}
+
+/// レジスタファイルのバス
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusProtocol {
+    AxiLite,
+    Avalon,
+}
+
+impl BusProtocol {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "axi-lite" | "axi4-lite" | "axilite" => Some(BusProtocol::AxiLite),
+            "avalon" | "avalon-mm" => Some(BusProtocol::Avalon),
+            _ => None,
+        }
+    }
+
+    /// 表示名
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BusProtocol::AxiLite => "AXI4-Lite",
+            BusProtocol::Avalon => "Avalon-MM",
+        }
+    }
+}
+
+/// レジスタ内のビットフィールド
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDef {
+    pub name: String,
+    pub lsb: u32,
+    pub width: u32,
+    pub access: Access,
+    pub reset: u64,
+    pub description: String,
+}
+
+impl FieldDef {
+    pub fn msb(&self) -> u32 {
+        self.lsb + self.width - 1
+    }
+
+    /// レジスタ内でこのフィールドが占めるビット
+    fn mask(&self) -> u64 {
+        ones(self.width) << self.lsb
+    }
+}
+
+/// レジスタ
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterDef {
+    pub name: String,
+    /// バイトオフセット
+    pub offset: u64,
+    pub description: String,
+    pub fields: Vec<FieldDef>,
+}
+
+impl RegisterDef {
+    /// フィールドに対応するユーザー側ポート名
+    pub fn port_name(&self, field: &FieldDef) -> String {
+        if field.name == self.name {
+            self.name.clone()
+        } else {
+            format!("{}_{}", self.name, field.name)
+        }
+    }
+
+    /// リセット値（レジスタ全体）
+    pub fn reset_value(&self) -> u64 {
+        self.fields.iter().fold(0, |v, f| v | (f.reset << f.lsb))
+    }
+
+    /// 指定したアクセス種別のフィールドのビット
+    fn mask_of(&self, access: &[Access]) -> u64 {
+        self.fields
+            .iter()
+            .filter(|f| access.contains(&f.access))
+            .fold(0, |m, f| m | f.mask())
+    }
+
+    /// フィールドのアクセス種別をまとめた表記
+    fn access_summary(&self) -> String {
+        let mut kinds: Vec<&str> = Vec::new();
+        for field in &self.fields {
+            if !kinds.contains(&field.access.as_str()) {
+                kinds.push(field.access.as_str());
+            }
+        }
+        kinds.join("/").to_uppercase()
+    }
+}
+
+/// レジスタマップ
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisterMap {
+    /// 生成するエンティティ名
+    pub name: String,
+    pub bus: BusProtocol,
+    pub data_width: u32,
+    /// バイトアドレスの幅
+    pub addr_width: u32,
+    pub registers: Vec<RegisterDef>,
+}
+
+/// レジスタマップ記述の読み込みエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegmapError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl RegmapError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for RegmapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for RegmapError {}
+
+impl From<TomlError> for RegmapError {
+    fn from(err: TomlError) -> Self {
+        Self::new(err.message, err.span)
+    }
+}
+
+impl From<YamlError> for RegmapError {
+    fn from(err: YamlError) -> Self {
+        Self::new(err.message, err.span)
+    }
+}
+
+impl From<json::JsonError> for RegmapError {
+    fn from(err: json::JsonError) -> Self {
+        Self::new(err.message, err.span)
+    }
+}
+
+/// 下位 `width` ビットが1の値
+fn ones(width: u32) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+/// `value` を表すのに必要なビット数（切り上げ）
+fn ceil_log2(value: u64) -> u32 {
+    if value <= 1 {
+        0
+    } else {
+        64 - (value - 1).leading_zeros()
+    }
+}
+
+impl RegisterMap {
+    /// TOML形式の記述から読み込む
+    pub fn from_toml(source: &str) -> Result<Self, RegmapError> {
+        Self::from_value(&toml::parse(source)?)
+    }
+
+    /// YAML形式の記述から読み込む（キーはTOML形式と同じ）
+    pub fn from_yaml(source: &str) -> Result<Self, RegmapError> {
+        Self::from_value(&yaml::parse(source)?)
+    }
+
+    /// JSON形式の記述から読み込む（キーはTOML形式と同じ）
+    pub fn from_json(source: &str) -> Result<Self, RegmapError> {
+        Self::from_value(&json::parse(source)?)
+    }
+
+    fn from_value(root: &Value) -> Result<Self, RegmapError> {
+        let err = |msg: String| RegmapError::new(msg, Span::new(0, 0));
+        let int = |v: &Value, key: &str, ctx: &str| -> Result<Option<u64>, RegmapError> {
+            match v.get(key) {
+                None => Ok(None),
+                Some(x) => x
+                    .as_i64()
+                    .and_then(|n| u64::try_from(n).ok())
+                    .map(Some)
+                    .ok_or_else(|| {
+                        err(format!(
+                            "'{}' of {} must be a non-negative integer",
+                            key, ctx
+                        ))
+                    }),
+            }
+        };
+        let text =
+            |v: &Value, key: &str| v.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+
+        let name = root
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| err("missing 'name'".to_string()))?
+            .to_string();
+        let bus = match root
+            .get("bus")
+            .map(|b| b.as_str().and_then(BusProtocol::parse))
+        {
+            None => BusProtocol::AxiLite,
+            Some(Some(bus)) => bus,
+            Some(None) => {
+                return Err(err(
+                    "invalid 'bus' (expected \"axi-lite\" or \"avalon\")".to_string()
+                ));
+            }
+        };
+        let data_width = match int(root, "data_width", "register map")? {
+            None => 32,
+            Some(w @ (32 | 64)) => w as u32,
+            Some(w) => {
+                return Err(err(format!(
+                    "unsupported data_width {} (expected 32 or 64)",
+                    w
+                )));
+            }
+        };
+
+        let mut registers: Vec<RegisterDef> = Vec::new();
+        for reg in root
+            .get("registers")
+            .and_then(Value::as_array)
+            .unwrap_or(&[])
+        {
+            let reg_name = reg
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| err("register without 'name'".to_string()))?
+                .to_string();
+            let ctx = format!("register '{}'", reg_name);
+            let offset =
+                int(reg, "offset", &ctx)?.ok_or_else(|| err(format!("{} has no 'offset'", ctx)))?;
+            let reg_access = match reg.get("access") {
+                None => Access::ReadWrite,
+                Some(a) => a
+                    .as_str()
+                    .and_then(Access::parse)
+                    .ok_or_else(|| err(format!("invalid access on {}", ctx)))?,
+            };
+
+            let mut fields = Vec::new();
+            match reg.get("fields").and_then(Value::as_array) {
+                Some(items) => {
+                    for field in items {
+                        let field_name = field
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .ok_or_else(|| err(format!("field without 'name' in {}", ctx)))?
+                            .to_string();
+                        let fctx = format!("field '{}.{}'", reg_name, field_name);
+                        let (msb, lsb): (u32, u32) = match field.get("bits") {
+                            Some(Value::Integer(bit)) => {
+                                let bit = u32::try_from(*bit)
+                                    .map_err(|_| err(format!("invalid 'bits' on {}", fctx)))?;
+                                (bit, bit)
+                            }
+                            Some(Value::String(range)) => range
+                                .split_once(':')
+                                .and_then(|(m, l)| {
+                                    Some((m.trim().parse().ok()?, l.trim().parse().ok()?))
+                                })
+                                .filter(|(m, l)| m >= l)
+                                .ok_or_else(|| err(format!("invalid 'bits' on {}", fctx)))?,
+                            _ => return Err(err(format!("{} has no valid 'bits'", fctx))),
+                        };
+                        if msb >= data_width {
+                            return Err(err(format!("{} exceeds the data width", fctx)));
+                        }
+                        let access = match field.get("access") {
+                            None => reg_access,
+                            Some(a) => a
+                                .as_str()
+                                .and_then(Access::parse)
+                                .ok_or_else(|| err(format!("invalid access on {}", fctx)))?,
+                        };
+                        fields.push(FieldDef {
+                            name: field_name,
+                            lsb,
+                            // msb < data_width なので桁あふれしない
+                            width: msb - lsb + 1,
+                            access,
+                            reset: int(field, "reset", &fctx)?.unwrap_or(0),
+                            description: text(field, "description"),
+                        });
+                    }
+                }
+                // フィールド省略時はデータ幅全体を1フィールドとする
+                None => fields.push(FieldDef {
+                    name: reg_name.clone(),
+                    lsb: 0,
+                    width: data_width,
+                    access: reg_access,
+                    reset: int(reg, "reset", &ctx)?.unwrap_or(0),
+                    description: String::new(),
+                }),
+            }
+
+            registers.push(RegisterDef {
+                name: reg_name,
+                offset,
+                description: text(reg, "description"),
+                fields,
+            });
+        }
+
+        let bytes = u64::from(data_width / 8);
+        let addr_lsb = ceil_log2(bytes);
+        let mut max_end = bytes;
+        for reg in &registers {
+            let end = reg.offset.checked_add(bytes).ok_or_else(|| {
+                err(format!(
+                    "offset 0x{:x} of register '{}' is out of range",
+                    reg.offset, reg.name
+                ))
+            })?;
+            max_end = max_end.max(end);
+        }
+        let needed = ceil_log2(max_end).max(addr_lsb + 1);
+        let addr_width = match int(root, "addr_width", "register map")? {
+            None if needed <= 32 => needed,
+            None => {
+                return Err(err(format!(
+                    "register offsets are out of range (need {} address bits, at most 32)",
+                    needed
+                )));
+            }
+            Some(w) if w >= u64::from(needed) && w <= 32 => w as u32,
+            Some(w) => {
+                return Err(err(format!(
+                    "addr_width {} is out of range (needs at least {})",
+                    w, needed
+                )));
+            }
+        };
+
+        let map = Self {
+            name,
+            bus,
+            data_width,
+            addr_width,
+            registers,
+        };
+        map.validate().map_err(err)?;
+        Ok(map)
+    }
+
+    /// オフセット・フィールド配置の整合性を確認
+    fn validate(&self) -> Result<(), String> {
+        if self.registers.is_empty() {
+            return Err("register map has no registers".to_string());
+        }
+        let bytes = u64::from(self.data_width / 8);
+        for (i, reg) in self.registers.iter().enumerate() {
+            if reg.offset % bytes != 0 {
+                return Err(format!(
+                    "offset 0x{:x} of register '{}' is not aligned to {} bytes",
+                    reg.offset, reg.name, bytes
+                ));
+            }
+            for other in &self.registers[..i] {
+                if other.name == reg.name {
+                    return Err(format!("duplicate register '{}'", reg.name));
+                }
+                if other.offset == reg.offset {
+                    return Err(format!(
+                        "registers '{}' and '{}' share offset 0x{:x}",
+                        other.name, reg.name, reg.offset
+                    ));
+                }
+            }
+            if reg.fields.is_empty() {
+                return Err(format!("register '{}' has no fields", reg.name));
+            }
+            for (j, field) in reg.fields.iter().enumerate() {
+                let end = field.lsb.checked_add(field.width);
+                if field.width == 0 || end.is_none_or(|end| end > self.data_width) {
+                    return Err(format!(
+                        "field '{}.{}' exceeds the data width",
+                        reg.name, field.name
+                    ));
+                }
+                if field.reset > ones(field.width) {
+                    return Err(format!(
+                        "reset value of field '{}.{}' does not fit in {} bits",
+                        reg.name, field.name, field.width
+                    ));
+                }
+                for other in &reg.fields[..j] {
+                    if other.name == field.name {
+                        return Err(format!("duplicate field '{}.{}'", reg.name, field.name));
+                    }
+                    if other.mask() & field.mask() != 0 {
+                        return Err(format!(
+                            "fields '{}' and '{}' of register '{}' overlap",
+                            other.name, field.name, reg.name
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 生成するレジスタファイルのエンティティ
+    pub fn to_entity(&self) -> EntityDef {
+        let span = Span::new(0, 0);
+        let port = |name: &str, direction: PortDirection, vhdl_type: VhdlType| PortDef {
+            name: name.to_string(),
+            direction,
+            vhdl_type,
+            span,
+        };
+        let vector = |width: u32| VhdlType::StdLogicVector {
+            high: (i64::from(width) - 1).into(),
+            low: 0.into(),
+            ascending: false,
+        };
+        use PortDirection::{In, Out};
+        use VhdlType::StdLogic;
+
+        let dw = self.data_width;
+        let aw = self.addr_width;
+        let mut ports = match self.bus {
+            BusProtocol::AxiLite => vec![
+                port("s_axi_aclk", In, StdLogic),
+                port("s_axi_aresetn", In, StdLogic),
+                port("s_axi_awaddr", In, vector(aw)),
+                port("s_axi_awvalid", In, StdLogic),
+                port("s_axi_awready", Out, StdLogic),
+                port("s_axi_wdata", In, vector(dw)),
+                port("s_axi_wstrb", In, vector(dw / 8)),
+                port("s_axi_wvalid", In, StdLogic),
+                port("s_axi_wready", Out, StdLogic),
+                port("s_axi_bresp", Out, vector(2)),
+                port("s_axi_bvalid", Out, StdLogic),
+                port("s_axi_bready", In, StdLogic),
+                port("s_axi_araddr", In, vector(aw)),
+                port("s_axi_arvalid", In, StdLogic),
+                port("s_axi_arready", Out, StdLogic),
+                port("s_axi_rdata", Out, vector(dw)),
+                port("s_axi_rresp", Out, vector(2)),
+                port("s_axi_rvalid", Out, StdLogic),
+                port("s_axi_rready", In, StdLogic),
+            ],
+            BusProtocol::Avalon => vec![
+                port("clk", In, StdLogic),
+                port("reset", In, StdLogic),
+                port("avs_address", In, vector(aw - self.addr_lsb())),
+                port("avs_read", In, StdLogic),
+                port("avs_readdata", Out, vector(dw)),
+                port("avs_write", In, StdLogic),
+                port("avs_writedata", In, vector(dw)),
+                port("avs_byteenable", In, vector(dw / 8)),
+            ],
+        };
+
+        for reg in &self.registers {
+            for field in &reg.fields {
+                let direction = match field.access {
+                    Access::ReadOnly => In,
+                    Access::ReadWrite | Access::WriteOnly => Out,
+                };
+                ports.push(port(&reg.port_name(field), direction, field_type(field)));
+            }
+        }
+
+        EntityDef {
+            name: self.name.clone(),
+            generics: Vec::new(),
+            ports,
+            span,
+        }
+    }
+
+    /// ワードアドレスに変換するための下位ビット数
+    fn addr_lsb(&self) -> u32 {
+        ceil_log2(u64::from(self.data_width / 8))
+    }
+
+    /// データ幅分の16進リテラル
+    fn hex_literal(&self, value: u64) -> String {
+        format!(
+            "x\"{:0width$X}\"",
+            value,
+            width = (self.data_width / 4) as usize
+        )
+    }
+
+    /// レジスタファイルのVHDLコードを生成する
+    pub fn generate_vhdl(&self) -> String {
+        let emitter = Emitter::default();
+        let entity = self.to_entity();
+        let mut out = String::new();
+
+        out.push_str(&format!(
+            "-- {} レジスタファイル ({})\n",
+            self.name,
+            self.bus.display_name()
+        ));
+        out.push_str("-- vig regmap により生成\n\n");
+        out.push_str(
+            &emitter.context("ieee", &["ieee.std_logic_1164.all", "ieee.numeric_std.all"]),
+        );
+        out.push('\n');
+        out.push_str(&emitter.entity(&entity));
+        out.push('\n');
+
+        let lines = match self.bus {
+            BusProtocol::AxiLite => self.axi_architecture(),
+            BusProtocol::Avalon => self.avalon_architecture(),
+        };
+        out.push_str(&format!("architecture rtl of {} is\n", self.name));
+        for line in lines {
+            if !line.is_empty() {
+                out.push_str(&line);
+            }
+            out.push('\n');
+        }
+        out.push_str("end architecture rtl;\n");
+        out
+    }
+
+    /// レジスタ保持用の信号宣言と出力ポートへの接続
+    fn register_signals(&self) -> (Vec<String>, Vec<String>) {
+        let mut decls = vec!["    -- レジスタ".to_string()];
+        let mut assigns = Vec::new();
+        for reg in &self.registers {
+            for field in reg.fields.iter().filter(|f| f.access != Access::ReadOnly) {
+                let port = reg.port_name(field);
+                decls.push(format!(
+                    "    signal reg_{} : {} := {};",
+                    port,
+                    type_to_vhdl(&field_type(field)),
+                    field_literal(field, field.reset)
+                ));
+                assigns.push(format!("    {} <= reg_{};", port, port));
+            }
+        }
+        if decls.len() == 1 {
+            decls.clear();
+        }
+        (decls, assigns)
+    }
+
+    /// リセット時の代入
+    fn reset_assigns(&self, indent: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        for reg in &self.registers {
+            for field in reg.fields.iter().filter(|f| f.access != Access::ReadOnly) {
+                lines.push(format!(
+                    "{}reg_{} <= {};",
+                    indent,
+                    reg.port_name(field),
+                    field_literal(field, field.reset)
+                ));
+            }
+        }
+        lines
+    }
+
+    /// 書き込みデコード（`case` 文、バイトマスクは変数 `wmask`）
+    fn write_case(&self, selector: &str, wdata: &str, indent: &str) -> Vec<String> {
+        let bytes = u64::from(self.data_width / 8);
+        let mut lines = vec![format!("{}case {} is", indent, selector)];
+        for reg in &self.registers {
+            let writable: Vec<&FieldDef> = reg
+                .fields
+                .iter()
+                .filter(|f| f.access != Access::ReadOnly)
+                .collect();
+            if writable.is_empty() {
+                continue;
+            }
+            lines.push(format!("{}    when {} =>", indent, reg.offset / bytes));
+            for field in writable {
+                let target = format!("reg_{}", reg.port_name(field));
+                if field.width == 1 {
+                    lines.push(format!(
+                        "{}        if wmask({}) = '1' then",
+                        indent, field.lsb
+                    ));
+                    lines.push(format!(
+                        "{}            {} <= {}({});",
+                        indent, target, wdata, field.lsb
+                    ));
+                    lines.push(format!("{}        end if;", indent));
+                } else {
+                    let range = format!("({} downto {})", field.msb(), field.lsb);
+                    lines.push(format!(
+                        "{}        {} <= ({} and not wmask{}) or ({}{} and wmask{});",
+                        indent, target, target, range, wdata, range, range
+                    ));
+                }
+            }
+        }
+        lines.push(format!("{}    when others =>", indent));
+        lines.push(format!("{}        null;", indent));
+        lines.push(format!("{}end case;", indent));
+        lines
+    }
+
+    /// 読み出しデコード（`case` 文）
+    fn read_case(&self, selector: &str, rdata: &str, indent: &str) -> Vec<String> {
+        let bytes = u64::from(self.data_width / 8);
+        let mut lines = vec![format!("{}case {} is", indent, selector)];
+        for reg in &self.registers {
+            let readable: Vec<&FieldDef> = reg
+                .fields
+                .iter()
+                .filter(|f| f.access != Access::WriteOnly)
+                .collect();
+            if readable.is_empty() {
+                continue;
+            }
+            lines.push(format!("{}    when {} =>", indent, reg.offset / bytes));
+            for field in readable {
+                let port = reg.port_name(field);
+                let source = match field.access {
+                    Access::ReadOnly => port,
+                    _ => format!("reg_{}", port),
+                };
+                let target = if field.width == 1 {
+                    format!("{}({})", rdata, field.lsb)
+                } else {
+                    format!("{}({} downto {})", rdata, field.msb(), field.lsb)
+                };
+                lines.push(format!("{}        {} <= {};", indent, target, source));
+            }
+        }
+        lines.push(format!("{}    when others =>", indent));
+        lines.push(format!("{}        null;", indent));
+        lines.push(format!("{}end case;", indent));
+        lines
+    }
+
+    /// バイトイネーブルからビットマスクを作る行
+    fn wmask_lines(&self, strobe: &str, indent: &str) -> Vec<String> {
+        vec![
+            format!("{}for i in 0 to {} loop", indent, self.data_width / 8 - 1),
+            format!(
+                "{}    wmask(8 * i + 7 downto 8 * i) := (others => {}(i));",
+                indent, strobe
+            ),
+            format!("{}end loop;", indent),
+        ]
+    }
+
+    fn axi_architecture(&self) -> Vec<String> {
+        let dw = self.data_width;
+        let aw = self.addr_width;
+        let lsb = self.addr_lsb();
+        let (decls, assigns) = self.register_signals();
+        let mut l: Vec<String> = vec![
+            "    signal axi_awready : std_logic := '0';".into(),
+            "    signal axi_wready : std_logic := '0';".into(),
+            "    signal axi_bvalid : std_logic := '0';".into(),
+            "    signal axi_arready : std_logic := '0';".into(),
+            "    signal axi_rvalid : std_logic := '0';".into(),
+            format!(
+                "    signal axi_rdata : std_logic_vector({} downto 0) := (others => '0');",
+                dw - 1
+            ),
+        ];
+        if !decls.is_empty() {
+            l.push(String::new());
+            l.extend(decls);
+        }
+        l.extend([
+            "begin".into(),
+            "    s_axi_awready <= axi_awready;".into(),
+            "    s_axi_wready <= axi_wready;".into(),
+            "    s_axi_bresp <= \"00\";".into(),
+            "    s_axi_bvalid <= axi_bvalid;".into(),
+            "    s_axi_arready <= axi_arready;".into(),
+            "    s_axi_rdata <= axi_rdata;".into(),
+            "    s_axi_rresp <= \"00\";".into(),
+            "    s_axi_rvalid <= axi_rvalid;".into(),
+        ]);
+        if !assigns.is_empty() {
+            l.push(String::new());
+            l.extend(assigns);
+        }
+
+        // 書き込みチャネル（アドレスとデータが揃ったら受け付ける）
+        l.extend([
+            String::new(),
+            "    -- 書き込み".into(),
+            "    write_process: process(s_axi_aclk)".into(),
+            format!(
+                "        variable wmask : std_logic_vector({} downto 0);",
+                dw - 1
+            ),
+            "    begin".into(),
+            "        if rising_edge(s_axi_aclk) then".into(),
+            "            if s_axi_aresetn = '0' then".into(),
+            "                axi_awready <= '0';".into(),
+            "                axi_wready <= '0';".into(),
+            "                axi_bvalid <= '0';".into(),
+        ]);
+        l.extend(self.reset_assigns("                "));
+        l.extend([
+            "            else".into(),
+            "                axi_awready <= '0';".into(),
+            "                axi_wready <= '0';".into(),
+            "                if axi_bvalid = '1' and s_axi_bready = '1' then".into(),
+            "                    axi_bvalid <= '0';".into(),
+            "                end if;".into(),
+            "                if axi_awready = '0' and axi_bvalid = '0' and s_axi_awvalid = '1' and s_axi_wvalid = '1' then".into(),
+            "                    axi_awready <= '1';".into(),
+            "                    axi_wready <= '1';".into(),
+            "                    axi_bvalid <= '1';".into(),
+        ]);
+        l.extend(self.wmask_lines("s_axi_wstrb", "                    "));
+        l.extend(self.write_case(
+            &format!(
+                "to_integer(unsigned(s_axi_awaddr({} downto {})))",
+                aw - 1,
+                lsb
+            ),
+            "s_axi_wdata",
+            "                    ",
+        ));
+        l.extend([
+            "                end if;".into(),
+            "            end if;".into(),
+            "        end if;".into(),
+            "    end process write_process;".into(),
+        ]);
+
+        // 読み出しチャネル
+        l.extend([
+            String::new(),
+            "    -- 読み出し".into(),
+            "    read_process: process(s_axi_aclk)".into(),
+            "    begin".into(),
+            "        if rising_edge(s_axi_aclk) then".into(),
+            "            if s_axi_aresetn = '0' then".into(),
+            "                axi_arready <= '0';".into(),
+            "                axi_rvalid <= '0';".into(),
+            "                axi_rdata <= (others => '0');".into(),
+            "            else".into(),
+            "                axi_arready <= '0';".into(),
+            "                if axi_rvalid = '1' and s_axi_rready = '1' then".into(),
+            "                    axi_rvalid <= '0';".into(),
+            "                end if;".into(),
+            "                if axi_arready = '0' and axi_rvalid = '0' and s_axi_arvalid = '1' then".into(),
+            "                    axi_arready <= '1';".into(),
+            "                    axi_rvalid <= '1';".into(),
+            "                    axi_rdata <= (others => '0');".into(),
+        ]);
+        l.extend(self.read_case(
+            &format!(
+                "to_integer(unsigned(s_axi_araddr({} downto {})))",
+                aw - 1,
+                lsb
+            ),
+            "axi_rdata",
+            "                    ",
+        ));
+        l.extend([
+            "                end if;".into(),
+            "            end if;".into(),
+            "        end if;".into(),
+            "    end process read_process;".into(),
+        ]);
+        l
+    }
+
+    fn avalon_architecture(&self) -> Vec<String> {
+        let dw = self.data_width;
+        let (decls, assigns) = self.register_signals();
+        let mut l = decls;
+        l.push("begin".into());
+        if !assigns.is_empty() {
+            l.extend(assigns);
+            l.push(String::new());
+        }
+
+        l.extend([
+            "    -- 書き込み".into(),
+            "    write_process: process(clk)".into(),
+            format!(
+                "        variable wmask : std_logic_vector({} downto 0);",
+                dw - 1
+            ),
+            "    begin".into(),
+            "        if rising_edge(clk) then".into(),
+            "            if reset = '1' then".into(),
+        ]);
+        l.extend(self.reset_assigns("                "));
+        l.push("            elsif avs_write = '1' then".into());
+        l.extend(self.wmask_lines("avs_byteenable", "                "));
+        l.extend(self.write_case(
+            "to_integer(unsigned(avs_address))",
+            "avs_writedata",
+            "                ",
+        ));
+        l.extend([
+            "            end if;".into(),
+            "        end if;".into(),
+            "    end process write_process;".into(),
+        ]);
+
+        // 読み出しレイテンシ1
+        l.extend([
+            String::new(),
+            "    -- 読み出し（レイテンシ1）".into(),
+            "    read_process: process(clk)".into(),
+            "    begin".into(),
+            "        if rising_edge(clk) then".into(),
+            "            if reset = '1' then".into(),
+            "                avs_readdata <= (others => '0');".into(),
+            "            elsif avs_read = '1' then".into(),
+            "                avs_readdata <= (others => '0');".into(),
+        ]);
+        l.extend(self.read_case(
+            "to_integer(unsigned(avs_address))",
+            "avs_readdata",
+            "                ",
+        ));
+        l.extend([
+            "            end if;".into(),
+            "        end if;".into(),
+            "    end process read_process;".into(),
+        ]);
+        l
+    }
+
+    /// レジスタファイルのテストベンチを生成する
+    ///
+    /// リセット値の読み出し確認と、書き込み可能なビットを反転させた値の
+    /// 書き込み・読み戻し確認を全レジスタに対して行います。
+    pub fn generate_testbench(&self, config: &TbConfig) -> String {
+        let entity = self.to_entity();
+        let mut model = build_model(&entity, config);
+        let period = config.clock_period_ns;
+        let dw = self.data_width;
+
+        model.header = vec![
+            format!(
+                "{} レジスタファイルのテストベンチ ({})",
+                self.name,
+                self.bus.display_name()
+            ),
+            "vig regmap により生成".to_string(),
+        ];
+
+        // (リセット信号, アサート値, ネゲート値)
+        let (declarations, reset) = match self.bus {
+            BusProtocol::AxiLite => (self.axi_tb_procedures(), ("s_axi_aresetn", "'0'", "'1'")),
+            BusProtocol::Avalon => (self.avalon_tb_procedures(), ("reset", "'1'", "'0'")),
+        };
+
+        let mut body = vec![
+            "-- リセット".to_string(),
+            format!("{} <= {};", reset.0, reset.1),
+            format!("wait for {} ns;", period * 2),
+            format!("{} <= {};", reset.0, reset.2),
+            format!("wait for {} ns;", period * 2),
+        ];
+        for reg in &self.registers {
+            let readable = reg.mask_of(&[Access::ReadWrite, Access::ReadOnly]);
+            let writable = reg.mask_of(&[Access::ReadWrite, Access::WriteOnly]);
+            let read_write = reg.mask_of(&[Access::ReadWrite]);
+            // 入力ポート（ro）は0のまま
+            let reset_value = reg.reset_value() & read_write;
+            let addr = format!("16#{:X}#", reg.offset);
+
+            body.push(String::new());
+            body.push(format!("-- {} (0x{:02X})", reg.name, reg.offset));
+            if readable != 0 {
+                body.push(format!("bus_read({}, rdata);", addr));
+                body.push(format!(
+                    "assert rdata = {} report \"{}: unexpected reset value\" severity {};",
+                    self.hex_literal(reset_value),
+                    reg.name,
+                    config.check_severity.as_str()
+                ));
+            }
+            if writable != 0 {
+                let pattern = !reg.reset_value() & writable;
+                body.push(format!(
+                    "bus_write({}, {});",
+                    addr,
+                    self.hex_literal(pattern)
+                ));
+                if read_write != 0 {
+                    body.push(format!("bus_read({}, rdata);", addr));
+                    body.push(format!(
+                        "assert rdata = {} report \"{}: read back mismatch\" severity {};",
+                        self.hex_literal(pattern & read_write),
+                        reg.name,
+                        config.check_severity.as_str()
+                    ));
+                }
+            }
+        }
+        body.push(String::new());
+        body.extend(simulation_end(
+            &config.target_ports(&entity).ports,
+            config,
+            &model.done_signal,
+        ));
+
+        if let Some(stim) = model.process_mut("stim_process") {
+            stim.comment = Some("レジスタアクセスのテスト".to_string());
+            stim.declarations = declarations;
+            stim.declarations.push(format!(
+                "variable rdata : std_logic_vector({} downto 0);",
+                dw - 1
+            ));
+            stim.body = body;
+        }
+        model.render()
+    }
+
+    /// AXI4-Liteのバスアクセス手続き
+    fn axi_tb_procedures(&self) -> Vec<String> {
+        let dw = self.data_width;
+        let aw = self.addr_width;
+        vec![
+            format!(
+                "procedure bus_write(addr : in natural; data : in std_logic_vector({} downto 0)) is",
+                dw - 1
+            ),
+            "begin".into(),
+            format!(
+                "    s_axi_awaddr <= std_logic_vector(to_unsigned(addr, {}));",
+                aw
+            ),
+            "    s_axi_awvalid <= '1';".into(),
+            "    s_axi_wdata <= data;".into(),
+            "    s_axi_wstrb <= (others => '1');".into(),
+            "    s_axi_wvalid <= '1';".into(),
+            "    s_axi_bready <= '1';".into(),
+            "    wait until rising_edge(s_axi_aclk) and s_axi_awready = '1';".into(),
+            "    s_axi_awvalid <= '0';".into(),
+            "    s_axi_wvalid <= '0';".into(),
+            "    if s_axi_bvalid = '0' then".into(),
+            "        wait until rising_edge(s_axi_aclk) and s_axi_bvalid = '1';".into(),
+            "    end if;".into(),
+            "    s_axi_bready <= '0';".into(),
+            "end procedure;".into(),
+            String::new(),
+            format!(
+                "procedure bus_read(addr : in natural; data : out std_logic_vector({} downto 0)) is",
+                dw - 1
+            ),
+            "begin".into(),
+            format!(
+                "    s_axi_araddr <= std_logic_vector(to_unsigned(addr, {}));",
+                aw
+            ),
+            "    s_axi_arvalid <= '1';".into(),
+            "    s_axi_rready <= '1';".into(),
+            "    wait until rising_edge(s_axi_aclk) and s_axi_arready = '1';".into(),
+            "    s_axi_arvalid <= '0';".into(),
+            "    if s_axi_rvalid = '0' then".into(),
+            "        wait until rising_edge(s_axi_aclk) and s_axi_rvalid = '1';".into(),
+            "    end if;".into(),
+            "    data := s_axi_rdata;".into(),
+            "    s_axi_rready <= '0';".into(),
+            "end procedure;".into(),
+            String::new(),
+        ]
+    }
+
+    /// Avalon-MMのバスアクセス手続き
+    fn avalon_tb_procedures(&self) -> Vec<String> {
+        let dw = self.data_width;
+        let words = self.addr_width - self.addr_lsb();
+        let bytes = self.data_width / 8;
+        vec![
+            format!(
+                "procedure bus_write(addr : in natural; data : in std_logic_vector({} downto 0)) is",
+                dw - 1
+            ),
+            "begin".into(),
+            format!(
+                "    avs_address <= std_logic_vector(to_unsigned(addr / {}, {}));",
+                bytes, words
+            ),
+            "    avs_writedata <= data;".into(),
+            "    avs_byteenable <= (others => '1');".into(),
+            "    avs_write <= '1';".into(),
+            "    wait until rising_edge(clk);".into(),
+            "    avs_write <= '0';".into(),
+            "end procedure;".into(),
+            String::new(),
+            format!(
+                "procedure bus_read(addr : in natural; data : out std_logic_vector({} downto 0)) is",
+                dw - 1
+            ),
+            "begin".into(),
+            format!(
+                "    avs_address <= std_logic_vector(to_unsigned(addr / {}, {}));",
+                bytes, words
+            ),
+            "    avs_read <= '1';".into(),
+            "    wait until rising_edge(clk);".into(),
+            "    avs_read <= '0';".into(),
+            "    wait until falling_edge(clk);".into(),
+            "    data := avs_readdata;".into(),
+            "end procedure;".into(),
+            String::new(),
+        ]
+    }
+
+    /// Markdownのレジスタマップ表を生成する
+    pub fn generate_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("# {} レジスタマップ\n\n", self.name));
+        out.push_str(&format!("- バス: {}\n", self.bus.display_name()));
+        out.push_str(&format!("- データ幅: {} bit\n", self.data_width));
+        out.push_str(&format!("- アドレス幅: {} bit\n\n", self.addr_width));
+
+        out.push_str("| オフセット | 名前 | アクセス | リセット値 | 説明 |\n");
+        out.push_str("|---|---|---|---|---|\n");
+        for reg in &self.registers {
+            out.push_str(&format!(
+                "| 0x{:02X} | {} | {} | {} | {} |\n",
+                reg.offset,
+                reg.name,
+                reg.access_summary(),
+                self.hex_value(reg.reset_value()),
+                reg.description
+            ));
+        }
+
+        for reg in &self.registers {
+            out.push_str(&format!("\n## {} (0x{:02X})\n\n", reg.name, reg.offset));
+            if !reg.description.is_empty() {
+                out.push_str(&format!("{}\n\n", reg.description));
+            }
+            out.push_str("| ビット | フィールド | アクセス | リセット値 | 説明 |\n");
+            out.push_str("|---|---|---|---|---|\n");
+            let mut fields: Vec<&FieldDef> = reg.fields.iter().collect();
+            fields.sort_by_key(|f| std::cmp::Reverse(f.lsb));
+            for field in fields {
+                let bits = if field.width == 1 {
+                    format!("{}", field.lsb)
+                } else {
+                    format!("{}:{}", field.msb(), field.lsb)
+                };
+                out.push_str(&format!(
+                    "| {} | {} | {} | 0x{:X} | {} |\n",
+                    bits,
+                    field.name,
+                    field.access.as_str().to_uppercase(),
+                    field.reset,
+                    field.description
+                ));
+            }
+        }
+        out
+    }
+
+    /// データ幅分の16進表記（"0x" 付き）
+    fn hex_value(&self, value: u64) -> String {
+        format!(
+            "0x{:0width$X}",
+            value,
+            width = (self.data_width / 4) as usize
+        )
+    }
+}
+
+/// フィールドの型
+fn field_type(field: &FieldDef) -> VhdlType {
+    if field.width == 1 {
+        VhdlType::StdLogic
+    } else {
+        VhdlType::StdLogicVector {
+            high: (i64::from(field.width) - 1).into(),
+            low: 0.into(),
+            ascending: false,
+        }
+    }
+}
+
+/// フィールド幅の値リテラル（'0' / x"1B" / "101"）
+fn field_literal(field: &FieldDef, value: u64) -> String {
+    if field.width == 1 {
+        format!("'{}'", value & 1)
+    } else if field.width.is_multiple_of(4) {
+        format!(
+            "x\"{:0width$X}\"",
+            value,
+            width = (field.width / 4) as usize
+        )
+    } else {
+        format!("\"{:0width$b}\"", value, width = field.width as usize)
+    }
+}
@@ -0,0 +1,235 @@
+//! 複数エンティティの回帰テスト
+//!
+//! フィルタに合うエンティティごとにテストベンチを用意し（設計ファイルに `<エンティティ>_tb`
+//! があればそれを使い、なければ生成する）、シミュレータのバックエンドで並列に実行して
+//! 結果（[`SimReport`]）と実行時間を集めます。
+//!
+//! ```
+//! use vig::regress::matches_filter;
+//!
+//! assert!(matches_filter("uart_tx", "uart_*"));
+//! assert!(matches_filter("fifo", "uart_*,fifo"));
+//! assert!(!matches_filter("spi", "uart_*,fifo"));
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::analyzer::{EntityDef, analyze_vhdl};
+use crate::generator::{AssertSeverity, TbConfig, build_model};
+use crate::ident;
+use crate::sim::{self, SimJob, Simulator};
+use crate::simlog::{SimReport, SimStatus, format_time};
+
+/// 1つのテストベンチの実行内容
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bench {
+    /// 対象のエンティティ名
+    pub entity: String,
+    /// テストベンチ（トップ）のエンティティ名
+    pub top: String,
+    /// コンパイルするソース（依存順）
+    pub sources: Vec<PathBuf>,
+    /// 生成したテストベンチ（None なら `sources` の既存のものを使う）
+    pub generated: Option<String>,
+}
+
+/// 1つのテストベンチの実行結果
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub entity: String,
+    pub top: String,
+    pub report: SimReport,
+    /// コンパイルから実行までにかかった時間
+    pub elapsed: Duration,
+}
+
+/// 名前がフィルタに合うか（`*` `?` のワイルドカード、`,` 区切りでいずれか、大文字小文字を区別しない）
+pub fn matches_filter(name: &str, filter: &str) -> bool {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    filter.split(',').map(str::trim).any(|pattern| {
+        let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+        wildcard(&pattern, &name)
+    })
+}
+
+fn wildcard(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| wildcard(rest, &name[i..])),
+        Some((&c, rest)) => name
+            .split_first()
+            .is_some_and(|(&n, name)| (c == '?' || c == n) && wildcard(rest, name)),
+    }
+}
+
+/// 設計ファイル（パスとソース）から、フィルタに合うエンティティのテストベンチを用意する
+///
+/// ポートのないエンティティ（テストベンチ自身）は対象にしません。生成するテストベンチの設定は
+/// `config_for` でエンティティごとに決めます（エンティティを定義するファイルのパスも渡します）。
+pub fn plan(
+    designs: &[(PathBuf, String)],
+    filter: &str,
+    config_for: impl Fn(&Path, &EntityDef) -> TbConfig,
+) -> Vec<Bench> {
+    let sources: Vec<&str> = designs.iter().map(|(_, s)| s.as_str()).collect();
+    let ordered: Vec<PathBuf> = sim::compile_order(&sources)
+        .into_iter()
+        .map(|i| designs[i].0.clone())
+        .collect();
+    let tops = sim::top_candidates(&sources);
+
+    let mut benches = Vec::new();
+    for (path, source) in designs {
+        let Ok(result) = analyze_vhdl(source) else {
+            continue;
+        };
+        for entity in &result.entities {
+            if entity.ports.is_empty() || !matches_filter(&entity.name, filter) {
+                continue;
+            }
+            let top = ident::suffixed(&entity.name, "_tb");
+            let existing = tops.iter().any(|t| t.eq_ignore_ascii_case(&top));
+            // 生成する場合のトップはポートと重ならないよう変えた名前になることがある
+            let (top, generated) = if existing {
+                (top, None)
+            } else {
+                let model = build_model(entity, &config_for(path, entity));
+                (model.name.clone(), Some(model.render()))
+            };
+            benches.push(Bench {
+                entity: entity.name.clone(),
+                generated,
+                // 拡張識別子は大文字・小文字を区別するのでそのまま
+                top: if ident::is_extended(&top) {
+                    top
+                } else {
+                    top.to_lowercase()
+                },
+                sources: ordered.clone(),
+            });
+        }
+    }
+    benches
+}
+
+/// 生成したテストベンチを `dir/<トップ>.vhd` に書き出し、ソースの最後に加える
+///
+/// ファイル名はトップを [`ident::file_stem`] にしたものです（`\my top_tb\` なら `my_top_tb.vhd`）。
+pub fn write_testbenches(benches: &mut [Bench], dir: &Path) -> crate::Result<()> {
+    std::fs::create_dir_all(dir).map_err(|e| crate::Error::io(dir, e))?;
+    for bench in benches {
+        let Some(code) = &bench.generated else {
+            continue;
+        };
+        let path = dir.join(format!("{}.vhd", ident::file_stem(&bench.top)));
+        std::fs::write(&path, code).map_err(|e| crate::Error::io(&path, e))?;
+        bench.sources.push(path);
+    }
+    Ok(())
+}
+
+/// テストベンチを `jobs` 並列で実行する（結果は `benches` の順）
+///
+/// ライブラリ work はテストベンチごとに `template.work_dir` の下に分けます
+/// （ディレクトリ名はトップを [`ident::file_stem`] にしたもの）。
+pub fn run(
+    benches: &[Bench],
+    simulator: &(dyn Simulator + Sync),
+    template: &SimJob,
+    jobs: usize,
+) -> Vec<BenchResult> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<BenchResult>>> = Mutex::new(vec![None; benches.len()]);
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, benches.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(bench) = benches.get(i) else {
+                        break;
+                    };
+                    let result = run_bench(bench, simulator, template);
+                    results.lock().unwrap()[i] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn run_bench(bench: &Bench, simulator: &dyn Simulator, template: &SimJob) -> BenchResult {
+    let mut job = template.clone();
+    job.top = bench.top.clone();
+    job.sources = bench.sources.clone();
+    job.work_dir = template.work_dir.join(ident::file_stem(&bench.top));
+    let start = Instant::now();
+    let report = match simulator.simulate(&job) {
+        Ok(output) => SimReport::from_output(&output),
+        Err(err) => SimReport::from_error(err.to_string()),
+    };
+    BenchResult {
+        entity: bench.entity.clone(),
+        top: bench.top.clone(),
+        report,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// 結果の表と集計の行
+///
+/// ```text
+/// bench       result     errors  warnings  sim time    wall
+/// counter_tb  passed          0         1   1300 ns  0.42 s
+/// uart_tx_tb  FAILED          2         0    850 ns  0.57 s
+///
+/// 1 passed, 1 failed, 0 timed out
+/// ```
+pub fn summary_table(results: &[BenchResult]) -> String {
+    let width = results
+        .iter()
+        .map(|r| r.top.len())
+        .max()
+        .unwrap_or(0)
+        .max("bench".len());
+    let mut out = format!(
+        "{:<width$}  {:<9}  {:>6}  {:>8}  {:>8}  {:>6}\n",
+        "bench", "result", "errors", "warnings", "sim time", "wall"
+    );
+    for result in results {
+        let report = &result.report;
+        let status = match report.status {
+            SimStatus::Passed => "passed",
+            SimStatus::Failed => "FAILED",
+            SimStatus::TimedOut => "TIMED OUT",
+        };
+        let errors = report.failures().count() + report.tool_errors.len();
+        let sim_time = report.end_time_fs.map_or("-".to_string(), format_time);
+        out.push_str(&format!(
+            "{:<width$}  {:<9}  {:>6}  {:>8}  {:>8}  {:>6}\n",
+            result.top,
+            status,
+            errors,
+            report.count(AssertSeverity::Warning),
+            sim_time,
+            format!("{:.2} s", result.elapsed.as_secs_f64()),
+        ));
+    }
+    let count = |status: SimStatus| results.iter().filter(|r| r.report.status == status).count();
+    out.push_str(&format!(
+        "\n{} passed, {} failed, {} timed out\n",
+        count(SimStatus::Passed),
+        count(SimStatus::Failed),
+        count(SimStatus::TimedOut),
+    ));
+    out
+}
@@ -0,0 +1,179 @@
+//! 診断メッセージの表示
+//!
+//! [`Diagnostic`] を [`SourceMap`] と組み合わせて、該当行と下線付きのテキスト、
+//! ANSIカラー付きのテキスト、またはJSONに整形します。
+//! CLI・リンタ・エディタ連携はすべてこのモジュールを通して同じ表示にします。
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::json::Value;
+use crate::lexer::Span;
+use crate::source_map::SourceMap;
+
+/// 診断の出力形式
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// 該当行と下線付きのテキスト
+    Plain,
+    /// Plain にANSIカラーを付けたもの
+    Ansi,
+    /// 1診断1オブジェクトのJSON配列
+    Json,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+
+fn severity_color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "\x1b[1;31m",
+        Severity::Warning => "\x1b[1;33m",
+        Severity::Info => "\x1b[1;36m",
+    }
+}
+
+/// 診断をまとめて整形する
+///
+/// テキスト形式は診断ごとに空行で区切り、JSON形式は配列1つにします。
+pub fn render(diagnostics: &[Diagnostic], map: &SourceMap, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Json => {
+            let items = diagnostics.iter().map(|d| to_json(d, map)).collect();
+            format!("{}\n", Value::Array(items))
+        }
+        _ => diagnostics
+            .iter()
+            .map(|d| render_one(d, map, format == ReportFormat::Ansi))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// 1件の診断をテキストに整形する
+///
+/// ```text
+/// error: expected Colon, found StdLogic 'std_logic'
+///  --> counter.vhd:7:15
+///   |
+/// 7 |         clk   std_logic;
+///   |               ^^^^^^^^^
+/// ```
+///
+/// 関連する位置は `note:` に続けて同じ形式（下線は `-`）で示します。
+fn render_one(diagnostic: &Diagnostic, map: &SourceMap, color: bool) -> String {
+    let paint = |style: &str, text: &str| paint(color, style, text);
+    let style = severity_color(diagnostic.severity);
+    let mut out = String::new();
+    out.push_str(&format!(
+        "{}{}\n",
+        paint(style, &diagnostic.severity.to_string()),
+        paint(BOLD, &format!(": {}", diagnostic.message))
+    ));
+    out.push_str(&snippet(map, diagnostic.span, '^', style, color));
+    for (span, message) in &diagnostic.related {
+        out.push_str(&format!(
+            "{}{}\n",
+            paint(BLUE, "note"),
+            paint(BOLD, &format!(": {}", message))
+        ));
+        out.push_str(&snippet(map, *span, '-', BLUE, color));
+    }
+    out
+}
+
+fn paint(color: bool, style: &str, text: &str) -> String {
+    if color {
+        format!("{}{}{}", style, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+/// 位置・該当行・下線の部分
+fn snippet(map: &SourceMap, span: Span, marker: char, style: &str, color: bool) -> String {
+    let paint = |style: &str, text: &str| paint(color, style, text);
+    let (line, col) = map.line_col(span.start);
+    let (end_line, end_col) = map.line_col(span.end);
+    let text = map.line_text(line).unwrap_or("");
+    let gutter = " ".repeat(line.to_string().len());
+
+    // 下線は開始行の範囲だけに引く（タブは揃えるためにそのまま残す）
+    let padding: String = text
+        .chars()
+        .take(col - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let line_len = text.chars().count();
+    let width = if end_line == line {
+        end_col.saturating_sub(col)
+    } else {
+        (line_len + 1).saturating_sub(col)
+    }
+    .max(1);
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "{}{} {}:{}:{}\n",
+        gutter,
+        paint(BLUE, "-->"),
+        map.name(),
+        line,
+        col
+    ));
+    out.push_str(&format!("{} {}\n", gutter, paint(BLUE, "|")));
+    let code = format!(
+        "{} {} {}",
+        paint(BLUE, &line.to_string()),
+        paint(BLUE, "|"),
+        text
+    );
+    out.push_str(code.trim_end());
+    out.push('\n');
+    out.push_str(&format!(
+        "{} {} {}{}\n",
+        gutter,
+        paint(BLUE, "|"),
+        padding,
+        paint(style, &marker.to_string().repeat(width))
+    ));
+    out
+}
+
+fn to_json(diagnostic: &Diagnostic, map: &SourceMap) -> Value {
+    let (line, column) = map.line_col(diagnostic.span.start);
+    let (end_line, end_column) = map.line_col(diagnostic.span.end);
+    let int = |n: usize| Value::Integer(n as i64);
+    let mut members = vec![
+        ("file".into(), Value::String(map.name().to_string())),
+        (
+            "severity".into(),
+            Value::String(diagnostic.severity.to_string()),
+        ),
+        ("message".into(), Value::String(diagnostic.message.clone())),
+        ("line".into(), int(line)),
+        ("column".into(), int(column)),
+        ("end_line".into(), int(end_line)),
+        ("end_column".into(), int(end_column)),
+        ("start".into(), int(diagnostic.span.start)),
+        ("end".into(), int(diagnostic.span.end)),
+    ];
+    if !diagnostic.related.is_empty() {
+        let related = diagnostic
+            .related
+            .iter()
+            .map(|(span, message)| {
+                let (line, column) = map.line_col(span.start);
+                Value::Object(vec![
+                    ("message".into(), Value::String(message.clone())),
+                    ("line".into(), int(line)),
+                    ("column".into(), int(column)),
+                    ("start".into(), int(span.start)),
+                    ("end".into(), int(span.end)),
+                ])
+            })
+            .collect();
+        members.push(("related".into(), Value::Array(related)));
+    }
+    Value::Object(members)
+}
@@ -0,0 +1,528 @@
+//! トークンの位置を使ったソースの書き換え
+//!
+//! 元のソースに対して位置を指定した編集（置き換え・挿入・削除）を積み、最後にまとめて
+//! 適用します。編集しない部分のインデント・コメント・大文字小文字はそのまま残ります。
+//! ポートの追加・識別子の名前変更・範囲の変更といったよく使う編集も用意しています。
+//!
+//! ```
+//! use vig::rewrite::Rewriter;
+//!
+//! let source = "entity e is\n  port (\n    a : in std_logic_vector(3 downto 0)\n  );\nend entity;\n";
+//! let mut rw = Rewriter::new(source);
+//! rw.insert_port("e", "y : out std_logic").unwrap();
+//! rw.change_range("a", "7 downto 0").unwrap();
+//! rw.rename("a", "din");
+//! assert_eq!(
+//!     rw.finish().unwrap(),
+//!     "entity e is\n  port (\n    din : in std_logic_vector(7 downto 0);\n    y : out std_logic\n  );\nend entity;\n"
+//! );
+//! ```
+
+use crate::lexer::{Lexer, Span, Token, TokenKind};
+use crate::source_map::LineEnding;
+
+/// 書き換えのエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl RewriteError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for RewriteError {}
+
+/// テキストの置き換え（挿入は空の範囲、削除は空のテキスト）
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub span: Span,
+    pub text: String,
+}
+
+impl Edit {
+    pub fn replace(span: Span, text: impl Into<String>) -> Self {
+        Self {
+            span,
+            text: text.into(),
+        }
+    }
+
+    pub fn insert(at: usize, text: impl Into<String>) -> Self {
+        Self::replace(Span::new(at, at), text)
+    }
+
+    pub fn delete(span: Span) -> Self {
+        Self::replace(span, "")
+    }
+
+    pub(crate) fn new(start: usize, end: usize, text: impl Into<String>) -> Self {
+        Self::replace(Span::new(start, end), text)
+    }
+}
+
+/// 編集をまとめて適用する
+///
+/// 同じ位置への挿入は渡した順に並びます。範囲が重なる編集やソースの外を指す編集はエラーです。
+/// 編集のテキストの改行はソースで多く使われている改行コード（LF か CRLF）にそろえます。
+pub fn apply(source: &str, mut edits: Vec<Edit>) -> Result<String, RewriteError> {
+    // 同じ位置では挿入を先に、削除・置き換えを後に並べる
+    edits.sort_by_key(|e| (e.span.start, e.span.end));
+    for pair in edits.windows(2) {
+        if pair[1].span.start < pair[0].span.end {
+            return Err(RewriteError::new("overlapping edits", pair[1].span));
+        }
+    }
+    if let Some(edit) = edits.iter().find(|e| {
+        e.span.start > e.span.end
+            || e.span.end > source.len()
+            || !source.is_char_boundary(e.span.start)
+            || !source.is_char_boundary(e.span.end)
+    }) {
+        return Err(RewriteError::new("edit out of range", edit.span));
+    }
+    let line_ending = LineEnding::detect(source);
+    let mut out = String::with_capacity(source.len());
+    let mut pos = 0;
+    for edit in &edits {
+        out.push_str(&source[pos..edit.span.start]);
+        if line_ending == LineEnding::Lf {
+            out.push_str(&edit.text);
+        } else {
+            out.push_str(&line_ending.apply(&edit.text));
+        }
+        pos = edit.span.end;
+    }
+    out.push_str(&source[pos..]);
+    Ok(out)
+}
+
+/// 編集を積んでまとめて適用する書き換え器
+#[derive(Debug, Clone)]
+pub struct Rewriter<'a> {
+    source: &'a str,
+    /// コメントを除くトークン
+    tokens: Vec<Token<'a>>,
+    edits: Vec<Edit>,
+}
+
+impl<'a> Rewriter<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let tokens = Lexer::new(source)
+            .filter_map(|r| r.ok())
+            .filter(|t| t.kind != TokenKind::Comment && t.kind != TokenKind::Eof)
+            .collect();
+        Self {
+            source,
+            tokens,
+            edits: Vec::new(),
+        }
+    }
+
+    /// 積んだ編集
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    pub fn edit(&mut self, edit: Edit) -> &mut Self {
+        self.edits.push(edit);
+        self
+    }
+
+    pub fn replace(&mut self, span: Span, text: impl Into<String>) -> &mut Self {
+        self.edit(Edit::replace(span, text))
+    }
+
+    pub fn insert(&mut self, at: usize, text: impl Into<String>) -> &mut Self {
+        self.edit(Edit::insert(at, text))
+    }
+
+    pub fn delete(&mut self, span: Span) -> &mut Self {
+        self.edit(Edit::delete(span))
+    }
+
+    /// 識別子の名前を変える（大文字小文字は区別しない、コメントと文字列は対象外）
+    ///
+    /// 置き換えた箇所の数を返します。
+    pub fn rename(&mut self, from: &str, to: &str) -> usize {
+        let spans: Vec<Span> = self
+            .tokens
+            .iter()
+            .filter(|t| t.kind == TokenKind::Identifier && t.text.eq_ignore_ascii_case(from))
+            .map(|t| t.span)
+            .collect();
+        for span in &spans {
+            self.replace(*span, to);
+        }
+        spans.len()
+    }
+
+    /// エンティティのポート宣言の最後にポートを追加する
+    ///
+    /// `decl` は `name : in std_logic` のような宣言1つです。既存の宣言と同じ改行・インデントで
+    /// 並べ、ポート節がなければ作ります。
+    pub fn insert_port(&mut self, entity: &str, decl: &str) -> Result<(), RewriteError> {
+        let (start, end, port) = self.entity_ports(entity)?;
+        let Some(port) = port else {
+            // ポート節を作る（generic 節があればその後ろ）
+            let generic_end = (start..end)
+                .rev()
+                .find(|&i| self.tokens[i].kind == TokenKind::Semicolon)
+                .map(|i| self.tokens[i].span.end);
+            let at = generic_end.unwrap_or(self.tokens[start + 2].span.end);
+            let indent = line_indent(self.source, self.tokens[start].span.start);
+            self.insert(
+                at,
+                format!("\n{0}    port (\n{0}        {1}\n{0}    );", indent, decl),
+            );
+            return Ok(());
+        };
+        let open = self.tokens[port + 1].span.end;
+        let items = list_items(&self.tokens[port + 2..], TokenKind::Semicolon);
+        let changes: Vec<ItemChange> = items.iter().map(|_| ItemChange::Keep).collect();
+        let edits = list_edits(
+            self.source,
+            open,
+            &items,
+            &changes,
+            &[decl.to_string()],
+            ';',
+        );
+        self.edits.extend(edits);
+        Ok(())
+    }
+
+    /// エンティティのポート宣言を `order` の順に並べ替える
+    ///
+    /// `order` はポート名の並び（大文字小文字は区別しない）で、挙げなかったポートは元の順で
+    /// 後ろに続けます。名前を並べた宣言（`a, b : in std_logic`）は、並べ替えた後も続いていれば
+    /// そのまま、離れる場合は1つずつの宣言に分けます。宣言の間のコメントは元の位置に残ります。
+    pub fn reorder_ports(&mut self, entity: &str, order: &[&str]) -> Result<(), RewriteError> {
+        let (start, _, port) = self.entity_ports(entity)?;
+        let Some(port) = port else {
+            return Ok(());
+        };
+        let open = self.tokens[port + 1].span.end;
+        let items = list_items(&self.tokens[port + 2..], TokenKind::Semicolon);
+
+        // 宣言ごとの名前と `:` の後ろ
+        let mut decls: Vec<(Vec<String>, &str)> = Vec::new();
+        for item in &items {
+            let tokens: Vec<&Token> = self
+                .tokens
+                .iter()
+                .filter(|t| t.span.start >= item.start && t.span.end <= item.end)
+                .collect();
+            let colon = tokens
+                .iter()
+                .position(|t| t.kind == TokenKind::Colon)
+                .ok_or_else(|| RewriteError::new("port declaration without ':'", *item))?;
+            let names = tokens[..colon]
+                .iter()
+                .filter(|t| t.kind == TokenKind::Identifier)
+                .map(|t| t.text.to_string())
+                .collect();
+            let rest = self.source[tokens[colon].span.end..item.end].trim_start();
+            decls.push((names, rest));
+        }
+
+        let all: Vec<&str> = decls
+            .iter()
+            .flat_map(|(names, _)| names.iter().map(String::as_str))
+            .collect();
+        let mut sorted: Vec<&str> = Vec::new();
+        for name in order {
+            let found = all
+                .iter()
+                .find(|n| n.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    RewriteError::new(
+                        format!("entity '{}' has no port '{}'", entity, name),
+                        self.tokens[start + 1].span,
+                    )
+                })?;
+            if !sorted.contains(found) {
+                sorted.push(found);
+            }
+        }
+        for name in &all {
+            if !sorted.contains(name) {
+                sorted.push(name);
+            }
+        }
+
+        // 元の宣言の名前が続いていればまとめたまま、そうでなければ分ける
+        let mut texts = Vec::new();
+        let mut i = 0;
+        while i < sorted.len() {
+            let index = decls
+                .iter()
+                .position(|(names, _)| names.iter().any(|n| n == sorted[i]))
+                .expect("並べ替えた名前はどれかの宣言にある");
+            let (names, rest) = &decls[index];
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            if sorted[i..].starts_with(&names) {
+                texts.push(self.source[items[index].start..items[index].end].to_string());
+                i += names.len();
+            } else {
+                texts.push(format!("{} : {}", sorted[i], rest));
+                i += 1;
+            }
+        }
+
+        let changes: Vec<ItemChange> = texts
+            .iter()
+            .take(items.len())
+            .enumerate()
+            .map(|(i, text)| {
+                if *text == self.source[items[i].start..items[i].end] {
+                    ItemChange::Keep
+                } else {
+                    ItemChange::Replace(text.clone())
+                }
+            })
+            .collect();
+        let added = texts[items.len()..].to_vec();
+        self.edits
+            .extend(list_edits(self.source, open, &items, &changes, &added, ';'));
+        Ok(())
+    }
+
+    /// エンティティ宣言の範囲（`entity` と `end` のトークンの添字）と、ポート節の `port` の添字
+    fn entity_ports(&self, entity: &str) -> Result<(usize, usize, Option<usize>), RewriteError> {
+        let start = self
+            .tokens
+            .windows(3)
+            .position(|w| {
+                w[0].kind == TokenKind::Entity
+                    && w[1].kind == TokenKind::Identifier
+                    && w[1].text.eq_ignore_ascii_case(entity)
+                    && w[2].kind == TokenKind::Is
+            })
+            .ok_or_else(|| {
+                RewriteError::new(format!("entity '{}' not found", entity), Span::new(0, 0))
+            })?;
+        let end = (start..self.tokens.len())
+            .find(|&i| self.tokens[i].kind == TokenKind::End)
+            .ok_or_else(|| {
+                RewriteError::new(
+                    format!("entity '{}' is not terminated", entity),
+                    self.tokens[start].span,
+                )
+            })?;
+        let port = (start..end).find(|&i| {
+            self.tokens[i].kind == TokenKind::Port
+                && self.tokens.get(i + 1).map(|t| &t.kind) == Some(&TokenKind::LeftParen)
+        });
+        Ok((start, end, port))
+    }
+
+    /// ポート・信号の宣言の型の範囲を変える
+    ///
+    /// `range` は `7 downto 0` のような括弧の中身です。名前を並べた宣言
+    /// （`a, b : std_logic_vector(...)`）では全員の範囲が変わります。
+    pub fn change_range(&mut self, name: &str, range: &str) -> Result<(), RewriteError> {
+        let not_found = || {
+            RewriteError::new(
+                format!("no declaration of '{}' with a range", name),
+                Span::new(0, 0),
+            )
+        };
+        let mut i = 0;
+        while i < self.tokens.len() {
+            let t = &self.tokens[i];
+            if t.kind != TokenKind::Identifier || !t.text.eq_ignore_ascii_case(name) {
+                i += 1;
+                continue;
+            }
+            // 名前の並びの後の `:`
+            let mut j = i + 1;
+            while self.tokens.get(j).map(|t| &t.kind) == Some(&TokenKind::Comma)
+                && self.tokens.get(j + 1).map(|t| &t.kind) == Some(&TokenKind::Identifier)
+            {
+                j += 2;
+            }
+            if self.tokens.get(j).map(|t| &t.kind) != Some(&TokenKind::Colon) {
+                i += 1;
+                continue;
+            }
+            j += 1;
+            if matches!(
+                self.tokens.get(j).map(|t| t.kind.clone()),
+                Some(TokenKind::In | TokenKind::Out | TokenKind::Inout | TokenKind::Buffer)
+            ) {
+                j += 1;
+            }
+            // 型名の後の括弧
+            if self.tokens.get(j + 1).map(|t| &t.kind) != Some(&TokenKind::LeftParen) {
+                return Err(not_found());
+            }
+            let open = j + 1;
+            let mut depth = 0;
+            for k in open..self.tokens.len() {
+                match self.tokens[k].kind {
+                    TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightParen => {
+                        depth -= 1;
+                        if depth == 0 {
+                            let span =
+                                Span::new(self.tokens[open].span.end, self.tokens[k].span.start);
+                            self.replace(span, range);
+                            return Ok(());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            return Err(not_found());
+        }
+        Err(not_found())
+    }
+
+    /// 積んだ編集を適用した結果
+    pub fn finish(self) -> Result<String, RewriteError> {
+        apply(self.source, self.edits)
+    }
+}
+
+/// 括弧の中で `sep` で区切った要素の範囲（閉じ括弧まで）
+fn list_items(tokens: &[Token], sep: TokenKind) -> Vec<Span> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut first: Option<Span> = None;
+    let mut last = Span::new(0, 0);
+    for t in tokens {
+        match t.kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen if depth == 0 => break,
+            TokenKind::RightParen => depth -= 1,
+            _ if depth == 0 && t.kind == sep => {
+                if let Some(f) = first.take() {
+                    items.push(Span::new(f.start, last.end));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        first.get_or_insert(t.span);
+        last = t.span;
+    }
+    if let Some(f) = first {
+        items.push(Span::new(f.start, last.end));
+    }
+    items
+}
+
+/// 並びの要素の扱い
+pub(crate) enum ItemChange {
+    Keep,
+    Replace(String),
+    Remove,
+}
+
+/// 区切り文字で並んだ要素（ポート宣言・関連付け）の編集
+///
+/// 削除した要素の区切りも取り除き、追加する要素は最後に残る要素の後ろに
+/// 既存の要素と同じ改行・インデントで並べます。
+pub(crate) fn list_edits(
+    source: &str,
+    open: usize,
+    items: &[Span],
+    changes: &[ItemChange],
+    added: &[String],
+    sep: char,
+) -> Vec<Edit> {
+    let gap = list_gap(source, open, items);
+    let kept: Vec<usize> = (0..items.len())
+        .filter(|&i| !matches!(changes[i], ItemChange::Remove))
+        .collect();
+    let Some(&last_kept) = kept.last() else {
+        let joined = added.join(&format!("{}{}", sep, gap));
+        return match (items.first(), items.last()) {
+            (Some(first), Some(last)) => vec![Edit::new(first.start, last.end, joined)],
+            _ if !added.is_empty() => vec![Edit::new(open, open, format!("{}{}", gap, joined))],
+            _ => Vec::new(),
+        };
+    };
+
+    let mut edits = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match &changes[i] {
+            ItemChange::Keep => {}
+            ItemChange::Replace(text) => edits.push(Edit::new(item.start, item.end, text.as_str())),
+            // 後ろに残る要素があれば次の要素の直前まで、なければ前の要素の直後から消す
+            ItemChange::Remove if i < last_kept => {
+                edits.push(Edit::new(item.start, items[i + 1].start, ""))
+            }
+            ItemChange::Remove => edits.push(Edit::new(items[i - 1].end, item.end, "")),
+        }
+    }
+    if !added.is_empty() {
+        let end = items[last_kept].end;
+        let text: String = added
+            .iter()
+            .map(|item| format!("{}{}{}", sep, gap, item))
+            .collect();
+        edits.push(Edit::new(end, end, text));
+    }
+    edits
+}
+
+/// 要素の間の改行とインデント（1行に並べている場合は空白1つ）
+fn list_gap(source: &str, open: usize, items: &[Span]) -> String {
+    let between = match items {
+        [first, second, ..] => &source[first.end..second.start],
+        [first] => &source[open..first.start],
+        [] => "",
+    };
+    match between.rfind('\n') {
+        Some(i) if between[i + 1..].trim().is_empty() => format!("\n{}", &between[i + 1..]),
+        _ => " ".to_string(),
+    }
+}
+
+/// 行全体を占める範囲なら、行頭から改行までに広げる
+pub(crate) fn line_extent(source: &str, span: Span) -> Span {
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[span.end..]
+        .find('\n')
+        .map_or(source.len(), |i| span.end + i + 1);
+    if source[line_start..span.start].trim().is_empty()
+        && source[span.end..line_end].trim().is_empty()
+    {
+        Span::new(line_start, line_end)
+    } else {
+        span
+    }
+}
+
+/// 位置を含む行のインデント
+pub(crate) fn line_indent(source: &str, pos: usize) -> &str {
+    let line_start = source[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// 位置を含む行の次の行頭
+pub(crate) fn next_line_start(source: &str, pos: usize) -> usize {
+    source[pos..]
+        .find('\n')
+        .map_or(source.len(), |i| pos + i + 1)
+}
@@ -0,0 +1,298 @@
+//! エディタ向けのセマンティックトークン分類
+//!
+//! 字句解析と意味解析の結果を合わせて、キーワード・型・ポート・信号・定数・
+//! エンティティ名の範囲を返します。LSPの `textDocument/semanticTokens` 用に
+//! 相対位置で符号化する [`encode_lsp`] も用意しています。
+
+use crate::analyzer::{AnalyzeResult, VhdlType, analyze_vhdl};
+use crate::lexer::{Lexer, LexerOptions, Span, Token, TokenKind};
+use crate::trace::timed;
+
+/// セマンティックトークンの種類
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Keyword,
+    Type,
+    Port,
+    Signal,
+    Constant,
+    EntityName,
+}
+
+/// LSPの凡例に渡すトークン種別（[`encode_lsp`] の種別番号と対応）
+pub const LSP_TOKEN_TYPES: &[&str] = &["keyword", "type", "parameter", "variable", "class"];
+/// LSPの凡例に渡す修飾子（ビット0: readonly）
+pub const LSP_TOKEN_MODIFIERS: &[&str] = &["readonly"];
+
+impl SemanticTokenKind {
+    /// (LSP_TOKEN_TYPES の番号, 修飾子のビット)
+    fn lsp_encoding(&self) -> (u32, u32) {
+        match self {
+            SemanticTokenKind::Keyword => (0, 0),
+            SemanticTokenKind::Type => (1, 0),
+            SemanticTokenKind::Port => (2, 0),
+            SemanticTokenKind::Signal => (3, 0),
+            SemanticTokenKind::Constant => (3, 1),
+            SemanticTokenKind::EntityName => (4, 0),
+        }
+    }
+}
+
+/// 分類済みのトークン範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+}
+
+/// 標準ライブラリの主な型名
+pub(crate) const STANDARD_TYPES: &[&str] = &[
+    "bit",
+    "bit_vector",
+    "character",
+    "natural",
+    "positive",
+    "real",
+    "signed",
+    "std_ulogic",
+    "std_ulogic_vector",
+    "string",
+    "time",
+    "unsigned",
+];
+
+/// 名前の有効範囲付きの宣言
+pub(crate) struct Scoped {
+    pub(crate) name: String,
+    pub(crate) scope: Span,
+    pub(crate) kind: SemanticTokenKind,
+}
+
+/// ソース中で宣言された名前と型名
+pub(crate) struct Symbols {
+    pub(crate) names: Vec<Scoped>,
+    pub(crate) type_names: Vec<String>,
+}
+
+/// 解析結果とトークン列から名前表を作る
+pub(crate) fn collect_symbols(tokens: &[Token], result: &AnalyzeResult, whole: Span) -> Symbols {
+    let mut names: Vec<Scoped> = Vec::new();
+    let mut type_names: Vec<String> = STANDARD_TYPES.iter().map(|s| s.to_string()).collect();
+    let mut add_type = |t: &VhdlType| {
+        if let VhdlType::Other(name) = t {
+            type_names.push(name.to_lowercase());
+        }
+    };
+    for entity in &result.entities {
+        names.push(Scoped {
+            name: entity.name.to_lowercase(),
+            scope: whole,
+            kind: SemanticTokenKind::EntityName,
+        });
+        // ポートはエンティティ宣言とそのアーキテクチャ内で有効
+        let mut scopes = vec![entity.span];
+        scopes.extend(
+            result
+                .architectures
+                .iter()
+                .filter(|a| a.entity_name.eq_ignore_ascii_case(&entity.name))
+                .map(|a| a.span),
+        );
+        for port in &entity.ports {
+            add_type(&port.vhdl_type);
+            for scope in &scopes {
+                names.push(Scoped {
+                    name: port.name.to_lowercase(),
+                    scope: *scope,
+                    kind: SemanticTokenKind::Port,
+                });
+            }
+        }
+    }
+    for arch in &result.architectures {
+        for signal in &arch.signals {
+            add_type(&signal.vhdl_type);
+            names.push(Scoped {
+                name: signal.name.to_lowercase(),
+                scope: arch.span,
+                kind: SemanticTokenKind::Signal,
+            });
+        }
+    }
+    scan_declarations(tokens, whole, &mut names, &mut type_names);
+    Symbols { names, type_names }
+}
+
+/// コメントを除いたトークン列（字句エラーは読み飛ばす）
+pub(crate) fn lex_tokens(source: &str) -> Vec<Token<'_>> {
+    Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .filter_map(|r| r.ok())
+        .collect()
+}
+
+/// ソースをセマンティックトークンに分類する
+///
+/// 編集途中で意味解析に失敗した場合でも、字句から分かるキーワード・型は返します。
+pub fn semantic_tokens(source: &str) -> Vec<SemanticToken> {
+    let _timed = timed!("semantic_tokens");
+    let tokens = lex_tokens(source);
+    let result = analyze_vhdl(source).unwrap_or_default();
+    let Symbols { names, type_names } =
+        collect_symbols(&tokens, &result, Span::new(0, source.len()));
+
+    let mut out = Vec::new();
+    for token in &tokens {
+        let kind = match token.kind {
+            TokenKind::StdLogic
+            | TokenKind::StdLogicVector
+            | TokenKind::Integer
+            | TokenKind::Boolean => Some(SemanticTokenKind::Type),
+            TokenKind::Identifier => {
+                let lower = token.text.to_lowercase();
+                // 内側の（範囲の狭い）宣言を優先
+                names
+                    .iter()
+                    .filter(|n| {
+                        n.name == lower
+                            && n.scope.start <= token.span.start
+                            && token.span.end <= n.scope.end
+                    })
+                    .min_by_key(|n| n.scope.len())
+                    .map(|n| n.kind)
+                    .or_else(|| {
+                        type_names
+                            .contains(&lower)
+                            .then_some(SemanticTokenKind::Type)
+                    })
+            }
+            // キーワード（演算子キーワードを含む）
+            _ if token.kind.is_keyword() => Some(SemanticTokenKind::Keyword),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            out.push(SemanticToken {
+                span: token.span,
+                kind,
+            });
+        }
+    }
+    out
+}
+
+/// 意味解析で扱わない宣言（generic・constant・type/subtype・宣言中の型名）をトークン列から拾う
+fn scan_declarations(
+    tokens: &[Token],
+    whole: Span,
+    names: &mut Vec<Scoped>,
+    type_names: &mut Vec<String>,
+) {
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        if token.kind == TokenKind::Constant {
+            // constant a, b : type := ...;
+            let mut j = i + 1;
+            while let Some(t) = tokens.get(j) {
+                match t.kind {
+                    TokenKind::Identifier => names.push(Scoped {
+                        name: t.text.to_lowercase(),
+                        scope: whole,
+                        kind: SemanticTokenKind::Constant,
+                    }),
+                    TokenKind::Comma => {}
+                    _ => break,
+                }
+                j += 1;
+            }
+        } else if matches!(token.kind, TokenKind::Type | TokenKind::Subtype)
+            && let Some(t) = tokens
+                .get(i + 1)
+                .filter(|t| t.kind == TokenKind::Identifier)
+        {
+            type_names.push(t.text.to_lowercase());
+        } else if token.kind == TokenKind::Colon {
+            // 名前 : [向き] 型名
+            let mut j = i + 1;
+            while tokens.get(j).is_some_and(|t| {
+                matches!(
+                    t.kind,
+                    TokenKind::In | TokenKind::Out | TokenKind::Inout | TokenKind::Buffer
+                )
+            }) {
+                j += 1;
+            }
+            if let Some(t) = tokens.get(j).filter(|t| t.kind == TokenKind::Identifier) {
+                type_names.push(t.text.to_lowercase());
+            }
+        } else if token.kind == TokenKind::Generic
+            && tokens.get(i + 1).map(|t| &t.kind) == Some(&TokenKind::LeftParen)
+        {
+            // generic ( a : t := v; b, c : t ) の名前部分
+            let mut depth = 0;
+            let mut expect_name = true;
+            let mut j = i + 1;
+            while let Some(t) = tokens.get(j) {
+                match t.kind {
+                    TokenKind::LeftParen => depth += 1,
+                    TokenKind::RightParen => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    TokenKind::Semicolon if depth == 1 => expect_name = true,
+                    TokenKind::Colon if depth == 1 => expect_name = false,
+                    TokenKind::Identifier if depth == 1 && expect_name => names.push(Scoped {
+                        name: t.text.to_lowercase(),
+                        scope: whole,
+                        kind: SemanticTokenKind::Constant,
+                    }),
+                    _ => {}
+                }
+                j += 1;
+            }
+            i = j;
+        }
+        i += 1;
+    }
+}
+
+/// LSPの相対形式（deltaLine, deltaStart, length, tokenType, tokenModifiers）に符号化する
+///
+/// 列と長さはUTF-16のコード単位で数えます。`tokens` は出現順である必要があります。
+pub fn encode_lsp(source: &str, tokens: &[SemanticToken]) -> Vec<u32> {
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let mut line = 0u32;
+    let mut line_start = 0usize;
+    let mut scanned = 0usize;
+    let mut prev_line = 0u32;
+    let mut prev_col = 0u32;
+
+    for token in tokens {
+        // トークン開始位置までの改行を数える
+        for (offset, c) in source[scanned..token.span.start].char_indices() {
+            if c == '\n' {
+                line += 1;
+                line_start = scanned + offset + 1;
+            }
+        }
+        scanned = token.span.start;
+
+        let col = source[line_start..token.span.start].encode_utf16().count() as u32;
+        let length = source[token.span.start..token.span.end]
+            .encode_utf16()
+            .count() as u32;
+        let (token_type, modifiers) = token.kind.lsp_encoding();
+        let delta_line = line - prev_line;
+        let delta_col = if delta_line == 0 { col - prev_col } else { col };
+        data.extend([delta_line, delta_col, length, token_type, modifiers]);
+        prev_line = line;
+        prev_col = col;
+    }
+    data
+}
@@ -0,0 +1,354 @@
+//! 信号の駆動元と参照先の一覧
+//!
+//! アーキテクチャごとに、ポートと信号を代入しているプロセス・並行代入（駆動元）と、
+//! 参照しているもの（参照先）を集めます。解決関数のない型（`integer` や `std_ulogic` など）の
+//! 信号を複数の箇所から駆動していれば、合成やエラボレーションで失敗するので診断にします。
+
+use crate::analyzer::{EntityDef, PortDirection, VhdlType};
+use crate::diagnostic::Diagnostic;
+use crate::drivers::{self, Driver};
+use crate::json::Value;
+use crate::lexer::Span;
+use crate::report::ReportFormat;
+use crate::source_map::SourceMap;
+use crate::trace::timed;
+
+/// 解決関数を持つ型（複数の駆動元を許す型）
+const RESOLVED_TYPES: [&str; 7] = [
+    "std_logic",
+    "std_logic_vector",
+    "signed",
+    "unsigned",
+    "ufixed",
+    "sfixed",
+    "float",
+];
+
+/// ポートか信号か
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalKind {
+    Port(PortDirection),
+    Signal,
+}
+
+/// 信号を駆動・参照する文（プロセス1つ、または並行代入1つ）
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Access {
+    /// プロセスのラベル
+    pub label: Option<String>,
+    pub is_process: bool,
+    /// プロセスなら `process` キーワード、並行代入なら代入先の位置
+    pub span: Span,
+    /// 文の中で名前が現れた位置
+    pub locations: Vec<Span>,
+}
+
+/// ポートまたは信号1つの駆動元と参照先
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalUsage {
+    pub name: String,
+    pub kind: SignalKind,
+    /// 小文字の型名（制約を除く）
+    pub type_name: String,
+    /// 宣言の位置
+    pub span: Span,
+    pub drivers: Vec<Access>,
+    pub readers: Vec<Access>,
+}
+
+impl SignalUsage {
+    /// 型が解決関数を持つか（型が分からなければ true）
+    pub fn is_resolved(&self) -> bool {
+        self.type_name.is_empty() || RESOLVED_TYPES.contains(&self.type_name.as_str())
+    }
+
+    /// 解決関数のない型なのに複数の駆動元があるか
+    pub fn is_multiply_driven(&self) -> bool {
+        self.drivers.len() > 1 && !self.is_resolved()
+    }
+}
+
+/// アーキテクチャ1つの一覧
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchitectureUsage {
+    pub entity: String,
+    pub architecture: String,
+    /// ポートと信号（宣言順）
+    pub signals: Vec<SignalUsage>,
+}
+
+/// エンティティのアーキテクチャごとに駆動元と参照先を集める
+///
+/// `source` 中のこのエンティティのアーキテクチャをすべて対象にします。
+pub fn usages(source: &str, entity: &EntityDef) -> Vec<ArchitectureUsage> {
+    let _timed = timed!("signal_usages", entity = %entity.name);
+    drivers::architectures(source)
+        .into_iter()
+        .filter(|a| a.entity.eq_ignore_ascii_case(&entity.name))
+        .map(|arch| {
+            let mut signals: Vec<SignalUsage> = entity
+                .ports
+                .iter()
+                .map(|p| {
+                    usage(
+                        &p.name,
+                        SignalKind::Port(p.direction.clone()),
+                        type_name(&p.vhdl_type),
+                        p.span,
+                        &arch.drivers,
+                    )
+                })
+                .collect();
+            for decl in &arch.signals {
+                if signals
+                    .iter()
+                    .any(|s| s.name.eq_ignore_ascii_case(&decl.name))
+                {
+                    continue;
+                }
+                let name = &source[decl.span.start..decl.span.end];
+                signals.push(usage(
+                    name,
+                    SignalKind::Signal,
+                    decl.type_name.clone(),
+                    decl.span,
+                    &arch.drivers,
+                ));
+            }
+            ArchitectureUsage {
+                entity: entity.name.clone(),
+                architecture: arch.name,
+                signals,
+            }
+        })
+        .collect()
+}
+
+fn usage(
+    name: &str,
+    kind: SignalKind,
+    type_name: String,
+    span: Span,
+    drivers: &[Driver],
+) -> SignalUsage {
+    let key = name.to_lowercase();
+    let access = |d: &Driver, locations: Vec<Span>| {
+        (!locations.is_empty()).then(|| Access {
+            label: d.label.clone(),
+            is_process: d.is_process,
+            span: d.span,
+            locations,
+        })
+    };
+    SignalUsage {
+        name: name.to_string(),
+        kind,
+        type_name,
+        span,
+        drivers: drivers
+            .iter()
+            .filter_map(|d| {
+                let locations = d
+                    .writes
+                    .iter()
+                    .filter(|(w, _)| *w == key)
+                    .map(|(_, span)| *span)
+                    .collect();
+                access(d, locations)
+            })
+            .collect(),
+        readers: drivers
+            .iter()
+            .filter_map(|d| {
+                let locations = d
+                    .reads
+                    .iter()
+                    .filter(|r| r.name == key)
+                    .map(|r| r.span)
+                    .collect();
+                access(d, locations)
+            })
+            .collect(),
+    }
+}
+
+/// 型の表記から制約を除いた小文字の型名
+fn type_name(vhdl_type: &VhdlType) -> String {
+    match vhdl_type {
+        VhdlType::Other(text) => text
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+            .next()
+            .unwrap_or_default()
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase(),
+        _ => vhdl_type.base_name().to_string(),
+    }
+}
+
+/// 解決関数のない型の信号を複数の箇所から駆動していないか調べる
+///
+/// 2つ目以降の駆動元の位置にエラーを出し、最初の駆動元を関連位置にします。
+pub fn check_multiple_drivers(source: &str, entity: &EntityDef) -> Vec<Diagnostic> {
+    let _timed = timed!("check_multiple_drivers", entity = %entity.name);
+    let mut diagnostics = Vec::new();
+    for arch in usages(source, entity) {
+        for signal in arch.signals.iter().filter(|s| s.is_multiply_driven()) {
+            let first = signal.drivers[0].locations[0];
+            for driver in &signal.drivers[1..] {
+                diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "'{}' has multiple drivers but its type '{}' is not resolved",
+                            signal.name, signal.type_name
+                        ),
+                        driver.locations[0],
+                    )
+                    .with_related(first, format!("'{}' is also driven here", signal.name)),
+                );
+            }
+        }
+    }
+    diagnostics
+}
+
+/// ファイルごとの一覧を表示用の文字列にする
+///
+/// テキストはアーキテクチャごとに信号と駆動元・参照先を並べ、`Json` はアーキテクチャ1つ
+/// 1オブジェクトの配列です。`Ansi` は `Plain` と同じです。
+pub fn render(found: &[(SourceMap, Vec<ArchitectureUsage>)], format: ReportFormat) -> String {
+    if format == ReportFormat::Json {
+        let items = found
+            .iter()
+            .flat_map(|(map, archs)| archs.iter().map(move |arch| arch_value(map, arch)))
+            .collect();
+        let mut s = Value::Array(items).to_pretty_string();
+        s.push('\n');
+        return s;
+    }
+    let mut out = String::new();
+    for (map, archs) in found {
+        for arch in archs {
+            out.push_str(&format!(
+                "{}: {} ({})\n",
+                map.name(),
+                arch.entity,
+                arch.architecture
+            ));
+            for signal in &arch.signals {
+                out.push_str(&format!(
+                    "  {} ({} {})",
+                    signal.name,
+                    kind_str(&signal.kind),
+                    signal.type_name
+                ));
+                if signal.is_multiply_driven() {
+                    out.push_str(" 複数駆動");
+                }
+                out.push('\n');
+                out.push_str(&format!(
+                    "    駆動: {}\n",
+                    accesses_text(map, &signal.drivers)
+                ));
+                out.push_str(&format!(
+                    "    参照: {}\n",
+                    accesses_text(map, &signal.readers)
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn kind_str(kind: &SignalKind) -> &'static str {
+    match kind {
+        SignalKind::Port(direction) => direction.as_str(),
+        SignalKind::Signal => "signal",
+    }
+}
+
+fn accesses_text(map: &SourceMap, accesses: &[Access]) -> String {
+    if accesses.is_empty() {
+        return "なし".to_string();
+    }
+    accesses
+        .iter()
+        .map(|a| {
+            let (line, _) = map.line_col(a.locations[0].start);
+            match (a.is_process, &a.label) {
+                (true, Some(label)) => format!("プロセス {} ({}行目)", label, line),
+                (true, None) => format!("プロセス ({}行目)", line),
+                (false, _) => format!("並行代入 ({}行目)", line),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn arch_value(map: &SourceMap, arch: &ArchitectureUsage) -> Value {
+    let signals = arch
+        .signals
+        .iter()
+        .map(|s| {
+            Value::Object(vec![
+                ("name".to_string(), Value::String(s.name.clone())),
+                (
+                    "kind".to_string(),
+                    Value::String(kind_str(&s.kind).to_string()),
+                ),
+                ("type".to_string(), Value::String(s.type_name.clone())),
+                ("resolved".to_string(), Value::Bool(s.is_resolved())),
+                (
+                    "multiply_driven".to_string(),
+                    Value::Bool(s.is_multiply_driven()),
+                ),
+                ("drivers".to_string(), accesses_value(map, &s.drivers)),
+                ("readers".to_string(), accesses_value(map, &s.readers)),
+            ])
+        })
+        .collect();
+    Value::Object(vec![
+        ("file".to_string(), Value::String(map.name().to_string())),
+        ("entity".to_string(), Value::String(arch.entity.clone())),
+        (
+            "architecture".to_string(),
+            Value::String(arch.architecture.clone()),
+        ),
+        ("signals".to_string(), Value::Array(signals)),
+    ])
+}
+
+fn accesses_value(map: &SourceMap, accesses: &[Access]) -> Value {
+    Value::Array(
+        accesses
+            .iter()
+            .map(|a| {
+                let (line, column) = map.line_col(a.locations[0].start);
+                Value::Object(vec![
+                    (
+                        "kind".to_string(),
+                        Value::String(
+                            if a.is_process {
+                                "process"
+                            } else {
+                                "concurrent"
+                            }
+                            .to_string(),
+                        ),
+                    ),
+                    (
+                        "label".to_string(),
+                        a.label.clone().map_or(Value::Null, Value::String),
+                    ),
+                    ("line".to_string(), Value::Integer(line as i64)),
+                    ("column".to_string(), Value::Integer(column as i64)),
+                ])
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,510 @@
+//! シミュレータの呼び出し
+//!
+//! GHDL・NVC・ModelSim/Questa のコマンドの組み立てと実行を [`Simulator`] で抽象化します。
+//! ソースは [`compile_order`] で設計単位の依存順に並べてから渡します。
+//!
+//! ```
+//! use vig::sim::{Ghdl, SimJob, Simulator};
+//!
+//! let job = SimJob::new("counter_tb", ["counter.vhd", "counter_tb.vhd"]);
+//! let commands: Vec<String> = Ghdl.commands(&job).iter().map(|c| c.to_string()).collect();
+//! assert_eq!(commands[0], "ghdl -a --std=08 --workdir=vig_work counter.vhd");
+//! assert_eq!(commands[3], "ghdl -r --std=08 --workdir=vig_work counter_tb");
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::lexer::{Lexer, LexerOptions, Token, TokenKind};
+
+/// VHDLの版
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VhdlStandard {
+    Vhdl93,
+    Vhdl2002,
+    #[default]
+    Vhdl2008,
+}
+
+impl VhdlStandard {
+    /// 西暦の下2桁（`93` `02` `08`）
+    pub fn short(self) -> &'static str {
+        match self {
+            VhdlStandard::Vhdl93 => "93",
+            VhdlStandard::Vhdl2002 => "02",
+            VhdlStandard::Vhdl2008 => "08",
+        }
+    }
+
+    /// 西暦（`1993` `2002` `2008`）
+    pub fn year(self) -> &'static str {
+        match self {
+            VhdlStandard::Vhdl93 => "1993",
+            VhdlStandard::Vhdl2002 => "2002",
+            VhdlStandard::Vhdl2008 => "2008",
+        }
+    }
+}
+
+impl std::str::FromStr for VhdlStandard {
+    type Err = String;
+
+    /// `93` `1993` `02` `2002` `08` `2008`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "93" | "1993" => Ok(VhdlStandard::Vhdl93),
+            "02" | "2002" => Ok(VhdlStandard::Vhdl2002),
+            "08" | "2008" => Ok(VhdlStandard::Vhdl2008),
+            _ => Err(format!("unsupported VHDL standard '{}'", s)),
+        }
+    }
+}
+
+/// シミュレーションの内容
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimJob {
+    /// コンパイルするソース（依存順）
+    pub sources: Vec<PathBuf>,
+    /// トップのエンティティ名（通常はテストベンチ）
+    pub top: String,
+    /// ライブラリ work を置くディレクトリ
+    pub work_dir: PathBuf,
+    pub standard: VhdlStandard,
+    /// シミュレーション時間の上限（ns、None ならテストベンチが止まるまで）
+    pub stop_time_ns: Option<u64>,
+}
+
+impl Default for SimJob {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            top: String::new(),
+            work_dir: PathBuf::from("vig_work"),
+            standard: VhdlStandard::default(),
+            stop_time_ns: None,
+        }
+    }
+}
+
+impl SimJob {
+    pub fn new<P: Into<PathBuf>>(
+        top: impl Into<String>,
+        sources: impl IntoIterator<Item = P>,
+    ) -> Self {
+        Self {
+            top: top.into(),
+            sources: sources.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// 実行するコマンド
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl SimCommand {
+    pub fn new<S: Into<String>>(
+        program: impl Into<String>,
+        args: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// `std::process::Command` に変換
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        command
+    }
+}
+
+impl std::fmt::Display for SimCommand {
+    /// シェルにそのまま貼れる形（空白などを含む引数は引用符で囲む）
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&shell_quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", shell_quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:+,@%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// 1つのコマンドの実行結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimStep {
+    pub command: SimCommand,
+    /// 終了コード（シグナルで終了した場合は None）
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl SimStep {
+    pub fn success(&self) -> bool {
+        self.status == Some(0)
+    }
+}
+
+/// シミュレーションの実行結果（失敗したコマンドまで）
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SimOutput {
+    pub steps: Vec<SimStep>,
+}
+
+impl SimOutput {
+    /// すべてのコマンドが成功したか
+    pub fn success(&self) -> bool {
+        self.steps.iter().all(SimStep::success)
+    }
+
+    /// 全コマンドの標準出力と標準エラー出力をつなげたもの
+    pub fn log(&self) -> String {
+        self.steps
+            .iter()
+            .map(|s| format!("{}{}", s.stdout, s.stderr))
+            .collect()
+    }
+}
+
+/// シミュレータのバックエンド
+pub trait Simulator {
+    /// 表示名
+    fn name(&self) -> &str;
+
+    /// ソースをライブラリ work にコンパイルするコマンド
+    fn compile_commands(&self, job: &SimJob) -> Vec<SimCommand>;
+
+    /// トップをエラボレーションして実行するコマンド
+    fn run_commands(&self, job: &SimJob) -> Vec<SimCommand>;
+
+    /// 実行するすべてのコマンド
+    fn commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        let mut commands = self.compile_commands(job);
+        commands.extend(self.run_commands(job));
+        commands
+    }
+
+    /// コマンドを順に実行して出力を集める（失敗したコマンドで止める）
+    ///
+    /// コマンドを起動できなかった場合（シミュレータが未インストールなど）はエラーです。
+    fn simulate(&self, job: &SimJob) -> crate::Result<SimOutput> {
+        std::fs::create_dir_all(&job.work_dir).map_err(|e| crate::Error::io(&job.work_dir, e))?;
+        let mut output = SimOutput::default();
+        for command in self.commands(job) {
+            let result = command
+                .to_command()
+                .output()
+                .map_err(|e| crate::Error::io(&command.program, e))?;
+            let step = SimStep {
+                command,
+                status: result.status.code(),
+                stdout: String::from_utf8_lossy(&result.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&result.stderr).into_owned(),
+            };
+            let ok = step.success();
+            output.steps.push(step);
+            if !ok {
+                break;
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn path_arg(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// GHDL
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ghdl;
+
+impl Ghdl {
+    fn options(job: &SimJob) -> Vec<String> {
+        vec![
+            format!("--std={}", job.standard.short()),
+            format!("--workdir={}", path_arg(&job.work_dir)),
+        ]
+    }
+}
+
+impl Simulator for Ghdl {
+    fn name(&self) -> &str {
+        "ghdl"
+    }
+
+    fn compile_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        job.sources
+            .iter()
+            .map(|source| {
+                let mut args = vec!["-a".to_string()];
+                args.extend(Self::options(job));
+                args.push(path_arg(source));
+                SimCommand::new("ghdl", args)
+            })
+            .collect()
+    }
+
+    fn run_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        let step = |mode: &str| {
+            let mut args = vec![mode.to_string()];
+            args.extend(Self::options(job));
+            args.push(job.top.clone());
+            args
+        };
+        let mut run = step("-r");
+        if let Some(ns) = job.stop_time_ns {
+            run.push(format!("--stop-time={}ns", ns));
+        }
+        vec![
+            SimCommand::new("ghdl", step("-e")),
+            SimCommand::new("ghdl", run),
+        ]
+    }
+}
+
+/// NVC
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nvc;
+
+impl Nvc {
+    fn options(job: &SimJob) -> Vec<String> {
+        vec![
+            format!("--std={}", job.standard.year()),
+            format!("--work={}", path_arg(&job.work_dir.join("work"))),
+        ]
+    }
+}
+
+impl Simulator for Nvc {
+    fn name(&self) -> &str {
+        "nvc"
+    }
+
+    fn compile_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        if job.sources.is_empty() {
+            return Vec::new();
+        }
+        let mut args = Self::options(job);
+        args.push("-a".to_string());
+        args.extend(job.sources.iter().map(|s| path_arg(s)));
+        vec![SimCommand::new("nvc", args)]
+    }
+
+    fn run_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        let mut args = Self::options(job);
+        args.extend(["-e".to_string(), job.top.clone()]);
+        args.extend(["-r".to_string(), job.top.clone()]);
+        if let Some(ns) = job.stop_time_ns {
+            args.push(format!("--stop-time={}ns", ns));
+        }
+        vec![SimCommand::new("nvc", args)]
+    }
+}
+
+/// ModelSim / Questa
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Questa;
+
+impl Simulator for Questa {
+    fn name(&self) -> &str {
+        "questa"
+    }
+
+    fn compile_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        let work = path_arg(&job.work_dir.join("work"));
+        let mut commands = vec![SimCommand::new("vlib", [work.clone()])];
+        if !job.sources.is_empty() {
+            let mut args = vec![
+                format!("-{}", job.standard.year()),
+                "-work".to_string(),
+                work,
+            ];
+            args.extend(job.sources.iter().map(|s| path_arg(s)));
+            commands.push(SimCommand::new("vcom", args));
+        }
+        commands
+    }
+
+    fn run_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        let work = path_arg(&job.work_dir.join("work"));
+        let run = match job.stop_time_ns {
+            Some(ns) => format!("run {} ns; quit -f", ns),
+            None => "run -all; quit -f".to_string(),
+        };
+        vec![SimCommand::new(
+            "vsim",
+            [
+                "-c".to_string(),
+                "-lib".to_string(),
+                work,
+                job.top.clone(),
+                "-do".to_string(),
+                run,
+            ],
+        )]
+    }
+}
+
+/// 名前からバックエンドを選ぶ（`ghdl` `nvc` `questa` `modelsim` `vsim`）
+pub fn simulator(name: &str) -> Option<Box<dyn Simulator + Send + Sync>> {
+    match name.to_lowercase().as_str() {
+        "ghdl" => Some(Box::new(Ghdl)),
+        "nvc" => Some(Box::new(Nvc)),
+        "questa" | "modelsim" | "vsim" => Some(Box::new(Questa)),
+        _ => None,
+    }
+}
+
+/// 1つのソースで定義・参照する設計単位（小文字）
+struct Units {
+    defines: Vec<String>,
+    entities: Vec<String>,
+    /// アーキテクチャ・パッケージ本体が属する単位
+    owners: Vec<String>,
+    uses: Vec<String>,
+}
+
+fn scan_units(source: &str) -> Units {
+    let tokens: Vec<Token> = Lexer::new(source)
+        .with_options(LexerOptions {
+            skip_comments: true,
+            ..LexerOptions::default()
+        })
+        .filter_map(|r| r.ok())
+        .collect();
+    let is_word = |t: &Token, word: &str| t.text.eq_ignore_ascii_case(word);
+    let mut units = Units {
+        defines: Vec::new(),
+        entities: Vec::new(),
+        owners: Vec::new(),
+        uses: Vec::new(),
+    };
+    for (i, token) in tokens.iter().enumerate() {
+        let next = |n: usize| tokens.get(i + n);
+        let prev_is_end = i > 0 && tokens[i - 1].kind == TokenKind::End;
+        let name = next(1).map(|t| t.text.to_lowercase()).unwrap_or_default();
+        match token.kind {
+            // entity X is
+            TokenKind::Entity
+                if !prev_is_end && next(2).is_some_and(|t| t.kind == TokenKind::Is) =>
+            {
+                units.defines.push(name.clone());
+                units.entities.push(name);
+            }
+            // architecture A of X is
+            TokenKind::Architecture if next(2).is_some_and(|t| t.kind == TokenKind::Of) => {
+                if let Some(entity) = next(3) {
+                    units.owners.push(entity.text.to_lowercase());
+                }
+            }
+            // package X is / package body X is
+            TokenKind::Package if !prev_is_end => match next(1) {
+                Some(t) if is_word(t, "body") => {
+                    if let Some(pkg) = next(2) {
+                        units.owners.push(pkg.text.to_lowercase());
+                    }
+                }
+                Some(_) if next(2).is_some_and(|t| t.kind == TokenKind::Is) => {
+                    units.defines.push(name)
+                }
+                _ => {}
+            },
+            // work.X（use 節とエンティティの直接インスタンス）
+            TokenKind::Identifier
+                if is_word(token, "work") && next(1).is_some_and(|t| t.kind == TokenKind::Dot) =>
+            {
+                if let Some(unit) = next(2) {
+                    units.uses.push(unit.text.to_lowercase());
+                }
+            }
+            // label : [component] X port/generic map
+            TokenKind::Colon if i > 0 && tokens[i - 1].kind == TokenKind::Identifier => {
+                let at = if next(1).is_some_and(|t| t.kind == TokenKind::Component) {
+                    2
+                } else {
+                    1
+                };
+                let is_instance = next(at).is_some_and(|t| t.kind == TokenKind::Identifier)
+                    && next(at + 1)
+                        .is_some_and(|t| matches!(t.kind, TokenKind::Port | TokenKind::Generic))
+                    && next(at + 2).is_some_and(|t| t.kind == TokenKind::Map);
+                if is_instance {
+                    units.uses.push(tokens[i + at].text.to_lowercase());
+                }
+            }
+            _ => {}
+        }
+    }
+    units
+}
+
+/// 設計単位の依存関係からコンパイル順を求める（ソースの添字の並び）
+///
+/// 参照先（`use work.pkg`・インスタンス化するエンティティなど）を定義するソースを先にします。
+/// 依存関係のないソース同士や循環している場合は与えた順のままです。
+///
+/// ```
+/// use vig::sim::compile_order;
+///
+/// let tb = "entity top_tb is end; architecture a of top_tb is begin u: entity work.top; end;";
+/// let top = "use work.pkg.all; entity top is end;";
+/// let pkg = "package pkg is end package;";
+/// assert_eq!(compile_order(&[tb, top, pkg]), [2, 1, 0]);
+/// ```
+pub fn compile_order(sources: &[&str]) -> Vec<usize> {
+    let units: Vec<Units> = sources.iter().map(|s| scan_units(s)).collect();
+    let depends = |i: usize, j: usize| {
+        i != j
+            && units[i]
+                .uses
+                .iter()
+                .chain(&units[i].owners)
+                .any(|u| units[j].defines.contains(u) && !units[i].defines.contains(u))
+    };
+    let mut order = Vec::new();
+    let mut done = vec![false; sources.len()];
+    while order.len() < sources.len() {
+        // 未処理のソースに依存していない最初のソース（循環していれば最初の未処理）
+        let ready = (0..sources.len())
+            .filter(|&i| !done[i])
+            .find(|&i| !(0..sources.len()).any(|j| !done[j] && depends(i, j)))
+            .or_else(|| (0..sources.len()).find(|&i| !done[i]));
+        let Some(i) = ready else { break };
+        done[i] = true;
+        order.push(i);
+    }
+    order
+}
+
+/// ほかのソースから参照されないエンティティ（トップの候補、出現順）
+pub fn top_candidates(sources: &[&str]) -> Vec<String> {
+    let units: Vec<Units> = sources.iter().map(|s| scan_units(s)).collect();
+    units
+        .iter()
+        .flat_map(|u| &u.entities)
+        .filter(|name| !units.iter().any(|u| u.uses.contains(name)))
+        .cloned()
+        .collect()
+}
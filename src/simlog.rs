@@ -0,0 +1,483 @@
+//! シミュレーションログの解析
+//!
+//! GHDL・NVC・ModelSim/Questa の出力から assert / report の報告（重大度・時刻・位置）を
+//! 取り出し、最終的な結果を [`SimReport`] にまとめます。形式は行ごとに判別するため、
+//! どのシミュレータのログかを指定する必要はありません。
+//!
+//! ```
+//! use vig::generator::AssertSeverity;
+//! use vig::simlog::{SimReport, SimStatus};
+//!
+//! let log = "\
+//! counter_tb.vhd:80:13:@50ns:(assertion error): row 3: q mismatch
+//! counter_tb.vhd:92:9:@130ns:(assertion note): Simulation finished
+//! ";
+//! let report = SimReport::parse(log);
+//! assert_eq!(report.status, SimStatus::Failed);
+//! assert_eq!(report.messages[0].severity, AssertSeverity::Error);
+//! assert_eq!(report.messages[0].time_fs, Some(50_000_000));
+//! assert!(report.finished);
+//! ```
+
+use crate::generator::{AssertSeverity, FINISH_MESSAGE, TIMEOUT_MESSAGE};
+use crate::literal::parse_time_fs;
+use crate::sim::SimOutput;
+use crate::xml::escape;
+
+/// assert / report による報告1件
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimMessage {
+    pub severity: AssertSeverity,
+    /// シミュレーション時刻（fs）
+    pub time_fs: Option<u128>,
+    pub text: String,
+    /// 報告した文のファイル
+    pub file: Option<String>,
+    /// 報告した文の行（1始まり）
+    pub line: Option<usize>,
+}
+
+impl SimMessage {
+    fn new(severity: AssertSeverity, time_fs: Option<u128>, text: &str) -> Self {
+        Self {
+            severity,
+            time_fs,
+            text: text.trim().to_string(),
+            file: None,
+            line: None,
+        }
+    }
+
+    /// 生成したテストベンチの終了の報告か
+    pub fn is_finish(&self) -> bool {
+        self.text == FINISH_MESSAGE
+    }
+
+    /// 生成したテストベンチの監視プロセスによる時間切れの報告か
+    pub fn is_timeout(&self) -> bool {
+        self.text.starts_with(TIMEOUT_MESSAGE)
+    }
+
+    /// 失敗として扱う報告か（error 以上と時間切れ、終了の報告は除く）
+    pub fn is_failure(&self) -> bool {
+        (self.severity >= AssertSeverity::Error && !self.is_finish()) || self.is_timeout()
+    }
+}
+
+impl std::fmt::Display for SimMessage {
+    /// `counter_tb.vhd:80: @50 ns: error: row 3: q mismatch`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}: ", file, line)?,
+            (Some(file), None) => write!(f, "{}: ", file)?,
+            _ => {}
+        }
+        if let Some(fs) = self.time_fs {
+            write!(f, "@{}: ", format_time(fs))?;
+        }
+        write!(f, "{}: {}", self.severity.as_str(), self.text)
+    }
+}
+
+/// シミュレーションの最終的な結果
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimStatus {
+    /// error 以上の報告がない
+    Passed,
+    /// error 以上の報告、またはシミュレータ自身のエラーがある
+    Failed,
+    /// 監視プロセスが時間切れを報告した
+    TimedOut,
+}
+
+impl SimStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SimStatus::Passed => "passed",
+            SimStatus::Failed => "failed",
+            SimStatus::TimedOut => "timed out",
+        }
+    }
+}
+
+/// シミュレーションログの解析結果
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimReport {
+    /// assert / report の報告（出現順）
+    pub messages: Vec<SimMessage>,
+    /// シミュレータ自身のエラー（コンパイルエラー・エラボレーションの失敗など）
+    pub tool_errors: Vec<String>,
+    /// 終了の報告（`Simulation finished`）があったか
+    pub finished: bool,
+    /// ログに現れた最後の時刻（fs）
+    pub end_time_fs: Option<u128>,
+    pub status: SimStatus,
+}
+
+impl SimReport {
+    /// ログを解析する
+    pub fn parse(log: &str) -> Self {
+        let mut messages: Vec<SimMessage> = Vec::new();
+        let mut tool_errors = Vec::new();
+        let mut end_time_fs = None;
+        // ModelSim の報告は続く行の Time: で時刻が付く
+        let mut pending: Option<SimMessage> = None;
+
+        for raw in log.lines() {
+            let line = raw.trim_end();
+            let body = line.strip_prefix('#').map_or(line, str::trim_start);
+
+            if let Some(message) = pending.as_mut() {
+                if body.starts_with("Time:") {
+                    attach_modelsim_details(message, body);
+                    messages.extend(pending.take());
+                    continue;
+                }
+                flush_untimed(pending.take(), &mut tool_errors);
+            }
+
+            if let Some(message) = parse_ghdl(body) {
+                messages.push(message);
+            } else if let Some(time) = ghdl_stop_time(body) {
+                end_time_fs = end_time_fs.max(Some(time));
+            } else if is_ghdl_error(body) {
+                // failure の報告で止めたときの後始末の行は数えない
+                let stopped = messages
+                    .last()
+                    .is_some_and(|m| m.severity == AssertSeverity::Failure);
+                if !(stopped && is_ghdl_abort(body)) {
+                    tool_errors.push(body.to_string());
+                }
+            } else if let Some(message) = parse_nvc(body) {
+                messages.push(message);
+            } else if let Some(message) = parse_modelsim(body) {
+                pending = Some(message);
+            } else if let Some(last) = messages.last_mut() {
+                attach_nvc_location(last, body);
+            }
+        }
+        flush_untimed(pending, &mut tool_errors);
+
+        let finished = messages.iter().any(SimMessage::is_finish);
+        let end_time_fs = messages
+            .iter()
+            .filter_map(|m| m.time_fs)
+            .max()
+            .max(end_time_fs);
+        let status = if messages.iter().any(SimMessage::is_timeout) {
+            SimStatus::TimedOut
+        } else if !tool_errors.is_empty() || messages.iter().any(SimMessage::is_failure) {
+            SimStatus::Failed
+        } else {
+            SimStatus::Passed
+        };
+        Self {
+            messages,
+            tool_errors,
+            finished,
+            end_time_fs,
+            status,
+        }
+    }
+
+    /// 実行結果の全コマンドの出力を解析する
+    ///
+    /// 終了の報告より前にコマンドが失敗していれば、報告がなくても失敗とします
+    /// （`finish_severity = "failure"` で止めた場合の終了コードは問いません）。
+    pub fn from_output(output: &SimOutput) -> Self {
+        let mut report = Self::parse(&output.log());
+        if !report.finished
+            && let Some(step) = output.steps.iter().find(|s| !s.success())
+        {
+            report.tool_errors.push(match step.status {
+                Some(code) => format!("'{}' exited with status {}", step.command, code),
+                None => format!("'{}' was terminated by a signal", step.command),
+            });
+            if report.status == SimStatus::Passed {
+                report.status = SimStatus::Failed;
+            }
+        }
+        report
+    }
+
+    /// シミュレータを実行できなかったときの結果
+    pub(crate) fn from_error(message: String) -> Self {
+        Self {
+            messages: Vec::new(),
+            tool_errors: vec![message],
+            finished: false,
+            end_time_fs: None,
+            status: SimStatus::Failed,
+        }
+    }
+
+    /// 重大度ごとの報告の数
+    pub fn count(&self, severity: AssertSeverity) -> usize {
+        self.messages
+            .iter()
+            .filter(|m| m.severity == severity && !m.is_finish())
+            .count()
+    }
+
+    /// 失敗として扱う報告（error 以上と時間切れ）
+    pub fn failures(&self) -> impl Iterator<Item = &SimMessage> {
+        self.messages.iter().filter(|m| m.is_failure())
+    }
+
+    /// 1行の要約（`failed: 2 errors, 1 warning at 130 ns`）
+    pub fn summary(&self) -> String {
+        let plural = |n: usize, word: &str| match n {
+            1 => format!("1 {}", word),
+            n => format!("{} {}s", n, word),
+        };
+        let mut counts = Vec::new();
+        let failures = self.count(AssertSeverity::Error) + self.count(AssertSeverity::Failure);
+        counts.push(plural(failures, "error"));
+        counts.push(plural(self.count(AssertSeverity::Warning), "warning"));
+        if !self.tool_errors.is_empty() {
+            counts.push(plural(self.tool_errors.len(), "simulator error"));
+        }
+        let mut out = format!("{}: {}", self.status.as_str(), counts.join(", "));
+        if let Some(fs) = self.end_time_fs {
+            out.push_str(&format!(" at {}", format_time(fs)));
+        }
+        if !self.finished {
+            out.push_str(" (not finished)");
+        }
+        out
+    }
+}
+
+/// テストベンチごとの結果を JUnit XML にする（`suite` はテストスイート名）
+///
+/// 失敗・時間切れは `<failure>`、シミュレータ自身のエラーは `<error>` で、
+/// すべての報告を `<system-out>` に含めます。
+pub fn to_junit(suite: &str, cases: &[(String, SimReport)]) -> String {
+    let failures = cases
+        .iter()
+        .filter(|(_, r)| r.status != SimStatus::Passed && r.tool_errors.is_empty())
+        .count();
+    let errors = cases
+        .iter()
+        .filter(|(_, r)| !r.tool_errors.is_empty())
+        .count();
+    let counts = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\"",
+        cases.len(),
+        failures,
+        errors
+    );
+    let suite = escape(suite);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!("<testsuites name=\"{}\" {}>\n", suite, counts));
+    out.push_str(&format!("  <testsuite name=\"{}\" {}>\n", suite, counts));
+    for (name, report) in cases {
+        out.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\"",
+            escape(name),
+            suite
+        ));
+        if report.status == SimStatus::Passed && report.messages.is_empty() {
+            out.push_str("/>\n");
+            continue;
+        }
+        out.push_str(">\n");
+        if !report.tool_errors.is_empty() {
+            out.push_str(&format!(
+                "      <error message=\"{}\">{}</error>\n",
+                escape(&report.tool_errors[0]),
+                escape(&report.tool_errors.join("\n"))
+            ));
+        } else if report.status != SimStatus::Passed {
+            let details: Vec<String> = report.failures().map(ToString::to_string).collect();
+            let first = report.failures().next().map_or("", |m| m.text.as_str());
+            out.push_str(&format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                escape(first),
+                report.status.as_str(),
+                escape(&details.join("\n"))
+            ));
+        }
+        if !report.messages.is_empty() {
+            let log: Vec<String> = report.messages.iter().map(ToString::to_string).collect();
+            out.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                escape(&log.join("\n"))
+            ));
+        }
+        out.push_str("    </testcase>\n");
+    }
+    out.push_str("  </testsuite>\n");
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// テストベンチごとの結果を TAP（version 13）にする
+///
+/// 通らなかったテストには状態と報告を YAML ブロックで付けます。
+pub fn to_tap(cases: &[(String, SimReport)]) -> String {
+    let mut out = String::from("TAP version 13\n");
+    out.push_str(&format!("1..{}\n", cases.len()));
+    for (i, (name, report)) in cases.iter().enumerate() {
+        if report.status == SimStatus::Passed {
+            out.push_str(&format!("ok {} - {}\n", i + 1, name));
+            continue;
+        }
+        out.push_str(&format!("not ok {} - {}\n", i + 1, name));
+        out.push_str("  ---\n");
+        out.push_str(&format!("  status: {}\n", report.status.as_str()));
+        let lines: Vec<String> = report
+            .tool_errors
+            .iter()
+            .cloned()
+            .chain(report.failures().map(ToString::to_string))
+            .collect();
+        if !lines.is_empty() {
+            out.push_str("  messages:\n");
+            for line in lines {
+                let quoted = line.replace('\\', "\\\\").replace('"', "\\\"");
+                out.push_str(&format!("    - \"{}\"\n", quoted));
+            }
+        }
+        out.push_str("  ...\n");
+    }
+    out
+}
+
+/// 時刻を割り切れる最大の単位で表す（`130 ns`、`1500 ps`）
+pub fn format_time(fs: u128) -> String {
+    const UNITS: [(&str, u128); 6] = [
+        ("sec", 1_000_000_000_000_000),
+        ("ms", 1_000_000_000_000),
+        ("us", 1_000_000_000),
+        ("ns", 1_000_000),
+        ("ps", 1_000),
+        ("fs", 1),
+    ];
+    let (unit, scale) = UNITS
+        .iter()
+        .find(|(_, scale)| fs.is_multiple_of(*scale) && fs >= *scale)
+        .unwrap_or(&("fs", 1));
+    format!("{} {}", fs / scale, unit)
+}
+
+fn parse_severity(text: &str) -> Option<AssertSeverity> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "fatal" => Some(AssertSeverity::Failure),
+        other => other.parse().ok(),
+    }
+}
+
+/// GHDL: `file.vhd:80:13:@50ns:(assertion error): メッセージ`
+fn parse_ghdl(line: &str) -> Option<SimMessage> {
+    let (location, rest) = line.split_once(":@")?;
+    let (time, rest) = rest.split_once(":(")?;
+    let (kind, text) = rest.split_once("):")?;
+    let (kind, severity) = kind.split_once(' ')?;
+    if !matches!(kind, "assertion" | "report") {
+        return None;
+    }
+    let mut message = SimMessage::new(parse_severity(severity)?, parse_time_fs(time), text);
+    let mut parts = location.rsplitn(3, ':');
+    let _column = parts.next();
+    message.line = parts.next().and_then(|l| l.parse().ok());
+    message.file = parts.next().map(str::to_string);
+    Some(message)
+}
+
+/// GHDL: `ghdl:info: simulation stopped by --stop-time @1us`
+fn ghdl_stop_time(line: &str) -> Option<u128> {
+    let rest = line.strip_prefix("ghdl:info: simulation stopped")?;
+    let (_, time) = rest.rsplit_once('@')?;
+    parse_time_fs(time)
+}
+
+/// GHDL: `ghdl:error: ...`、`file.vhd:12:5:error: ...`（コンパイルエラー）、
+/// `./counter_tb:error: simulation failed`
+fn is_ghdl_error(line: &str) -> bool {
+    line.contains(":error:") && !line.starts_with("**")
+}
+
+/// GHDL が failure の報告の後に出す `assertion failed` / `simulation failed`
+fn is_ghdl_abort(line: &str) -> bool {
+    ["assertion failed", "report failed", "simulation failed"]
+        .iter()
+        .any(|tail| line.ends_with(&format!("error: {}", tail)))
+}
+
+/// NVC: `** Error: 50ns+1: Assertion Error: メッセージ`（時刻のないものは ModelSim の形式）
+fn parse_nvc(line: &str) -> Option<SimMessage> {
+    let rest = line.strip_prefix("** ")?;
+    let (severity, rest) = rest.split_once(':')?;
+    let severity = parse_severity(severity)?;
+    let (time, text) = rest.trim_start().split_once(": ")?;
+    let (time, delta) = time.split_once('+')?;
+    delta.trim().parse::<u64>().ok()?;
+    let time = parse_time_fs(time)?;
+    // `Assertion Error: ` や `Report Note: ` の接頭辞を除く
+    let text = ["Assertion ", "Report "]
+        .iter()
+        .find_map(|prefix| {
+            let rest = text.strip_prefix(prefix)?;
+            let (sev, text) = rest.split_once(": ")?;
+            parse_severity(sev).map(|_| text)
+        })
+        .unwrap_or(text);
+    Some(SimMessage::new(severity, Some(time), text))
+}
+
+/// NVC: 報告の次の行 `Process :tb:stim at counter_tb.vhd:80`
+fn attach_nvc_location(message: &mut SimMessage, line: &str) {
+    let line = line.trim();
+    if message.file.is_some() || !line.starts_with("Process ") {
+        return;
+    }
+    let Some((_, location)) = line.rsplit_once(" at ") else {
+        return;
+    };
+    if let Some((file, number)) = location.rsplit_once(':')
+        && let Ok(number) = number.parse()
+    {
+        message.file = Some(file.to_string());
+        message.line = Some(number);
+    }
+}
+
+/// ModelSim: `** Error: メッセージ`（時刻は続く行）
+fn parse_modelsim(line: &str) -> Option<SimMessage> {
+    let rest = line.strip_prefix("** ")?;
+    let (severity, text) = rest.split_once(':')?;
+    // `** Error (suppressible): ...`
+    let severity = severity.split_whitespace().next()?;
+    Some(SimMessage::new(parse_severity(severity)?, None, text))
+}
+
+/// ModelSim: `Time: 50 ns  Iteration: 1  Process: /tb/stim File: tb.vhd Line: 80`
+fn attach_modelsim_details(message: &mut SimMessage, line: &str) {
+    let field = |name: &str| {
+        let start = line.find(name)? + name.len();
+        let rest = line[start..].trim_start();
+        Some(rest)
+    };
+    message.time_fs = field("Time:").and_then(|rest| {
+        let mut words = rest.split_whitespace();
+        let number = words.next()?;
+        let unit = words.next()?;
+        parse_time_fs(&format!("{} {}", number, unit))
+    });
+    message.file =
+        field("File:").and_then(|rest| rest.split_whitespace().next().map(str::to_string));
+    message.line = field("Line:").and_then(|rest| rest.split_whitespace().next()?.parse().ok());
+}
+
+/// 時刻が付かなかった ModelSim の報告（コンパイラ・シミュレータ自身のもの）
+fn flush_untimed(message: Option<SimMessage>, tool_errors: &mut Vec<String>) {
+    if let Some(message) = message
+        && message.severity >= AssertSeverity::Error
+    {
+        tool_errors.push(message.text);
+    }
+}
@@ -0,0 +1,209 @@
+//! バイトオフセットと行・列の対応表
+//!
+//! 位置は BOM を含む元のソースのバイトオフセットです。BOM は列に数えず、改行は LF と
+//! CRLF のどちらも（混在していても）1つの改行として扱います。
+
+use crate::lexer::{BOM, Span};
+
+/// 改行コード
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    /// テキストで多く使われている改行コード（同数か改行がなければ LF）
+    pub fn detect(text: &str) -> Self {
+        let lines = text.matches('\n').count();
+        let crlf = text.matches("\r\n").count();
+        if crlf * 2 > lines {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// テキストの改行（LF・CRLF）をこの改行コードにそろえる
+    pub fn apply(self, text: &str) -> String {
+        let lf = text.replace("\r\n", "\n");
+        match self {
+            LineEnding::Lf => lf,
+            LineEnding::CrLf => lf.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// エラーなどのソース上の位置（行・列は1始まり、列は文字単位）
+///
+/// `file:line:col` の形で表示します（ファイル名が分からなければ `line:col`）。
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Location {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// ソースファイルの行頭位置の表
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    name: String,
+    source: String,
+    /// 各行の先頭のバイトオフセット
+    line_starts: Vec<usize>,
+    line_ending: LineEnding,
+}
+
+impl SourceMap {
+    /// ファイル名とソースから対応表を作る
+    pub fn new(name: impl Into<String>, source: impl Into<String>) -> Self {
+        let source = source.into();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            name: name.into(),
+            line_ending: LineEnding::detect(&source),
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// 先頭に BOM があるか
+    pub fn has_bom(&self) -> bool {
+        self.source.starts_with(BOM)
+    }
+
+    /// 多く使われている改行コード
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// 生成したテキストをこのファイルの表記（BOM の有無と改行コード）にそろえる
+    pub fn match_conventions(&self, text: &str) -> String {
+        let text = self
+            .line_ending
+            .apply(text.strip_prefix(BOM).unwrap_or(text));
+        if self.has_bom() {
+            format!("{}{}", BOM, text)
+        } else {
+            text
+        }
+    }
+
+    /// 行数
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// オフセットの行・列（どちらも1始まり、列は文字単位）
+    ///
+    /// ソース末尾を超えるオフセットは末尾として扱います。
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let text = &self.source[line_start..offset];
+        let col = text.strip_prefix(BOM).unwrap_or(text).chars().count();
+        (line + 1, col + 1)
+    }
+
+    /// 行・列（どちらも1始まり、列は文字単位）のオフセット（[`SourceMap::line_col`] の逆）
+    ///
+    /// 列は行末（改行の `\n`）まで指せます。行や列が範囲外なら None です。
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let mut start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.source.len(), |&next| next - 1);
+        // 1行目の BOM は列に数えない（CRLF の CR は `line_col` と同じく1列に数える）
+        if line == 1 && self.has_bom() {
+            start += BOM.len_utf8();
+        }
+        let text = &self.source[start..end];
+        let column = column.checked_sub(1)?;
+        let len = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()))
+            .nth(column)?;
+        Some(start + len)
+    }
+
+    /// 範囲の始まりと終わりの行・列
+    pub fn span_line_cols(&self, span: Span) -> ((usize, usize), (usize, usize)) {
+        (self.line_col(span.start), self.line_col(span.end))
+    }
+
+    /// 始まりと終わりの行・列の範囲（[`SourceMap::span_line_cols`] の逆）
+    ///
+    /// ```
+    /// use vig::lexer::Span;
+    /// use vig::source_map::SourceMap;
+    ///
+    /// let map = SourceMap::new("x.vhd", "entity e is\nend;");
+    /// let span = map.span((2, 1), (2, 4)).unwrap();
+    /// assert_eq!(span, Span::new(12, 15));
+    /// assert_eq!(map.span_line_cols(span), ((2, 1), (2, 4)));
+    /// ```
+    pub fn span(&self, start: (usize, usize), end: (usize, usize)) -> Option<Span> {
+        let start = self.offset(start.0, start.1)?;
+        let end = self.offset(end.0, end.1)?;
+        (start <= end).then(|| Span::new(start, end))
+    }
+
+    /// オフセットの位置（名前が空ならファイル名なし）
+    pub fn location(&self, offset: usize) -> Location {
+        let (line, column) = self.line_col(offset);
+        Location {
+            file: (!self.name.is_empty()).then(|| self.name.clone()),
+            line,
+            column,
+        }
+    }
+
+    /// 行の内容（1始まり、改行を除く）
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.source.len(), |&next| next - 1);
+        let text = &self.source[start..end];
+        Some(
+            text.strip_prefix(BOM)
+                .unwrap_or(text)
+                .trim_end_matches('\r'),
+        )
+    }
+}
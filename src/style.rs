@@ -0,0 +1,381 @@
+//! テストベンチのスタイル
+//!
+//! [`TbConfig::style`](crate::generator::TbConfig::style) で選んだスタイルに合わせて、
+//! 標準の生成結果（[`TestbenchModel`]）を組み替えます。生成パスより前に適用するため、
+//! パスからはスタイル適用後のモデルが見えます。
+//!
+//! | スタイル | 内容 |
+//! |---|---|
+//! | `standard` | 標準（リセット・テストパターンの雛形・入力の例） |
+//! | `minimal` | 注釈を省いた最小限の構成 |
+//! | `assert-heavy` | 出力の不定値を毎クロック検査し、期待値の検査の雛形を付ける |
+//! | `file-driven` | `<エンティティ>_stim.txt` の各行を印加して期待値と比べる |
+//! | `procedure-based` | リセット・クロック待ち・入力の印加を手続きにまとめる |
+//!
+//! ```
+//! use vig::analyzer::{EntityDef, VhdlType::*};
+//! use vig::generator::TestbenchBuilder;
+//! use vig::style::TbStyle;
+//!
+//! let entity = EntityDef::builder("counter")
+//!     .input("clk", StdLogic)
+//!     .input("en", StdLogic)
+//!     .output("count", StdLogicVector { high: 7.into(), low: 0.into(), ascending: false })
+//!     .build();
+//! let tb = TestbenchBuilder::new(&entity)
+//!     .style("procedure-based".parse::<TbStyle>()?)
+//!     .build();
+//! assert!(tb.contains("procedure apply(en_value : std_logic) is"));
+//! # Ok::<(), String>(())
+//! ```
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use crate::generator::{
+    RESET_CYCLES, TEST_CYCLES, TbConfig, TbProcess, TestbenchModel, find_clock_port,
+    find_reset_port, reset_sequence, simulation_end, type_default_value, type_to_vhdl,
+};
+use crate::ident::{self, suffixed};
+
+/// テストベンチのスタイル
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TbStyle {
+    #[default]
+    Standard,
+    Minimal,
+    AssertHeavy,
+    FileDriven,
+    ProcedureBased,
+}
+
+impl TbStyle {
+    /// すべてのスタイル
+    pub const ALL: [TbStyle; 5] = [
+        TbStyle::Standard,
+        TbStyle::Minimal,
+        TbStyle::AssertHeavy,
+        TbStyle::FileDriven,
+        TbStyle::ProcedureBased,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TbStyle::Standard => "standard",
+            TbStyle::Minimal => "minimal",
+            TbStyle::AssertHeavy => "assert-heavy",
+            TbStyle::FileDriven => "file-driven",
+            TbStyle::ProcedureBased => "procedure-based",
+        }
+    }
+}
+
+impl std::str::FromStr for TbStyle {
+    type Err = String;
+
+    /// `assert_heavy` のように `_` で区切ってもよい
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('_', "-");
+        TbStyle::ALL
+            .into_iter()
+            .find(|style| style.as_str() == name)
+            .ok_or_else(|| format!("unknown testbench style '{}'", s))
+    }
+}
+
+/// スタイルに合わせてモデルを組み替える（`entity` は検査の対象にするポートだけのもの）
+pub(crate) fn apply(model: &mut TestbenchModel, entity: &EntityDef, config: &TbConfig) {
+    match config.style {
+        TbStyle::Standard => {}
+        TbStyle::Minimal => minimal(model),
+        TbStyle::AssertHeavy => assert_heavy(model, entity, config),
+        TbStyle::FileDriven => file_driven(model, entity, config),
+        TbStyle::ProcedureBased => procedure_based(model, entity, config),
+    }
+}
+
+/// クロック・リセットを除く入力（双方向を含む）
+fn inputs<'a>(entity: &'a EntityDef, clk: Option<&str>, rst: Option<&str>) -> Vec<&'a PortDef> {
+    entity
+        .ports
+        .iter()
+        .filter(|p| matches!(p.direction, PortDirection::In | PortDirection::Inout))
+        .filter(|p| Some(p.name.as_str()) != clk && Some(p.name.as_str()) != rst)
+        .collect()
+}
+
+fn outputs(entity: &EntityDef) -> Vec<&PortDef> {
+    entity
+        .ports
+        .iter()
+        .filter(|p| matches!(p.direction, PortDirection::Out | PortDirection::Buffer))
+        .collect()
+}
+
+/// ファイルから読み書きできる型か
+fn is_readable(vhdl_type: &VhdlType) -> bool {
+    !matches!(vhdl_type, VhdlType::Other(_))
+}
+
+/// 注釈の行とプロセスの説明を除き、続く空行を1行にまとめる
+fn minimal(model: &mut TestbenchModel) {
+    for process in &mut model.processes {
+        process.comment = None;
+        let mut body: Vec<String> = Vec::new();
+        for line in process.body.drain(..) {
+            if line.trim_start().starts_with("--") {
+                continue;
+            }
+            if line.is_empty() && body.last().is_none_or(String::is_empty) {
+                continue;
+            }
+            body.push(line);
+        }
+        process.body = body;
+    }
+}
+
+/// 出力の不定値を検査するプロセスと、スティミュラスに期待値の検査の雛形を加える
+fn assert_heavy(model: &mut TestbenchModel, entity: &EntityDef, config: &TbConfig) {
+    let clk = find_clock_port(&entity.ports);
+    let rst = find_reset_port(&entity.ports);
+    let severity = config.check_severity.as_str();
+    let outputs = outputs(entity);
+
+    if let Some(stim) = model.process_mut("stim_process") {
+        let at = stim
+            .body
+            .iter()
+            .position(|l| {
+                [
+                    "-- カバレッジの報告",
+                    "-- 監視プロセスへの終了の通知",
+                    "-- シミュレーション終了",
+                ]
+                .contains(&l.as_str())
+            })
+            .unwrap_or(stim.body.len());
+        let mut template = vec!["-- 期待値の検査の例:".to_string()];
+        template.extend(outputs.iter().map(|p| {
+            format!(
+                "-- assert {} = {} report \"{} mismatch\" severity {};",
+                p.name,
+                type_default_value(&p.vhdl_type),
+                p.name,
+                severity
+            )
+        }));
+        template.push(String::new());
+        if !outputs.is_empty() {
+            stim.body.splice(at..at, template);
+        }
+    }
+
+    let checks: Vec<String> = outputs
+        .iter()
+        .filter(|p| {
+            matches!(
+                p.vhdl_type,
+                VhdlType::StdLogic
+                    | VhdlType::StdLogicVector { .. }
+                    | VhdlType::Signed { .. }
+                    | VhdlType::Unsigned { .. }
+            )
+        })
+        .map(|p| {
+            format!(
+                "assert not is_x({0}) report \"{0} is unknown\" severity {1};",
+                p.name, severity
+            )
+        })
+        .collect();
+    if checks.is_empty() {
+        return;
+    }
+    let mut process = TbProcess::new("output_checks");
+    process.comment = Some("出力の不定値の検査".to_string());
+    let guard = match (&clk, &rst) {
+        (Some(clk), Some(rst)) => format!(
+            "if rising_edge({}) and {} = {} then",
+            clk,
+            rst,
+            config.reset_polarity.released()
+        ),
+        (Some(clk), None) => format!(
+            "if rising_edge({}) and now > {} ns then",
+            clk,
+            config.clock_period_ns * RESET_CYCLES
+        ),
+        (None, _) => "if now > 0 ns then".to_string(),
+    };
+    process.sensitivity = match &clk {
+        Some(clk) => vec![clk.clone()],
+        None => outputs.iter().map(|p| p.name.clone()).collect(),
+    };
+    process.body.push(guard);
+    process
+        .body
+        .extend(checks.iter().map(|c| format!("    {}", c)));
+    process.body.push("end if;".to_string());
+    model.processes.push(process);
+}
+
+/// 入力ファイルの各行を印加して出力を期待値と比べるスティミュラスにする
+fn file_driven(model: &mut TestbenchModel, entity: &EntityDef, config: &TbConfig) {
+    let clk = find_clock_port(&entity.ports);
+    let rst = find_reset_port(&entity.ports);
+    let inputs: Vec<&PortDef> = inputs(entity, clk.as_deref(), rst.as_deref())
+        .into_iter()
+        .filter(|p| is_readable(&p.vhdl_type))
+        .collect();
+    let outputs: Vec<&PortDef> = outputs(entity)
+        .into_iter()
+        .filter(|p| is_readable(&p.vhdl_type))
+        .collect();
+    let file = format!("{}_stim.txt", ident::bare(&entity.name));
+    let columns: Vec<&str> = inputs
+        .iter()
+        .chain(&outputs)
+        .map(|p| p.name.as_str())
+        .collect();
+
+    for line in ["use std.textio.all;", "use ieee.std_logic_textio.all;"] {
+        if !model.context.iter().any(|l| l == line) {
+            model.context.push(line.to_string());
+        }
+    }
+    model.header.extend([
+        format!("入力ファイル {} の各行: {}", file, columns.join(" ")),
+        "（入力の後に出力の期待値、'#' で始まる行と空行は読み飛ばす）".to_string(),
+    ]);
+
+    let done = model.done_signal.clone();
+    let Some(stim) = model.process_mut("stim_process") else {
+        return;
+    };
+    stim.comment = Some(format!("{} の各行を印加して検査", file));
+    stim.declarations = vec![
+        format!("file stim_file : text open read_mode is \"{}\";", file),
+        "variable l : line;".to_string(),
+        "variable n : natural := 0;".to_string(),
+    ];
+    stim.declarations
+        .extend(inputs.iter().chain(&outputs).map(|p| {
+            format!(
+                "variable {} : {};",
+                suffixed(&p.name, "_value"),
+                type_to_vhdl(&p.vhdl_type)
+            )
+        }));
+
+    let mut body = Vec::new();
+    if let Some(rst) = &rst {
+        body.push("-- リセット".to_string());
+        body.extend(reset_sequence(rst, config));
+        body.push(String::new());
+    }
+    body.push("while not endfile(stim_file) loop".to_string());
+    body.push("    readline(stim_file, l);".to_string());
+    body.push("    n := n + 1;".to_string());
+    body.push("    next when l'length = 0;".to_string());
+    body.push("    next when l(l'low) = '#';".to_string());
+    for port in &inputs {
+        body.push(format!("    read(l, {});", suffixed(&port.name, "_value")));
+        body.push(format!(
+            "    {} <= {};",
+            port.name,
+            suffixed(&port.name, "_value")
+        ));
+    }
+    for port in &outputs {
+        body.push(format!("    read(l, {});", suffixed(&port.name, "_value")));
+    }
+    body.push(format!("    wait for {} ns;", config.clock_period_ns));
+    for port in &outputs {
+        body.push(format!(
+            "    assert {0} = {1} report \"line \" & integer'image(n) & \": {0} mismatch\" severity {2};",
+            port.name,
+            suffixed(&port.name, "_value"),
+            config.check_severity.as_str()
+        ));
+    }
+    body.push("end loop;".to_string());
+    body.push(String::new());
+    body.extend(simulation_end(&entity.ports, config, &done));
+    stim.body = body;
+}
+
+/// リセット・クロック待ち・入力の印加を手続きにまとめたスティミュラスにする
+fn procedure_based(model: &mut TestbenchModel, entity: &EntityDef, config: &TbConfig) {
+    let clk = find_clock_port(&entity.ports);
+    let rst = find_reset_port(&entity.ports);
+    let inputs = inputs(entity, clk.as_deref(), rst.as_deref());
+    let period = config.clock_period_ns;
+    let done = model.done_signal.clone();
+
+    let Some(stim) = model.process_mut("stim_process") else {
+        return;
+    };
+    stim.comment = Some("手続きによるテストシナリオ".to_string());
+    let mut decls = Vec::new();
+    if let Some(rst) = &rst {
+        decls.push("procedure reset_dut is".to_string());
+        decls.push("begin".to_string());
+        decls.extend(
+            reset_sequence(rst, config)
+                .into_iter()
+                .map(|l| format!("    {}", l)),
+        );
+        decls.push("end procedure;".to_string());
+        decls.push(String::new());
+    }
+    decls.push("procedure tick(cycles : positive := 1) is".to_string());
+    decls.push("begin".to_string());
+    decls.push("    for i in 1 to cycles loop".to_string());
+    decls.push(match &clk {
+        Some(clk) => format!("        wait until rising_edge({});", clk),
+        None => format!("        wait for {} ns;", period),
+    });
+    decls.push("    end loop;".to_string());
+    decls.push("end procedure;".to_string());
+
+    let mut body = Vec::new();
+    if rst.is_some() {
+        body.push("reset_dut;".to_string());
+        body.push(String::new());
+    }
+    body.push("-- TODO: テストパターンを記述".to_string());
+    if !inputs.is_empty() {
+        let params: Vec<String> = inputs
+            .iter()
+            .map(|p| {
+                let value = suffixed(&p.name, "_value");
+                format!("{} : {}", value, type_to_vhdl(&p.vhdl_type))
+            })
+            .collect();
+        decls.push(String::new());
+        decls.push(format!("procedure apply({}) is", params.join("; ")));
+        decls.push("begin".to_string());
+        decls.extend(
+            inputs
+                .iter()
+                .map(|p| format!("    {} <= {};", p.name, suffixed(&p.name, "_value"))),
+        );
+        decls.push("    tick;".to_string());
+        decls.push("end procedure;".to_string());
+
+        let args: Vec<String> = inputs
+            .iter()
+            .map(|p| {
+                let value = suffixed(&p.name, "_value");
+                format!("{} => {}", value, type_default_value(&p.vhdl_type))
+            })
+            .collect();
+        body.push(format!("apply({});", args.join(", ")));
+    }
+    body.push(format!("tick({});", TEST_CYCLES));
+    body.push(String::new());
+    body.extend(simulation_end(&entity.ports, config, &done));
+
+    stim.declarations = decls;
+    stim.body = body;
+}
@@ -0,0 +1,220 @@
+//! ジェネリックの組ごとの一括テストベンチ
+//!
+//! パラメータを持つ IP を1回のシミュレーションで検証するため、ジェネリックの値の組ごとに
+//! DUT・スティミュラス・検査をまとめたテストベンチ（ハーネス）を作り、それらをすべて
+//! 実体化するトップを生成します。ハーネスの DUT は `entity work.名前` で直接実体化し、
+//! `generic map` で組の値を渡します。
+//!
+//! 値の組は vig.toml のテーブル配列 `[[sweep.<エンティティ>]]` で指定します（[`from_config`]）。
+//!
+//! ```toml
+//! [[sweep.fifo]]
+//! name = "shallow"
+//! DEPTH = 4
+//!
+//! [[sweep.fifo]]
+//! name = "deep"
+//! DEPTH = 64
+//! MODE = '"safe"'
+//! ```
+
+use crate::analyzer::EntityDef;
+use crate::generator::{TbConfig, build_model};
+use crate::json::Value;
+use crate::lexer::Span;
+use crate::toml::{self, TomlError};
+
+/// ジェネリックの組の設定エラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl SweepError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for SweepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+impl From<TomlError> for SweepError {
+    fn from(err: TomlError) -> Self {
+        SweepError::new(err.message, err.span)
+    }
+}
+
+/// ジェネリックの値の組1つ
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenericSet {
+    /// 組の名前（ハーネスとインスタンスの名前に使う）
+    pub name: String,
+    /// (ジェネリック名, VHDL の値の表記) の組
+    pub values: Vec<(String, String)>,
+}
+
+impl GenericSet {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            values: Vec::new(),
+        }
+    }
+
+    /// ジェネリックの値を追加（値は VHDL の表記のまま）
+    pub fn value(mut self, generic: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values.push((generic.into(), value.into()));
+        self
+    }
+}
+
+/// vig.toml の `[[sweep.<エンティティ>]]` からジェネリックの組を読み込む
+///
+/// `name` 以外のキーがジェネリックです。整数・実数・真偽値はそのまま VHDL の値になり、
+/// 文字列は VHDL の式として埋め込みます（文字列の値なら `'"fast"'` のように引用符ごと書きます）。
+/// `name` を省略した組は `set1`、`set2`… と番号で呼びます。
+pub fn from_config(source: &str, entity: &str) -> Result<Vec<GenericSet>, SweepError> {
+    let root = toml::parse(source)?;
+    let Some(Value::Object(table)) = root.get("sweep") else {
+        return Ok(Vec::new());
+    };
+    let Some((key, value)) = table.iter().find(|(k, _)| k.eq_ignore_ascii_case(entity)) else {
+        return Ok(Vec::new());
+    };
+    // 値の位置はファイル中の文字列から探す
+    let locate = |text: &str| {
+        source
+            .find(text)
+            .map_or(Span::new(0, 0), |pos| Span::new(pos, pos + text.len()))
+    };
+    let header = format!("[[sweep.{}]]", key);
+    let items = value.as_array().ok_or_else(|| {
+        SweepError::new(
+            format!("sweep of '{}' must be an array of tables ({})", key, header),
+            locate(&format!("sweep.{}", key)),
+        )
+    })?;
+
+    let mut sets: Vec<GenericSet> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let Value::Object(fields) = item else {
+            return Err(SweepError::new(
+                format!("sweep of '{}' must be an array of tables ({})", key, header),
+                locate(&format!("sweep.{}", key)),
+            ));
+        };
+        let mut set = GenericSet::new(format!("set{}", i + 1));
+        for (field, value) in fields {
+            if field == "name" {
+                let name = value.as_str().filter(|n| is_identifier(n)).ok_or_else(|| {
+                    SweepError::new("'name' must be a VHDL identifier", locate("name"))
+                })?;
+                set.name = name.to_string();
+                continue;
+            }
+            let text = match value {
+                Value::Integer(n) => n.to_string(),
+                Value::Float(x) => format!("{:?}", x),
+                Value::Bool(b) => b.to_string(),
+                Value::String(s) => s.clone(),
+                _ => {
+                    return Err(SweepError::new(
+                        format!(
+                            "generic '{}' must be an integer, real, boolean or string",
+                            field
+                        ),
+                        locate(field),
+                    ));
+                }
+            };
+            set.values.push((field.clone(), text));
+        }
+        if sets.iter().any(|s| s.name.eq_ignore_ascii_case(&set.name)) {
+            return Err(SweepError::new(
+                format!("duplicate generic set '{}'", set.name),
+                locate(&format!("\"{}\"", set.name)),
+            ));
+        }
+        sets.push(set);
+    }
+    Ok(sets)
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic())
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !text.contains("__")
+        && !text.ends_with('_')
+}
+
+/// ジェネリックの組ごとのハーネスと、それらを実体化するトップを生成する
+///
+/// ハーネスは通常のテストベンチと同じ内容（`config` のアサーション・カバレッジなどを含む）で、
+/// 名前は `<エンティティ>_tb_<組>`、トップは `<エンティティ>_sweep_tb` です。
+pub fn generate_sweep(entity: &EntityDef, sets: &[GenericSet], config: &TbConfig) -> String {
+    let top = format!("{}_sweep_tb", entity.name);
+    let mut out = String::new();
+    let mut instances = Vec::new();
+    for set in sets {
+        // ポートの範囲は組の値で評価する
+        let mut entity = entity.clone();
+        for generic in &mut entity.generics {
+            if let Some((_, value)) = set
+                .values
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&generic.name))
+            {
+                // 式として読めない値はポートの範囲の評価に使わない
+                generic.default_value = value.parse().ok();
+            }
+        }
+        let mut model = build_model(&entity, config);
+        let label = format!("{}_tb", set.name);
+        // ステートマシンの観測の外部名をトップからの階層に直す
+        let old_path = format!(".{}.{}.", model.name, model.instance.label);
+        let new_path = format!(".{}.{}.{}.", top, label, model.instance.label);
+        for process in &mut model.processes {
+            for line in process.declarations.iter_mut().chain(&mut process.body) {
+                *line = line.replace(&old_path, &new_path);
+            }
+        }
+        model.name = format!("{}_tb_{}", entity.name, set.name);
+        model.instance.unit = format!("entity work.{}", entity.name);
+        model.instance.generic_map = set.values.clone();
+        out.push_str(&model.render());
+        out.push('\n');
+        instances.push(format!("    {}: entity work.{};\n", label, model.name));
+    }
+
+    out.push_str(&format!(
+        "-- {} をジェネリックの組ごとに実体化するトップ\n",
+        entity.name
+    ));
+    out.push_str(&format!("entity {} is\n", top));
+    out.push_str(&format!("end entity {};\n", top));
+    out.push('\n');
+    out.push_str(&format!("architecture sweep of {} is\n", top));
+    out.push_str("begin\n");
+    out.push('\n');
+    for instance in instances {
+        out.push_str(&instance);
+    }
+    out.push('\n');
+    out.push_str("end architecture sweep;\n");
+    out
+}
@@ -0,0 +1,1007 @@
+//! 既存テストベンチの読み取りと更新
+//!
+//! 手で編集したテストベンチを再生成せずに DUT のポート変更へ追従させます。
+//! DUT のコンポーネント宣言・信号宣言・ポートマップだけを読み取り、
+//! 変わったポートに関係する箇所だけを書き換えます。
+//! 設計資料が失われたテストベンチから DUT のポートを復元することもできます。
+
+use std::collections::HashMap;
+
+use crate::analyzer::{
+    EntityDef, InstanceDef, InstantiatedUnit, PortDef, PortDirection, VhdlType, analyze_vhdl,
+    parse_type_str,
+};
+use crate::diagnostic::Diagnostic;
+use crate::generator::type_default_value;
+use crate::lexer::{Lexer, LexerOptions, Span, Token, TokenKind};
+use crate::rewrite::{
+    self, Edit, ItemChange, line_extent, line_indent, list_edits, next_line_start,
+};
+
+/// テストベンチの読み取りエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestbenchError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl TestbenchError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for TestbenchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for TestbenchError {}
+
+/// テストベンチ更新の結果
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TbUpdate {
+    /// 更新後のテストベンチ
+    pub text: String,
+    /// 行った変更（info）と手で直す必要がある箇所（warning）。位置は更新前のテキスト上
+    pub changes: Vec<Diagnostic>,
+}
+
+/// 既存テストベンチを DUT の現在のポートに合わせて更新する
+///
+/// コンポーネント宣言とポートマップに追加・削除・型や方向の変更を反映し、
+/// 新しいポートにはポート名の信号を宣言します。外したポートにつないでいた信号は、
+/// ほかで参照していなければ宣言も削除します。それ以外の記述はそのまま残します。
+///
+/// ```
+/// use vig::analyzer::{EntityDef, VhdlType::*};
+/// use vig::generator::TestbenchBuilder;
+/// use vig::testbench::update_testbench;
+///
+/// let old = EntityDef::builder("dut").input("clk", StdLogic).output("q", StdLogic).build();
+/// let tb = TestbenchBuilder::new(&old).build();
+///
+/// let new = EntityDef::builder("dut").input("clk", StdLogic).input("en", StdLogic)
+///     .output("q", StdLogic).build();
+/// let update = update_testbench(&tb, &new)?;
+/// assert!(update.text.contains("en => en"));
+/// assert!(update.text.contains("signal en : std_logic := '0';"));
+/// # Ok::<(), vig::testbench::TestbenchError>(())
+/// ```
+pub fn update_testbench(source: &str, entity: &EntityDef) -> Result<TbUpdate, TestbenchError> {
+    let tb = Testbench::parse(source);
+    let instance = tb
+        .instances
+        .iter()
+        .find(|i| i.unit().eq_ignore_ascii_case(&entity.name))
+        .ok_or_else(|| {
+            TestbenchError::new(
+                format!("no instance of '{}' found", entity.name),
+                Span::new(0, 0),
+            )
+        })?;
+    if let Some(assoc) = instance.def.port_map.iter().find(|a| a.formal.is_none()) {
+        return Err(TestbenchError::new(
+            format!(
+                "positional port map of '{}' is not supported",
+                instance.def.label
+            ),
+            assoc.span,
+        ));
+    }
+    let component = tb
+        .components
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(&entity.name));
+
+    let mut updater = Updater {
+        source,
+        tb: &tb,
+        entity,
+        edits: Vec::new(),
+        changes: Vec::new(),
+    };
+    match component {
+        Some(component) => updater.update_component(component),
+        None if instance.is_component() => updater.changes.push(Diagnostic::warning(
+            format!(
+                "component declaration of '{}' not found; only the port map was updated",
+                entity.name
+            ),
+            instance.label_span,
+        )),
+        None => {}
+    }
+    updater.update_port_map(instance, component);
+    updater.update_signals(instance, component);
+
+    let Updater { edits, changes, .. } = updater;
+    Ok(TbUpdate {
+        text: rewrite::apply(source, edits).map_err(|e| TestbenchError::new(e.message, e.span))?,
+        changes,
+    })
+}
+
+/// テストベンチから復元した DUT
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedDut {
+    pub entity: EntityDef,
+    /// 推定で補った方向・型（warning）。位置はテストベンチ上
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// テストベンチから DUT のポートを復元する
+///
+/// DUT はラベルが `uut` か `dut` のインスタンス、なければ最初のインスタンスとします。
+/// コンポーネント宣言があればそのポート宣言をそのまま使います。エンティティを直接
+/// インスタンス化している場合はポートマップから復元し、型はつないだ信号の宣言から、
+/// 方向はテストベンチがその信号に代入しているか（代入していれば `in`）から推定します。
+///
+/// ```
+/// use vig::testbench::extract_dut;
+///
+/// let tb = "architecture sim of top_tb is
+///     signal a, y : std_logic;
+/// begin
+///     dut: entity work.top port map (a => a, y => y);
+///     a <= '1';
+/// end sim;";
+/// let dut = extract_dut(tb)?;
+/// assert_eq!(dut.entity.name, "top");
+/// assert_eq!(dut.entity.ports[0].direction.to_string(), "in");
+/// assert_eq!(dut.entity.ports[1].direction.to_string(), "out");
+/// # Ok::<(), vig::testbench::TestbenchError>(())
+/// ```
+pub fn extract_dut(source: &str) -> Result<ExtractedDut, TestbenchError> {
+    let tb = Testbench::parse(source);
+    let is_dut_label = |i: &&Instance| {
+        i.def.label.eq_ignore_ascii_case("uut") || i.def.label.eq_ignore_ascii_case("dut")
+    };
+    let instance = tb
+        .instances
+        .iter()
+        .find(is_dut_label)
+        .or(tb.instances.first())
+        .ok_or_else(|| TestbenchError::new("no component instance found", Span::new(0, 0)))?;
+
+    let component = tb
+        .components
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(instance.unit()));
+    if let Some(component) = component {
+        return Ok(ExtractedDut {
+            entity: component.to_entity(),
+            warnings: Vec::new(),
+        });
+    }
+
+    if let Some(assoc) = instance.def.port_map.iter().find(|a| a.formal.is_none()) {
+        return Err(TestbenchError::new(
+            format!(
+                "positional port map of '{}' needs a component declaration",
+                instance.def.label
+            ),
+            assoc.span,
+        ));
+    }
+    let mut ports: Vec<PortDef> = Vec::new();
+    let mut warnings = Vec::new();
+    for assoc in &instance.def.port_map {
+        let name = assoc.formal_name().unwrap_or_default();
+        // 部分的な関連付け（data(0) => ...）は1つのポートにまとめる
+        if ports.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+        let signal = is_simple_name(&assoc.actual)
+            .then(|| tb.signal(&assoc.actual))
+            .flatten();
+        let vhdl_type = match signal {
+            Some((decl, _)) => {
+                let text = &source[decl.type_span.start..decl.type_span.end];
+                parse_type_str(text).unwrap_or_else(|_| VhdlType::Other(text.to_string()))
+            }
+            None => {
+                warnings.push(Diagnostic::warning(
+                    format!("type of port '{}' is unknown; assumed std_logic", name),
+                    assoc.span,
+                ));
+                VhdlType::StdLogic
+            }
+        };
+        let direction = if signal.is_some() && tb.is_assigned(&assoc.actual) {
+            PortDirection::In
+        } else {
+            PortDirection::Out
+        };
+        warnings.push(Diagnostic::warning(
+            format!("direction of port '{}' inferred as '{}'", name, direction),
+            assoc.span,
+        ));
+        ports.push(PortDef {
+            name: name.to_string(),
+            direction,
+            vhdl_type,
+            span: assoc.span,
+        });
+    }
+    Ok(ExtractedDut {
+        entity: EntityDef {
+            name: instance.unit().to_string(),
+            generics: Vec::new(),
+            ports,
+            span: instance.label_span,
+        },
+        warnings,
+    })
+}
+
+/// ソースのコンポーネント宣言をエンティティにする（宣言の順）
+///
+/// 実装がまだない下位ブロックのポートを、上位のアーキテクチャの宣言から得るためのものです。
+pub fn component_declarations(source: &str) -> Vec<EntityDef> {
+    Testbench::parse(source)
+        .components
+        .iter()
+        .map(Component::to_entity)
+        .collect()
+}
+
+/// 型の表記がポートの型と同じか（空白と大文字・小文字の違いは無視）
+pub(crate) fn same_type(text: &str, vhdl_type: &VhdlType) -> bool {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    parse_type_str(text).is_ok_and(|t| t == *vhdl_type)
+        || normalize(text) == normalize(&vhdl_type.to_string())
+}
+
+/// ポート宣言の記述
+fn port_decl(port: &PortDef) -> String {
+    format!("{} : {} {}", port.name, port.direction, port.vhdl_type)
+}
+
+/// 信号宣言の記述
+fn signal_decl(name: &str, vhdl_type: &VhdlType) -> String {
+    format!(
+        "signal {} : {} := {};",
+        name,
+        vhdl_type,
+        type_default_value(vhdl_type)
+    )
+}
+
+/// 更新の作業状態
+struct Updater<'a> {
+    source: &'a str,
+    tb: &'a Testbench<'a>,
+    entity: &'a EntityDef,
+    edits: Vec<Edit>,
+    changes: Vec<Diagnostic>,
+}
+
+impl<'a> Updater<'a> {
+    fn port(&self, name: &str) -> Option<&'a PortDef> {
+        self.entity
+            .ports
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// コンポーネント宣言のポートを更新し、ポートごとの変更を報告する
+    fn update_component(&mut self, component: &Component) {
+        let mut changes = Vec::new();
+        let mut added = Vec::new();
+        for group in &component.groups {
+            let mut kept = Vec::new();
+            let mut replaced = None;
+            for (name, span) in &group.names {
+                match self.port(name) {
+                    None => self
+                        .changes
+                        .push(Diagnostic::info(format!("removed port '{}'", name), *span)),
+                    Some(port)
+                        if port.direction != group.direction
+                            || !same_type(&group.type_text, &port.vhdl_type) =>
+                    {
+                        self.changes.push(Diagnostic::info(
+                            format!(
+                                "changed port '{}' to '{} {}'",
+                                name, port.direction, port.vhdl_type
+                            ),
+                            *span,
+                        ));
+                        // まとめた宣言から外したものは末尾に宣言し直す
+                        if group.names.len() == 1 {
+                            replaced = Some(port_decl(port));
+                        } else {
+                            added.push(port_decl(port));
+                        }
+                    }
+                    Some(_) => kept.push(name.as_str()),
+                }
+            }
+            let change = if let Some(text) = replaced {
+                ItemChange::Replace(text)
+            } else if kept.len() == group.names.len() {
+                ItemChange::Keep
+            } else if kept.is_empty() {
+                ItemChange::Remove
+            } else {
+                // 残す名前だけにしてモードと型はそのまま
+                let last = group.names[group.names.len() - 1].1;
+                let rest = &self.source[last.end..group.span.end];
+                ItemChange::Replace(format!("{}{}", kept.join(", "), rest))
+            };
+            changes.push(change);
+        }
+
+        for port in &self.entity.ports {
+            let declared = component
+                .groups
+                .iter()
+                .flat_map(|g| &g.names)
+                .any(|(name, _)| name.eq_ignore_ascii_case(&port.name));
+            if !declared {
+                self.changes.push(Diagnostic::info(
+                    format!("added port '{}'", port.name),
+                    component.name_span,
+                ));
+                added.push(port_decl(port));
+            }
+        }
+
+        let items: Vec<Span> = component.groups.iter().map(|g| g.span).collect();
+        let edits = list_edits(self.source, component.open, &items, &changes, &added, ';');
+        self.edits.extend(edits);
+    }
+
+    /// ポートマップの関連付けを更新する
+    fn update_port_map(&mut self, instance: &Instance, component: Option<&Component>) {
+        let mut changes = Vec::new();
+        for assoc in &instance.def.port_map {
+            let formal = assoc.formal_name().unwrap_or_default();
+            if self.port(formal).is_some() {
+                changes.push(ItemChange::Keep);
+            } else {
+                // コンポーネント宣言があればそちらで報告済み
+                if component.is_none() {
+                    self.changes.push(Diagnostic::info(
+                        format!("removed port '{}'", formal),
+                        assoc.span,
+                    ));
+                }
+                changes.push(ItemChange::Remove);
+            }
+        }
+        let mut added = Vec::new();
+        for port in &self.entity.ports {
+            let connected = instance.def.port_map.iter().any(|a| {
+                a.formal_name()
+                    .is_some_and(|f| f.eq_ignore_ascii_case(&port.name))
+            });
+            if !connected {
+                if component.is_none() {
+                    self.changes.push(Diagnostic::info(
+                        format!("added port '{}'", port.name),
+                        instance.label_span,
+                    ));
+                }
+                added.push(format!("{} => {}", port.name, port.name));
+            }
+        }
+        let items: Vec<Span> = instance.def.port_map.iter().map(|a| a.span).collect();
+        let edits = list_edits(self.source, instance.open, &items, &changes, &added, ',');
+        self.edits.extend(edits);
+    }
+
+    /// ポートにつなぐ信号の宣言を追加・削除し、型を合わせる
+    fn update_signals(&mut self, instance: &Instance, component: Option<&Component>) {
+        // 信号名（小文字）ごとの新しい型と、削除する信号
+        let mut retype: HashMap<String, &VhdlType> = HashMap::new();
+        let mut remove: Vec<String> = Vec::new();
+        let mut declare: Vec<(&str, &VhdlType)> = Vec::new();
+
+        for port in &self.entity.ports {
+            let assoc = instance.def.port_map.iter().find(|a| {
+                a.formal_name()
+                    .is_some_and(|f| f.eq_ignore_ascii_case(&port.name))
+            });
+            let signal = match assoc {
+                Some(assoc) if is_simple_name(&assoc.actual) => assoc.actual.as_str(),
+                Some(_) => continue,
+                None => port.name.as_str(),
+            };
+            match self.tb.signal(signal) {
+                Some((decl, _))
+                    if !same_type(
+                        &self.source[decl.type_span.start..decl.type_span.end],
+                        &port.vhdl_type,
+                    ) =>
+                {
+                    retype.insert(signal.to_lowercase(), &port.vhdl_type);
+                }
+                Some(_) => {}
+                None if assoc.is_none() => declare.push((signal, &port.vhdl_type)),
+                None => {}
+            }
+        }
+
+        for assoc in &instance.def.port_map {
+            let formal = assoc.formal_name().unwrap_or_default();
+            if self.port(formal).is_some() || !is_simple_name(&assoc.actual) {
+                continue;
+            }
+            let Some((_, name_span)) = self.tb.signal(&assoc.actual) else {
+                continue;
+            };
+            // DUT の宣言・この関連付け・信号宣言以外で使っているか
+            let skip = [Some(assoc.span), component.map(|c| c.span), Some(name_span)];
+            let used = self.tb.tokens.iter().any(|t| {
+                t.kind == TokenKind::Identifier
+                    && t.text.eq_ignore_ascii_case(&assoc.actual)
+                    && !skip
+                        .iter()
+                        .flatten()
+                        .any(|s| s.start <= t.span.start && t.span.end <= s.end)
+            });
+            if used {
+                self.changes.push(Diagnostic::warning(
+                    format!(
+                        "signal '{}' is no longer connected but is still referenced",
+                        assoc.actual
+                    ),
+                    name_span,
+                ));
+            } else {
+                remove.push(assoc.actual.to_lowercase());
+            }
+        }
+
+        for decl in &self.tb.signals {
+            self.edit_signal_decl(decl, &retype, &remove);
+        }
+
+        if !declare.is_empty() {
+            let (at, indent) = match self.tb.signals.last() {
+                Some(last) => (
+                    next_line_start(self.source, last.span.end),
+                    line_indent(self.source, last.span.start).to_string(),
+                ),
+                None => match self.tb.arch_begin {
+                    Some(begin) => (
+                        self.source[..begin].rfind('\n').map_or(0, |i| i + 1),
+                        "    ".to_string(),
+                    ),
+                    None => return,
+                },
+            };
+            let text: String = declare
+                .iter()
+                .map(|(name, ty)| format!("{}{}\n", indent, signal_decl(name, ty)))
+                .collect();
+            self.edits.push(Edit::new(at, at, text));
+        }
+    }
+
+    /// 1つの信号宣言から名前を外す・型を変える
+    fn edit_signal_decl(
+        &mut self,
+        decl: &SignalDecl,
+        retype: &HashMap<String, &VhdlType>,
+        remove: &[String],
+    ) {
+        let source = self.source;
+        let lower = |name: &str| name.to_lowercase();
+        let changed: Vec<(&str, &VhdlType)> = decl
+            .names
+            .iter()
+            .filter_map(|(name, _)| retype.get(&lower(name)).map(|ty| (name.as_str(), *ty)))
+            .collect();
+        let kept: Vec<&str> = decl
+            .names
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| !retype.contains_key(&lower(name)) && !remove.contains(&lower(name)))
+            .collect();
+        if kept.len() == decl.names.len() {
+            return;
+        }
+        for (name, ty) in &changed {
+            self.changes.push(Diagnostic::info(
+                format!("changed type of signal '{}' to '{}'", name, ty),
+                decl.span,
+            ));
+        }
+        for (name, span) in &decl.names {
+            if remove.contains(&lower(name)) {
+                self.changes.push(Diagnostic::info(
+                    format!("removed signal '{}'", name),
+                    *span,
+                ));
+            }
+        }
+
+        // 1つだけの宣言は型と初期値をその場で書き換える
+        if let [(_, ty)] = changed[..]
+            && decl.names.len() == 1
+        {
+            self.edits.push(Edit::new(
+                decl.type_span.start,
+                decl.type_span.end,
+                ty.to_string(),
+            ));
+            if let Some(init) = decl.init_span {
+                self.edits
+                    .push(Edit::new(init.start, init.end, type_default_value(ty)));
+            }
+            return;
+        }
+
+        let indent = line_indent(source, decl.span.start);
+        let split: Vec<String> = changed
+            .iter()
+            .map(|(name, ty)| signal_decl(name, ty))
+            .collect();
+        if kept.is_empty() {
+            if split.is_empty() {
+                let extent = line_extent(source, decl.span);
+                self.edits.push(Edit::new(extent.start, extent.end, ""));
+            } else {
+                let text = split.join(&format!("\n{}", indent));
+                self.edits
+                    .push(Edit::new(decl.span.start, decl.span.end, text));
+            }
+            return;
+        }
+        let first = decl.names[0].1;
+        let last = decl.names[decl.names.len() - 1].1;
+        self.edits
+            .push(Edit::new(first.start, last.end, kept.join(", ")));
+        if !split.is_empty() {
+            let at = next_line_start(source, decl.span.end);
+            let text: String = split
+                .iter()
+                .map(|line| format!("{}{}\n", indent, line))
+                .collect();
+            self.edits.push(Edit::new(at, at, text));
+        }
+    }
+}
+
+/// 識別子1つだけの式か
+fn is_simple_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic())
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// コンポーネント宣言のポート宣言（`a, b : in std_logic` の1まとまり）
+pub(crate) struct PortGroup {
+    pub(crate) span: Span,
+    pub(crate) names: Vec<(String, Span)>,
+    pub(crate) direction: PortDirection,
+    pub(crate) type_text: String,
+}
+
+/// コンポーネント宣言
+pub(crate) struct Component {
+    pub(crate) name: String,
+    pub(crate) name_span: Span,
+    /// `component` から `end component ...;` まで
+    pub(crate) span: Span,
+    /// ポート節の `(` の直後
+    open: usize,
+    pub(crate) groups: Vec<PortGroup>,
+}
+
+impl Component {
+    /// 宣言どおりのポートを持つエンティティ（位置は宣言全体）
+    fn to_entity(&self) -> EntityDef {
+        let ports = self
+            .groups
+            .iter()
+            .flat_map(|group| {
+                let vhdl_type = parse_type_str(&group.type_text)
+                    .unwrap_or_else(|_| VhdlType::Other(group.type_text.clone()));
+                group.names.iter().map(move |(name, span)| PortDef {
+                    name: name.clone(),
+                    direction: group.direction.clone(),
+                    vhdl_type: vhdl_type.clone(),
+                    span: *span,
+                })
+            })
+            .collect();
+        EntityDef {
+            name: self.name.clone(),
+            generics: Vec::new(),
+            ports,
+            span: self.span,
+        }
+    }
+}
+
+/// 信号宣言
+pub(crate) struct SignalDecl {
+    /// `signal` から `;` まで
+    span: Span,
+    names: Vec<(String, Span)>,
+    pub(crate) type_span: Span,
+    init_span: Option<Span>,
+}
+
+/// テストベンチの DUT 候補のインスタンス（[`InstanceDef`] に書き換えに使う位置を加えたもの）
+pub(crate) struct Instance {
+    pub(crate) def: InstanceDef,
+    pub(crate) label_span: Span,
+    /// ポートマップの `(` の直後
+    open: usize,
+}
+
+impl Instance {
+    pub(crate) fn unit(&self) -> &str {
+        self.def.unit.name()
+    }
+
+    /// `component` を付けるか省略した（エンティティの直接インスタンスでない）
+    fn is_component(&self) -> bool {
+        matches!(self.def.unit, InstantiatedUnit::Component(_))
+    }
+}
+
+/// テストベンチから読み取った宣言
+///
+/// インスタンスは解析器の結果（[`ArchitectureDef::instances`]）から、ポートマップのあるものを
+/// 使います。コンポーネント宣言と信号宣言は、更新に必要な位置をトークン列から拾います。
+pub(crate) struct Testbench<'source> {
+    pub(crate) tokens: Vec<Token<'source>>,
+    pub(crate) components: Vec<Component>,
+    signals: Vec<SignalDecl>,
+    pub(crate) instances: Vec<Instance>,
+    /// アーキテクチャ本体の `begin` の位置
+    arch_begin: Option<usize>,
+}
+
+impl<'source> Testbench<'source> {
+    pub(crate) fn parse(source: &'source str) -> Self {
+        // 字句解析エラー（属性の `'` など）は読み飛ばす
+        let tokens: Vec<Token> = Lexer::new(source)
+            .with_options(LexerOptions {
+                skip_comments: true,
+                ..LexerOptions::default()
+            })
+            .filter_map(|r| r.ok())
+            .collect();
+        let scanner = TokenScanner {
+            source,
+            tokens: &tokens,
+        };
+        let mut components = Vec::new();
+        let mut signals = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let prev = i.checked_sub(1).map(|p| &tokens[p].kind);
+            let next = match tokens[i].kind {
+                // `end component` とインスタンスの `: component` は宣言ではない
+                TokenKind::Component
+                    if !matches!(prev.cloned(), Some(TokenKind::Colon | TokenKind::End)) =>
+                {
+                    scanner.component(i).map(|(component, next)| {
+                        components.push(component);
+                        next
+                    })
+                }
+                TokenKind::Signal => scanner.signal(i).map(|(signal, next)| {
+                    signals.push(signal);
+                    next
+                }),
+                _ => None,
+            };
+            i = next.unwrap_or(i + 1);
+        }
+        let arch_begin = architecture_begin(&tokens).map(|i| tokens[i].span.start);
+        let instances = analyze_vhdl(source)
+            .map(|result| result.architectures)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|arch| arch.instances)
+            .filter_map(|def| {
+                let open = port_map_open(&tokens, def.span)?;
+                Some(Instance {
+                    label_span: Span::new(def.span.start, def.span.start + def.label.len()),
+                    open,
+                    def,
+                })
+            })
+            .collect();
+        Testbench {
+            tokens,
+            components,
+            signals,
+            instances,
+            arch_begin,
+        }
+    }
+
+    /// 文の先頭で `name <=` と代入しているか
+    fn is_assigned(&self, name: &str) -> bool {
+        self.tokens.windows(3).any(|w| {
+            matches!(
+                w[0].kind,
+                TokenKind::Semicolon
+                    | TokenKind::Begin
+                    | TokenKind::Then
+                    | TokenKind::Else
+                    | TokenKind::Association
+            ) && w[1].kind == TokenKind::Identifier
+                && w[1].text.eq_ignore_ascii_case(name)
+                && w[2].kind == TokenKind::Lte
+        })
+    }
+
+    /// 名前で信号宣言を探す（宣言と名前の位置）
+    pub(crate) fn signal(&self, name: &str) -> Option<(&SignalDecl, Span)> {
+        self.signals.iter().find_map(|decl| {
+            decl.names
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, span)| (decl, *span))
+        })
+    }
+}
+
+/// インスタンス化文の `port map (` の `(` の直後
+fn port_map_open(tokens: &[Token], span: Span) -> Option<usize> {
+    let start = tokens.partition_point(|t| t.span.start < span.start);
+    let end = tokens.partition_point(|t| t.span.end <= span.end);
+    tokens
+        .get(start..end)?
+        .windows(3)
+        .find(|w| {
+            w[0].kind == TokenKind::Port
+                && w[1].kind == TokenKind::Map
+                && w[2].kind == TokenKind::LeftParen
+        })
+        .map(|w| w[2].span.end)
+}
+
+/// アーキテクチャの宣言部を飛ばして本体の `begin` を探す
+fn architecture_begin(tokens: &[Token]) -> Option<usize> {
+    let arch = tokens
+        .iter()
+        .position(|t| t.kind == TokenKind::Architecture)?;
+    let mut i = arch
+        + tokens[arch..]
+            .iter()
+            .position(|t| t.kind == TokenKind::Is)?
+        + 1;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        if token.kind == TokenKind::Begin {
+            return Some(i);
+        }
+        // 関数・手続きの本体にある begin を読み飛ばす
+        if matches!(token.kind, TokenKind::Function | TokenKind::Procedure) {
+            i = skip_subprogram(tokens, i);
+            continue;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// 副プログラムの宣言または本体の次のトークン
+fn skip_subprogram(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = start + 1;
+    // 宣言だけなら `;`、本体があれば `is` まで
+    while i < tokens.len() {
+        match tokens[i].kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen => depth = depth.saturating_sub(1),
+            TokenKind::Semicolon if depth == 0 => return i + 1,
+            TokenKind::Is if depth == 0 => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    while i < tokens.len() && tokens[i].kind != TokenKind::Begin {
+        i += 1;
+    }
+    // `end if` `end case` `end loop` 以外の end が本体の終わり
+    while i < tokens.len() {
+        if tokens[i].kind == TokenKind::End {
+            let closes_statement = tokens.get(i + 1).is_some_and(|t| {
+                matches!(t.kind, TokenKind::If | TokenKind::Case | TokenKind::Loop)
+            });
+            if !closes_statement {
+                break;
+            }
+        }
+        i += 1;
+    }
+    while i < tokens.len() && tokens[i].kind != TokenKind::Semicolon {
+        i += 1;
+    }
+    i + 1
+}
+
+/// トークン列から宣言を拾う
+struct TokenScanner<'a> {
+    source: &'a str,
+    tokens: &'a [Token<'a>],
+}
+
+impl TokenScanner<'_> {
+    fn kind(&self, i: usize) -> Option<&TokenKind> {
+        self.tokens.get(i).map(|t| &t.kind)
+    }
+
+    /// `(` に対応する `)` の位置
+    fn closing_paren(&self, open: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        for (i, token) in self.tokens.iter().enumerate().skip(open) {
+            match token.kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// 括弧内を深さ0の区切りで分けたトークン範囲
+    fn split(&self, open: usize, close: usize, sep: TokenKind) -> Vec<(usize, usize)> {
+        let mut parts = Vec::new();
+        let mut depth = 0usize;
+        let mut start = open + 1;
+        for i in open + 1..close {
+            match self.tokens[i].kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => depth = depth.saturating_sub(1),
+                _ if self.tokens[i].kind == sep && depth == 0 => {
+                    parts.push((start, i));
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if start < close {
+            parts.push((start, close));
+        }
+        parts
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        Span::new(self.tokens[start].span.start, self.tokens[end - 1].span.end)
+    }
+
+    fn text(&self, start: usize, end: usize) -> &str {
+        let span = self.span(start, end);
+        &self.source[span.start..span.end]
+    }
+
+    /// コロンまでの名前の並び
+    fn names(&self, start: usize, colon: usize) -> Vec<(String, Span)> {
+        self.tokens[start..colon]
+            .iter()
+            .filter(|t| t.kind == TokenKind::Identifier)
+            .map(|t| (t.text.to_string(), t.span))
+            .collect()
+    }
+
+    /// `component name [is] [generic (...);] [port (...);] end component [name];`
+    fn component(&self, start: usize) -> Option<(Component, usize)> {
+        let name = self.tokens.get(start + 1)?;
+        if name.kind != TokenKind::Identifier {
+            return None;
+        }
+        let mut i = start + 2;
+        if self.kind(i) == Some(&TokenKind::Is) {
+            i += 1;
+        }
+        if self.kind(i) == Some(&TokenKind::Generic) {
+            i = self.closing_paren(i + 1)? + 2;
+        }
+        let mut open = None;
+        let mut groups = Vec::new();
+        if self.kind(i) == Some(&TokenKind::Port) && self.kind(i + 1) == Some(&TokenKind::LeftParen)
+        {
+            let close = self.closing_paren(i + 1)?;
+            open = Some(self.tokens[i + 1].span.end);
+            for (a, b) in self.split(i + 1, close, TokenKind::Semicolon) {
+                groups.push(self.port_group(a, b)?);
+            }
+            i = close + 1;
+        }
+        while self.kind(i)? != &TokenKind::End {
+            i += 1;
+        }
+        while self.kind(i)? != &TokenKind::Semicolon {
+            i += 1;
+        }
+        let component = Component {
+            name: name.text.to_string(),
+            name_span: name.span,
+            span: self.span(start, i + 1),
+            open: open?,
+            groups,
+        };
+        Some((component, i + 1))
+    }
+
+    /// `a, b : in std_logic [:= '0']`
+    fn port_group(&self, start: usize, end: usize) -> Option<PortGroup> {
+        let colon = (start..end).find(|&i| self.tokens[i].kind == TokenKind::Colon)?;
+        // モードを省略したポートは in
+        let (direction, type_start) = match self.kind(colon + 1).cloned() {
+            Some(TokenKind::In) => (PortDirection::In, colon + 2),
+            Some(TokenKind::Out) => (PortDirection::Out, colon + 2),
+            Some(TokenKind::Inout) => (PortDirection::Inout, colon + 2),
+            Some(TokenKind::Buffer) => (PortDirection::Buffer, colon + 2),
+            _ => (PortDirection::In, colon + 1),
+        };
+        let type_end = (type_start..end)
+            .find(|&i| self.tokens[i].kind == TokenKind::Assignment)
+            .unwrap_or(end);
+        if type_start >= type_end {
+            return None;
+        }
+        Some(PortGroup {
+            span: self.span(start, end),
+            names: self.names(start, colon),
+            direction,
+            type_text: self.text(type_start, type_end).to_string(),
+        })
+    }
+
+    /// `signal a, b : type [:= init];`（副プログラムの仮引数は除く）
+    fn signal(&self, start: usize) -> Option<(SignalDecl, usize)> {
+        let colon = start
+            + self.tokens[start..]
+                .iter()
+                .position(|t| t.kind == TokenKind::Colon)?;
+        let mut depth = 0usize;
+        let mut assign = None;
+        let mut i = colon + 1;
+        loop {
+            match *self.kind(i)? {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen if depth == 0 => return None,
+                TokenKind::RightParen => depth -= 1,
+                TokenKind::Assignment if depth == 0 => assign = Some(i),
+                TokenKind::Semicolon if depth == 0 => break,
+                _ => {}
+            }
+            i += 1;
+        }
+        let type_end = assign.unwrap_or(i);
+        if colon + 1 >= type_end {
+            return None;
+        }
+        let decl = SignalDecl {
+            span: self.span(start, i + 1),
+            names: self.names(start + 1, colon),
+            type_span: self.span(colon + 1, type_end),
+            init_span: assign.filter(|&a| a + 1 < i).map(|a| self.span(a + 1, i)),
+        };
+        Some((decl, i + 1))
+    }
+}
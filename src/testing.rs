@@ -0,0 +1,194 @@
+//! ゴールデンファイルによるスナップショットテスト
+//!
+//! 生成したテストベンチを改行コードや行末の空白の違いを無視して期待値ファイルと比べ、
+//! 違いがあれば行単位の差分を表示します。環境変数 `VIG_UPDATE_GOLDEN=1` を付けて
+//! 実行すると、比較する代わりに期待値ファイルを書き換えます。
+//!
+//! ```no_run
+//! use vig::testing::assert_golden;
+//!
+//! let source = std::fs::read_to_string("testdata/counter.vhd")?;
+//! let result = vig::analyze(&source)?;
+//! let tb = vig::TestbenchBuilder::new(&result.entities[0]).build();
+//! assert_golden("tests/golden/counter_tb.vhd", &tb);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::path::Path;
+
+/// 期待値ファイルを更新するための環境変数
+pub const UPDATE_ENV: &str = "VIG_UPDATE_GOLDEN";
+
+/// 差分の前後に表示する一致行の数
+const CONTEXT_LINES: usize = 3;
+
+/// 比較用に正規化する（CRLF を LF に、行末の空白と末尾の空行を除き、最後に改行を1つ付ける）
+pub fn normalize(text: &str) -> String {
+    let mut out: String = text
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .lines()
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect();
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    if out == "\n" {
+        out.clear();
+    }
+    out
+}
+
+/// 行単位の差分（`-` は期待値のみ、`+` は実際の出力のみの行）
+///
+/// 一致しない箇所ごとに `@@ -行,行数 +行,行数 @@` の見出しを付け、前後の一致行も表示します。
+/// 同じ内容なら空文字列を返します。
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+    let ops = diff_ops(&old, &new);
+    if ops.iter().all(|op| matches!(op, Op::Same(..))) {
+        return String::new();
+    }
+
+    // 変更のある操作の位置から、文脈を含めた区間にまとめる
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Same(..)) {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + CONTEXT_LINES + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    // 各操作の直前までに進んだ (期待値, 実際) の行位置
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for op in &ops {
+        positions.push((old_pos, new_pos));
+        match op {
+            Op::Same(..) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Op::Delete(_) => old_pos += 1,
+            Op::Insert(_) => new_pos += 1,
+        }
+    }
+
+    let mut out = String::new();
+    for (start, end) in hunks {
+        let (old_start, new_start) = positions[start];
+        let slice = &ops[start..end];
+        let old_len = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_len = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + 1,
+            old_len,
+            new_start + 1,
+            new_len
+        ));
+        for op in slice {
+            match op {
+                Op::Same(i) => out.push_str(&format!(" {}\n", old[*i])),
+                Op::Delete(i) => out.push_str(&format!("-{}\n", old[*i])),
+                Op::Insert(j) => out.push_str(&format!("+{}\n", new[*j])),
+            }
+        }
+    }
+    out
+}
+
+/// 差分の1操作（行番号は0始まり）
+enum Op {
+    Same(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// 最長共通部分列による差分
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j] = old[i..] と new[j..] の最長共通部分列の長さ
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            ops.push(Op::Same(i));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // 置き換えは削除を先に出す
+            ops.push(Op::Delete(i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// 期待値ファイルと比べる（違えば差分を含むメッセージを返す）
+///
+/// [`UPDATE_ENV`] が設定されていれば、比較せずに期待値ファイルを書き換えます。
+pub fn check_golden(path: impl AsRef<Path>, actual: &str) -> Result<(), String> {
+    let path = path.as_ref();
+    let actual = normalize(actual);
+    if std::env::var_os(UPDATE_ENV).is_some_and(|v| !v.is_empty() && v != "0") {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create '{}': {}", dir.display(), e))?;
+        }
+        return std::fs::write(path, actual)
+            .map_err(|e| format!("failed to write '{}': {}", path.display(), e));
+    }
+    let expected = std::fs::read_to_string(path).map_err(|e| {
+        format!(
+            "failed to read golden file '{}': {} (run with {}=1 to create it)",
+            path.display(),
+            e,
+            UPDATE_ENV
+        )
+    })?;
+    let difference = diff(&normalize(&expected), &actual);
+    if difference.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "output differs from golden file '{}' (run with {}=1 to update it)\n{}",
+            path.display(),
+            UPDATE_ENV,
+            difference
+        ))
+    }
+}
+
+/// 期待値ファイルと比べ、違えば差分を表示してパニックする（テスト用）
+#[track_caller]
+pub fn assert_golden(path: impl AsRef<Path>, actual: &str) {
+    if let Err(message) = check_golden(path, actual) {
+        panic!("{}", message);
+    }
+}
@@ -0,0 +1,137 @@
+//! TODO / FIXME / HACK コメントの抽出
+//!
+//! 設計やテストベンチのコメントから作業の残りを示す目印を集めます。生成したテストベンチの
+//! スティミュラス（`-- TODO: テストパターンを記述`）も対象になるので、CI でテストパターンを
+//! 書き終えていないテストベンチを見つけられます。
+
+use crate::json::Value;
+use crate::lexer::{Lexer, Span, TokenKind};
+use crate::report::ReportFormat;
+use crate::source_map::SourceMap;
+
+/// 目印として扱う語（大文字で、単語として現れたもの）
+pub const MARKERS: [&str; 3] = ["TODO", "FIXME", "HACK"];
+
+/// 目印の付いたコメント1つ
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    /// `TODO` `FIXME` `HACK` のいずれか
+    pub marker: &'static str,
+    /// 目印の後の説明（前後の空白と `:` を除く）
+    pub text: String,
+    /// 目印の位置
+    pub span: Span,
+    /// コメントを含むプロセスのラベル（生成したテストベンチなら `stim_process` など）
+    pub process: Option<String>,
+}
+
+/// ソース中の目印付きコメントを出現順に集める
+pub fn find_todos(source: &str) -> Vec<TodoItem> {
+    let tokens: Vec<_> = Lexer::new(source).filter_map(|r| r.ok()).collect();
+    let mut items = Vec::new();
+    let mut process: Option<String> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Process => {
+                let after_end = i > 0 && tokens[i - 1].kind == TokenKind::End;
+                if after_end {
+                    process = None;
+                } else if i >= 2 && tokens[i - 1].kind == TokenKind::Colon {
+                    process = Some(tokens[i - 2].text.to_string());
+                } else {
+                    process = Some(String::new());
+                }
+            }
+            TokenKind::Comment => {
+                // コメントのトークンは `--` を含まないので末尾から位置を求める
+                let start = token.span.end - token.text.len();
+                if let Some((marker, at, text)) = marker_in(token.text) {
+                    items.push(TodoItem {
+                        marker,
+                        text,
+                        span: Span::new(start + at, start + at + marker.len()),
+                        process: process.clone().filter(|p| !p.is_empty()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    items
+}
+
+/// コメント中の最初の目印（目印, 位置, 説明）
+fn marker_in(comment: &str) -> Option<(&'static str, usize, String)> {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    MARKERS
+        .iter()
+        .filter_map(|&marker| {
+            comment
+                .match_indices(marker)
+                .find_map(|(at, _)| {
+                    let before = comment[..at].chars().next_back();
+                    let after = comment[at + marker.len()..].chars().next();
+                    (!before.is_some_and(is_word) && !after.is_some_and(is_word)).then_some(at)
+                })
+                .map(|at| (marker, at))
+        })
+        .min_by_key(|&(_, at)| at)
+        .map(|(marker, at)| {
+            let rest = comment[at + marker.len()..].trim_start();
+            let rest = rest.strip_prefix(':').unwrap_or(rest);
+            (marker, at, rest.trim().to_string())
+        })
+}
+
+/// ファイルごとの抽出結果を表示用の文字列にする
+///
+/// テキストは `ファイル:行:列: TODO: 説明` の1行1件で、`Json` は1件1オブジェクトの配列です。
+/// `Ansi` は `Plain` と同じです。
+pub fn render(found: &[(SourceMap, Vec<TodoItem>)], format: ReportFormat) -> String {
+    if format == ReportFormat::Json {
+        let items = found
+            .iter()
+            .flat_map(|(map, items)| items.iter().map(move |item| to_value(map, item)))
+            .collect();
+        let mut s = Value::Array(items).to_pretty_string();
+        s.push('\n');
+        return s;
+    }
+    let mut out = String::new();
+    for (map, items) in found {
+        for item in items {
+            let (line, column) = map.line_col(item.span.start);
+            out.push_str(&format!(
+                "{}:{}:{}: {}",
+                map.name(),
+                line,
+                column,
+                item.marker
+            ));
+            if !item.text.is_empty() {
+                out.push_str(&format!(": {}", item.text));
+            }
+            if let Some(process) = &item.process {
+                out.push_str(&format!(" ({})", process));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn to_value(map: &SourceMap, item: &TodoItem) -> Value {
+    let (line, column) = map.line_col(item.span.start);
+    Value::Object(vec![
+        ("file".to_string(), Value::String(map.name().to_string())),
+        ("line".to_string(), Value::Integer(line as i64)),
+        ("column".to_string(), Value::Integer(column as i64)),
+        ("marker".to_string(), Value::String(item.marker.to_string())),
+        ("text".to_string(), Value::String(item.text.clone())),
+        (
+            "process".to_string(),
+            item.process.clone().map_or(Value::Null, Value::String),
+        ),
+    ])
+}
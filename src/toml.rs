@@ -0,0 +1,397 @@
+//! 設定ファイル向けの最小限のTOMLパーサ
+//!
+//! テーブル・テーブル配列・ドット区切りキー・文字列・整数（16/8/2進数を含む）・
+//! 浮動小数点数・真偽値・配列・インラインテーブルに対応し、結果を [`Value`] で返します。
+//! 日時型と複数行文字列には対応していません。
+
+use crate::json::Value;
+use crate::lexer::{Span, skip_bom};
+
+/// TOMLの解析エラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl TomlError {
+    fn new(message: impl Into<String>, pos: usize) -> Self {
+        Self {
+            message: message.into(),
+            span: Span::new(pos, pos),
+        }
+    }
+}
+
+/// TOML文書を解析する
+pub fn parse(source: &str) -> Result<Value, TomlError> {
+    let mut parser = Parser {
+        src: source.as_bytes(),
+        text: source,
+        pos: skip_bom(source),
+    };
+    let mut root = Value::Object(Vec::new());
+    // 現在のテーブルへのパス
+    let mut current: Vec<String> = Vec::new();
+
+    loop {
+        parser.skip_whitespace_and_newlines();
+        let Some(b) = parser.peek() else {
+            break;
+        };
+        if b == b'[' {
+            let start = parser.pos;
+            parser.pos += 1;
+            let is_array = parser.peek() == Some(b'[');
+            if is_array {
+                parser.pos += 1;
+            }
+            parser.skip_spaces();
+            let path = parser.parse_key()?;
+            parser.skip_spaces();
+            parser.expect_byte(b']')?;
+            if is_array {
+                parser.expect_byte(b']')?;
+            }
+            parser.expect_line_end()?;
+
+            let (last, parents) = path.split_last().expect("key is never empty");
+            let parent = navigate(&mut root, parents, start)?;
+            let Value::Object(members) = parent else {
+                return Err(TomlError::new("key is not a table", start));
+            };
+            match members.iter_mut().find(|(k, _)| k == last) {
+                Some((_, Value::Array(items))) if is_array => {
+                    items.push(Value::Object(Vec::new()));
+                }
+                Some(_) => {
+                    return Err(TomlError::new(
+                        format!("duplicate table '{}'", path.join(".")),
+                        start,
+                    ));
+                }
+                None if is_array => {
+                    members.push((last.clone(), Value::Array(vec![Value::Object(Vec::new())])))
+                }
+                None => members.push((last.clone(), Value::Object(Vec::new()))),
+            }
+            current = path;
+        } else {
+            let start = parser.pos;
+            let (key, value) = parser.parse_key_value()?;
+            parser.expect_line_end()?;
+            let table = navigate(&mut root, &current, start)?;
+            insert(table, &key, value, start)?;
+        }
+    }
+    Ok(root)
+}
+
+/// パスを辿ってテーブルを取得（途中のテーブルは作成、テーブル配列は末尾要素）
+fn navigate<'v>(
+    mut table: &'v mut Value,
+    path: &[String],
+    pos: usize,
+) -> Result<&'v mut Value, TomlError> {
+    for segment in path {
+        let Value::Object(members) = table else {
+            return Err(TomlError::new("key is not a table", pos));
+        };
+        let index = match members.iter().position(|(k, _)| k == segment) {
+            Some(i) => i,
+            None => {
+                members.push((segment.clone(), Value::Object(Vec::new())));
+                members.len() - 1
+            }
+        };
+        table = match &mut members[index].1 {
+            Value::Array(items) => items
+                .last_mut()
+                .ok_or_else(|| TomlError::new("key is not a table", pos))?,
+            value => value,
+        };
+    }
+    Ok(table)
+}
+
+/// ドット区切りキーで値を追加
+fn insert(table: &mut Value, key: &[String], value: Value, pos: usize) -> Result<(), TomlError> {
+    let (last, parents) = key.split_last().expect("key is never empty");
+    let Value::Object(members) = navigate(table, parents, pos)? else {
+        return Err(TomlError::new("key is not a table", pos));
+    };
+    if members.iter().any(|(k, _)| k == last) {
+        return Err(TomlError::new(
+            format!("duplicate key '{}'", key.join(".")),
+            pos,
+        ));
+    }
+    members.push((last.clone(), value));
+    Ok(())
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some(b'#') {
+            while !matches!(self.peek(), None | Some(b'\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace_and_newlines(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some(b'\n' | b'\r') => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn expect_byte(&mut self, b: u8) -> Result<(), TomlError> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(TomlError::new(
+                format!("expected '{}'", b as char),
+                self.pos,
+            ))
+        }
+    }
+
+    /// 行末（コメント可）を期待する
+    fn expect_line_end(&mut self) -> Result<(), TomlError> {
+        self.skip_spaces();
+        self.skip_comment();
+        match self.peek() {
+            None | Some(b'\n' | b'\r') => Ok(()),
+            _ => Err(TomlError::new("expected end of line", self.pos)),
+        }
+    }
+
+    /// ドット区切りのキー
+    fn parse_key(&mut self) -> Result<Vec<String>, TomlError> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_spaces();
+            let part = match self.peek() {
+                Some(b'"') => self.parse_basic_string()?,
+                Some(b'\'') => self.parse_literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while matches!(
+                        self.peek(),
+                        Some(b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-')
+                    ) {
+                        self.pos += 1;
+                    }
+                    if start == self.pos {
+                        return Err(TomlError::new("expected key", start));
+                    }
+                    self.text[start..self.pos].to_string()
+                }
+            };
+            parts.push(part);
+            self.skip_spaces();
+            if self.peek() == Some(b'.') {
+                self.pos += 1;
+            } else {
+                return Ok(parts);
+            }
+        }
+    }
+
+    fn parse_key_value(&mut self) -> Result<(Vec<String>, Value), TomlError> {
+        let key = self.parse_key()?;
+        self.skip_spaces();
+        self.expect_byte(b'=')?;
+        self.skip_spaces();
+        let value = self.parse_value()?;
+        Ok((key, value))
+    }
+
+    fn parse_value(&mut self) -> Result<Value, TomlError> {
+        match self.peek() {
+            Some(b'"') => Ok(Value::String(self.parse_basic_string()?)),
+            Some(b'\'') => Ok(Value::String(self.parse_literal_string()?)),
+            Some(b'[') => self.parse_array(),
+            Some(b'{') => self.parse_inline_table(),
+            Some(b't') => self.parse_keyword("true", Value::Bool(true)),
+            Some(b'f') => self.parse_keyword("false", Value::Bool(false)),
+            Some(b'+' | b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(TomlError::new("unexpected character", self.pos)),
+            None => Err(TomlError::new("unexpected end of input", self.pos)),
+        }
+    }
+
+    fn parse_keyword(&mut self, word: &str, value: Value) -> Result<Value, TomlError> {
+        if self.src[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(TomlError::new("invalid literal", self.pos))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, TomlError> {
+        let start = self.pos;
+        while matches!(
+            self.peek(),
+            Some(b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'+' | b'-' | b'.')
+        ) {
+            self.pos += 1;
+        }
+        let text: String = self.text[start..self.pos]
+            .chars()
+            .filter(|&c| c != '_')
+            .collect();
+        let (negative, digits) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text.as_str()),
+        };
+        let radix = match digits.get(..2) {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+        let value = match radix {
+            Some(radix) if !negative => i64::from_str_radix(&digits[2..], radix)
+                .ok()
+                .map(Value::Integer),
+            Some(_) => None,
+            None if digits.contains(['.', 'e', 'E']) => text.parse().ok().map(Value::Float),
+            None => text.parse().ok().map(Value::Integer),
+        };
+        value.ok_or_else(|| TomlError::new(format!("invalid number '{}'", text), start))
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String, TomlError> {
+        let start = self.pos;
+        self.expect_byte(b'\'')?;
+        let rest = &self.text[self.pos..];
+        match rest.find(['\'', '\n']) {
+            Some(i) if rest.as_bytes()[i] == b'\'' => {
+                self.pos += i + 1;
+                Ok(rest[..i].to_string())
+            }
+            _ => Err(TomlError::new("unterminated string", start)),
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String, TomlError> {
+        let start = self.pos;
+        self.expect_byte(b'"')?;
+        let mut out = String::new();
+        loop {
+            let rest = &self.text[self.pos..];
+            let Some(i) = rest.find(['"', '\\', '\n']) else {
+                return Err(TomlError::new("unterminated string", start));
+            };
+            out.push_str(&rest[..i]);
+            self.pos += i;
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\n') => return Err(TomlError::new("unterminated string", start)),
+                _ => {}
+            }
+            // エスケープシーケンス
+            self.pos += 1;
+            let esc = self
+                .peek()
+                .ok_or_else(|| TomlError::new("unterminated string", start))?;
+            self.pos += 1;
+            match esc {
+                b'"' => out.push('"'),
+                b'\\' => out.push('\\'),
+                b'b' => out.push('\u{8}'),
+                b'f' => out.push('\u{c}'),
+                b'n' => out.push('\n'),
+                b'r' => out.push('\r'),
+                b't' => out.push('\t'),
+                b'u' | b'U' => {
+                    let len = if esc == b'u' { 4 } else { 8 };
+                    let c = self
+                        .text
+                        .get(self.pos..self.pos + len)
+                        .and_then(|h| u32::from_str_radix(h, 16).ok())
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| TomlError::new("invalid unicode escape", self.pos))?;
+                    self.pos += len;
+                    out.push(c);
+                }
+                _ => return Err(TomlError::new("invalid escape sequence", self.pos - 1)),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, TomlError> {
+        self.expect_byte(b'[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace_and_newlines();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.parse_value()?);
+            self.skip_whitespace_and_newlines();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(TomlError::new("expected ',' or ']'", self.pos)),
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<Value, TomlError> {
+        self.expect_byte(b'{')?;
+        let mut table = Value::Object(Vec::new());
+        self.skip_spaces();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(table);
+        }
+        loop {
+            self.skip_spaces();
+            let start = self.pos;
+            let (key, value) = self.parse_key_value()?;
+            insert(&mut table, &key, value, start)?;
+            self.skip_spaces();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(table);
+                }
+                _ => return Err(TomlError::new("expected ',' or '}'", self.pos)),
+            }
+        }
+    }
+}
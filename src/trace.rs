@@ -0,0 +1,57 @@
+//! tracing による計測
+//!
+//! `tracing` 機能を有効にすると、[`timed!`] で囲んだ処理が `vig` のスパンになり、
+//! 終了時に経過時間（マイクロ秒）を `vig::timing` の DEBUG イベントとして記録します。
+//! 機能が無効なら何もしません。
+
+/// 処理の計測を始める（戻り値を破棄するまでをスパンとして計測する）
+///
+/// ```ignore
+/// let _timed = timed!("lex", bytes = source.len());
+/// ```
+macro_rules! timed {
+    ($name:literal $(, $($fields:tt)+)?) => {{
+        #[cfg(feature = "tracing")]
+        let timed = $crate::trace::Timed::new(
+            tracing::info_span!(target: "vig", $name $(, $($fields)+)?),
+            $name,
+        );
+        #[cfg(not(feature = "tracing"))]
+        let timed = $crate::trace::Timed;
+        timed
+    }};
+}
+
+pub(crate) use timed;
+
+/// 計測中の処理
+#[cfg(feature = "tracing")]
+pub(crate) struct Timed {
+    _span: tracing::span::EnteredSpan,
+    name: &'static str,
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl Timed {
+    pub(crate) fn new(span: tracing::Span, name: &'static str) -> Self {
+        Self {
+            _span: span.entered(),
+            name,
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Timed {
+    fn drop(&mut self) {
+        // スパンを抜ける前に記録する
+        let elapsed_us = u64::try_from(self.start.elapsed().as_micros()).unwrap_or(u64::MAX);
+        tracing::debug!(target: "vig::timing", name = self.name, elapsed_us, "finished");
+    }
+}
+
+/// 計測中の処理（tracing 機能が無効なので何もしない）
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Timed;
@@ -0,0 +1,478 @@
+//! 真理値表（CSV）からの自己検査テストベンチ生成
+//!
+//! 1行目の見出しにポート名を並べ、以降の各行に入力値と期待値を書きます。
+//! 入力ポートの列は印加する値、出力ポートの列は期待値として扱います。
+//!
+//! ```csv
+//! op_a,op_b,alu_op,result,carry_out
+//! 0011,0001,000,0100,0
+//! 0x9,0x9,000,2,1
+//! 1111,0000,101,0000,-
+//! ```
+//!
+//! 値の書き方:
+//! - `std_logic`: `0` `1` `X` `Z` `-` など1文字
+//! - `std_logic_vector`: ビット幅と同じ長さのビット列（`-` を含めると `std_match` で比較）、
+//!   または10進数・`0x`/`0b` 付きの数値（負数は2の補数）
+//! - `integer`: 10進数、`boolean`: `true`/`false`
+//! - 期待値の空欄と `-` は比較せず、入力の空欄は前の行の値のまま
+//!
+//! 空行と `#` で始まる行は読み飛ばします。見出しにタブが含まれていればTSVとして読みます。
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use crate::generator::{
+    RESET_CYCLES, TbConfig, build_model, find_clock_port, find_reset_port, simulation_end,
+};
+use crate::interp::{Interpreter, Value};
+use crate::lexer::{BOM, Span};
+use crate::logic::{LogicVector, MAX_VECTOR_WIDTH, StdLogic};
+
+/// 真理値表の読み込み・変換エラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl VectorError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for VectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for VectorError {}
+
+/// 真理値表の1行
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruthRow {
+    pub values: Vec<String>,
+    /// ソース上の行の範囲
+    pub span: Span,
+}
+
+/// 真理値表
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruthTable {
+    /// 見出し（ポート名）
+    pub columns: Vec<String>,
+    /// ソース上の見出し行の範囲
+    pub header_span: Span,
+    pub rows: Vec<TruthRow>,
+}
+
+impl TruthTable {
+    /// CSV（またはTSV）から読み込む
+    pub fn from_csv(source: &str) -> Result<Self, VectorError> {
+        let mut lines = Vec::new();
+        // 表計算ソフトが付ける BOM は読み飛ばす
+        let body = source.strip_prefix(BOM).unwrap_or(source);
+        let mut offset = source.len() - body.len();
+        for line in body.split_inclusive('\n') {
+            let text = line.trim_end_matches(['\n', '\r']);
+            let span = Span::new(offset, offset + text.len());
+            offset += line.len();
+            let trimmed = text.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                lines.push((text, span));
+            }
+        }
+        let Some(&(header, header_span)) = lines.first() else {
+            return Err(VectorError::new("truth table is empty", Span::new(0, 0)));
+        };
+        let separator = if header.contains('\t') && !header.contains(',') {
+            '\t'
+        } else {
+            ','
+        };
+
+        let columns = split_fields(header, separator, header_span)?;
+        if let Some(i) = columns.iter().position(String::is_empty) {
+            return Err(VectorError::new(
+                format!("column {} has no name", i + 1),
+                header_span,
+            ));
+        }
+        let mut rows = Vec::new();
+        for &(text, span) in &lines[1..] {
+            let values = split_fields(text, separator, span)?;
+            if values.len() != columns.len() {
+                return Err(VectorError::new(
+                    format!("expected {} columns, found {}", columns.len(), values.len()),
+                    span,
+                ));
+            }
+            rows.push(TruthRow { values, span });
+        }
+        Ok(Self {
+            columns,
+            header_span,
+            rows,
+        })
+    }
+
+    /// 出力ポートの期待値をインタプリタで予測して空欄を埋める
+    ///
+    /// 表にない出力ポートは列を追加します。一度も与えていない入力は
+    /// テストベンチの初期値（`'0'`・全ビット0・`0`・`false`）として評価し、
+    /// 予測に `'0'`/`'1'` 以外が含まれる場合はその欄を空欄のまま残します。
+    pub fn fill_expected(&mut self, interp: &Interpreter) -> Result<(), VectorError> {
+        let entity = interp.entity();
+        for port in &entity.ports {
+            let is_output = matches!(port.direction, PortDirection::Out | PortDirection::Buffer);
+            if is_output
+                && !self
+                    .columns
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(&port.name))
+            {
+                self.columns.push(port.name.clone());
+                for row in &mut self.rows {
+                    row.values.push(String::new());
+                }
+            }
+        }
+        let ports = column_ports(entity, &self.columns, self.header_span)?;
+
+        let mut inputs: Vec<(&str, Value)> = entity
+            .ports
+            .iter()
+            .filter(|p| p.direction == PortDirection::In)
+            .filter_map(|p| Some((p.name.as_str(), initial_value(&p.vhdl_type)?)))
+            .collect();
+        for (n, row) in self.rows.iter_mut().enumerate() {
+            let n = n + 1;
+            let row_error = |msg: String| VectorError::new(format!("row {}: {}", n, msg), row.span);
+            for (port, value) in ports.iter().zip(&row.values) {
+                if !matches!(port.direction, PortDirection::In | PortDirection::Inout) {
+                    continue;
+                }
+                let literal = to_literal(port, value).map_err(row_error)?;
+                if let Some(value) = literal.as_deref().and_then(literal_value) {
+                    inputs.retain(|(name, _)| *name != port.name);
+                    inputs.push((port.name.as_str(), value));
+                }
+            }
+            let outputs = interp
+                .evaluate(&inputs)
+                .map_err(|err| row_error(err.message))?;
+            for (port, cell) in ports.iter().zip(row.values.iter_mut()) {
+                if !cell.is_empty() {
+                    continue;
+                }
+                if let Some(text) = outputs
+                    .iter()
+                    .find(|(name, _)| *name == port.name)
+                    .and_then(|(_, value)| cell_text(value))
+                {
+                    *cell = text;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 見出しの各列に対応するポート
+fn column_ports<'a>(
+    entity: &'a EntityDef,
+    columns: &[String],
+    header_span: Span,
+) -> Result<Vec<&'a PortDef>, VectorError> {
+    let clk = find_clock_port(&entity.ports);
+    let mut ports: Vec<&PortDef> = Vec::new();
+    for column in columns {
+        let port = entity
+            .ports
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(column))
+            .ok_or_else(|| {
+                VectorError::new(
+                    format!("'{}' is not a port of '{}'", column, entity.name),
+                    header_span,
+                )
+            })?;
+        if clk.as_deref() == Some(port.name.as_str()) {
+            return Err(VectorError::new(
+                format!("clock port '{}' cannot be a column", port.name),
+                header_span,
+            ));
+        }
+        if ports.iter().any(|p| p.name == port.name) {
+            return Err(VectorError::new(
+                format!("duplicate column '{}'", column),
+                header_span,
+            ));
+        }
+        ports.push(port);
+    }
+    Ok(ports)
+}
+
+/// テストベンチで入力に与える初期値
+fn initial_value(vhdl_type: &VhdlType) -> Option<Value> {
+    match vhdl_type {
+        VhdlType::StdLogic | VhdlType::Bit => Some(Value::Logic(StdLogic::Zero)),
+        VhdlType::Integer(range) | VhdlType::Natural(range) | VhdlType::Positive(range) => {
+            // 範囲の制約があれば左端（VHDL の既定値と同じ）
+            let left = range.as_ref().and_then(|range| range.left.value());
+            let fallback = if matches!(vhdl_type, VhdlType::Positive(_)) {
+                1
+            } else {
+                0
+            };
+            Some(Value::Integer(left.unwrap_or(fallback)))
+        }
+        VhdlType::Boolean => Some(Value::Boolean(false)),
+        _ => {
+            vhdl_type.vector_range()?;
+            let width = usize::try_from(vhdl_type.bit_width()?).ok()?;
+            (width <= MAX_VECTOR_WIDTH).then(|| Value::Vector(LogicVector::zeros(width)))
+        }
+    }
+}
+
+/// [`to_literal`] の結果をインタプリタの値に
+fn literal_value(literal: &str) -> Option<Value> {
+    if let Some(c) = literal
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+    {
+        return c
+            .chars()
+            .next()
+            .and_then(StdLogic::from_char)
+            .map(Value::Logic);
+    }
+    if let Some(bits) = literal.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return LogicVector::parse(bits).map(Value::Vector);
+    }
+    match literal {
+        "true" => Some(Value::Boolean(true)),
+        "false" => Some(Value::Boolean(false)),
+        _ => literal.parse().ok().map(Value::Integer),
+    }
+}
+
+/// 予測値を真理値表のセルに（不定値を含む場合は None）
+fn cell_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Logic(b) if b.is_01() => Some(b.to_string()),
+        Value::Vector(bits) if bits.is_01() => Some(bits.to_string()),
+        Value::Integer(n) => Some(n.to_string()),
+        Value::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// 1行を区切り文字で分割（`"` で囲んだ値と `""` のエスケープに対応）
+fn split_fields(line: &str, separator: char, span: Span) -> Result<Vec<String>, VectorError> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(VectorError::new("unterminated quoted field", span));
+    }
+    fields.push(field.trim().to_string());
+    Ok(fields)
+}
+
+/// 真理値表の各行を印加して出力を検査するテストベンチを生成する
+///
+/// 各行の入力を与えた後クロック1周期待ってから期待値を `assert` で確認するため、
+/// 組み合わせ回路と1クロックで出力が確定する順序回路の両方に使えます。
+pub fn generate_testbench(
+    entity: &EntityDef,
+    table: &TruthTable,
+    config: &TbConfig,
+) -> Result<String, VectorError> {
+    let header_span = table.header_span;
+
+    let ports = column_ports(entity, &table.columns, header_span)?;
+
+    let period = config.clock_period_ns;
+    let mut body = Vec::new();
+
+    // 表で駆動しないリセットは最初にかけておく
+    let target = config.target_ports(entity);
+    if let Some(rst) =
+        find_reset_port(&target.ports).filter(|r| !ports.iter().any(|p| &p.name == r))
+    {
+        let polarity = config.reset_polarity;
+        body.push("-- リセット".to_string());
+        body.push(format!("{} <= {};", rst, polarity.asserted()));
+        body.push(format!("wait for {} ns;", period * RESET_CYCLES));
+        body.push(format!("{} <= {};", rst, polarity.released()));
+        body.push(format!("wait for {} ns;", period * RESET_CYCLES));
+        body.push(String::new());
+    }
+
+    body.push(format!("-- 真理値表 ({} 行)", table.rows.len()));
+    for (n, row) in table.rows.iter().enumerate() {
+        let n = n + 1;
+        let mut drives = Vec::new();
+        let mut checks = Vec::new();
+        for (port, value) in ports.iter().zip(&row.values) {
+            let literal = to_literal(port, value)
+                .map_err(|msg| VectorError::new(format!("row {}: {}", n, msg), row.span))?;
+            match port.direction {
+                PortDirection::In | PortDirection::Inout => {
+                    if let Some(literal) = literal {
+                        drives.push(format!("{} <= {};", port.name, literal));
+                    }
+                }
+                PortDirection::Out | PortDirection::Buffer => {
+                    if let Some(literal) = literal {
+                        let condition = if literal.starts_with('"') && literal.contains('-') {
+                            format!("std_match({}, {})", port.name, literal)
+                        } else {
+                            format!("{} = {}", port.name, literal)
+                        };
+                        checks.push(format!(
+                            "assert {} report \"row {}: {} mismatch\" severity {};",
+                            condition,
+                            n,
+                            port.name,
+                            config.check_severity.as_str()
+                        ));
+                    }
+                }
+            }
+        }
+        body.push(format!("-- 行 {}", n));
+        body.extend(drives);
+        body.push(format!("wait for {} ns;", period));
+        body.extend(checks);
+    }
+    let mut model = build_model(entity, config);
+    body.push(String::new());
+    body.extend(simulation_end(&target.ports, config, &model.done_signal));
+
+    model.header = vec![
+        format!("{} の真理値表テストベンチ", entity.name),
+        "vig vectors により生成".to_string(),
+    ];
+    if let Some(stim) = model.process_mut("stim_process") {
+        stim.comment = Some("真理値表の印加と検査".to_string());
+        stim.body = body;
+    }
+    Ok(model.render())
+}
+
+/// セルの値をポートの型のVHDLリテラルに変換（比較しない値は None）
+fn to_literal(port: &PortDef, value: &str) -> Result<Option<String>, String> {
+    if value.is_empty() || value == "-" {
+        return Ok(None);
+    }
+    let invalid = || format!("invalid value '{}' for '{}'", value, port.name);
+    match &port.vhdl_type {
+        VhdlType::StdLogic => {
+            let mut chars = value.chars();
+            match (chars.next().and_then(StdLogic::from_char), chars.next()) {
+                (Some(b), None) => Ok(Some(format!("'{}'", b))),
+                _ => Err(invalid()),
+            }
+        }
+        VhdlType::Bit => match value {
+            "0" | "1" => Ok(Some(format!("'{}'", value))),
+            _ => Err(invalid()),
+        },
+        VhdlType::StdLogicVector { .. }
+        | VhdlType::Signed { .. }
+        | VhdlType::Unsigned { .. }
+        | VhdlType::BitVector { .. } => {
+            let Some(width) = port
+                .vhdl_type
+                .bit_width()
+                .filter(|&w| w <= MAX_VECTOR_WIDTH as u64)
+            else {
+                // 幅の分からない（または広すぎる）ベクタはビット列だけを受け付け、幅を検査しない
+                let bits = LogicVector::parse(value).ok_or_else(invalid)?;
+                return Ok(Some(format!("\"{}\"", bits)));
+            };
+            if let Some(bits) = LogicVector::parse(value).filter(|b| b.width() as u64 == width) {
+                return Ok(Some(format!("\"{}\"", bits)));
+            }
+            let number = parse_number(value).ok_or_else(invalid)?;
+            // 幅に収まる符号なし・符号付きの値だけを受け付ける
+            let fits = width >= 127
+                || (number >= 0 && number < 1i128 << width)
+                || (number < 0 && number >= -(1i128 << (width - 1)));
+            if !fits {
+                return Err(format!(
+                    "value '{}' does not fit in {} bits of '{}'",
+                    value, width, port.name
+                ));
+            }
+            let pattern = LogicVector::from_signed(number, width as usize);
+            Ok(Some(format!("\"{}\"", pattern)))
+        }
+        VhdlType::Integer(_) => value
+            .parse::<i64>()
+            .map(|n| Some(n.to_string()))
+            .map_err(|_| invalid()),
+        VhdlType::Natural(_) | VhdlType::Positive(_) => {
+            let min = if matches!(port.vhdl_type, VhdlType::Natural(_)) {
+                0
+            } else {
+                1
+            };
+            match value.parse::<i64>() {
+                Ok(n) if n >= min => Ok(Some(n.to_string())),
+                _ => Err(invalid()),
+            }
+        }
+        VhdlType::Boolean => match value.to_lowercase().as_str() {
+            "true" | "1" => Ok(Some("true".to_string())),
+            "false" | "0" => Ok(Some("false".to_string())),
+            _ => Err(invalid()),
+        },
+        // 実数・時間・文字列と未知の型はそのままVHDLの式として使う
+        _ => Ok(Some(value.to_string())),
+    }
+}
+
+/// 10進数・`0x`・`0b` 付きの数値
+fn parse_number(text: &str) -> Option<i128> {
+    let text: String = text.chars().filter(|&c| c != '_').collect();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    let lower = digits.to_lowercase();
+    let value = if let Some(hex) = lower.strip_prefix("0x") {
+        i128::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = lower.strip_prefix("0b") {
+        i128::from_str_radix(bin, 2).ok()?
+    } else {
+        lower.parse::<i128>().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
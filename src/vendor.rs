@@ -0,0 +1,352 @@
+//! ベンダーのプリミティブと標準パッケージの組み込みスタブ
+//!
+//! Xilinx の `unisim`・Intel の `altera_mf` のプリミティブ（`BUFG`・`IBUFDS`・`altsyncram` など）の
+//! インターフェースと、`ieee`・`std` の標準パッケージの型宣言を持っています。設計の解析
+//! （[`crate::project`]）では、設計に含まれない単位をここから探すので、プリミティブの
+//! インスタンスが未解決になりません。
+//!
+//! スタブはポートだけで、ジェネリックと論理は持ちません。幅がジェネリックで決まるポートは
+//! 範囲が `width_a - 1` のような式（[`crate::analyzer::BoundExpr`]）の型になります。
+//!
+//! ```
+//! use vig::vendor;
+//!
+//! let (library, bufg) = vendor::find_entity("bufg").unwrap();
+//! assert_eq!(library, "unisim");
+//! assert_eq!(bufg.ports.len(), 2);
+//! ```
+
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::analyzer::{
+    EntityDef, PackageDef, PortDef, PortDirection, VhdlType, analyze_vhdl, parse_type_str,
+};
+
+/// Xilinx 7 シリーズの主なプリミティブ（unisim）
+const UNISIM: &[(&str, &str)] = &[
+    ("BUFG", "O : out std_logic; I : in std_logic"),
+    ("BUFGCE", "O : out std_logic; CE, I : in std_logic"),
+    (
+        "BUFGCTRL",
+        "O : out std_logic; CE0, CE1, I0, I1, IGNORE0, IGNORE1, S0, S1 : in std_logic",
+    ),
+    ("BUFGMUX", "O : out std_logic; I0, I1, S : in std_logic"),
+    ("BUFH", "O : out std_logic; I : in std_logic"),
+    ("BUFIO", "O : out std_logic; I : in std_logic"),
+    ("BUFR", "O : out std_logic; CE, CLR, I : in std_logic"),
+    ("IBUF", "O : out std_logic; I : in std_logic"),
+    ("IBUFG", "O : out std_logic; I : in std_logic"),
+    ("IBUFDS", "O : out std_logic; I, IB : in std_logic"),
+    ("IBUFGDS", "O : out std_logic; I, IB : in std_logic"),
+    (
+        "IBUFDS_GTE2",
+        "O, ODIV2 : out std_logic; CEB, I, IB : in std_logic",
+    ),
+    ("OBUF", "O : out std_logic; I : in std_logic"),
+    ("OBUFT", "O : out std_logic; I, T : in std_logic"),
+    ("OBUFDS", "O, OB : out std_logic; I : in std_logic"),
+    (
+        "IOBUF",
+        "O : out std_logic; IO : inout std_logic; I, T : in std_logic",
+    ),
+    (
+        "IDDR",
+        "Q1, Q2 : out std_logic; C, CE, D, R, S : in std_logic",
+    ),
+    (
+        "ODDR",
+        "Q : out std_logic; C, CE, D1, D2, R, S : in std_logic",
+    ),
+    (
+        "IDELAYCTRL",
+        "RDY : out std_logic; REFCLK, RST : in std_logic",
+    ),
+    ("FDRE", "Q : out std_logic; C, CE, D, R : in std_logic"),
+    ("FDSE", "Q : out std_logic; C, CE, D, S : in std_logic"),
+    ("FDCE", "Q : out std_logic; C, CE, CLR, D : in std_logic"),
+    ("FDPE", "Q : out std_logic; C, CE, D, PRE : in std_logic"),
+    ("LUT1", "O : out std_logic; I0 : in std_logic"),
+    ("LUT2", "O : out std_logic; I0, I1 : in std_logic"),
+    ("LUT3", "O : out std_logic; I0, I1, I2 : in std_logic"),
+    ("LUT4", "O : out std_logic; I0, I1, I2, I3 : in std_logic"),
+    (
+        "LUT5",
+        "O : out std_logic; I0, I1, I2, I3, I4 : in std_logic",
+    ),
+    (
+        "LUT6",
+        "O : out std_logic; I0, I1, I2, I3, I4, I5 : in std_logic",
+    ),
+    (
+        "SRL16E",
+        "Q : out std_logic; A0, A1, A2, A3, CE, CLK, D : in std_logic",
+    ),
+    (
+        "SRLC32E",
+        "Q, Q31 : out std_logic; A : in std_logic_vector(4 downto 0); CE, CLK, D : in std_logic",
+    ),
+    (
+        "CARRY4",
+        "CO, O : out std_logic_vector(3 downto 0); CI, CYINIT : in std_logic; \
+         DI, S : in std_logic_vector(3 downto 0)",
+    ),
+    (
+        "MMCME2_BASE",
+        "CLKFBOUT, CLKFBOUTB, CLKOUT0, CLKOUT0B, CLKOUT1, CLKOUT1B, CLKOUT2, CLKOUT2B, \
+         CLKOUT3, CLKOUT3B, CLKOUT4, CLKOUT5, CLKOUT6, LOCKED : out std_logic; \
+         CLKFBIN, CLKIN1, PWRDWN, RST : in std_logic",
+    ),
+    (
+        "PLLE2_BASE",
+        "CLKFBOUT, CLKOUT0, CLKOUT1, CLKOUT2, CLKOUT3, CLKOUT4, CLKOUT5, LOCKED : out std_logic; \
+         CLKFBIN, CLKIN1, PWRDWN, RST : in std_logic",
+    ),
+    (
+        "STARTUPE2",
+        "CFGCLK, CFGMCLK, EOS, PREQ : out std_logic; \
+         CLK, GSR, GTS, KEYCLEARB, PACK, USRCCLKO, USRCCLKTS, USRDONEO, USRDONETS : in std_logic",
+    ),
+    (
+        "RAMB18E1",
+        "DOADO, DOBDO : out std_logic_vector(15 downto 0); \
+         DOPADOP, DOPBDOP : out std_logic_vector(1 downto 0); \
+         ADDRARDADDR, ADDRBWRADDR : in std_logic_vector(13 downto 0); \
+         CLKARDCLK, CLKBWRCLK : in std_logic; \
+         DIADI, DIBDI : in std_logic_vector(15 downto 0); \
+         DIPADIP, DIPBDIP : in std_logic_vector(1 downto 0); \
+         ENARDEN, ENBWREN, REGCEAREGCE, REGCEB, RSTRAMARSTRAM, RSTRAMB, RSTREGARSTREG, \
+         RSTREGB : in std_logic; \
+         WEA : in std_logic_vector(1 downto 0); WEBWE : in std_logic_vector(3 downto 0)",
+    ),
+    (
+        "RAMB36E1",
+        "CASCADEOUTA, CASCADEOUTB, DBITERR, SBITERR : out std_logic; \
+         DOADO, DOBDO : out std_logic_vector(31 downto 0); \
+         DOPADOP, DOPBDOP : out std_logic_vector(3 downto 0); \
+         ECCPARITY : out std_logic_vector(7 downto 0); \
+         RDADDRECC : out std_logic_vector(8 downto 0); \
+         ADDRARDADDR, ADDRBWRADDR : in std_logic_vector(15 downto 0); \
+         CASCADEINA, CASCADEINB, CLKARDCLK, CLKBWRCLK : in std_logic; \
+         DIADI, DIBDI : in std_logic_vector(31 downto 0); \
+         DIPADIP, DIPBDIP : in std_logic_vector(3 downto 0); \
+         ENARDEN, ENBWREN, INJECTDBITERR, INJECTSBITERR, REGCEAREGCE, REGCEB, RSTRAMARSTRAM, \
+         RSTRAMB, RSTREGARSTREG, RSTREGB : in std_logic; \
+         WEA : in std_logic_vector(3 downto 0); WEBWE : in std_logic_vector(7 downto 0)",
+    ),
+];
+
+/// Intel (Altera) のメガファンクション（altera_mf）
+const ALTERA_MF: &[(&str, &str)] = &[
+    (
+        "altsyncram",
+        "wren_a, wren_b, rden_a, rden_b : in std_logic; \
+         data_a : in std_logic_vector(width_a-1 downto 0); \
+         data_b : in std_logic_vector(width_b-1 downto 0); \
+         address_a : in std_logic_vector(widthad_a-1 downto 0); \
+         address_b : in std_logic_vector(widthad_b-1 downto 0); \
+         clock0, clock1, clocken0, clocken1, clocken2, clocken3, aclr0, aclr1 : in std_logic; \
+         byteena_a : in std_logic_vector(width_byteena_a-1 downto 0); \
+         byteena_b : in std_logic_vector(width_byteena_b-1 downto 0); \
+         addressstall_a, addressstall_b : in std_logic; \
+         q_a : out std_logic_vector(width_a-1 downto 0); \
+         q_b : out std_logic_vector(width_b-1 downto 0); \
+         eccstatus : out std_logic_vector(2 downto 0)",
+    ),
+    (
+        "altpll",
+        "inclk : in std_logic_vector(1 downto 0); areset, pfdena : in std_logic; \
+         clk : out std_logic_vector(width_clock-1 downto 0); locked : out std_logic",
+    ),
+    (
+        "scfifo",
+        "aclr, clock : in std_logic; data : in std_logic_vector(lpm_width-1 downto 0); \
+         rdreq, sclr, wrreq : in std_logic; \
+         almost_empty, almost_full, empty, full : out std_logic; \
+         q : out std_logic_vector(lpm_width-1 downto 0); \
+         usedw : out std_logic_vector(lpm_widthu-1 downto 0)",
+    ),
+    (
+        "dcfifo",
+        "aclr : in std_logic; data : in std_logic_vector(lpm_width-1 downto 0); \
+         rdclk, rdreq, wrclk, wrreq : in std_logic; \
+         q : out std_logic_vector(lpm_width-1 downto 0); rdempty, rdfull : out std_logic; \
+         rdusedw : out std_logic_vector(lpm_widthu-1 downto 0); wrempty, wrfull : out std_logic; \
+         wrusedw : out std_logic_vector(lpm_widthu-1 downto 0)",
+    ),
+    (
+        "altddio_in",
+        "datain : in std_logic_vector(width-1 downto 0); \
+         inclock, inclocken, aclr, aset, sclr, sset : in std_logic; \
+         dataout_h, dataout_l : out std_logic_vector(width-1 downto 0)",
+    ),
+    (
+        "altddio_out",
+        "datain_h, datain_l : in std_logic_vector(width-1 downto 0); \
+         outclock, outclocken, aclr, aset, sclr, sset, oe : in std_logic; \
+         dataout : out std_logic_vector(width-1 downto 0); oe_out : out std_logic_vector(width-1 downto 0)",
+    ),
+];
+
+/// ライブラリごとのプリミティブ
+const ENTITY_LIBRARIES: &[(&str, &[(&str, &str)])] =
+    &[("unisim", UNISIM), ("altera_mf", ALTERA_MF)];
+
+/// 標準パッケージとプリミティブのコンポーネントのパッケージ（型宣言だけ）
+///
+/// `std_logic` `std_logic_vector` `integer` `boolean` は解析器が組み込みで扱うので含めません。
+/// 範囲の属性（`'high`）は字句解析できないので値で書きます。
+const PACKAGES: &[(&str, &str)] = &[
+    (
+        "ieee",
+        "package std_logic_1164 is
+             type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
+             type std_ulogic_vector is array (natural range <>) of std_ulogic;
+             subtype X01 is resolved std_ulogic range 'X' to '1';
+             subtype X01Z is resolved std_ulogic range 'X' to 'Z';
+             subtype UX01 is resolved std_ulogic range 'U' to '1';
+             subtype UX01Z is resolved std_ulogic range 'U' to 'Z';
+         end package;
+         package numeric_std is
+             type unresolved_unsigned is array (natural range <>) of std_ulogic;
+             type unresolved_signed is array (natural range <>) of std_ulogic;
+             subtype unsigned is (resolved) unresolved_unsigned;
+             subtype signed is (resolved) unresolved_signed;
+         end package;
+         package numeric_bit is
+             type unsigned is array (natural range <>) of bit;
+             type signed is array (natural range <>) of bit;
+         end package;
+         package std_logic_arith is
+             type unsigned is array (natural range <>) of std_logic;
+             type signed is array (natural range <>) of std_logic;
+             subtype small_int is integer range 0 to 1;
+         end package;
+         package std_logic_unsigned is end package;
+         package std_logic_signed is end package;
+         package std_logic_textio is end package;
+         package math_real is end package;
+         package math_complex is
+             type complex is record re : real; im : real; end record;
+             subtype positive_real is real range 0.0 to 1.0e308;
+             subtype principal_value is real range -3.141592653589793 to 3.141592653589793;
+             type complex_polar is record mag : positive_real; arg : principal_value; end record;
+         end package;
+         package fixed_float_types is
+             type fixed_round_style_type is (fixed_round, fixed_truncate);
+             type fixed_overflow_style_type is (fixed_saturate, fixed_wrap);
+             type round_type is (round_nearest, round_inf, round_neginf, round_zero);
+         end package;
+         package fixed_pkg is
+             type ufixed is array (integer range <>) of std_logic;
+             type sfixed is array (integer range <>) of std_logic;
+         end package;
+         package float_pkg is
+             type float is array (integer range <>) of std_logic;
+             subtype float32 is float(8 downto -23);
+             subtype float64 is float(11 downto -52);
+             subtype float128 is float(15 downto -112);
+         end package;",
+    ),
+    (
+        "std",
+        "package standard is
+             type bit is ('0', '1');
+             type character is (nul, soh);
+             type severity_level is (note, warning, error, failure);
+             type real is range -1.0e308 to 1.0e308;
+             type time is range -9223372036854775807 to 9223372036854775807;
+             subtype natural is integer range 0 to 2147483647;
+             subtype positive is integer range 1 to 2147483647;
+             type string is array (positive range <>) of character;
+             type bit_vector is array (natural range <>) of bit;
+         end package;
+         package textio is
+             type line is access string;
+             type text is file of string;
+             type side is (right, left);
+             subtype width is natural;
+         end package;
+         package env is end package;",
+    ),
+    ("unisim", "package vcomponents is end package;"),
+    ("altera_mf", "package altera_mf_components is end package;"),
+];
+
+static ENTITIES: LazyLock<Vec<(&'static str, EntityDef)>> = LazyLock::new(|| {
+    ENTITY_LIBRARIES
+        .iter()
+        .flat_map(|(library, stubs)| {
+            stubs
+                .iter()
+                .map(move |(name, ports)| (*library, stub_entity(name, ports)))
+        })
+        .collect()
+});
+
+static PACKAGE_DEFS: LazyLock<Vec<(&'static str, PackageDef)>> = LazyLock::new(|| {
+    PACKAGES
+        .iter()
+        .flat_map(|(library, source)| {
+            analyze_vhdl(source)
+                .expect("組み込みのパッケージのスタブは解析できる")
+                .packages
+                .into_iter()
+                .map(move |package| (*library, package))
+        })
+        .collect()
+});
+
+/// 組み込みのスタブがあるライブラリか（大文字小文字を区別しない）
+pub fn is_builtin_library(library: &str) -> bool {
+    PACKAGES
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case(library))
+}
+
+/// ライブラリのプリミティブを探す（大文字小文字を区別しない）
+pub fn entity(library: &str, name: &str) -> Option<EntityDef> {
+    ENTITIES
+        .iter()
+        .find(|(lib, e)| lib.eq_ignore_ascii_case(library) && e.name.eq_ignore_ascii_case(name))
+        .map(|(_, e)| e.clone())
+}
+
+/// すべてのライブラリからプリミティブを探す（(ライブラリ, エンティティ) の組）
+pub fn find_entity(name: &str) -> Option<(&'static str, EntityDef)> {
+    ENTITIES
+        .iter()
+        .find(|(_, e)| e.name.eq_ignore_ascii_case(name))
+        .map(|(lib, e)| (*lib, e.clone()))
+}
+
+/// 標準パッケージ・コンポーネントのパッケージを探す
+pub fn package(library: &str, name: &str) -> Option<&'static PackageDef> {
+    PACKAGE_DEFS
+        .iter()
+        .find(|(lib, p)| lib.eq_ignore_ascii_case(library) && p.name.eq_ignore_ascii_case(name))
+        .map(|(_, p)| p)
+}
+
+/// `名前, 名前 : 方向 型; ...` の表からエンティティを作る
+fn stub_entity(name: &str, ports: &str) -> EntityDef {
+    let mut builder = EntityDef::builder(name);
+    for group in ports.split(';') {
+        let Some((names, rest)) = group.split_once(':') else {
+            continue;
+        };
+        let (direction, type_text) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+        let direction = PortDirection::from_str(direction).unwrap_or(PortDirection::In);
+        let type_text = type_text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let vhdl_type =
+            parse_type_str(&type_text).unwrap_or_else(|_| VhdlType::Other(type_text.clone()));
+        for port in names.split(',') {
+            builder = builder.port_def(PortDef::new(
+                port.trim(),
+                direction.clone(),
+                vhdl_type.clone(),
+            ));
+        }
+    }
+    builder.build()
+}
@@ -0,0 +1,799 @@
+//! Verilog モジュールのヘッダの読み取り
+//!
+//! 混在言語の設計で VHDL から Verilog のモジュールを使えるように、`module` のヘッダ
+//! （パラメータとポート）と本体のインスタンスだけを読み取ります。本体の論理は解釈しません。
+//! ポートは [`Module::to_entity`] で VHDL の型に対応付けた [`EntityDef`] になります。
+//!
+//! ```
+//! use vig::verilog::parse_modules;
+//!
+//! let modules = parse_modules(
+//!     "module adder #(parameter W = 8) (input [W-1:0] a, b, output reg [7:0] sum);\n\
+//!      endmodule",
+//! )
+//! .unwrap();
+//! let entity = modules[0].to_entity();
+//! assert_eq!(entity.name, "adder");
+//! assert_eq!(entity.ports.len(), 3);
+//! ```
+
+use crate::analyzer::{EntityDef, PortDef, PortDirection, VhdlType};
+use crate::lexer::{BOM, Span};
+
+/// Verilog の読み取りエラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerilogError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl VerilogError {
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for VerilogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at position {}-{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for VerilogError {}
+
+/// パラメータ
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    /// 既定値の式（ソースの表記のまま）
+    pub default: Option<String>,
+}
+
+/// ポート
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Port {
+    pub name: String,
+    pub direction: PortDirection,
+    /// `[msb:lsb]` の中身（ソースの表記のまま、スカラーなら None）
+    pub range: Option<(String, String)>,
+    /// `integer` `real` などのデータ型（ネット・`reg`・`logic` なら None）
+    pub data_type: Option<String>,
+    pub span: Span,
+}
+
+impl Port {
+    /// VHDL の型
+    ///
+    /// 範囲が整数なら `std_logic_vector`、式なら `std_logic_vector(式 downto 式)` の
+    /// 名前の型になります。
+    pub fn vhdl_type(&self) -> VhdlType {
+        match self.data_type.as_deref() {
+            Some("integer") | Some("int") => return VhdlType::Integer(None),
+            Some("real") | Some("realtime") => return VhdlType::Real,
+            _ => {}
+        }
+        let Some((msb, lsb)) = &self.range else {
+            return VhdlType::StdLogic;
+        };
+        match (parse_decimal(msb), parse_decimal(lsb)) {
+            // [0:7] は `to` の範囲
+            (Some(msb), Some(lsb)) => VhdlType::StdLogicVector {
+                high: msb.max(lsb).into(),
+                low: msb.min(lsb).into(),
+                ascending: msb < lsb,
+            },
+            _ => VhdlType::Other(format!("std_logic_vector({} downto {})", msb, lsb)),
+        }
+    }
+}
+
+/// 本体のインスタンス
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleInstance {
+    /// インスタンス名
+    pub label: String,
+    /// インスタンス化するモジュール（またはエンティティ）の名前
+    pub unit: String,
+    pub span: Span,
+}
+
+/// モジュール1つ
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub ports: Vec<Port>,
+    pub instances: Vec<ModuleInstance>,
+    /// `module` から `endmodule` まで
+    pub span: Span,
+}
+
+impl Module {
+    /// ポートを VHDL の型にしたエンティティ
+    ///
+    /// 範囲の式はパラメータの既定値で計算できれば数値の範囲にします。
+    pub fn to_entity(&self) -> EntityDef {
+        let mut builder = EntityDef::builder(&self.name);
+        for port in &self.ports {
+            let mut def = PortDef::new(&port.name, port.direction.clone(), self.port_type(port));
+            def.span = port.span;
+            builder = builder.port_def(def);
+        }
+        let mut entity = builder.build();
+        entity.span = self.span;
+        entity
+    }
+
+    /// パラメータの既定値で範囲を計算したポートの型
+    pub fn port_type(&self, port: &Port) -> VhdlType {
+        if port.data_type.is_none()
+            && let Some((msb, lsb)) = &port.range
+            && let (Some(msb), Some(lsb)) = (self.eval(msb, 0), self.eval(lsb, 0))
+        {
+            return VhdlType::StdLogicVector {
+                high: msb.max(lsb).into(),
+                low: msb.min(lsb).into(),
+                ascending: msb < lsb,
+            };
+        }
+        port.vhdl_type()
+    }
+
+    /// 定数式を計算する（パラメータは既定値を使う）
+    fn eval(&self, expr: &str, depth: usize) -> Option<i64> {
+        // パラメータが互いを参照し合っていても止まるように
+        if depth > 16 {
+            return None;
+        }
+        let mut eval = ConstEval {
+            module: self,
+            chars: expr.chars().filter(|c| !c.is_whitespace()).collect(),
+            pos: 0,
+            depth,
+        };
+        let value = eval.sum()?;
+        (eval.pos == eval.chars.len()).then_some(value)
+    }
+}
+
+/// 範囲の定数式（`+ - * / %` と括弧）の計算
+struct ConstEval<'a> {
+    module: &'a Module,
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl ConstEval<'_> {
+    fn sum(&mut self) -> Option<i64> {
+        let mut value = self.product()?;
+        while let Some(&op) = self.chars.get(self.pos).filter(|c| matches!(c, '+' | '-')) {
+            self.pos += 1;
+            let rhs = self.product()?;
+            value = if op == '+' {
+                value.checked_add(rhs)?
+            } else {
+                value.checked_sub(rhs)?
+            };
+        }
+        Some(value)
+    }
+
+    fn product(&mut self) -> Option<i64> {
+        let mut value = self.unary()?;
+        while let Some(&op) = self
+            .chars
+            .get(self.pos)
+            .filter(|c| matches!(c, '*' | '/' | '%'))
+        {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value.checked_mul(rhs)?,
+                '/' => value.checked_div(rhs)?,
+                _ => value.checked_rem(rhs)?,
+            };
+        }
+        Some(value)
+    }
+
+    fn unary(&mut self) -> Option<i64> {
+        match self.chars.get(self.pos)? {
+            '-' => {
+                self.pos += 1;
+                self.unary()?.checked_neg()
+            }
+            '(' => {
+                self.pos += 1;
+                let value = self.sum()?;
+                (self.chars.get(self.pos) == Some(&')')).then_some(())?;
+                self.pos += 1;
+                Some(value)
+            }
+            c if c.is_ascii_digit() => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == '_')
+                {
+                    self.pos += 1;
+                }
+                parse_decimal(&self.chars[start..self.pos].iter().collect::<String>())
+            }
+            c if c.is_ascii_alphabetic() || *c == '_' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '$')
+                {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                let parameter = self.module.parameters.iter().find(|p| p.name == name)?;
+                self.module
+                    .eval(parameter.default.as_deref()?, self.depth + 1)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// ソース中のモジュールをすべて読む
+pub fn parse_modules(source: &str) -> Result<Vec<Module>, VerilogError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        source,
+        tokens: &tokens,
+        pos: 0,
+    };
+    let mut modules = Vec::new();
+    while let Some(token) = parser.tokens.get(parser.pos) {
+        if token.is_word("module") || token.is_word("macromodule") {
+            modules.push(parser.module()?);
+        } else {
+            parser.pos += 1;
+        }
+    }
+    Ok(modules)
+}
+
+fn parse_decimal(text: &str) -> Option<i64> {
+    let digits: String = text.chars().filter(|&c| c != '_').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+// --- 字句解析 ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Word,
+    Number,
+    Str,
+    Symbol(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    text: String,
+    span: Span,
+}
+
+impl Token {
+    fn is_word(&self, word: &str) -> bool {
+        self.kind == Kind::Word && self.text == word
+    }
+
+    fn is_symbol(&self, c: char) -> bool {
+        self.kind == Kind::Symbol(c)
+    }
+}
+
+/// コメント・コンパイラ指示子・属性を除いてトークンに分ける
+fn tokenize(source: &str) -> Result<Vec<Token>, VerilogError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        let rest = &source[start..];
+        if c.is_whitespace() || (start == 0 && c == BOM) {
+            chars.next();
+        } else if rest.starts_with("//") || c == '`' {
+            // 行コメントとコンパイラ指示子（`define など）は行末まで
+            let len = rest.find('\n').unwrap_or(rest.len());
+            skip_to(&mut chars, start + len);
+        } else if rest.starts_with("/*") || (rest.starts_with("(*") && !rest.starts_with("(*)")) {
+            let close = if c == '/' { "*/" } else { "*)" };
+            let Some(len) = rest[2..].find(close) else {
+                return Err(VerilogError::new(
+                    "unterminated comment",
+                    Span::new(start, source.len()),
+                ));
+            };
+            skip_to(&mut chars, start + 2 + len + 2);
+        } else if c == '"' {
+            let mut end = None;
+            let mut escaped = false;
+            for (i, ch) in rest.char_indices().skip(1) {
+                match ch {
+                    '\\' if !escaped => escaped = true,
+                    '"' if !escaped => {
+                        end = Some(start + i + 1);
+                        break;
+                    }
+                    _ => escaped = false,
+                }
+            }
+            let Some(end) = end else {
+                return Err(VerilogError::new(
+                    "unterminated string",
+                    Span::new(start, source.len()),
+                ));
+            };
+            tokens.push(token(source, Kind::Str, start, end));
+            skip_to(&mut chars, end);
+        } else if c == '\\' {
+            // エスケープされた識別子（空白まで）
+            let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            tokens.push(Token {
+                kind: Kind::Word,
+                text: rest[1..len].to_string(),
+                span: Span::new(start, start + len),
+            });
+            skip_to(&mut chars, start + len);
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let len = rest
+                .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+                .unwrap_or(rest.len());
+            tokens.push(token(source, Kind::Word, start, start + len));
+            skip_to(&mut chars, start + len);
+        } else if c.is_ascii_digit() || c == '\'' {
+            // 10、8'hFF、'b1 などの数値（基数の後の桁も含める）
+            let len = rest
+                .char_indices()
+                .skip(1)
+                .find(|&(_, ch)| !(ch.is_ascii_alphanumeric() || matches!(ch, '_' | '\'' | '?')))
+                .map_or(rest.len(), |(i, _)| i);
+            tokens.push(token(source, Kind::Number, start, start + len));
+            skip_to(&mut chars, start + len);
+        } else {
+            tokens.push(token(source, Kind::Symbol(c), start, start + c.len_utf8()));
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+fn token(source: &str, kind: Kind, start: usize, end: usize) -> Token {
+    Token {
+        kind,
+        text: source[start..end].to_string(),
+        span: Span::new(start, end),
+    }
+}
+
+fn skip_to(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>, end: usize) {
+    while chars.next_if(|&(i, _)| i < end).is_some() {}
+}
+
+// --- 構文解析 ---
+
+/// モジュール名やインスタンス名にならない予約語
+const KEYWORDS: &[&str] = &[
+    "always",
+    "always_comb",
+    "always_ff",
+    "always_latch",
+    "and",
+    "assign",
+    "assert",
+    "automatic",
+    "begin",
+    "bit",
+    "buf",
+    "case",
+    "casex",
+    "casez",
+    "default",
+    "defparam",
+    "else",
+    "end",
+    "endcase",
+    "endfunction",
+    "endgenerate",
+    "endtask",
+    "for",
+    "function",
+    "generate",
+    "genvar",
+    "if",
+    "initial",
+    "inout",
+    "input",
+    "int",
+    "integer",
+    "localparam",
+    "logic",
+    "nand",
+    "nor",
+    "not",
+    "or",
+    "output",
+    "parameter",
+    "real",
+    "reg",
+    "return",
+    "signed",
+    "supply0",
+    "supply1",
+    "task",
+    "time",
+    "tri",
+    "typedef",
+    "unsigned",
+    "var",
+    "while",
+    "wire",
+    "xnor",
+    "xor",
+];
+
+/// ネット・変数の型（ポートのデータ型としては無視する）
+const NET_TYPES: &[&str] = &[
+    "wire", "reg", "logic", "bit", "tri", "var", "signed", "unsigned", "wand", "wor", "uwire",
+];
+
+/// データ型（ポートの型として残す）
+const DATA_TYPES: &[&str] = &["integer", "int", "real", "realtime", "time"];
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+/// ポート宣言の方向・型・範囲（ANSI 形式では後続の名前に引き継ぐ）
+#[derive(Clone)]
+struct PortStyle {
+    direction: PortDirection,
+    range: Option<(String, String)>,
+    data_type: Option<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn get(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn error(&self, message: &str) -> VerilogError {
+        let span = self
+            .get(0)
+            .map_or(Span::new(self.source.len(), self.source.len()), |t| t.span);
+        VerilogError::new(message, span)
+    }
+
+    fn expect_symbol(&mut self, c: char) -> Result<(), VerilogError> {
+        if self.get(0).is_some_and(|t| t.is_symbol(c)) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<&'a Token, VerilogError> {
+        match self.get(0) {
+            Some(t) if t.kind == Kind::Word && !KEYWORDS.contains(&t.text.as_str()) => {
+                self.pos += 1;
+                Ok(t)
+            }
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    /// 対応する閉じ括弧の位置（開き括弧の位置から）
+    fn matching(&self, open: usize) -> Option<usize> {
+        let (l, r) = match self.tokens.get(open)?.kind {
+            Kind::Symbol('(') => ('(', ')'),
+            Kind::Symbol('[') => ('[', ']'),
+            Kind::Symbol('{') => ('{', '}'),
+            _ => return None,
+        };
+        let mut depth = 0usize;
+        for (i, t) in self.tokens.iter().enumerate().skip(open) {
+            if t.is_symbol(l) {
+                depth += 1;
+            } else if t.is_symbol(r) {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+        }
+        None
+    }
+
+    /// トークンの範囲を括弧の外の区切り記号で分けた範囲
+    fn split(&self, start: usize, end: usize, sep: char) -> Vec<(usize, usize)> {
+        let mut items = Vec::new();
+        let mut depth = 0usize;
+        let mut item = start;
+        for i in start..end {
+            let t = &self.tokens[i];
+            match t.kind {
+                Kind::Symbol('(' | '[' | '{') => depth += 1,
+                Kind::Symbol(')' | ']' | '}') => depth = depth.saturating_sub(1),
+                Kind::Symbol(c) if c == sep && depth == 0 => {
+                    items.push((item, i));
+                    item = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if item < end {
+            items.push((item, end));
+        }
+        items
+    }
+
+    /// トークンの範囲のソース上の表記
+    fn text(&self, start: usize, end: usize) -> String {
+        if start >= end {
+            return String::new();
+        }
+        let from = self.tokens[start].span.start;
+        let to = self.tokens[end - 1].span.end;
+        self.source[from..to].trim().to_string()
+    }
+
+    fn module(&mut self) -> Result<Module, VerilogError> {
+        let start = self.tokens[self.pos].span.start;
+        self.pos += 1;
+        let name = self.identifier()?.text.clone();
+        // import 宣言（SystemVerilog）は読み飛ばす
+        while self.get(0).is_some_and(|t| t.is_word("import")) {
+            while self.get(0).is_some_and(|t| !t.is_symbol(';')) {
+                self.pos += 1;
+            }
+            self.pos += 1;
+        }
+
+        let mut parameters = Vec::new();
+        if self.get(0).is_some_and(|t| t.is_symbol('#')) {
+            self.pos += 1;
+            let open = self.pos;
+            let close = self
+                .matching(open)
+                .ok_or_else(|| self.error("expected '('"))?;
+            for (s, e) in self.split(open + 1, close, ',') {
+                parameters.extend(self.parameter(s, e));
+            }
+            self.pos = close + 1;
+        }
+
+        let mut ports = Vec::new();
+        let mut listed = Vec::new();
+        if self.get(0).is_some_and(|t| t.is_symbol('(')) {
+            let open = self.pos;
+            let close = self
+                .matching(open)
+                .ok_or_else(|| self.error("unbalanced port list"))?;
+            let mut style: Option<PortStyle> = None;
+            for (s, e) in self.split(open + 1, close, ',') {
+                match self.port_style(s, e)? {
+                    (Some(declared), rest) => {
+                        style = Some(declared);
+                        ports.extend(self.port_names(rest, e, style.as_ref()));
+                    }
+                    (None, rest) if style.is_some() => {
+                        ports.extend(self.port_names(rest, e, style.as_ref()));
+                    }
+                    // 非 ANSI 形式: 名前だけ並べ、本体で宣言する
+                    (None, rest) => {
+                        if let Some(t) = self.tokens[rest..e].iter().find(|t| t.kind == Kind::Word)
+                        {
+                            listed.push((t.text.clone(), t.span));
+                        }
+                    }
+                }
+            }
+            self.pos = close + 1;
+        }
+        self.expect_symbol(';')?;
+
+        let mut instances = Vec::new();
+        let mut declared: Vec<Port> = Vec::new();
+        let end = loop {
+            let Some(t) = self.get(0) else {
+                return Err(VerilogError::new(
+                    format!("module '{}' has no endmodule", name),
+                    Span::new(start, self.source.len()),
+                ));
+            };
+            if t.is_word("endmodule") {
+                self.pos += 1;
+                break t.span.end;
+            }
+            if t.is_word("input") || t.is_word("output") || t.is_word("inout") {
+                let s = self.pos;
+                let mut e = s;
+                while self.tokens.get(e).is_some_and(|t| !t.is_symbol(';')) {
+                    e += 1;
+                }
+                // `input a, b;` の名前は `,` で区切られ、方向は最初の項目だけにある
+                let mut style = None;
+                for (is, ie) in self.split(s, e, ',') {
+                    let (declared_style, rest) = self.port_style(is, ie)?;
+                    if declared_style.is_some() {
+                        style = declared_style;
+                    }
+                    declared.extend(self.port_names(rest, ie, style.as_ref()));
+                }
+                self.pos = e + 1;
+                continue;
+            }
+            if let Some(instance) = self.instance() {
+                instances.push(instance);
+                continue;
+            }
+            self.pos += 1;
+        };
+
+        // 非 ANSI 形式はヘッダの順に本体の宣言を並べる
+        for (port_name, span) in listed {
+            match declared.iter().position(|p| p.name == port_name) {
+                Some(i) => {
+                    let mut port = declared.remove(i);
+                    port.span = span;
+                    ports.push(port);
+                }
+                None => {
+                    return Err(VerilogError::new(
+                        format!("port '{}' has no direction declaration", port_name),
+                        span,
+                    ));
+                }
+            }
+        }
+
+        Ok(Module {
+            name,
+            parameters,
+            ports,
+            instances,
+            span: Span::new(start, end),
+        })
+    }
+
+    /// `parameter [型] [範囲] NAME = 既定値`
+    fn parameter(&self, start: usize, end: usize) -> Option<Parameter> {
+        let eq = (start..end).find(|&i| self.tokens[i].is_symbol('='));
+        let name = self.tokens[start..eq.unwrap_or(end)]
+            .iter()
+            .rev()
+            .find(|t| t.kind == Kind::Word && !KEYWORDS.contains(&t.text.as_str()))?;
+        Some(Parameter {
+            name: name.text.clone(),
+            default: eq.map(|i| self.text(i + 1, end)),
+        })
+    }
+
+    /// ポート宣言の先頭の方向・型・範囲を読み、名前の始まる位置を返す
+    fn port_style(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<(Option<PortStyle>, usize), VerilogError> {
+        let mut i = start;
+        let direction = match self.tokens.get(i).map(|t| t.text.as_str()) {
+            Some("input") => PortDirection::In,
+            Some("output") => PortDirection::Out,
+            Some("inout") => PortDirection::Inout,
+            _ => return Ok((None, start)),
+        };
+        i += 1;
+        let mut data_type = None;
+        while i < end {
+            let t = &self.tokens[i];
+            if t.kind != Kind::Word {
+                break;
+            }
+            if DATA_TYPES.contains(&t.text.as_str()) {
+                data_type = Some(t.text.clone());
+            } else if !NET_TYPES.contains(&t.text.as_str()) {
+                break;
+            }
+            i += 1;
+        }
+        let mut range = None;
+        if i < end && self.tokens[i].is_symbol('[') {
+            let close = self
+                .matching(i)
+                .filter(|&c| c < end)
+                .ok_or_else(|| VerilogError::new("unbalanced range", self.tokens[i].span))?;
+            let parts = self.split(i + 1, close, ':');
+            if let [(ms, me), (ls, le)] = parts[..] {
+                range = Some((self.text(ms, me), self.text(ls, le)));
+            }
+            i = close + 1;
+        }
+        Ok((
+            Some(PortStyle {
+                direction,
+                range,
+                data_type,
+            }),
+            i,
+        ))
+    }
+
+    /// 宣言の名前（`= 既定値` と配列の次元は除く）
+    fn port_names(&self, start: usize, end: usize, style: Option<&PortStyle>) -> Vec<Port> {
+        let Some(style) = style else {
+            return Vec::new();
+        };
+        self.tokens[start..end]
+            .iter()
+            .take_while(|t| !t.is_symbol('=') && !t.is_symbol('['))
+            .filter(|t| t.kind == Kind::Word && !KEYWORDS.contains(&t.text.as_str()))
+            .map(|t| Port {
+                name: t.text.clone(),
+                direction: style.direction.clone(),
+                range: style.range.clone(),
+                data_type: style.data_type.clone(),
+                span: t.span,
+            })
+            .collect()
+    }
+
+    /// `モジュール名 [#(...)] インスタンス名 [範囲] (...) ;` ならインスタンスとして読む
+    fn instance(&mut self) -> Option<ModuleInstance> {
+        let unit = self.get(0)?;
+        let after_dot = self.pos > 0 && self.tokens[self.pos - 1].is_symbol('.');
+        if unit.kind != Kind::Word || KEYWORDS.contains(&unit.text.as_str()) || after_dot {
+            return None;
+        }
+        let mut at = self.pos + 1;
+        if self.tokens.get(at)?.is_symbol('#') {
+            at = self.matching(at + 1)? + 1;
+        }
+        let label = self.tokens.get(at)?;
+        if label.kind != Kind::Word || KEYWORDS.contains(&label.text.as_str()) {
+            return None;
+        }
+        at += 1;
+        if self.tokens.get(at)?.is_symbol('[') {
+            at = self.matching(at)? + 1;
+        }
+        if !self.tokens.get(at)?.is_symbol('(') {
+            return None;
+        }
+        let close = self.matching(at)?;
+        // `m u1 (...), u2 (...);` の後続は読み飛ばす
+        let mut end = close + 1;
+        while self.tokens.get(end).is_some_and(|t| !t.is_symbol(';')) {
+            end += 1;
+        }
+        self.pos = end + 1;
+        Some(ModuleInstance {
+            label: label.text.clone(),
+            unit: unit.text.clone(),
+            span: Span::new(unit.span.start, self.tokens[close].span.end),
+        })
+    }
+}
@@ -0,0 +1,50 @@
+//! WebAssemblyバインディング（`wasm` feature）
+//!
+//! ブラウザやVS Code Web拡張から利用するためのエントリポイントです。
+//! 入力はVHDLソース文字列、出力はJSONまたはVHDL文字列で、
+//! エラーはメッセージ文字列を持つ例外としてJavaScript側に送出されます。
+
+use wasm_bindgen::prelude::*;
+
+use crate::analyzer;
+use crate::generator::{self, TbConfig};
+use crate::interface::InterfaceDef;
+
+/// VHDLソースを解析し、結果をJSON文字列で返す
+#[wasm_bindgen(js_name = analyzeVhdl)]
+pub fn analyze_vhdl(source: &str) -> Result<String, JsValue> {
+    analyzer::analyze_vhdl(source)
+        .map(|result| result.to_json())
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// VHDLソース中の全エンティティのテストベンチを生成する
+#[wasm_bindgen(js_name = generateTestbench)]
+pub fn generate_testbench(source: &str, clock_period_ns: u32) -> Result<String, JsValue> {
+    let result =
+        analyzer::analyze_vhdl(source).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let config = TbConfig {
+        clock_period_ns: u64::from(clock_period_ns),
+        ..TbConfig::default()
+    };
+    Ok(result
+        .entities
+        .iter()
+        .map(|e| generator::generate_testbench(e, &config))
+        .collect())
+}
+
+/// インターフェース記述(JSON)からテストベンチを生成する
+#[wasm_bindgen(js_name = generateTestbenchFromInterface)]
+pub fn generate_testbench_from_interface(
+    interface_json: &str,
+    clock_period_ns: u32,
+) -> Result<String, JsValue> {
+    let interface = InterfaceDef::from_json(interface_json)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let config = TbConfig {
+        clock_period_ns: u64::from(clock_period_ns),
+        ..TbConfig::default()
+    };
+    Ok(generator::generate_testbench(&interface.entity, &config))
+}
@@ -0,0 +1,107 @@
+//! 生成するスティミュラスのタイミング図（WaveDrom JSON）
+//!
+//! テストベンチのテストシナリオと同じ時間軸で、クロック・リセット・入力信号の
+//! 波形を [WaveDrom](https://wavedrom.com/) 形式で出力します。1文字が1クロック周期です。
+
+use crate::analyzer::{EntityDef, PortDirection, VhdlType};
+use crate::generator::{
+    RESET_CYCLES, ResetPolarity, TEST_CYCLES, TbConfig, find_clock_port, find_reset_port,
+};
+use crate::json::Value;
+
+/// EntityDefからスティミュラスのWaveDrom JSONを生成する（末尾改行付き）
+pub fn generate_wavedrom(entity: &EntityDef, config: &TbConfig) -> String {
+    let entity = &config.target_ports(entity);
+    let clk_port = find_clock_port(&entity.ports);
+    let rst_port = find_reset_port(&entity.ports);
+
+    // リセットシーケンス + テストパターン
+    let reset_cycles = if rst_port.is_some() {
+        RESET_CYCLES * 2
+    } else {
+        0
+    };
+    let cycles = (reset_cycles + TEST_CYCLES) as usize;
+    let hold = |first: &str| format!("{}{}", first, ".".repeat(cycles - 1));
+    let wave = |name: &str, wave: String| {
+        Value::Object(vec![
+            ("name".into(), name.into()),
+            ("wave".into(), wave.into()),
+        ])
+    };
+
+    let mut control = Vec::new();
+    if let Some(clk) = &clk_port {
+        // '0' から始まり半周期後に立ち上がる
+        control.push(wave(clk, hold("n")));
+    }
+    if let Some(rst) = &rst_port {
+        let assert_cycles = RESET_CYCLES as usize;
+        let (asserted, released) = match config.reset_polarity {
+            ResetPolarity::ActiveLow => ('0', '1'),
+            ResetPolarity::ActiveHigh => ('1', '0'),
+        };
+        control.push(wave(
+            rst,
+            format!(
+                "{}{}{}{}",
+                asserted,
+                ".".repeat(assert_cycles - 1),
+                released,
+                ".".repeat(cycles - assert_cycles - 1)
+            ),
+        ));
+    }
+
+    let mut inputs = Vec::new();
+    for port in &entity.ports {
+        if !matches!(port.direction, PortDirection::In | PortDirection::Inout)
+            || Some(&port.name) == clk_port.as_ref()
+            || Some(&port.name) == rst_port.as_ref()
+        {
+            continue;
+        }
+        // 入力はシナリオ中デフォルト値のまま
+        let (first, data) = match &port.vhdl_type {
+            VhdlType::StdLogic | VhdlType::Boolean | VhdlType::Bit => ("0", None),
+            VhdlType::Integer(_) | VhdlType::Natural(_) => ("=", Some("0")),
+            VhdlType::Positive(_) => ("=", Some("1")),
+            VhdlType::Real => ("=", Some("0.0")),
+            VhdlType::Time => ("=", Some("0 ns")),
+            _ if port.vhdl_type.vector_range().is_some() => ("=", Some("0")),
+            _ => ("x", None),
+        };
+        let mut members = vec![
+            ("name".to_string(), port.name.as_str().into()),
+            ("wave".to_string(), hold(first).into()),
+        ];
+        if let Some(data) = data {
+            members.push(("data".to_string(), Value::Array(vec![data.into()])));
+        }
+        inputs.push(Value::Object(members));
+    }
+
+    let mut signal = control;
+    if !signal.is_empty() && !inputs.is_empty() {
+        signal.push(Value::Object(Vec::new()));
+    }
+    signal.extend(inputs);
+
+    let root = Value::Object(vec![
+        ("signal".into(), Value::Array(signal)),
+        (
+            "head".into(),
+            Value::Object(vec![("text".into(), format!("{}_tb", entity.name).into())]),
+        ),
+        (
+            "foot".into(),
+            Value::Object(vec![(
+                "text".into(),
+                format!("1周期 = {} ns", config.clock_period_ns).into(),
+            )]),
+        ),
+    ]);
+    let mut s = root.to_pretty_string();
+    s.push('\n');
+    s
+}
@@ -0,0 +1,272 @@
+use crate::lexer::{Span, skip_bom};
+
+/// XML要素（名前空間プレフィックスは除去したローカル名で保持）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: String,
+    pub span: Span,
+}
+
+impl Element {
+    /// 指定した名前の最初の子要素
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// 指定した名前の子要素をすべて列挙
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// 子要素のテキスト（前後の空白を除去）
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|c| c.text.trim())
+    }
+}
+
+/// XMLの解析エラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl XmlError {
+    fn new(message: impl Into<String>, pos: usize) -> Self {
+        Self {
+            message: message.into(),
+            span: Span::new(pos, pos),
+        }
+    }
+}
+
+/// 簡易XMLパーサ（DTD・処理命令は読み飛ばす）
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+/// XML文書を解析してルート要素を返す
+pub fn parse(source: &str) -> Result<Element, XmlError> {
+    let mut parser = Parser {
+        src: source,
+        pos: skip_bom(source),
+    };
+    parser.skip_misc()?;
+    let root = parser.parse_element()?;
+    parser.skip_misc()?;
+    if parser.pos < parser.src.len() {
+        return Err(XmlError::new(
+            "unexpected content after root element",
+            parser.pos,
+        ));
+    }
+    Ok(root)
+}
+
+/// 特殊文字をエスケープ（テキスト・属性値のどちらにも使える）
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `prefix:name` からローカル名を取り出す
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.src.len() - trimmed.len();
+    }
+
+    /// `end` までを読み飛ばす
+    fn skip_past(&mut self, end: &str) -> Result<(), XmlError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => Err(XmlError::new(format!("missing '{}'", end), self.pos)),
+        }
+    }
+
+    /// XML宣言・コメント・DOCTYPE・空白を読み飛ばす
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '>' | '/' | '='))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(XmlError::new("expected name", self.pos));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn parse_element(&mut self) -> Result<Element, XmlError> {
+        let start = self.pos;
+        if !self.rest().starts_with('<') {
+            return Err(XmlError::new("expected '<'", self.pos));
+        }
+        self.pos += 1;
+        let qname = self.parse_name()?;
+
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(Element {
+                    name: local_name(qname).to_string(),
+                    attributes,
+                    children: Vec::new(),
+                    text: String::new(),
+                    span: Span::new(start, self.pos),
+                });
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            if rest.is_empty() {
+                return Err(XmlError::new("unterminated start tag", start));
+            }
+            let attr = self.parse_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(XmlError::new("expected '=' after attribute", self.pos));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(XmlError::new("expected quoted attribute value", self.pos)),
+            };
+            self.pos += 1;
+            let end = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| XmlError::new("unterminated attribute value", self.pos))?;
+            let value = decode_entities(&self.rest()[..end]);
+            self.pos += end + 1;
+            attributes.push((local_name(attr).to_string(), value));
+        }
+
+        let mut children = Vec::new();
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(XmlError::new(
+                    format!("unclosed element <{}>", qname),
+                    start,
+                ));
+            }
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let close = self.parse_name()?;
+                if close != qname {
+                    return Err(XmlError::new(
+                        format!("mismatched closing tag </{}> for <{}>", close, qname),
+                        self.pos,
+                    ));
+                }
+                self.skip_whitespace();
+                self.skip_past(">")?;
+                break;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let end = self
+                    .rest()
+                    .find("]]>")
+                    .ok_or_else(|| XmlError::new("unterminated CDATA", self.pos))?;
+                text.push_str(&self.rest()[..end]);
+                self.pos += end + 3;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                children.push(self.parse_element()?);
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                text.push_str(&decode_entities(&rest[..end]));
+                self.pos += end;
+            }
+        }
+
+        Ok(Element {
+            name: local_name(qname).to_string(),
+            attributes,
+            children,
+            text,
+            span: Span::new(start, self.pos),
+        })
+    }
+}
+
+/// 文字実体参照を展開する
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
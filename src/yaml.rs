@@ -0,0 +1,443 @@
+//! 設定ファイル向けの最小限のYAMLパーサ
+//!
+//! ブロック形式のマッピング・シーケンス（`- name: x` の形を含む）・
+//! フロー形式の `[a, b]` `{k: v}`・引用符付き/なしのスカラー・コメントに対応し、
+//! 結果を [`Value`] で返します。スカラーは `null` `~` `true` `false`、
+//! 整数（16/8/2進数を含む）、浮動小数点数、それ以外は文字列として読みます。
+//! アンカー・エイリアス・タグ・複数行スカラー（`|` `>`）・複数文書には対応していません。
+
+use crate::json::Value;
+use crate::lexer::{Span, skip_bom};
+
+/// YAMLの解析エラー
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl YamlError {
+    fn new(message: impl Into<String>, pos: usize) -> Self {
+        Self {
+            message: message.into(),
+            span: Span::new(pos, pos),
+        }
+    }
+}
+
+/// YAML文書を解析する
+pub fn parse(source: &str) -> Result<Value, YamlError> {
+    let mut lines = Vec::new();
+    let mut pos = skip_bom(source);
+    for raw in source[pos..].split_inclusive('\n') {
+        let line = raw.trim_end_matches(['\n', '\r']);
+        let body = strip_comment(line);
+        let content = body.trim_start_matches(' ');
+        let indent = body.len() - content.len();
+        if content.starts_with('\t') {
+            return Err(YamlError::new("tabs are not allowed in indentation", pos));
+        }
+        let content = content.trim_end();
+        match (indent, content) {
+            (0, "...") => break,
+            (_, "") | (0, "---") => {}
+            _ => lines.push(Line {
+                indent,
+                text: content,
+                pos: pos + indent,
+            }),
+        }
+        pos += raw.len();
+    }
+
+    let mut parser = Parser { lines, index: 0 };
+    let Some(first) = parser.lines.first() else {
+        return Ok(Value::Null);
+    };
+    let value = parser.parse_block(first.indent)?;
+    match parser.lines.get(parser.index) {
+        None => Ok(value),
+        Some(line) => Err(YamlError::new("unexpected indentation", line.pos)),
+    }
+}
+
+/// 行末のコメントを除く（`#` は行頭か空白の後、引用符の外にあるもの）
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut prev = b' ';
+    for (i, b) in line.bytes().enumerate() {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if b == b'#' && matches!(prev, b' ' | b'\t') => return &line[..i],
+            None if matches!(b, b'"' | b'\'')
+                && matches!(prev, b' ' | b'[' | b'{' | b',' | b':') =>
+            {
+                quote = Some(b)
+            }
+            None => {}
+        }
+        prev = b;
+    }
+    line
+}
+
+/// コメントと空行を除いた1行
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+    /// 内容の先頭のバイト位置
+    pos: usize,
+}
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// `indent` の位置から始まるブロック（マッピング・シーケンス・スカラー）
+    fn parse_block(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let line = &self.lines[self.index];
+        if is_sequence_item(line.text) {
+            self.parse_sequence(indent)
+        } else if split_key(line.text, line.pos)?.is_some() {
+            self.parse_mapping(indent)
+        } else {
+            self.index += 1;
+            parse_inline(line.text, line.pos)
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get(self.index) {
+            if line.indent != indent || !is_sequence_item(line.text) {
+                break;
+            }
+            let rest = line.text[1..].trim_start_matches(' ');
+            if rest.is_empty() {
+                self.index += 1;
+                items.push(self.parse_nested(indent)?);
+            } else {
+                // `- key: value` は要素の内容を1段深い行として読む
+                let offset = line.text.len() - rest.len();
+                let item_indent = indent + offset;
+                self.lines[self.index] = Line {
+                    indent: item_indent,
+                    text: rest,
+                    pos: line.pos + offset,
+                };
+                items.push(self.parse_block(item_indent)?);
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Value, YamlError> {
+        let mut members: Vec<(String, Value)> = Vec::new();
+        while let Some(line) = self.lines.get(self.index) {
+            if line.indent != indent || is_sequence_item(line.text) {
+                break;
+            }
+            let (pos, text) = (line.pos, line.text);
+            let Some((key, rest)) = split_key(text, pos)? else {
+                return Err(YamlError::new("expected 'key: value'", pos));
+            };
+            if members.iter().any(|(k, _)| *k == key) {
+                return Err(YamlError::new(format!("duplicate key '{}'", key), pos));
+            }
+            self.index += 1;
+            let value = if rest.is_empty() {
+                // 値のシーケンスはキーと同じ字下げでもよい
+                match self.lines.get(self.index) {
+                    Some(next) if next.indent == indent && is_sequence_item(next.text) => {
+                        self.parse_sequence(indent)?
+                    }
+                    _ => self.parse_nested(indent)?,
+                }
+            } else {
+                let start = pos + text.len() - rest.len();
+                parse_inline(rest, start)?
+            };
+            members.push((key, value));
+        }
+        Ok(Value::Object(members))
+    }
+
+    /// `parent` より深い字下げの行があればそのブロック、なければ null
+    fn parse_nested(&mut self, parent: usize) -> Result<Value, YamlError> {
+        match self.lines.get(self.index) {
+            Some(next) if next.indent > parent => self.parse_block(next.indent),
+            _ => Ok(Value::Null),
+        }
+    }
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// `key: value` をキーと値の部分に分ける（マッピングの行でなければ `None`）
+fn split_key(text: &str, pos: usize) -> Result<Option<(String, &str)>, YamlError> {
+    let (key, rest) = if text.starts_with(['"', '\'']) {
+        let mut scanner = Scanner {
+            text,
+            pos: 0,
+            base: pos,
+        };
+        let key = scanner.parse_quoted()?;
+        let rest = text[scanner.pos..].trim_start_matches(' ');
+        match rest.strip_prefix(':') {
+            Some(after) if after.is_empty() || after.starts_with(' ') => (key, after),
+            _ => return Ok(None),
+        }
+    } else if text.starts_with(['[', '{']) {
+        return Ok(None);
+    } else {
+        let bytes = text.as_bytes();
+        let Some(colon) = (0..bytes.len())
+            .find(|&i| bytes[i] == b':' && matches!(bytes.get(i + 1), None | Some(b' ')))
+        else {
+            return Ok(None);
+        };
+        (text[..colon].trim_end().to_string(), &text[colon + 1..])
+    };
+    if key.is_empty() {
+        return Err(YamlError::new("empty key", pos));
+    }
+    if rest.trim_start().starts_with(['|', '>', '&', '*', '!']) {
+        return Err(YamlError::new(
+            "block scalars, anchors, aliases and tags are not supported",
+            pos,
+        ));
+    }
+    Ok(Some((key, rest.trim_start())))
+}
+
+/// 1行の中の値（フロー形式またはスカラー）
+fn parse_inline(text: &str, pos: usize) -> Result<Value, YamlError> {
+    let mut scanner = Scanner {
+        text,
+        pos: 0,
+        base: pos,
+    };
+    let value = scanner.parse_value(false)?;
+    scanner.skip_spaces();
+    if scanner.pos < text.len() {
+        return Err(scanner.error("unexpected characters after value"));
+    }
+    Ok(value)
+}
+
+/// 1行分の文字列を読み進める
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    /// 文書内での行の位置（エラー位置用）
+    base: usize,
+}
+
+impl Scanner<'_> {
+    fn error(&self, message: impl Into<String>) -> YamlError {
+        YamlError::new(message, self.base + self.pos)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    /// `in_flow` ならプレーンなスカラーは `,` `]` `}` で終わる
+    fn parse_value(&mut self, in_flow: bool) -> Result<Value, YamlError> {
+        self.skip_spaces();
+        match self.peek() {
+            Some(b'"' | b'\'') => Ok(Value::String(self.parse_quoted()?)),
+            Some(b'[') => self.parse_flow_sequence(),
+            Some(b'{') => self.parse_flow_mapping(),
+            Some(b'&' | b'*' | b'!' | b'|' | b'>') => {
+                Err(self.error("block scalars, anchors, aliases and tags are not supported"))
+            }
+            _ => {
+                let start = self.pos;
+                let bytes = self.text.as_bytes();
+                while let Some(&b) = bytes.get(self.pos) {
+                    if in_flow
+                        && (matches!(b, b',' | b']' | b'}')
+                            || (b == b':' && matches!(bytes.get(self.pos + 1), None | Some(b' '))))
+                    {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                Ok(plain_scalar(self.text[start..self.pos].trim_end()))
+            }
+        }
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, YamlError> {
+        let start = self.pos;
+        let quote = self.peek().expect("called on a quote");
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let rest = &self.text[self.pos..];
+            let Some(i) = rest.find(|c| c == quote as char || (quote == b'"' && c == '\\')) else {
+                self.pos = start;
+                return Err(self.error("unterminated string"));
+            };
+            out.push_str(&rest[..i]);
+            self.pos += i + 1;
+            if quote == b'\'' {
+                // 単一引用符の中では '' が ' を表す
+                if self.peek() == Some(b'\'') {
+                    out.push('\'');
+                    self.pos += 1;
+                    continue;
+                }
+                return Ok(out);
+            }
+            if rest.as_bytes()[i] == b'"' {
+                return Ok(out);
+            }
+            let esc = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match esc {
+                b'"' => out.push('"'),
+                b'\\' => out.push('\\'),
+                b'/' => out.push('/'),
+                b'0' => out.push('\0'),
+                b't' => out.push('\t'),
+                b'n' => out.push('\n'),
+                b'r' => out.push('\r'),
+                b'x' | b'u' | b'U' => {
+                    let len = match esc {
+                        b'x' => 2,
+                        b'u' => 4,
+                        _ => 8,
+                    };
+                    let c = self
+                        .text
+                        .get(self.pos..self.pos + len)
+                        .and_then(|h| u32::from_str_radix(h, 16).ok())
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| self.error("invalid unicode escape"))?;
+                    self.pos += len;
+                    out.push(c);
+                }
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("invalid escape sequence"));
+                }
+            }
+        }
+    }
+
+    fn parse_flow_sequence(&mut self) -> Result<Value, YamlError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_spaces();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.parse_value(true)?);
+            self.skip_spaces();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_flow_mapping(&mut self) -> Result<Value, YamlError> {
+        self.pos += 1;
+        let mut members: Vec<(String, Value)> = Vec::new();
+        loop {
+            self.skip_spaces();
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Value::Object(members));
+            }
+            let key_pos = self.pos;
+            let key = match self.parse_value(true)? {
+                Value::String(s) => s,
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            if key.is_empty() {
+                self.pos = key_pos;
+                return Err(self.error("empty key"));
+            }
+            if members.iter().any(|(k, _)| *k == key) {
+                self.pos = key_pos;
+                return Err(self.error(format!("duplicate key '{}'", key)));
+            }
+            self.skip_spaces();
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.pos += 1;
+            let value = self.parse_value(true)?;
+            members.push((key, value));
+            self.skip_spaces();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+/// 引用符なしのスカラーの型を決める
+fn plain_scalar(text: &str) -> Value {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+    let (negative, digits) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let radix = match digits.get(..2) {
+        Some("0x") => Some(16),
+        Some("0o") => Some(8),
+        Some("0b") => Some(2),
+        _ => None,
+    };
+    let integer = match radix {
+        Some(radix) => i64::from_str_radix(&digits[2..], radix).ok(),
+        None if digits.bytes().all(|b| b.is_ascii_digit()) => digits.parse().ok(),
+        None => None,
+    };
+    if let Some(n) = integer.filter(|_| !digits.is_empty()) {
+        return Value::Integer(if negative { -n } else { n });
+    }
+    let is_float = digits.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && digits
+            .bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'+' | b'-'));
+    match text.parse::<f64>() {
+        Ok(f) if is_float => Value::Float(f),
+        _ => Value::String(text.to_string()),
+    }
+}
@@ -30,25 +30,25 @@ begin
                 when "000" =>
                     -- 加算
                     result_internal <= std_logic_vector(
-                        unsigned('0' and op_a) + unsigned('0' and op_b)
+                        resize(unsigned(op_a), 5) + resize(unsigned(op_b), 5)
                     );
                 when "001" =>
                     -- 減算
                     result_internal <= std_logic_vector(
-                        unsigned('0' and op_a) - unsigned('0' and op_b)
+                        resize(unsigned(op_a), 5) - resize(unsigned(op_b), 5)
                     );
                 when "010" =>
                     -- AND
-                    result_internal <= '0' and (op_a and op_b);
+                    result_internal <= '0' & (op_a and op_b);
                 when "011" =>
                     -- OR
-                    result_internal <= '0' and (op_a or op_b);
+                    result_internal <= '0' & (op_a or op_b);
                 when "100" =>
                     -- XOR
-                    result_internal <= '0' and (op_a xor op_b);
+                    result_internal <= '0' & (op_a xor op_b);
                 when "101" =>
                     -- NOT A
-                    result_internal <= '0' and (not op_a);
+                    result_internal <= '0' & (not op_a);
                 when others =>
                     result_internal <= (others => '0');
             end case;
@@ -0,0 +1,73 @@
+-- 信号機の制御（ステートマシンの例）
+library ieee;
+use ieee.std_logic_1164.all;
+
+package traffic_pkg is
+    type light_state is (IDLE, GREEN, YELLOW, RED);
+end package traffic_pkg;
+
+library ieee;
+use ieee.std_logic_1164.all;
+use work.traffic_pkg.all;
+
+entity traffic_light is
+    port (
+        clk    : in  std_logic;
+        reset  : in  std_logic;
+        enable : in  std_logic;
+        car    : in  std_logic;
+        green  : out std_logic;
+        yellow : out std_logic;
+        red    : out std_logic
+    );
+end entity traffic_light;
+
+architecture rtl of traffic_light is
+    signal state      : light_state := IDLE;
+    signal next_state : light_state := IDLE;
+    signal timer : integer := 0;
+begin
+    -- 状態レジスタ
+    process(clk, reset)
+    begin
+        if reset = '1' then
+            state <= IDLE;
+            timer <= 0;
+        elsif rising_edge(clk) then
+            state <= next_state;
+            if state /= next_state then
+                timer <= 0;
+            else
+                timer <= timer + 1;
+            end if;
+        end if;
+    end process;
+
+    -- 次状態の決定
+    process(state, enable, car, timer)
+    begin
+        next_state <= state;
+        case state is
+            when IDLE =>
+                if enable = '1' then
+                    next_state <= GREEN;
+                end if;
+            when GREEN =>
+                if enable = '0' then
+                    next_state <= IDLE;
+                elsif car = '1' and timer > 3 then
+                    next_state <= YELLOW;
+                end if;
+            when YELLOW =>
+                next_state <= RED;
+            when others =>
+                if timer = 5 then
+                    next_state <= GREEN;
+                end if;
+        end case;
+    end process;
+
+    green  <= '1' when state = GREEN else '0';
+    yellow <= '1' when state = YELLOW else '0';
+    red    <= '1' when state = RED else '0';
+end architecture rtl;
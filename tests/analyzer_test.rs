@@ -1,5 +1,10 @@
 use std::fs;
-use vig::analyzer::{AnalyzeResult, PortDirection, VhdlType, analyze_vhdl};
+use vig::analyzer::{
+    AnalyzeResult, InstantiatedUnit, LoopScheme, PortDirection, Statement, SubprogramKind, TypeDef,
+    VhdlType, analyze_vhdl,
+};
+use vig::expr::{BinaryOp, Expr};
+use vig::lexer::LitKind;
 
 fn analyze_file(path: &str) -> AnalyzeResult {
     let source = fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path));
@@ -39,7 +44,11 @@ fn test_counter_entity_port_details() {
     assert_eq!(ports[2].direction, PortDirection::Out);
     assert_eq!(
         ports[2].vhdl_type,
-        VhdlType::StdLogicVector { high: 7, low: 0 }
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
     );
 }
 
@@ -58,8 +67,8 @@ fn test_counter_architecture_signal() {
 
     assert_eq!(signals.len(), 1);
     assert_eq!(signals[0].name, "counter_value");
-    assert_eq!(signals[0].vhdl_type, VhdlType::Integer);
-    assert_eq!(signals[0].default_value, Some("0".to_string()));
+    assert_eq!(signals[0].vhdl_type, VhdlType::Integer(None));
+    assert_eq!(signals[0].default_value, Some(0.into()));
 }
 
 #[test]
@@ -92,6 +101,27 @@ fn test_signal_without_default() {
     assert_eq!(sig.default_value, None);
 }
 
+#[test]
+fn test_signal_name_list() {
+    let source = r#"
+        architecture rtl of foo is
+            signal a, b : std_logic := '0';
+        begin
+        end architecture rtl;
+    "#;
+    let result = analyze_vhdl(source).unwrap();
+    let names: Vec<_> = result.architectures[0]
+        .signals
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(names, ["a", "b"]);
+    assert_eq!(
+        result.architectures[0].signals[1].vhdl_type,
+        VhdlType::StdLogic
+    );
+}
+
 #[test]
 fn test_multiple_entities() {
     let source = r#"
@@ -110,7 +140,1054 @@ fn test_multiple_entities() {
 
 #[test]
 fn test_case_preservation() {
-    let source = "ENTITY MyEnt IS\nEND ENTITY MyEnt;";
+    let source = "ENTITY MyEnt IS\n    PORT (Clk_In : IN std_logic);\nEND ENTITY MyEnt;\n\
+                  ARCHITECTURE Rtl OF MYENT IS\n    SIGNAL \\Cnt\\ : integer;\nBEGIN\nEND Rtl;";
     let result = analyze_vhdl(source).unwrap();
-    assert_eq!(result.entities[0].name, "MyEnt");
+    // 書いたままの表記と、比較用の正規形
+    let entity = &result.entities[0];
+    assert_eq!(entity.name, "MyEnt");
+    assert_eq!(entity.name_normalized(), "myent");
+    assert_eq!(entity.ports[0].name, "Clk_In");
+    assert_eq!(entity.ports[0].name_normalized(), "clk_in");
+    let arch = &result.architectures[0];
+    assert_eq!(arch.name_normalized(), "rtl");
+    assert!(arch.is_of(&entity.name));
+    // 拡張識別子は大文字・小文字を区別する
+    assert_eq!(arch.signals[0].name_normalized(), "\\Cnt\\");
+}
+
+#[test]
+fn test_result_to_json() {
+    let result = analyze_file("testdata/counter.vhd");
+    let json = result.to_json();
+    assert!(json.contains("\"entities\": ["));
+    assert!(json.contains("\"vig_interface\": 1"));
+    assert!(json.contains("\"entity\": \"counter\""));
+    assert!(json.contains("\"name\": \"counter_value\""));
+    assert!(json.contains("\"default\": \"0\""));
+}
+
+#[test]
+fn test_type_display_and_from_str() {
+    use vig::analyzer::parse_type_str;
+
+    for text in [
+        "std_logic",
+        "std_logic_vector(7 downto 0)",
+        "integer",
+        "boolean",
+        "my_record",
+        "unsigned(3 downto 0)",
+        "std_logic_vector(0 to 7)",
+        "signed(WIDTH - 1 to 2 * WIDTH)",
+    ] {
+        let vhdl_type: VhdlType = text.parse().unwrap();
+        assert_eq!(vhdl_type.to_string(), text);
+    }
+    assert_eq!(
+        parse_type_str("  STD_LOGIC_VECTOR ( 15 downto 0 ) "),
+        Ok(VhdlType::StdLogicVector {
+            high: 15.into(),
+            low: 0.into(),
+            ascending: false
+        })
+    );
+    // `to` の範囲も上・下の境界で持ち、向きを表記に戻す
+    let ascending = parse_type_str("bit_vector(0 to 7)").unwrap();
+    assert_eq!(
+        ascending,
+        VhdlType::BitVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: true
+        }
+    );
+    assert_eq!(ascending.bit_width(), Some(8));
+    assert_eq!(ascending.vector_bounds(), Some((&0.into(), &7.into())));
+    assert_eq!(
+        parse_type_str("std_logic_vector(7 0)").unwrap_err().message,
+        "expected 'to' or 'downto', found '0'"
+    );
+    assert_eq!(
+        parse_type_str("word_array(7 downto 0)"),
+        Ok(VhdlType::Other("word_array(7 downto 0)".to_string()))
+    );
+
+    assert_eq!(parse_type_str("").unwrap_err().message, "empty type");
+    let err = parse_type_str("std_logic foo").unwrap_err();
+    assert_eq!(err.message, "unexpected 'foo' after type");
+    assert_eq!(err.span.start, 10);
+    assert!(parse_type_str("std_logic_vector(7 downto)").is_err());
+    assert!(parse_type_str("unsigned(7 downto 0").is_err());
+}
+
+#[test]
+fn test_direction_display_and_from_str() {
+    for direction in [
+        PortDirection::In,
+        PortDirection::Out,
+        PortDirection::Inout,
+        PortDirection::Buffer,
+    ] {
+        assert_eq!(
+            direction.to_string().parse::<PortDirection>(),
+            Ok(direction)
+        );
+    }
+    assert_eq!("OUT".parse::<PortDirection>(), Ok(PortDirection::Out));
+    let err = "sideways".parse::<PortDirection>().unwrap_err();
+    assert_eq!(err.message, "invalid port direction 'sideways'");
+}
+
+#[test]
+fn test_entity_with_generic_clause() {
+    let source = "\
+entity fifo is
+    generic (
+        DEPTH : natural := 4;
+        MODE  : string := \"fast\"
+    );
+    port (
+        clk : in std_logic;
+        dout : out std_logic_vector(7 downto 0)
+    );
+end entity fifo;
+";
+    let result = analyze_vhdl(source).expect("analysis failed");
+    assert_eq!(result.entities[0].name, "fifo");
+    assert_eq!(result.entities[0].ports.len(), 2);
+
+    let generics = &result.entities[0].generics;
+    assert_eq!(generics.len(), 2);
+    assert_eq!(generics[0].name, "DEPTH");
+    assert_eq!(generics[0].vhdl_type, VhdlType::Natural(None));
+    assert_eq!(generics[0].default_value, Some(4.into()));
+    assert_eq!(
+        generics[1].default_value,
+        Some(Expr::Literal(LitKind::String, "\"fast\"".to_string()))
+    );
+}
+
+#[test]
+fn test_generic_declarations() {
+    let source = "\
+entity core is
+    generic (
+        constant WIDTH, DEPTH : in integer range 1 to 64 := 8;
+        INIT : std_logic_vector(3 downto 0) := (others => '0');
+        type element_t;
+        function to_str(x : element_t) return string;
+        ENABLE : boolean
+    );
+    port (clk : in std_logic);
+end entity;
+";
+    let result = analyze_vhdl(source).expect("analysis failed");
+    let entity = &result.entities[0];
+    let summary: Vec<(&str, &VhdlType, Option<String>)> = entity
+        .generics
+        .iter()
+        .map(|g| {
+            let default = g.default_value.as_ref().map(|v| v.to_string());
+            (g.name.as_str(), &g.vhdl_type, default)
+        })
+        .collect();
+    let range: VhdlType = "integer range 1 to 64".parse().unwrap();
+    assert_eq!(
+        summary,
+        [
+            ("WIDTH", &range, Some("8".into())),
+            ("DEPTH", &range, Some("8".into())),
+            (
+                "INIT",
+                &VhdlType::StdLogicVector {
+                    high: 3.into(),
+                    low: 0.into(),
+                    ascending: false
+                },
+                Some("(others => '0')".into())
+            ),
+            ("ENABLE", &VhdlType::Boolean, None),
+        ]
+    );
+    assert_eq!(entity.ports.len(), 1);
+    assert_eq!(&source[entity.generics[0].span.start..][..5], "WIDTH");
+
+    // 型のない要素はエラー
+    let err = analyze_vhdl("entity e is generic (N := 1); end;").unwrap_err();
+    assert!(err.message.contains("expected Colon"), "{}", err.message);
+}
+
+#[test]
+fn test_symbolic_bounds() {
+    use vig::analyzer::{BoundExpr, BoundOp, parse_type_str};
+
+    let ty = parse_type_str("std_logic_vector(WIDTH-1 downto 0)").unwrap();
+    assert_eq!(
+        ty,
+        VhdlType::StdLogicVector {
+            high: BoundExpr::Binary(
+                Box::new(BoundExpr::Name("WIDTH".to_string())),
+                BoundOp::Sub,
+                Box::new(1.into()),
+            ),
+            low: 0.into(),
+            ascending: false,
+        }
+    );
+    assert!(ty.is_symbolic());
+    assert_eq!(ty.bit_width(), None);
+    assert_eq!(ty.to_string(), "std_logic_vector(WIDTH - 1 downto 0)");
+    let resolved = ty.resolve_bounds(&|name| (name == "WIDTH").then_some(16));
+    assert_eq!(resolved.to_string(), "std_logic_vector(15 downto 0)");
+    assert_eq!(resolved.bit_width(), Some(16));
+
+    // 優先順位と括弧は評価と表記で保たれる
+    for (text, value) in [
+        ("2 ** N - 1", 15),
+        ("(N + 1) * 2", 10),
+        ("N - (2 - 1)", 3),
+        ("N * 4 / 2", 8),
+        ("-1 + N", 3),
+    ] {
+        let bound: BoundExpr = text.parse().unwrap();
+        assert_eq!(bound.evaluate(&|_| Some(4)), Some(value), "{}", text);
+        assert_eq!(bound.to_string(), text);
+    }
+    let bound: BoundExpr = "2**(N)".parse().unwrap();
+    assert_eq!(bound.to_string(), "2 ** N");
+    assert_eq!("8 / 0".parse::<BoundExpr>().unwrap().value(), None);
+
+    let err = parse_type_str("std_logic_vector(d'length - 1 downto 0)").unwrap_err();
+    assert_eq!(err.message, "expected range bound, found 'd'length'");
+    let err = "N +".parse::<BoundExpr>().unwrap_err();
+    assert_eq!(err.message, "expected expression, found ''");
+}
+
+#[test]
+fn test_generic_values() {
+    let source = "\
+entity e is
+    generic (ADDR : natural := 4; DEPTH : natural := 2 ** ADDR; NAME : string := \"x\"; N : natural);
+    port (d : in std_logic_vector(DEPTH - 1 downto 0); q : out std_logic_vector(N downto 0));
+end entity;";
+    let entity = analyze_vhdl(source).unwrap().entities.remove(0);
+    assert_eq!(entity.generic_value("depth"), Some(16));
+    assert_eq!(entity.generic_value("NAME"), None);
+    assert_eq!(entity.generic_value("N"), None);
+    assert_eq!(entity.generic_value("missing"), None);
+
+    let resolved = entity.with_generic_bounds();
+    assert_eq!(resolved.ports[0].vhdl_type.bit_width(), Some(16));
+    assert_eq!(resolved.ports[1].vhdl_type, entity.ports[1].vhdl_type);
+}
+
+#[test]
+fn test_builtin_types() {
+    use vig::analyzer::parse_type_str;
+
+    let source = "\
+entity e is
+    port (
+        a : in  signed(7 downto 0);
+        b : in  UNSIGNED(WIDTH - 1 downto 0);
+        c : in  natural;
+        d : in  positive;
+        e : in  real;
+        f : in  time;
+        g : in  bit;
+        h : in  bit_vector(3 downto 0);
+        i : in  string(1 to 8);
+        j : out my_type
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let types: Vec<_> = result.entities[0]
+        .ports
+        .iter()
+        .map(|p| p.vhdl_type.clone())
+        .collect();
+    assert_eq!(
+        types[0],
+        VhdlType::Signed {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    assert!(matches!(types[1], VhdlType::Unsigned { .. }));
+    assert_eq!(
+        types[2..7],
+        [
+            VhdlType::Natural(None),
+            VhdlType::Positive(None),
+            VhdlType::Real,
+            VhdlType::Time,
+            VhdlType::Bit
+        ]
+    );
+    assert_eq!(types[7].bit_width(), Some(4));
+    assert_eq!(types[8], VhdlType::Str(Some((1.into(), 8.into()))));
+    assert_eq!(types[9], VhdlType::Other("my_type".to_string()));
+
+    // 表記は小文字の型名に正規化する
+    let texts: Vec<_> = types.iter().map(|t| t.to_string()).collect();
+    assert_eq!(texts[1], "unsigned(WIDTH - 1 downto 0)");
+    assert_eq!(texts[8], "string(1 to 8)");
+    for text in &texts {
+        assert_eq!(parse_type_str(text).unwrap().to_string(), *text);
+    }
+    assert_eq!(parse_type_str("string"), Ok(VhdlType::Str(None)));
+    assert_eq!(VhdlType::Bit.bit_width(), Some(1));
+    assert_eq!(VhdlType::Natural(None).bit_width(), None);
+}
+
+#[test]
+fn test_scalar_range_constraints() {
+    use vig::analyzer::{BoundExpr, RangeConstraint, parse_type_str};
+
+    let source = "\
+entity e is
+    generic (DEPTH : positive := 16);
+    port (
+        n : in  natural range 0 to 15;
+        i : in  integer range -5 to 5;
+        c : out INTEGER RANGE DEPTH - 1 DOWNTO 0;
+        p : in  positive
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let ports = &result.entities[0].ports;
+    assert_eq!(
+        ports[0].vhdl_type,
+        VhdlType::Natural(Some(RangeConstraint {
+            left: 0.into(),
+            right: 15.into(),
+            ascending: true
+        }))
+    );
+    assert_eq!(
+        ports[1].vhdl_type.scalar_range().unwrap().left,
+        BoundExpr::Literal(-5)
+    );
+    assert!(ports[2].vhdl_type.is_symbolic());
+    assert_eq!(ports[3].vhdl_type, VhdlType::Positive(None));
+
+    // 表記は `range 左 to/downto 右` に戻す
+    let texts: Vec<_> = ports.iter().map(|p| p.vhdl_type.to_string()).collect();
+    assert_eq!(
+        texts,
+        [
+            "natural range 0 to 15",
+            "integer range -5 to 5",
+            "integer range DEPTH - 1 downto 0",
+            "positive"
+        ]
+    );
+    for text in &texts {
+        assert_eq!(parse_type_str(text).unwrap().to_string(), *text);
+    }
+    let resolved = ports[2]
+        .vhdl_type
+        .resolve_bounds(&|name| (name == "DEPTH").then_some(16));
+    assert_eq!(resolved.to_string(), "integer range 15 downto 0");
+
+    assert_eq!(
+        parse_type_str("natural range 0 15").unwrap_err().message,
+        "expected 'to' or 'downto', found '15'"
+    );
+}
+
+#[test]
+fn test_architecture_constants() {
+    let source = "\
+entity e is port (clk : in std_logic); end entity;
+architecture rtl of e is
+    constant CLK_PERIOD : time := 10 ns;
+    constant LO, HI : integer range 0 to 15 := 3;
+    constant INIT : word_array(0 to 1) := (x\"00\", x\"FF\");
+    signal count : unsigned(3 downto 0);
+begin
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let arch = &result.architectures[0];
+    assert_eq!(arch.signals.len(), 1);
+
+    let constants = &arch.constants;
+    assert_eq!(constants.len(), 4);
+    assert_eq!(constants[0].name, "CLK_PERIOD");
+    assert_eq!(constants[0].vhdl_type, VhdlType::Time);
+    assert_eq!(
+        constants[0].value,
+        Some(Expr::Physical("10".to_string(), "ns".to_string()))
+    );
+    assert_eq!(constants[1].name, "LO");
+    assert_eq!(constants[2].name, "HI");
+    assert_eq!(constants[2].vhdl_type.to_string(), "integer range 0 to 15");
+    assert_eq!(constants[2].value, Some(3.into()));
+    assert_eq!(
+        constants[3].vhdl_type,
+        VhdlType::Other("word_array".to_string())
+    );
+    let Some(Expr::Aggregate(elements)) = &constants[3].value else {
+        panic!("expected an aggregate, got {:?}", constants[3].value);
+    };
+    assert_eq!(elements.len(), 2);
+    assert_eq!(
+        constants[3].value.as_ref().unwrap().to_string(),
+        "(x\"00\", x\"FF\")"
+    );
+    assert_eq!(
+        &source[constants[0].span.start..constants[0].span.end],
+        "constant CLK_PERIOD : time := 10 ns;"
+    );
+
+    let json = result.to_json();
+    assert!(json.contains("\"constants\": ["));
+    assert!(json.contains("\"value\": \"10 ns\""));
+    assert!(
+        result
+            .to_string()
+            .contains("  Constant: CLK_PERIOD : Time := 10 ns\n")
+    );
+
+    let err = analyze_vhdl(&source.replace("LO, HI", "LO HI")).unwrap_err();
+    assert_eq!(err.message, "expected Colon, found Identifier 'HI'");
+}
+
+#[test]
+fn test_component_instances() {
+    let source = "\
+entity top is port (clk : in std_logic; q : out std_logic_vector(7 downto 0)); end entity;
+architecture rtl of top is
+    signal sum : std_logic_vector(7 downto 0);
+begin
+    u_add : component adder
+        generic map (WIDTH => 8, 2)
+        port map (clk => clk, a => sum(3 downto 0), b => open, y => sum);
+    u_reg : reg port map (clk, sum, q);
+    q_en : q <= sum when clk = '1' else (others => '0');
+    proc : process (clk) begin end process;
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let instances = &result.architectures[0].instances;
+    assert_eq!(instances.len(), 2);
+
+    let add = &instances[0];
+    assert_eq!(add.label, "u_add");
+    assert_eq!(add.unit, InstantiatedUnit::Component("adder".to_string()));
+    assert_eq!(add.generic_map.len(), 2);
+    assert_eq!(add.generic_map[0].formal.as_deref(), Some("WIDTH"));
+    assert_eq!(add.generic_map[0].actual, "8");
+    assert_eq!(add.generic_map[1].formal, None);
+    assert_eq!(add.port_map.len(), 4);
+    assert_eq!(add.port_actual("A"), Some("sum ( 3 downto 0 )"));
+    assert_eq!(add.port_actual("b"), Some("open"));
+    assert_eq!(add.port_actual("c"), None);
+    assert!(source[add.span.start..add.span.end].starts_with("u_add : component adder"));
+    assert!(source[add.span.start..add.span.end].ends_with("y => sum);"));
+
+    let reg = &instances[1];
+    assert_eq!(reg.unit.name(), "reg");
+    assert!(reg.generic_map.is_empty());
+    let actuals: Vec<_> = reg.port_map.iter().map(|a| a.actual.as_str()).collect();
+    assert_eq!(actuals, ["clk", "sum", "q"]);
+
+    let json = result.to_json();
+    assert!(json.contains("\"instances\": ["));
+    assert!(json.contains("\"kind\": \"component\""));
+    assert!(result.to_string().contains(
+        "  Instance: u_add : component adder\n    Generic map: WIDTH => 8\n    Generic map: 2\n"
+    ));
+
+    let err = analyze_vhdl(&source.replace("b => open,", "b => ,")).unwrap_err();
+    assert_eq!(err.message, "expected association, found ','");
+
+    // 部分的な関連付けの仮引数は先頭の名前で引ける
+    let partial = source.replace("a => sum(3 downto 0)", "a(3 downto 0) => sum(3 downto 0)");
+    let result = analyze_vhdl(&partial).unwrap();
+    let assoc = &result.architectures[0].instances[0].port_map[1];
+    assert_eq!(assoc.formal.as_deref(), Some("a ( 3 downto 0 )"));
+    assert_eq!(assoc.formal_name(), Some("a"));
+    assert_eq!(
+        result.architectures[0].instances[1].port_map[0].formal_name(),
+        None
+    );
+}
+
+#[test]
+fn test_entity_instances() {
+    let source = "\
+entity top is port (clk : in std_logic); end entity;
+architecture rtl of top is
+begin
+    u0 : entity work.counter(rtl) generic map (WIDTH => 4) port map (clk => clk);
+    u1 : entity counter port map (clk => clk);
+    u2 : entity work.blinky;
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let instances = &result.architectures[0].instances;
+    assert_eq!(instances.len(), 3);
+    assert_eq!(
+        instances[0].unit,
+        InstantiatedUnit::Entity {
+            library: Some("work".to_string()),
+            name: "counter".to_string(),
+            architecture: Some("rtl".to_string()),
+        }
+    );
+    assert_eq!(instances[0].unit.to_string(), "entity work.counter(rtl)");
+    assert_eq!(instances[0].generic_map[0].actual, "4");
+    assert_eq!(instances[1].unit.to_string(), "entity counter");
+    assert_eq!(instances[1].unit.name(), "counter");
+    assert!(instances[2].port_map.is_empty());
+    assert_eq!(
+        &source[instances[2].span.start..instances[2].span.end],
+        "u2 : entity work.blinky;"
+    );
+
+    let json = result.to_json();
+    assert!(json.contains("\"kind\": \"entity\""));
+    assert!(json.contains("\"library\": \"work\""));
+    assert!(json.contains("\"architecture\": \"rtl\""));
+
+    let err = analyze_vhdl(&source.replace("(rtl)", "(rtl")).unwrap_err();
+    assert_eq!(err.message, "expected RightParen, found Generic 'generic'");
+}
+
+#[test]
+fn test_process_statements() {
+    let source = "\
+entity e is port (clk, rst : in std_logic; q : out std_logic_vector(3 downto 0)); end entity;
+architecture rtl of e is
+    signal state : state_t;
+begin
+    q <= (others => '0') when rst = '1' else x\"F\";
+    seq : process (clk, rst)
+        variable count, limit : integer range 0 to 15 := 0;
+        type scratch_t is array (0 to 3) of bit;
+    begin
+        if rst = '1' then
+            count := 0;
+        elsif rising_edge(clk) then
+            case state is
+                when IDLE | WAIT_ST =>
+                    state <= RUN after 1 ns;
+                when others =>
+                    null;
+            end case;
+        else
+            q(count) <= '1';
+        end if;
+    end process seq;
+
+    process
+    begin
+        fill : for i in 0 to 3 loop
+            exit fill when i = limit;
+        end loop fill;
+        while count <= 3 loop
+            wait until clk = '1' for 10 ns;
+        end loop;
+        report \"done\";
+        wait on clk, rst;
+    end process;
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let processes = &result.architectures[0].processes;
+    assert_eq!(processes.len(), 2);
+
+    let seq = &processes[0];
+    assert_eq!(seq.label.as_deref(), Some("seq"));
+    assert_eq!(seq.sensitivity, ["clk", "rst"]);
+    let variables: Vec<_> = seq.variables.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(variables, ["count", "limit"]);
+    assert_eq!(seq.types[0].name(), "scratch_t");
+    assert_eq!(
+        seq.variables[1].vhdl_type.to_string(),
+        "integer range 0 to 15"
+    );
+    assert_eq!(seq.variables[1].default_value, Some(0.into()));
+    assert_eq!(seq.body.len(), 1);
+    let Statement::If {
+        branches,
+        else_body,
+        span,
+    } = &seq.body[0]
+    else {
+        panic!("expected if, got {:?}", seq.body[0]);
+    };
+    assert!(source[span.start..span.end].ends_with("end if;"));
+    assert_eq!(branches.len(), 2);
+    assert_eq!(
+        branches[0].0,
+        Expr::Binary(
+            Box::new(Expr::name("rst")),
+            BinaryOp::Eq,
+            Box::new(Expr::Literal(LitKind::Character, "'1'".to_string()))
+        )
+    );
+    assert!(matches!(
+        &branches[0].1[0],
+        Statement::VariableAssign { target, value, .. }
+            if *target == Expr::name("count") && *value == 0.into()
+    ));
+    assert_eq!(branches[1].0.to_string(), "rising_edge(clk)");
+    let Statement::Case { selector, arms, .. } = &branches[1].1[0] else {
+        panic!("expected case");
+    };
+    assert_eq!(*selector, Expr::name("state"));
+    assert_eq!(arms[0].0, [Expr::name("IDLE"), Expr::name("WAIT_ST")]);
+    assert!(matches!(
+        &arms[0].1[0],
+        Statement::SignalAssign { value, after: Some(after), .. }
+            if *value == Expr::name("RUN") && after.to_string() == "1 ns"
+    ));
+    assert_eq!(arms[1].0, [Expr::Others]);
+    assert!(matches!(arms[1].1[0], Statement::Null { .. }));
+    assert!(matches!(
+        &else_body[0],
+        Statement::SignalAssign { target, after: None, .. } if target.to_string() == "q(count)"
+    ));
+
+    let body = &processes[1].body;
+    assert_eq!(processes[1].label, None);
+    assert!(processes[1].sensitivity.is_empty());
+    let Statement::Loop {
+        label,
+        scheme,
+        body: fill,
+        ..
+    } = &body[0]
+    else {
+        panic!("expected loop");
+    };
+    assert_eq!(label.as_deref(), Some("fill"));
+    assert_eq!(
+        scheme,
+        &LoopScheme::For {
+            parameter: "i".to_string(),
+            range: "0 to 3".parse().unwrap()
+        }
+    );
+    assert!(
+        matches!(&fill[0], Statement::Other { text, .. } if text == "exit fill when i = limit")
+    );
+    let Statement::Loop {
+        scheme,
+        body: inner,
+        ..
+    } = &body[1]
+    else {
+        panic!("expected loop");
+    };
+    assert_eq!(scheme, &LoopScheme::While("count <= 3".parse().unwrap()));
+    assert_eq!(
+        inner[0],
+        Statement::Wait {
+            on: Vec::new(),
+            until: Some("clk = '1'".parse().unwrap()),
+            timeout: Some("10 ns".parse().unwrap()),
+            span: inner[0].span(),
+        }
+    );
+    assert!(matches!(&body[2], Statement::Other { text, .. } if text == "report \"done\""));
+    assert!(matches!(
+        &body[3],
+        Statement::Wait { on, until: None, .. } if *on == [Expr::name("clk"), Expr::name("rst")]
+    ));
+
+    let json = result.to_json();
+    assert!(json.contains("\"processes\": ["));
+    assert!(json.contains("\"kind\": \"case\""));
+    assert!(result.to_string().contains("  Process: seq (clk, rst)\n"));
+
+    let err = analyze_vhdl(&source.replace("end case;", "end if;")).unwrap_err();
+    assert_eq!(err.message, "expected Case, found If 'if'");
+}
+
+#[test]
+fn test_package_declarations() {
+    let source = "\
+library ieee;
+use ieee.std_logic_1164.all;
+package my_pkg is
+    generic (type element_t);
+    constant WIDTH : natural := 8;
+    constant SEED : integer;
+    subtype byte_t is std_logic_vector(WIDTH - 1 downto 0);
+    subtype small_t is integer range 0 to 15;
+    subtype flag_t is resolved std_ulogic;
+    type state_t is (IDLE, RUN);
+    type pair_t is record
+        a : std_logic;
+        b : byte_t;
+    end record pair_t;
+    type cell_t;
+    component foo port (x : in std_logic); end component;
+    attribute keep : boolean;
+    function parity(v : std_logic_vector) return std_logic;
+    pure function \"+\"(a, b : pair_t) return pair_t;
+    procedure reset(signal s : out byte_t);
+end package my_pkg;
+
+package body my_pkg is
+    constant SEED : integer := 1;
+end package body;
+
+package int_pkg is new work.gen_pkg generic map (N => 4);
+package empty_pkg is end;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let names: Vec<_> = result.packages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["my_pkg", "empty_pkg"]);
+
+    let pkg = &result.packages[0];
+    let constants: Vec<_> = pkg.constants.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(constants, ["WIDTH", "SEED"]);
+    assert_eq!(pkg.constants[0].value, Some(8.into()));
+    assert_eq!(pkg.constants[1].value, None);
+
+    let types: Vec<_> = pkg.types.iter().map(|t| t.name()).collect();
+    assert_eq!(
+        types,
+        ["byte_t", "small_t", "flag_t", "state_t", "pair_t", "cell_t"]
+    );
+    assert!(matches!(
+        pkg.find_type("BYTE_T"),
+        Some(TypeDef::Subtype {
+            vhdl_type: VhdlType::StdLogicVector { .. },
+            ..
+        })
+    ));
+    assert!(matches!(
+        &pkg.types[1],
+        TypeDef::Subtype {
+            vhdl_type: VhdlType::Integer(Some(_)),
+            ..
+        }
+    ));
+    assert!(matches!(
+        &pkg.types[2],
+        TypeDef::Other { definition, .. } if definition == "resolved std_ulogic"
+    ));
+    assert!(matches!(
+        &pkg.types[4],
+        TypeDef::Other { definition, .. }
+            if definition.starts_with("record") && definition.ends_with("end record pair_t")
+    ));
+    assert!(matches!(&pkg.types[5], TypeDef::Other { definition, .. } if definition.is_empty()));
+
+    let subprograms: Vec<_> = pkg
+        .subprograms
+        .iter()
+        .map(|d| (d.kind, d.name.as_str(), d.signature.as_str()))
+        .collect();
+    assert_eq!(
+        subprograms,
+        [
+            (
+                SubprogramKind::Function,
+                "parity",
+                "( v : std_logic_vector ) return std_logic"
+            ),
+            (
+                SubprogramKind::Function,
+                "\"+\"",
+                "( a , b : pair_t ) return pair_t"
+            ),
+            (
+                SubprogramKind::Procedure,
+                "reset",
+                "( signal s : out byte_t )"
+            ),
+        ]
+    );
+    let decl = &pkg.subprograms[2];
+    assert_eq!(
+        &source[decl.span.start..decl.span.end],
+        "procedure reset(signal s : out byte_t);"
+    );
+    assert!(source[pkg.span.start..pkg.span.end].ends_with("end package my_pkg;"));
+
+    let json = result.to_json();
+    assert!(json.contains("\"packages\": ["));
+    assert!(json.contains("\"kind\": \"subtype\""));
+    let text = result.to_string();
+    assert!(text.contains("Package: my_pkg\n"));
+    assert!(text.contains("  Subprogram: procedure reset ( signal s : out byte_t )\n"));
+
+    let err = analyze_vhdl("package p is function return bit; end package;").unwrap_err();
+    assert_eq!(err.message, "expected subprogram name, found 'return'");
+}
+
+#[test]
+fn test_package_bodies() {
+    let source = "\
+package util is
+    constant SEED : integer;
+    function parity(v : std_logic_vector) return std_logic;
+    procedure clear(signal s : out std_logic_vector);
+end package;
+
+package body util is
+    constant SEED : integer := 42;
+
+    function parity(v : std_logic_vector) return std_logic is
+        variable p : std_logic := '0';
+        function flip(b : std_logic) return std_logic is
+        begin
+            return not b;
+        end function;
+    begin
+        for i in v'range loop
+            if v(i) = '1' then
+                p := flip(p);
+            end if;
+        end loop;
+        return p;
+    end function parity;
+
+    procedure clear(signal s : out std_logic_vector) is
+    begin
+        s <= (s'range => '0');
+    end clear;
+
+    type table_t is array (0 to 3) of integer;
+    function \"=\"(a, b : table_t) return boolean;
+end package body util;
+";
+    let result = analyze_vhdl(source).unwrap();
+    assert_eq!(result.packages.len(), 1);
+    assert_eq!(result.package_bodies.len(), 1);
+
+    let body = &result.package_bodies[0];
+    assert_eq!(body.name, "util");
+    assert_eq!(body.constants[0].value, Some(42.into()));
+    let names: Vec<_> = body.subprograms.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["parity", "clear", "\"=\""]);
+    assert_eq!(body.types[0].name(), "table_t");
+
+    let parity = &body.subprograms[0];
+    assert_eq!(
+        parity.signature,
+        "( v : std_logic_vector ) return std_logic"
+    );
+    assert_eq!(parity.variables[0].name, "p");
+    assert_eq!(parity.subprograms[0].name, "flip");
+    assert!(!parity.impure);
+    let statements = parity.body.as_ref().unwrap();
+    assert!(matches!(statements[0], Statement::Loop { .. }));
+    assert!(matches!(&statements[1], Statement::Other { text, .. } if text == "return p"));
+    assert!(source[parity.span.start..parity.span.end].ends_with("end function parity;"));
+    assert!(matches!(
+        &body.subprograms[1].body.as_deref().unwrap()[0],
+        Statement::SignalAssign { .. }
+    ));
+    assert_eq!(body.subprograms[2].body, None);
+    assert_eq!(result.packages[0].subprograms[0].body, None);
+    assert!(source[body.span.start..body.span.end].ends_with("end package body util;"));
+
+    let json = result.to_json();
+    assert!(json.contains("\"package_bodies\": ["));
+    assert!(result.to_string().contains("Package body: util\n"));
+
+    let err = analyze_vhdl(&source.replace("    begin\n        for", "        for")).unwrap_err();
+    assert_eq!(err.message, "expected Begin, found For 'for'");
+}
+
+#[test]
+fn test_function_declarations() {
+    let source = "\
+package util is
+    function parity(v : std_logic_vector) return std_logic;
+    pure function clamp(constant x : integer; lo, hi : integer := 0) return integer;
+    procedure clear(signal s : out std_logic_vector);
+end package;
+
+entity e is
+    port (a : in std_logic_vector(7 downto 0); y : out std_logic);
+end entity;
+
+architecture rtl of e is
+    function parity(v : std_logic_vector) return std_logic is
+        variable p : std_logic := '0';
+    begin
+        for i in v'range loop
+            p := p xor v(i);
+        end loop;
+        return p;
+    end function parity;
+    procedure noop is
+    begin
+        null;
+    end procedure;
+    signal t : std_logic;
+begin
+    y <= parity(a);
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let package = &result.packages[0];
+    assert_eq!(package.subprograms.len(), 3);
+    let clamp = package.find_function("CLAMP").unwrap();
+    let params: Vec<_> = clamp.params.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(params, ["x", "lo", "hi"]);
+    assert_eq!(clamp.params[1].direction, PortDirection::In);
+    assert_eq!(clamp.params[2].default_value, Some(0.into()));
+    assert_eq!(clamp.return_type, Some(VhdlType::Integer(None)));
+    assert_eq!(
+        clamp.type_signature(),
+        "(integer, integer, integer) return integer"
+    );
+    assert_eq!(clamp.body, None);
+    let clear = &package.subprograms[2];
+    assert_eq!(clear.params[0].name, "s");
+    assert_eq!(clear.params[0].direction, PortDirection::Out);
+    assert_eq!(clear.return_type, None);
+    assert_eq!(clear.type_signature(), "(std_logic_vector)");
+    assert!(package.find_function("clear").is_none());
+
+    let arch = &result.architectures[0];
+    assert_eq!(arch.signals.len(), 1);
+    assert_eq!(arch.subprograms.len(), 2);
+    let parity = &arch.subprograms[0];
+    assert_eq!(parity.name, "parity");
+    assert_eq!(parity.params[0].name, "v");
+    assert_eq!(parity.return_type, Some(VhdlType::StdLogic));
+    assert!(matches!(
+        parity.body.as_deref(),
+        Some([Statement::Loop { .. }, Statement::Other { .. }])
+    ));
+    assert!(source[parity.span.start..parity.span.end].ends_with("end function parity;"));
+    assert_eq!(arch.subprograms[1].kind, SubprogramKind::Procedure);
+    assert_eq!(arch.subprograms[1].type_signature(), "()");
+
+    let json = result.to_json();
+    assert!(json.contains("\"return_type\": {"));
+    assert!(json.contains("\"return_type\": null"));
+    assert!(
+        result
+            .to_string()
+            .contains("  Subprogram: function parity ( v : std_logic_vector ) return std_logic\n")
+    );
+
+    let err = analyze_vhdl(&source.replace("return integer;", "integer;")).unwrap_err();
+    assert_eq!(err.message, "expected Return, found Integer 'integer'");
+}
+
+#[test]
+fn test_enumeration_types() {
+    let result = analyze_file("testdata/traffic_light.vhd");
+    let package = &result.packages[0];
+    assert!(matches!(
+        package.find_type("LIGHT_STATE"),
+        Some(TypeDef::Enum { literals, .. }) if literals == &["IDLE", "GREEN", "YELLOW", "RED"]
+    ));
+
+    let source = "\
+entity e is
+    port (clk : in std_logic);
+end entity;
+architecture rtl of e is
+    type state_t is (IDLE, RUN, DONE);
+    type level_t is ('L', 'H', 'Z');
+    subtype byte_t is std_logic_vector(7 downto 0);
+    type mem_t is array (0 to 3) of byte_t;
+    signal state : state_t := IDLE;
+begin
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let arch = &result.architectures[0];
+    assert_eq!(arch.signals.len(), 1);
+    let names: Vec<_> = arch.types.iter().map(|t| t.name()).collect();
+    assert_eq!(names, ["state_t", "level_t", "byte_t", "mem_t"]);
+    let TypeDef::Enum { literals, span, .. } = &arch.types[1] else {
+        panic!("expected an enumeration type, got {:?}", arch.types[1]);
+    };
+    assert_eq!(literals, &["'L'", "'H'", "'Z'"]);
+    assert_eq!(
+        &source[span.start..span.end],
+        "type level_t is ('L', 'H', 'Z');"
+    );
+    assert!(matches!(arch.types[3], TypeDef::Array { .. }));
+
+    assert!(result.to_json().contains("\"kind\": \"enum\""));
+    assert!(
+        result
+            .to_string()
+            .contains("  Type: state_t is (IDLE, RUN, DONE)\n")
+    );
+
+    let err = analyze_vhdl(&source.replace("RUN, DONE", "RUN, 3")).unwrap_err();
+    assert_eq!(err.message, "expected enumeration literal, found '3'");
+}
+
+#[test]
+fn test_array_types() {
+    let source = "\
+package mem_pkg is
+    type slv_array is array (natural range <>) of std_logic_vector(7 downto 0);
+    type matrix_t is array (0 to 3, 0 to 3) of integer;
+end package;
+
+entity e is
+    port (clk : in std_logic);
+end entity;
+architecture rtl of e is
+    type mem_t is array (0 to 255) of std_logic_vector(7 downto 0);
+    type count_t is array (state_t) of natural;
+    signal mem : mem_t := (others => (others => '0'));
+    signal window : slv_array(0 to 3);
+    signal q : std_logic;
+begin
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let arch = &result.architectures[0];
+    let names: Vec<_> = arch.signals.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["mem", "window", "q"]);
+    assert_eq!(
+        arch.signals[1].vhdl_type,
+        VhdlType::Other("slv_array".to_string())
+    );
+
+    let TypeDef::Array {
+        index_range,
+        element,
+        span,
+        ..
+    } = &arch.types[0]
+    else {
+        panic!("expected an array type, got {:?}", arch.types[0]);
+    };
+    assert_eq!(index_range.as_ref().unwrap().to_string(), "0 to 255");
+    assert_eq!(element.bit_width(), Some(8));
+    assert!(source[span.start..span.end].ends_with("std_logic_vector(7 downto 0);"));
+    assert!(matches!(
+        &arch.types[1],
+        TypeDef::Array { index_range: Some(Expr::Name(n)), element: VhdlType::Natural(None), .. }
+            if n == "state_t"
+    ));
+
+    let package = &result.packages[0];
+    assert!(matches!(
+        package.find_type("slv_array"),
+        Some(TypeDef::Array {
+            index_range: None,
+            index_type: Some(index),
+            ..
+        }) if index == "natural"
+    ));
+    // 多次元の配列は表記のまま
+    assert!(matches!(
+        package.find_type("matrix_t"),
+        Some(TypeDef::Other { definition, .. }) if definition.starts_with("array ( 0 to 3 ,")
+    ));
+
+    assert!(result.to_json().contains("\"kind\": \"array\""));
+    let text = result.to_string();
+    assert!(text.contains("  Type: mem_t is array (0 to 255) of "));
+    assert!(text.contains("  Type: slv_array is array (natural range <>) of "));
 }
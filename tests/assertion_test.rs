@@ -0,0 +1,230 @@
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::assertion::{self, CompareOp, Condition, Property, PropertyKind};
+use vig::generator::TestbenchBuilder;
+
+fn uart() -> vig::analyzer::EntityDef {
+    analyze_file("testdata/uart_tx.vhd")
+        .unwrap()
+        .entities
+        .remove(0)
+}
+
+#[test]
+fn test_parse_kinds() {
+    let p = Property::parse("after reset, busy=0").unwrap();
+    let PropertyKind::AfterReset(Condition::Compare {
+        signal, op, value, ..
+    }) = p.kind
+    else {
+        panic!("unexpected {:?}", p.kind);
+    };
+    assert_eq!(signal, "busy");
+    assert_eq!(op, Some(CompareOp::Eq));
+    assert_eq!(value.as_deref(), Some("0"));
+
+    let p = Property::parse("  done within 100 cycles of start ").unwrap();
+    assert_eq!(p.text, "done within 100 cycles of start");
+    assert!(matches!(p.kind, PropertyKind::Within { cycles: 100, .. }));
+
+    let p = Property::parse("never (a or b) and not c").unwrap();
+    let PropertyKind::Never(Condition::And(left, right)) = p.kind else {
+        panic!("unexpected {:?}", p.kind);
+    };
+    assert!(matches!(*left, Condition::Or(..)));
+    assert!(matches!(*right, Condition::Not(..)));
+}
+
+#[test]
+fn test_parse_errors() {
+    let err = Property::parse("after reset, busy=").unwrap_err();
+    assert_eq!(err.message, "expected a value, found end of assertion");
+
+    let err = Property::parse("done within 0 cycles of start").unwrap_err();
+    assert_eq!(err.message, "expected a positive number of cycles");
+    assert_eq!((err.span.start, err.span.end), (12, 13));
+
+    let err = Property::parse("always busy = 0 busy").unwrap_err();
+    assert_eq!(err.message, "expected end of assertion, found 'busy'");
+}
+
+#[test]
+fn test_checkers() {
+    let entity = uart();
+    let tb = TestbenchBuilder::new(&entity)
+        .assertion(Property::parse("after reset, tx_busy=0").unwrap())
+        .assertion(Property::parse("always tx_data /= 255 or not tx_start").unwrap())
+        .assertion(Property::parse("tx_done within 20 cycles of tx_start").unwrap())
+        .build();
+
+    assert!(tb.contains("    -- アサーション: after reset, tx_busy=0\n    assert_1: process\n"));
+    assert!(tb.contains(
+        "        wait until reset = '0';
+        assert tx_busy = '0' report \"assertion failed: after reset, tx_busy=0\" severity error;
+        wait;
+"
+    ));
+    assert!(tb.contains("    assert_2: process(clk)\n"));
+    assert!(
+        tb.contains("            assert unsigned(tx_data) /= 255 or not (tx_start = '1') report")
+    );
+    assert!(tb.contains(
+        "        wait until rising_edge(clk) and reset = '0' and (tx_start = '1');
+        for i in 1 to 20 loop
+            wait until rising_edge(clk);
+            exit when tx_done = '1';
+            assert i < 20 report \"assertion failed: tx_done within 20 cycles of tx_start\" severity error;
+        end loop;
+"
+    ));
+}
+
+#[test]
+fn test_check_against_entity() {
+    let entity = uart();
+    let err = Property::parse("after reset, busy=0")
+        .unwrap()
+        .check(&entity)
+        .unwrap_err();
+    assert_eq!(err.message, "unknown signal 'busy'");
+    let err = Property::parse("always tx_data")
+        .unwrap()
+        .check(&entity)
+        .unwrap_err();
+    assert_eq!(err.message, "'tx_data' needs a comparison");
+
+    let comb =
+        analyze_vhdl("entity inv is port (a : in std_logic; y : out std_logic); end entity;")
+            .unwrap()
+            .entities
+            .remove(0);
+    let err = Property::parse("always y /= a")
+        .unwrap()
+        .check(&comb)
+        .unwrap_err();
+    assert_eq!(err.message, "assertion needs a clock port");
+
+    // 生成時は理由をコメントに残して飛ばす
+    let tb = TestbenchBuilder::new(&comb)
+        .assertion(Property::parse("after reset, y=0").unwrap())
+        .build();
+    assert!(tb.contains("    -- アサーション 'after reset, y=0' は生成できません: 'after reset' needs a reset port\n"));
+    assert!(!tb.contains("assert_1"));
+}
+
+#[test]
+fn test_from_comments() {
+    let source = "entity a is
+    -- vig: assert after reset, x=0
+    port (x : out std_logic);
+end entity a;
+architecture rtl of a is
+begin
+    -- vig: assert always x = '0'
+    -- vig:assertion is not a property
+end architecture;
+entity b is
+    -- vig: assert never y
+end entity b;
+";
+    let props = assertion::from_comments(source, "A").unwrap();
+    assert_eq!(props.len(), 2);
+    assert_eq!(props[0].text, "after reset, x=0");
+    assert_eq!(
+        &source[props[0].span.start..props[0].span.end],
+        "after reset, x=0"
+    );
+    assert_eq!(props[1].text, "always x = '0'");
+
+    let props = assertion::from_comments(source, "b").unwrap();
+    assert_eq!(props.len(), 1);
+
+    let err =
+        assertion::from_comments("entity c is\n-- vig: assert always\nend;", "c").unwrap_err();
+    assert_eq!(err.span.start, 33);
+}
+
+#[test]
+fn test_from_config() {
+    let source = "[assertions]
+uart_tx = [\"after reset, tx_busy=0\", \"tx_done within 10 cycles of tx_start\"]
+other = [\"always z\"]
+";
+    let props = assertion::from_config(source, "UART_TX").unwrap();
+    assert_eq!(props.len(), 2);
+    assert_eq!(
+        &source[props[1].span.start..props[1].span.end],
+        props[1].text
+    );
+    assert!(
+        assertion::from_config("name = 1\n", "uart_tx")
+            .unwrap()
+            .is_empty()
+    );
+
+    let err = assertion::from_config("[assertions]\nuart_tx = 1\n", "uart_tx").unwrap_err();
+    assert_eq!(
+        err.message,
+        "assertions of 'uart_tx' must be an array of strings"
+    );
+}
+
+#[test]
+fn test_from_props() {
+    let source = "invariants = [\"not (tx_busy and tx_done)\"]
+
+[reset]
+tx_busy = 0
+tx_out = \"1\"
+tx_data = 0
+
+[[latency]]
+from = \"tx_start\"
+to = \"tx_done\"
+max_cycles = 5000
+";
+    let props = assertion::from_props(source).unwrap();
+    let texts: Vec<&str> = props.iter().map(|p| p.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        [
+            "after reset, tx_busy = 0",
+            "after reset, tx_out = '1'",
+            "after reset, tx_data = 0",
+            "always not (tx_busy and tx_done)",
+            "tx_done within 5000 cycles of tx_start",
+        ]
+    );
+    // 位置はファイル中の値を指す
+    assert_eq!(&source[props[1].span.start..props[1].span.end], "tx_out");
+    assert_eq!(&source[props[4].span.start..props[4].span.end], "tx_start");
+
+    let entity = uart();
+    for p in &props {
+        p.check(&entity).unwrap();
+    }
+    let tb = TestbenchBuilder::new(&entity)
+        .assertion(props[2].clone())
+        .build();
+    assert!(tb.contains("assert unsigned(tx_data) = 0 report"));
+}
+
+#[test]
+fn test_from_props_errors() {
+    let source = "[reset]\nbusy = 0\n";
+    let err = assertion::from_props(source)
+        .unwrap()
+        .remove(0)
+        .check(&uart())
+        .unwrap_err();
+    assert_eq!(err.message, "unknown signal 'busy'");
+    assert_eq!((err.span.start, err.span.end), (8, 12));
+
+    let err = assertion::from_props("[[latency]]\nfrom = \"a\"\nto = \"b\"\n").unwrap_err();
+    assert_eq!(
+        err.message,
+        "'latency' must be an array of tables with 'from', 'to' and 'max_cycles'"
+    );
+    let err = assertion::from_props("invariants = [\"a =\"]\n").unwrap_err();
+    assert_eq!(err.message, "expected a value, found end of assertion");
+    assert_eq!(err.span.start, 15);
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use vig::banner::{Banner, from_config};
+
+#[test]
+fn test_render_lines() {
+    let config = "\
+[banner]
+text = [\"Copyright (c) {year} {author}\", \"SPDX-License-Identifier: MIT\", \"\", \"-- {entity}\"]
+author = \"Example Corp.\"
+";
+    let banner = from_config(config, Path::new(".")).unwrap().unwrap();
+    assert_eq!(banner.author, "Example Corp.");
+    // 空行はコメント記号だけ、コメント記号で始まる行はそのまま
+    assert_eq!(
+        banner.render("counter", 2026, "--"),
+        "-- Copyright (c) 2026 Example Corp.\n-- SPDX-License-Identifier: MIT\n--\n-- counter\n\n"
+    );
+}
+
+#[test]
+fn test_render_script_comment() {
+    let banner = Banner::new("(c) {year} {author}\n{entity}", "ACME");
+    assert_eq!(
+        banner.render("top", 2025, "#"),
+        "# (c) 2025 ACME\n# top\n\n"
+    );
+}
+
+#[test]
+fn test_inline_text_with_escapes() {
+    let config = "[banner]\ntext = \"Line 1\\nLine 2 for {entity}\"\n";
+    let banner = from_config(config, Path::new(".")).unwrap().unwrap();
+    assert_eq!(banner.author, "");
+    assert_eq!(
+        banner.render("uart", 2026, "--"),
+        "-- Line 1\n-- Line 2 for uart\n\n"
+    );
+}
+
+#[test]
+fn test_prepend_keeps_text() {
+    let banner = Banner::new("{entity}", "");
+    let out = banner.prepend("entity counter_tb is\n", "counter", "--");
+    assert_eq!(out, "-- counter\n\nentity counter_tb is\n");
+}
+
+#[test]
+fn test_template_file() {
+    let dir = std::env::temp_dir().join("vig_banner_test");
+    std::fs::create_dir_all(dir.join("legal")).unwrap();
+    std::fs::write(
+        dir.join("legal").join("header.txt"),
+        "Copyright {year} {author}\nAll rights reserved.\n",
+    )
+    .unwrap();
+    let config = "[banner]\nfile = \"legal/header.txt\"\nauthor = \"ACME\"\n";
+    let banner = from_config(config, &dir).unwrap().unwrap();
+    assert_eq!(
+        banner.render("fifo", 2026, "--"),
+        "-- Copyright 2026 ACME\n-- All rights reserved.\n\n"
+    );
+
+    let config = "[banner]\nfile = \"legal/missing.txt\"\n";
+    let err = from_config(config, &dir).unwrap_err();
+    assert!(
+        err.message
+            .starts_with("cannot read banner file 'legal/missing.txt'")
+    );
+    assert_eq!(&config[err.span.start..err.span.end], "legal/missing.txt");
+}
+
+#[test]
+fn test_no_banner() {
+    let config = "[entity.counter]\nclock_period_ns = 20\n";
+    assert_eq!(from_config(config, Path::new(".")), Ok(None));
+}
+
+#[test]
+fn test_banner_errors() {
+    let config = "[banner]\ntext = \"(c) {yaer}\"\n";
+    let err = from_config(config, Path::new(".")).unwrap_err();
+    assert_eq!(
+        err.message,
+        "unknown placeholder '{yaer}' in banner (expected {year}, {entity} or {author})"
+    );
+    assert_eq!(&config[err.span.start..err.span.end], "{yaer}");
+
+    let config = "[banner]\nauthor = \"ACME\"\n";
+    let err = from_config(config, Path::new(".")).unwrap_err();
+    assert_eq!(err.message, "banner needs 'text' or 'file'");
+
+    let config = "[banner]\ntext = 1\n";
+    let err = from_config(config, Path::new(".")).unwrap_err();
+    assert_eq!(
+        err.message,
+        "'text' must be a string or an array of strings"
+    );
+
+    let config = "[banner]\ntext = \"x\"\nlicense = \"MIT\"\n";
+    let err = from_config(config, Path::new(".")).unwrap_err();
+    assert_eq!(err.message, "unknown field 'license' in banner");
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "capi")]
+
+use std::ffi::{CStr, CString, c_char};
+use std::fs;
+use std::ptr;
+use vig::capi::{vig_analyze_to_json, vig_generate_testbench, vig_string_free};
+
+fn take(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    let text = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+    unsafe { vig_string_free(s) };
+    text
+}
+
+#[test]
+fn test_analyze_to_json() {
+    let source = CString::new(fs::read_to_string("testdata/counter.vhd").unwrap()).unwrap();
+    let json = take(unsafe { vig_analyze_to_json(source.as_ptr(), ptr::null_mut()) });
+    assert!(json.contains("\"name\": \"counter\""));
+}
+
+#[test]
+fn test_generate_testbench() {
+    let source = CString::new(fs::read_to_string("testdata/counter.vhd").unwrap()).unwrap();
+    let tb = take(unsafe { vig_generate_testbench(source.as_ptr(), 20, ptr::null_mut()) });
+    assert!(tb.contains("entity counter_tb is"));
+    assert!(tb.contains("周期 20 ns"));
+}
+
+#[test]
+fn test_error_reporting() {
+    let source = CString::new("entity is port").unwrap();
+    let mut error: *mut c_char = ptr::null_mut();
+    let result = unsafe { vig_analyze_to_json(source.as_ptr(), &mut error) };
+    assert!(result.is_null());
+    assert!(take(error).contains("expected Identifier"));
+
+    // NULL入力
+    let mut error: *mut c_char = ptr::null_mut();
+    let result = unsafe { vig_generate_testbench(ptr::null(), 10, &mut error) };
+    assert!(result.is_null());
+    assert!(take(error).contains("NULL"));
+
+    // NULLの解放は無視される
+    unsafe { vig_string_free(ptr::null_mut()) };
+}
@@ -0,0 +1,102 @@
+use vig::completion::{CompletionKind, completions};
+
+fn labels(source: &str, offset: usize) -> Vec<String> {
+    completions(source, offset)
+        .into_iter()
+        .map(|item| item.label)
+        .collect()
+}
+
+#[test]
+fn test_top_level_keywords() {
+    let items = labels("", 0);
+    assert!(items.contains(&"entity".to_string()));
+    assert!(items.contains(&"library".to_string()));
+    assert!(!items.contains(&"signal".to_string()));
+}
+
+#[test]
+fn test_names_in_architecture_body() {
+    let source = std::fs::read_to_string("testdata/counter.vhd").unwrap();
+    // "count <= ..." の "cou" まで入力した位置
+    let offset = source.find("count <=").unwrap() + 3;
+    let items = completions(&source, offset);
+    let names: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+    assert_eq!(names, ["count", "counter_value"]);
+
+    assert_eq!(items[0].kind, CompletionKind::Port);
+    assert_eq!(
+        items[0].detail.as_deref(),
+        Some("out std_logic_vector(7 downto 0)")
+    );
+    assert_eq!(items[1].kind, CompletionKind::Signal);
+    assert_eq!(items[1].detail.as_deref(), Some("integer"));
+}
+
+#[test]
+fn test_port_clause_directions_and_types() {
+    let source = "entity e is port (a : ";
+    let items = labels(source, source.len());
+    assert!(items.contains(&"in".to_string()));
+    assert!(items.contains(&"std_logic_vector".to_string()));
+
+    let source = "entity e is port (a : in std_";
+    let items = labels(source, source.len());
+    assert_eq!(
+        items,
+        [
+            "std_logic",
+            "std_logic_vector",
+            "std_ulogic",
+            "std_ulogic_vector"
+        ]
+    );
+}
+
+#[test]
+fn test_incomplete_architecture() {
+    // 編集途中で解析できないアーキテクチャでもポートと宣言済みの信号を返す
+    let source = "entity e is port (clk : in std_logic; q : out std_logic); end entity;
+architecture rtl of e is
+    constant DEPTH : integer := 4;
+    signal s_reg : std_logic;
+begin
+    q <= ";
+    let items = completions(source, source.len());
+    let find = |name: &str| items.iter().find(|i| i.label == name).map(|i| i.kind);
+    assert_eq!(find("clk"), Some(CompletionKind::Port));
+    assert_eq!(find("q"), Some(CompletionKind::Port));
+    assert_eq!(find("s_reg"), Some(CompletionKind::Signal));
+    assert_eq!(find("DEPTH"), Some(CompletionKind::Constant));
+    assert_eq!(find("process"), Some(CompletionKind::Keyword));
+}
+
+#[test]
+fn test_declarative_region() {
+    let source = "entity e is end;
+architecture rtl of e is
+    sig";
+    assert_eq!(labels(source, source.len()), ["signal"]);
+
+    let source = "entity e is end;
+architecture rtl of e is
+    signal s : ";
+    assert!(labels(source, source.len()).contains(&"unsigned".to_string()));
+}
+
+#[test]
+fn test_entity_names_for_instantiation() {
+    let source = "entity sub is port (a : in std_logic); end;
+entity top is end;
+architecture rtl of top is
+begin
+    u1 : entity work.";
+    let items = completions(source, source.len());
+    assert!(items.iter().all(|i| i.kind == CompletionKind::Entity));
+    let names: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+    assert_eq!(names, ["sub", "top"]);
+
+    let source = "entity sub is end;
+architecture rtl of s";
+    assert_eq!(labels(source, source.len()), ["sub"]);
+}
@@ -0,0 +1,47 @@
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::constraints::{ConstraintConfig, ConstraintFormat, generate_constraints};
+
+#[test]
+fn test_xdc_clock_and_pins() {
+    let result = analyze_file("testdata/counter.vhd").unwrap();
+    let xdc = generate_constraints(&result.entities[0], &ConstraintConfig::default());
+    assert!(xdc.contains("create_clock -period 10.000 -name clk [get_ports clk]\n"));
+    assert!(xdc.contains("# set_property PACKAGE_PIN <pin> [get_ports reset]\n"));
+    // ベクタはポート単位にまとめ、ピンは先頭ビットの例だけを出す
+    assert!(xdc.contains("# count[7:0]（ピンはビットごとに割り当てる）\n"));
+    assert!(xdc.contains("# set_property PACKAGE_PIN <pin> [get_ports {count[0]}]\n"));
+    assert!(xdc.contains("# set_property IOSTANDARD LVCMOS33 [get_ports {count[*]}]\n"));
+    assert!(!xdc.contains("{count[1]}"));
+}
+
+#[test]
+fn test_sdc_period_and_delays() {
+    let result = analyze_file("testdata/uart_tx.vhd").unwrap();
+    let mut config = ConstraintConfig::default();
+    config.format = ConstraintFormat::Sdc;
+    config.clock_period_ns = 8;
+    let sdc = generate_constraints(&result.entities[0], &config);
+    assert!(sdc.contains("create_clock -name clk -period 8.000 [get_ports {clk}]\n"));
+    assert!(sdc.contains("# set_input_delay -clock clk <delay> [get_ports {tx_data[*]}]\n"));
+    assert!(sdc.contains("# set_output_delay -clock clk <delay> [get_ports {tx_busy}]\n"));
+    // クロック自体には遅延を付けない
+    assert!(!sdc.contains("[get_ports {clk}]\n# "));
+    assert!(!sdc.contains("PACKAGE_PIN"));
+    // ピン配置・IO規格は Quartus の割り当てで出す
+    assert!(sdc.contains("# set_location_assignment <pin> -to tx_busy\n"));
+    assert!(sdc.contains("# set_location_assignment <pin> -to tx_data[0]\n"));
+    assert!(
+        sdc.contains("# set_instance_assignment -name IO_STANDARD \"LVCMOS33\" -to tx_data[*]\n")
+    );
+}
+
+#[test]
+fn test_no_clock() {
+    let result =
+        analyze_vhdl("entity mux is port (a, b : in std_logic; y : out integer); end;").unwrap();
+    let mut config = ConstraintConfig::default();
+    config.format = ConstraintFormat::Sdc;
+    let sdc = generate_constraints(&result.entities[0], &config);
+    assert!(!sdc.contains("create_clock"));
+    assert!(sdc.contains("# set_output_delay -clock <clock> <delay> [get_ports {y[*]}]\n"));
+}
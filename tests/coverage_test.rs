@@ -0,0 +1,90 @@
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::generator::{TbConfig, TestbenchBuilder, generate_testbench};
+use vig::regmap::RegisterMap;
+use vig::vectors::{self, TruthTable};
+
+fn coverage_config() -> TbConfig {
+    let mut config = TbConfig::default();
+    config.toggle_coverage = true;
+    config
+}
+
+#[test]
+fn test_disabled_by_default() {
+    let result = analyze_file("testdata/counter.vhd").unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+    assert!(!tb.contains("sim_done"));
+    assert!(!tb.contains("toggle_coverage"));
+}
+
+#[test]
+fn test_vector_output_coverage() {
+    let result = analyze_file("testdata/counter.vhd").unwrap();
+    let tb = generate_testbench(&result.entities[0], &coverage_config());
+
+    assert!(tb.contains("    signal sim_done : boolean := false;\n"));
+    assert!(tb.contains(
+        "        variable count_last : std_logic_vector(7 downto 0) := (others => 'U');\n"
+    ));
+    assert!(tb.contains("        wait on count, sim_done;\n"));
+    assert!(tb.contains("                if count_rose(i) = '0' and count_fell(i) = '0' then\n"));
+    assert!(tb.contains("& integer'image(covered) & \"/8 bits\" severity note;\n"));
+    assert!(tb.contains("        count_last := count;\n"));
+    assert!(tb.contains("    end process toggle_coverage;\n"));
+
+    // 終了直前に報告を促す
+    let done = tb.find("        sim_done <= true;\n").unwrap();
+    let finish = tb.find("-- シミュレーション終了").unwrap();
+    assert!(done < finish);
+}
+
+#[test]
+fn test_only_logic_outputs() {
+    let source = "entity mix is
+    port (
+        clk   : in  std_logic;
+        valid : out std_logic;
+        level : out integer;
+        bus_a : inout std_logic_vector(0 to 3)
+    );
+end entity mix;";
+    let result = analyze_vhdl(source).unwrap();
+    let tb = TestbenchBuilder::new(&result.entities[0])
+        .toggle_coverage(true)
+        .build();
+
+    assert!(tb.contains("        wait on valid, bus_a, sim_done;\n"));
+    assert!(tb.contains("        variable valid_rose, valid_fell : std_logic := '0';\n"));
+    assert!(tb.contains(
+        "        variable bus_a_rose, bus_a_fell : std_logic_vector(0 to 3) := (others => '0');\n"
+    ));
+    assert!(tb.contains("report \"toggle coverage: \" & \"valid\" & \" never toggled\""));
+    assert!(!tb.contains("level_last"));
+    assert!(!tb.contains("clk_last"));
+}
+
+#[test]
+fn test_no_outputs() {
+    let source = "entity sink is
+    port (a : in std_logic);
+end entity sink;";
+    let result = analyze_vhdl(source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &coverage_config());
+    assert!(!tb.contains("sim_done"));
+}
+
+#[test]
+fn test_vectors_and_regmap() {
+    let result = analyze_file("testdata/alu.vhd").unwrap();
+    let source = std::fs::read_to_string("testdata/alu_vectors.csv").unwrap();
+    let table = TruthTable::from_csv(&source).unwrap();
+    let tb = vectors::generate_testbench(&result.entities[0], &table, &coverage_config()).unwrap();
+    assert!(tb.contains("        sim_done <= true;\n"));
+    assert!(tb.contains("    end process toggle_coverage;\n"));
+
+    let source = std::fs::read_to_string("testdata/regs.toml").unwrap();
+    let map = RegisterMap::from_toml(&source).unwrap();
+    let tb = map.generate_testbench(&coverage_config());
+    assert!(tb.contains("        sim_done <= true;\n"));
+    assert!(tb.contains("    end process toggle_coverage;\n"));
+}
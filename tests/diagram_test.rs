@@ -0,0 +1,37 @@
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::diagram::{generate_dot, generate_svg};
+
+#[test]
+fn test_dot_sides_and_buses() {
+    let result = analyze_file("testdata/uart_tx.vhd").unwrap();
+    let dot = generate_dot(&result.entities[0]);
+    assert!(dot.starts_with("digraph \"uart_tx\" {\n"));
+    assert!(dot.contains("<TD ALIGN=\"LEFT\" PORT=\"tx_data\">tx_data[7:0]</TD>"));
+    assert!(dot.contains("<TD ALIGN=\"RIGHT\" PORT=\"tx_busy\">tx_busy</TD>"));
+    // tx_* のバス見出しが入力側・出力側の両方に付く
+    assert_eq!(dot.matches("<I>tx</I>").count(), 2);
+    assert!(dot.contains("    \"p_clk\" -> entity:\"clk\":w;\n"));
+    assert!(dot.contains("    \"p_tx_data\" -> entity:\"tx_data\":w [penwidth=2.5];\n"));
+    assert!(dot.contains("    entity:\"tx_out\":e -> \"p_tx_out\";\n"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn test_svg() {
+    let result = analyze_file("testdata/counter.vhd").unwrap();
+    let svg = generate_svg(&result.entities[0]);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.contains(">counter</text>"));
+    assert!(svg.contains(">count[7:0]</text>"));
+    assert_eq!(svg.matches("<line ").count(), 3);
+    assert!(svg.ends_with("</svg>\n"));
+}
+
+#[test]
+fn test_escape_and_inout() {
+    let result = analyze_vhdl("entity io is port (sda : inout std_logic); end;").unwrap();
+    let dot = generate_dot(&result.entities[0]);
+    assert!(dot.contains("entity:\"sda\":e -> \"p_sda\" [dir=both];"));
+    let svg = generate_svg(&result.entities[0]);
+    assert!(svg.contains("marker-start=\"url(#arrow)\""));
+}
@@ -0,0 +1,382 @@
+use std::fs;
+use vig::analyzer::{AnalyzeResult, AssociationDef, TypeDef, analyze_vhdl};
+use vig::emit::{EmitStyle, Emitter, KeywordCase};
+
+fn analyze_file(path: &str) -> AnalyzeResult {
+    let source = fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path));
+    analyze_vhdl(&source).expect("analysis failed")
+}
+
+#[test]
+fn test_round_trip_design_file() {
+    for path in [
+        "testdata/counter.vhd",
+        "testdata/alu.vhd",
+        "testdata/uart_tx.vhd",
+    ] {
+        let result = analyze_file(path);
+        let text = Emitter::default().design_file(&result);
+        let back = analyze_vhdl(&text).expect("re-analysis failed");
+
+        // 位置情報以外が一致すること
+        assert_eq!(back.entities.len(), result.entities.len());
+        for (a, b) in result.entities.iter().zip(&back.entities) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.ports.len(), b.ports.len());
+            for (p, q) in a.ports.iter().zip(&b.ports) {
+                assert_eq!(p.name, q.name);
+                assert_eq!(p.direction, q.direction);
+                assert_eq!(p.vhdl_type, q.vhdl_type);
+            }
+        }
+        assert_eq!(back.architectures.len(), result.architectures.len());
+        for (a, b) in result.architectures.iter().zip(&back.architectures) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.entity_name, b.entity_name);
+            let names: Vec<_> = a.signals.iter().map(|s| &s.name).collect();
+            let back_names: Vec<_> = b.signals.iter().map(|s| &s.name).collect();
+            assert_eq!(names, back_names);
+            let names: Vec<_> = a.constants.iter().map(|c| &c.name).collect();
+            let back_names: Vec<_> = b.constants.iter().map(|c| &c.name).collect();
+            assert_eq!(names, back_names);
+        }
+    }
+}
+
+#[test]
+fn test_round_trip_generics() {
+    let source = "\
+entity fifo is
+    generic (
+        WIDTH : positive := 8;
+        DEPTH : integer range 1 to 64 := 2 ** 4;
+        INIT  : std_logic_vector(WIDTH - 1 downto 0) := (others => '0');
+        NAME  : string := \"fifo\";
+        SYNC  : boolean
+    );
+    port (
+        clk  : in  std_logic;
+        din  : in  std_logic_vector(WIDTH - 1 downto 0);
+        dout : out std_logic_vector(WIDTH - 1 downto 0)
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let emitter = Emitter::default();
+    let summary = |result: &AnalyzeResult| -> Vec<_> {
+        result.entities[0]
+            .generics
+            .iter()
+            .map(|g| (g.name.clone(), g.vhdl_type.clone(), g.default_value.clone()))
+            .collect()
+    };
+
+    // 解析 → 出力 → 再解析でジェネリックが変わらないこと
+    let text = emitter.entity(&result.entities[0]);
+    assert!(text.starts_with("entity fifo is\n    generic (\n        WIDTH : positive := 8;\n"));
+    assert!(text.contains("        SYNC : boolean\n    );\n    port (\n"));
+    let back = analyze_vhdl(&text).expect("re-analysis failed");
+    assert_eq!(summary(&back), summary(&result));
+
+    // コンポーネント宣言にも generic 節を書く
+    let component = emitter.component(&result.entities[0], 1);
+    assert!(component.contains("        generic (\n            WIDTH : positive := 8;\n"));
+    let wrapped = format!(
+        "architecture a of e is\n{}begin\nend architecture;",
+        component
+    );
+    assert!(analyze_vhdl(&wrapped).is_ok());
+}
+
+#[test]
+fn test_round_trip_constants() {
+    let source = "\
+architecture rtl of e is
+    constant WIDTH  : natural := 8;
+    constant PERIOD : time := 10 ns;
+    constant ZERO   : std_logic_vector(WIDTH - 1 downto 0) := (others => '0');
+    signal data : std_logic_vector(WIDTH - 1 downto 0) := ZERO;
+begin
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let text = Emitter::default().architecture(&result.architectures[0]);
+    assert!(text.contains("    constant WIDTH : natural := 8;\n"));
+    assert!(text.contains("    constant PERIOD : time := 10 ns;\n"));
+
+    // 定数は信号より前に宣言する
+    let back = analyze_vhdl(&text).expect("re-analysis failed");
+    let summary = |result: &AnalyzeResult| -> Vec<_> {
+        result.architectures[0]
+            .constants
+            .iter()
+            .map(|c| (c.name.clone(), c.vhdl_type.clone(), c.value.clone()))
+            .collect()
+    };
+    assert_eq!(summary(&back), summary(&result));
+    assert!(text.find("constant ZERO").unwrap() < text.find("signal data").unwrap());
+
+    let mut style = EmitStyle::default();
+    style.align_colons = true;
+    let text = Emitter::new(style).architecture(&result.architectures[0]);
+    assert!(text.contains("    constant WIDTH  : natural := 8;\n"));
+    assert!(text.contains("    signal data     : std_logic_vector(WIDTH - 1 downto 0) := ZERO;\n"));
+}
+
+#[test]
+fn test_round_trip_component_instances() {
+    let source = "\
+entity adder is
+    generic (WIDTH : natural := 8);
+    port (a, b : in std_logic_vector(WIDTH - 1 downto 0); s : out std_logic_vector(WIDTH - 1 downto 0));
+end entity;
+
+architecture rtl of top is
+    component adder is
+        generic (WIDTH : natural := 8);
+        port (a, b : in std_logic_vector(WIDTH - 1 downto 0); s : out std_logic_vector(WIDTH - 1 downto 0));
+    end component;
+    signal x : std_logic_vector(3 downto 0);
+    signal y : std_logic_vector(3 downto 0);
+    signal z : std_logic_vector(3 downto 0);
+begin
+    u0 : adder generic map (WIDTH => 4) port map (a => x, b => y, s => z);
+    u1 : component adder port map (x(3 downto 0), y, open);
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let text = Emitter::default().design_file(&result);
+    assert!(text.contains(
+        "    u0 : adder\n        generic map (\n            WIDTH => 4\n        )\n        port map (\n"
+    ));
+    assert!(text.contains("            s => z\n        );\n"));
+    // 読み飛ばしたコンポーネント宣言はエンティティから書き直す
+    assert_eq!(text.matches("component adder is").count(), 1);
+
+    let back = analyze_vhdl(&text).expect("re-analysis failed");
+    let summary = |result: &AnalyzeResult| -> Vec<_> {
+        result.architectures[0]
+            .instances
+            .iter()
+            .map(|i| {
+                let map = |list: &[AssociationDef]| -> Vec<_> {
+                    list.iter()
+                        .map(|a| (a.formal.clone(), a.actual.clone()))
+                        .collect()
+                };
+                (
+                    i.label.clone(),
+                    i.unit.clone(),
+                    map(&i.generic_map),
+                    map(&i.port_map),
+                )
+            })
+            .collect()
+    };
+    assert_eq!(summary(&back), summary(&result));
+}
+
+#[test]
+fn test_round_trip_entity_instances() {
+    let source = "\
+architecture rtl of top is
+    signal x : std_logic;
+begin
+    u0 : entity work.inverter(rtl) port map (a => x, y => open);
+    u1 : entity inverter generic map (DELAY => 2 ns) port map (x, open);
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let mut style = EmitStyle::default();
+    style.keyword_case = KeywordCase::Upper;
+    let text = Emitter::new(style).architecture(&result.architectures[0]);
+    assert!(text.contains("    u0 : ENTITY work.inverter(rtl)\n        PORT MAP (\n"));
+    assert!(text.contains("    u1 : ENTITY inverter\n        GENERIC MAP (\n"));
+
+    let back = analyze_vhdl(&text).expect("re-analysis failed");
+    for (a, b) in result.architectures[0]
+        .instances
+        .iter()
+        .zip(&back.architectures[0].instances)
+    {
+        assert_eq!(a.label, b.label);
+        assert_eq!(a.unit, b.unit);
+        assert_eq!(a.generic_map.len(), b.generic_map.len());
+        assert_eq!(a.port_actual("y"), b.port_actual("y"));
+        let actuals: Vec<_> = a.port_map.iter().map(|p| &p.actual).collect();
+        let back_actuals: Vec<_> = b.port_map.iter().map(|p| &p.actual).collect();
+        assert_eq!(actuals, back_actuals);
+    }
+    assert_eq!(back.architectures[0].instances.len(), 2);
+}
+
+#[test]
+fn test_design_file_keeps_every_unit() {
+    for path in [
+        "testdata/counter.vhd",
+        "testdata/alu.vhd",
+        "testdata/uart_tx.vhd",
+        "testdata/traffic_light.vhd",
+    ] {
+        let result = analyze_file(path);
+        let text = Emitter::default().design_file(&result);
+        let back = analyze_vhdl(&text).unwrap_or_else(|e| panic!("{}: {}\n{}", path, e, text));
+        // 出力を読み直して出力しても同じ（位置情報以外は失われない）
+        assert_eq!(Emitter::default().design_file(&back), text, "{}", path);
+        assert_eq!(back.packages.len(), result.packages.len());
+        for (a, b) in result.architectures.iter().zip(&back.architectures) {
+            assert_eq!(a.processes.len(), b.processes.len());
+            assert_eq!(a.types.len(), b.types.len());
+            assert_eq!(a.subprograms.len(), b.subprograms.len());
+        }
+    }
+}
+
+#[test]
+fn test_round_trip_processes_packages_and_subprograms() {
+    let source = "\
+package util is
+    constant SEED : integer;
+    subtype byte_t is std_logic_vector(7 downto 0);
+    type state_t is (IDLE, RUN);
+    type bytes_t is array (natural range <>) of byte_t;
+    type pair_t is record a : bit; b : bit; end record;
+    function parity(v : std_logic_vector) return std_logic;
+    impure function now_ns return integer;
+    procedure clear(signal s : out std_logic_vector);
+end package;
+
+package body util is
+    constant SEED : integer := 42;
+    function parity(v : std_logic_vector) return std_logic is
+        constant INIT : std_logic := '0';
+        variable p : std_logic := INIT;
+        function flip(b : std_logic) return std_logic is
+        begin
+            return not b;
+        end function;
+    begin
+        for i in v'range loop
+            if v(i) = '1' then
+                p := flip(p);
+            end if;
+        end loop;
+        return p;
+    end function parity;
+    impure function now_ns return integer is
+    begin
+        return 0;
+    end function;
+    procedure clear(signal s : out std_logic_vector) is
+    begin
+        s <= (s'range => '0');
+    end procedure;
+end package body;
+
+entity e is
+    port (clk, rst : in std_logic; q : out std_logic_vector(3 downto 0));
+end entity;
+
+architecture rtl of e is
+    constant DEPTH : natural := 4;
+    type mem_t is array (0 to DEPTH - 1) of std_logic_vector(3 downto 0);
+    signal mem : mem_t;
+    signal state : state_t;
+begin
+    seq : process (clk, rst)
+        constant LIMIT : natural := 15;
+        variable count : integer range 0 to 15 := 0;
+    begin
+        if rst = '1' then
+            count := 0;
+        elsif rising_edge(clk) then
+            case state is
+                when IDLE | RUN =>
+                    count := count + 1;
+                when others =>
+                    null;
+            end case;
+        end if;
+        q <= mem(0) after 1 ns;
+    end process seq;
+    process
+    begin
+        outer : while true loop
+            wait on clk until clk = '1' for 10 ns;
+            exit outer;
+        end loop outer;
+        wait;
+    end process;
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let text = Emitter::default().design_file(&result);
+    let back = analyze_vhdl(&text).unwrap_or_else(|e| panic!("{}\n{}", e, text));
+    assert_eq!(Emitter::default().design_file(&back), text);
+
+    // パッケージ・本体・プロセス・型・サブプログラムを落とさない
+    assert!(text.starts_with("package util is\n    constant SEED : integer;\n"));
+    assert!(text.contains("    type bytes_t is array (natural range <>) of byte_t;\n"));
+    assert!(text.contains("    impure function now_ns return integer;\n"));
+    assert!(text.contains("package body util is\n"));
+    assert!(text.contains("        variable p : std_logic := INIT;\n        function flip"));
+    assert!(text.contains("    end function parity;\n"));
+    assert!(
+        text.contains(
+            "    type mem_t is array (0 to DEPTH - 1) of std_logic_vector(3 downto 0);\n"
+        )
+    );
+    assert!(
+        text.contains("    seq : process (clk, rst)\n        constant LIMIT : natural := 15;\n")
+    );
+    assert!(text.contains("                when IDLE | RUN =>\n"));
+    assert!(text.contains("        q <= mem(0) after 1 ns;\n"));
+    assert!(text.contains("        outer : while true loop\n"));
+    assert!(text.contains("            wait on clk until clk = '1' for 10 ns;\n"));
+    assert!(text.contains("        end loop outer;\n        wait;\n    end process;\n"));
+
+    let names =
+        |types: &[TypeDef]| -> Vec<String> { types.iter().map(|t| t.name().to_string()).collect() };
+    assert_eq!(
+        names(&back.packages[0].types),
+        names(&result.packages[0].types)
+    );
+    let body = &back.package_bodies[0];
+    assert_eq!(body.subprograms.len(), 3);
+    assert_eq!(body.subprograms[0].constants[0].name, "INIT");
+    assert_eq!(body.subprograms[0].subprograms[0].name, "flip");
+    assert!(body.subprograms[1].impure);
+    let arch = &back.architectures[0];
+    assert_eq!(arch.processes.len(), 2);
+    assert_eq!(arch.processes[0].label.as_deref(), Some("seq"));
+    assert_eq!(arch.processes[0].constants[0].name, "LIMIT");
+    assert_eq!(arch.processes[0].body.len(), 2);
+}
+
+#[test]
+fn test_default_entity_format() {
+    let result = analyze_file("testdata/counter.vhd");
+    let text = Emitter::default().entity(&result.entities[0]);
+    assert!(text.starts_with("entity counter is\n    port (\n"));
+    assert!(text.contains("        count : out std_logic_vector(7 downto 0)\n"));
+    assert!(text.ends_with("    );\nend entity counter;\n"));
+}
+
+#[test]
+fn test_upper_case_keywords_and_indent() {
+    let result = analyze_file("testdata/counter.vhd");
+    let mut style = EmitStyle::default();
+    style.keyword_case = KeywordCase::Upper;
+    style.indent = 2;
+    let text = Emitter::new(style).entity(&result.entities[0]);
+    assert!(text.starts_with("ENTITY counter IS\n  PORT (\n"));
+    assert!(text.contains("    clk : IN std_logic;\n"));
+    assert!(text.contains("std_logic_vector(7 DOWNTO 0)"));
+    assert!(text.ends_with("END ENTITY counter;\n"));
+}
+
+#[test]
+fn test_align_colons() {
+    let result = analyze_file("testdata/counter.vhd");
+    let mut style = EmitStyle::default();
+    style.align_colons = true;
+    let text = Emitter::new(style).component(&result.entities[0], 0);
+    assert!(text.contains("        clk   : in std_logic;\n"));
+    assert!(text.contains("        count : out std_logic_vector(7 downto 0)\n"));
+}
@@ -0,0 +1,97 @@
+use vig::analyzer::analyze_vhdl;
+use vig::encoding::{Encoding, decode, decode_lossy, encode, read_source, read_source_lossy};
+
+// 「カウンタ」（Shift_JIS）
+const SJIS_COMMENT: &[u8] = b"-- \x83\x4a\x83\x45\x83\x93\x83\x5e\n";
+
+fn sjis_source() -> Vec<u8> {
+    let mut bytes = SJIS_COMMENT.to_vec();
+    bytes.extend_from_slice(b"entity c is port (clk : in std_logic); end entity;\n");
+    bytes
+}
+
+#[test]
+fn test_detect() {
+    assert_eq!(Encoding::detect("-- カウンタ".as_bytes()), Encoding::Utf8);
+    assert_eq!(Encoding::detect(&sjis_source()), Encoding::ShiftJis);
+    // 「café」（Latin-1）は Shift_JIS として正しくない
+    assert_eq!(Encoding::detect(b"-- caf\xe9 \n"), Encoding::Latin1);
+}
+
+#[test]
+fn test_parse_names() {
+    assert_eq!("Shift_JIS".parse::<Encoding>(), Ok(Encoding::ShiftJis));
+    assert_eq!("cp932".parse::<Encoding>(), Ok(Encoding::ShiftJis));
+    assert_eq!("ISO-8859-1".parse::<Encoding>(), Ok(Encoding::Latin1));
+    assert_eq!("UTF8".parse::<Encoding>(), Ok(Encoding::Utf8));
+    assert!("ebcdic".parse::<Encoding>().is_err());
+}
+
+#[test]
+fn test_spans_refer_to_decoded_text() {
+    let source = decode(&sjis_source(), Encoding::ShiftJis).unwrap();
+    assert!(source.starts_with("-- カウンタ\n"));
+    let entity = &analyze_vhdl(&source).unwrap().entities[0];
+    let span = entity.ports[0].span;
+    assert_eq!(&source[span.start..span.start + 3], "clk");
+}
+
+#[test]
+fn test_latin1_roundtrip() {
+    let text = decode(b"-- caf\xe9\n", Encoding::Latin1).unwrap();
+    assert_eq!(text, "-- café\n");
+    assert_eq!(encode(&text, Encoding::Latin1).unwrap(), b"-- caf\xe9\n");
+}
+
+#[test]
+fn test_shift_jis_roundtrip() {
+    let text = decode(&sjis_source(), Encoding::ShiftJis).unwrap();
+    assert_eq!(encode(&text, Encoding::ShiftJis).unwrap(), sjis_source());
+}
+
+#[test]
+fn test_errors() {
+    let err = decode(b"ab\xff", Encoding::Utf8).unwrap_err();
+    assert_eq!((err.span.start, err.span.end), (2, 3));
+    let err = decode(b"-- \x83\n", Encoding::ShiftJis).unwrap_err();
+    assert!(err.message.contains("Shift_JIS"), "{}", err.message);
+    assert_eq!(err.span.start, 3);
+    let err = encode("-- 日本", Encoding::Latin1).unwrap_err();
+    assert_eq!(err.span.start, 3);
+    let err = encode("-- 😀", Encoding::ShiftJis).unwrap_err();
+    assert_eq!(err.span.start, 3);
+}
+
+#[test]
+fn test_read_source_detects() {
+    let path = std::env::temp_dir().join(format!("vig_encoding_{}.vhd", std::process::id()));
+    std::fs::write(&path, sjis_source()).unwrap();
+    let detected = read_source(&path, None);
+    let forced = read_source(&path, Some(Encoding::Utf8));
+    std::fs::remove_file(&path).unwrap();
+    assert!(detected.unwrap().contains("カウンタ"));
+    assert!(matches!(forced, Err(vig::Error::Encoding(_))));
+}
+
+#[test]
+fn test_lossy_decoding_of_vendor_files() {
+    // Latin-1 のコメントと、バイナリを含む保護された領域
+    let mut bytes =
+        b"-- \xa9 vendor\nentity ip is port (clk : in std_logic); end entity;\n".to_vec();
+    bytes.extend_from_slice(b"`protect begin_protected\n`protect data_block\n\x00\xff\xfe\x80\n");
+    bytes.extend_from_slice(b"`protect end_protected\n");
+
+    let text = decode_lossy(&bytes, Encoding::Utf8);
+    assert!(text.contains('\u{fffd}'));
+    let entity = &analyze_vhdl(&text).unwrap().entities[0];
+    assert_eq!(entity.name, "ip");
+    assert_eq!(entity.ports.len(), 1);
+
+    let path = std::env::temp_dir().join(format!("vig_lossy_{}.vhd", std::process::id()));
+    std::fs::write(&path, &bytes).unwrap();
+    let lossy = read_source_lossy(&path, Some(Encoding::Utf8));
+    let strict = read_source(&path, Some(Encoding::Utf8));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(lossy.unwrap(), text);
+    assert!(strict.is_err());
+}
@@ -0,0 +1,60 @@
+use std::error::Error as _;
+use vig::analyzer::analyze_file;
+use vig::interface::InterfaceDef;
+use vig::ipxact::import_component;
+
+#[test]
+fn test_analyze_file_ok() {
+    let result = analyze_file("testdata/counter.vhd").unwrap();
+    assert_eq!(result.entities[0].name, "counter");
+}
+
+#[test]
+fn test_io_error_has_path_and_source() {
+    let err = analyze_file("testdata/does_not_exist.vhd").unwrap_err();
+    assert!(matches!(err, vig::Error::Io { .. }));
+    assert!(err.to_string().contains("does_not_exist.vhd"));
+    assert!(err.source().is_some());
+    assert_eq!(err.span(), None);
+}
+
+#[test]
+fn test_analyze_error_conversion() {
+    fn run() -> vig::Result<()> {
+        vig::analyzer::analyze_vhdl("entity is")?;
+        Ok(())
+    }
+    let err = run().unwrap_err();
+    assert!(matches!(err, vig::Error::Analyze(_)));
+    let span = err.span().unwrap();
+    assert_eq!((span.start, span.end), (7, 9));
+    let source = err.source().unwrap();
+    assert!(source.to_string().contains("expected Identifier"));
+}
+
+#[test]
+fn test_analyze_file_error_has_location() {
+    let path = std::env::temp_dir().join(format!("vig_error_{}.vhd", std::process::id()));
+    std::fs::write(&path, "-- top\nentity is\n").unwrap();
+    let err = analyze_file(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    let expected = format!("{}:2:8: expected Identifier", path.display());
+    assert!(err.to_string().contains(&expected), "{}", err);
+}
+
+#[test]
+fn test_import_errors_unify() {
+    fn run() -> vig::Result<()> {
+        import_component("<design/>")?;
+        Ok(())
+    }
+    assert!(matches!(run().unwrap_err(), vig::Error::Import(_)));
+
+    fn run_json() -> vig::Result<()> {
+        InterfaceDef::from_json("{}")?;
+        Ok(())
+    }
+    let err = run_json().unwrap_err();
+    assert!(matches!(err, vig::Error::Interface(_)));
+    assert!(err.to_string().starts_with("interface error:"));
+}
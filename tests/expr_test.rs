@@ -0,0 +1,291 @@
+use vig::analyzer::{Statement, analyze_vhdl, parse_expr_str};
+use vig::expr::{BinaryOp, Direction, Element, Expr, UnaryOp};
+use vig::lexer::LitKind;
+
+fn parse(text: &str) -> Expr {
+    text.parse()
+        .unwrap_or_else(|err| panic!("{}: {:?}", text, err))
+}
+
+fn binary(lhs: Expr, op: BinaryOp, rhs: Expr) -> Expr {
+    Expr::Binary(Box::new(lhs), op, Box::new(rhs))
+}
+
+#[test]
+fn test_precedence() {
+    let (a, b, c) = (Expr::name("a"), Expr::name("b"), Expr::name("c"));
+    assert_eq!(
+        parse("a + b * c"),
+        binary(
+            a.clone(),
+            BinaryOp::Add,
+            binary(b.clone(), BinaryOp::Mul, c.clone())
+        )
+    );
+    assert_eq!(
+        parse("a - b - c"),
+        binary(
+            binary(a.clone(), BinaryOp::Sub, b.clone()),
+            BinaryOp::Sub,
+            c.clone()
+        )
+    );
+    assert_eq!(
+        parse("a = b and b /= c"),
+        binary(
+            binary(a.clone(), BinaryOp::Eq, b.clone()),
+            BinaryOp::And,
+            binary(b.clone(), BinaryOp::Neq, c.clone())
+        )
+    );
+    assert_eq!(
+        parse("a & b sll 2"),
+        binary(
+            binary(a.clone(), BinaryOp::Concat, b.clone()),
+            BinaryOp::Sll,
+            2.into()
+        )
+    );
+    // 符号は乗除算まで、not は一次子だけに掛かる
+    assert_eq!(
+        parse("-a * b + c"),
+        binary(
+            Expr::Unary(
+                UnaryOp::Minus,
+                Box::new(binary(a.clone(), BinaryOp::Mul, b.clone()))
+            ),
+            BinaryOp::Add,
+            c.clone()
+        )
+    );
+    assert_eq!(
+        parse("not a or b"),
+        binary(
+            Expr::Unary(UnaryOp::Not, Box::new(a.clone())),
+            BinaryOp::Or,
+            b.clone()
+        )
+    );
+    assert_eq!(
+        parse("a ?= b"),
+        binary(a.clone(), BinaryOp::MatchEq, b.clone())
+    );
+    assert_eq!(parse("?? a"), Expr::Unary(UnaryOp::Condition, Box::new(a)));
+}
+
+#[test]
+fn test_names_and_aggregates() {
+    let expr = parse("ram(to_integer(addr)).data'length");
+    assert_eq!(expr.base_name(), Some("ram"));
+    let Expr::Attribute(prefix, attribute) = &expr else {
+        panic!("expected an attribute, got {:?}", expr);
+    };
+    assert_eq!(attribute, "length");
+    assert!(matches!(prefix.as_ref(), Expr::Selected(_, field) if field == "data"));
+
+    assert_eq!(
+        parse("(others => '0')"),
+        Expr::Aggregate(vec![Element {
+            choices: vec![Expr::Others],
+            value: Expr::Literal(LitKind::Character, "'0'".to_string()),
+        }])
+    );
+    assert_eq!(
+        parse("q(7 downto 0)"),
+        Expr::Apply(
+            Box::new(Expr::name("q")),
+            vec![Element {
+                choices: Vec::new(),
+                value: Expr::Range(Box::new(7.into()), Direction::Downto, Box::new(0.into())),
+            }]
+        )
+    );
+    assert!(matches!(parse("unsigned'(x\"0F\")"), Expr::Qualified(..)));
+    assert!(matches!(parse("s'reverse_range"), Expr::Attribute(_, a) if a == "reverse_range"));
+    assert!(matches!(parse("work.pkg.all"), Expr::Selected(_, all) if all == "all"));
+    assert_eq!(
+        parse("10 ns"),
+        Expr::Physical("10".to_string(), "ns".to_string())
+    );
+    // 括弧だけの式は括弧を残さない
+    assert_eq!(parse("((a))"), Expr::name("a"));
+}
+
+#[test]
+fn test_display_round_trip() {
+    for text in [
+        "a + b * c",
+        "(a + b) * c",
+        "a - (b - c)",
+        "a - b - c",
+        "-a * b",
+        "(-a) * b",
+        "a * (-b)",
+        "-(-a)",
+        "2 ** (N + 1)",
+        "not (a and b)",
+        "a and b and c",
+        "(a nand b) nand c",
+        "(a and b) or c",
+        "(a = b) = c",
+        "abs x mod 4",
+        "f(x => 1, y => open_n)",
+        "(0 to 3 => '1', others => '0')",
+        "(1 | 2 => a, b)",
+        "rec_t'(a => 1, b => 2)",
+        "integer'image(count)",
+        "x\"FF\" & \"00\"",
+        "0 to N - 1",
+    ] {
+        let expr = parse(text);
+        assert_eq!(expr.to_string(), text);
+        assert_eq!(parse(&expr.to_string()), expr, "{}", text);
+    }
+    assert_eq!(parse("a+b  *c").to_string(), "a + b * c");
+    assert_eq!(Expr::from(-3).to_string(), "-3");
+}
+
+#[test]
+fn test_evaluate() {
+    let value_of = |name: &str| match name {
+        "WIDTH" => Some(8),
+        "DEPTH" => Some(-3),
+        _ => None,
+    };
+    for (text, value) in [
+        ("WIDTH * 2 - 1", Some(15)),
+        ("2 ** WIDTH", Some(256)),
+        ("abs DEPTH", Some(3)),
+        ("DEPTH mod 4", Some(1)),
+        ("DEPTH rem 4", Some(-3)),
+        ("-(WIDTH / 3)", Some(-2)),
+        ("16#10# + 1", Some(17)),
+        ("WIDTH / 0", None),
+        ("UNKNOWN + 1", None),
+        ("WIDTH = 8", None),
+        ("1.5", None),
+    ] {
+        assert_eq!(parse(text).evaluate(&value_of), value, "{}", text);
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    let err = parse_expr_str("a +").unwrap_err();
+    assert_eq!(err.message, "expected expression, found ''");
+    let err = parse_expr_str("a b").unwrap_err();
+    assert_eq!(err.message, "unexpected 'b' after expression");
+    let err = parse_expr_str("f(a, b").unwrap_err();
+    assert_eq!(err.message, "expected RightParen, found Eof ''");
+    let err = parse_expr_str("(a, others)").unwrap_err();
+    assert!(
+        err.message.starts_with("expected Association"),
+        "{}",
+        err.message
+    );
+    let err = parse_expr_str("r.'1'").unwrap_err();
+    assert_eq!(err.message, "expected selected name, found ''1''");
+
+    let deep = format!("{}a{}", "(".repeat(1000), ")".repeat(1000));
+    assert_eq!(
+        parse_expr_str(&deep).unwrap_err().message,
+        "nesting too deep"
+    );
+    let long = vec!["a"; 2000].join(" + ");
+    assert_eq!(
+        parse_expr_str(&long).unwrap_err().message,
+        "expression too long"
+    );
+}
+
+#[test]
+fn test_operators_needing_parentheses() {
+    // 論理演算の混在、nand・nor・関係・シフト・`**` の連続は VHDL の文法で括弧が要る
+    for (text, message) in [
+        ("a and b or c", "'or' after 'and' needs parentheses"),
+        ("a or b xor c", "'xor' after 'or' needs parentheses"),
+        ("a nand b nand c", "'nand' after 'nand' needs parentheses"),
+        ("a nor b nor c", "'nor' after 'nor' needs parentheses"),
+        ("a = b = c", "'=' after '=' needs parentheses"),
+        ("a < b /= c", "'/=' after '<' needs parentheses"),
+        ("a sll 1 srl 2", "'srl' after 'sll' needs parentheses"),
+        ("2**3**2", "'**' after '**' needs parentheses"),
+        ("x = 2 ** 3 ** 2", "'**' after '**' needs parentheses"),
+    ] {
+        let err = parse_expr_str(text).unwrap_err();
+        assert_eq!(err.message, message, "{}", text);
+    }
+    let err = parse_expr_str("a and b or c").unwrap_err();
+    assert_eq!(err.span.start, 8);
+
+    // 括弧を付ければ読め、同じ演算子の論理演算と加減・乗除は連ねられる
+    for text in [
+        "(a and b) or c",
+        "a and (b or c)",
+        "a xor b xor c",
+        "(2 ** 3) ** 2",
+        "2 ** (3 ** 2)",
+        "a = b and c /= d and e",
+        "a + b - c & d",
+        "a * b / c mod d",
+    ] {
+        assert!(parse_expr_str(text).is_ok(), "{}", text);
+    }
+    let err = analyze_vhdl(
+        "architecture a of e is constant X : boolean := a and b or c; begin end architecture;",
+    )
+    .unwrap_err();
+    assert_eq!(err.message, "'or' after 'and' needs parentheses");
+}
+
+#[test]
+fn test_expressions_in_declarations_and_statements() {
+    let source = "\
+entity e is
+    generic (WIDTH : integer := 4 * 2; MASK : std_logic_vector(7 downto 0) := (others => '1'));
+    port (clk : in std_logic; q : out std_logic_vector(WIDTH - 1 downto 0));
+end entity;
+architecture rtl of e is
+    signal r : std_logic_vector(WIDTH - 1 downto 0) := (0 => '1', others => '0');
+begin
+    process (clk)
+    begin
+        q <= r;
+        r <= r(WIDTH - 2 downto 0) & r(WIDTH - 1) after 1 ns;
+        r <= '1' after 1 ns, '0' after 2 ns;
+        r <= transport r after 3 ns;
+    end process;
+end architecture;";
+    let result = analyze_vhdl(source).unwrap();
+    let entity = &result.entities[0];
+    assert_eq!(entity.generic_value("WIDTH"), Some(8));
+    assert_eq!(
+        entity.generics[1]
+            .default_value
+            .as_ref()
+            .unwrap()
+            .to_string(),
+        "(others => '1')"
+    );
+    let signal = &result.architectures[0].signals[0];
+    assert!(
+        matches!(&signal.default_value, Some(Expr::Aggregate(elements)) if elements.len() == 2)
+    );
+
+    let body = &result.architectures[0].processes[0].body;
+    let Statement::SignalAssign { value, after, .. } = &body[1] else {
+        panic!("expected a signal assignment, got {:?}", body[1]);
+    };
+    assert!(matches!(value, Expr::Binary(_, BinaryOp::Concat, _)));
+    assert_eq!(after.as_ref().unwrap().to_string(), "1 ns");
+    // 複数の波形要素や遅延の種類は表記のまま残す
+    assert!(
+        matches!(&body[2], Statement::Other { text, .. } if text.starts_with("r <= '1' after"))
+    );
+    assert!(
+        matches!(&body[3], Statement::Other { text, .. } if text == "r <= transport r after 3 ns")
+    );
+
+    let err = analyze_vhdl(&source.replace("4 * 2", "4 *")).unwrap_err();
+    assert_eq!(err.message, "expected expression, found ';'");
+}
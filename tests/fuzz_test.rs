@@ -0,0 +1,195 @@
+use std::fs;
+
+use vig::fuzz::{run, run_source};
+
+/// 再現できる擬似乱数（xorshift）
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+const FRAGMENTS: &[&str] = &[
+    "(",
+    ")",
+    ";",
+    "end if;",
+    "end ",
+    "\"",
+    "'",
+    "--",
+    "#",
+    "16#",
+    "x\"",
+    "é",
+    "process ",
+    "begin ",
+    "if ",
+    "case ",
+    "when ",
+    "=> ",
+    "loop ",
+    "99999999999999999999999",
+    "99999999999999x\"F\"",
+    "std_logic_vector(99999999999 downto 0)",
+    "to_unsigned(1, 99999999999)",
+];
+
+/// 数値やビット列リテラルの幅指定と置き換える大きな数
+const OVERSIZED: &[&str] = &[
+    "65537",
+    "4294967296",
+    "99999999999999",
+    "18446744073709551616",
+];
+
+/// 定数・ビット列リテラル・幅を取る関数を含む種（インタプリタとリントの定数評価を通る）
+const LITERALS: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity literals is
+    port (
+        sel : in  std_logic_vector(1 downto 0);
+        y   : out std_logic_vector(7 downto 0)
+    );
+end entity;
+
+architecture rtl of literals is
+    constant A : std_logic_vector(7 downto 0) := 8x\"A5\";
+    constant B : std_logic_vector(7 downto 0) := 8sb\"101\";
+    constant C : unsigned(7 downto 0) := to_unsigned(3, 8);
+    signal tmp : unsigned(7 downto 0) := (others => '0');
+begin
+    tmp <= resize(C, 8) + 1;
+    with sel select
+        y <= A when \"00\",
+             B when \"01\",
+             std_logic_vector(tmp) when others;
+end architecture;
+";
+
+#[test]
+fn test_mutated_testdata_never_panics() {
+    let mut corpus: Vec<String> = ["alu", "counter", "traffic_light", "uart_tx"]
+        .iter()
+        .map(|name| fs::read_to_string(format!("testdata/{}.vhd", name)).unwrap())
+        .collect();
+    corpus.push(LITERALS.to_string());
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    for _ in 0..200 {
+        let base = &corpus[rng.below(corpus.len())];
+        // 単語の区切りで分けて、削除・複製・置換・切り詰め・数値の拡大を加える
+        let mut pieces: Vec<String> = base
+            .split_inclusive(|c: char| !c.is_alphanumeric() && c != '_')
+            .map(str::to_string)
+            .collect();
+        for _ in 0..1 + rng.below(6) {
+            if pieces.is_empty() {
+                break;
+            }
+            let i = rng.below(pieces.len());
+            match rng.below(5) {
+                0 => {
+                    let end = (i + rng.below(20)).min(pieces.len());
+                    pieces.drain(i..end);
+                }
+                1 => {
+                    let piece = pieces[rng.below(pieces.len())].clone();
+                    pieces.insert(i, piece);
+                }
+                2 => pieces[i] = FRAGMENTS[rng.below(FRAGMENTS.len())].to_string(),
+                3 => oversize(&mut pieces, &mut rng),
+                _ => pieces.truncate(i),
+            }
+        }
+        run_source(&pieces.concat());
+    }
+}
+
+/// 数値か、ビット列リテラルの基数指定（`x"` など）を1つ選び、大きな数に置き換える・前に付ける
+fn oversize(pieces: &mut [String], rng: &mut Rng) {
+    let candidates: Vec<usize> = (0..pieces.len())
+        .filter(|&i| {
+            let piece = &pieces[i];
+            piece.starts_with(|c: char| c.is_ascii_digit())
+                || (piece.len() <= 3
+                    && piece.ends_with('"')
+                    && piece.starts_with(|c: char| c.is_ascii_alphabetic()))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+    let i = candidates[rng.below(candidates.len())];
+    let huge = OVERSIZED[rng.below(OVERSIZED.len())];
+    let rest = pieces[i].trim_start_matches(|c: char| c.is_ascii_digit() || c == '_');
+    pieces[i] = format!("{}{}", huge, rest);
+}
+
+#[test]
+fn test_invalid_utf8_and_control_bytes() {
+    run(b"entity \xff\xfe is port (a : in \x00std_logic); end entity;");
+    run(&[0xC3]);
+    run(b"");
+}
+
+#[test]
+fn test_unbalanced_end() {
+    // 対応する if のない `end if` と、途中で終わる case
+    run_source(
+        "entity e is port (s : in std_logic); end entity;
+architecture a of e is
+    type state_t is (idle, busy);
+    signal state : state_t;
+begin
+    process begin end if; end process;
+    process(s) begin case state is when idle => state <=",
+    );
+}
+
+#[test]
+fn test_deep_nesting() {
+    let n = 10_000;
+    let parens = format!("{}1{}", "(".repeat(n), ")".repeat(n));
+    run_source(&format!(
+        "entity e is port (x : out integer; y : out std_logic); end entity;
+architecture a of e is
+begin
+    x <= {parens};
+    x <= 1{};
+    y <= {}'1';
+    process begin {} y <= '0'; {} wait; end process;
+    process begin x <= {parens}; end process;
+end architecture;",
+        " + 1".repeat(n),
+        "not ".repeat(n),
+        "if y = '1' then ".repeat(n),
+        "end if; ".repeat(n),
+    ));
+}
+
+#[test]
+fn test_extreme_vector_bounds() {
+    let source = "entity e is port (
+        d : in std_logic_vector(9223372036854775807 downto 0);
+        q : out std_logic_vector(99999999999999999999 downto 0)
+    ); end entity;";
+    run_source(source);
+    let ty = vig::VhdlType::StdLogicVector {
+        high: i64::MAX.into(),
+        low: i64::MIN.into(),
+        ascending: false,
+    };
+    assert_eq!(ty.bit_width(), None);
+}
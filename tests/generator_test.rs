@@ -192,9 +192,8 @@ fn test_alu_tb_input_hints() {
 fn test_custom_clock_period() {
     let source = fs::read_to_string("testdata/counter.vhd").unwrap();
     let result = analyze_vhdl(&source).unwrap();
-    let config = TbConfig {
-        clock_period_ns: 20,
-    };
+    let mut config = TbConfig::default();
+    config.clock_period_ns = 20;
     let tb = generate_testbench(&result.entities[0], &config);
     // 周期20ns -> 半周期10ns
     assert!(tb.contains("wait for 10 ns;"));
@@ -214,3 +213,226 @@ fn test_empty_entity_tb() {
     assert!(!tb.contains("clk_process"));
     assert!(!tb.contains("reset <="));
 }
+
+// === 生成パス テスト ===
+
+fn add_company_header(
+    model: &mut vig::generator::TestbenchModel,
+    entity: &vig::analyzer::EntityDef,
+) {
+    model.header.push("Copyright (c) Example Corp.".to_string());
+    model.header.push(format!("DUT: {}", entity.name));
+}
+
+fn rename_instance(model: &mut vig::generator::TestbenchModel, _: &vig::analyzer::EntityDef) {
+    model.instance.label = "dut".to_string();
+}
+
+fn add_monitor(model: &mut vig::generator::TestbenchModel, _: &vig::analyzer::EntityDef) {
+    let mut monitor = vig::generator::TbProcess::new("monitor");
+    monitor.sensitivity.push("count".to_string());
+    monitor
+        .body
+        .push("report \"count changed\" severity note;".to_string());
+    model.processes.push(monitor);
+}
+
+#[test]
+fn test_generation_passes() {
+    let source = fs::read_to_string("testdata/counter.vhd").unwrap();
+    let result = analyze_vhdl(&source).unwrap();
+    let mut config = TbConfig::default();
+    config.passes = vec![add_company_header, rename_instance, add_monitor];
+    let tb = generate_testbench(&result.entities[0], &config);
+
+    assert!(tb.starts_with("-- Copyright (c) Example Corp.\n-- DUT: counter\n\nlibrary ieee;"));
+    assert!(tb.contains("    dut: counter\n"));
+    assert!(!tb.contains("uut:"));
+    assert!(tb.contains("    monitor: process(count)\n    begin\n        report \"count changed\" severity note;\n    end process monitor;"));
+}
+
+#[test]
+fn test_builder_pass_and_model() {
+    let source = fs::read_to_string("testdata/counter.vhd").unwrap();
+    let result = analyze_vhdl(&source).unwrap();
+    let builder = vig::generator::TestbenchBuilder::new(&result.entities[0]).pass(rename_instance);
+    let model = builder.build_model();
+    assert_eq!(model.instance.label, "dut");
+    assert_eq!(model.name, "counter_tb");
+    assert_eq!(model.processes.len(), 2);
+    assert_eq!(model.render(), builder.build());
+}
+
+#[test]
+fn test_default_severities() {
+    let source = fs::read_to_string("testdata/counter.vhd").unwrap();
+    let result = analyze_vhdl(&source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+    assert!(tb.contains("assert false report \"Simulation finished\" severity note;"));
+    // 制限時間を指定しなければ監視しない
+    assert!(!tb.contains("watchdog"));
+    assert!(!tb.contains("sim_done"));
+}
+
+#[test]
+fn test_configured_severities() {
+    use vig::assertion::Property;
+    use vig::generator::{AssertSeverity, TestbenchBuilder};
+
+    let source = fs::read_to_string("testdata/counter.vhd").unwrap();
+    let result = analyze_vhdl(&source).unwrap();
+    let tb = TestbenchBuilder::new(&result.entities[0])
+        .assertion(Property::parse("always count = count").unwrap())
+        .check_severity(AssertSeverity::Failure)
+        .timeout(50000, AssertSeverity::Error)
+        .finish_severity(AssertSeverity::Failure)
+        .build();
+
+    assert!(tb.contains("report \"assertion failed: always count = count\" severity failure;"));
+    assert!(tb.contains("assert false report \"Simulation finished\" severity failure;"));
+    // 監視プロセスはスティミュラスの終了か制限時間まで待つ
+    assert!(tb.contains("    signal sim_done : boolean := false;"));
+    assert!(tb.contains(
+        "    watchdog: process\n    begin\n        wait until sim_done for 50000 ns;\n        assert sim_done report \"Simulation timed out after 50000 ns\" severity error;\n        wait;\n    end process watchdog;"
+    ));
+    assert!(tb.contains("        sim_done <= true;\n        wait for 0 ns;"));
+}
+
+#[test]
+fn test_testbench_per_architecture() {
+    use vig::generator::TestbenchBuilder;
+
+    let source = "\
+entity counter is
+    port (clk : in std_logic; count : out std_logic_vector(7 downto 0));
+end entity;
+
+architecture rtl of counter is
+    signal count_reg : std_logic_vector(7 downto 0);
+    signal state : state_t;
+    signal enable : std_logic;
+begin
+end architecture;
+
+architecture behavioral of counter is
+begin
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let entity = &result.entities[0];
+    let rtl = &result.architectures[0];
+
+    let tb = TestbenchBuilder::new(entity)
+        .architecture(result.architectures[1].clone())
+        .build();
+    assert!(tb.contains("entity counter_behavioral_tb is"));
+    assert!(tb.contains("    uut: entity work.counter(behavioral)\n"));
+    // 直接実体化なのでコンポーネント宣言は不要
+    assert!(!tb.contains("component counter is"));
+    assert!(!tb.contains("whitebox_monitor"));
+
+    let tb = TestbenchBuilder::new(entity)
+        .architecture(rtl.clone())
+        .whitebox("count_*, state")
+        .build();
+    assert!(tb.contains("entity counter_rtl_tb is"));
+    assert!(tb.contains(
+        "        alias wb_count_reg is << signal .counter_rtl_tb.uut.count_reg : std_logic_vector(7 downto 0) >>;\n        -- state の型 state_t は表示できないため観測しません\n"
+    ));
+    assert!(tb.contains(
+        "        wait on wb_count_reg;\n        if wb_count_reg'event then\n            report \"rtl: count_reg = \" & to_string(wb_count_reg) severity note;\n        end if;\n"
+    ));
+    assert!(!tb.contains("wb_enable"));
+}
+
+#[test]
+fn test_symbolic_widths() {
+    let source = "\
+entity fifo is
+    generic (WIDTH : natural := 8; DEPTH : natural := 2 ** WIDTH; TAG : natural);
+    port (
+        din   : in  std_logic_vector(WIDTH - 1 downto 0);
+        count : out std_logic_vector(DEPTH downto 1);
+        tag   : out std_logic_vector(TAG - 1 downto 0)
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+
+    // 既定値の分かるジェネリックは評価し、分からないものは式のまま
+    assert!(tb.contains("signal din : std_logic_vector(7 downto 0)"));
+    assert!(tb.contains("signal count : std_logic_vector(256 downto 1)"));
+    assert!(tb.contains("signal tag : std_logic_vector(TAG - 1 downto 0)"));
+}
+
+#[test]
+fn test_ascending_ranges() {
+    let source = "\
+entity swap is
+    generic (N : natural := 4);
+    port (
+        a2 : in  std_logic_vector(0 to 7);
+        b  : out unsigned(1 to N)
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+
+    // `to` の範囲は向きを保ったまま宣言する
+    assert!(tb.contains("signal a2 : std_logic_vector(0 to 7)"));
+    assert!(tb.contains("signal b : unsigned(1 to 4)"));
+    assert!(tb.contains("a2 : in std_logic_vector(0 to 7)"));
+}
+
+#[test]
+fn test_scalar_range_defaults() {
+    let source = "\
+entity e is
+    port (
+        n : in natural range 0 to 15;
+        i : in integer range -5 to 5;
+        p : in positive range 3 to 9
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+
+    // 範囲の制約は宣言に残し、初期値は左端（VHDL の既定値）にする
+    assert!(tb.contains("signal n : natural range 0 to 15 := 0;"));
+    assert!(tb.contains("signal i : integer range -5 to 5 := -5;"));
+    assert!(tb.contains("signal p : positive range 3 to 9 := 3;"));
+}
+
+#[test]
+fn test_builtin_type_defaults() {
+    let source = "\
+entity e is
+    port (
+        clk : in std_logic;
+        a : in unsigned(7 downto 0);
+        b : in signed(3 downto 0);
+        c : in natural;
+        d : in positive;
+        r : in real;
+        t : in time;
+        s : in bit;
+        v : in bit_vector(1 downto 0);
+        m : in string(1 to 4)
+    );
+end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+    for decl in [
+        "signal a : unsigned(7 downto 0) := (others => '0');",
+        "signal b : signed(3 downto 0) := (others => '0');",
+        "signal c : natural := 0;",
+        "signal d : positive := 1;",
+        "signal r : real := 0.0;",
+        "signal t : time := 0 ns;",
+        "signal s : bit := '0';",
+        "signal v : bit_vector(1 downto 0) := (others => '0');",
+        "signal m : string(1 to 4) := (others => ' ');",
+    ] {
+        assert!(tb.contains(decl), "missing {}\n{}", decl, tb);
+    }
+}
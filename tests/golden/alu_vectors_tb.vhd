@@ -0,0 +1,124 @@
+-- alu の真理値表テストベンチ
+-- vig vectors により生成
+
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity alu_tb is
+end entity alu_tb;
+
+architecture testbench of alu_tb is
+
+    component alu is
+        port (
+            clk : in std_logic;
+            reset : in std_logic;
+            op_a : in std_logic_vector(3 downto 0);
+            op_b : in std_logic_vector(3 downto 0);
+            alu_op : in std_logic_vector(2 downto 0);
+            result : out std_logic_vector(3 downto 0);
+            carry_out : out std_logic;
+            zero_flag : out std_logic
+        );
+    end component alu;
+
+    signal clk : std_logic := '0';
+    signal reset : std_logic := '0';
+    signal op_a : std_logic_vector(3 downto 0) := (others => '0');
+    signal op_b : std_logic_vector(3 downto 0) := (others => '0');
+    signal alu_op : std_logic_vector(2 downto 0) := (others => '0');
+    signal result : std_logic_vector(3 downto 0) := (others => '0');
+    signal carry_out : std_logic := '0';
+    signal zero_flag : std_logic := '0';
+
+begin
+
+    uut: alu
+        port map (
+            clk => clk,
+            reset => reset,
+            op_a => op_a,
+            op_b => op_b,
+            alu_op => alu_op,
+            result => result,
+            carry_out => carry_out,
+            zero_flag => zero_flag
+        );
+
+    -- クロック生成 (周期 10 ns)
+    clk_process: process
+    begin
+        clk <= '0';
+        wait for 5 ns;
+        clk <= '1';
+        wait for 5 ns;
+    end process clk_process;
+
+    -- 真理値表の印加と検査
+    stim_process: process
+    begin
+        -- リセット
+        reset <= '1';
+        wait for 20 ns;
+        reset <= '0';
+        wait for 20 ns;
+
+        -- 真理値表 (8 行)
+        -- 行 1
+        op_a <= "0011";
+        op_b <= "0001";
+        alu_op <= "000";
+        wait for 10 ns;
+        assert result = "0100" report "row 1: result mismatch" severity error;
+        assert carry_out = '0' report "row 1: carry_out mismatch" severity error;
+        -- 行 2
+        op_a <= "1001";
+        op_b <= "1001";
+        alu_op <= "000";
+        wait for 10 ns;
+        assert result = "0010" report "row 2: result mismatch" severity error;
+        assert carry_out = '1' report "row 2: carry_out mismatch" severity error;
+        -- 行 3
+        op_a <= "0101";
+        op_b <= "0011";
+        alu_op <= "001";
+        wait for 10 ns;
+        assert result = "0010" report "row 3: result mismatch" severity error;
+        assert carry_out = '0' report "row 3: carry_out mismatch" severity error;
+        -- 行 4
+        op_a <= "1100";
+        op_b <= "1010";
+        alu_op <= "010";
+        wait for 10 ns;
+        assert result = "1000" report "row 4: result mismatch" severity error;
+        -- 行 5
+        op_a <= "1100";
+        op_b <= "1010";
+        alu_op <= "011";
+        wait for 10 ns;
+        assert result = "1110" report "row 5: result mismatch" severity error;
+        -- 行 6
+        op_a <= "1100";
+        op_b <= "1010";
+        alu_op <= "100";
+        wait for 10 ns;
+        assert result = "0110" report "row 6: result mismatch" severity error;
+        -- 行 7
+        op_a <= "1111";
+        op_b <= "0000";
+        alu_op <= "101";
+        wait for 10 ns;
+        assert result = "0000" report "row 7: result mismatch" severity error;
+        -- 行 8
+        alu_op <= "110";
+        wait for 10 ns;
+        assert result = "0000" report "row 8: result mismatch" severity error;
+        assert carry_out = '0' report "row 8: carry_out mismatch" severity error;
+
+        -- シミュレーション終了
+        assert false report "Simulation finished" severity note;
+        wait;
+    end process stim_process;
+
+end architecture testbench;
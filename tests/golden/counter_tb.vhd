@@ -0,0 +1,57 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+entity counter_tb is
+end entity counter_tb;
+
+architecture testbench of counter_tb is
+
+    component counter is
+        port (
+            clk : in std_logic;
+            reset : in std_logic;
+            count : out std_logic_vector(7 downto 0)
+        );
+    end component counter;
+
+    signal clk : std_logic := '0';
+    signal reset : std_logic := '0';
+    signal count : std_logic_vector(7 downto 0) := (others => '0');
+
+begin
+
+    uut: counter
+        port map (
+            clk => clk,
+            reset => reset,
+            count => count
+        );
+
+    -- クロック生成 (周期 10 ns)
+    clk_process: process
+    begin
+        clk <= '0';
+        wait for 5 ns;
+        clk <= '1';
+        wait for 5 ns;
+    end process clk_process;
+
+    -- テストシナリオ
+    stim_process: process
+    begin
+        -- リセット
+        reset <= '1';
+        wait for 20 ns;
+        reset <= '0';
+        wait for 20 ns;
+
+        -- TODO: テストパターンを記述
+        wait for 100 ns;
+
+        -- シミュレーション終了
+        assert false report "Simulation finished" severity note;
+        wait;
+    end process stim_process;
+
+end architecture testbench;
@@ -0,0 +1,59 @@
+use vig::analyzer::analyze_file;
+use vig::generator::{TbConfig, generate_testbench};
+use vig::testing::{assert_golden, check_golden, diff, normalize};
+use vig::vectors::{self, TruthTable};
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("a  \r\nb\t\r\n\r\n\n"), "a\nb\n");
+    assert_eq!(normalize("a\rb"), "a\nb\n");
+    assert_eq!(normalize("\n\n"), "");
+    assert_eq!(normalize(""), "");
+}
+
+#[test]
+fn test_diff() {
+    assert_eq!(diff("a\nb\n", "a\nb\n"), "");
+    let text = diff("a\nb\nc\nd\n", "a\nc\nx\nd\n");
+    assert_eq!(text, "@@ -1,4 +1,4 @@\n a\n-b\n c\n+x\n d\n");
+    // 離れた変更は別の区間になり、行番号は各ファイルでの位置
+    let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+    let new = old.replacen("2\n", "two\n", 1).replace("18\n", "");
+    let text = diff(&old, &new);
+    assert!(text.starts_with("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n"));
+    assert!(text.contains("@@ -15,6 +15,5 @@\n 15\n 16\n 17\n-18\n 19\n 20\n"));
+}
+
+#[test]
+fn test_check_golden_reports_diff() {
+    // 更新モードでは比較しないため検査できない
+    if std::env::var_os(vig::testing::UPDATE_ENV).is_some() {
+        return;
+    }
+    let path = std::env::temp_dir().join("vig_golden_test.txt");
+    std::fs::write(&path, "line 1\r\nline 2  \r\n").unwrap();
+    assert!(check_golden(&path, "line 1\nline 2\n").is_ok());
+    let err = check_golden(&path, "line 1\nline 3\n").unwrap_err();
+    assert!(err.contains("-line 2\n+line 3\n"));
+    std::fs::remove_file(&path).unwrap();
+
+    let err = check_golden("tests/golden/missing.vhd", "x").unwrap_err();
+    assert!(err.contains("VIG_UPDATE_GOLDEN=1"));
+}
+
+#[test]
+fn test_counter_tb_golden() {
+    let result = analyze_file("testdata/counter.vhd").unwrap();
+    let tb = generate_testbench(&result.entities[0], &TbConfig::default());
+    assert_golden("tests/golden/counter_tb.vhd", &tb);
+}
+
+#[test]
+fn test_alu_vectors_tb_golden() {
+    let result = analyze_file("testdata/alu.vhd").unwrap();
+    let source = std::fs::read_to_string("testdata/alu_vectors.csv").unwrap();
+    let table = TruthTable::from_csv(&source).unwrap();
+    let tb =
+        vectors::generate_testbench(&result.entities[0], &table, &TbConfig::default()).unwrap();
+    assert_golden("tests/golden/alu_vectors_tb.vhd", &tb);
+}
@@ -0,0 +1,135 @@
+use vig::analyze;
+use vig::generator::{TbConfig, TestbenchBuilder, generate_testbench};
+use vig::ident::{Namer, bare, escape, file_stem, is_reserved, normalize, suffixed};
+use vig::interface::InterfaceDef;
+use vig::style::TbStyle;
+
+#[test]
+fn test_reserved_words() {
+    assert!(is_reserved("end"));
+    assert!(is_reserved("Signal"));
+    assert!(is_reserved("xnor"));
+    assert!(!is_reserved("uut"));
+    assert!(!is_reserved("std_logic"));
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("Data_In"), "data_in");
+    assert_eq!(normalize("\\Data\\"), "\\Data\\");
+    assert_ne!(normalize("\\data\\"), normalize("data"));
+}
+
+#[test]
+fn test_escape_and_suffix() {
+    assert_eq!(escape("END"), "\\END\\");
+    assert_eq!(escape("count"), "count");
+    assert_eq!(bare("\\end\\"), "end");
+    assert_eq!(bare("count"), "count");
+    assert_eq!(suffixed("\\out\\", "_last"), "out_last");
+    // 接尾辞を付けても予約語なら拡張識別子のまま
+    assert_eq!(suffixed("\\in\\", "out"), "\\inout\\");
+    // 基本識別子として書けない名前も拡張識別子にする
+    assert_eq!(escape("data-in"), "\\data-in\\");
+    assert_eq!(escape("a\\b"), "\\a\\\\b\\");
+    assert_eq!(escape("\\Bus-Signal\\"), "\\Bus-Signal\\");
+    assert_eq!(suffixed("\\Bus-Signal\\", "_last"), "\\Bus-Signal_last\\");
+    assert_eq!(suffixed("\\Data\\", "_last"), "Data_last");
+    // ファイル名には拡張識別子の中身を、パスに使えない文字を `_` にして使う
+    assert_eq!(file_stem("\\my top\\"), "my_top");
+    assert_eq!(file_stem("\\..\\"), "__");
+    assert_eq!(file_stem("\\a\\\\b\\"), "a__b");
+}
+
+#[test]
+fn test_namer_is_deterministic() {
+    let mut names = Namer::new(["UUT", "uut_1", "clk"]);
+    assert!(names.is_taken("Clk"));
+    assert_eq!(names.fresh("uut"), "uut_2");
+    assert_eq!(names.fresh("uut"), "uut_3");
+    assert_eq!(names.fresh("process"), "process_1");
+    assert_eq!(names.fresh("sim_done"), "sim_done");
+    assert_eq!(names.fresh("sim_done"), "sim_done_1");
+}
+
+#[test]
+fn test_generated_names_avoid_ports() {
+    let source = "\
+entity counter is
+    port (
+        clk      : in  std_logic;
+        uut      : in  std_logic;
+        sim_done : out std_logic;
+        counter_tb : out std_logic;
+        stim_process : out std_logic
+    );
+end entity;
+";
+    let entity = analyze(source).unwrap().entities.remove(0);
+    let mut config = TbConfig::default();
+    config.timeout_ns = Some(1000);
+    let tb = generate_testbench(&entity, &config);
+    assert!(tb.contains("entity counter_tb_1 is"));
+    assert!(tb.contains("    uut_1: counter"));
+    assert!(tb.contains("    signal sim_done_1 : boolean := false;"));
+    assert!(tb.contains("        sim_done_1 <= true;"));
+    assert!(tb.contains("        wait until sim_done_1 for 1000 ns;"));
+    assert!(tb.contains("    stim_process_1: process\n"));
+    assert!(tb.contains("    end process stim_process_1;"));
+    assert!(tb.contains("    clk_process: process\n"));
+
+    // 生成したテストベンチはそのまま解析できる
+    let result = analyze(&tb).unwrap();
+    assert_eq!(result.entities[0].name, "counter_tb_1");
+}
+
+#[test]
+fn test_reserved_dut_names_are_escaped() {
+    let source = r#"{
+  "vig_interface": 1,
+  "name": "end",
+  "ports": [
+    { "name": "clk", "direction": "in", "type": { "name": "std_logic" } },
+    { "name": "in", "direction": "in", "type": { "name": "std_logic" } },
+    { "name": "out", "direction": "out", "type": { "name": "std_logic" } }
+  ]
+}"#;
+    let entity = InterfaceDef::from_json(source).unwrap().entity;
+    let tb = TestbenchBuilder::new(&entity)
+        .style(TbStyle::ProcedureBased)
+        .toggle_coverage(true)
+        .build();
+    assert!(tb.contains("entity end_tb is"));
+    assert!(tb.contains("    component \\end\\ is"));
+    assert!(tb.contains("    signal \\in\\ : std_logic := '0';"));
+    assert!(tb.contains("            \\in\\ => \\in\\,"));
+    assert!(tb.contains("procedure apply(in_value : std_logic) is"));
+    assert!(tb.contains("variable out_toggles : natural := 0;"));
+}
+
+#[test]
+fn test_extended_identifiers_keep_their_case() {
+    let source = r"
+entity \Net-List\ is
+    port (
+        \CLK\ : in std_logic;
+        \Bus-Signal\ : in std_logic_vector(7 downto 0);
+        \bus-signal\ : out std_logic
+    );
+end entity;
+";
+    let entity = &analyze(source).unwrap().entities[0];
+    assert_eq!(entity.name, r"\Net-List\");
+    assert_eq!(entity.ports[1].name, r"\Bus-Signal\");
+    // 拡張識別子は大文字・小文字を区別するので重複ではない
+    assert_eq!(entity.validate(), []);
+
+    let tb = generate_testbench(entity, &TbConfig::default());
+    assert!(tb.contains("entity \\Net-List_tb\\ is"));
+    assert!(tb.contains("    signal \\Bus-Signal\\ : std_logic_vector(7 downto 0)"));
+    assert!(tb.contains("            \\bus-signal\\ => \\bus-signal\\"));
+    assert!(tb.contains("        \\CLK\\ <= '1';"));
+
+    let result = analyze(&tb).unwrap();
+    assert_eq!(result.entities[0].name, r"\Net-List_tb\");
+}
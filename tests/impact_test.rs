@@ -0,0 +1,196 @@
+use vig::analyzer::{EntityDef, VhdlType::*};
+use vig::impact::{PortIssueKind, ReferenceKind, analyze_impact};
+
+const TOP: &str = "architecture rtl of top is
+    component fifo
+        port (
+            clk : in std_logic;
+            din : in std_logic_vector(7 downto 0);
+            dout : out std_logic_vector(15 downto 0);
+            full : out std_logic
+        );
+    end component;
+    signal dd : std_logic_vector(7 downto 0);
+    signal q : std_logic_vector(15 downto 0);
+begin
+    u_fifo: fifo port map (clk => clk, din => dd, dout => q, full => open);
+    u_other: other port map (a => b);
+end architecture;";
+
+const TB: &str = "entity fifo_tb is end entity;
+architecture sim of fifo_tb is
+    signal clk : std_logic;
+    signal en : std_logic;
+    signal din : std_logic_vector(15 downto 0);
+begin
+    uut: entity work.fifo port map (clk, en, din, open);
+end architecture;";
+
+/// din を 16 ビットにし、en を加え、full を外した fifo
+fn fifo() -> EntityDef {
+    EntityDef::builder("fifo")
+        .input("clk", StdLogic)
+        .input("en", StdLogic)
+        .input(
+            "din",
+            StdLogicVector {
+                high: 15.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .output(
+            "dout",
+            StdLogicVector {
+                high: 15.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .build()
+}
+
+fn kinds(issues: &[vig::impact::PortIssue]) -> Vec<(&str, &PortIssueKind)> {
+    issues.iter().map(|i| (i.port.as_str(), &i.kind)).collect()
+}
+
+#[test]
+fn test_finds_all_references() {
+    let arch = "architecture rtl of fifo is begin end architecture;";
+    let impact = analyze_impact(
+        &fifo(),
+        &[("fifo.vhd", arch), ("top.vhd", TOP), ("fifo_tb.vhd", TB)],
+    );
+    let found: Vec<_> = impact
+        .references
+        .iter()
+        .map(|r| (r.file, r.kind, r.name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (0, ReferenceKind::Architecture, "rtl"),
+            (1, ReferenceKind::Component, "fifo"),
+            (1, ReferenceKind::Instance, "u_fifo"),
+            (2, ReferenceKind::Instance, "uut"),
+        ]
+    );
+    assert!(impact.references[0].is_compatible());
+    assert_eq!(impact.references[2].owner.as_deref(), Some("top"));
+    assert!(!impact.references[2].in_testbench);
+    assert!(impact.references[3].in_testbench);
+}
+
+#[test]
+fn test_component_declaration_issues() {
+    let impact = analyze_impact(&fifo(), &[("top.vhd", TOP)]);
+    let component = &impact.references[0];
+    assert_eq!(
+        kinds(&component.issues),
+        [
+            (
+                "din",
+                &PortIssueKind::Retyped {
+                    found: "std_logic_vector(7 downto 0)".to_string()
+                }
+            ),
+            ("full", &PortIssueKind::Extra),
+            ("en", &PortIssueKind::Missing),
+        ]
+    );
+    let messages: Vec<_> = component
+        .diagnostics(&fifo())
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+    assert_eq!(
+        messages[2],
+        "port 'en' of 'fifo' is not declared in the component"
+    );
+}
+
+#[test]
+fn test_named_port_map_issues() {
+    let impact = analyze_impact(&fifo(), &[("top.vhd", TOP)]);
+    let instance = &impact.references[1];
+    assert_eq!(
+        kinds(&instance.issues),
+        [
+            (
+                "din",
+                &PortIssueKind::Retyped {
+                    found: "std_logic_vector(7 downto 0)".to_string()
+                }
+            ),
+            ("full", &PortIssueKind::Extra),
+            ("en", &PortIssueKind::Missing),
+        ]
+    );
+    assert_eq!(
+        instance.diagnostics(&fifo())[2].message,
+        "input port 'en' of 'fifo' is not connected"
+    );
+    assert_eq!(impact.incompatible().len(), 2);
+}
+
+#[test]
+fn test_positional_port_map() {
+    let impact = analyze_impact(&fifo(), &[("fifo_tb.vhd", TB)]);
+    assert!(
+        impact.references[0].is_compatible(),
+        "{:?}",
+        impact.references[0].issues
+    );
+
+    let shorter = EntityDef::builder("fifo").input("clk", StdLogic).build();
+    let impact = analyze_impact(&shorter, &[("fifo_tb.vhd", TB)]);
+    let ports: Vec<_> = impact.references[0]
+        .issues
+        .iter()
+        .map(|i| i.port.as_str())
+        .collect();
+    assert_eq!(ports, ["#2", "#3", "#4"]);
+}
+
+#[test]
+fn test_symbolic_widths_are_not_reported() {
+    let source = "architecture rtl of top is
+    signal d : std_logic_vector(WIDTH-1 downto 0);
+begin
+    u: entity work.fifo port map (clk => c, en => e, din => d);
+end architecture;";
+    let impact = analyze_impact(&fifo(), &[("top.vhd", source)]);
+    assert!(
+        impact.references[0].is_compatible(),
+        "{:?}",
+        impact.references[0].issues
+    );
+
+    // 型の名前が違えば報告する
+    let source = source.replace("std_logic_vector(WIDTH-1", "unsigned(WIDTH-1");
+    let impact = analyze_impact(&fifo(), &[("top.vhd", &source)]);
+    assert_eq!(impact.references[0].issues.len(), 1);
+}
+
+#[test]
+fn test_package_components_have_no_owner() {
+    let source = "architecture rtl of top is begin end architecture;
+package comps is
+    component fifo port (clk : in std_logic; en : in std_logic;
+        din : in std_logic_vector(15 downto 0); dout : out std_logic_vector(15 downto 0));
+    end component;
+end package;";
+    let impact = analyze_impact(&fifo(), &[("comps.vhd", source)]);
+    assert_eq!(impact.references.len(), 1);
+    assert_eq!(impact.references[0].owner, None);
+    assert!(impact.references[0].is_compatible());
+}
+
+#[test]
+fn test_verilog_instances_are_listed() {
+    let source = "module top (input clk);\nfifo u_fifo (.clk(clk));\nendmodule";
+    let impact = analyze_impact(&fifo(), &[("top.v", source)]);
+    assert_eq!(impact.references.len(), 1);
+    assert_eq!(impact.references[0].name, "u_fifo");
+    assert_eq!(impact.references[0].owner.as_deref(), Some("top"));
+}
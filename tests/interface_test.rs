@@ -0,0 +1,210 @@
+use std::fs;
+use vig::analyzer::{PortDirection, VhdlType, analyze_vhdl};
+use vig::interface::InterfaceDef;
+
+fn interface_from_file(path: &str) -> InterfaceDef {
+    let source = fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path));
+    let result = analyze_vhdl(&source).expect("analysis failed");
+    InterfaceDef::from_entity(&result.entities[0])
+}
+
+#[test]
+fn test_uart_clock_reset_and_bus() {
+    let iface = interface_from_file("testdata/uart_tx.vhd");
+    assert_eq!(iface.clocks, vec!["clk".to_string()]);
+    assert_eq!(iface.resets.len(), 1);
+    assert_eq!(iface.resets[0].name, "reset");
+    assert!(iface.resets[0].active_high);
+
+    // tx_* のポートが1つのバスにまとめられる
+    assert_eq!(iface.buses.len(), 1);
+    assert_eq!(iface.buses[0].name, "tx");
+    assert_eq!(iface.buses[0].ports.len(), 5);
+    assert_eq!(iface.buses[0].ports[0].name, "tx_start");
+    assert_eq!(iface.buses[0].ports[0].role, "start");
+}
+
+#[test]
+fn test_reset_polarity_from_name() {
+    let polarity = |name: &str| {
+        let source = format!("entity e is port ({name} : in std_logic); end entity;");
+        let result = analyze_vhdl(&source).unwrap();
+        let iface = InterfaceDef::from_entity(&result.entities[0]);
+        assert_eq!(iface.resets.len(), 1, "{name}");
+        iface.resets[0].active_high
+    };
+    // 途中や別の語の末尾の n は負論理の印ではない
+    for name in ["rst", "reset", "rst_in", "reset_main", "rst_gen"] {
+        assert!(polarity(name), "{name} should be active-high");
+    }
+    for name in [
+        "rst_n", "RST_N", "rst_b", "rst_l", "rst_ni", "rstn", "aresetn", "nrst", "n_reset",
+    ] {
+        assert!(!polarity(name), "{name} should be active-low");
+    }
+}
+
+#[test]
+fn test_export_json_shape() {
+    let iface = interface_from_file("testdata/counter.vhd");
+    let json = iface.to_json();
+    assert!(json.contains("\"vig_interface\": 1"));
+    assert!(json.contains("\"name\": \"counter\""));
+    assert!(json.contains("\"name\": \"std_logic_vector\""));
+    assert!(json.contains("\"high\": 7"));
+    assert!(json.ends_with("}\n"));
+}
+
+#[test]
+fn test_round_trip() {
+    let iface = interface_from_file("testdata/alu.vhd");
+    let back = InterfaceDef::from_json(&iface.to_json()).unwrap();
+    assert_eq!(back.entity.name, "alu");
+    assert_eq!(back.clocks, iface.clocks);
+    assert_eq!(back.resets, iface.resets);
+    assert_eq!(back.buses, iface.buses);
+    for (a, b) in back.entity.ports.iter().zip(&iface.entity.ports) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.direction, b.direction);
+        assert_eq!(a.vhdl_type, b.vhdl_type);
+    }
+}
+
+#[test]
+fn test_import_minimal() {
+    let source = r#"{
+        "vig_interface": 1,
+        "name": "blinker",
+        "ports": [
+            { "name": "clk", "direction": "in", "type": { "name": "std_logic" } },
+            { "name": "rst_n", "direction": "in", "type": { "name": "std_logic" } },
+            { "name": "leds", "direction": "out",
+              "type": { "name": "std_logic_vector", "high": 3, "low": 0 } },
+            { "name": "mode", "direction": "in", "type": { "name": "mode_t" } }
+        ],
+        "resets": [{ "name": "rst_n", "active": "low" }]
+    }"#;
+    let iface = InterfaceDef::from_json(source).unwrap();
+    assert_eq!(iface.entity.name, "blinker");
+    assert_eq!(iface.entity.ports.len(), 4);
+    assert_eq!(iface.entity.ports[2].direction, PortDirection::Out);
+    assert_eq!(
+        iface.entity.ports[2].vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 3.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    assert_eq!(
+        iface.entity.ports[3].vhdl_type,
+        VhdlType::Other("mode_t".to_string())
+    );
+    assert!(!iface.resets[0].active_high);
+    assert!(iface.clocks.is_empty());
+}
+
+#[test]
+fn test_import_errors() {
+    let missing_version = r#"{ "name": "x", "ports": [] }"#;
+    assert!(InterfaceDef::from_json(missing_version).is_err());
+
+    let bad_direction = r#"{ "vig_interface": 1, "name": "x",
+        "ports": [{ "name": "a", "direction": "sideways", "type": { "name": "std_logic" } }] }"#;
+    let err = InterfaceDef::from_json(bad_direction).unwrap_err();
+    assert!(err.message.contains("direction"));
+
+    let unknown_clock = r#"{ "vig_interface": 1, "name": "x", "ports": [], "clocks": ["clk"] }"#;
+    let err = InterfaceDef::from_json(unknown_clock).unwrap_err();
+    assert!(err.message.contains("unknown port 'clk'"));
+
+    let not_json = "{ \"vig_interface\": 1, ";
+    assert!(InterfaceDef::from_json(not_json).is_err());
+}
+
+#[test]
+fn test_symbolic_bounds_as_strings() {
+    let source = "entity e is port (d : in std_logic_vector(WIDTH - 1 downto 0)); end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let iface = InterfaceDef::from_entity(&result.entities[0]);
+    let json = iface.to_json();
+    assert!(json.contains("\"high\": \"WIDTH - 1\""));
+    assert!(json.contains("\"low\": 0"));
+    let back = InterfaceDef::from_json(&json).unwrap();
+    assert_eq!(
+        back.entity.ports[0].vhdl_type,
+        iface.entity.ports[0].vhdl_type
+    );
+}
+
+#[test]
+fn test_round_trip_generics_and_bus_roles() {
+    let source = "entity fifo is
+        generic (W : positive := 8; DEPTH : natural := W * 2);
+        port (clk : in std_logic;
+              wr_data : in std_logic_vector(W - 1 downto 0);
+              wr_en : in std_logic;
+              level : out natural range 0 to DEPTH);
+    end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let iface = InterfaceDef::from_entity(&result.entities[0]);
+    let json = iface.to_json();
+    assert!(json.contains("\"default\": \"W * 2\""));
+    assert!(json.contains("\"bus\": \"wr\""));
+    assert!(json.contains("\"role\": \"en\""));
+
+    let back = InterfaceDef::from_json(&json).unwrap();
+    assert_eq!(back.entity.generics.len(), 2);
+    for (a, b) in back.entity.generics.iter().zip(&iface.entity.generics) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.vhdl_type, b.vhdl_type);
+        assert_eq!(a.default_value, b.default_value);
+    }
+    assert_eq!(back.buses, iface.buses);
+
+    // 記号の範囲をジェネリックの既定値で評価できる
+    let resolved = back.entity.with_generic_bounds();
+    assert_eq!(resolved.ports[1].vhdl_type.bit_width(), Some(8));
+    assert_eq!(back.entity.generic_value("DEPTH"), Some(16));
+}
+
+#[test]
+fn test_import_bus_roles() {
+    let source = r#"{
+        "vig_interface": 1,
+        "name": "slave",
+        "generics": [{ "name": "AW", "type": { "name": "integer" }, "default": 4 }],
+        "ports": [
+            { "name": "addr", "direction": "in", "bus": "axi", "role": "awaddr",
+              "type": { "name": "std_logic_vector", "high": "AW - 1", "low": 0 } },
+            { "name": "axi_valid", "direction": "in", "bus": "axi",
+              "type": { "name": "std_logic" } }
+        ]
+    }"#;
+    let iface = InterfaceDef::from_json(source).unwrap();
+    assert_eq!(iface.buses.len(), 1);
+    assert_eq!(iface.buses[0].name, "axi");
+    assert_eq!(iface.buses[0].ports[0].role, "awaddr");
+    assert_eq!(iface.buses[0].ports[1].role, "valid");
+    assert_eq!(iface.entity.generic_value("AW"), Some(4));
+
+    let bad_default = source.replace("\"default\": 4", "\"default\": \"4 +\"");
+    assert!(InterfaceDef::from_json(&bad_default).is_err());
+}
+
+#[test]
+fn test_builtin_types_round_trip() {
+    let source = "entity e is port (u : in unsigned(3 downto 0); n : in natural; \
+                  s : in string(1 to 8); b : out bit; \
+                  i : in integer range -5 to 5; c : in natural range 7 downto 0); end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    let iface = InterfaceDef::from_entity(&result.entities[0]);
+    let json = iface.to_json();
+    assert!(json.contains("\"name\": \"unsigned\""));
+    assert!(json.contains("\"left\": 1"));
+    assert!(json.contains("\"left\": -5"));
+    let back = InterfaceDef::from_json(&json).unwrap();
+    for (a, b) in back.entity.ports.iter().zip(&iface.entity.ports) {
+        assert_eq!(a.vhdl_type, b.vhdl_type);
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs;
+
+use vig::analyzer::analyze_vhdl;
+use vig::intern::{InternedAst, Interner, TypeNode};
+
+#[test]
+fn test_interner_deduplicates() {
+    let mut interner = Interner::new();
+    let a = interner.intern("clk");
+    let b = interner.intern("rst");
+    assert_eq!(interner.intern("clk"), a);
+    assert_ne!(a, b);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.resolve(b), "rst");
+    assert_eq!(interner.get("rst"), Some(b));
+    assert_eq!(interner.get("data"), None);
+}
+
+#[test]
+fn test_round_trip() {
+    let source = fs::read_to_string("testdata/counter.vhd").unwrap();
+    let result = analyze_vhdl(&source).unwrap();
+    let ast = InternedAst::from_result(&result);
+    assert_eq!(ast.to_result(), result);
+}
+
+#[test]
+fn test_shared_names_and_lookup() {
+    let source = "\
+entity a is port (clk : in std_logic; d : in word_t); end entity;
+entity b is port (CLK : in std_logic; d : out word_t); end entity;
+architecture rtl of A is
+    signal d_q : word_t := x\"0\";
+begin
+end architecture;
+";
+    let ast = InternedAst::analyze(source).unwrap();
+    // a, A, b, clk, CLK, d, word_t, rtl, d_q, x"0"（小文字のキーは綴りと共有）
+    assert_eq!(ast.interner().len(), 10);
+
+    let a = ast.entity("A").unwrap();
+    let b = ast.entity("B").unwrap();
+    assert_eq!(ast.ports(a)[1].name, ast.ports(b)[1].name);
+    assert_eq!(ast.ports(a)[1].vhdl_type, ast.ports(b)[1].vhdl_type);
+    let TypeNode::Other(ty) = ast.ports(a)[1].vhdl_type else {
+        panic!("expected a named type");
+    };
+    assert_eq!(ast.interner().resolve(ty), "word_t");
+
+    let archs: Vec<_> = ast.architectures_of(a).collect();
+    assert_eq!(archs.len(), 1);
+    assert_eq!(ast.resolve(ast.signals(archs[0])[0].name), "d_q");
+    assert_eq!(ast.architectures_of(b).count(), 0);
+    assert!(ast.entity("c").is_none());
+}
+
+#[test]
+fn test_round_trip_generics() {
+    let source = "\
+entity g is
+    generic (WIDTH : integer := 8; FAST : boolean);
+    port (d : in std_logic_vector(WIDTH - 1 downto 0));
+end entity;
+architecture rtl of g is
+    constant LAST : natural := WIDTH - 1;
+begin
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let ast = InternedAst::from_result(&result);
+    let entity = ast.entity("g").unwrap();
+    let generics = ast.generics(entity);
+    assert_eq!(generics.len(), 2);
+    assert_eq!(ast.resolve(generics[0].name), "WIDTH");
+    assert_eq!(
+        generics[0].default_value.map(|v| ast.interner().resolve(v)),
+        Some("8")
+    );
+    assert_eq!(generics[1].default_value, None);
+    let constants = ast.constants(&ast.architectures()[0]);
+    assert_eq!(ast.resolve(constants[0].name), "LAST");
+    assert_eq!(ast.to_result(), result);
+}
+
+#[test]
+fn test_round_trip_instances() {
+    let source = "\
+entity top is port (clk : in std_logic); end entity;
+architecture rtl of top is
+begin
+    u0 : counter generic map (8) port map (clk => clk, q => open);
+    u1 : entity work.counter(rtl) port map (clk);
+end architecture;
+";
+    let result = analyze_vhdl(source).unwrap();
+    let ast = InternedAst::from_result(&result);
+    let instances = ast.instances(&ast.architectures()[0]);
+    assert_eq!(instances.len(), 2);
+    assert_eq!(ast.resolve(instances[0].label), "u0");
+    let port_map = ast.port_map(&instances[0]);
+    assert_eq!(
+        port_map[1].formal.map(|f| ast.interner().resolve(f)),
+        Some("q")
+    );
+    assert_eq!(ast.generic_map(&instances[0])[0].formal, None);
+    assert_eq!(ast.to_result(), result);
+}
@@ -0,0 +1,202 @@
+use vig::interp::{Interpreter, Value};
+use vig::logic::{LogicVector, StdLogic};
+
+fn eval(source: &str, inputs: &[(&str, Value)]) -> Vec<(String, Value)> {
+    let interp = Interpreter::new(source, "dut").unwrap();
+    interp.evaluate(inputs).unwrap()
+}
+
+fn logic(c: char) -> Value {
+    Value::Logic(StdLogic::from_char(c).unwrap())
+}
+
+fn vector(bits: &str) -> Value {
+    Value::Vector(LogicVector::parse(bits).unwrap())
+}
+
+#[test]
+fn test_gates_and_conditional_assignment() {
+    let source = "
+entity dut is
+    port (a, b, sel : in std_logic; y, z, m : out std_logic);
+end entity;
+architecture rtl of dut is
+    signal n : std_logic;
+begin
+    n <= a nand b;
+    y <= not n;
+    z <= a xor b;
+    m <= a when sel = '1' else b;
+end architecture;
+";
+    let out = eval(
+        source,
+        &[("a", logic('1')), ("b", logic('1')), ("sel", logic('0'))],
+    );
+    assert_eq!(
+        out,
+        [
+            ("y".to_string(), logic('1')),
+            ("z".to_string(), logic('0')),
+            ("m".to_string(), logic('1')),
+        ]
+    );
+
+    // 'U' は and の片方が '0' なら消える
+    let out = eval(source, &[("a", logic('0')), ("sel", logic('1'))]);
+    assert_eq!(out[0], ("y".to_string(), logic('0')));
+    assert_eq!(out[1], ("z".to_string(), logic('U')));
+}
+
+#[test]
+fn test_selected_assignment_and_case() {
+    let source = "
+entity dut is
+    port (s : in std_logic_vector(1 downto 0); y : out std_logic_vector(3 downto 0); p : out std_logic);
+end entity;
+architecture rtl of dut is
+begin
+    with s select y <=
+        \"0001\" when \"00\",
+        X\"2\" when \"01\",
+        (others => '1') when others;
+
+    decode: process(s)
+    begin
+        case s is
+            when \"00\" | \"11\" => p <= '1';
+            when others => p <= '0';
+        end case;
+    end process decode;
+end architecture;
+";
+    let out = eval(source, &[("s", vector("01"))]);
+    assert_eq!(out[0].1, vector("0010"));
+    assert_eq!(out[1].1, logic('0'));
+    let out = eval(source, &[("s", vector("11"))]);
+    assert_eq!(out[0].1, vector("1111"));
+    assert_eq!(out[1].1, logic('1'));
+}
+
+#[test]
+fn test_alu_prediction() {
+    let source = std::fs::read_to_string("testdata/alu.vhd").unwrap();
+    let interp = Interpreter::new(&source, "ALU").unwrap();
+    assert_eq!(interp.entity().name, "alu");
+
+    let run = |a: &str, b: &str, op: &str| {
+        interp
+            .evaluate(&[
+                ("clk", logic('0')),
+                ("reset", logic('0')),
+                ("op_a", vector(a)),
+                ("op_b", vector(b)),
+                ("alu_op", vector(op)),
+            ])
+            .unwrap()
+    };
+    // 9 + 9 = 18 → 桁上がり
+    let out = run("1001", "1001", "000");
+    assert_eq!(out[0], ("result".to_string(), vector("0010")));
+    assert_eq!(out[1], ("carry_out".to_string(), logic('1')));
+    assert_eq!(out[2], ("zero_flag".to_string(), logic('0')));
+    // 3 - 5 は借りが出る
+    let out = run("0011", "0101", "001");
+    assert_eq!(out[0].1, vector("1110"));
+    assert_eq!(out[1].1, logic('1'));
+    // NOT 1111 = 0000 でゼロフラグ
+    let out = run("1111", "0000", "101");
+    assert_eq!(out[0].1, vector("0000"));
+    assert_eq!(out[2].1, logic('1'));
+}
+
+#[test]
+fn test_variables_slices_and_loops() {
+    let source = "
+entity dut is
+    port (d : in std_logic_vector(7 downto 0); r : out std_logic_vector(7 downto 0);
+          ones : out integer; hi : out std_logic_vector(3 downto 0));
+end entity;
+architecture rtl of dut is
+begin
+    process(d)
+        variable count : integer := 0;
+    begin
+        for i in 0 to 7 loop
+            r(i) <= d(7 - i);
+            if d(i) = '1' then
+                count := count + 1;
+            end if;
+        end loop;
+        ones <= count;
+    end process;
+    hi <= d(7 downto 4);
+end architecture;
+";
+    let out = eval(source, &[("d", vector("11010000"))]);
+    assert_eq!(out[0].1, vector("00001011"));
+    assert_eq!(out[1].1, Value::Integer(3));
+    assert_eq!(out[2].1, vector("1101"));
+}
+
+#[test]
+fn test_numeric_std_functions() {
+    let source = "
+entity dut is
+    port (a : in std_logic_vector(3 downto 0); n : in integer;
+          s : out std_logic_vector(3 downto 0); v : out integer; neg : out boolean);
+end entity;
+architecture rtl of dut is
+    constant OFFSET : integer := 2;
+begin
+    s <= std_logic_vector(shift_left(unsigned(a), 1) + to_unsigned(n, 4));
+    v <= to_integer(signed(a)) * OFFSET;
+    neg <= signed(a) < 0;
+end architecture;
+";
+    let out = eval(source, &[("a", vector("1100")), ("n", Value::Integer(3))]);
+    assert_eq!(out[0].1, vector("1011"));
+    assert_eq!(out[1].1, Value::Integer(-8));
+    assert_eq!(out[2].1, Value::Boolean(true));
+}
+
+#[test]
+fn test_unsupported_designs() {
+    // レジスタを介した帰還は収束しない
+    let counter = std::fs::read_to_string("testdata/counter.vhd").unwrap();
+    let interp = Interpreter::new(&counter, "counter").unwrap();
+    let inputs = [("clk", logic('0')), ("reset", logic('0'))];
+    let err = interp.evaluate(&inputs).unwrap_err();
+    assert!(err.message.contains("did not settle"), "{}", err.message);
+
+    let source = "
+entity dut is port (a : in std_logic; y : out std_logic); end entity;
+architecture rtl of dut is
+begin
+    u0: entity work.inv port map (a => a, y => y);
+end architecture;
+";
+    let err = Interpreter::new(source, "dut").unwrap_err();
+    assert!(err.message.contains("not supported"));
+    assert_eq!(&source[err.span.start..err.span.end], "u0");
+
+    let err = Interpreter::new(source, "missing").unwrap_err();
+    assert_eq!(err.message, "entity 'missing' not found");
+
+    // 入力でないポートには値を与えられない
+    let source = "
+entity dut is port (a : in std_logic; y : out std_logic); end entity;
+architecture rtl of dut is begin y <= a; end architecture;
+";
+    let interp = Interpreter::new(source, "dut").unwrap();
+    assert!(interp.evaluate(&[("y", logic('1'))]).is_err());
+    assert!(interp.evaluate(&[("a", vector("01"))]).is_err());
+}
+
+#[test]
+fn test_value_display() {
+    assert_eq!(logic('1').to_string(), "'1'");
+    assert_eq!(vector("0101").to_string(), "\"0101\"");
+    assert_eq!(Value::Integer(-3).to_string(), "-3");
+    assert_eq!(Value::Boolean(true).to_string(), "true");
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use vig::analyzer::{PortDirection, VhdlType};
+use vig::generator::{TbConfig, generate_entity_skeleton, generate_testbench};
+use vig::ipxact::import_component;
+
+fn import_file(path: &str) -> vig::analyzer::EntityDef {
+    let source = fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path));
+    import_component(&source).expect("import failed")
+}
+
+#[test]
+fn test_fifo_component_name() {
+    let entity = import_file("testdata/fifo.xml");
+    assert_eq!(entity.name, "fifo");
+    assert_eq!(entity.ports.len(), 6);
+}
+
+#[test]
+fn test_fifo_port_details() {
+    let entity = import_file("testdata/fifo.xml");
+    let ports = &entity.ports;
+
+    assert_eq!(ports[0].name, "clk");
+    assert_eq!(ports[0].direction, PortDirection::In);
+    assert_eq!(ports[0].vhdl_type, VhdlType::StdLogic);
+
+    // <vectors> のみ指定 -> std_logic_vector
+    assert_eq!(ports[2].name, "wr_data");
+    assert_eq!(
+        ports[2].vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+
+    assert_eq!(ports[3].direction, PortDirection::Out);
+    assert_eq!(
+        ports[3].vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+
+    // wireTypeDef の typeName が優先される
+    assert_eq!(ports[4].vhdl_type, VhdlType::Integer(None));
+}
+
+#[test]
+fn test_spirit_2009_vector() {
+    let source = r#"
+        <spirit:component xmlns:spirit="http://www.spiritconsortium.org/XMLSchema/SPIRIT/1685-2009">
+          <spirit:name>adder</spirit:name>
+          <spirit:model><spirit:ports>
+            <spirit:port>
+              <spirit:name>a</spirit:name>
+              <spirit:wire>
+                <spirit:direction>in</spirit:direction>
+                <spirit:vector><spirit:left>3</spirit:left><spirit:right>0</spirit:right></spirit:vector>
+              </spirit:wire>
+            </spirit:port>
+            <spirit:port>
+              <spirit:name>ghost</spirit:name>
+              <spirit:wire><spirit:direction>phantom</spirit:direction></spirit:wire>
+            </spirit:port>
+          </spirit:ports></spirit:model>
+        </spirit:component>
+    "#;
+    let entity = import_component(source).unwrap();
+    assert_eq!(entity.name, "adder");
+    assert_eq!(entity.ports.len(), 1);
+    assert_eq!(
+        entity.ports[0].vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 3.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+}
+
+#[test]
+fn test_not_a_component() {
+    let err = import_component("<design><name>x</name></design>").unwrap_err();
+    assert!(err.message.contains("component"));
+}
+
+#[test]
+fn test_malformed_xml() {
+    let err = import_component("<ipxact:component><ipxact:name>x</ipxact:component>").unwrap_err();
+    assert!(err.message.contains("mismatched"));
+}
+
+#[test]
+fn test_fifo_testbench_generation() {
+    let entity = import_file("testdata/fifo.xml");
+    let tb = generate_testbench(&entity, &TbConfig::default());
+    assert!(tb.contains("entity fifo_tb is"));
+    assert!(tb.contains("wr_data : in std_logic_vector(7 downto 0)"));
+    assert!(tb.contains("clk_process: process"));
+    assert!(tb.contains("rst <= '1';"));
+}
+
+#[test]
+fn test_fifo_entity_skeleton() {
+    let entity = import_file("testdata/fifo.xml");
+    let vhdl = generate_entity_skeleton(&entity);
+    assert!(vhdl.contains("entity fifo is"));
+    assert!(vhdl.contains("        rd_data : out std_logic_vector(7 downto 0);"));
+    assert!(vhdl.contains("        empty : out std_logic\n    );"));
+    assert!(vhdl.contains("architecture rtl of fifo is"));
+
+    // 生成した雛形は再解析できる
+    let reparsed = vig::analyzer::analyze_vhdl(&vhdl).unwrap();
+    let ports = &reparsed.entities[0].ports;
+    assert_eq!(ports.len(), entity.ports.len());
+    for (a, b) in ports.iter().zip(&entity.ports) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.direction, b.direction);
+        assert_eq!(a.vhdl_type, b.vhdl_type);
+    }
+}
@@ -1,5 +1,6 @@
 use std::fs;
-use vig::lexer::{Lexer, TokenKind};
+use vig::lexer::{Lexer, Span, TokenKind};
+use vig::rewrite::Edit;
 
 fn lex_file(path: &str) -> Vec<(TokenKind, String)> {
     let source = fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path));
@@ -7,7 +8,7 @@ fn lex_file(path: &str) -> Vec<(TokenKind, String)> {
     lexer
         .map(|r| {
             let token = r.expect("unexpected lex error");
-            (token.kind, token.text)
+            (token.kind, token.text.to_string())
         })
         .collect()
 }
@@ -128,3 +129,57 @@ fn test_counter_vhd_downto() {
     // std_logic_vector(7 downto 0) の downto が存在する
     assert!(kinds.contains(&&TokenKind::Downto));
 }
+
+#[test]
+fn test_trivia_round_trips_files() {
+    for path in [
+        "testdata/alu.vhd",
+        "testdata/counter.vhd",
+        "testdata/traffic_light.vhd",
+        "testdata/uart_tx.vhd",
+    ] {
+        let source = fs::read_to_string(path).unwrap();
+        let mut lexer = Lexer::new(&source).with_trivia();
+        let mut rebuilt = String::new();
+        loop {
+            let token = lexer.next_token().expect("unexpected lex error");
+            assert_ne!(token.kind, TokenKind::Comment);
+            rebuilt.push_str(token.leading);
+            rebuilt.push_str(token.text);
+            rebuilt.push_str(token.trailing);
+            if token.kind == TokenKind::Eof {
+                break;
+            }
+        }
+        assert_eq!(rebuilt, source, "{} が元に戻りません", path);
+    }
+}
+
+#[test]
+fn test_relex_matches_full_lex() {
+    let source = fs::read_to_string("testdata/counter.vhd").unwrap();
+    let (old_tokens, _) = Lexer::tokenize_with_errors(&source);
+    let check = |edit: Edit| {
+        let mut edited = source.clone();
+        edited.replace_range(edit.span.start..edit.span.end, &edit.text);
+        let relexed = Lexer::relex(&edited, &old_tokens, &edit);
+        assert_eq!(
+            relexed,
+            Lexer::tokenize_with_errors(&edited).0,
+            "{:?} で結果が違います",
+            edit
+        );
+    };
+    for at in (0..=source.len()).filter(|&i| source.is_char_boundary(i)) {
+        // 文字列・コメント・文字リテラルを開く文字や、トークンをつなぐ・分ける文字
+        for text in ["\"", "--", "'", "x", "\n", " ", "=", "\\", "1"] {
+            check(Edit::insert(at, text));
+        }
+        for len in 1..=3 {
+            let end = at + len;
+            if end <= source.len() && source.is_char_boundary(end) {
+                check(Edit::delete(Span::new(at, end)));
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vig::analyzer::{TypeDef, VhdlType, analyze_vhdl};
+use vig::library::{LibraryPaths, Resolver, from_config, use_clauses};
+
+const PACKAGE: &str = "\
+package my_pkg is
+    subtype byte_t is std_logic_vector(7 downto 0);
+    subtype data_t is byte_t;
+    type state_t is (IDLE, RUN);
+    type pair_t is record
+        a : std_logic;
+    end record;
+    component foo port (x : in std_logic); end component;
+    subtype word_t is std_logic_vector(15 downto 0);
+end package my_pkg;
+
+package body my_pkg is
+end package body;
+";
+
+const TOP: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+use work.my_pkg.all;
+use common.util_pkg.flag_t;
+use work.missing_pkg.all;
+entity top is
+    port (d : in data_t; w : out word_t; s : out state_t; f : in flag_t);
+end entity;
+";
+
+/// テストごとの一時ディレクトリ
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vig_library_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, text: &str) {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, text).unwrap();
+}
+
+#[test]
+fn test_package_types() {
+    // リゾルバは解析器のパッケージ宣言をそのまま使う
+    let result = analyze_vhdl(PACKAGE).unwrap();
+    let packages = &result.packages;
+    assert_eq!(packages.len(), 1);
+    let names: Vec<_> = packages[0].types.iter().map(|t| t.name()).collect();
+    // record と component の end でパッケージは終わらない
+    assert_eq!(names, ["byte_t", "data_t", "state_t", "pair_t", "word_t"]);
+    assert!(matches!(
+        packages[0].find_type("BYTE_T").unwrap(),
+        TypeDef::Subtype {
+            vhdl_type: VhdlType::StdLogicVector { .. },
+            ..
+        }
+    ));
+    assert!(matches!(
+        packages[0].find_type("state_t").unwrap(),
+        TypeDef::Enum { literals, .. } if literals == &["IDLE", "RUN"]
+    ));
+    assert!(matches!(
+        packages[0].find_type("pair_t").unwrap(),
+        TypeDef::Other { .. }
+    ));
+}
+
+#[test]
+fn test_use_clauses() {
+    let clauses = use_clauses(TOP);
+    let names: Vec<_> = clauses
+        .iter()
+        .map(|c| format!("{}.{}.{}", c.library, c.package, c.item))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "ieee.std_logic_1164.all",
+            "work.my_pkg.all",
+            "common.util_pkg.flag_t",
+            "work.missing_pkg.all"
+        ]
+    );
+    assert!(clauses[2].makes_visible("FLAG_T"));
+    assert!(!clauses[2].makes_visible("other_t"));
+}
+
+#[test]
+fn test_from_config() {
+    let base = Path::new("/proj");
+    let paths = from_config("[libraries]\nwork = [\"rtl\", \"pkg\"]\n", base)
+        .unwrap()
+        .unwrap();
+    assert_eq!(paths.dirs("WORK"), [base.join("rtl"), base.join("pkg")]);
+    assert!(paths.dirs("common").is_empty());
+    assert_eq!(from_config("[lint]\n", base).unwrap(), None);
+    let err = from_config("[libraries]\nwork = \"rtl\"\n", base).unwrap_err();
+    assert!(err.message.contains("array"), "{}", err.message);
+}
+
+#[test]
+fn test_resolve_entity() {
+    let dir = temp_dir("resolve");
+    write(&dir, "pkg/sub/types.vhd", PACKAGE);
+    write(
+        &dir,
+        "common/util.vhdl",
+        "package util_pkg is subtype flag_t is std_logic; end;",
+    );
+    let mut paths = LibraryPaths::new();
+    paths
+        .add("work", dir.join("pkg"))
+        .add("common", dir.join("common"));
+    let mut resolver = Resolver::new(paths);
+
+    let mut entity = analyze_vhdl(TOP).unwrap().entities.remove(0);
+    let diagnostics = resolver.resolve_entity(TOP, &mut entity).unwrap();
+    let types: Vec<_> = entity.ports.iter().map(|p| p.vhdl_type.clone()).collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    // サブタイプのサブタイプもたどる
+    assert_eq!(
+        types[0],
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    assert_eq!(
+        types[1],
+        VhdlType::StdLogicVector {
+            high: 15.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    // 列挙型はそのまま
+    assert_eq!(types[2], VhdlType::Other("state_t".to_string()));
+    assert_eq!(types[3], VhdlType::StdLogic);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.contains("work.missing_pkg"));
+    assert_eq!(
+        &TOP[diagnostics[0].span.start..diagnostics[0].span.end],
+        "use work.missing_pkg.all;"
+    );
+}
+
+#[test]
+fn test_find_entity_prefers_file_name() {
+    let dir = temp_dir("entity");
+    write(
+        &dir,
+        "a.vhd",
+        "entity sub is port (x : in std_logic); end entity;",
+    );
+    write(
+        &dir,
+        "sub.vhd",
+        "entity sub is port (y : in std_logic); end entity;",
+    );
+    let mut paths = LibraryPaths::new();
+    paths.add("work", &dir);
+    let mut resolver = Resolver::new(paths);
+    let entity = resolver.entity("work", "SUB").unwrap().unwrap();
+    let missing = resolver.entity("work", "nothing").unwrap();
+    let unknown_library = resolver.package("other", "my_pkg").unwrap().is_none();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(entity.ports[0].name, "y");
+    assert!(missing.is_none());
+    assert!(unknown_library);
+}
+
+#[test]
+fn test_analysis_error_carries_path() {
+    let dir = temp_dir("error");
+    write(&dir, "bad.vhd", "entity bad is port (x : in ); end entity;");
+    write(
+        &dir,
+        "bad_pkg.vhd",
+        "package bad_pkg is type t is (a, ); end package;",
+    );
+    let mut paths = LibraryPaths::new();
+    paths.add("work", &dir);
+    let mut resolver = Resolver::new(paths);
+    let err = resolver.entity("work", "bad").unwrap_err();
+    let package_err = resolver.package("work", "bad_pkg").unwrap_err();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(err.path, Some(dir.join("bad.vhd")));
+    assert!(err.to_string().contains("bad.vhd: "));
+    // パッケージと同じ名前のファイルが解析できなければ、見つからないのではなくエラー
+    assert_eq!(package_err.path, Some(dir.join("bad_pkg.vhd")));
+}
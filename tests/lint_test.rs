@@ -0,0 +1,550 @@
+use vig::Severity;
+use vig::lint::{
+    LintConfig, PortClass, PortOrderPolicy, check_cdc, check_dead_logic, check_naming,
+    check_port_order, expected_port_order, fix_port_order, lint, lint_analyzed,
+};
+use vig::report::{ReportFormat, render};
+use vig::source_map::SourceMap;
+
+// 2つのクロックの間で信号を受け渡す設計
+const CROSSING: &str = "\
+entity cross is
+  port (
+    clk_a, clk_b : in std_logic;
+    din : in std_logic;
+    dout : out std_logic
+  );
+end entity;
+
+architecture rtl of cross is
+  signal flag : std_logic;
+begin
+  process(clk_a)
+  begin
+    if rising_edge(clk_a) then
+      flag <= din;
+    end if;
+  end process;
+
+  process(clk_b)
+  begin
+    if rising_edge(clk_b) then
+      dout <= flag;
+    end if;
+  end process;
+end architecture;
+";
+
+#[test]
+fn test_unsynchronized_crossing() {
+    let diagnostics = check_cdc(CROSSING);
+    assert_eq!(diagnostics.len(), 1);
+    let d = &diagnostics[0];
+    assert_eq!(
+        d.message,
+        "signal 'flag' crosses from clock domain 'clk_a' to 'clk_b' without a synchronizer"
+    );
+    // 参照位置と駆動位置の両方を報告
+    assert_eq!(&CROSSING[d.span.start..d.span.end], "flag");
+    assert!(CROSSING[d.span.start..].starts_with("flag;"));
+    let (driver, note) = &d.related[0];
+    assert!(CROSSING[driver.start..].starts_with("flag <= din"));
+    assert_eq!(note, "'flag' is driven in clock domain 'clk_a' here");
+}
+
+#[test]
+fn test_two_stage_synchronizer_is_accepted() {
+    let source = CROSSING.replace(
+        "      dout <= flag;",
+        "      flag_s1 <= flag;\n      dout <= flag_s1;",
+    );
+    assert!(check_cdc(&source).is_empty());
+}
+
+#[test]
+fn test_named_synchronizer_is_accepted() {
+    let source = CROSSING.replace(
+        "      dout <= flag;",
+        "      flag_sync <= flag_sync(0) & flag;",
+    );
+    assert!(check_cdc(&source).is_empty());
+
+    let source = CROSSING.replace("      dout <= flag;", "      meta <= flag;");
+    assert!(check_cdc(&source).is_empty());
+}
+
+#[test]
+fn test_domain_through_combinational_logic() {
+    // 組み合わせ回路を通してもドメインを引き継ぐ
+    let source = CROSSING
+        .replace(
+            "begin\n  process(clk_a)",
+            "begin\n  mixed <= flag and din;\n\n  process(clk_a)",
+        )
+        .replace("      dout <= flag;", "      dout <= mixed;");
+    let diagnostics = check_cdc(&source);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(
+        diagnostics[0]
+            .message
+            .starts_with("signal 'mixed' crosses from clock domain 'clk_a'")
+    );
+}
+
+#[test]
+fn test_same_domain_is_clean() {
+    let source = CROSSING.replace("rising_edge(clk_b)", "rising_edge(clk_a)");
+    assert!(lint(&source, &LintConfig::default()).is_empty());
+}
+
+#[test]
+fn test_related_span_is_rendered() {
+    let map = SourceMap::new("cross.vhd", CROSSING);
+    let text = render(
+        &lint(CROSSING, &LintConfig::default()),
+        &map,
+        ReportFormat::Plain,
+    );
+    assert_eq!(
+        text,
+        "warning: signal 'flag' crosses from clock domain 'clk_a' to 'clk_b' without a synchronizer
+  --> cross.vhd:22:15
+   |
+22 |       dout <= flag;
+   |               ^^^^
+note: 'flag' is driven in clock domain 'clk_a' here
+  --> cross.vhd:15:7
+   |
+15 |       flag <= din;
+   |       ----
+"
+    );
+    let json = render(
+        &lint(CROSSING, &LintConfig::default()),
+        &map,
+        ReportFormat::Json,
+    );
+    assert!(json.contains("\"related\":[{\"message\":\"'flag' is driven in clock domain 'clk_a' here\",\"line\":15,\"column\":7"));
+}
+
+// 命名規則の検査対象
+const NAMING: &str = "\
+entity Uart_Tx is
+  generic (WIDTH : integer := 8);
+  port (
+    clk, rst_n : in std_logic;
+    i_data : in std_logic_vector(7 downto 0);
+    tx : out std_logic;
+    o_busy : out std_logic
+  );
+end entity;
+
+architecture rtl of Uart_Tx is
+  component fifo
+    port (din : in std_logic);
+  end component;
+  signal shiftReg : std_logic_vector(7 downto 0);
+  signal enable : std_logic;
+begin
+  process(clk)
+  begin
+    if rising_edge(clk) then
+      if enable = '0' then
+        tx <= '1';
+      end if;
+    end if;
+  end process;
+end architecture;
+";
+
+const NAMING_CONFIG: &str = r#"
+[lint.naming.identifiers]
+case = "snake"
+allow = ["Uart_Tx"]
+
+[lint.naming.generics]
+prefix = "g_"
+case = "upper_snake"
+severity = "error"
+
+[lint.naming.inputs]
+prefix = "i_"
+allow = ["clk", "rst_n"]
+
+[lint.naming.outputs]
+prefix = "o_"
+
+[lint.naming.active_low]
+suffix = "_n"
+"#;
+
+fn naming_messages() -> Vec<(Severity, String)> {
+    let config = LintConfig::from_toml(NAMING_CONFIG).unwrap();
+    check_naming(NAMING, &config.naming)
+        .into_iter()
+        .map(|d| (d.severity, d.message))
+        .collect()
+}
+
+#[test]
+fn test_naming_violations() {
+    let messages = naming_messages();
+    let warning = |m: &str| (Severity::Warning, m.to_string());
+    assert_eq!(
+        messages,
+        vec![
+            (
+                Severity::Error,
+                "generic 'WIDTH' should start with 'g_'".to_string()
+            ),
+            warning("output port 'tx' should start with 'o_'"),
+            warning("signal 'shiftReg' should be snake_case"),
+            warning("active-low signal 'enable' should end with '_n'"),
+        ]
+    );
+}
+
+#[test]
+fn test_naming_skips_components() {
+    // コンポーネント宣言のポートは別エンティティの写しなので検査しない
+    assert!(!naming_messages().iter().any(|(_, m)| m.contains("din")));
+}
+
+#[test]
+fn test_naming_case_ignores_prefix() {
+    // 対象ごとの case は接頭辞を除いた部分で判定し、identifiers の case より優先
+    let source = NAMING.replace("WIDTH", "g_WIDTH");
+    let config = LintConfig::from_toml(NAMING_CONFIG).unwrap();
+    let diagnostics = check_naming(&source, &config.naming);
+    assert!(!diagnostics.iter().any(|d| d.message.contains("WIDTH")));
+
+    let source = NAMING.replace("WIDTH", "g_Width");
+    let diagnostics = check_naming(&source, &config.naming);
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| d.message == "generic 'g_Width' should be UPPER_SNAKE_CASE")
+    );
+}
+
+#[test]
+fn test_naming_config_errors() {
+    let err = LintConfig::from_toml("[lint.naming.ports]\nprefix = \"p_\"\n").unwrap_err();
+    assert_eq!(err.message, "unknown naming rule 'ports'");
+
+    let source = "[lint.naming.signals]\ncase = \"kebab\"\n";
+    let err = LintConfig::from_toml(source).unwrap_err();
+    assert_eq!(
+        err.message,
+        "unknown case 'kebab' (expected snake, upper_snake, pascal or camel)"
+    );
+    assert_eq!(&source[err.span.start..err.span.end], "\"kebab\"");
+
+    let err = LintConfig::from_toml("[lint.naming.signals]\nallow = \"x\"\n").unwrap_err();
+    assert_eq!(err.message, "'allow' must be an array of strings");
+
+    // [lint] がなければ検査しない
+    let config = LintConfig::from_toml("[assertions]\n").unwrap();
+    assert!(config.naming.is_empty());
+    assert!(lint(NAMING, &config).is_empty());
+}
+
+// 実行されない論理を含む設計
+const DEAD: &str = "\
+entity dead is
+  port (
+    clk, en : in std_logic;
+    sel : in std_logic_vector(1 downto 0);
+    q, r : out std_logic
+  );
+end entity;
+
+architecture rtl of dead is
+  constant DEBUG : boolean := false;
+  constant MODE : integer := 2;
+begin
+  process(clk)
+  begin
+    if rising_edge(clk) then
+      q <= '0';
+      q <= en;
+      if '1' = '0' then
+        r <= '1';
+      elsif DEBUG or MODE = 2 then
+        r <= '0';
+      elsif en = '1' then
+        r <= en;
+      end if;
+      case sel is
+        when \"00\" => r <= '0';
+        when \"01\" | \"00\" => r <= '1';
+        when \"01\" => r <= en;
+        when others => null;
+      end case;
+      case MODE is
+        when 1 => r <= '1';
+        when 2 => r <= '0';
+        when others => r <= en;
+      end case;
+    end if;
+  end process;
+end architecture;
+";
+
+fn dead_logic() -> Vec<(String, String)> {
+    check_dead_logic(DEAD)
+        .into_iter()
+        .map(|d| {
+            assert_eq!(d.severity, Severity::Info);
+            (DEAD[d.span.start..d.span.end].to_string(), d.message)
+        })
+        .collect()
+}
+
+#[test]
+fn test_constant_conditions() {
+    let found = dead_logic();
+    assert!(found.contains(&(
+        "'1' = '0'".to_string(),
+        "condition is always false; the branch is never taken".to_string()
+    )));
+    // 定数を参照する条件も評価する
+    assert!(found.contains(&(
+        "DEBUG or MODE = 2".to_string(),
+        "condition is always true".to_string()
+    )));
+    // 入力に依存する条件は報告しない
+    assert!(!found.iter().any(|(text, _)| text == "en = '1'"));
+}
+
+#[test]
+fn test_unreachable_case_branches() {
+    let found = dead_logic();
+    let messages: Vec<&str> = found
+        .iter()
+        .filter(|(_, m)| m.starts_with("case branch"))
+        .map(|(text, m)| {
+            assert!(!text.is_empty());
+            m.as_str()
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            // when "01" は前の分岐で網羅済み
+            "case branch is unreachable: its choices are covered by earlier branches",
+            // MODE は常に 2
+            "case branch is never taken",
+            "case branch is never taken",
+        ]
+    );
+    let diagnostics = check_dead_logic(DEAD);
+    let never = diagnostics
+        .iter()
+        .find(|d| d.message == "case branch is never taken")
+        .unwrap();
+    assert_eq!(&DEAD[never.span.start..never.span.end], "1");
+    assert_eq!(never.related[0].1, "the selector is always 2");
+}
+
+#[test]
+fn test_overwritten_assignment() {
+    let diagnostics = check_dead_logic(DEAD);
+    let d = diagnostics
+        .iter()
+        .find(|d| d.message.starts_with("assignment to 'q'"))
+        .unwrap();
+    assert!(DEAD[d.span.start..].starts_with("q <= '0'"));
+    assert!(DEAD[d.related[0].0.start..].starts_with("q <= en"));
+    // 分岐の中の代入は上書きとみなさない
+    assert!(!diagnostics.iter().any(|d| d.message.contains("'r'")));
+}
+
+#[test]
+fn test_wait_separates_assignments() {
+    let source = "\
+architecture sim of tb is
+begin
+  process
+  begin
+    start <= '1';
+    wait for 10 ns;
+    start <= '0';
+    wait;
+  end process;
+end architecture;
+";
+    assert!(check_dead_logic(source).is_empty());
+}
+
+#[test]
+fn test_lint_reports_multiple_drivers() {
+    let source = "\
+entity e is
+  port (a : in std_logic; n : out integer);
+end entity;
+
+architecture rtl of e is
+begin
+  n <= 1 when a = '1' else 0;
+  n <= 2;
+end architecture;
+";
+    let diagnostics = lint(source, &LintConfig::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert!(source[diagnostics[0].span.start..].starts_with("n <= 2"));
+}
+
+#[test]
+fn test_lint_shared_analysis_across_threads() {
+    let source = "\
+entity a is port (clk : in std_logic; q : out std_logic); end entity;
+architecture rtl of a is
+    signal n : integer;
+begin
+    n <= 1;
+    n <= 2;
+    q <= '0';
+end architecture;
+";
+    let result = vig::analyze_shared(source).unwrap();
+    let config = LintConfig::default();
+    let expected = lint(source, &config);
+    // 1つの解析結果を複数のスレッドで共有する
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let result = std::sync::Arc::clone(&result);
+            let config = config.clone();
+            std::thread::spawn(move || lint_analyzed(source, &result, &config))
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+    assert!(
+        expected
+            .iter()
+            .any(|d| d.message.contains("multiple drivers"))
+    );
+}
+
+// 並び順がばらばらのポート
+const UNORDERED: &str = "\
+entity dma is
+    port (
+        s_axi_rdata : out std_logic_vector(31 downto 0);
+        m_axi_araddr : out std_logic_vector(31 downto 0);
+        -- 受信側
+        s_axi_araddr : in std_logic_vector(31 downto 0);
+        rst_n, clk : in std_logic;
+        m_axi_rdata : in std_logic_vector(31 downto 0);
+        irq : out std_logic
+    );
+end entity;
+";
+
+#[test]
+fn test_expected_port_order() {
+    let entity = vig::analyzer::analyze_vhdl(UNORDERED)
+        .unwrap()
+        .entities
+        .remove(0);
+    let order = expected_port_order(&entity, &PortOrderPolicy::default());
+    assert_eq!(
+        order,
+        [
+            "clk",
+            "rst_n",
+            "s_axi_araddr",
+            "m_axi_rdata",
+            "s_axi_rdata",
+            "m_axi_araddr",
+            "irq"
+        ]
+    );
+}
+
+#[test]
+fn test_port_order_diagnostic() {
+    let diagnostics = check_port_order(UNORDERED, &PortOrderPolicy::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "port 'clk' (clock) of entity 'dma' should come before 's_axi_rdata' (output)"
+    );
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    // まとめて宣言したポートは宣言全体の位置を指す
+    let line = UNORDERED[diagnostics[0].span.start..]
+        .lines()
+        .next()
+        .unwrap();
+    assert!(line.contains("clk"));
+
+    // 並べ替えた後は報告しない
+    let fixed = fix_port_order(UNORDERED, &PortOrderPolicy::default()).unwrap();
+    assert!(check_port_order(&fixed, &PortOrderPolicy::default()).is_empty());
+}
+
+#[test]
+fn test_fix_port_order_keeps_formatting() {
+    let fixed = fix_port_order(UNORDERED, &PortOrderPolicy::default()).unwrap();
+    assert_eq!(
+        fixed,
+        "\
+entity dma is
+    port (
+        clk : in std_logic;
+        rst_n : in std_logic;
+        -- 受信側
+        s_axi_araddr : in std_logic_vector(31 downto 0);
+        m_axi_rdata : in std_logic_vector(31 downto 0);
+        s_axi_rdata : out std_logic_vector(31 downto 0);
+        m_axi_araddr : out std_logic_vector(31 downto 0);
+        irq : out std_logic
+    );
+end entity;
+"
+    );
+}
+
+#[test]
+fn test_port_order_config() {
+    let config = LintConfig::from_toml(
+        "[lint.port_order]\norder = [\"outputs\", \"clocks\"]\ngroup_by_prefix = false\nseverity = \"error\"\n",
+    )
+    .unwrap();
+    let policy = config.port_order.as_ref().unwrap();
+    assert_eq!(policy.order, [PortClass::Outputs, PortClass::Clocks]);
+    assert!(!policy.group_by_prefix);
+
+    let entity = vig::analyzer::analyze_vhdl(UNORDERED)
+        .unwrap()
+        .entities
+        .remove(0);
+    // 挙げなかった分類（リセット・入力）は既定の順で後ろに続く
+    assert_eq!(
+        expected_port_order(&entity, policy),
+        [
+            "s_axi_rdata",
+            "m_axi_araddr",
+            "irq",
+            "clk",
+            "rst_n",
+            "s_axi_araddr",
+            "m_axi_rdata"
+        ]
+    );
+    let diagnostics = lint(UNORDERED, &config);
+    assert!(diagnostics.iter().any(|d| d.severity == Severity::Error));
+
+    // 設定しなければ検査しない
+    assert!(lint(UNORDERED, &LintConfig::default()).is_empty());
+
+    let err = LintConfig::from_toml("[lint.port_order]\norder = [\"clock\"]\n").unwrap_err();
+    assert!(
+        err.message.contains("unknown port class 'clock'"),
+        "{}",
+        err.message
+    );
+}
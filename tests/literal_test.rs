@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use vig::analyzer::{VhdlType, analyze_vhdl};
+use vig::interp::Interpreter;
+use vig::lexer::Lexer;
+use vig::lint::{LintConfig, lint};
+use vig::literal::{
+    Numeric, bit_string_vector, parse_bit_string, parse_integer, parse_real, parse_time,
+    parse_time_fs, physical_literal, physical_literals,
+};
+use vig::logic::{LogicVector, MAX_VECTOR_WIDTH};
+
+#[test]
+fn test_parse_integer() {
+    assert_eq!(parse_integer("42"), Some(42));
+    assert_eq!(parse_integer("1_000_000"), Some(1_000_000));
+    assert_eq!(parse_integer("1e3"), Some(1000));
+    assert_eq!(parse_integer("2E+2"), Some(200));
+    assert_eq!(parse_integer("16#FF#"), Some(255));
+    assert_eq!(parse_integer("16#ff_ff#"), Some(0xFFFF));
+    assert_eq!(parse_integer("8#17#"), Some(15));
+    assert_eq!(parse_integer("2#1010#E2"), Some(40));
+    assert_eq!(parse_integer("16#E#"), Some(14));
+
+    // 不正な書式と範囲外
+    assert_eq!(parse_integer(""), None);
+    assert_eq!(parse_integer("1__0"), None);
+    assert_eq!(parse_integer("_1"), None);
+    assert_eq!(parse_integer("1e-3"), None);
+    assert_eq!(parse_integer("17#1#"), None);
+    assert_eq!(parse_integer("2#102#"), None);
+    assert_eq!(parse_integer("16#FF"), None);
+    assert_eq!(parse_integer("1e40"), None);
+}
+
+#[test]
+fn test_parse_real() {
+    assert_eq!(parse_real("1.5"), Some(1.5));
+    assert_eq!(parse_real("1_000.000_1"), Some(1000.0001));
+    assert_eq!(parse_real("2.0E+2"), Some(200.0));
+    assert_eq!(parse_real("2.5e-1"), Some(0.25));
+    assert_eq!(parse_real("2#0.1#"), Some(0.5));
+    assert_eq!(parse_real("16#F.8#E-1"), Some(0.96875));
+
+    assert_eq!(parse_real("15"), None);
+    assert_eq!(parse_real("1.2.3"), None);
+    assert_eq!(parse_real("1._5"), None);
+    assert_eq!(parse_real(".5"), None);
+    assert_eq!(parse_real("2#0.2#"), None);
+}
+
+#[test]
+fn test_token_numeric_value() {
+    let values: Vec<_> = Lexer::new("255 16#FF# 1.5e2 8#0.4#")
+        .map(|t| t.unwrap().numeric_value())
+        .collect();
+    assert_eq!(
+        values,
+        [
+            Some(Numeric::Integer(255)),
+            Some(Numeric::Integer(255)),
+            Some(Numeric::Real(150.0)),
+            Some(Numeric::Real(0.5)),
+        ]
+    );
+    let token = Lexer::new("x").next().unwrap().unwrap();
+    assert_eq!(token.numeric_value(), None);
+}
+
+#[test]
+fn test_parse_bit_string() {
+    assert_eq!(parse_bit_string("X\"FF\""), Some(255));
+    assert_eq!(parse_bit_string("o\"17\""), Some(15));
+    assert_eq!(parse_bit_string("B\"1010_0001\""), Some(0xA1));
+    assert_eq!(parse_bit_string("D\"300\""), Some(300));
+    assert_eq!(parse_bit_string("X\"Z0\""), None);
+    assert_eq!(parse_bit_string("Q\"1\""), None);
+    assert_eq!(parse_bit_string("X\"FF"), None);
+
+    // 幅指定は0で拡張し、0のビットだけ切り詰められる
+    let bits = bit_string_vector("12X\"FF\"").unwrap();
+    assert_eq!(bits, LogicVector::parse("000011111111").unwrap());
+    assert_eq!(bit_string_vector("6X\"0F\"").unwrap().to_string(), "001111");
+    assert_eq!(bit_string_vector("6X\"FF\""), None);
+    assert_eq!(bit_string_vector("4D\"9\"").unwrap().to_string(), "1001");
+    // 符号付きは符号拡張し、符号と同じビットだけ切り詰められる
+    assert_eq!(
+        bit_string_vector("8SX\"F\"").unwrap().to_string(),
+        "11111111"
+    );
+    assert_eq!(
+        bit_string_vector("8UX\"F\"").unwrap().to_string(),
+        "00001111"
+    );
+    assert_eq!(
+        bit_string_vector("3SB\"11110\"").unwrap().to_string(),
+        "110"
+    );
+    assert_eq!(bit_string_vector("3SB\"01110\""), None);
+}
+
+#[test]
+fn test_oversized_bit_string_width() {
+    // 幅指定は上限までに限り、それを超えるものは確保せずに不正とする
+    assert_eq!(parse_bit_string("99999999999999x\"F\""), None);
+    assert_eq!(bit_string_vector("99999999999999x\"F\""), None);
+    assert_eq!(
+        bit_string_vector(&format!("{}X\"F\"", MAX_VECTOR_WIDTH + 1)),
+        None
+    );
+    let bits = bit_string_vector(&format!("{}X\"F\"", MAX_VECTOR_WIDTH)).unwrap();
+    assert_eq!(bits.width(), MAX_VECTOR_WIDTH);
+
+    // インタプリタはエラーとして報告し、lint は中断せずに最後まで進む
+    let source = "\
+entity e is
+  port (y : out bit_vector(3 downto 0));
+end entity;
+architecture rtl of e is
+  constant C : bit_vector(3 downto 0) := 99999999999999x\"F\";
+begin
+  y <= C;
+end architecture;
+";
+    let err = Interpreter::new(source, "e").unwrap_err();
+    assert_eq!(err.message, "invalid bit string literal");
+    lint(source, &LintConfig::default());
+}
+
+#[test]
+fn test_parse_time() {
+    assert_eq!(parse_time("10 ns"), Some(Duration::from_nanos(10)));
+    assert_eq!(parse_time("10ns"), Some(Duration::from_nanos(10)));
+    assert_eq!(parse_time("1.5 us"), Some(Duration::from_nanos(1500)));
+    assert_eq!(parse_time("2 ms"), Some(Duration::from_millis(2)));
+    assert_eq!(parse_time("1 sec"), Some(Duration::from_secs(1)));
+    assert_eq!(parse_time("1 hr"), Some(Duration::from_secs(3600)));
+    assert_eq!(parse_time("2000 ps"), Some(Duration::from_nanos(2)));
+    assert_eq!(parse_time("1 NS"), Some(Duration::from_nanos(1)));
+    // ns 未満の端数は Duration で表せない
+    assert_eq!(parse_time("100 ps"), None);
+    assert_eq!(parse_time_fs("100 ps"), Some(100_000));
+    assert_eq!(parse_time_fs("1.5 fs"), None);
+    assert_eq!(parse_time("10"), None);
+    assert_eq!(parse_time("10 parsecs"), None);
+}
+
+#[test]
+fn test_analyzer_bounds() {
+    let source = "entity e is port (d : in std_logic_vector(1_5 downto 0)); end entity;";
+    let result = analyze_vhdl(source).unwrap();
+    assert_eq!(
+        result.entities[0].ports[0].vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 15.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+
+    let source = "entity e is port (d : in std_logic_vector(1.5 downto 0)); end entity;";
+    let err = analyze_vhdl(source).unwrap_err();
+    assert_eq!(err.message, "invalid integer literal '1.5'");
+}
+
+#[test]
+fn test_physical_literals() {
+    let source = "wait for 10 ns; clk_period := 2.5 Us; f := 100 MHz; x := 3 mod 2; y := 4;";
+    let tokens: Vec<_> = Lexer::new(source).collect::<Result<_, _>>().unwrap();
+    let found = physical_literals(&tokens);
+    let texts: Vec<String> = found.iter().map(|p| p.to_string()).collect();
+    // 予約語（mod）は単位名ではない
+    assert_eq!(texts, ["10 ns", "2.5 Us", "100 MHz"]);
+    assert_eq!(found[0].value, Numeric::Integer(10));
+    assert_eq!(&source[found[1].span.start..found[1].span.end], "2.5 Us");
+    assert_eq!(found[1].time_fs(), Some(2_500_000_000));
+    // 時間でない単位
+    assert_eq!(found[2].time_fs(), None);
+
+    assert_eq!(physical_literal(&tokens, 0), None);
+    assert_eq!(physical_literal(&tokens, 2), Some(found[0].clone()));
+}
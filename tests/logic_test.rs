@@ -0,0 +1,125 @@
+use vig::logic::{LogicVector, StdLogic, resolve};
+
+fn v(bits: &str) -> LogicVector {
+    LogicVector::parse(bits).unwrap()
+}
+
+#[test]
+fn test_std_logic_chars() {
+    for value in StdLogic::ALL {
+        assert_eq!(StdLogic::from_char(value.to_char()), Some(value));
+    }
+    assert_eq!(StdLogic::from_char('h'), Some(StdLogic::H));
+    assert_eq!(StdLogic::from_char('2'), None);
+    assert_eq!(StdLogic::DontCare.to_string(), "-");
+    assert!(StdLogic::U < StdLogic::DontCare);
+}
+
+#[test]
+fn test_logic_tables() {
+    use StdLogic::*;
+    // std_logic_1164 の and/or/xor/not 表と一致する
+    assert_eq!(Zero & U, Zero);
+    assert_eq!(One & U, U);
+    assert_eq!(H & One, One);
+    assert_eq!(Z & One, X);
+    assert_eq!(One | X, One);
+    assert_eq!(Zero | W, X);
+    assert_eq!(L | U, U);
+    assert_eq!(One ^ H, Zero);
+    assert_eq!(U ^ Zero, U);
+    assert_eq!(!L, One);
+    assert_eq!(!Z, X);
+    assert_eq!(One.nand(One), Zero);
+    assert_eq!(Zero.xnor(L), One);
+}
+
+#[test]
+fn test_resolution() {
+    use StdLogic::*;
+    assert_eq!(resolve(&[]), Z);
+    assert_eq!(resolve(&[Z, One]), One);
+    assert_eq!(resolve(&[Zero, One]), X);
+    assert_eq!(resolve(&[L, H]), W);
+    assert_eq!(resolve(&[H, Zero, Z]), Zero);
+    assert_eq!(resolve(&[One, U]), U);
+    assert_eq!(v("Z1L").resolve(&v("0ZH")), Some(v("01W")));
+    assert_eq!(v("Z1").resolve(&v("0")), None);
+}
+
+#[test]
+fn test_vector_conversions() {
+    assert_eq!(v("1_0x").to_string(), "10X");
+    assert!(LogicVector::parse("10a").is_none());
+    assert_eq!(LogicVector::from_unsigned(5, 4), v("0101"));
+    assert_eq!(LogicVector::from_signed(-3, 4), v("1101"));
+    assert_eq!(v("1101").to_unsigned(), Some(13));
+    assert_eq!(v("1101").to_signed(), Some(-3));
+    assert_eq!(v("1H01").to_unsigned(), Some(13));
+    assert_eq!(v("1X01").to_unsigned(), None);
+    assert!(v("0110").is_01());
+    assert!(!v("01Z0").is_01());
+}
+
+#[test]
+fn test_bit_string_literals() {
+    assert_eq!(LogicVector::from_bit_string("X", "A5"), Some(v("10100101")));
+    assert_eq!(LogicVector::from_bit_string("o", "17"), Some(v("001111")));
+    assert_eq!(LogicVector::from_bit_string("b", "10_1"), Some(v("101")));
+    // メタ値の桁は桁幅ぶん展開する
+    assert_eq!(LogicVector::from_bit_string("x", "Z1"), Some(v("ZZZZ0001")));
+    assert_eq!(LogicVector::from_bit_string("b", "2"), None);
+    assert_eq!(LogicVector::from_bit_string("d", "9"), None);
+}
+
+#[test]
+fn test_vector_operations() {
+    assert_eq!(v("1100").and(&v("1010")), Some(v("1000")));
+    assert_eq!(v("1100").xor(&v("1010")), Some(v("0110")));
+    assert_eq!(v("1100").or(&v("10")), None);
+    assert_eq!(v("10U").not(), v("01U"));
+    assert_eq!(v("10").concat(&v("Z")), v("10Z"));
+    assert_eq!(v("0110").shift_left(1), v("1100"));
+    assert_eq!(v("1010").shift_right(1, true), v("1101"));
+    assert_eq!(v("1010").shift_right(5, false), v("0000"));
+    assert_eq!(v("101").resize(5, false), v("00101"));
+    assert_eq!(v("101").resize(5, true), v("11101"));
+    assert_eq!(v("10110").resize(3, true), v("110"));
+    assert!(v("1-0").std_match(&v("110")));
+    assert!(!v("1X0").std_match(&v("1X0")));
+}
+
+#[test]
+fn test_numeric_std_arithmetic() {
+    // 幅は長い方に合わせ、溢れたビットは捨てる
+    assert_eq!(v("1001").add(&v("1001"), false), v("0010"));
+    assert_eq!(v("00011").sub(&v("101"), false), v("11110"));
+    assert_eq!(v("1111").add(&v("01"), true), v("0000"));
+    assert_eq!(v("11").mul(&v("11"), false), v("1001"));
+    assert_eq!(v("11").mul(&v("11"), true), v("0001"));
+    assert_eq!(v("1000").div(&v("0011"), false), Some(v("0010")));
+    assert_eq!(v("1001").modulo(&v("0100"), true), Some(v("0001")));
+    assert_eq!(v("1001").rem(&v("0100"), true), Some(v("1101")));
+    assert_eq!(v("1000").div(&v("0000"), false), None);
+    // メタ値を含む演算は全ビット 'X'
+    assert_eq!(v("10U1").add(&v("0001"), false), v("XXXX"));
+}
+
+#[test]
+fn test_arithmetic_at_127_bits() {
+    // 127 ビットは整数に変換できる最大の幅（途中の値は i128 を溢れる）
+    let ones = LogicVector::from_unsigned(u128::MAX, 127);
+    let zero = LogicVector::from_unsigned(0, 127);
+    let one = LogicVector::from_unsigned(1, 127);
+    assert_eq!(ones.to_signed(), Some(-1));
+    assert_eq!(ones.add(&ones, false), ones.sub(&one, false));
+    assert_eq!(ones.add(&one, false), zero);
+    assert_eq!(ones.add(&ones, true).to_signed(), Some(-2));
+    assert_eq!(zero.sub(&one, false), ones);
+
+    let max = LogicVector::from_signed(i128::MAX >> 1, 127);
+    let min = LogicVector::from_signed(-(1 << 126), 127);
+    assert_eq!(max.add(&one, true), min);
+    assert_eq!(min.sub(&one, true), max);
+    assert_eq!(min.sub(&max, false).to_unsigned(), Some(1));
+}
@@ -0,0 +1,89 @@
+use vig::analyze;
+use vig::metrics::{line_counts, metrics, to_json};
+
+const SOURCE: &str = "\
+-- 入れ子のあるカウンタ
+entity nest is
+  port (
+    clk, rst : in std_logic;
+    en : in std_logic;
+    q : out std_logic_vector(3 downto 0)
+  );
+end entity;
+
+architecture rtl of nest is
+  signal count : std_logic_vector(3 downto 0);
+begin
+  p_count: process(clk)
+  begin
+    if rising_edge(clk) then
+      if rst = '1' then
+        count <= (others => '0');
+      elsif en = '1' then
+        for i in 0 to 3 loop
+          count <= std_logic_vector(unsigned(count) + 1);
+        end loop;
+      end if;
+    end if;
+  end process;
+
+  q <= count;
+end architecture;
+";
+
+#[test]
+fn test_entity_metrics() {
+    let result = analyze(SOURCE).unwrap();
+    let m = metrics(SOURCE, &result.entities[0]);
+    assert_eq!(m.name, "nest");
+    assert_eq!(m.ports, 4);
+    assert_eq!(m.signals, 1);
+    assert_eq!(m.process_count, 1);
+    assert_eq!(m.concurrent_statements, 1);
+    // 代入2つ + for + 内側の if + 外側の if、並行代入1つ
+    assert_eq!(m.statements, 6);
+    assert_eq!(m.max_nesting_depth, 3);
+}
+
+#[test]
+fn test_process_metrics() {
+    let result = analyze(SOURCE).unwrap();
+    let m = metrics(SOURCE, &result.entities[0]);
+    let p = &m.processes[0];
+    assert_eq!(p.label.as_deref(), Some("p_count"));
+    assert_eq!(p.statements, 5);
+    assert_eq!(p.nesting_depth, 3);
+    // clk, rst, en, count を参照し count に代入
+    assert_eq!((p.fan_in, p.fan_out), (4, 1));
+}
+
+#[test]
+fn test_signal_fan_in_out() {
+    let result = analyze(SOURCE).unwrap();
+    let m = metrics(SOURCE, &result.entities[0]);
+    let fan = |name: &str| {
+        let s = m.signal_metrics.iter().find(|s| s.name == name).unwrap();
+        (s.fan_in, s.fan_out)
+    };
+    assert_eq!(fan("count"), (1, 2));
+    assert_eq!(fan("q"), (1, 0));
+    assert_eq!(fan("en"), (0, 1));
+}
+
+#[test]
+fn test_line_counts() {
+    let lines = line_counts(SOURCE);
+    assert_eq!(lines.total, 27);
+    assert_eq!(lines.comment, 1);
+    assert_eq!(lines.blank, 2);
+    assert_eq!(lines.code, 24);
+}
+
+#[test]
+fn test_json() {
+    let result = analyze(SOURCE).unwrap();
+    let json = to_json(&[metrics(SOURCE, &result.entities[0])]);
+    assert!(json.starts_with("[\n  {\n    \"entity\": \"nest\","));
+    assert!(json.contains("\"label\": \"p_count\""));
+    assert!(json.contains("\"max_nesting_depth\": 3"));
+}
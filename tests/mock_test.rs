@@ -0,0 +1,115 @@
+use vig::analyzer::{EntityDef, PortDirection, VhdlType::*};
+use vig::mock::{MockStyle, generate_mock};
+use vig::testbench::component_declarations;
+
+fn block() -> EntityDef {
+    EntityDef::builder("filt")
+        .input("clk", StdLogic)
+        .input("rst", StdLogic)
+        .input(
+            "din",
+            StdLogicVector {
+                high: 7.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .input("en", StdLogic)
+        .output(
+            "dout",
+            StdLogicVector {
+                high: 7.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .output("valid", StdLogic)
+        .output("count", Integer(None))
+        .port("sda", PortDirection::Inout, StdLogic)
+        .build()
+}
+
+#[test]
+fn test_tie_outputs_to_defaults() {
+    let mock = generate_mock(&block(), MockStyle::Tie);
+    assert!(mock.contains("entity filt is"), "{}", mock);
+    assert!(mock.contains("architecture mock of filt is"));
+    assert!(mock.contains("    dout <= (others => '0');\n"));
+    assert!(mock.contains("    valid <= '0';\n"));
+    assert!(mock.contains("    count <= 0;\n"));
+    // 双方向は開放する
+    assert!(mock.contains("    sda <= 'Z';\n"));
+    assert!(!mock.contains("process"));
+}
+
+#[test]
+fn test_echo_registers_matching_inputs() {
+    let mock = generate_mock(&block(), MockStyle::Echo);
+    assert!(mock.contains("if rising_edge(clk) then"), "{}", mock);
+    assert!(mock.contains("            dout <= din;\n"));
+    // クロック・リセットは返さず、同じ型の次の入力を使う
+    assert!(mock.contains("            valid <= en;\n"));
+    // 対応する入力がなければ既定値
+    assert!(mock.contains("    count <= 0;\n"));
+}
+
+#[test]
+fn test_echo_without_clock_is_combinational() {
+    let entity = EntityDef::builder("inv")
+        .input("a", StdLogic)
+        .output("y", StdLogic)
+        .build();
+    let mock = generate_mock(&entity, MockStyle::Echo);
+    assert!(
+        mock.contains("begin\n    y <= a;\nend architecture mock;"),
+        "{}",
+        mock
+    );
+}
+
+#[test]
+fn test_style_from_str() {
+    assert_eq!("ECHO".parse::<MockStyle>(), Ok(MockStyle::Echo));
+    assert_eq!("tie".parse::<MockStyle>(), Ok(MockStyle::Tie));
+    assert!("random".parse::<MockStyle>().is_err());
+}
+
+#[test]
+fn test_interface_from_component_declaration() {
+    let source = "architecture rtl of top is
+    component filt is
+        generic (N : integer := 8);
+        port (
+            clk : in std_logic;
+            din : in std_logic_vector(7 downto 0);
+            dout, dbg : out std_logic_vector(7 downto 0)
+        );
+    end component;
+    component pll port (clk_in : in std_logic; locked : out std_logic); end component pll;
+begin
+    u_filt: component filt port map (clk => clk, din => d, dout => q, dbg => open);
+end architecture;";
+    let components = component_declarations(source);
+    let names: Vec<_> = components.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["filt", "pll"]);
+    let ports: Vec<_> = components[0]
+        .ports
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(ports, ["clk", "din", "dout", "dbg"]);
+    assert_eq!(components[0].ports[3].direction, PortDirection::Out);
+
+    let mock = generate_mock(&components[1], MockStyle::Tie);
+    assert!(mock.contains("locked <= '0';"), "{}", mock);
+}
+
+#[test]
+fn test_stub_ties_all_outputs() {
+    let stub = vig::mock::generate_stub(&block());
+    assert!(stub.starts_with("-- filt のスタブ"), "{}", stub);
+    assert!(stub.contains("architecture stub of filt is"));
+    assert!(stub.contains("    dout <= (others => '0');\n"));
+    assert!(stub.contains("    valid <= '0';\n"));
+    assert!(!stub.contains("rising_edge"));
+}
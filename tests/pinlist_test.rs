@@ -0,0 +1,24 @@
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::pinlist::{PinListFormat, generate_pin_list};
+
+#[test]
+fn test_csv() {
+    let result = analyze_file("testdata/uart_tx.vhd").unwrap();
+    let csv = generate_pin_list(&result.entities[0], PinListFormat::Csv);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "name,direction,width,bank");
+    assert!(lines.contains(&"clk,in,1,"));
+    assert!(lines.contains(&"tx_data,in,8,"));
+    assert!(lines.contains(&"tx_out,out,1,"));
+    assert_eq!(lines.len(), result.entities[0].ports.len() + 1);
+}
+
+#[test]
+fn test_tsv_and_unknown_width() {
+    let result = analyze_vhdl("entity e is port (n : in integer; b : out boolean); end;").unwrap();
+    let tsv = generate_pin_list(&result.entities[0], PinListFormat::Tsv);
+    assert_eq!(
+        tsv,
+        "name\tdirection\twidth\tbank\nn\tin\t\t\nb\tout\t1\t\n"
+    );
+}
@@ -0,0 +1,87 @@
+use vig::prelude::*;
+
+#[test]
+fn test_prelude_analyze_and_build() {
+    let source = std::fs::read_to_string("testdata/counter.vhd").unwrap();
+    let result: AnalyzeResult = analyze(&source).unwrap();
+    let entity: &Entity = &result.entities[0];
+    let port: &Port = &entity.ports[0];
+    assert_eq!(port.direction, PortDirection::In);
+    assert_eq!(port.vhdl_type, VhdlType::StdLogic);
+
+    let tb = TestbenchBuilder::new(entity).clock_period_ns(40).build();
+    assert!(tb.contains("周期 40 ns"));
+    assert!(tb.contains("wait for 20 ns;"));
+}
+
+#[test]
+fn test_builder_with_config() {
+    let result = analyze("entity e is port (clk : in std_logic); end entity;").unwrap();
+    let mut config = TbConfig::default();
+    config.clock_period_ns = 8;
+    let tb = TestbenchBuilder::new(&result.entities[0])
+        .config(config)
+        .build();
+    assert!(tb.contains("wait for 4 ns;"));
+}
+
+#[test]
+fn test_diagnose_reports_lex_and_analyze_errors() {
+    let diagnostics = vig::diagnostic::diagnose("entity $ is\nport (a : in std_logic);");
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| d.message.contains("unexpected character"))
+    );
+    assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+}
+
+#[test]
+fn test_diagnose_clean_source() {
+    let source = std::fs::read_to_string("testdata/uart_tx.vhd").unwrap();
+    assert!(vig::diagnostic::diagnose(&source).is_empty());
+}
+
+#[test]
+fn test_diagnostic_display() {
+    let d = Diagnostic::warning("unused signal", vig::lexer::Span::new(3, 7));
+    assert_eq!(d.to_string(), "warning: unused signal at position 3-7");
+}
+
+#[test]
+fn test_entity_builder() {
+    let entity = Entity::builder("fifo")
+        .port("clk", PortDirection::In, VhdlType::StdLogic)
+        .input("rst", VhdlType::StdLogic)
+        .input(
+            "din",
+            VhdlType::StdLogicVector {
+                high: 7.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .output(
+            "dout",
+            VhdlType::StdLogicVector {
+                high: 7.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .port_def(Port::new("empty", PortDirection::Buffer, VhdlType::Boolean))
+        .build();
+    assert_eq!(entity.name, "fifo");
+    assert_eq!(entity.ports.len(), 5);
+    assert_eq!(entity.ports[3].direction, PortDirection::Out);
+    assert_eq!(
+        entity.ports[4],
+        Port::new("empty", PortDirection::Buffer, VhdlType::Boolean)
+    );
+
+    // 解析結果と同じようにテストベンチを生成できる
+    let tb = TestbenchBuilder::new(&entity).build();
+    assert!(tb.contains("entity fifo_tb is"));
+    assert!(tb.contains("signal din : std_logic_vector(7 downto 0)"));
+    assert!(tb.contains("clk_process"));
+}
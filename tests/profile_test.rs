@@ -0,0 +1,122 @@
+use vig::analyze;
+use vig::assertion::Property;
+use vig::generator::{
+    AssertSeverity, ResetPolarity, TbConfig, TestbenchBuilder, generate_testbench,
+};
+use vig::profile::from_config;
+
+const SOURCE: &str = "\
+entity uart_tx is
+    port (
+        clk     : in  std_logic;
+        rst_n   : in  std_logic;
+        clk_sel : in  std_logic;
+        start   : in  std_logic;
+        busy    : out std_logic
+    );
+end entity;
+";
+
+const CONFIG: &str = r#"
+[entity.uart_tx]
+clock_period_ns = 20
+reset = "active_low"
+coverage = true
+fsm = false
+exclude_ports = ["CLK_SEL"]
+"#;
+
+#[test]
+fn test_from_config() {
+    let profile = from_config(CONFIG, "UART_TX").unwrap().unwrap();
+    assert_eq!(profile.clock_period_ns, Some(20));
+    assert_eq!(profile.reset_polarity, Some(ResetPolarity::ActiveLow));
+    assert_eq!(profile.toggle_coverage, Some(true));
+    assert_eq!(profile.fsm, Some(false));
+    assert_eq!(profile.exclude_ports, ["CLK_SEL"]);
+    assert!(from_config(CONFIG, "other").unwrap().is_none());
+}
+
+#[test]
+fn test_from_config_errors() {
+    let err = from_config("[entity.a]\nreset = \"low\"\n", "a").unwrap_err();
+    assert_eq!(
+        err.message,
+        "'reset' must be \"active_high\" or \"active_low\""
+    );
+    let err = from_config("[entity.a]\nclock_period_ns = 0\n", "a").unwrap_err();
+    assert_eq!(err.message, "'clock_period_ns' must be a positive integer");
+    let err = from_config("[entity.a]\ncoverage = 1\n", "a").unwrap_err();
+    assert_eq!(err.message, "'coverage' must be a boolean");
+    let err = from_config("[entity.a]\nstyle = \"x\"\n", "a").unwrap_err();
+    assert_eq!(err.message, "unknown field 'style' in profile of 'a'");
+    assert_eq!(err.span.start, "[entity.a]\n".len());
+}
+
+#[test]
+fn test_apply_profile() {
+    let entity = analyze(SOURCE).unwrap().entities.remove(0);
+    let mut config = TbConfig::default();
+    from_config(CONFIG, "uart_tx")
+        .unwrap()
+        .unwrap()
+        .apply(&mut config);
+    let tb = generate_testbench(&entity, &config);
+
+    assert!(tb.contains("        wait for 10 ns;\n        clk <= '1';"));
+    // 負論理のリセット
+    assert!(tb.contains("        rst_n <= '0';\n        wait for 40 ns;\n        rst_n <= '1';"));
+    // 除外したポートは接続するが例やカバレッジには出さない
+    assert!(tb.contains("            clk_sel => clk_sel,"));
+    assert!(!tb.contains("-- clk_sel <="));
+    assert!(tb.contains("toggle_coverage: process"));
+}
+
+#[test]
+fn test_active_low_reset_in_assertions() {
+    let entity = analyze(SOURCE).unwrap().entities.remove(0);
+    let tb = TestbenchBuilder::new(&entity)
+        .reset_polarity(ResetPolarity::ActiveLow)
+        .assertion(Property::parse("after reset, busy = 0").unwrap())
+        .assertion(Property::parse("always not (busy and start)").unwrap())
+        .build();
+    assert!(tb.contains("wait until rst_n = '0';\n        wait until rst_n = '1';"));
+    assert!(tb.contains("if rising_edge(clk) and rst_n = '1' then"));
+}
+
+#[test]
+fn test_excluded_port_is_not_a_clock() {
+    let source = "entity e is port (clk_en : in std_logic; d : in std_logic); end entity;";
+    let entity = analyze(source).unwrap().entities.remove(0);
+    let tb = TestbenchBuilder::new(&entity)
+        .exclude_port("clk_en")
+        .build();
+    assert!(!tb.contains("clk_process"));
+}
+
+#[test]
+fn test_severities_from_config() {
+    let config = "\
+[entity.uart_tx]
+check_severity = \"failure\"
+timeout_ns = 100000
+finish_severity = \"FAILURE\"
+";
+    let profile = from_config(config, "uart_tx").unwrap().unwrap();
+    assert_eq!(profile.check_severity, Some(AssertSeverity::Failure));
+    assert_eq!(profile.timeout_ns, Some(100000));
+    assert_eq!(profile.timeout_severity, None);
+
+    let mut tb_config = TbConfig::default();
+    profile.apply(&mut tb_config);
+    assert_eq!(tb_config.check_severity, AssertSeverity::Failure);
+    assert_eq!(tb_config.timeout_ns, Some(100000));
+    assert_eq!(tb_config.timeout_severity, AssertSeverity::Failure);
+    assert_eq!(tb_config.finish_severity, AssertSeverity::Failure);
+
+    let err = from_config("[entity.a]\ncheck_severity = \"fatal\"\n", "a").unwrap_err();
+    assert_eq!(
+        err.message,
+        "'check_severity' must be \"note\", \"warning\", \"error\" or \"failure\""
+    );
+}
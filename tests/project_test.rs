@@ -0,0 +1,182 @@
+use vig::project::{Language, Origin, analyze_project};
+
+const TOP: &str = r#"
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+    port (clk : in std_logic);
+end entity;
+
+architecture rtl of top is
+    component Core
+        port (clk : in std_logic);
+    end component;
+    signal s : std_logic;
+begin
+    u_core: Core port map (clk => clk);
+    u_fifo: entity work.fifo port map (clk => clk);
+    u_comp: component core port map (clk => clk);
+    u_prim: BUFG port map (I => clk, O => s);
+    u_ip: clk_wiz_0 port map (clk_in1 => clk);
+    p: process (clk) begin end process;
+end architecture;
+"#;
+
+const CORE: &str = "module core (input clk);\n\
+                    adder u_add (.clk(clk));\n\
+                    endmodule\n\
+                    module adder (input clk); endmodule\n";
+
+const FIFO: &str = "entity fifo is port (clk : in std_logic); end entity;";
+
+fn project() -> vig::project::Project {
+    analyze_project(&[("top.vhd", TOP), ("core.v", CORE), ("fifo.vhdl", FIFO)]).unwrap()
+}
+
+#[test]
+fn test_language_from_path() {
+    assert_eq!(Language::from_path("a.vhd"), Language::Vhdl);
+    assert_eq!(Language::from_path("dir/A.SV"), Language::Verilog);
+    assert_eq!(Language::from_path("b.v"), Language::Verilog);
+}
+
+#[test]
+fn test_units_in_one_namespace() {
+    let project = project();
+    let names: Vec<_> = project
+        .units
+        .iter()
+        .map(|u| u.entity.name.as_str())
+        .collect();
+    // 組み込みのスタブはファイルの設計単位のあと
+    assert_eq!(names, ["top", "core", "adder", "fifo", "BUFG"]);
+    assert_eq!(project.unit("core").unwrap().language, Language::Verilog);
+    assert_eq!(project.unit("fifo").unwrap().origin, Origin::File(2));
+    assert_eq!(project.files[2], "fifo.vhdl");
+}
+
+#[test]
+fn test_cross_language_resolution() {
+    let project = project();
+    let top = project.unit("top").unwrap();
+    let labels: Vec<_> = top.instances.iter().map(|i| i.label.as_str()).collect();
+    assert_eq!(labels, ["u_core", "u_fifo", "u_comp", "u_prim", "u_ip"]);
+    // VHDL からは大文字小文字を区別せずに解決する
+    assert_eq!(top.instances[0].resolved, project.find("core"));
+    assert_eq!(top.instances[1].resolved, project.find("fifo"));
+    assert_eq!(top.instances[2].resolved, project.find("core"));
+    assert_eq!(top.instances[4].resolved, None);
+
+    let unresolved = project.unresolved();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].1.unit, "clk_wiz_0");
+}
+
+#[test]
+fn test_tops_and_hierarchy() {
+    let project = project();
+    assert_eq!(project.tops(), vec![0]);
+    let tree = project.render_hierarchy();
+    assert!(tree.starts_with("top (VHDL, top.vhd)\n"), "{}", tree);
+    assert!(
+        tree.contains("  u_core: core (Verilog, core.v)\n    u_add: adder (Verilog, core.v)\n")
+    );
+    assert!(
+        tree.contains("  u_prim: BUFG (unisim, 組み込み)\n"),
+        "{}",
+        tree
+    );
+    assert!(tree.contains("  u_ip: clk_wiz_0 (未解決)\n"));
+}
+
+#[test]
+fn test_duplicate_unit() {
+    let err = analyze_project(&[
+        ("a.vhd", FIFO),
+        ("b.v", "module FIFO (input clk); endmodule"),
+    ])
+    .unwrap_err();
+    assert_eq!(err.path, "b.v");
+    assert!(
+        err.message.contains("duplicate design unit 'FIFO'"),
+        "{}",
+        err.message
+    );
+}
+
+#[test]
+fn test_error_carries_path() {
+    let err = analyze_project(&[("bad.v", "module m (a); endmodule")]).unwrap_err();
+    assert_eq!(err.path, "bad.v");
+    assert!(err.to_string().starts_with("bad.v: "));
+    let err: vig::Error = err.into();
+    assert!(err.to_string().starts_with("project error: "));
+}
+
+#[test]
+fn test_vendor_primitives_resolve_to_builtin_stubs() {
+    let project = project();
+    let top = project.unit("top").unwrap();
+    let bufg = &project.units[top.instances[3].resolved.unwrap()];
+    assert_eq!(bufg.origin, Origin::Builtin("unisim"));
+    assert_eq!(bufg.entity.name, "BUFG");
+    // 設計のファイルのものではないので、最上位にも重複にもならない
+    assert_eq!(project.tops(), vec![0]);
+
+    // Verilog からのインスタンスも同じスタブになる
+    let project = analyze_project(&[(
+        "top.v",
+        "module top (input p, input n);
+IBUFDS u_ibuf (.I(p), .IB(n));
+endmodule",
+    )])
+    .unwrap();
+    assert!(project.unresolved().is_empty());
+    assert_eq!(project.units[1].origin, Origin::Builtin("unisim"));
+}
+
+#[test]
+fn test_design_unit_shadows_builtin_stub() {
+    let project = analyze_project(&[
+        ("top.vhd", TOP),
+        ("core.v", CORE),
+        ("fifo.vhdl", FIFO),
+        (
+            "bufg.vhd",
+            "entity bufg is port (I : in std_logic; O : out std_logic); end entity;",
+        ),
+    ])
+    .unwrap();
+    let top = project.unit("top").unwrap();
+    let bufg = top.instances[3].resolved.unwrap();
+    assert_eq!(project.units[bufg].origin, Origin::File(3));
+    assert!(
+        project
+            .units
+            .iter()
+            .all(|u| !matches!(u.origin, Origin::Builtin(_)))
+    );
+}
+
+#[test]
+fn test_unbound_components() {
+    let pkg = "package comps is
+    component dma port (req : in std_logic; ack : out std_logic); end component;
+    component BUFG port (I : in std_logic; O : out std_logic); end component;
+end package;";
+    let project = analyze_project(&[
+        ("top.vhd", TOP),
+        ("core.v", CORE),
+        ("comps.vhd", pkg),
+        ("more.vhd", "architecture a of top2 is\n component DMA port (req : in std_logic); end component;\nbegin end;"),
+    ])
+    .unwrap();
+    assert_eq!(project.components.len(), 4);
+    // core は Verilog のモジュール、BUFG は組み込みのスタブがある。dma は最初の宣言だけ
+    let unbound = project.unbound_components();
+    assert_eq!(unbound.len(), 1);
+    assert_eq!(unbound[0].interface.name, "dma");
+    assert_eq!(project.files[unbound[0].file], "comps.vhd");
+    assert_eq!(unbound[0].interface.ports.len(), 2);
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use vig::TbConfig;
+use vig::analyzer::{PortDirection, VhdlType, analyze_vhdl};
+use vig::regmap::{Access, BusProtocol, RegisterMap};
+
+fn load(path: &str) -> RegisterMap {
+    let source = fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path));
+    RegisterMap::from_toml(&source).expect("failed to load register map")
+}
+
+#[test]
+fn test_load_toml() {
+    let map = load("testdata/regs.toml");
+    assert_eq!(map.name, "uart_regs");
+    assert_eq!(map.bus, BusProtocol::AxiLite);
+    assert_eq!(map.data_width, 32);
+    assert_eq!(map.addr_width, 4);
+    assert_eq!(map.registers.len(), 4);
+
+    let ctrl = &map.registers[0];
+    assert_eq!(ctrl.fields[1].name, "parity");
+    assert_eq!((ctrl.fields[1].lsb, ctrl.fields[1].width), (4, 2));
+    assert_eq!(ctrl.reset_value(), 0x20);
+
+    // フィールド省略時はデータ幅全体の1フィールド
+    let baud = &map.registers[2];
+    assert_eq!(baud.fields.len(), 1);
+    assert_eq!(baud.fields[0].width, 32);
+    assert_eq!(baud.reset_value(), 0x1b2);
+    assert_eq!(map.registers[3].fields[0].access, Access::WriteOnly);
+}
+
+#[test]
+fn test_yaml_matches_toml() {
+    let source = fs::read_to_string("testdata/regs.yaml").expect("failed to read regs.yaml");
+    let map = RegisterMap::from_yaml(&source).expect("failed to load register map");
+    assert_eq!(map, load("testdata/regs.toml"));
+
+    let err =
+        RegisterMap::from_yaml("name: x\nregisters:\n  - name: a\n   offset: 0\n").unwrap_err();
+    assert!(err.message.contains("indentation"), "{}", err.message);
+}
+
+#[test]
+fn test_entity_ports() {
+    let entity = load("testdata/regs.toml").to_entity();
+    let port = |name: &str| entity.ports.iter().find(|p| p.name == name).unwrap();
+    assert_eq!(port("ctrl_enable").direction, PortDirection::Out);
+    assert_eq!(port("status_busy").direction, PortDirection::In);
+    assert_eq!(
+        port("status_rx_count").vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    assert_eq!(port("baud_div").direction, PortDirection::Out);
+    assert_eq!(
+        port("s_axi_awaddr").vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 3.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+}
+
+#[test]
+fn test_generated_vhdl_is_analyzable() {
+    let map = load("testdata/regs.toml");
+    let vhdl = map.generate_vhdl();
+    let result = analyze_vhdl(&vhdl).expect("generated VHDL should analyze");
+    let names: Vec<_> = result.entities[0].ports.iter().map(|p| &p.name).collect();
+    let expected = map.to_entity();
+    let expected: Vec<_> = expected.ports.iter().map(|p| &p.name).collect();
+    assert_eq!(names, expected);
+    assert!(vhdl.contains("when 2 =>\n                            reg_baud_div <="));
+    assert!(vhdl.contains("axi_rdata(15 downto 8) <= status_rx_count;"));
+    assert!(vhdl.contains("signal reg_baud_div : std_logic_vector(31 downto 0) := x\"000001B2\";"));
+}
+
+#[test]
+fn test_testbench_checks_reset_and_read_back() {
+    let tb = load("testdata/regs.toml").generate_testbench(&TbConfig::default());
+    assert!(tb.contains("uut: uart_regs"));
+    assert!(tb.contains("procedure bus_write"));
+    assert!(tb.contains("s_axi_aresetn <= '0';"));
+    assert!(tb.contains("assert rdata = x\"00000020\" report \"ctrl: unexpected reset value\""));
+    // 書き込み可能なビットを反転して書き込む
+    assert!(tb.contains("bus_write(16#0#, x\"00000011\");"));
+    assert!(tb.contains("bus_write(16#C#, x\"000000FF\");"));
+    // 読み出し専用・書き込み専用は読み戻し確認しない
+    assert!(!tb.contains("tx_data: read back mismatch"));
+    assert!(!tb.contains("status: read back mismatch"));
+}
+
+#[test]
+fn test_markdown() {
+    let md = load("testdata/regs.toml").generate_markdown();
+    assert!(md.starts_with("# uart_regs レジスタマップ\n"));
+    assert!(md.contains("| 0x08 | baud_div | RW | 0x000001B2 | ボーレート分周比 |"));
+    assert!(md.contains("| 5:4 | parity | RW | 0x2 | パリティ設定 |"));
+}
+
+#[test]
+fn test_avalon_from_json() {
+    let json = r#"{
+        "name": "leds",
+        "bus": "avalon",
+        "registers": [
+            { "name": "led", "offset": 0, "fields": [{ "name": "on", "bits": "3:0" }] },
+            { "name": "sw", "offset": 4, "access": "ro" }
+        ]
+    }"#;
+    let map = RegisterMap::from_json(json).unwrap();
+    assert_eq!(map.bus, BusProtocol::Avalon);
+    let vhdl = map.generate_vhdl();
+    assert!(vhdl.contains("avs_address : in std_logic_vector(0 downto 0)"));
+    assert!(vhdl.contains("avs_readdata(31 downto 0) <= sw;"));
+    assert!(analyze_vhdl(&vhdl).is_ok());
+}
+
+#[test]
+fn test_validation_errors() {
+    let cases = [
+        ("name = \"x\"\n", "no registers"),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 2\n",
+            "not aligned",
+        ),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0\n[[registers]]\nname = \"b\"\noffset = 0\n",
+            "share offset",
+        ),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0\n[[registers.fields]]\nname = \"f\"\nbits = \"3:0\"\n[[registers.fields]]\nname = \"g\"\nbits = 2\n",
+            "overlap",
+        ),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0\n[[registers.fields]]\nname = \"f\"\nbits = 1\nreset = 2\n",
+            "does not fit",
+        ),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0\n[[registers.fields]]\nname = \"f\"\nbits = \"32:0\"\n",
+            "exceeds the data width",
+        ),
+        // 桁あふれする範囲・オフセット
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0\n[[registers.fields]]\nname = \"f\"\nbits = \"4294967295:0\"\n",
+            "exceeds the data width",
+        ),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0\n[[registers.fields]]\nname = \"f\"\nbits = 4294967296\n",
+            "invalid 'bits'",
+        ),
+        (
+            "name = \"x\"\n[[registers]]\nname = \"a\"\noffset = 0x7ffffffffffffffc\n",
+            "out of range",
+        ),
+        ("name = \"x\"\nbus = \"wishbone\"\n", "invalid 'bus'"),
+        ("name = \n", "unexpected"),
+    ];
+    for (source, expected) in cases {
+        let err = RegisterMap::from_toml(source).unwrap_err();
+        assert!(
+            err.message.contains(expected),
+            "{:?}: expected '{}' in '{}'",
+            source,
+            expected,
+            err.message
+        );
+    }
+}
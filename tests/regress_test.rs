@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use vig::generator::TbConfig;
+use vig::regress::{matches_filter, plan, run, summary_table, write_testbenches};
+use vig::sim::{SimCommand, SimJob, Simulator};
+use vig::simlog::SimStatus;
+
+const COUNTER: &str = "\
+entity counter is
+    port (clk : in std_logic; count : out std_logic_vector(7 downto 0));
+end entity;
+";
+
+const UART: &str = "\
+entity uart_tx is
+    port (clk : in std_logic; busy : out std_logic);
+end entity;
+";
+
+const UART_TB: &str = "\
+entity uart_tx_tb is
+end entity;
+
+architecture sim of uart_tx_tb is
+begin
+    dut: entity work.uart_tx port map (clk => open, busy => open);
+end architecture;
+";
+
+fn designs() -> Vec<(PathBuf, String)> {
+    vec![
+        ("tb/uart_tx_tb.vhd".into(), UART_TB.to_string()),
+        ("rtl/counter.vhd".into(), COUNTER.to_string()),
+        ("rtl/uart_tx.vhd".into(), UART.to_string()),
+    ]
+}
+
+#[test]
+fn test_matches_filter() {
+    assert!(matches_filter("UART_TX", "uart_*"));
+    assert!(matches_filter("uart_tx", "*"));
+    assert!(matches_filter("fifo_1", "fifo_?"));
+    assert!(!matches_filter("fifo_10", "fifo_?"));
+    assert!(matches_filter("spi", "uart_*, spi"));
+    assert!(!matches_filter("uart_tx", "uart"));
+}
+
+#[test]
+fn test_plan_reuses_existing_testbench() {
+    let benches = plan(&designs(), "*", |_, _| TbConfig::default());
+    // ポートのないテストベンチ自身は対象にしない
+    let names: Vec<&str> = benches.iter().map(|b| b.entity.as_str()).collect();
+    assert_eq!(names, ["counter", "uart_tx"]);
+
+    assert_eq!(benches[0].top, "counter_tb");
+    assert!(
+        benches[0]
+            .generated
+            .as_ref()
+            .unwrap()
+            .contains("uut: counter")
+    );
+    assert_eq!(benches[1].top, "uart_tx_tb");
+    assert!(benches[1].generated.is_none());
+    // テストベンチは依存先の後にコンパイルする
+    assert_eq!(
+        benches[1].sources,
+        [
+            PathBuf::from("rtl/counter.vhd"),
+            "rtl/uart_tx.vhd".into(),
+            "tb/uart_tx_tb.vhd".into()
+        ]
+    );
+
+    let benches = plan(&designs(), "uart*", |_, _| TbConfig::default());
+    assert_eq!(benches.len(), 1);
+}
+
+#[test]
+fn test_plan_uses_entity_config() {
+    let benches = plan(&designs(), "counter", |path, entity| {
+        assert_eq!(path, PathBuf::from("rtl/counter.vhd"));
+        assert_eq!(entity.name, "counter");
+        let mut config = TbConfig::default();
+        config.clock_period_ns = 40;
+        config
+    });
+    assert!(
+        benches[0]
+            .generated
+            .as_ref()
+            .unwrap()
+            .contains("wait for 20 ns;")
+    );
+}
+
+/// テストベンチ名に error を含むものだけ失敗を報告する偽のシミュレータ
+struct Fake;
+
+impl Simulator for Fake {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn compile_commands(&self, _job: &SimJob) -> Vec<SimCommand> {
+        Vec::new()
+    }
+
+    fn run_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        let mut lines = vec![format!(
+            "{}.vhd:10:5:@20ns:(assertion warning): slow",
+            job.top
+        )];
+        if job.top.contains("uart") {
+            lines.push(format!(
+                "{}.vhd:12:5:@50ns:(assertion error): busy stuck",
+                job.top
+            ));
+        }
+        lines.push(format!(
+            "{}.vhd:20:5:@130ns:(assertion note): Simulation finished",
+            job.top
+        ));
+        vec![SimCommand::new("echo", [lines.join("\n")])]
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_in_parallel() {
+    let dir = std::env::temp_dir().join("vig_regress_test");
+    let mut benches = plan(&designs(), "*", |_, _| TbConfig::default());
+    write_testbenches(&mut benches, &dir.join("tb")).unwrap();
+    let generated = dir.join("tb").join("counter_tb.vhd");
+    assert_eq!(benches[0].sources.last(), Some(&generated));
+    assert!(generated.is_file());
+
+    let mut template = SimJob::default();
+    template.work_dir = dir.join("work");
+    let results = run(&benches, &Fake, &template, 4);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].top, "counter_tb");
+    assert_eq!(results[0].report.status, SimStatus::Passed);
+    assert_eq!(results[1].report.status, SimStatus::Failed);
+    assert!(dir.join("work").join("uart_tx_tb").is_dir());
+
+    let table = summary_table(&results);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(
+        lines[0],
+        "bench       result     errors  warnings  sim time    wall"
+    );
+    assert!(lines[1].starts_with("counter_tb  passed          0         1    130 ns"));
+    assert!(lines[2].starts_with("uart_tx_tb  FAILED          1         1    130 ns"));
+    assert_eq!(lines[4], "1 passed, 1 failed, 0 timed out");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extended_identifier_top() {
+    let dir = std::env::temp_dir().join("vig_regress_extended_test");
+    let designs = vec![(
+        PathBuf::from("rtl/my_top.vhd"),
+        "entity \\my top\\ is port (clk : in std_logic); end entity;".to_string(),
+    )];
+    let mut benches = plan(&designs, "*", |_, _| TbConfig::default());
+    assert_eq!(benches[0].top, "\\my top_tb\\");
+    write_testbenches(&mut benches, &dir.join("tb")).unwrap();
+    assert!(dir.join("tb").join("my_top_tb.vhd").is_file());
+
+    let mut template = SimJob::default();
+    template.work_dir = dir.join("work");
+    let results = run(&benches, &Fake, &template, 1);
+    assert_eq!(results[0].top, "\\my top_tb\\");
+    // work は拡張識別子の `\` や空白を含まないディレクトリに分ける
+    assert!(dir.join("work").join("my_top_tb").is_dir());
+}
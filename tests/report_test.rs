@@ -0,0 +1,86 @@
+use vig::diagnostic::diagnose;
+use vig::lexer::Span;
+use vig::report::{ReportFormat, render};
+use vig::source_map::SourceMap;
+use vig::{Diagnostic, Severity};
+
+const SOURCE: &str = "entity e is\n  port (\n    clk   std_logic\n  );\nend;\n";
+
+#[test]
+fn test_plain() {
+    let map = SourceMap::new("bad.vhd", SOURCE);
+    let text = render(&diagnose(SOURCE), &map, ReportFormat::Plain);
+    assert_eq!(
+        text,
+        "error: expected Colon, found StdLogic 'std_logic'
+ --> bad.vhd:3:11
+  |
+3 |     clk   std_logic
+  |           ^^^^^^^^^
+"
+    );
+}
+
+#[test]
+fn test_ansi_has_same_text() {
+    let map = SourceMap::new("bad.vhd", SOURCE);
+    let diagnostics = diagnose(SOURCE);
+    let ansi = render(&diagnostics, &map, ReportFormat::Ansi);
+    assert!(ansi.contains("\x1b[1;31merror\x1b[0m"));
+
+    // エスケープシーケンスを除くとPlainと一致
+    let mut stripped = String::new();
+    let mut chars = ansi.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+    assert_eq!(stripped, render(&diagnostics, &map, ReportFormat::Plain));
+}
+
+#[test]
+fn test_json() {
+    let map = SourceMap::new("bad.vhd", SOURCE);
+    let diagnostics = vec![
+        Diagnostic::warning("unused \"x\"", Span::new(0, 6)),
+        Diagnostic::new(Severity::Info, "note", Span::new(14, 18)),
+    ];
+    let json = render(&diagnostics, &map, ReportFormat::Json);
+    assert_eq!(
+        json,
+        concat!(
+            r#"[{"file":"bad.vhd","severity":"warning","message":"unused \"x\"","line":1,"column":1,"end_line":1,"end_column":7,"start":0,"end":6},"#,
+            r#"{"file":"bad.vhd","severity":"info","message":"note","line":2,"column":3,"end_line":2,"end_column":7,"start":14,"end":18}]"#,
+            "\n"
+        )
+    );
+}
+
+#[test]
+fn test_multiple_and_multiline_spans() {
+    let map = SourceMap::new("a.vhd", SOURCE);
+    let diagnostics = vec![
+        // 複数行にまたがる範囲は開始行の末尾まで下線を引く
+        Diagnostic::error("first", Span::new(14, 30)),
+        Diagnostic::warning("second", Span::new(SOURCE.len(), SOURCE.len())),
+    ];
+    let text = render(&diagnostics, &map, ReportFormat::Plain);
+    assert_eq!(
+        text,
+        "error: first
+ --> a.vhd:2:3
+  |
+2 |   port (
+  |   ^^^^^^
+
+warning: second
+ --> a.vhd:6:1
+  |
+6 |
+  | ^
+"
+    );
+}
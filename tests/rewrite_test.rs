@@ -0,0 +1,146 @@
+use vig::lexer::Span;
+use vig::rewrite::{Edit, Rewriter, apply};
+
+const SOURCE: &str = "\
+-- カウンタ
+entity Counter is
+    generic (WIDTH : integer := 8);
+    port (
+        clk   : in  std_logic;  -- クロック
+        count : out std_logic_vector(7 downto 0)
+    );
+end entity;
+
+architecture rtl of Counter is
+    signal cnt : unsigned(7 downto 0);
+begin
+    -- cnt を出力する
+    count <= std_logic_vector(CNT);
+end architecture;
+";
+
+#[test]
+fn test_rename_keeps_comments_and_formatting() {
+    let mut rw = Rewriter::new(SOURCE);
+    assert_eq!(rw.rename("cnt", "counter_q"), 2);
+    let out = rw.finish().unwrap();
+    assert!(out.contains("    signal counter_q : unsigned(7 downto 0);\n"));
+    assert!(out.contains("std_logic_vector(counter_q);"));
+    // コメント中の名前は変えない
+    assert!(out.contains("-- cnt を出力する"));
+    assert!(out.contains("        clk   : in  std_logic;  -- クロック\n"));
+}
+
+#[test]
+fn test_insert_port_follows_layout() {
+    let mut rw = Rewriter::new(SOURCE);
+    rw.insert_port("counter", "rst_n : in  std_logic").unwrap();
+    let out = rw.finish().unwrap();
+    assert!(out.contains(
+        "        count : out std_logic_vector(7 downto 0);\n        rst_n : in  std_logic\n    );"
+    ));
+}
+
+#[test]
+fn test_insert_port_creates_clause() {
+    let source = "entity e is\n    generic (N : integer := 1);\nend entity;\n";
+    let mut rw = Rewriter::new(source);
+    rw.insert_port("e", "a : in std_logic").unwrap();
+    assert_eq!(
+        rw.finish().unwrap(),
+        "entity e is\n    generic (N : integer := 1);\n    port (\n        a : in std_logic\n    );\nend entity;\n"
+    );
+
+    let err = Rewriter::new(source)
+        .insert_port("f", "a : in bit")
+        .unwrap_err();
+    assert_eq!(err.message, "entity 'f' not found");
+}
+
+#[test]
+fn test_change_range() {
+    let mut rw = Rewriter::new(SOURCE);
+    rw.change_range("count", "WIDTH - 1 downto 0").unwrap();
+    rw.change_range("cnt", "WIDTH - 1 downto 0").unwrap();
+    let out = rw.finish().unwrap();
+    assert!(out.contains("count : out std_logic_vector(WIDTH - 1 downto 0)\n"));
+    assert!(out.contains("signal cnt : unsigned(WIDTH - 1 downto 0);"));
+
+    // 範囲のない型
+    let err = Rewriter::new(SOURCE)
+        .change_range("clk", "1 downto 0")
+        .unwrap_err();
+    assert_eq!(err.message, "no declaration of 'clk' with a range");
+}
+
+#[test]
+fn test_apply_edits() {
+    let source = "a <= b;";
+    let out = apply(
+        source,
+        vec![
+            Edit::replace(Span::new(5, 6), "c"),
+            Edit::insert(0, "x"),
+            Edit::insert(0, "y"),
+            Edit::delete(Span::new(6, 7)),
+        ],
+    )
+    .unwrap();
+    assert_eq!(out, "xya <= c");
+
+    let err = apply(
+        source,
+        vec![
+            Edit::replace(Span::new(0, 4), "z"),
+            Edit::replace(Span::new(2, 6), "w"),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(err.message, "overlapping edits");
+    assert_eq!(err.span, Span::new(2, 6));
+}
+
+#[test]
+fn test_edits_follow_crlf() {
+    let source = SOURCE.replace('\n', "\r\n");
+    let mut rw = Rewriter::new(&source);
+    rw.insert_port("Counter", "rst : in std_logic").unwrap();
+    let out = rw.finish().unwrap();
+    assert!(out.contains("rst : in std_logic\r\n"), "{:?}", out);
+    assert_eq!(out.matches('\n').count(), out.matches("\r\n").count());
+}
+
+#[test]
+fn test_bom_is_kept() {
+    let source = format!("\u{feff}{}", SOURCE);
+    let mut rw = Rewriter::new(&source);
+    rw.rename("Counter", "Counter2");
+    assert!(
+        rw.finish()
+            .unwrap()
+            .starts_with("\u{feff}-- カウンタ\nentity Counter2 is")
+    );
+}
+
+#[test]
+fn test_reorder_ports_splits_separated_names() {
+    let source = "entity e is\n  port (\n    a, b : in std_logic;\n    y : out std_logic\n  );\nend entity;\n";
+    let mut rw = Rewriter::new(source);
+    rw.reorder_ports("e", &["b", "y"]).unwrap();
+    assert_eq!(
+        rw.finish().unwrap(),
+        "entity e is\n  port (\n    b : in std_logic;\n    y : out std_logic;\n    a : in std_logic\n  );\nend entity;\n"
+    );
+
+    // 続いたままなら宣言はそのまま
+    let mut rw = Rewriter::new(source);
+    rw.reorder_ports("e", &["y", "a", "b"]).unwrap();
+    assert_eq!(
+        rw.finish().unwrap(),
+        "entity e is\n  port (\n    y : out std_logic;\n    a, b : in std_logic\n  );\nend entity;\n"
+    );
+
+    let mut rw = Rewriter::new(source);
+    let err = rw.reorder_ports("e", &["z"]).unwrap_err();
+    assert_eq!(err.message, "entity 'e' has no port 'z'");
+}
@@ -0,0 +1,156 @@
+use vig::semantic::{LSP_TOKEN_TYPES, SemanticTokenKind, encode_lsp, semantic_tokens};
+
+/// 指定テキストの最初の出現位置にあるトークンの種類
+fn kind_at(
+    source: &str,
+    tokens: &[vig::semantic::SemanticToken],
+    text: &str,
+    nth: usize,
+) -> Option<SemanticTokenKind> {
+    let start = source.match_indices(text).nth(nth)?.0;
+    tokens
+        .iter()
+        .find(|t| t.span.start == start)
+        .map(|t| t.kind)
+}
+
+#[test]
+fn test_classify_counter() {
+    let source = std::fs::read_to_string("testdata/counter.vhd").unwrap();
+    let tokens = semantic_tokens(&source);
+
+    assert_eq!(
+        kind_at(&source, &tokens, "entity", 0),
+        Some(SemanticTokenKind::Keyword)
+    );
+    assert_eq!(
+        kind_at(&source, &tokens, "std_logic_vector", 0),
+        Some(SemanticTokenKind::Type)
+    );
+    // エンティティ名・アーキテクチャ内のポート参照・信号
+    assert_eq!(
+        kind_at(&source, &tokens, "counter ", 0),
+        Some(SemanticTokenKind::EntityName)
+    );
+    assert_eq!(
+        kind_at(&source, &tokens, "clk", 1),
+        Some(SemanticTokenKind::Port)
+    );
+    assert_eq!(
+        kind_at(&source, &tokens, "count <=", 0),
+        Some(SemanticTokenKind::Port)
+    );
+    assert_eq!(
+        kind_at(&source, &tokens, "counter_value", 2),
+        Some(SemanticTokenKind::Signal)
+    );
+    // 関数呼び出しは分類しない
+    assert_eq!(kind_at(&source, &tokens, "rising_edge", 0), None);
+}
+
+#[test]
+fn test_constants_generics_and_types() {
+    let source = "entity e is
+    generic (WIDTH : integer := 8; A, B : natural);
+    port (d : in my_word);
+end entity;
+architecture rtl of e is
+    type state_t is (IDLE, RUN);
+    constant MAX : integer := WIDTH - 1;
+    signal s : state_t;
+begin
+    s <= IDLE when d = MAX else RUN;
+end architecture;
+";
+    let tokens = semantic_tokens(source);
+    assert_eq!(
+        kind_at(source, &tokens, "WIDTH", 1),
+        Some(SemanticTokenKind::Constant)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "B", 0),
+        Some(SemanticTokenKind::Constant)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "natural", 0),
+        Some(SemanticTokenKind::Type)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "my_word", 0),
+        Some(SemanticTokenKind::Type)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "state_t", 1),
+        Some(SemanticTokenKind::Type)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "MAX", 1),
+        Some(SemanticTokenKind::Constant)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "constant", 0),
+        Some(SemanticTokenKind::Keyword)
+    );
+}
+
+#[test]
+fn test_scope_of_signals() {
+    // 別アーキテクチャの信号名は信号として扱わない
+    let source = "entity a is port (x : in std_logic); end;
+architecture r of a is signal t : std_logic; begin end architecture;
+entity b is port (y : out std_logic); end;
+architecture r of b is begin y <= t; end architecture;
+";
+    let tokens = semantic_tokens(source);
+    assert_eq!(
+        kind_at(source, &tokens, "t :", 0),
+        Some(SemanticTokenKind::Signal)
+    );
+    assert_eq!(kind_at(source, &tokens, "t;", 1), None);
+    assert_eq!(
+        kind_at(source, &tokens, "x", 0),
+        Some(SemanticTokenKind::Port)
+    );
+}
+
+#[test]
+fn test_invalid_source_still_classifies_keywords() {
+    let source = "entity broken is port (";
+    let tokens = semantic_tokens(source);
+    assert_eq!(
+        kind_at(source, &tokens, "entity", 0),
+        Some(SemanticTokenKind::Keyword)
+    );
+    assert_eq!(
+        kind_at(source, &tokens, "port", 0),
+        Some(SemanticTokenKind::Keyword)
+    );
+}
+
+#[test]
+fn test_encode_lsp() {
+    let source = "entity e is\n  port (\u{3042}x : in bit);\nend;";
+    let tokens = semantic_tokens(source);
+    let data = encode_lsp(source, &tokens);
+    assert_eq!(data.len() % 5, 0);
+    // entity, e, is
+    assert_eq!(&data[..5], &[0, 0, 6, 0, 0]);
+    assert_eq!(&data[5..10], &[0, 7, 1, 4, 0]);
+    assert_eq!(&data[10..15], &[0, 2, 2, 0, 0]);
+    // 次の行の port
+    assert_eq!(&data[15..20], &[1, 2, 4, 0, 0]);
+    assert_eq!(LSP_TOKEN_TYPES[data[18] as usize], "keyword");
+}
+
+#[test]
+fn test_component_instance_is_entity_name() {
+    let source = "entity sub is port (a : in std_logic); end;
+entity top is port (b : in std_logic); end;
+architecture rtl of top is begin u1 : sub port map (a => b); end architecture;
+";
+    let tokens = semantic_tokens(source);
+    assert_eq!(
+        kind_at(source, &tokens, "sub", 1),
+        Some(SemanticTokenKind::EntityName)
+    );
+}
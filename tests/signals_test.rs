@@ -0,0 +1,107 @@
+use vig::analyze;
+use vig::diagnostic::Severity;
+use vig::report::ReportFormat;
+use vig::signals::{SignalKind, check_multiple_drivers, render, usages};
+use vig::source_map::SourceMap;
+
+const SOURCE: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity arb is
+    port (
+        clk   : in  std_logic;
+        req   : in  std_logic;
+        grant : out std_logic;
+        bus_o : out std_logic
+    );
+end entity;
+
+architecture rtl of arb is
+    signal state : integer;
+    signal shared_bus : std_logic;
+begin
+    fsm: process(clk)
+    begin
+        if rising_edge(clk) then
+            state <= 1;
+            grant <= req;
+        end if;
+    end process;
+
+    state <= 0 when req = '1' else 2;
+    shared_bus <= req;
+    shared_bus <= 'Z';
+    bus_o <= shared_bus;
+end architecture;
+";
+
+fn entity() -> vig::analyzer::EntityDef {
+    analyze(SOURCE).unwrap().entities.remove(0)
+}
+
+#[test]
+fn test_drivers_and_readers() {
+    let archs = usages(SOURCE, &entity());
+    assert_eq!(archs.len(), 1);
+    assert_eq!(archs[0].architecture, "rtl");
+    let names: Vec<_> = archs[0].signals.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["clk", "req", "grant", "bus_o", "state", "shared_bus"]
+    );
+
+    let req = &archs[0].signals[1];
+    assert!(matches!(req.kind, SignalKind::Port(_)));
+    assert!(req.drivers.is_empty());
+    assert_eq!(req.readers.len(), 3);
+    assert_eq!(req.readers[0].label.as_deref(), Some("fsm"));
+    assert!(req.readers[0].is_process);
+    assert!(!req.readers[1].is_process);
+
+    let grant = &archs[0].signals[2];
+    assert_eq!(grant.drivers.len(), 1);
+    assert_eq!(grant.drivers[0].label.as_deref(), Some("fsm"));
+    assert!(grant.readers.is_empty());
+}
+
+#[test]
+fn test_multiply_driven_only_for_unresolved_types() {
+    let archs = usages(SOURCE, &entity());
+    let state = &archs[0].signals[4];
+    assert_eq!(state.kind, SignalKind::Signal);
+    assert_eq!(state.type_name, "integer");
+    assert!(!state.is_resolved());
+    assert!(state.is_multiply_driven());
+    // std_logic は解決関数を持つので複数駆動でもよい
+    let shared = &archs[0].signals[5];
+    assert_eq!(shared.drivers.len(), 2);
+    assert!(!shared.is_multiply_driven());
+
+    let diagnostics = check_multiple_drivers(SOURCE, &entity());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(
+        diagnostics[0].message,
+        "'state' has multiple drivers but its type 'integer' is not resolved"
+    );
+    let span = diagnostics[0].span;
+    assert_eq!(&SOURCE[span.start..span.end], "state");
+    assert_eq!(diagnostics[0].related.len(), 1);
+    assert!(diagnostics[0].related[0].0.start < span.start);
+}
+
+#[test]
+fn test_render() {
+    let map = SourceMap::new("arb.vhd", SOURCE);
+    let archs = usages(SOURCE, &entity());
+    let found = vec![(map, archs)];
+    let text = render(&found, ReportFormat::Plain);
+    assert!(text.starts_with("arb.vhd: arb (rtl)\n"));
+    assert!(text.contains(
+        "  state (signal integer) 複数駆動\n    駆動: プロセス fsm (20行目), 並行代入 (25行目)\n    参照: なし\n"
+    ));
+    let json = render(&found, ReportFormat::Json);
+    assert!(json.contains("\"multiply_driven\": true"));
+    assert!(json.contains("\"kind\": \"concurrent\""));
+}
@@ -0,0 +1,142 @@
+use vig::sim::{
+    Ghdl, Nvc, Questa, SimCommand, SimJob, Simulator, VhdlStandard, compile_order, simulator,
+    top_candidates,
+};
+
+fn job() -> SimJob {
+    let mut job = SimJob::new("alu_tb", ["rtl/alu.vhd", "tb/alu_tb.vhd"]);
+    job.work_dir = "build".into();
+    job.stop_time_ns = Some(500);
+    job
+}
+
+fn lines(sim: &dyn Simulator, job: &SimJob) -> Vec<String> {
+    sim.commands(job).iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn test_ghdl_commands() {
+    let mut job = job();
+    job.standard = VhdlStandard::Vhdl93;
+    assert_eq!(
+        lines(&Ghdl, &job),
+        [
+            "ghdl -a --std=93 --workdir=build rtl/alu.vhd",
+            "ghdl -a --std=93 --workdir=build tb/alu_tb.vhd",
+            "ghdl -e --std=93 --workdir=build alu_tb",
+            "ghdl -r --std=93 --workdir=build alu_tb --stop-time=500ns",
+        ]
+    );
+}
+
+#[test]
+fn test_nvc_commands() {
+    assert_eq!(
+        lines(&Nvc, &job()),
+        [
+            "nvc --std=2008 --work=build/work -a rtl/alu.vhd tb/alu_tb.vhd",
+            "nvc --std=2008 --work=build/work -e alu_tb -r alu_tb --stop-time=500ns",
+        ]
+    );
+}
+
+#[test]
+fn test_questa_commands() {
+    let mut job = job();
+    job.stop_time_ns = None;
+    assert_eq!(
+        lines(&Questa, &job),
+        [
+            "vlib build/work",
+            "vcom -2008 -work build/work rtl/alu.vhd tb/alu_tb.vhd",
+            "vsim -c -lib build/work alu_tb -do 'run -all; quit -f'",
+        ]
+    );
+}
+
+#[test]
+fn test_simulator_lookup() {
+    assert_eq!(simulator("GHDL").unwrap().name(), "ghdl");
+    assert_eq!(simulator("modelsim").unwrap().name(), "questa");
+    assert!(simulator("xsim").is_none());
+    assert_eq!("2002".parse(), Ok(VhdlStandard::Vhdl2002));
+    assert!("2019".parse::<VhdlStandard>().is_err());
+}
+
+#[test]
+fn test_compile_order() {
+    let tb = "entity alu_tb is end;
+architecture sim of alu_tb is
+    component alu port (a : in std_logic); end component;
+begin
+    uut: alu port map (a => open);
+end;";
+    let alu = "library work; use work.alu_pkg.all;\nentity alu is port (a : in std_logic); end entity alu;";
+    let arch = "architecture rtl of alu is begin end;";
+    let pkg = "package alu_pkg is end package;\npackage body alu_pkg is end package body;";
+    // テストベンチとアーキテクチャはどちらもエンティティの後ならよい
+    assert_eq!(compile_order(&[tb, arch, alu, pkg]), [3, 2, 0, 1]);
+    assert_eq!(compile_order(&[pkg, alu, arch, tb]), [0, 1, 2, 3]);
+    // 循環していても全ソースを返す
+    let a = "entity a is end; architecture x of a is begin u: entity work.b; end;";
+    let b = "entity b is end; architecture x of b is begin u: entity work.a; end;";
+    assert_eq!(compile_order(&[a, b]), [0, 1]);
+
+    assert_eq!(top_candidates(&[tb, arch, alu, pkg]), ["alu_tb"]);
+}
+
+struct Echo;
+
+impl Simulator for Echo {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn compile_commands(&self, job: &SimJob) -> Vec<SimCommand> {
+        vec![SimCommand::new("echo", [job.top.as_str()])]
+    }
+
+    fn run_commands(&self, _job: &SimJob) -> Vec<SimCommand> {
+        vec![
+            SimCommand::new("false", Vec::<String>::new()),
+            SimCommand::new("echo", ["not reached"]),
+        ]
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_simulate_stops_at_failure() {
+    let mut job = job();
+    job.work_dir = std::env::temp_dir().join("vig_sim_test");
+    let output = Echo.simulate(&job).unwrap();
+    assert_eq!(output.steps.len(), 2);
+    assert_eq!(output.steps[0].stdout, "alu_tb\n");
+    assert!(!output.success());
+    assert_eq!(output.log(), "alu_tb\n");
+}
+
+struct Missing;
+
+impl Simulator for Missing {
+    fn name(&self) -> &str {
+        "missing"
+    }
+
+    fn compile_commands(&self, _job: &SimJob) -> Vec<SimCommand> {
+        vec![SimCommand::new("vig-no-such-simulator", ["-a"])]
+    }
+
+    fn run_commands(&self, _job: &SimJob) -> Vec<SimCommand> {
+        Vec::new()
+    }
+}
+
+#[test]
+fn test_simulate_reports_missing_program() {
+    let mut job = job();
+    job.work_dir = std::env::temp_dir().join("vig_sim_test");
+    let err = Missing.simulate(&job).unwrap_err();
+    assert!(matches!(err, vig::Error::Io { .. }));
+    assert!(err.to_string().contains("vig-no-such-simulator"));
+}
@@ -0,0 +1,165 @@
+use vig::generator::AssertSeverity;
+use vig::simlog::{SimReport, SimStatus, format_time, to_junit, to_tap};
+
+const GHDL: &str = "\
+../src/ieee2008/numeric_std-body.vhdl:3036:7:@0ms:(assertion warning): NUMERIC_STD.TO_INTEGER: metavalue detected, returning 0
+counter_tb.vhd:80:13:@50ns:(assertion error): row 3: q mismatch
+counter_tb.vhd:85:13:@1us:(report note): toggle coverage: count: 254 toggles, 7/8 bits
+counter_tb.vhd:92:9:@1300ns:(assertion failure): Simulation finished
+./counter_tb:error: assertion failed
+in process .counter_tb(testbench).stim_process
+./counter_tb:error: simulation failed
+";
+
+const NVC: &str = "\
+** Warning: 0ms+0: Assertion Warning: NUMERIC_STD.TO_INTEGER: metavalue detected, returning 0
+** Note: 130ns+1: Report Note: Simulation finished
+   Process :counter_tb:stim_process at counter_tb.vhd:92
+";
+
+const MODELSIM: &str = "\
+# vsim -c -lib vig_work/work counter_tb -do \"run -all; quit -f\"
+# ** Note: (vsim-3812) Design is being optimized...
+# ** Error: row 3: q mismatch
+#    Time: 50 ns  Iteration: 1  Process: /counter_tb/stim_process File: counter_tb.vhd Line: 80
+# ** Failure: Simulation timed out after 100000 ns
+#    Time: 100 us  Iteration: 0  Process: /counter_tb/watchdog File: counter_tb.vhd
+";
+
+#[test]
+fn test_parse_ghdl() {
+    let report = SimReport::parse(GHDL);
+    assert_eq!(report.messages.len(), 4);
+    let error = &report.messages[1];
+    assert_eq!(error.severity, AssertSeverity::Error);
+    assert_eq!(error.text, "row 3: q mismatch");
+    assert_eq!(error.time_fs, Some(50_000_000));
+    assert_eq!(error.file.as_deref(), Some("counter_tb.vhd"));
+    assert_eq!(error.line, Some(80));
+    assert_eq!(report.messages[0].time_fs, Some(0));
+
+    // failure で止めた終了の報告は失敗に数えず、その後始末の行も無視する
+    assert!(report.finished);
+    assert!(report.tool_errors.is_empty());
+    assert_eq!(report.status, SimStatus::Failed);
+    assert_eq!(report.end_time_fs, Some(1_300_000_000));
+    assert_eq!(report.failures().count(), 1);
+    assert_eq!(report.summary(), "failed: 1 error, 1 warning at 1300 ns");
+}
+
+#[test]
+fn test_parse_nvc() {
+    let report = SimReport::parse(NVC);
+    assert_eq!(report.status, SimStatus::Passed);
+    assert_eq!(report.messages[0].severity, AssertSeverity::Warning);
+    assert_eq!(
+        report.messages[0].text,
+        "NUMERIC_STD.TO_INTEGER: metavalue detected, returning 0"
+    );
+    let finish = &report.messages[1];
+    assert_eq!(finish.text, "Simulation finished");
+    assert_eq!(finish.time_fs, Some(130_000_000));
+    assert_eq!(finish.file.as_deref(), Some("counter_tb.vhd"));
+    assert_eq!(finish.line, Some(92));
+    assert_eq!(report.summary(), "passed: 0 errors, 1 warning at 130 ns");
+}
+
+#[test]
+fn test_parse_modelsim() {
+    let report = SimReport::parse(MODELSIM);
+    // シミュレータ自身の note は報告に含めない
+    assert_eq!(report.messages.len(), 2);
+    assert_eq!(report.messages[0].time_fs, Some(50_000_000));
+    assert_eq!(report.messages[0].line, Some(80));
+    assert_eq!(report.messages[1].severity, AssertSeverity::Failure);
+    assert_eq!(report.messages[1].line, None);
+    assert_eq!(report.status, SimStatus::TimedOut);
+    assert!(!report.finished);
+    assert_eq!(
+        report.summary(),
+        "timed out: 2 errors, 0 warnings at 100 us (not finished)"
+    );
+}
+
+#[test]
+fn test_tool_errors() {
+    let report =
+        SimReport::parse("counter.vhd:12:5:error: ';' expected\nghdl:error: compilation error\n");
+    assert_eq!(report.tool_errors.len(), 2);
+    assert_eq!(report.status, SimStatus::Failed);
+
+    let report = SimReport::parse("** Error: counter.vhd(12): near \";\": syntax error\n");
+    assert_eq!(
+        report.tool_errors,
+        ["counter.vhd(12): near \";\": syntax error"]
+    );
+    assert!(report.messages.is_empty());
+}
+
+#[test]
+fn test_stop_time() {
+    let report = SimReport::parse("ghdl:info: simulation stopped by --stop-time @1us\n");
+    assert_eq!(report.status, SimStatus::Passed);
+    assert_eq!(report.end_time_fs, Some(1_000_000_000));
+    assert!(!report.finished);
+}
+
+#[test]
+fn test_format_time() {
+    assert_eq!(format_time(0), "0 fs");
+    assert_eq!(format_time(1_500_000), "1500 ps");
+    assert_eq!(format_time(2_000_000_000_000), "2 ms");
+}
+
+fn cases() -> Vec<(String, SimReport)> {
+    vec![
+        ("counter_tb".to_string(), SimReport::parse(NVC)),
+        ("uart_tb".to_string(), SimReport::parse(GHDL)),
+        ("fifo_tb".to_string(), SimReport::parse(MODELSIM)),
+        (
+            "alu_tb".to_string(),
+            SimReport::parse("alu.vhd:3:1:error: <identifier> expected\n"),
+        ),
+    ]
+}
+
+#[test]
+fn test_junit() {
+    let xml = to_junit("vig", &cases());
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"vig\" tests=\"4\" failures=\"2\" errors=\"1\">\n"));
+    assert!(
+        xml.contains("    <testcase name=\"counter_tb\" classname=\"vig\">\n      <system-out>")
+    );
+    assert!(xml.contains(
+        "      <failure message=\"row 3: q mismatch\" type=\"failed\">counter_tb.vhd:80: @50 ns: error: row 3: q mismatch</failure>\n"
+    ));
+    assert!(
+        xml.contains(
+            "type=\"timed out\">counter_tb.vhd:80: @50 ns: error: row 3: q mismatch\ncounter_tb.vhd: @100 us: failure: Simulation timed out after 100000 ns</failure>"
+        )
+    );
+    assert!(
+        xml.contains("      <error message=\"alu.vhd:3:1:error: &lt;identifier&gt; expected\">")
+    );
+    assert!(xml.ends_with("    </testcase>\n  </testsuite>\n</testsuites>\n"));
+}
+
+#[test]
+fn test_tap() {
+    let tap = to_tap(&cases());
+    let lines: Vec<&str> = tap.lines().collect();
+    assert_eq!(
+        lines[..4],
+        [
+            "TAP version 13",
+            "1..4",
+            "ok 1 - counter_tb",
+            "not ok 2 - uart_tb"
+        ]
+    );
+    assert!(tap.contains(
+        "not ok 2 - uart_tb\n  ---\n  status: failed\n  messages:\n    - \"counter_tb.vhd:80: @50 ns: error: row 3: q mismatch\"\n  ...\n"
+    ));
+    assert!(tap.contains("not ok 3 - fifo_tb\n  ---\n  status: timed out\n"));
+    assert!(tap.contains("    - \"alu.vhd:3:1:error: <identifier> expected\"\n"));
+}
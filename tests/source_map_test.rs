@@ -0,0 +1,106 @@
+use vig::analyzer::analyze_vhdl;
+use vig::lexer::{Lexer, Span, TokenKind};
+use vig::source_map::{LineEnding, SourceMap};
+
+#[test]
+fn test_line_col() {
+    let map = SourceMap::new("x.vhd", "ab\r\n-- あい\nc");
+    assert_eq!(map.line_count(), 3);
+    assert_eq!(map.line_col(0), (1, 1));
+    assert_eq!(map.line_col(4), (2, 1));
+    // 列は文字単位
+    assert_eq!(map.line_col(13), (2, 6));
+    assert_eq!(map.line_col(14), (3, 1));
+    assert_eq!(map.line_col(100), (3, 2));
+    assert_eq!(map.line_text(1), Some("ab"));
+    assert_eq!(map.line_text(2), Some("-- あい"));
+    assert_eq!(map.line_text(4), None);
+    assert_eq!(map.line_text(0), None);
+}
+
+#[test]
+fn test_offset_round_trip() {
+    let source = "\u{feff}ab\r\n-- あい\nc";
+    let map = SourceMap::new("x.vhd", source);
+    for offset in (0..=source.len()).filter(|&i| source.is_char_boundary(i)) {
+        let (line, col) = map.line_col(offset);
+        let back = map.offset(line, col).unwrap();
+        // BOM の中は行頭に寄せる
+        assert_eq!(map.line_col(back), (line, col), "{}", offset);
+    }
+    assert_eq!(map.offset(1, 1), Some(3));
+    assert_eq!(map.offset(1, 3), Some(5));
+    assert_eq!(map.offset(1, 4), Some(6));
+    assert_eq!(map.offset(1, 5), None);
+    assert_eq!(map.offset(2, 5), Some(13));
+    assert_eq!(map.offset(3, 2), Some(source.len()));
+    assert_eq!(map.offset(4, 1), None);
+    assert_eq!(map.offset(0, 1), None);
+    assert_eq!(map.offset(1, 0), None);
+
+    let span = Span::new(7, 16);
+    assert_eq!(map.span_line_cols(span), ((2, 1), (2, 6)));
+    assert_eq!(map.span((2, 1), (2, 6)), Some(span));
+    assert_eq!(map.span((2, 6), (2, 1)), None);
+}
+
+#[test]
+fn test_bom_is_not_a_column() {
+    let source = "\u{feff}entity e is\r\n  port (a : in bit);\r\nend;\r\n";
+    let map = SourceMap::new("x.vhd", source);
+    assert!(map.has_bom());
+    assert_eq!(map.line_col(3), (1, 1));
+    assert_eq!(map.line_text(1), Some("entity e is"));
+
+    // 位置は BOM を含む元のソースのバイトオフセット
+    let entity = &analyze_vhdl(source).unwrap().entities[0];
+    assert_eq!(&source[entity.ports[0].span.start..][..1], "a");
+    assert_eq!(map.line_col(entity.ports[0].span.start), (2, 9));
+}
+
+#[test]
+fn test_comment_excludes_cr() {
+    let tokens: Vec<_> = Lexer::new("-- note\r\nx").map(Result::unwrap).collect();
+    assert_eq!(tokens[0].kind, TokenKind::Comment);
+    assert_eq!(tokens[0].text, "-- note");
+    assert_eq!(tokens[0].span.end, 7);
+    assert_eq!(tokens[1].text, "x");
+}
+
+#[test]
+fn test_line_ending() {
+    assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::CrLf);
+    assert_eq!(LineEnding::detect("a\r\nb\nc\n"), LineEnding::Lf);
+    assert_eq!(LineEnding::detect("abc"), LineEnding::Lf);
+    assert_eq!(LineEnding::CrLf.apply("a\nb\r\n"), "a\r\nb\r\n");
+    assert_eq!(LineEnding::Lf.apply("a\r\nb\n"), "a\nb\n");
+}
+
+#[test]
+fn test_match_conventions() {
+    let map = SourceMap::new("x.vhd", "\u{feff}a\r\nb\r\n");
+    assert_eq!(map.line_ending(), LineEnding::CrLf);
+    assert_eq!(map.match_conventions("x\ny\n"), "\u{feff}x\r\ny\r\n");
+    let map = SourceMap::new("x.vhd", "a\nb\n");
+    assert_eq!(map.match_conventions("\u{feff}x\r\n"), "x\n");
+}
+
+#[test]
+fn test_error_locations() {
+    let source = "entity e is\r\n  port (a : in bit; b $ c);\nend;";
+    let err = Lexer::new(source)
+        .find_map(Result::err)
+        .unwrap()
+        .with_file("e.vhd");
+    assert_eq!(err.to_string(), "e.vhd:2:23: unexpected character: '$'");
+
+    let err = analyze_vhdl("library ieee;\n  entity is").unwrap_err();
+    let location = err.location.clone().unwrap();
+    assert_eq!((location.line, location.column), (2, 10));
+    assert_eq!(err.to_string(), "2:10: expected Identifier, found Is 'is'");
+    assert_eq!(
+        err.with_file("top.vhd").to_string(),
+        "top.vhd:2:10: expected Identifier, found Is 'is'"
+    );
+    assert_eq!(SourceMap::new("", "a").location(0).to_string(), "1:1");
+}
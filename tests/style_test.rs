@@ -0,0 +1,109 @@
+use vig::analyze;
+use vig::analyzer::EntityDef;
+use vig::generator::{AssertSeverity, TestbenchBuilder};
+use vig::style::TbStyle;
+
+const COUNTER: &str = "\
+entity counter is
+    port (
+        clk   : in  std_logic;
+        rst   : in  std_logic;
+        en    : in  std_logic;
+        din   : in  std_logic_vector(7 downto 0);
+        count : out std_logic_vector(7 downto 0);
+        done  : out std_logic
+    );
+end entity;
+";
+
+fn counter() -> EntityDef {
+    analyze(COUNTER).unwrap().entities.remove(0)
+}
+
+fn generate(style: TbStyle) -> String {
+    TestbenchBuilder::new(&counter()).style(style).build()
+}
+
+#[test]
+fn test_style_names() {
+    for style in TbStyle::ALL {
+        assert_eq!(style.as_str().parse::<TbStyle>(), Ok(style));
+    }
+    assert_eq!("Assert_Heavy".parse::<TbStyle>(), Ok(TbStyle::AssertHeavy));
+    assert_eq!(
+        "fancy".parse::<TbStyle>().unwrap_err(),
+        "unknown testbench style 'fancy'"
+    );
+    assert_eq!(TbStyle::default(), TbStyle::Standard);
+}
+
+#[test]
+fn test_every_style_is_valid_vhdl() {
+    for style in TbStyle::ALL {
+        let tb = generate(style);
+        let result = analyze(&tb).unwrap_or_else(|e| panic!("{}: {}", style.as_str(), e));
+        assert_eq!(result.entities[0].name, "counter_tb");
+        assert!(tb.contains("uut: counter"));
+        assert!(tb.contains("report \"Simulation finished\""));
+    }
+}
+
+#[test]
+fn test_minimal_style() {
+    let tb = generate(TbStyle::Minimal);
+    assert!(!tb.contains("    -- "));
+    assert!(!tb.contains("        -- "));
+    assert!(tb.contains("        rst <= '1';\n        wait for 20 ns;"));
+}
+
+#[test]
+fn test_assert_heavy_style() {
+    let tb = TestbenchBuilder::new(&counter())
+        .style(TbStyle::AssertHeavy)
+        .check_severity(AssertSeverity::Failure)
+        .build();
+    assert!(tb.contains(
+        "    output_checks: process(clk)\n    begin\n        if rising_edge(clk) and rst = '0' then\n            assert not is_x(count) report \"count is unknown\" severity failure;\n            assert not is_x(done) report \"done is unknown\" severity failure;\n        end if;\n    end process output_checks;"
+    ));
+    assert!(tb.contains(
+        "        -- assert count = (others => '0') report \"count mismatch\" severity failure;\n"
+    ));
+}
+
+#[test]
+fn test_file_driven_style() {
+    let tb = generate(TbStyle::FileDriven);
+    assert!(tb.starts_with("-- 入力ファイル counter_stim.txt の各行: en din count done\n"));
+    assert!(tb.contains("use std.textio.all;\nuse ieee.std_logic_textio.all;\n"));
+    assert!(tb.contains("        file stim_file : text open read_mode is \"counter_stim.txt\";\n"));
+    assert!(tb.contains("        variable din_value : std_logic_vector(7 downto 0);\n"));
+    assert!(tb.contains(
+        "            read(l, din_value);\n            din <= din_value;\n            read(l, count_value);\n            read(l, done_value);\n            wait for 10 ns;\n"
+    ));
+    assert!(tb.contains(
+        "            assert count = count_value report \"line \" & integer'image(n) & \": count mismatch\" severity error;\n"
+    ));
+}
+
+#[test]
+fn test_procedure_based_style() {
+    let tb = generate(TbStyle::ProcedureBased);
+    assert!(tb.contains(
+        "        procedure apply(en_value : std_logic; din_value : std_logic_vector(7 downto 0)) is\n        begin\n            en <= en_value;\n            din <= din_value;\n            tick;\n        end procedure;\n"
+    ));
+    assert!(tb.contains(
+        "        reset_dut;\n\n        -- TODO: テストパターンを記述\n        apply(en_value => '0', din_value => (others => '0'));\n        tick(10);\n"
+    ));
+    assert!(tb.contains("                wait until rising_edge(clk);\n"));
+}
+
+#[test]
+fn test_procedure_based_without_clock() {
+    let source = "entity mux is port (a, b, sel : in std_logic; y : out std_logic); end entity;";
+    let entity = analyze(source).unwrap().entities.remove(0);
+    let tb = TestbenchBuilder::new(&entity)
+        .style(TbStyle::ProcedureBased)
+        .build();
+    assert!(!tb.contains("reset_dut"));
+    assert!(tb.contains("                wait for 10 ns;\n"));
+}
@@ -0,0 +1,102 @@
+use vig::analyze;
+use vig::generator::TbConfig;
+use vig::sweep::{GenericSet, from_config, generate_sweep};
+
+const SOURCE: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity fifo is
+    generic (
+        DEPTH : natural := 4;
+        MODE  : string := \"fast\"
+    );
+    port (
+        clk  : in  std_logic;
+        din  : in  std_logic_vector(7 downto 0);
+        dout : out std_logic_vector(7 downto 0)
+    );
+end entity fifo;
+";
+
+const CONFIG: &str = r#"
+[[sweep.fifo]]
+name = "shallow"
+DEPTH = 4
+
+[[sweep.fifo]]
+DEPTH = 64
+MODE = '"safe"'
+
+[[sweep.other]]
+X = 1
+"#;
+
+#[test]
+fn test_from_config() {
+    let sets = from_config(CONFIG, "FIFO").unwrap();
+    assert_eq!(
+        sets,
+        vec![
+            GenericSet::new("shallow").value("DEPTH", "4"),
+            GenericSet::new("set2")
+                .value("DEPTH", "64")
+                .value("MODE", "\"safe\""),
+        ]
+    );
+    assert!(from_config(CONFIG, "missing").unwrap().is_empty());
+}
+
+#[test]
+fn test_from_config_errors() {
+    let err = from_config("[[sweep.fifo]]\nname = \"a b\"\n", "fifo").unwrap_err();
+    assert_eq!(err.message, "'name' must be a VHDL identifier");
+
+    let err = from_config("[[sweep.fifo]]\nDEPTH = [1, 2]\n", "fifo").unwrap_err();
+    assert_eq!(
+        err.message,
+        "generic 'DEPTH' must be an integer, real, boolean or string"
+    );
+
+    let source = "[[sweep.fifo]]\nname = \"a\"\n\n[[sweep.fifo]]\nname = \"a\"\n";
+    let err = from_config(source, "fifo").unwrap_err();
+    assert_eq!(err.message, "duplicate generic set 'a'");
+
+    let err = from_config("[sweep.fifo]\nDEPTH = 1\n", "fifo").unwrap_err();
+    assert!(
+        err.message
+            .starts_with("sweep of 'fifo' must be an array of tables")
+    );
+}
+
+#[test]
+fn test_generate_sweep() {
+    let entity = analyze(SOURCE).unwrap().entities.remove(0);
+    let sets = from_config(CONFIG, "fifo").unwrap();
+    let tb = generate_sweep(&entity, &sets, &TbConfig::default());
+
+    // 組ごとのハーネス（DUT は直接実体化）
+    assert!(tb.contains("entity fifo_tb_shallow is\n"));
+    assert!(tb.contains("entity fifo_tb_set2 is\n"));
+    assert!(!tb.contains("component fifo"));
+    assert!(tb.contains(
+        "    uut: entity work.fifo\n        generic map (\n            DEPTH => 64,\n            MODE => \"safe\"\n        )\n        port map (\n"
+    ));
+    assert_eq!(tb.matches("stim_process: process").count(), 2);
+
+    // すべてのハーネスを実体化するトップ
+    assert!(tb.ends_with(
+        "entity fifo_sweep_tb is\nend entity fifo_sweep_tb;\n\narchitecture sweep of fifo_sweep_tb is\nbegin\n\n    shallow_tb: entity work.fifo_tb_shallow;\n    set2_tb: entity work.fifo_tb_set2;\n\nend architecture sweep;\n"
+    ));
+}
+
+#[test]
+fn test_sweep_evaluates_widths() {
+    let source = SOURCE.replace("(7 downto 0)", "(DEPTH - 1 downto 0)");
+    let entity = analyze(&source).unwrap().entities.remove(0);
+    let sets = from_config(CONFIG, "fifo").unwrap();
+    let tb = generate_sweep(&entity, &sets, &TbConfig::default());
+
+    assert!(tb.contains("signal din : std_logic_vector(3 downto 0)"));
+    assert!(tb.contains("signal din : std_logic_vector(63 downto 0)"));
+}
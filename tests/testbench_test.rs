@@ -0,0 +1,260 @@
+use vig::analyzer::{EntityDef, VhdlType::*, analyze_vhdl};
+use vig::diagnostic::Severity;
+use vig::generator::TestbenchBuilder;
+use vig::testbench::{extract_dut, update_testbench};
+
+fn counter(high: i64) -> EntityDef {
+    EntityDef::builder("counter")
+        .input("clk", StdLogic)
+        .input("reset", StdLogic)
+        .input("load", StdLogic)
+        .output(
+            "count",
+            StdLogicVector {
+                high: high.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .build()
+}
+
+#[test]
+fn test_update_generated_testbench() {
+    let tb = TestbenchBuilder::new(&counter(7)).build();
+    let new = EntityDef::builder("counter")
+        .input("clk", StdLogic)
+        .input("reset", StdLogic)
+        .input("enable", StdLogic)
+        .output(
+            "count",
+            StdLogicVector {
+                high: 15.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .build();
+    let update = update_testbench(&tb, &new).unwrap();
+
+    // 再生成した場合と同じ宣言になり、それ以外は変わらない
+    assert!(update.text.contains(
+        "            clk : in std_logic;
+            reset : in std_logic;
+            count : out std_logic_vector(15 downto 0);
+            enable : in std_logic
+        );"
+    ));
+    assert!(update.text.contains(
+        "    signal reset : std_logic := '0';
+    signal count : std_logic_vector(15 downto 0) := (others => '0');
+    signal enable : std_logic := '0';
+"
+    ));
+    assert!(update.text.contains(
+        "            count => count,
+            enable => enable
+        );"
+    ));
+    assert!(!update.text.contains("load :"));
+    assert!(update.text.contains("-- load <= '0';"));
+    let result = analyze_vhdl(&update.text).unwrap();
+    assert_eq!(result.architectures[0].signals.len(), 4);
+
+    let messages: Vec<&str> = update.changes.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "removed port 'load'",
+            "changed port 'count' to 'out std_logic_vector(15 downto 0)'",
+            "added port 'enable'",
+            "removed signal 'load'",
+            "changed type of signal 'count' to 'std_logic_vector(15 downto 0)'",
+        ]
+    );
+}
+
+#[test]
+fn test_update_unchanged_testbench() {
+    let tb = TestbenchBuilder::new(&counter(7)).build();
+    let update = update_testbench(&tb, &counter(7)).unwrap();
+    assert_eq!(update.text, tb);
+    assert!(update.changes.is_empty());
+}
+
+#[test]
+fn test_update_keeps_referenced_signal() {
+    let tb = TestbenchBuilder::new(&counter(7)).build();
+    let new = EntityDef::builder("counter")
+        .input("clk", StdLogic)
+        .input("load", StdLogic)
+        .output(
+            "count",
+            StdLogicVector {
+                high: 7.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .build();
+    let update = update_testbench(&tb, &new).unwrap();
+    // スティミュラスで使っている reset は宣言を残して警告する
+    assert!(update.text.contains("signal reset : std_logic"));
+    assert!(!update.text.contains("reset => reset"));
+    let warning = update
+        .changes
+        .iter()
+        .find(|d| d.severity == Severity::Warning)
+        .unwrap();
+    assert_eq!(
+        warning.message,
+        "signal 'reset' is no longer connected but is still referenced"
+    );
+}
+
+#[test]
+fn test_update_hand_written_testbench() {
+    let tb = "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top_tb is
+end top_tb;
+
+architecture sim of top_tb is
+    function inv(b : std_logic) return std_logic is
+    begin
+        if b = '1' then
+            return '0';
+        end if;
+        return '1';
+    end function;
+
+    signal a, b, unused : std_logic;  -- 入力
+    signal y : std_logic_vector(3 downto 0);
+begin
+    dut : entity work.top port map (a => a, b => b, x => unused, y => y);
+
+    process
+    begin
+        a <= inv(b);
+        wait;
+    end process;
+end sim;
+";
+    let new = EntityDef::builder("top")
+        .input("a", StdLogic)
+        .input(
+            "b",
+            StdLogicVector {
+                high: 1.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .output(
+            "y",
+            StdLogicVector {
+                high: 3.into(),
+                low: 0.into(),
+                ascending: false,
+            },
+        )
+        .output("valid", StdLogic)
+        .build();
+    let update = update_testbench(tb, &new).unwrap();
+    assert_eq!(
+        update.text,
+        tb.replace(
+            "    signal a, b, unused : std_logic;  -- 入力\n",
+            "    signal a : std_logic;  -- 入力\n    signal b : std_logic_vector(1 downto 0) := (others => '0');\n",
+        )
+        .replace(
+            "    signal y : std_logic_vector(3 downto 0);\n",
+            "    signal y : std_logic_vector(3 downto 0);\n    signal valid : std_logic := '0';\n",
+        )
+        .replace("x => unused, y => y", "y => y, valid => valid")
+    );
+    let messages: Vec<&str> = update.changes.iter().map(|d| d.message.as_str()).collect();
+    assert!(messages.contains(&"removed port 'x'"));
+    assert!(messages.contains(&"added port 'valid'"));
+    assert!(messages.contains(&"removed signal 'unused'"));
+}
+
+#[test]
+fn test_update_errors() {
+    let tb = TestbenchBuilder::new(&counter(7)).build();
+    let other = EntityDef::builder("timer").input("clk", StdLogic).build();
+    let err = update_testbench(&tb, &other).unwrap_err();
+    assert_eq!(err.message, "no instance of 'timer' found");
+    assert!(
+        vig::Error::from(err)
+            .to_string()
+            .starts_with("testbench error:")
+    );
+
+    let positional = "architecture a of t is begin u: counter port map (clk, reset); end a;";
+    let err = update_testbench(positional, &counter(7)).unwrap_err();
+    assert_eq!(err.message, "positional port map of 'u' is not supported");
+}
+
+#[test]
+fn test_extract_dut_from_component() {
+    let entity = counter(7);
+    let tb = TestbenchBuilder::new(&entity).build();
+    let dut = extract_dut(&tb).unwrap();
+    assert_eq!(dut.entity.name, "counter");
+    let ports: Vec<_> = dut
+        .entity
+        .ports
+        .iter()
+        .map(|p| (&p.name, &p.direction, &p.vhdl_type))
+        .collect();
+    let expected: Vec<_> = entity
+        .ports
+        .iter()
+        .map(|p| (&p.name, &p.direction, &p.vhdl_type))
+        .collect();
+    assert_eq!(ports, expected);
+    assert!(dut.warnings.is_empty());
+}
+
+#[test]
+fn test_extract_dut_from_direct_instance() {
+    let tb = "\
+architecture sim of top_tb is
+    signal clk : std_logic := '0';
+    signal data : std_logic_vector(7 downto 0);
+    signal flags : unsigned(1 downto 0);
+begin
+    mon : entity work.monitor port map (d => data);
+    dut : entity work.top(rtl)
+        port map (clk => clk, data(7 downto 4) => data(7 downto 4),
+                  data(3 downto 0) => x\"0\", flags => flags, dbg => open);
+    clk <= not clk after 5 ns;
+end sim;
+";
+    let dut = extract_dut(tb).unwrap();
+    assert_eq!(dut.entity.name, "top");
+    let ports: Vec<String> = dut
+        .entity
+        .ports
+        .iter()
+        .map(|p| format!("{} : {} {}", p.name, p.direction, p.vhdl_type))
+        .collect();
+    assert_eq!(
+        ports,
+        [
+            "clk : in std_logic",
+            "data : out std_logic",
+            "flags : out unsigned(1 downto 0)",
+            "dbg : out std_logic",
+        ]
+    );
+    let messages: Vec<&str> = dut.warnings.iter().map(|d| d.message.as_str()).collect();
+    assert!(messages.contains(&"type of port 'data' is unknown; assumed std_logic"));
+    assert!(messages.contains(&"direction of port 'clk' inferred as 'in'"));
+
+    let err = extract_dut("architecture a of t is begin end a;").unwrap_err();
+    assert_eq!(err.message, "no component instance found");
+}
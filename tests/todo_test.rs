@@ -0,0 +1,70 @@
+use vig::analyze;
+use vig::generator::generate_testbench;
+use vig::report::ReportFormat;
+use vig::source_map::SourceMap;
+use vig::todo::{find_todos, render};
+
+const SOURCE: &str = "\
+-- TODO: ポートを整理
+entity e is
+  port (a : in std_logic; y : out std_logic);
+end entity;
+
+architecture rtl of e is
+begin
+  decode: process(a)
+  begin
+    y <= a; -- FIXME 極性を確認
+  end process;
+  -- HACK: todo や TODOS は目印にしない
+  -- TODOS と todo だけのコメント
+end architecture;
+";
+
+#[test]
+fn test_find_markers() {
+    let items = find_todos(SOURCE);
+    let found: Vec<_> = items
+        .iter()
+        .map(|i| (i.marker, i.text.as_str(), i.process.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("TODO", "ポートを整理", None),
+            ("FIXME", "極性を確認", Some("decode")),
+            ("HACK", "todo や TODOS は目印にしない", None),
+        ]
+    );
+    assert_eq!(&SOURCE[items[1].span.start..items[1].span.end], "FIXME");
+}
+
+#[test]
+fn test_generated_stimulus() {
+    let result = analyze(SOURCE).unwrap();
+    let tb = generate_testbench(&result.entities[0], &Default::default());
+    let items = find_todos(&tb);
+    assert!(items.iter().any(|i| i.marker == "TODO"
+        && i.text == "テストパターンを記述"
+        && i.process.as_deref() == Some("stim_process")));
+}
+
+#[test]
+fn test_render() {
+    let map = SourceMap::new("e.vhd", SOURCE);
+    let items = find_todos(SOURCE);
+    let found = vec![(map, items)];
+    let text = render(&found, ReportFormat::Plain);
+    assert_eq!(
+        text.lines().take(2).collect::<Vec<_>>(),
+        vec![
+            "e.vhd:1:4: TODO: ポートを整理",
+            "e.vhd:10:16: FIXME: 極性を確認 (decode)"
+        ]
+    );
+    let json = render(&found, ReportFormat::Json);
+    assert!(json.contains(
+        "\"file\": \"e.vhd\",\n    \"line\": 10,\n    \"column\": 16,\n    \"marker\": \"FIXME\""
+    ));
+    assert!(json.contains("\"process\": null"));
+}
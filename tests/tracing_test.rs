@@ -0,0 +1,63 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// 作られたスパンの名前と、記録されたイベントの数を集める
+#[derive(Default)]
+struct Collector {
+    spans: Mutex<Vec<&'static str>>,
+    timings: Mutex<usize>,
+}
+
+struct Shared(Arc<Collector>);
+
+impl Subscriber for Shared {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.0.spans.lock().unwrap();
+        spans.push(span.metadata().name());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        if event.metadata().target() == "vig::timing" {
+            *self.0.timings.lock().unwrap() += 1;
+        }
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_spans_cover_each_stage() {
+    let collector = Arc::new(Collector::default());
+    let source = std::fs::read_to_string("testdata/counter.vhd").unwrap();
+    tracing::subscriber::with_default(Shared(collector.clone()), || {
+        let result = vig::analyze(&source).unwrap();
+        let _ = vig::lint::lint(&source, &vig::lint::LintConfig::default());
+        let _ = vig::generator::generate_testbench(&result.entities[0], &Default::default());
+    });
+    let spans = collector.spans.lock().unwrap();
+    for name in ["lex", "parse", "lint", "check_cdc", "build_model", "render"] {
+        assert!(
+            spans.contains(&name),
+            "missing span '{}' in {:?}",
+            name,
+            spans
+        );
+    }
+    // スパンごとに経過時間のイベントが1つ
+    assert_eq!(*collector.timings.lock().unwrap(), spans.len());
+}
@@ -0,0 +1,113 @@
+use vig::analyzer::{EntityDef, PortDirection, VhdlType, analyze_vhdl};
+use vig::diagnostic::Severity;
+use vig::interface::InterfaceDef;
+
+#[test]
+fn test_valid_entities() {
+    let source = std::fs::read_to_string("testdata/uart_tx.vhd").unwrap();
+    let result = analyze_vhdl(&source).unwrap();
+    assert_eq!(result.entities[0].validate(), []);
+
+    let entity = EntityDef::builder("fifo")
+        .input("clk", VhdlType::StdLogic)
+        .input(
+            "data_in",
+            VhdlType::StdLogicVector {
+                high: 0.into(),
+                low: 7.into(),
+                ascending: false,
+            },
+        )
+        .output("full", VhdlType::Other("unsigned(3 downto 0)".to_string()))
+        .build();
+    assert_eq!(entity.validate(), []);
+}
+
+#[test]
+fn test_invalid_names() {
+    let entity = EntityDef::builder("2fifo")
+        .input("clk", VhdlType::StdLogic)
+        .input("CLK", VhdlType::StdLogic)
+        .input("data-in", VhdlType::StdLogic)
+        .input("signal", VhdlType::StdLogic)
+        .input("wait", VhdlType::StdLogic)
+        .input("d__x", VhdlType::StdLogic)
+        .input("q_", VhdlType::StdLogic)
+        .input("", VhdlType::StdLogic)
+        .output("integer", VhdlType::Integer(None))
+        .build();
+    let messages: Vec<_> = entity
+        .validate()
+        .into_iter()
+        .map(|d| (d.severity, d.message))
+        .collect();
+    let errors = |m: &str| (Severity::Error, m.to_string());
+    assert_eq!(
+        messages,
+        [
+            errors("entity name '2fifo' must start with a letter"),
+            errors("duplicate port 'CLK'"),
+            errors("port name 'data-in' contains invalid characters"),
+            errors("port name 'signal' is a reserved word"),
+            errors("port name 'wait' is a reserved word"),
+            errors("port name 'd__x' has a misplaced underscore"),
+            errors("port name 'q_' has a misplaced underscore"),
+            errors("port name '' is empty"),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_types_and_empty_entity() {
+    let entity = EntityDef::builder("dut")
+        .port(
+            "a",
+            PortDirection::In,
+            VhdlType::StdLogicVector {
+                high: 3.into(),
+                low: (-1).into(),
+                ascending: false,
+            },
+        )
+        .port("b", PortDirection::Out, VhdlType::Other(" ".to_string()))
+        .build();
+    let diagnostics = entity.validate();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+        diagnostics[0].message,
+        "range (3, -1) of port 'a' has a negative index"
+    );
+    assert_eq!(diagnostics[1].message, "port 'b' has an empty type name");
+
+    let diagnostics = EntityDef::builder("empty").build().validate();
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].message, "entity 'empty' has no ports");
+}
+
+#[test]
+fn test_imported_interface() {
+    let json = r#"{"vig_interface": 1, "name": "dut",
+        "ports": [{"name": "in", "direction": "in", "type": {"name": "std_logic"}}]}"#;
+    let interface = InterfaceDef::from_json(json).unwrap();
+    let diagnostics = interface.entity.validate();
+    assert_eq!(diagnostics[0].message, "port name 'in' is a reserved word");
+}
+
+#[test]
+fn test_extended_names() {
+    let entity = EntityDef::builder(r"\Net-List\")
+        .input(r"\A\", VhdlType::StdLogic)
+        .input(r"\a\", VhdlType::StdLogic)
+        .input(r"\a\\b\", VhdlType::StdLogic)
+        .input(r"\a\b\", VhdlType::StdLogic)
+        .input(r"\A\", VhdlType::StdLogic)
+        .build();
+    let messages: Vec<_> = entity.validate().into_iter().map(|d| d.message).collect();
+    assert_eq!(
+        messages,
+        [
+            r"port name '\a\b\' has an unescaped backslash",
+            r"duplicate port '\A\'",
+        ]
+    );
+}
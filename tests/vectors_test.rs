@@ -0,0 +1,130 @@
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::generator::TbConfig;
+use vig::vectors::{TruthTable, generate_testbench};
+
+#[test]
+fn test_parse_csv_with_bom() {
+    let table = TruthTable::from_csv("\u{feff}a,b\r\n1,0\r\n").unwrap();
+    assert_eq!(table.columns, ["a", "b"]);
+    assert_eq!(table.header_span.start, 3);
+}
+
+#[test]
+fn test_parse_csv() {
+    let table = TruthTable::from_csv("# comment\na, \"b\"\n\n1,\"0\"\"\"\r\n0,1\n").unwrap();
+    assert_eq!(table.columns, ["a", "b"]);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].values, ["1", "0\""]);
+    assert_eq!(table.rows[1].values, ["0", "1"]);
+    assert_eq!(table.header_span.start, 10);
+}
+
+#[test]
+fn test_parse_tsv_and_errors() {
+    let table = TruthTable::from_csv("a\tb\n1\t0\n").unwrap();
+    assert_eq!(table.rows[0].values, ["1", "0"]);
+
+    assert!(TruthTable::from_csv("# only comments\n").is_err());
+    let err = TruthTable::from_csv("a,b\n1,0\n1\n").unwrap_err();
+    assert_eq!(err.message, "expected 2 columns, found 1");
+    assert_eq!(err.span.start, 8);
+}
+
+#[test]
+fn test_alu_vectors() {
+    let result = analyze_file("testdata/alu.vhd").unwrap();
+    let source = std::fs::read_to_string("testdata/alu_vectors.csv").unwrap();
+    let table = TruthTable::from_csv(&source).unwrap();
+    let tb = generate_testbench(&result.entities[0], &table, &TbConfig::default()).unwrap();
+
+    assert!(tb.contains("-- alu の真理値表テストベンチ"));
+    // リセット後に各行を印加して検査
+    assert!(tb.contains("        reset <= '0';\n"));
+    assert!(tb.contains(
+        "        -- 行 2
+        op_a <= \"1001\";
+        op_b <= \"1001\";
+        alu_op <= \"000\";
+        wait for 10 ns;
+        assert result = \"0010\" report \"row 2: result mismatch\" severity error;
+        assert carry_out = '1' report \"row 2: carry_out mismatch\" severity error;
+"
+    ));
+    // 10進数の5は4ビットの0101
+    assert!(tb.contains("op_a <= \"0101\";"));
+    // 空欄の入力は前の値のまま、期待値の - は比較しない
+    assert!(tb.contains("        -- 行 8\n        alu_op <= \"110\";\n        wait for 10 ns;\n"));
+    assert!(!tb.contains("carry_out = '-'"));
+    assert_eq!(tb.matches("assert carry_out").count(), 4);
+    assert!(tb.contains("        -- 真理値表 (8 行)\n"));
+}
+
+#[test]
+fn test_value_formats() {
+    let result = analyze_vhdl(
+        "entity e is port (
+            v : in std_logic_vector(7 downto 0);
+            n : in integer;
+            b : in boolean;
+            y : out std_logic_vector(3 downto 0)
+        ); end;",
+    )
+    .unwrap();
+    let table = TruthTable::from_csv("v,n,b,y\n-1,-7,true,1-0x\n0xA5,3,0,\n").unwrap();
+    let tb = generate_testbench(&result.entities[0], &table, &TbConfig::default()).unwrap();
+    assert!(tb.contains("v <= \"11111111\";"));
+    assert!(tb.contains("n <= -7;"));
+    assert!(tb.contains("b <= true;"));
+    assert!(tb.contains("assert std_match(y, \"1-0X\")"));
+    assert!(tb.contains("v <= \"10100101\";"));
+    assert!(tb.contains("b <= false;"));
+    assert_eq!(tb.matches("assert std_match").count(), 1);
+}
+
+#[test]
+fn test_invalid_tables() {
+    let result = analyze_file("testdata/alu.vhd").unwrap();
+    let entity = &result.entities[0];
+    let config = TbConfig::default();
+    let error = |csv: &str| {
+        let table = TruthTable::from_csv(csv).unwrap();
+        generate_testbench(entity, &table, &config)
+            .unwrap_err()
+            .message
+    };
+    assert_eq!(error("op_a,foo\n1,1\n"), "'foo' is not a port of 'alu'");
+    assert_eq!(
+        error("clk,op_a\n1,1\n"),
+        "clock port 'clk' cannot be a column"
+    );
+    assert_eq!(error("op_a,OP_A\n1,1\n"), "duplicate column 'OP_A'");
+    assert_eq!(
+        error("op_a\n16\n"),
+        "row 1: value '16' does not fit in 4 bits of 'op_a'"
+    );
+    assert_eq!(
+        error("op_a\n0011\n2x\n"),
+        "row 2: invalid value '2x' for 'op_a'"
+    );
+}
+
+#[test]
+fn test_fill_expected() {
+    let source = std::fs::read_to_string("testdata/alu.vhd").unwrap();
+    let interp = vig::interp::Interpreter::new(&source, "alu").unwrap();
+    let mut table =
+        TruthTable::from_csv("op_a,op_b,alu_op,result\n3,5,001,\n1111,,101,-\n").unwrap();
+    table.fill_expected(&interp).unwrap();
+
+    // 表にない出力ポートの列を追加し、空欄だけを埋める
+    assert_eq!(
+        table.columns,
+        ["op_a", "op_b", "alu_op", "result", "carry_out", "zero_flag"]
+    );
+    assert_eq!(table.rows[0].values[3..], ["1110", "1", "0"]);
+    assert_eq!(table.rows[1].values[3..], ["-", "0", "1"]);
+
+    let mut table = TruthTable::from_csv("op_a,alu_op\nxyz,000\n").unwrap();
+    let err = table.fill_expected(&interp).unwrap_err();
+    assert_eq!(err.message, "row 1: invalid value 'xyz' for 'op_a'");
+}
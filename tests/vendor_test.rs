@@ -0,0 +1,104 @@
+use vig::analyzer::{PortDirection, TypeDef, VhdlType};
+use vig::generator::{TbConfig, generate_testbench};
+use vig::library::{LibraryPaths, Resolver};
+use vig::vendor;
+
+#[test]
+fn test_unisim_primitive() {
+    let (library, ibufds) = vendor::find_entity("IBUFDS").unwrap();
+    assert_eq!(library, "unisim");
+    let ports: Vec<_> = ibufds.ports.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(ports, ["O", "I", "IB"]);
+    assert_eq!(ibufds.ports[0].direction, PortDirection::Out);
+    assert_eq!(ibufds.ports[1].vhdl_type, VhdlType::StdLogic);
+
+    let iobuf = vendor::entity("UNISIM", "iobuf").unwrap();
+    assert_eq!(iobuf.ports[1].direction, PortDirection::Inout);
+}
+
+#[test]
+fn test_ram_macro_widths() {
+    let ram = vendor::entity("unisim", "RAMB18E1").unwrap();
+    let wea = ram.ports.iter().find(|p| p.name == "WEA").unwrap();
+    assert_eq!(
+        wea.vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 1.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+
+    // 幅がジェネリックで決まるポートは範囲が式のまま
+    let (library, ram) = vendor::find_entity("altsyncram").unwrap();
+    assert_eq!(library, "altera_mf");
+    let q_a = ram.ports.iter().find(|p| p.name == "q_a").unwrap();
+    assert_eq!(q_a.direction, PortDirection::Out);
+    assert!(q_a.vhdl_type.is_symbolic());
+    assert_eq!(q_a.vhdl_type.bit_width(), None);
+    assert_eq!(
+        q_a.vhdl_type.to_string(),
+        "std_logic_vector(width_a - 1 downto 0)"
+    );
+}
+
+#[test]
+fn test_unknown_primitive() {
+    assert!(vendor::find_entity("clk_wiz_0").is_none());
+    // ライブラリが違えば見つからない
+    assert!(vendor::entity("altera_mf", "BUFG").is_none());
+}
+
+#[test]
+fn test_standard_packages() {
+    assert!(vendor::is_builtin_library("IEEE"));
+    assert!(vendor::is_builtin_library("std"));
+    assert!(!vendor::is_builtin_library("work"));
+
+    let numeric_std = vendor::package("ieee", "numeric_std").unwrap();
+    assert!(numeric_std.find_type("unsigned").is_some());
+    let fixed = vendor::package("ieee", "fixed_float_types").unwrap();
+    assert!(matches!(
+        fixed.find_type("round_type").unwrap(),
+        TypeDef::Enum { literals, .. } if literals.len() == 4
+    ));
+    assert!(vendor::package("unisim", "vcomponents").is_some());
+    assert!(vendor::package("ieee", "no_such_pkg").is_none());
+}
+
+#[test]
+fn test_resolver_falls_back_to_builtin() {
+    let mut resolver = Resolver::new(LibraryPaths::new());
+    assert!(
+        resolver
+            .package("ieee", "std_logic_1164")
+            .unwrap()
+            .is_some()
+    );
+    let bufg = resolver.entity("unisim", "bufg").unwrap().unwrap();
+    assert_eq!(bufg.name, "BUFG");
+
+    // 組み込みのライブラリで見つからないパッケージは警告しない
+    let source = "library ieee, unisim;\n\
+                  use ieee.numeric_std.all;\n\
+                  use ieee.vendor_extras.all;\n\
+                  use unisim.vcomponents.all;\n\
+                  entity e is port (a : in unsigned(3 downto 0)); end entity;";
+    let mut entity = vig::analyzer::EntityDef::builder("e")
+        .port("a", PortDirection::In, VhdlType::Other("unsigned".into()))
+        .build();
+    let diagnostics = resolver.resolve_entity(source, &mut entity).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert_eq!(
+        entity.ports[0].vhdl_type,
+        VhdlType::Other("unsigned".into())
+    );
+}
+
+#[test]
+fn test_stub_testbench() {
+    let (_, bufgmux) = vendor::find_entity("BUFGMUX").unwrap();
+    let tb = generate_testbench(&bufgmux, &TbConfig::default());
+    assert!(tb.contains("component BUFGMUX"), "{}", tb);
+    assert!(tb.contains("S"));
+}
@@ -0,0 +1,127 @@
+use vig::analyzer::{PortDirection, VhdlType};
+use vig::generator::{TbConfig, generate_testbench};
+use vig::verilog::parse_modules;
+
+const ANSI: &str = r#"
+`timescale 1ns/1ps
+// カウンタ
+module counter #(
+    parameter WIDTH = 8,
+    parameter MAX = (1 << 4)
+) (
+    input  wire             clk,
+    input                   rst_n, en,
+    (* keep *) output reg [WIDTH-1:0] count,
+    output [3:0]            \flags[0] ,
+    output integer          total
+);
+    /* 本体は読まない */
+    adder #(.W(WIDTH)) u_add (.a(count), .b(8'hFF), .y());
+    always @(*) begin end
+endmodule
+"#;
+
+#[test]
+fn test_ansi_ports() {
+    let modules = parse_modules(ANSI).unwrap();
+    assert_eq!(modules.len(), 1);
+    let module = &modules[0];
+    assert_eq!(module.name, "counter");
+    let params: Vec<_> = module.parameters.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(params, ["WIDTH", "MAX"]);
+    assert_eq!(module.parameters[0].default.as_deref(), Some("8"));
+
+    let names: Vec<_> = module.ports.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["clk", "rst_n", "en", "count", "flags[0]", "total"]);
+    // 方向は次の宣言まで引き継ぐ
+    assert_eq!(module.ports[2].direction, PortDirection::In);
+    assert_eq!(module.ports[3].direction, PortDirection::Out);
+    assert_eq!(
+        module.ports[3].range,
+        Some(("WIDTH-1".to_string(), "0".to_string()))
+    );
+}
+
+#[test]
+fn test_instances() {
+    let module = &parse_modules(ANSI).unwrap()[0];
+    assert_eq!(module.instances.len(), 1);
+    assert_eq!(module.instances[0].label, "u_add");
+    assert_eq!(module.instances[0].unit, "adder");
+}
+
+#[test]
+fn test_to_entity_evaluates_parameters() {
+    let entity = parse_modules(ANSI).unwrap()[0].to_entity();
+    let types: Vec<_> = entity.ports.iter().map(|p| p.vhdl_type.clone()).collect();
+    assert_eq!(types[0], VhdlType::StdLogic);
+    assert_eq!(
+        types[3],
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    assert_eq!(
+        types[4],
+        VhdlType::StdLogicVector {
+            high: 3.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+    assert_eq!(types[5], VhdlType::Integer(None));
+}
+
+#[test]
+fn test_unknown_parameter_keeps_expression() {
+    let module = &parse_modules("module m (input [N-1:0] a); endmodule").unwrap()[0];
+    assert_eq!(
+        module.to_entity().ports[0].vhdl_type,
+        VhdlType::Other("std_logic_vector(N-1 downto 0)".to_string())
+    );
+}
+
+#[test]
+fn test_non_ansi_ports() {
+    let source = "module dff (clk, d, q);\n\
+                  input clk;\n\
+                  input [7:0] d;\n\
+                  output reg [7:0] q;\n\
+                  endmodule";
+    let entity = parse_modules(source).unwrap()[0].to_entity();
+    let names: Vec<_> = entity.ports.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["clk", "d", "q"]);
+    assert_eq!(entity.ports[1].direction, PortDirection::In);
+    assert_eq!(
+        entity.ports[2].vhdl_type,
+        VhdlType::StdLogicVector {
+            high: 7.into(),
+            low: 0.into(),
+            ascending: false
+        }
+    );
+}
+
+#[test]
+fn test_errors() {
+    let err = parse_modules("module m (a); endmodule").unwrap_err();
+    assert!(err.message.contains("no direction"), "{}", err.message);
+    let err = parse_modules("module m (input a);").unwrap_err();
+    assert!(err.message.contains("endmodule"), "{}", err.message);
+    let err = parse_modules("module m; /* ").unwrap_err();
+    assert!(
+        err.message.contains("unterminated comment"),
+        "{}",
+        err.message
+    );
+}
+
+#[test]
+fn test_generate_testbench_from_module() {
+    let entity = parse_modules(ANSI).unwrap()[0].to_entity();
+    let tb = generate_testbench(&entity, &TbConfig::default());
+    assert!(tb.contains("entity counter_tb is"));
+    assert!(tb.contains("count : out std_logic_vector(7 downto 0)"));
+}
@@ -0,0 +1,26 @@
+use vig::TbConfig;
+use vig::analyzer::{analyze_file, analyze_vhdl};
+use vig::wavedrom::generate_wavedrom;
+
+#[test]
+fn test_clock_reset_and_inputs() {
+    let result = analyze_file("testdata/uart_tx.vhd").unwrap();
+    let json = generate_wavedrom(&result.entities[0], &TbConfig::default());
+    // リセット 2+2 周期 + テストパターン 10 周期
+    assert!(json.contains("\"wave\": \"n.............\""));
+    assert!(json.contains("\"wave\": \"1.0...........\""));
+    assert!(json.contains("\"name\": \"tx_data\",\n      \"wave\": \"=.............\""));
+    // 出力ポートは含めない
+    assert!(!json.contains("tx_busy"));
+    assert!(json.contains("\"text\": \"uart_tx_tb\""));
+    assert!(json.contains("1周期 = 10 ns"));
+}
+
+#[test]
+fn test_without_clock_and_reset() {
+    let result =
+        analyze_vhdl("entity inv is port (a : in std_logic; y : out std_logic); end;").unwrap();
+    let json = generate_wavedrom(&result.entities[0], &TbConfig::default());
+    assert!(json.contains("\"wave\": \"0.........\""));
+    assert!(!json.contains("{}"));
+}